@@ -162,7 +162,7 @@ CREATE TABLE IF NOT EXISTS memory_links (
     project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
     source_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
     target_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
-    link_type TEXT NOT NULL,          -- 'related' | 'references' | 'depends_on' | 'modifies'
+    link_type TEXT NOT NULL,          -- 'related' | 'references' | 'depends_on' | 'modifies' | 'contradicts' | 'refines'
     created_by TEXT NOT NULL DEFAULT 'system',  -- 'system' | 'user' | 'ai'
     confidence REAL,                  -- AI confidence score (0.0-1.0)
     context TEXT,                     -- Why this link exists
@@ -178,6 +178,245 @@ CREATE INDEX IF NOT EXISTS idx_links_source ON memory_links(source_id);
 CREATE INDEX IF NOT EXISTS idx_links_target ON memory_links(target_id);
 CREATE INDEX IF NOT EXISTS idx_links_project_type ON memory_links(project_id, link_type);
 
+-- ============================================================================
+-- Memory Conflicts (new memories that contradict existing decisions)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS memory_conflicts (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    conflicting_memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    reason TEXT,                      -- LLM explanation of the contradiction
+    confidence REAL,
+    status TEXT NOT NULL DEFAULT 'open',  -- 'open' | 'resolved' | 'dismissed'
+    resolution TEXT,                  -- Human note on how it was resolved
+    resolved_by TEXT,                 -- User ID
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    resolved_at TEXT,
+
+    UNIQUE(memory_id, conflicting_memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_conflicts_project_status ON memory_conflicts(project_id, status);
+
+-- ============================================================================
+-- Memory Feedback (explicit relevance verdicts on search results)
+-- ============================================================================
+-- One verdict per memory, query and giver. Giving feedback again replaces it.
+CREATE TABLE IF NOT EXISTS memory_feedback (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    query TEXT NOT NULL DEFAULT '',
+    query_hash TEXT NOT NULL,
+    verdict TEXT NOT NULL,            -- 'used' | 'irrelevant' | 'wrong'
+    comment TEXT,
+    given_by TEXT NOT NULL,           -- Token ID
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    resolved_at TEXT,                 -- Set when a reviewer clears a 'wrong' verdict
+
+    UNIQUE(memory_id, query_hash, given_by)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_feedback_memory ON memory_feedback(memory_id);
+CREATE INDEX IF NOT EXISTS idx_memory_feedback_project_verdict ON memory_feedback(project_id, verdict);
+
+-- ============================================================================
+-- Memory Accesses (retrieval history for ACT-R base-level activation)
+-- ============================================================================
+-- One row per direct read of a memory or "used" verdict. Search impressions
+-- are not recorded.
+CREATE TABLE IF NOT EXISTS memory_accesses (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,               -- 'read' | 'used'
+    accessed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_accesses_memory ON memory_accesses(memory_id, accessed_at);
+
+-- ============================================================================
+-- Memory Revisions (earlier versions of updated memories)
+-- ============================================================================
+-- A row is written before each update with the version being replaced and
+-- the time range it was current, so searches can be run as of a past time.
+-- Deleting a memory writes a tombstone, a final revision that also holds the
+-- deleted memory. No foreign key on memory_id: revisions outlive the memory.
+CREATE TABLE IF NOT EXISTS memory_revisions (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL,
+    title TEXT,
+    content TEXT,
+    content_hash TEXT,
+    keywords TEXT,                    -- JSON array
+    tags TEXT,                        -- JSON array
+    context TEXT,
+    valid_from TEXT NOT NULL,         -- updated_at of the replaced version
+    valid_to TEXT NOT NULL,           -- when it was replaced
+    snapshot TEXT,                    -- tombstones only: JSON of the deleted memory
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_revisions_memory ON memory_revisions(memory_id, valid_to);
+CREATE INDEX IF NOT EXISTS idx_memory_revisions_project ON memory_revisions(project_id, valid_to);
+
+-- ============================================================================
+-- Fold Sync (human edits to fold/*.md files pushed to git)
+-- ============================================================================
+-- The last version of each memory file applied from a push. No foreign key on
+-- memory_id: the row is removed alongside the memory when the file is deleted.
+CREATE TABLE IF NOT EXISTS fold_sync_state (
+    memory_id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    content_hash TEXT NOT NULL,       -- SHA256 of the file as last applied
+    related_to TEXT,                  -- JSON array of related memory IDs as last applied
+    commit_sha TEXT,
+    synced_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_fold_sync_state_project ON fold_sync_state(project_id);
+
+-- Pushed edits that were not applied because the memory also changed in Fold
+CREATE TABLE IF NOT EXISTS fold_sync_conflicts (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    change_type TEXT NOT NULL,        -- 'modified' | 'deleted'
+    commit_sha TEXT,
+    file_content TEXT,                -- The pushed file (NULL for deletions)
+    status TEXT NOT NULL DEFAULT 'open',  -- 'open' | 'resolved' | 'dismissed'
+    resolution TEXT,
+    resolved_by TEXT,                 -- User ID
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    resolved_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_fold_sync_conflicts_project_status ON fold_sync_conflicts(project_id, status);
+
+-- Where readable-layout memory files live (fold/{type}/{slug}.md), since
+-- their paths cannot be derived from the memory ID. Keyed by the project root
+-- the file was written under, like the storage service itself.
+CREATE TABLE IF NOT EXISTS memory_file_paths (
+    project_root TEXT NOT NULL,
+    memory_id TEXT NOT NULL,
+    path TEXT NOT NULL,               -- Relative to project_root
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (project_root, memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_file_paths_path ON memory_file_paths(project_root, path);
+
+-- ============================================================================
+-- Memory Consolidation (merging near-duplicate agent memories)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS consolidation_settings (
+    project_id TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    enabled INTEGER NOT NULL DEFAULT 0,           -- Run periodically
+    similarity_threshold REAL NOT NULL DEFAULT 0.92,  -- Cosine similarity to cluster
+    auto_apply INTEGER NOT NULL DEFAULT 0,        -- Apply merges without review
+    max_cluster_size INTEGER NOT NULL DEFAULT 5,
+    interval_hours INTEGER NOT NULL DEFAULT 24,
+    last_run_at TEXT,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS memory_merges (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    job_id TEXT,
+    survivor_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    source_ids TEXT NOT NULL,         -- JSON array of memory IDs folded into the survivor
+    similarity REAL,                  -- Lowest pairwise similarity in the cluster
+    title TEXT,                       -- LLM-written merged title
+    content TEXT NOT NULL,            -- LLM-written merged content
+    status TEXT NOT NULL DEFAULT 'proposed',  -- 'proposed' | 'applied' | 'rejected'
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    decided_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_merges_project_status ON memory_merges(project_id, status);
+
+CREATE TABLE IF NOT EXISTS merged_memories (
+    memory_id TEXT PRIMARY KEY REFERENCES memories(id) ON DELETE CASCADE,
+    merged_into TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    merge_id TEXT NOT NULL REFERENCES memory_merges(id) ON DELETE CASCADE,
+    merged_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_merged_memories_into ON merged_memories(merged_into);
+
+-- ============================================================================
+-- Retention Policies (archiving weak memories to cold storage)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS retention_policies (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    memory_type TEXT,                 -- Match memories of this type (NULL = any)
+    source TEXT,                      -- Match 'agent' | 'file' | 'git' (NULL = any)
+    max_strength REAL,                -- Archive when decay strength is below this
+    min_age_days INTEGER NOT NULL DEFAULT 0,  -- ...and the memory is at least this old
+    never_archive INTEGER NOT NULL DEFAULT 0, -- Protect matching memories from all policies
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_retention_policies_project ON retention_policies(project_id);
+
+CREATE TABLE IF NOT EXISTS archived_memories (
+    memory_id TEXT PRIMARY KEY REFERENCES memories(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    policy_id TEXT REFERENCES retention_policies(id) ON DELETE SET NULL,
+    strength REAL,                    -- Decay strength when archived
+    reason TEXT,
+    archived_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_memories_project ON archived_memories(project_id);
+
+-- ============================================================================
+-- Provider Usage and Quotas (daily embedding and LLM calls per project)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS project_usage (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    day TEXT NOT NULL,                -- UTC date, YYYY-MM-DD
+    embedding_calls INTEGER NOT NULL DEFAULT 0,
+    llm_calls INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (project_id, day)
+);
+
+-- Per-project overrides of the configured daily quotas (NULL = use default, 0 = unlimited)
+CREATE TABLE IF NOT EXISTS project_quotas (
+    project_id TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    daily_embedding_calls INTEGER,
+    daily_llm_calls INTEGER,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- ============================================================================
+-- Audit Events (append-only record of who changed what)
+-- ============================================================================
+-- No foreign keys: events must outlive the users, tokens and projects they name.
+CREATE TABLE IF NOT EXISTS audit_events (
+    id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,             -- 'memory.create' | 'member.add' | 'token.create' | ...
+    resource_type TEXT NOT NULL,      -- 'memory' | 'project' | 'member' | 'token' | 'provider' | 'webhook' | 'mcp_tool'
+    resource_id TEXT,
+    project_id TEXT,
+    actor_user_id TEXT,
+    actor_token_id TEXT,              -- set when the request used an API token
+    details TEXT,                     -- JSON
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_created ON audit_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor ON audit_events(actor_user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_project ON audit_events(project_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_action ON audit_events(action, created_at);
+
 -- ============================================================================
 -- Jobs
 -- ============================================================================
@@ -232,6 +471,46 @@ CREATE TABLE IF NOT EXISTS job_logs (
 
 CREATE INDEX IF NOT EXISTS idx_job_logs_job ON job_logs(job_id);
 
+-- ============================================================================
+-- Webhook Registrations (git provider hooks and outbound project webhooks)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS webhook_registrations (
+    id TEXT PRIMARY KEY,
+    project_id TEXT REFERENCES projects(id) ON DELETE CASCADE,
+    repository_id TEXT,
+    provider TEXT NOT NULL,           -- 'outbound' | 'github' | 'gitlab'
+    webhook_id TEXT NOT NULL,         -- provider-side hook ID (own ID for outbound)
+    target_url TEXT,                  -- where outbound events are POSTed
+    secret TEXT NOT NULL,             -- HMAC signing secret
+    events TEXT NOT NULL DEFAULT '[]', -- JSON array of event types, e.g. 'memory:*'
+    active INTEGER NOT NULL DEFAULT 1,
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_registrations_project ON webhook_registrations(project_id, provider);
+
+-- ============================================================================
+-- Webhook Deliveries (outbound delivery attempts with retry)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id TEXT PRIMARY KEY,
+    webhook_id TEXT REFERENCES webhook_registrations(id) ON DELETE CASCADE,
+    type TEXT NOT NULL,               -- event type, e.g. 'memory:created'
+    target_url TEXT NOT NULL,
+    payload TEXT NOT NULL,            -- JSON body, signed as sent
+    status TEXT NOT NULL DEFAULT 'pending', -- 'pending' | 'success' | 'failed'
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_attempt_at TEXT,
+    next_attempt_at TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_next ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at);
+
 -- ============================================================================
 -- Users (OIDC)
 -- ============================================================================
@@ -301,6 +580,14 @@ CREATE TABLE IF NOT EXISTS api_tokens (
 CREATE INDEX IF NOT EXISTS idx_api_tokens_prefix ON api_tokens(token_prefix);
 CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
 
+-- Scopes granted to a token. Tokens without a row predate scopes and keep
+-- full access.
+CREATE TABLE IF NOT EXISTS api_token_scopes (
+    token_id TEXT PRIMARY KEY REFERENCES api_tokens(id) ON DELETE CASCADE,
+    scopes TEXT NOT NULL,             -- JSON array, e.g. ["memories:read"]
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
 -- ============================================================================
 -- Groups
 -- ============================================================================
@@ -439,6 +726,23 @@ CREATE TABLE IF NOT EXISTS embedding_providers (
 CREATE INDEX IF NOT EXISTS idx_embedding_providers_name ON embedding_providers(name);
 CREATE INDEX IF NOT EXISTS idx_embedding_providers_enabled ON embedding_providers(enabled);
 
+-- ============================================================================
+-- Project Provider Overrides (per-project LLM and embedding chains)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS project_providers (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,               -- 'llm' | 'embedding'
+    name TEXT NOT NULL,               -- 'gemini' | 'openai' | 'anthropic' | 'openrouter' | 'ollama'
+    priority INTEGER NOT NULL DEFAULT 0,
+    api_key TEXT,                     -- encrypted with the master key
+    config TEXT NOT NULL DEFAULT '{}', -- JSON: endpoint, model, dimension, search_priority
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_providers_project ON project_providers(project_id, kind, priority);
+
 -- ============================================================================
 -- Algorithm Configuration (per-project search tuning)
 -- ============================================================================
@@ -453,6 +757,114 @@ CREATE TABLE IF NOT EXISTS algorithm_config (
 
 CREATE INDEX IF NOT EXISTS idx_algorithm_config_project ON algorithm_config(project_id);
 
+-- ============================================================================
+-- Search Settings (per-project LLM query rewriting)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS search_settings (
+    project_id TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    query_expansion INTEGER NOT NULL DEFAULT 0,   -- Search LLM paraphrases of the query
+    hyde INTEGER NOT NULL DEFAULT 0,              -- Search a hypothetical answer passage
+    expansion_count INTEGER NOT NULL DEFAULT 3,   -- Paraphrases to generate
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS query_rewrites (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    query_hash TEXT NOT NULL,         -- SHA-256 of the normalised query
+    kind TEXT NOT NULL,               -- 'expand' | 'hyde'
+    query TEXT NOT NULL,
+    rewrites TEXT NOT NULL,           -- JSON array of rewritten queries
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (project_id, query_hash, kind)
+);
+
+-- ============================================================================
+-- Scoring Settings (per-project ranking model)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS scoring_settings (
+    project_id TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    decay_model TEXT NOT NULL DEFAULT 'exponential',  -- 'exponential' | 'power_law' | 'none'
+    type_half_lives TEXT NOT NULL DEFAULT '{}',       -- JSON: memory type to half-life days, null never decays
+    actr_decay REAL NOT NULL DEFAULT 0.5,             -- ACT-R decay exponent for the power-law model
+    link_boost_weight REAL NOT NULL DEFAULT 0.0,      -- Weight of link PageRank in the combined score
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- ============================================================================
+-- Search Evaluation (golden query sets and metric history)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS golden_sets (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(project_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS golden_queries (
+    id TEXT PRIMARY KEY,
+    set_id TEXT NOT NULL REFERENCES golden_sets(id) ON DELETE CASCADE,
+    query TEXT NOT NULL,
+    expected_memory_ids TEXT NOT NULL DEFAULT '[]',  -- JSON array
+    expected_file_paths TEXT NOT NULL DEFAULT '[]',  -- JSON array
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_golden_queries_set ON golden_queries(set_id);
+
+CREATE TABLE IF NOT EXISTS search_evaluations (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    set_id TEXT NOT NULL REFERENCES golden_sets(id) ON DELETE CASCADE,
+    job_id TEXT,
+    k INTEGER NOT NULL,
+    config TEXT NOT NULL DEFAULT '{}',  -- JSON: scoring settings evaluated
+    query_count INTEGER NOT NULL,
+    recall_at_k REAL NOT NULL,
+    mrr REAL NOT NULL,
+    ndcg REAL NOT NULL,
+    details TEXT NOT NULL DEFAULT '[]', -- JSON: per-query metrics
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_evaluations_set ON search_evaluations(set_id, created_at);
+
+-- ============================================================================
+-- Search Query Log (what agents search for, and how well it went)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS search_queries (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    token_id TEXT,
+    source TEXT NOT NULL,             -- 'api' | 'mcp'
+    query TEXT NOT NULL,
+    query_hash TEXT NOT NULL,
+    filters TEXT NOT NULL DEFAULT '{}',   -- JSON object
+    result_count INTEGER NOT NULL,
+    top_score REAL,                   -- Best raw similarity before filtering (NULL = no candidates)
+    result_ids TEXT NOT NULL DEFAULT '[]',  -- JSON array of returned memory IDs
+    latency_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_queries_project ON search_queries(project_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_search_queries_hash ON search_queries(project_id, query_hash);
+
+-- Periodic reports of what searches keep failing to find
+CREATE TABLE IF NOT EXISTS knowledge_gap_reports (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    job_id TEXT,
+    window_days INTEGER NOT NULL,
+    clustered INTEGER NOT NULL DEFAULT 0,   -- Whether the LLM grouped queries into topics
+    report TEXT NOT NULL,             -- JSON
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_knowledge_gap_reports_project ON knowledge_gap_reports(project_id, created_at);
+
 -- ============================================================================
 -- Attachments (files attached to memories)
 -- ============================================================================
@@ -500,6 +912,24 @@ CREATE TABLE IF NOT EXISTS ai_session_notes (
 
 CREATE INDEX IF NOT EXISTS idx_ai_session_notes_session ON ai_session_notes(session_id);
 
+-- ============================================================================
+-- Transcript Imports (agent transcripts ingested as session memories)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS transcript_imports (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    transcript_id TEXT NOT NULL,      -- Claude Code sessionId or caller-supplied id
+    memory_id TEXT REFERENCES memories(id) ON DELETE SET NULL,
+    source_path TEXT,                 -- Transcript file path (watcher imports)
+    message_count INTEGER NOT NULL DEFAULT 0,
+    files_touched TEXT,               -- JSON array
+    imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    UNIQUE(project_id, transcript_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transcript_imports_project ON transcript_imports(project_id);
+
 -- ============================================================================
 -- Workspaces (local workspace mappings)
 -- ============================================================================
@@ -0,0 +1,171 @@
+//! Ask Routes
+//!
+//! Answer questions from a project's memories with inline citations.
+//!
+//! Routes:
+//! - POST /projects/:project_id/ask - Answer a question (LLM rate limit)
+//!
+//! With `"stream": true` the answer is sent as Server-Sent Events:
+//! `sources` first, then `delta` events with answer text, then `done` with
+//! the final answer and citations. An `error` event ends the stream early.
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::post,
+    Json, Router,
+};
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::db;
+use crate::middleware::{rate_limit_llm, require_project_read};
+use crate::services::ask::{DEFAULT_CONTEXT_TOKENS, MAX_CONTEXT_TOKENS};
+use crate::services::{AskEvent, AskOptions};
+use crate::{AppState, Error, Result};
+
+/// Largest number of search hits a caller may ask for.
+const MAX_LIMIT: usize = 50;
+
+/// Build ask routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/:project_id/ask", post(ask))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_llm,
+        ))
+        .layer(middleware::from_fn_with_state(state, require_project_read))
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Question request.
+#[derive(Debug, Deserialize)]
+pub struct AskRequest {
+    /// Question to answer
+    pub question: String,
+
+    /// Search hits to retrieve
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Minimum similarity score (0.0 - 1.0) for a hit to be used
+    #[serde(default = "default_min_score")]
+    pub min_score: f32,
+
+    /// Token budget for the sources given to the LLM
+    #[serde(default = "default_max_context_tokens")]
+    pub max_context_tokens: usize,
+
+    /// Also use memories linked to the hits
+    #[serde(default = "default_true")]
+    pub follow_links: bool,
+
+    /// Stream the answer as Server-Sent Events
+    #[serde(default)]
+    pub stream: bool,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+fn default_min_score() -> f32 {
+    0.4
+}
+
+fn default_max_context_tokens() -> usize {
+    DEFAULT_CONTEXT_TOKENS
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl AskRequest {
+    fn options(&self) -> Result<AskOptions> {
+        if self.question.trim().is_empty() {
+            return Err(Error::Validation("Question cannot be empty".into()));
+        }
+        if self.limit == 0 || self.limit > MAX_LIMIT {
+            return Err(Error::Validation(format!(
+                "limit must be between 1 and {}",
+                MAX_LIMIT
+            )));
+        }
+        if self.max_context_tokens == 0 || self.max_context_tokens > MAX_CONTEXT_TOKENS {
+            return Err(Error::Validation(format!(
+                "max_context_tokens must be between 1 and {}",
+                MAX_CONTEXT_TOKENS
+            )));
+        }
+
+        Ok(AskOptions {
+            limit: self.limit,
+            min_score: self.min_score,
+            max_context_tokens: self.max_context_tokens,
+            follow_links: self.follow_links,
+        })
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Answer a question from the project's memories.
+///
+/// POST /projects/:project_id/ask
+///
+/// Questions the memories do not answer are refused (`grounded: false`).
+async fn ask(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Json(request): Json<AskRequest>,
+) -> Result<Response> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let options = request.options()?;
+
+    if !request.stream {
+        let answer = state.ask.ask(&project, &request.question, &options).await?;
+        return Ok(Json(answer).into_response());
+    }
+
+    let events = state
+        .ask
+        .ask_stream(&project, &request.question, &options)
+        .await?;
+
+    let stream = events.map(|event| {
+        let event = match event {
+            Ok(AskEvent::Sources(sources)) => Event::default()
+                .event("sources")
+                .data(json!({ "sources": sources }).to_string()),
+            Ok(AskEvent::Delta(text)) => Event::default()
+                .event("delta")
+                .data(json!({ "text": text }).to_string()),
+            Ok(AskEvent::Done(answer)) => Event::default()
+                .event("done")
+                .data(serde_json::to_string(&answer).unwrap_or_default()),
+            Err(e) => Event::default()
+                .event("error")
+                .data(json!({ "error": e.to_string() }).to_string()),
+        };
+        Ok::<_, Infallible>(event)
+    });
+
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+        .into_response())
+}
@@ -0,0 +1,172 @@
+//! Audit Log Routes
+//!
+//! Admin access to the audit log of security-relevant and data-changing
+//! operations. Set `AUDIT_LOG_PATH` to also stream events to a JSON lines
+//! file for external retention.
+//!
+//! Routes:
+//! - GET /audit - List audit events (admin only), filterable by action,
+//!   resource, project, actor and time range
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, AuditAction, AuditEvent, AuditFilter};
+use crate::middleware::AuthUser;
+use crate::{AppState, Error, Result};
+
+/// Build audit routes.
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(list_events))
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListEventsQuery {
+    /// Action, e.g. `memory.delete`
+    pub action: Option<String>,
+    /// Resource type, e.g. `memory`, `token`
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub project_id: Option<String>,
+    /// Acting user
+    pub user_id: Option<String>,
+    /// Acting API token
+    pub token_id: Option<String>,
+    /// Events at or after this time (RFC 3339 or `YYYY-MM-DD HH:MM:SS`)
+    pub since: Option<String>,
+    /// Events before this time (RFC 3339 or `YYYY-MM-DD HH:MM:SS`)
+    pub until: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// Audit event.
+#[derive(Debug, Serialize)]
+pub struct AuditEventResponse {
+    pub id: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub project_id: Option<String>,
+    pub actor_user_id: Option<String>,
+    pub actor_token_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub created_at: String,
+}
+
+impl From<AuditEvent> for AuditEventResponse {
+    fn from(e: AuditEvent) -> Self {
+        Self {
+            details: e.details_json(),
+            id: e.id,
+            action: e.action,
+            resource_type: e.resource_type,
+            resource_id: e.resource_id,
+            project_id: e.project_id,
+            actor_user_id: e.actor_user_id,
+            actor_token_id: e.actor_token_id,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListEventsResponse {
+    pub events: Vec<AuditEventResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Normalise a time filter to the `YYYY-MM-DD HH:MM:SS` UTC format
+/// `created_at` is stored in, so string comparison orders correctly.
+fn parse_time(name: &str, value: Option<String>) -> Result<Option<String>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(&value) {
+        return Ok(Some(
+            t.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ));
+    }
+    if chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S").is_ok() {
+        return Ok(Some(value));
+    }
+    if let Ok(d) = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+        return Ok(Some(format!("{} 00:00:00", d)));
+    }
+
+    Err(Error::Validation(format!(
+        "Invalid {} time: {}",
+        name, value
+    )))
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List audit events, most recent first.
+///
+/// GET /audit
+async fn list_events(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Query(query): Query<ListEventsQuery>,
+) -> Result<Json<ListEventsResponse>> {
+    if !auth.is_admin() {
+        return Err(Error::Forbidden);
+    }
+
+    if let Some(ref action) = query.action {
+        if AuditAction::from_str(action).is_none() {
+            return Err(Error::Validation(format!(
+                "Unknown audit action: {}",
+                action
+            )));
+        }
+    }
+
+    let limit = query.limit.clamp(1, 1000);
+    let offset = query.offset.max(0);
+
+    let filter = AuditFilter {
+        action: query.action,
+        resource_type: query.resource_type,
+        resource_id: query.resource_id,
+        project_id: query.project_id,
+        actor_user_id: query.user_id,
+        actor_token_id: query.token_id,
+        since: parse_time("since", query.since)?,
+        until: parse_time("until", query.until)?,
+        limit: Some(limit),
+        offset: Some(offset),
+    };
+
+    let events = db::list_audit_events(&state.db, &filter).await?;
+    let total = db::count_audit_events(&state.db, &filter).await?;
+
+    Ok(Json(ListEventsResponse {
+        events: events.into_iter().map(Into::into).collect(),
+        total,
+        limit,
+        offset,
+    }))
+}
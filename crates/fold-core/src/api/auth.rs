@@ -31,7 +31,9 @@ use std::collections::HashMap;
 use std::sync::OnceLock;
 use tokio::sync::RwLock;
 
-use crate::middleware::{require_auth, require_session, AuthUser};
+use crate::db::{self, AuditAction};
+use crate::middleware::{require_auth, require_session, scopes, AuthContext, AuthUser};
+use crate::services::AuditActor;
 use crate::{AppState, Error, Result};
 
 // ============================================================================
@@ -204,8 +206,8 @@ pub struct UserInfo {
 #[derive(Debug, Deserialize)]
 pub struct CreateTokenRequest {
     pub name: String,
+    /// Scopes to grant, e.g. `["memories:read"]` (empty = full access)
     #[serde(default)]
-    #[allow(dead_code)]
     pub scopes: Vec<String>,
     /// Optional expiry in days from now
     pub expires_in_days: Option<i64>,
@@ -221,6 +223,7 @@ pub struct CreateTokenResponse {
     pub name: String,
     pub token: String,
     pub token_prefix: String,
+    pub scopes: Vec<String>,
     pub created_at: String,
     pub expires_at: Option<String>,
 }
@@ -231,6 +234,7 @@ pub struct ApiTokenInfo {
     pub id: String,
     pub name: String,
     pub token_prefix: String,
+    pub scopes: Vec<String>,
     pub created_at: String,
     pub last_used: Option<String>,
     pub expires_at: Option<String>,
@@ -670,6 +674,17 @@ async fn bootstrap(
     .execute(&state.db)
     .await?;
 
+    state
+        .audit
+        .record(
+            &AuditActor::user(&user_id),
+            AuditAction::TokenCreate,
+            None,
+            Some(&token_id),
+            serde_json::json!({ "name": "Bootstrap Token", "owner_user_id": user_id }),
+        )
+        .await;
+
     Ok(Json(BootstrapResponse {
         user_id,
         api_token,
@@ -696,16 +711,19 @@ async fn list_tokens(
         String,
         String,
         String,
+        Option<String>,
         String,
         Option<String>,
         Option<String>,
         Option<String>,
     )> = sqlx::query_as(
         r#"
-        SELECT id, name, token_prefix, created_at, last_used, expires_at, revoked_at
-        FROM api_tokens
-        WHERE user_id = ?
-        ORDER BY created_at DESC
+        SELECT t.id, t.name, t.token_prefix, s.scopes, t.created_at, t.last_used,
+               t.expires_at, t.revoked_at
+        FROM api_tokens t
+        LEFT JOIN api_token_scopes s ON s.token_id = t.id
+        WHERE t.user_id = ?
+        ORDER BY t.created_at DESC
         "#,
     )
     .bind(&user.user_id)
@@ -715,11 +733,12 @@ async fn list_tokens(
     let tokens = tokens
         .into_iter()
         .map(
-            |(id, name, token_prefix, created_at, last_used, expires_at, revoked_at)| {
+            |(id, name, token_prefix, scopes, created_at, last_used, expires_at, revoked_at)| {
                 ApiTokenInfo {
                     id,
                     name,
                     token_prefix,
+                    scopes: stored_scopes(scopes),
                     created_at,
                     last_used,
                     expires_at,
@@ -736,7 +755,7 @@ async fn list_tokens(
 ///
 /// POST /auth/tokens
 ///
-/// Creates a new API token with the specified name and optional expiry.
+/// Creates a new API token with the specified name, scopes and optional expiry.
 /// The full token value is returned only once in the response.
 ///
 /// If user_id is provided, only admins can create tokens for other users.
@@ -744,6 +763,8 @@ async fn list_tokens(
 async fn create_token(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
+    caller: Option<Extension<AuthContext>>,
+    actor: AuditActor,
     Json(request): Json<CreateTokenRequest>,
 ) -> Result<Json<CreateTokenResponse>> {
     // Validate name
@@ -780,6 +801,27 @@ async fn create_token(
     };
     eprintln!("DEBUG: Final target_user_id = {}", target_user_id);
 
+    // Validate scopes. A token can only create tokens with scopes it holds.
+    let mut token_scopes: Vec<String> = Vec::new();
+    for scope in request.scopes.iter().map(|s| s.trim()) {
+        if !scopes::is_valid_scope(scope) {
+            return Err(Error::InvalidInput(format!("Unknown scope: {}", scope)));
+        }
+        if !token_scopes.iter().any(|s| s == scope) {
+            token_scopes.push(scope.to_string());
+        }
+    }
+    let granted = if token_scopes.is_empty() {
+        vec![scopes::ALL.to_string()]
+    } else {
+        token_scopes.clone()
+    };
+    if let Some(Extension(caller)) = &caller {
+        if !granted.iter().all(|s| caller.scopes.allows(s)) {
+            return Err(Error::Forbidden);
+        }
+    }
+
     // Generate API token in format: fold_{prefix}_{secret}
     let prefix = nanoid::nanoid!(8);
     let secret = nanoid::nanoid!(32);
@@ -817,16 +859,45 @@ async fn create_token(
     .execute(&state.db)
     .await?;
 
+    // Tokens without a scope record have full access
+    if !token_scopes.is_empty() {
+        db::set_api_token_scopes(&state.db, &token_id, &token_scopes).await?;
+    }
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::TokenCreate,
+            None,
+            Some(&token_id),
+            serde_json::json!({
+                "name": name,
+                "owner_user_id": target_user_id,
+                "scopes": granted,
+                "expires_at": expires_at,
+            }),
+        )
+        .await;
+
     Ok(Json(CreateTokenResponse {
         id: token_id,
         name: name.to_string(),
         token: api_token,
         token_prefix: prefix,
+        scopes: granted,
         created_at,
         expires_at,
     }))
 }
 
+/// Scopes for token info, `["*"]` for tokens without a scope record.
+fn stored_scopes(stored: Option<String>) -> Vec<String> {
+    stored
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_else(|| vec![scopes::ALL.to_string()])
+}
+
 /// Revoke an API token.
 ///
 /// DELETE /auth/tokens/:token_id
@@ -837,6 +908,7 @@ async fn revoke_token(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Path(token_id): Path<String>,
+    actor: AuditActor,
 ) -> Result<Json<serde_json::Value>> {
     // Verify the token belongs to the user
     let token_exists: Option<(String,)> =
@@ -858,6 +930,17 @@ async fn revoke_token(
         .execute(&state.db)
         .await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::TokenRevoke,
+            None,
+            Some(&token_id),
+            serde_json::Value::Null,
+        )
+        .await;
+
     Ok(Json(serde_json::json!({
         "message": "Token revoked successfully",
         "token_id": token_id
@@ -880,16 +963,19 @@ async fn admin_list_user_tokens(
         String,
         String,
         String,
+        Option<String>,
         String,
         Option<String>,
         Option<String>,
         Option<String>,
     )> = sqlx::query_as(
         r#"
-        SELECT id, name, token_prefix, created_at, last_used, expires_at, revoked_at
-        FROM api_tokens
-        WHERE user_id = ? AND revoked_at IS NULL
-        ORDER BY created_at DESC
+        SELECT t.id, t.name, t.token_prefix, s.scopes, t.created_at, t.last_used,
+               t.expires_at, t.revoked_at
+        FROM api_tokens t
+        LEFT JOIN api_token_scopes s ON s.token_id = t.id
+        WHERE t.user_id = ? AND t.revoked_at IS NULL
+        ORDER BY t.created_at DESC
         "#,
     )
     .bind(&user_id)
@@ -899,11 +985,12 @@ async fn admin_list_user_tokens(
     let tokens = result
         .into_iter()
         .map(
-            |(id, name, token_prefix, created_at, last_used, expires_at, revoked_at)| {
+            |(id, name, token_prefix, scopes, created_at, last_used, expires_at, revoked_at)| {
                 ApiTokenInfo {
                     id,
                     name,
                     token_prefix,
+                    scopes: stored_scopes(scopes),
                     created_at,
                     last_used,
                     expires_at,
@@ -922,6 +1009,7 @@ async fn admin_revoke_user_token(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
     Path((user_id, token_id)): Path<(String, String)>,
+    actor: AuditActor,
 ) -> Result<Json<serde_json::Value>> {
     // Only admins can revoke tokens for other users
     if !auth.is_admin() {
@@ -953,6 +1041,17 @@ async fn admin_revoke_user_token(
         .execute(&state.db)
         .await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::TokenRevoke,
+            None,
+            Some(&token_id),
+            serde_json::Value::Null,
+        )
+        .await;
+
     Ok(Json(serde_json::json!({
         "message": "Token revoked successfully",
         "token_id": token_id
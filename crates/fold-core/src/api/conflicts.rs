@@ -0,0 +1,310 @@
+//! Memory Conflict Routes
+//!
+//! Contradictions between new memories and existing decisions, and pushed
+//! fold/ file edits held back because the memory also changed in Fold, for
+//! humans to review and resolve.
+//!
+//! Routes:
+//! - GET /projects/:project_id/conflicts - List conflicts (default: open)
+//! - POST /projects/:project_id/conflicts/:conflict_id/resolve - Resolve or dismiss a conflict
+//! - GET /projects/:project_id/fold-sync/conflicts - List fold sync conflicts (default: open)
+//! - POST /projects/:project_id/fold-sync/conflicts/:conflict_id/resolve - Apply or discard a held-back edit
+
+use axum::{
+    extract::{Path, Query, State},
+    middleware,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ConflictStatus, FoldSyncConflict, MemoryConflict};
+use crate::middleware::{require_project_read, require_project_write, AuthContext};
+use crate::services::FoldSyncResult;
+use crate::{AppState, Error, Result};
+
+/// Build conflict routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/conflicts", get(list_conflicts))
+        .route(
+            "/:project_id/fold-sync/conflicts",
+            get(list_fold_sync_conflicts),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/conflicts/:conflict_id/resolve",
+            post(resolve_conflict),
+        )
+        .route(
+            "/:project_id/fold-sync/conflicts/:conflict_id/resolve",
+            post(resolve_fold_sync_conflict),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ListConflictsQuery {
+    /// open | resolved | dismissed | all
+    #[serde(default = "default_status")]
+    pub status: String,
+}
+
+fn default_status() -> String {
+    "open".to_string()
+}
+
+/// Conflict resolution request.
+#[derive(Debug, Deserialize)]
+pub struct ResolveConflictRequest {
+    /// resolved | dismissed
+    pub status: ConflictStatus,
+    /// How the conflict was settled (e.g. which decision stands)
+    pub resolution: Option<String>,
+}
+
+/// Summary of one side of a conflict.
+#[derive(Debug, Serialize)]
+pub struct ConflictMemory {
+    pub id: String,
+    pub title: Option<String>,
+    pub memory_type: Option<String>,
+}
+
+/// Memory conflict with both memories summarised.
+#[derive(Debug, Serialize)]
+pub struct ConflictResponse {
+    pub id: String,
+    pub memory: ConflictMemory,
+    pub conflicts_with: ConflictMemory,
+    pub reason: Option<String>,
+    pub confidence: Option<f64>,
+    pub status: String,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListConflictsResponse {
+    pub conflicts: Vec<ConflictResponse>,
+    pub total: usize,
+}
+
+/// Fold sync conflict resolution request.
+#[derive(Debug, Deserialize)]
+pub struct ResolveFoldSyncConflictRequest {
+    /// resolved | dismissed
+    pub status: ConflictStatus,
+    pub resolution: Option<String>,
+    /// Apply the pushed edit over Fold's copy (default: keep Fold's copy)
+    #[serde(default)]
+    pub apply_pushed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListFoldSyncConflictsResponse {
+    pub conflicts: Vec<FoldSyncConflict>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveFoldSyncConflictResponse {
+    pub conflict: FoldSyncConflict,
+    pub applied: Option<FoldSyncResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConflictPath {
+    pub project_id: String,
+    pub conflict_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List memory conflicts.
+///
+/// GET /projects/:project_id/conflicts
+async fn list_conflicts(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListConflictsQuery>,
+) -> Result<Json<ListConflictsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let status = parse_status_filter(&query.status)?;
+
+    let mut conflicts = Vec::new();
+    for conflict in db::list_project_conflicts(&state.db, &project.id, status).await? {
+        conflicts.push(to_response(&state, conflict).await);
+    }
+
+    Ok(Json(ListConflictsResponse {
+        total: conflicts.len(),
+        conflicts,
+    }))
+}
+
+/// Resolve or dismiss a conflict.
+///
+/// POST /projects/:project_id/conflicts/:conflict_id/resolve
+async fn resolve_conflict(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(path): Path<ConflictPath>,
+    Json(request): Json<ResolveConflictRequest>,
+) -> Result<Json<ConflictResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let conflict = db::get_memory_conflict(&state.db, &path.conflict_id).await?;
+    if conflict.project_id != project.id {
+        return Err(Error::NotFound(format!(
+            "Conflict not found: {}",
+            path.conflict_id
+        )));
+    }
+
+    let conflict = db::resolve_memory_conflict(
+        &state.db,
+        &conflict.id,
+        request.status,
+        request.resolution.as_deref(),
+        Some(&auth.user_id),
+    )
+    .await?;
+
+    Ok(Json(to_response(&state, conflict).await))
+}
+
+/// List pushed fold/ edits that were held back.
+///
+/// GET /projects/:project_id/fold-sync/conflicts
+async fn list_fold_sync_conflicts(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListConflictsQuery>,
+) -> Result<Json<ListFoldSyncConflictsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let status = parse_status_filter(&query.status)?;
+
+    let conflicts = db::list_fold_sync_conflicts(&state.db, &project.id, status).await?;
+
+    Ok(Json(ListFoldSyncConflictsResponse {
+        total: conflicts.len(),
+        conflicts,
+    }))
+}
+
+/// Resolve a fold sync conflict, optionally applying the pushed edit.
+///
+/// POST /projects/:project_id/fold-sync/conflicts/:conflict_id/resolve
+async fn resolve_fold_sync_conflict(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(path): Path<ConflictPath>,
+    Json(request): Json<ResolveFoldSyncConflictRequest>,
+) -> Result<Json<ResolveFoldSyncConflictResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let conflict = db::get_fold_sync_conflict(&state.db, &path.conflict_id).await?;
+    if conflict.project_id != project.id {
+        return Err(Error::NotFound(format!(
+            "Conflict not found: {}",
+            path.conflict_id
+        )));
+    }
+    if conflict.status != ConflictStatus::Open.as_str() {
+        return Err(Error::Validation(format!(
+            "Conflict {} is already {}",
+            conflict.id, conflict.status
+        )));
+    }
+    if request.apply_pushed && request.status != ConflictStatus::Resolved {
+        return Err(Error::Validation(
+            "apply_pushed requires status 'resolved'".to_string(),
+        ));
+    }
+
+    let applied = match request.status {
+        ConflictStatus::Resolved => Some(
+            state
+                .git_sync
+                .resolve_fold_conflict(&project, &conflict, request.apply_pushed)
+                .await?,
+        ),
+        _ => None,
+    };
+
+    let conflict = db::resolve_fold_sync_conflict(
+        &state.db,
+        &conflict.id,
+        request.status,
+        request.resolution.as_deref(),
+        Some(&auth.user_id),
+    )
+    .await?;
+
+    Ok(Json(ResolveFoldSyncConflictResponse { conflict, applied }))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn parse_status_filter(status: &str) -> Result<Option<ConflictStatus>> {
+    match status {
+        "all" => Ok(None),
+        s => ConflictStatus::from_str(s)
+            .map(Some)
+            .ok_or_else(|| Error::Validation(format!("Invalid conflict status: {}", s))),
+    }
+}
+
+async fn summarise_memory(state: &AppState, memory_id: &str) -> ConflictMemory {
+    match db::get_memory(&state.db, memory_id).await {
+        Ok(m) => ConflictMemory {
+            id: m.id,
+            title: m.title,
+            memory_type: Some(m.memory_type),
+        },
+        Err(_) => ConflictMemory {
+            id: memory_id.to_string(),
+            title: None,
+            memory_type: None,
+        },
+    }
+}
+
+async fn to_response(state: &AppState, c: MemoryConflict) -> ConflictResponse {
+    ConflictResponse {
+        memory: summarise_memory(state, &c.memory_id).await,
+        conflicts_with: summarise_memory(state, &c.conflicting_memory_id).await,
+        id: c.id,
+        reason: c.reason,
+        confidence: c.confidence,
+        status: c.status,
+        resolution: c.resolution,
+        resolved_by: c.resolved_by,
+        created_at: c.created_at,
+        resolved_at: c.resolved_at,
+    }
+}
@@ -0,0 +1,334 @@
+//! Memory Consolidation Routes
+//!
+//! Configure and run merging of near-duplicate agent memories, and review
+//! the merges it proposes.
+//!
+//! Routes:
+//! - GET /projects/:project_id/consolidation - Get consolidation settings
+//! - PUT /projects/:project_id/consolidation - Update consolidation settings
+//! - POST /projects/:project_id/consolidation/run - Queue a consolidation job
+//! - GET /projects/:project_id/consolidation/merges - List merges (default: proposed)
+//! - POST /projects/:project_id/consolidation/merges/:merge_id/apply - Apply a proposed merge
+//! - POST /projects/:project_id/consolidation/merges/:merge_id/reject - Reject a proposed merge
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ConsolidationSettings, JobType, MemoryMerge, MergeStatus};
+use crate::middleware::{require_project_read, require_project_write};
+use crate::{AppState, Error, Result};
+
+/// Build consolidation routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/consolidation", get(get_settings))
+        .route("/:project_id/consolidation/merges", get(list_merges))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route("/:project_id/consolidation", put(update_settings))
+        .route("/:project_id/consolidation/run", post(run_consolidation))
+        .route(
+            "/:project_id/consolidation/merges/:merge_id/apply",
+            post(apply_merge),
+        )
+        .route(
+            "/:project_id/consolidation/merges/:merge_id/reject",
+            post(reject_merge),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Consolidation settings.
+#[derive(Debug, Serialize)]
+pub struct SettingsResponse {
+    /// Run consolidation periodically
+    pub enabled: bool,
+    /// Cosine similarity (0.5-1.0) above which memories are merged
+    pub similarity_threshold: f64,
+    /// Apply merges immediately instead of proposing them for review
+    pub auto_apply: bool,
+    /// Maximum memories folded into one survivor
+    pub max_cluster_size: i64,
+    /// Hours between periodic runs
+    pub interval_hours: i64,
+    pub last_run_at: Option<String>,
+}
+
+impl From<ConsolidationSettings> for SettingsResponse {
+    fn from(s: ConsolidationSettings) -> Self {
+        Self {
+            enabled: s.is_enabled(),
+            auto_apply: s.is_auto_apply(),
+            similarity_threshold: s.similarity_threshold,
+            max_cluster_size: s.max_cluster_size,
+            interval_hours: s.interval_hours,
+            last_run_at: s.last_run_at,
+        }
+    }
+}
+
+/// Settings update request. Omitted fields are unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingsRequest {
+    pub enabled: Option<bool>,
+    pub similarity_threshold: Option<f64>,
+    pub auto_apply: Option<bool>,
+    pub max_cluster_size: Option<i64>,
+    pub interval_hours: Option<i64>,
+}
+
+/// Run request.
+#[derive(Debug, Deserialize, Default)]
+pub struct RunRequest {
+    /// Report clusters and merged text without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunResponse {
+    pub job_id: String,
+    pub dry_run: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListMergesQuery {
+    /// proposed | applied | rejected | all
+    #[serde(default = "default_status")]
+    pub status: String,
+}
+
+fn default_status() -> String {
+    "proposed".to_string()
+}
+
+/// Memory merge.
+#[derive(Debug, Serialize)]
+pub struct MergeResponse {
+    pub id: String,
+    pub survivor_id: String,
+    pub source_ids: Vec<String>,
+    pub similarity: Option<f64>,
+    pub title: Option<String>,
+    pub content: String,
+    pub status: String,
+    pub job_id: Option<String>,
+    pub created_at: String,
+    pub decided_at: Option<String>,
+}
+
+impl From<MemoryMerge> for MergeResponse {
+    fn from(m: MemoryMerge) -> Self {
+        Self {
+            source_ids: m.source_ids_vec(),
+            id: m.id,
+            survivor_id: m.survivor_id,
+            similarity: m.similarity,
+            title: m.title,
+            content: m.content,
+            status: m.status,
+            job_id: m.job_id,
+            created_at: m.created_at,
+            decided_at: m.decided_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListMergesResponse {
+    pub merges: Vec<MergeResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergePath {
+    pub project_id: String,
+    pub merge_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Get consolidation settings.
+///
+/// GET /projects/:project_id/consolidation
+async fn get_settings(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+) -> Result<Json<SettingsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let settings = db::get_consolidation_settings(&state.db, &project.id).await?;
+    Ok(Json(settings.into()))
+}
+
+/// Update consolidation settings.
+///
+/// PUT /projects/:project_id/consolidation
+async fn update_settings(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Json(request): Json<UpdateSettingsRequest>,
+) -> Result<Json<SettingsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    if let Some(threshold) = request.similarity_threshold {
+        if !(0.5..=1.0).contains(&threshold) {
+            return Err(Error::Validation(
+                "similarity_threshold must be between 0.5 and 1.0".into(),
+            ));
+        }
+    }
+    if let Some(size) = request.max_cluster_size {
+        if !(2..=20).contains(&size) {
+            return Err(Error::Validation(
+                "max_cluster_size must be between 2 and 20".into(),
+            ));
+        }
+    }
+    if let Some(hours) = request.interval_hours {
+        if hours < 1 {
+            return Err(Error::Validation(
+                "interval_hours must be at least 1".into(),
+            ));
+        }
+    }
+
+    let settings = db::update_consolidation_settings(
+        &state.db,
+        &project.id,
+        db::UpdateConsolidationSettings {
+            enabled: request.enabled,
+            similarity_threshold: request.similarity_threshold,
+            auto_apply: request.auto_apply,
+            max_cluster_size: request.max_cluster_size,
+            interval_hours: request.interval_hours,
+        },
+    )
+    .await?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Queue a consolidation job. The job's metadata holds the report.
+///
+/// POST /projects/:project_id/consolidation/run
+async fn run_consolidation(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    request: Option<Json<RunRequest>>,
+) -> Result<(StatusCode, Json<RunResponse>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let job_id = crate::models::new_id();
+    db::create_job(
+        &state.db,
+        db::CreateJob::new(job_id.clone(), JobType::ConsolidateMemories)
+            .with_project(project.id.clone())
+            .with_payload(serde_json::json!({ "dry_run": request.dry_run })),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RunResponse {
+            job_id,
+            dry_run: request.dry_run,
+            message: if request.dry_run {
+                "Dry run queued; results will be in the job metadata".to_string()
+            } else {
+                "Consolidation queued".to_string()
+            },
+        }),
+    ))
+}
+
+/// List merges.
+///
+/// GET /projects/:project_id/consolidation/merges
+async fn list_merges(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListMergesQuery>,
+) -> Result<Json<ListMergesResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let status = match query.status.as_str() {
+        "all" => None,
+        s => Some(
+            MergeStatus::from_str(s)
+                .ok_or_else(|| Error::Validation(format!("Invalid merge status: {}", s)))?,
+        ),
+    };
+
+    let merges: Vec<MergeResponse> = db::list_memory_merges(&state.db, &project.id, status)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ListMergesResponse {
+        total: merges.len(),
+        merges,
+    }))
+}
+
+/// Apply a proposed merge.
+///
+/// POST /projects/:project_id/consolidation/merges/:merge_id/apply
+async fn apply_merge(
+    State(state): State<AppState>,
+    Path(path): Path<MergePath>,
+) -> Result<Json<MergeResponse>> {
+    check_merge_project(&state, &path).await?;
+    let merge = state.consolidation.apply(&path.merge_id).await?;
+    Ok(Json(merge.into()))
+}
+
+/// Reject a proposed merge.
+///
+/// POST /projects/:project_id/consolidation/merges/:merge_id/reject
+async fn reject_merge(
+    State(state): State<AppState>,
+    Path(path): Path<MergePath>,
+) -> Result<Json<MergeResponse>> {
+    check_merge_project(&state, &path).await?;
+    let merge = state.consolidation.reject(&path.merge_id).await?;
+    Ok(Json(merge.into()))
+}
+
+/// Ensure the merge belongs to the project in the path.
+async fn check_merge_project(state: &AppState, path: &MergePath) -> Result<()> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let merge = db::get_memory_merge(&state.db, &path.merge_id).await?;
+    if merge.project_id != project.id {
+        return Err(Error::NotFound(format!(
+            "Merge not found: {}",
+            path.merge_id
+        )));
+    }
+    Ok(())
+}
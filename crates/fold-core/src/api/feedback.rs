@@ -0,0 +1,138 @@
+//! Feedback Routes
+//!
+//! Relevance feedback on search results. Verdicts feed ranking strength, and
+//! memories repeatedly marked wrong are flagged for review.
+//!
+//! Routes:
+//! - POST /projects/:project_id/feedback - Record verdicts on a query's results
+//! - GET /projects/:project_id/feedback/flagged - List memories flagged for review
+//! - POST /projects/:project_id/feedback/flagged/:memory_id/resolve - Clear a review flag
+
+use axum::{
+    extract::{Path, State},
+    middleware,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+use crate::middleware::{
+    rate_limit_writes, require_project_read, require_project_write, AuthContext,
+};
+use crate::services::feedback::{FeedbackOutcome, FlaggedMemoryReport};
+use crate::services::FeedbackEntry;
+use crate::{AppState, Result};
+
+/// Build feedback routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/feedback/flagged", get(list_flagged))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/feedback",
+            post(record_feedback).layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_writes,
+            )),
+        )
+        .route(
+            "/:project_id/feedback/flagged/:memory_id/resolve",
+            post(resolve_flag),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Record feedback request.
+#[derive(Debug, Deserialize)]
+pub struct FeedbackRequest {
+    /// The search query the results came from
+    #[serde(default)]
+    pub query: String,
+    pub results: Vec<FeedbackEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlaggedResponse {
+    pub memories: Vec<FlaggedMemoryReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveResponse {
+    pub memory_id: String,
+    /// Number of "wrong" verdicts cleared
+    pub resolved: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemoryPath {
+    pub project_id: String,
+    pub memory_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Record verdicts on the results of a search.
+///
+/// POST /projects/:project_id/feedback
+async fn record_feedback(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(path): Path<ProjectPath>,
+    Json(request): Json<FeedbackRequest>,
+) -> Result<Json<FeedbackOutcome>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let outcome = state
+        .feedback
+        .record(&project.id, &request.query, &auth.token_id, request.results)
+        .await?;
+
+    Ok(Json(outcome))
+}
+
+/// List memories flagged for review, most flagged first.
+///
+/// GET /projects/:project_id/feedback/flagged
+async fn list_flagged(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+) -> Result<Json<FlaggedResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let memories = state.feedback.list_flagged(&project.id).await?;
+
+    Ok(Json(FlaggedResponse { memories }))
+}
+
+/// Clear a memory's "wrong" verdicts once it has been reviewed or fixed.
+///
+/// POST /projects/:project_id/feedback/flagged/:memory_id/resolve
+async fn resolve_flag(
+    State(state): State<AppState>,
+    Path(path): Path<MemoryPath>,
+) -> Result<Json<ResolveResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let resolved = state.feedback.resolve(&project.id, &path.memory_id).await?;
+
+    Ok(Json(ResolveResponse {
+        memory_id: path.memory_id,
+        resolved,
+    }))
+}
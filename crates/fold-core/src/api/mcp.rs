@@ -28,9 +28,15 @@ use serde_json::Value;
 use tokio::sync::{broadcast, RwLock};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::db;
-use crate::middleware::require_token;
-use crate::models::{MemoryCreate, MemorySource, MemoryType, MemoryUpdate};
+use crate::db::{self, AuditAction};
+use crate::middleware::{require_token, scopes};
+use crate::models::{MemoryCreate, MemorySearchResult, MemorySource, MemoryType, MemoryUpdate};
+use crate::services::ask::{DEFAULT_CONTEXT_TOKENS, MAX_CONTEXT_TOKENS};
+use crate::services::point_in_time;
+use crate::services::search_explain::{self, ResultExplanation, SearchExplanation};
+use crate::services::{
+    AskOptions, FederatedSearchOptions, FeedbackEntry, QueryRewrite, RouteClass,
+};
 use crate::{AppState, Error, Result};
 
 // ============================================================================
@@ -157,6 +163,8 @@ const METHOD_NOT_FOUND: i32 = -32601;
 const INVALID_PARAMS: i32 = -32602;
 #[allow(dead_code)]
 const INTERNAL_ERROR: i32 = -32603;
+/// Server-defined: rate limit or daily quota exceeded
+const RATE_LIMITED: i32 = -32000;
 
 // ============================================================================
 // MCP Tool Types
@@ -373,6 +381,7 @@ fn handle_initialize(id: Option<Value>) -> JsonRpcResponse {
 ///
 /// All tools are listed for authenticated users except:
 /// - Admin only: github_project_create
+/// - Tools the token's scopes don't allow
 ///
 /// Permission checks on specific projects happen at execution time.
 async fn handle_tools_list(
@@ -411,11 +420,64 @@ async fn handle_tools_list(
                     "updated_after": { "type": "string", "description": "Filter by updated_at >= this date (ISO 8601 format)" },
                     "updated_before": { "type": "string", "description": "Filter by updated_at <= this date (ISO 8601 format)" },
                     "limit": { "type": "integer", "default": 10, "description": "Max results" },
-                    "min_score": { "type": "number", "default": 0.4, "description": "Minimum similarity score (0-1). Default 0.4 filters to relevant matches only." }
+                    "min_score": { "type": "number", "default": 0.4, "description": "Minimum similarity score (0-1). Default 0.4 filters to relevant matches only." },
+                    "expand": { "type": "boolean", "description": "Also search LLM paraphrases of the query. Defaults to the project setting." },
+                    "hyde": { "type": "boolean", "description": "Also search a hypothetical answer passage written by the LLM. Defaults to the project setting." },
+                    "explain": { "type": "boolean", "default": false, "description": "Explain why each result ranked where it did: vector score, matched chunk, decay inputs, link boosts, query variant ranks and filter effects." },
+                    "as_of": { "type": "string", "description": "Search memories as they were at this point: an ISO 8601 timestamp or date, or an indexed commit SHA. Memories created later are left out and updated ones are shown as they were then. Disables expand and hyde." }
                 },
                 "required": ["project", "query"]
             }),
         },
+        ToolDefinition {
+            name: "memory_search_all".into(),
+            description: "Search memories across every project you can read, for questions like 'where else do we handle retries?'. Scores are normalised per project and each result names its project. Use memory_search when you know the project.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Natural language search query. Use descriptive phrases, not keywords." },
+                    "projects": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only search these projects (IDs or slugs)"
+                    },
+                    "exclude_projects": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Skip these projects (IDs or slugs)"
+                    },
+                    "limit": { "type": "integer", "default": 20, "description": "Max results overall" },
+                    "per_project_limit": { "type": "integer", "default": 5, "description": "Max results from any one project" },
+                    "min_score": { "type": "number", "default": 0.4, "description": "Minimum similarity score (0-1)" }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "memory_feedback".into(),
+            description: "Tell Fold which search results helped. Mark results you relied on as 'used', results unrelated to the query as 'irrelevant', and results that are incorrect or out of date as 'wrong'. Used results rank higher in later searches; memories marked wrong repeatedly are flagged for human review.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project ID or slug" },
+                    "query": { "type": "string", "description": "The search query the results came from" },
+                    "results": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "memory_id": { "type": "string", "description": "Memory ID from the search results" },
+                                "verdict": { "type": "string", "enum": ["used", "irrelevant", "wrong"] },
+                                "comment": { "type": "string", "description": "Why it was wrong, or what it should say" }
+                            },
+                            "required": ["memory_id", "verdict"]
+                        },
+                        "description": "Verdicts on individual results"
+                    }
+                },
+                "required": ["project", "query", "results"]
+            }),
+        },
         ToolDefinition {
             name: "memory_list".into(),
             description: "List memories with optional filters. Supports date filtering and custom sorting.".into(),
@@ -458,11 +520,26 @@ async fn handle_tools_list(
                 "properties": {
                     "project": { "type": "string", "description": "Project ID or slug" },
                     "memory_id": { "type": "string", "description": "Memory ID to get context for" },
-                    "depth": { "type": "integer", "default": 1, "description": "Link traversal depth" }
+                    "depth": { "type": "integer", "default": 1, "description": "Link traversal depth" },
+                    "as_of": { "type": "string", "description": "Show the memory and its related and similar memories as they were at this point: an ISO 8601 timestamp or date, or an indexed commit SHA" }
                 },
                 "required": ["project", "memory_id"]
             }),
         },
+        ToolDefinition {
+            name: "project_ask".into(),
+            description: "Ask a question about a project and get an answer written from its memories, with inline [n] citations to the memories and file line ranges used. Questions the memories do not answer are refused (grounded: false) instead of guessed.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project ID or slug" },
+                    "question": { "type": "string", "description": "Question to answer" },
+                    "limit": { "type": "integer", "default": 10, "description": "Search hits to retrieve" },
+                    "max_context_tokens": { "type": "integer", "default": 6000, "description": "Token budget for the sources given to the LLM" }
+                },
+                "required": ["project", "question"]
+            }),
+        },
         ToolDefinition {
             name: "memory_add".into(),
             description: "Add a memory to a project. Agent memories are stored in the fold/ directory and indexed for semantic search. Use this to persist knowledge, decisions, context, or any information that should be recalled later. If a slug is provided, the memory ID is derived from it - using the same slug again will update the existing memory instead of creating a new one.".into(),
@@ -474,6 +551,12 @@ async fn handle_tools_list(
                     "title": { "type": "string", "description": "Short descriptive title for the memory" },
                     "author": { "type": "string", "description": "Who created this memory (e.g. 'claude', 'user')" },
                     "slug": { "type": "string", "description": "Optional unique slug. If provided, the memory ID is derived from the slug deterministically, enabling upsert behaviour - same slug always refers to the same memory." },
+                    "type": {
+                        "type": "string",
+                        "enum": ["general", "decision", "spec", "task", "session"],
+                        "default": "general",
+                        "description": "Memory type. New decisions and specs are checked against existing decisions and any contradictions are returned as conflicts."
+                    },
                     "tags": {
                         "type": "array",
                         "items": { "type": "string" },
@@ -525,6 +608,36 @@ async fn handle_tools_list(
                 "required": ["project"]
             }),
         },
+        ToolDefinition {
+            name: "team_status".into(),
+            description: "See who is working on what in a project, and publish your own status. Use action 'list' with 'files' before editing to check whether another team member is currently working on the same files. Use action 'update' to announce your current task and files, and 'leave' when you are done.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project ID or slug" },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "update", "leave"],
+                        "default": "list",
+                        "description": "list: show members and file conflicts; update: set your status; leave: remove yourself"
+                    },
+                    "username": { "type": "string", "description": "Name to publish status under (defaults to the token owner's name; only admins may update or remove another member)" },
+                    "status": {
+                        "type": "string",
+                        "enum": ["active", "idle", "away"],
+                        "default": "active",
+                        "description": "Your status (update only)"
+                    },
+                    "current_task": { "type": "string", "description": "What you are working on (update only)" },
+                    "files": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "For update: files you are editing. For list: files to check for conflicts with other active members."
+                    }
+                },
+                "required": ["project"]
+            }),
+        },
     ];
 
     // Admin-only tools
@@ -544,6 +657,9 @@ async fn handle_tools_list(
         });
     }
 
+    // Hide tools the token's scopes don't allow
+    tools.retain(|t| auth.scopes.allows_tool(&t.name, scopes::tool_scope(&t.name)));
+
     JsonRpcResponse::success(
         id,
         serde_json::to_value(ToolsListResponse { tools }).unwrap(),
@@ -555,8 +671,12 @@ async fn handle_tools_list(
 /// Permission checks:
 /// - github_project_create: Admin only
 /// - memory_add, memory_update, memory_delete: Requires project membership (member role)
-/// - project_stats: Requires project access (viewer or member)
+/// - project_stats, project_ask: Requires project access (viewer or member)
+/// - team_status: Project access for list; member role for update/leave
 /// - Other tools: Available to all authenticated users
+///
+/// Every tool also needs a token scope: `mcp:tools:<name>`, or the data scope
+/// the call needs (`memories:read`, `memories:write` or `projects:admin`).
 async fn handle_tools_call(
     state: &AppState,
     auth: &crate::middleware::AuthContext,
@@ -577,17 +697,49 @@ async fn handle_tools_call(
 
     // Admin-only tools
     if call_params.name == "github_project_create" && !auth.is_admin {
-        return JsonRpcResponse::error(
+        return deny_tool_call(
+            state,
+            auth,
             id,
-            INVALID_PARAMS,
-            "Permission denied: admin access required".into(),
+            &call_params.name,
             None,
-        );
+            "Permission denied: admin access required".into(),
+        )
+        .await;
     }
 
     // For project-scoped tools that require write access, check membership
-    let write_tools = ["memory_add", "memory_update", "memory_delete"];
-    let read_tools = ["project_stats"];
+    let write_tools = [
+        "memory_add",
+        "memory_update",
+        "memory_delete",
+        "memory_feedback",
+    ];
+    let read_tools = ["project_stats", "project_ask", "team_status"];
+
+    // team_status only writes for update/leave actions
+    let is_write = write_tools.contains(&call_params.name.as_str())
+        || (call_params.name == "team_status"
+            && call_params
+                .arguments
+                .get("action")
+                .and_then(|v| v.as_str())
+                .is_some_and(|a| a != "list"));
+
+    let required_scope = if is_write {
+        scopes::MEMORIES_WRITE
+    } else {
+        scopes::tool_scope(&call_params.name)
+    };
+    if !auth.scopes.allows_tool(&call_params.name, required_scope) {
+        let message = format!(
+            "Permission denied: token lacks scope {} or {}{}",
+            required_scope,
+            scopes::MCP_TOOLS_PREFIX,
+            call_params.name
+        );
+        return deny_tool_call(state, auth, id, &call_params.name, None, message).await;
+    }
 
     if write_tools.contains(&call_params.name.as_str()) || read_tools.contains(&call_params.name.as_str()) {
         // Extract project from arguments
@@ -613,39 +765,92 @@ async fn handle_tools_call(
                 match membership {
                     Ok(Some(member)) => {
                         // For write tools, require member role (not just viewer)
-                        if write_tools.contains(&call_params.name.as_str()) && member.role != "member" {
-                            return JsonRpcResponse::error(
+                        if is_write && member.role != "member" {
+                            return deny_tool_call(
+                                state,
+                                auth,
                                 id,
-                                INVALID_PARAMS,
+                                &call_params.name,
+                                Some(&project.id),
                                 "Permission denied: write access required".into(),
-                                None,
-                            );
+                            )
+                            .await;
                         }
                         // For read tools, viewer or member is fine
                     }
                     _ => {
-                        return JsonRpcResponse::error(
+                        return deny_tool_call(
+                            state,
+                            auth,
                             id,
-                            INVALID_PARAMS,
+                            &call_params.name,
+                            Some(&project.id),
                             "Permission denied: project access required".into(),
-                            None,
-                        );
+                        )
+                        .await;
                     }
                 }
             }
         }
     }
 
+    let project_ref = call_params
+        .arguments
+        .get("project")
+        .and_then(|v| v.as_str());
+    let project_id = match project_ref {
+        Some(project_ref) => db::get_project_by_id_or_slug(&state.db, project_ref)
+            .await
+            .ok()
+            .map(|p| p.id),
+        None => None,
+    };
+
+    // Same per-token buckets and project quotas as the REST routes
+    let route_class = match call_params.name.as_str() {
+        "memory_search" | "memory_search_all" | "memory_context" => Some(RouteClass::Search),
+        "memory_add" | "memory_update" | "memory_delete" | "memory_feedback" => {
+            Some(RouteClass::Write)
+        }
+        "project_ask" => Some(RouteClass::Llm),
+        _ => None,
+    };
+    if let Some(class) = route_class {
+        let key = format!("token:{}", auth.token_id);
+        if let Err(e) = state
+            .rate_limits
+            .check_request(class, Some(&key), project_id.as_deref())
+            .await
+        {
+            return JsonRpcResponse::error(
+                id,
+                RATE_LIMITED,
+                e.to_string(),
+                e.retry_after()
+                    .map(|secs| serde_json::json!({ "retry_after": secs })),
+            );
+        }
+    }
+
+    // Audit data-changing calls. Arguments are not recorded as they can
+    // hold memory content.
+    let audited = is_write || call_params.name == "github_project_create";
+    let tool = call_params.name.clone();
+
     let result = match call_params.name.as_str() {
         "project_list" => execute_project_list(state).await,
         "github_project_create" => execute_github_project_create(state, call_params.arguments).await,
         "project_stats" => execute_project_stats(state, call_params.arguments).await,
         "memory_add" => execute_memory_add(state, call_params.arguments).await,
-        "memory_search" => execute_memory_search(state, call_params.arguments).await,
+        "memory_search" => execute_memory_search(state, auth, call_params.arguments).await,
+        "memory_search_all" => execute_memory_search_all(state, auth, call_params.arguments).await,
         "memory_list" => execute_memory_list(state, call_params.arguments).await,
         "memory_context" => execute_memory_context(state, call_params.arguments).await,
+        "memory_feedback" => execute_memory_feedback(state, auth, call_params.arguments).await,
+        "project_ask" => execute_project_ask(state, call_params.arguments).await,
         "memory_update" => execute_memory_update(state, call_params.arguments).await,
         "memory_delete" => execute_memory_delete(state, call_params.arguments).await,
+        "team_status" => execute_team_status(state, auth, call_params.arguments).await,
         _ => {
             return JsonRpcResponse::error(
                 id,
@@ -656,6 +861,22 @@ async fn handle_tools_call(
         }
     };
 
+    if audited {
+        state
+            .audit
+            .record(
+                &auth.into(),
+                AuditAction::McpToolCall,
+                project_id.as_deref(),
+                Some(&tool),
+                serde_json::json!({
+                    "success": result.is_ok(),
+                    "error": result.as_ref().err().map(|e| e.to_string()),
+                }),
+            )
+            .await;
+    }
+
     match result {
         Ok(text) => {
             let response = ToolCallResponse {
@@ -664,6 +885,14 @@ async fn handle_tools_call(
             };
             JsonRpcResponse::success(id, serde_json::to_value(response).unwrap())
         }
+        // Quotas checked inside a tool, e.g. for query rewriting
+        Err(e) if e.retry_after().is_some() => JsonRpcResponse::error(
+            id,
+            RATE_LIMITED,
+            e.to_string(),
+            e.retry_after()
+                .map(|secs| serde_json::json!({ "retry_after": secs })),
+        ),
         Err(e) => {
             let response = ToolCallResponse {
                 content: vec![ToolContent::Text {
@@ -676,6 +905,29 @@ async fn handle_tools_call(
     }
 }
 
+/// Refuse a tool call and record the refusal in the audit log.
+async fn deny_tool_call(
+    state: &AppState,
+    auth: &crate::middleware::AuthContext,
+    id: Option<Value>,
+    tool: &str,
+    project_id: Option<&str>,
+    message: String,
+) -> JsonRpcResponse {
+    state
+        .audit
+        .record(
+            &auth.into(),
+            AuditAction::McpToolDenied,
+            project_id,
+            Some(tool),
+            serde_json::json!({ "reason": message }),
+        )
+        .await;
+
+    JsonRpcResponse::error(id, INVALID_PARAMS, message, None)
+}
+
 /// Handle resources/list method.
 fn handle_resources_list(id: Option<Value>) -> JsonRpcResponse {
     JsonRpcResponse::success(
@@ -874,23 +1126,39 @@ async fn execute_memory_add(state: &AppState, args: Value) -> Result<String> {
         /// Optional slug - if provided, the memory ID is derived from it deterministically.
         /// This enables upsert behaviour: same slug = same memory ID = update instead of create.
         slug: Option<String>,
+        #[serde(rename = "type")]
+        memory_type: Option<String>,
         #[serde(default)]
         tags: Vec<String>,
     }
 
     let params: Params = serde_json::from_value(args)?;
 
+    let memory_type = match params.memory_type.as_deref() {
+        None => MemoryType::General,
+        Some(t) => match MemoryType::from_str(t) {
+            Some(
+                mt @ (MemoryType::General
+                | MemoryType::Decision
+                | MemoryType::Spec
+                | MemoryType::Task
+                | MemoryType::Session),
+            ) => mt,
+            _ => return Err(Error::Validation(format!("Invalid memory type: {}", t))),
+        },
+    };
+
     // Get project by ID or slug
     let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
 
     // Create memory via service (handles DB + Qdrant)
-    let memory = state
+    let (memory, conflicts) = state
         .memory
-        .add(
+        .add_with_conflicts(
             &project.id,
             &project.slug,
             MemoryCreate {
-                memory_type: MemoryType::General,
+                memory_type,
                 content: params.content,
                 author: params.author,
                 title: params.title,
@@ -903,17 +1171,36 @@ async fn execute_memory_add(state: &AppState, args: Value) -> Result<String> {
         )
         .await?;
 
+    // Surface contradictions with existing decisions so the agent can react
+    let conflicts: Vec<Value> = conflicts
+        .into_iter()
+        .map(|c| {
+            serde_json::json!({
+                "conflict_id": c.id,
+                "conflicts_with": c.conflicting_memory_id,
+                "reason": c.reason,
+                "confidence": c.confidence
+            })
+        })
+        .collect();
+
     Ok(serde_json::to_string_pretty(&serde_json::json!({
         "id": memory.id,
         "title": memory.title,
+        "type": memory.memory_type,
         "content": memory.content.as_deref().unwrap_or("").chars().take(200).collect::<String>(),
         "author": memory.author,
         "source": memory.source,
-        "created_at": memory.created_at.to_rfc3339()
+        "created_at": memory.created_at.to_rfc3339(),
+        "conflicts": conflicts
     }))?)
 }
 
-async fn execute_memory_search(state: &AppState, args: Value) -> Result<String> {
+async fn execute_memory_search(
+    state: &AppState,
+    auth: &crate::middleware::AuthContext,
+    args: Value,
+) -> Result<String> {
     #[derive(Deserialize)]
     struct Params {
         project: String,
@@ -927,6 +1214,11 @@ async fn execute_memory_search(state: &AppState, args: Value) -> Result<String>
         limit: usize,
         #[serde(default = "default_min_score")]
         min_score: f32,
+        expand: Option<bool>,
+        hyde: Option<bool>,
+        #[serde(default)]
+        explain: bool,
+        as_of: Option<String>,
     }
 
     fn default_limit() -> usize {
@@ -938,16 +1230,65 @@ async fn execute_memory_search(state: &AppState, args: Value) -> Result<String>
     }
 
     let params: Params = serde_json::from_value(args)?;
+    let start = std::time::Instant::now();
 
     // Get project
     let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
+    let as_of = match &params.as_of {
+        Some(value) => Some(point_in_time::resolve_as_of(&state.db, &project.id, value).await?),
+        None => None,
+    };
+
+    // Query rewriting: project setting, overridden per call. Point-in-time
+    // search is not rewritten.
+    let settings = db::get_search_settings(&state.db, &project.id).await?;
+    let mut rewrite =
+        QueryRewrite::from_settings(&settings).with_overrides(params.expand, params.hyde);
+    if as_of.is_some() {
+        rewrite = rewrite.with_overrides(Some(false), Some(false));
+    }
+    if rewrite.is_enabled() {
+        // Rewriting calls the LLM, which the search route class does not cover
+        state
+            .rate_limits
+            .check_quota(&project.id, db::UsageKind::Llm)
+            .await?;
+    }
 
     // Search via memory service (pure similarity)
     // Fetch more results to account for post-filtering
-    let results = state
-        .memory
-        .search(&project.id, &project.slug, &params.query, params.limit * 3)
-        .await?;
+    let results = if let Some(as_of) = &as_of {
+        state
+            .memory
+            .search_as_of(
+                &project.id,
+                &project.slug,
+                &params.query,
+                None,
+                params.limit * 3,
+                as_of.at,
+            )
+            .await?
+    } else if rewrite.is_enabled() {
+        state
+            .memory
+            .search_rewritten(
+                &project.id,
+                &project.slug,
+                &params.query,
+                None,
+                params.limit * 3,
+                false,
+                rewrite,
+            )
+            .await?
+    } else {
+        state
+            .memory
+            .search(&project.id, &project.slug, &params.query, params.limit * 3)
+            .await?
+    };
+    let top_score = results.iter().map(|r| r.score).reduce(f32::max);
 
     // Parse date filters
     let created_after = params.created_after.as_ref();
@@ -955,77 +1296,228 @@ async fn execute_memory_search(state: &AppState, args: Value) -> Result<String>
     let updated_after = params.updated_after.as_ref();
     let updated_before = params.updated_before.as_ref();
 
-    // Filter by source, min_score, and date ranges
-    let filtered_results: Vec<_> = results
-        .into_iter()
-        .filter(|r| {
-            // Check source filter
-            if let Some(source_str) = &params.source {
-                let source = MemorySource::from_str(source_str);
-                let matches_source = r.memory
-                    .source
-                    .as_deref()
-                    .and_then(MemorySource::from_str)
-                    .map(|s| Some(s) == source)
-                    .unwrap_or(false);
-                if !matches_source {
-                    return false;
-                }
+    // Filter by source, min_score, and date ranges, naming the filter that
+    // rejects a result
+    let rejected_by = |r: &MemorySearchResult| -> Option<&'static str> {
+        // Check source filter
+        if let Some(source_str) = &params.source {
+            let source = MemorySource::from_str(source_str);
+            let matches_source = r.memory
+                .source
+                .as_deref()
+                .and_then(MemorySource::from_str)
+                .map(|s| Some(s) == source)
+                .unwrap_or(false);
+            if !matches_source {
+                return Some("source");
             }
-            // Check min_score
-            if r.score < params.min_score {
-                return false;
+        }
+        // Check min_score
+        if r.score < params.min_score {
+            return Some("min_score");
+        }
+        // Check date filters
+        let created_at = r.memory.created_at.to_rfc3339();
+        let updated_at = r.memory.updated_at.to_rfc3339();
+        if let Some(after) = created_after {
+            if created_at.as_str() < after.as_str() {
+                return Some("created_after");
             }
-            // Check date filters
-            let created_at = r.memory.created_at.to_rfc3339();
-            let updated_at = r.memory.updated_at.to_rfc3339();
-            if let Some(after) = created_after {
-                if created_at.as_str() < after.as_str() {
-                    return false;
-                }
+        }
+        if let Some(before) = created_before {
+            if created_at.as_str() > before.as_str() {
+                return Some("created_before");
             }
-            if let Some(before) = created_before {
-                if created_at.as_str() > before.as_str() {
-                    return false;
-                }
+        }
+        if let Some(after) = updated_after {
+            if updated_at.as_str() < after.as_str() {
+                return Some("updated_after");
             }
-            if let Some(after) = updated_after {
-                if updated_at.as_str() < after.as_str() {
-                    return false;
-                }
+        }
+        if let Some(before) = updated_before {
+            if updated_at.as_str() > before.as_str() {
+                return Some("updated_before");
             }
-            if let Some(before) = updated_before {
-                if updated_at.as_str() > before.as_str() {
-                    return false;
+        }
+        None
+    };
+
+    let ranked_by = if rewrite.is_enabled() {
+        "fusion"
+    } else {
+        "combined_score"
+    };
+    let mut explain = params
+        .explain
+        .then(|| SearchExplanation::new(results.len(), ranked_by, &rewrite));
+    if let Some(explain) = explain.as_mut() {
+        let applied = [
+            ("source", params.source.is_some()),
+            ("min_score", true),
+            ("created_after", created_after.is_some()),
+            ("created_before", created_before.is_some()),
+            ("updated_after", updated_after.is_some()),
+            ("updated_before", updated_before.is_some()),
+        ];
+        for (filter, _) in applied.iter().filter(|(_, set)| *set) {
+            explain.applied(filter);
+        }
+    }
+
+    let mut filtered_results = Vec::new();
+    for r in results {
+        match rejected_by(&r) {
+            Some(filter) => {
+                if let Some(explain) = explain.as_mut() {
+                    explain.removed(filter);
                 }
             }
-            true
-        })
-        .take(params.limit)
-        .collect();
+            None => filtered_results.push(r),
+        }
+    }
+    if let Some(explain) = explain.as_mut() {
+        explain.truncated = filtered_results.len().saturating_sub(params.limit);
+    }
+    filtered_results.truncate(params.limit);
+
+    state.search_analytics.log(
+        db::LogSearchQuery::new(&project.id, "mcp", &params.query)
+            .with_token(&auth.token_id)
+            .with_filters(serde_json::json!({
+                "source": params.source,
+                "created_after": params.created_after,
+                "created_before": params.created_before,
+                "updated_after": params.updated_after,
+                "updated_before": params.updated_before,
+                "limit": params.limit,
+                "min_score": params.min_score,
+                "expand": rewrite.expand,
+                "hyde": rewrite.hyde,
+                "as_of": as_of.as_ref().map(|a| a.at),
+            }))
+            .with_results(
+                filtered_results
+                    .iter()
+                    .map(|r| r.memory.id.clone())
+                    .collect(),
+                top_score,
+            )
+            .with_latency(start.elapsed()),
+    );
+
+    let mut results_json = Vec::with_capacity(filtered_results.len());
+    for (rank, r) in filtered_results.iter().enumerate() {
+        let mut result = serde_json::json!({
+            "id": r.memory.id,
+            "title": r.memory.title,
+            "content": r.memory.content.as_deref().unwrap_or("").chars().take(300).collect::<String>(),
+            "author": r.memory.author,
+            "source": r.memory.source,
+            "score": r.score,
+            "combined_score": r.combined_score,
+            "scoring": r.breakdown,
+            "file_path": r.memory.file_path,
+            "created_at": r.memory.created_at.to_rfc3339(),
+            "updated_at": r.memory.updated_at.to_rfc3339()
+        });
+        if params.explain {
+            let mut explanation = ResultExplanation::from_result(r);
+            search_explain::explain_links(&state.db, &mut explanation, &r.memory.id, rank + 1)
+                .await?;
+            result["explanation"] = serde_json::to_value(explanation)?;
+        }
+        results_json.push(result);
+    }
 
-    let results_json: Vec<_> = filtered_results
+    let mut response = serde_json::json!({
+        "project": project.slug,
+        "query": params.query,
+        "count": results_json.len(),
+        "results": results_json
+    });
+    if let Some(explain) = explain {
+        response["explain"] = serde_json::to_value(explain)?;
+    }
+    if let Some(as_of) = as_of {
+        response["as_of"] = serde_json::to_value(as_of)?;
+    }
+
+    Ok(serde_json::to_string_pretty(&response)?)
+}
+
+async fn execute_memory_search_all(
+    state: &AppState,
+    auth: &crate::middleware::AuthContext,
+    args: Value,
+) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Params {
+        query: String,
+        #[serde(default)]
+        projects: Vec<String>,
+        #[serde(default)]
+        exclude_projects: Vec<String>,
+        #[serde(default = "default_limit")]
+        limit: usize,
+        #[serde(default = "default_per_project_limit")]
+        per_project_limit: usize,
+        #[serde(default = "default_min_score")]
+        min_score: f32,
+    }
+
+    fn default_limit() -> usize {
+        20
+    }
+
+    fn default_per_project_limit() -> usize {
+        5
+    }
+
+    fn default_min_score() -> f32 {
+        0.4
+    }
+
+    let params: Params = serde_json::from_value(args)?;
+
+    // Permissions are checked per project by the federated search service
+    let role = if auth.is_admin { "admin" } else { "member" };
+    let options = FederatedSearchOptions {
+        limit: params.limit.clamp(1, 100),
+        per_project_limit: params.per_project_limit.clamp(1, 100),
+        min_score: params.min_score,
+        include: params.projects,
+        exclude: params.exclude_projects,
+    };
+    let found = state
+        .federated_search
+        .search(&auth.user_id, role, &params.query, &options)
+        .await?;
+
+    let results_json: Vec<_> = found
+        .results
         .iter()
-        .map(|r| {
+        .map(|hit| {
+            let r = &hit.result;
             serde_json::json!({
+                "project": hit.project_slug,
                 "id": r.memory.id,
                 "title": r.memory.title,
                 "content": r.memory.content.as_deref().unwrap_or("").chars().take(300).collect::<String>(),
-                "author": r.memory.author,
                 "source": r.memory.source,
                 "score": r.score,
+                "normalized_score": hit.normalized_score,
                 "file_path": r.memory.file_path,
-                "created_at": r.memory.created_at.to_rfc3339(),
                 "updated_at": r.memory.updated_at.to_rfc3339()
             })
         })
         .collect();
 
     Ok(serde_json::to_string_pretty(&serde_json::json!({
-        "project": project.slug,
-        "query": params.query,
+        "query": found.query,
+        "projects_searched": found.projects_searched,
         "count": results_json.len(),
-        "results": results_json
+        "results": results_json,
+        "failed": found.failed
     }))?)
 }
 
@@ -1128,6 +1620,34 @@ async fn execute_memory_list(state: &AppState, args: Value) -> Result<String> {
     }))?)
 }
 
+async fn execute_memory_feedback(
+    state: &AppState,
+    auth: &crate::middleware::AuthContext,
+    args: Value,
+) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Params {
+        project: String,
+        #[serde(default)]
+        query: String,
+        results: Vec<FeedbackEntry>,
+    }
+
+    let params: Params = serde_json::from_value(args)?;
+    let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
+
+    let outcome = state
+        .feedback
+        .record(&project.id, &params.query, &auth.token_id, params.results)
+        .await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "project": project.slug,
+        "recorded": outcome.recorded,
+        "flagged_for_review": outcome.flagged
+    }))?)
+}
+
 async fn execute_memory_context(state: &AppState, args: Value) -> Result<String> {
     #[derive(Deserialize)]
     struct Params {
@@ -1136,6 +1656,7 @@ async fn execute_memory_context(state: &AppState, args: Value) -> Result<String>
         #[serde(default = "default_depth")]
         #[allow(dead_code)]
         depth: usize,
+        as_of: Option<String>,
     }
 
     fn default_depth() -> usize {
@@ -1146,20 +1667,43 @@ async fn execute_memory_context(state: &AppState, args: Value) -> Result<String>
 
     // Get project
     let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
+    let as_of = match &params.as_of {
+        Some(value) => Some(point_in_time::resolve_as_of(&state.db, &project.id, value).await?),
+        None => None,
+    };
+    let at = as_of.as_ref().map(|a| a.at);
 
     // Get the memory
-    let memory = state
-        .memory
-        .get(&project.id, &params.memory_id)
-        .await?
-        .ok_or_else(|| Error::NotFound("Memory not found".into()))?;
+    let memory = match at {
+        Some(at) => {
+            state
+                .memory
+                .get_as_of(&project.id, &params.memory_id, at)
+                .await?
+        }
+        None => state.memory.get(&project.id, &params.memory_id).await?,
+    }
+    .ok_or_else(|| Error::NotFound("Memory not found".into()))?;
 
-    // Get linked memories
-    let links = db::get_memory_links(&state.db, &params.memory_id).await?;
+    // Get linked memories, leaving out links made after as_of
+    let links: Vec<_> = db::get_memory_links(&state.db, &params.memory_id)
+        .await?
+        .into_iter()
+        .filter(|l| at.is_none_or(|at| point_in_time::existed_at(&l.created_at, at)))
+        .collect();
     let mut related = Vec::new();
 
     for link in &links {
-        if let Ok(Some(linked_memory)) = state.memory.get(&project.id, &link.target_id).await {
+        let linked = match at {
+            Some(at) => {
+                state
+                    .memory
+                    .get_as_of(&project.id, &link.target_id, at)
+                    .await
+            }
+            None => state.memory.get(&project.id, &link.target_id).await,
+        };
+        if let Ok(Some(linked_memory)) = linked {
             related.push(serde_json::json!({
                 "id": linked_memory.id,
                 "title": linked_memory.title,
@@ -1176,14 +1720,18 @@ async fn execute_memory_context(state: &AppState, args: Value) -> Result<String>
 
     // Get similar memories
     let content = memory.content.as_deref().unwrap_or("");
-    let similar_results = if !content.is_empty() {
-        state
+    let similar_results = match at {
+        _ if content.is_empty() => Vec::new(),
+        Some(at) => state
+            .memory
+            .search_as_of(&project.id, &project.slug, content, None, 5, at)
+            .await
+            .unwrap_or_default(),
+        None => state
             .memory
             .search(&project.id, &project.slug, content, 5)
             .await
-            .unwrap_or_default()
-    } else {
-        Vec::new()
+            .unwrap_or_default(),
     };
 
     // Collect related memory IDs to exclude from similar
@@ -1216,10 +1764,49 @@ async fn execute_memory_context(state: &AppState, args: Value) -> Result<String>
             "source": memory.source
         },
         "related": related,
-        "similar": similar
+        "similar": similar,
+        "as_of": as_of
     }))?)
 }
 
+async fn execute_project_ask(state: &AppState, args: Value) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Params {
+        project: String,
+        question: String,
+        #[serde(default = "default_limit")]
+        limit: usize,
+        #[serde(default = "default_max_context_tokens")]
+        max_context_tokens: usize,
+    }
+
+    fn default_limit() -> usize {
+        10
+    }
+
+    fn default_max_context_tokens() -> usize {
+        DEFAULT_CONTEXT_TOKENS
+    }
+
+    let params: Params = serde_json::from_value(args)?;
+
+    if params.question.trim().is_empty() {
+        return Err(Error::Validation("Question cannot be empty".into()));
+    }
+
+    // Get project
+    let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
+
+    let options = AskOptions {
+        limit: params.limit.clamp(1, 50),
+        max_context_tokens: params.max_context_tokens.clamp(1, MAX_CONTEXT_TOKENS),
+        ..Default::default()
+    };
+    let answer = state.ask.ask(&project, &params.question, &options).await?;
+
+    Ok(serde_json::to_string_pretty(&answer)?)
+}
+
 async fn execute_memory_update(state: &AppState, args: Value) -> Result<String> {
     #[derive(Deserialize)]
     struct Params {
@@ -1322,5 +1909,125 @@ async fn execute_memory_delete(state: &AppState, args: Value) -> Result<String>
     }))?)
 }
 
+async fn execute_team_status(
+    state: &AppState,
+    auth: &crate::middleware::AuthContext,
+    args: Value,
+) -> Result<String> {
+    #[derive(Deserialize)]
+    struct Params {
+        project: String,
+        #[serde(default = "default_action")]
+        action: String,
+        username: Option<String>,
+        #[serde(default = "default_status")]
+        status: String,
+        current_task: Option<String>,
+        #[serde(default)]
+        files: Vec<String>,
+    }
+
+    fn default_action() -> String {
+        "list".into()
+    }
+
+    fn default_status() -> String {
+        "active".into()
+    }
 
+    let params: Params = serde_json::from_value(args)?;
+    let project = db::get_project_by_id_or_slug(&state.db, &params.project).await?;
 
+    // Default to the token owner's display name so agents don't need to know
+    // it. Only admins may update or remove someone else's presence.
+    let username = if params.action == "list" {
+        match params.username.filter(|u| !u.trim().is_empty()) {
+            Some(u) => u,
+            None => state.team.presence_name(&auth.user_id).await?,
+        }
+    } else {
+        state
+            .team
+            .acting_member(&auth.user_id, auth.is_admin, params.username.as_deref())
+            .await?
+    };
+
+    match params.action.as_str() {
+        "list" => {
+            let members = state.team.list(&project.id, false).await?;
+            let conflicts = state
+                .team
+                .find_conflicts(&project.id, &params.files, Some(&username))
+                .await?;
+
+            let members: Vec<Value> = members
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "username": m.username,
+                        "status": m.status,
+                        "current_task": m.current_task,
+                        "current_files": m.current_files_vec(),
+                        "last_seen": m.last_seen
+                    })
+                })
+                .collect();
+
+            Ok(serde_json::to_string_pretty(&serde_json::json!({
+                "project": project.slug,
+                "members": members,
+                "conflicts": conflicts
+            }))?)
+        }
+        "update" => {
+            let status = match params.status.as_str() {
+                "active" | "idle" | "away" => db::TeamMemberStatus::from_str(&params.status),
+                other => {
+                    return Err(Error::Validation(format!(
+                        "Invalid status '{}': expected active, idle or away",
+                        other
+                    )))
+                }
+            };
+
+            let member = state
+                .team
+                .update_status(
+                    &project.id,
+                    &username,
+                    db::UpdateTeamStatus {
+                        status,
+                        current_task: params.current_task,
+                        current_files: Some(params.files.clone()),
+                    },
+                )
+                .await?;
+
+            // Warn about overlapping work in the same response
+            let conflicts = state
+                .team
+                .find_conflicts(&project.id, &params.files, Some(&username))
+                .await?;
+
+            Ok(serde_json::to_string_pretty(&serde_json::json!({
+                "username": member.username,
+                "status": member.status,
+                "current_task": member.current_task,
+                "current_files": member.current_files_vec(),
+                "conflicts": conflicts
+            }))?)
+        }
+        "leave" => {
+            state.team.leave(&project.id, &username).await?;
+            Ok(serde_json::to_string_pretty(&serde_json::json!({
+                "left": true,
+                "username": username,
+                "project": project.slug
+            }))?)
+        }
+        other => Err(Error::Validation(format!(
+            "Unknown action '{}': expected list, update or leave",
+            other
+        ))),
+    }
+}
@@ -10,6 +10,9 @@
 //! - DELETE /projects/:project_id/memories/:id - Delete memory
 //! - POST /projects/:project_id/memories/search - Semantic search
 //! - GET /projects/:project_id/context/:id - Get context for a memory
+//!   (`as_of` shows the memory and its neighbours as they were then)
+//!
+//! Search and write routes are rate limited per token.
 
 use axum::{
     body::Body,
@@ -17,7 +20,7 @@ use axum::{
     http::{header, StatusCode},
     middleware,
     response::Response,
-    routing::{get, post},
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
@@ -25,29 +28,56 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::db;
-use crate::middleware::{require_project_read, require_project_write, AuthContext};
+use crate::db::{self, AuditAction};
+use crate::middleware::{
+    rate_limit_search, rate_limit_writes, require_project_read, require_project_write, AuthContext,
+};
 use crate::models::{MemoryCreate, MemorySource, MemoryType, MemoryUpdate};
+use crate::services::point_in_time::{self, AsOf};
+use crate::services::AuditActor;
 use crate::{AppState, Error, Result};
 
 /// Build memory routes (project-scoped).
 pub fn routes(state: AppState) -> Router<AppState> {
-    Router::new()
-        // Read operations (list, search, context, source file download)
-        .route("/search", post(search_memories))
+    // Read operations (list, get, search, context, source file download)
+    let read = Router::new()
+        .route(
+            "/search",
+            post(search_memories).layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_search,
+            )),
+        )
         .route("/context/:memory_id", get(get_context))
         .route("/:memory_id/source", get(download_source_file))
+        .route("/", get(list_memories))
+        .route("/:memory_id", get(get_memory))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             require_project_read,
-        ))
-        // Write operations (create, update, delete)
-        .route("/", get(list_memories).post(create_memory))
+        ));
+
+    // Write operations (create, update, delete)
+    let write = Router::new()
+        .route(
+            "/",
+            post(create_memory).layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_writes,
+            )),
+        )
         .route(
             "/:memory_id",
-            get(get_memory).put(update_memory).delete(delete_memory),
+            put(update_memory)
+                .delete(delete_memory)
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    rate_limit_writes,
+                )),
         )
-        .layer(middleware::from_fn_with_state(state, require_project_write))
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
 }
 
 /// Build global memory routes (cross-project).
@@ -151,6 +181,9 @@ pub struct CreateMemoryRequest {
     pub file_path: Option<String>,
     /// Custom slug (optional - auto-generated from title if not provided)
     pub slug: Option<String>,
+    /// Memory type (defaults to general). Decisions and specs are checked
+    /// against existing decisions for contradictions.
+    pub memory_type: Option<MemoryType>,
     /// Additional metadata
     #[serde(default)]
     #[allow(dead_code)]
@@ -211,6 +244,8 @@ pub struct ContextQuery {
     /// Depth of link traversal (default 1)
     #[serde(default = "default_depth")]
     pub depth: usize,
+    /// Show the memory as it was at this timestamp, date or commit SHA
+    pub as_of: Option<String>,
 }
 
 fn default_depth() -> usize {
@@ -279,6 +314,9 @@ pub struct ContextResponse {
     pub related: Vec<RelatedMemory>,
     /// Similar memories via vector search
     pub similar: Vec<SimilarMemory>,
+    /// Point in time shown (when as_of is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<AsOf>,
 }
 
 /// A related memory via explicit link.
@@ -494,6 +532,7 @@ async fn list_memories(
 async fn create_memory(
     State(state): State<AppState>,
     Path(path): Path<ProjectPath>,
+    actor: AuditActor,
     Json(request): Json<CreateMemoryRequest>,
 ) -> Result<Json<MemoryResponse>> {
     // Resolve project by ID or slug
@@ -513,7 +552,7 @@ async fn create_memory(
             &db_project.id,
             &db_project.slug,
             MemoryCreate {
-                memory_type: MemoryType::General,
+                memory_type: request.memory_type.unwrap_or(MemoryType::General),
                 content: request.content,
                 author: request.author,
                 title: request.title,
@@ -527,6 +566,17 @@ async fn create_memory(
         )
         .await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemoryCreate,
+            Some(&db_project.id),
+            Some(&memory.id),
+            serde_json::json!({ "title": memory.title, "type": memory.memory_type }),
+        )
+        .await;
+
     Ok(Json(memory_to_response_from_model(memory)))
 }
 
@@ -576,11 +626,22 @@ async fn get_memory(
 async fn update_memory(
     State(state): State<AppState>,
     Path(path): Path<MemoryPath>,
+    actor: AuditActor,
     Json(request): Json<UpdateMemoryRequest>,
 ) -> Result<Json<MemoryResponse>> {
     // Resolve project (to validate it exists and user has access)
     let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
 
+    // Record which fields changed, not their values
+    let changed: Vec<&str> = [
+        ("title", request.title.is_some()),
+        ("content", request.content.is_some()),
+        ("tags", request.tags.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(field, set)| set.then_some(field))
+    .collect();
+
     // Build update struct
     let update = MemoryUpdate {
         title: request.title,
@@ -599,6 +660,17 @@ async fn update_memory(
         .update(&project.id, &project.slug, &path.memory_id.to_string(), update)
         .await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemoryUpdate,
+            Some(&project.id),
+            Some(&memory.id),
+            serde_json::json!({ "fields": changed }),
+        )
+        .await;
+
     Ok(Json(memory_to_response_from_model(memory)))
 }
 
@@ -609,11 +681,30 @@ async fn update_memory(
 async fn delete_memory(
     State(state): State<AppState>,
     Path(path): Path<MemoryPath>,
+    actor: AuditActor,
 ) -> Result<Json<serde_json::Value>> {
     // Resolve project (to validate it exists and user has access)
     let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
 
-    // Delete memory from database
+    // Keep a tombstone and the vector for point-in-time queries, then
+    // delete from database
+    if let Some(memory) = state
+        .memory
+        .get_without_tracking(&project.id, &path.memory_id.to_string())
+        .await?
+    {
+        db::record_memory_tombstone(&state.db, &memory, None).await?;
+        let archived = db::get_archived_memory(&state.db, &memory.id)
+            .await?
+            .is_some();
+        if let Err(e) = state
+            .memory
+            .keep_deleted_vector(&project.slug, &memory, archived)
+            .await
+        {
+            warn!(error = %e, memory_id = %path.memory_id, "Failed to keep embedding of deleted memory");
+        }
+    }
     db::delete_memory(&state.db, &path.memory_id.to_string()).await?;
 
     // Delete embedding from Qdrant (non-blocking cleanup)
@@ -625,6 +716,17 @@ async fn delete_memory(
         warn!(error = %e, memory_id = %path.memory_id, "Failed to delete embedding from Qdrant");
     }
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemoryDelete,
+            Some(&project.id),
+            Some(&path.memory_id.to_string()),
+            serde_json::Value::Null,
+        )
+        .await;
+
     Ok(Json(serde_json::json!({
         "deleted": true,
         "id": path.memory_id
@@ -640,9 +742,12 @@ async fn delete_memory(
 #[axum::debug_handler]
 async fn search_memories(
     State(state): State<AppState>,
+    axum::Extension(auth): axum::Extension<AuthContext>,
     Path(path): Path<ProjectPath>,
     Json(request): Json<SearchMemoriesRequest>,
 ) -> Result<Json<SearchMemoriesResponse>> {
+    let start = std::time::Instant::now();
+
     // Resolve project by ID or slug
     let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
 
@@ -662,6 +767,7 @@ async fn search_memories(
             (request.limit * 3) as usize,
         )
         .await?;
+    let top_score = search_results.iter().map(|r| r.score).reduce(f32::max);
 
     // Parse date filters
     let created_after = request.created_after.as_ref();
@@ -717,6 +823,30 @@ async fn search_memories(
         .take(request.limit as usize)
         .collect();
 
+    state.search_analytics.log(
+        db::LogSearchQuery::new(&project.id, "api", &request.query)
+            .with_token(&auth.token_id)
+            .with_filters(serde_json::json!({
+                "source": request.source,
+                "tags": request.tags,
+                "author": request.author,
+                "created_after": request.created_after,
+                "created_before": request.created_before,
+                "updated_after": request.updated_after,
+                "updated_before": request.updated_before,
+                "limit": request.limit,
+                "min_score": request.min_score,
+            }))
+            .with_results(
+                filtered_results
+                    .iter()
+                    .map(|r| r.memory.id.clone())
+                    .collect(),
+                top_score,
+            )
+            .with_latency(start.elapsed()),
+    );
+
     // Convert to API response format
     let results: Vec<SearchResult> = filtered_results
         .into_iter()
@@ -741,25 +871,42 @@ async fn search_memories(
 async fn get_context(
     State(state): State<AppState>,
     Path(path): Path<ContextPath>,
-    Query(_query): Query<ContextQuery>,
+    Query(query): Query<ContextQuery>,
 ) -> Result<Json<ContextResponse>> {
     // Resolve project
     let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
     let memory_id = path.memory_id.to_string();
+    let as_of = match &query.as_of {
+        Some(value) => Some(point_in_time::resolve_as_of(&state.db, &project.id, value).await?),
+        None => None,
+    };
+    let at = as_of.as_ref().map(|a| a.at);
 
     // Get the memory itself
-    let memory = state
-        .memory
-        .get(&project.id, &memory_id)
-        .await?
-        .ok_or_else(|| Error::NotFound("Memory not found".into()))?;
+    let memory = match at {
+        Some(at) => state.memory.get_as_of(&project.id, &memory_id, at).await?,
+        None => state.memory.get(&project.id, &memory_id).await?,
+    }
+    .ok_or_else(|| Error::NotFound("Memory not found".into()))?;
 
-    // Get linked memories
+    // Get linked memories, leaving out links made after as_of
     let links = db::get_memory_links(&state.db, &memory_id).await?;
     let mut related = Vec::new();
 
-    for link in links {
-        if let Ok(Some(linked_memory)) = state.memory.get(&project.id, &link.target_id).await {
+    for link in links
+        .into_iter()
+        .filter(|l| at.is_none_or(|at| point_in_time::existed_at(&l.created_at, at)))
+    {
+        let linked = match at {
+            Some(at) => {
+                state
+                    .memory
+                    .get_as_of(&project.id, &link.target_id, at)
+                    .await
+            }
+            None => state.memory.get(&project.id, &link.target_id).await,
+        };
+        if let Ok(Some(linked_memory)) = linked {
             related.push(RelatedMemory {
                 id: linked_memory.id.clone(),
                 title: linked_memory.title.clone(),
@@ -778,14 +925,18 @@ async fn get_context(
 
     // Get similar memories via vector search
     let content = memory.content.as_deref().unwrap_or("");
-    let similar_results = if !content.is_empty() {
-        state
+    let similar_results = match at {
+        _ if content.is_empty() => Vec::new(),
+        Some(at) => state
+            .memory
+            .search_as_of(&project.id, &project.slug, content, None, 5, at)
+            .await
+            .unwrap_or_default(),
+        None => state
             .memory
             .search(&project.id, &project.slug, content, 5)
             .await
-            .unwrap_or_default()
-    } else {
-        Vec::new()
+            .unwrap_or_default(),
     };
 
     // Collect related memory IDs to exclude from similar
@@ -814,6 +965,7 @@ async fn get_context(
         memory: memory_to_response_from_model(memory),
         related,
         similar,
+        as_of,
     }))
 }
 
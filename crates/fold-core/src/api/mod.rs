@@ -3,22 +3,40 @@
 //! This module combines all API routes into a single router.
 //! Routes are organized by domain and apply appropriate middleware.
 
+mod ask;
+mod audit;
 mod auth;
+mod conflicts;
+mod consolidation;
 mod events;
+mod feedback;
 pub mod groups;
 pub mod mcp;
 mod memories;
+mod outbound_webhooks;
+mod project_archive;
+mod project_providers;
 mod projects;
 mod providers;
 // mod repositories; // Removed: repository info now lives on projects
+mod retention;
 mod search;
+mod search_analytics;
+mod search_eval;
 pub mod status;
+mod team;
+mod transcripts;
+mod usage;
 pub mod users;
+mod vault;
 mod webhooks;
 
 use axum::Router;
 
-use crate::middleware::{require_auth, require_token};
+use crate::middleware::{
+    require_audit_scope, require_auth, require_projects_admin_scope, require_providers_admin_scope,
+    require_read_scope, require_token, require_users_admin_scope,
+};
 use crate::AppState;
 
 /// Build the complete API router.
@@ -26,12 +44,16 @@ use crate::AppState;
 /// Route structure:
 /// - /auth/* - Authentication (public + session-protected)
 /// - /projects/* - Project management (token-protected)
-/// - /memories - Global memory listing (token-protected)
+/// - /memories - Global memory listing and federated search (token-protected)
 /// - /providers/* - Provider management (session-protected, admin)
 /// - /mcp - MCP JSON-RPC endpoint (token-protected)
 /// - /webhooks/* - Git webhooks (signature-verified)
 /// - /health, /metrics - Health checks (public)
 /// - /status/* - System status (token-protected)
+/// - /audit - Audit log (admin only)
+///
+/// Token requests are also checked against the token's scopes; see
+/// `middleware::scopes` for the vocabulary.
 pub fn routes(state: AppState) -> Router<AppState> {
     Router::new()
         // Health check endpoints (public - for load balancers/monitoring)
@@ -55,6 +77,8 @@ pub fn routes(state: AppState) -> Router<AppState> {
         // User and group management (token auth)
         .nest("/users", users_routes(state.clone()))
         .nest("/groups", groups_routes(state.clone()))
+        // Audit log (admin only)
+        .nest("/audit", audit_routes(state.clone()))
         // Protected API routes
         .nest("/projects", protected_routes(state))
 }
@@ -65,12 +89,47 @@ fn protected_routes(state: AppState) -> Router<AppState> {
         // Project CRUD
         .merge(projects::routes(state.clone()))
         // Merge project members routes (use merge instead of nest for proper path param handling)
-        .merge(projects::members_routes())
+        .merge(
+            projects::members_routes()
+                .layer(axum::middleware::from_fn(require_projects_admin_scope)),
+        )
         // Nested project resources
         .nest("/:project_id/memories", memories::routes(state.clone()))
-        .nest("/:project_id/config", projects::config_routes())
+        .nest(
+            "/:project_id/config",
+            projects::config_routes()
+                .layer(axum::middleware::from_fn(require_projects_admin_scope)),
+        )
         // Search and context endpoints
         .merge(search::routes(state.clone()))
+        // Relevance feedback on search results
+        .merge(feedback::routes(state.clone()))
+        // Golden query sets and search quality evaluation
+        .merge(search_eval::routes(state.clone()))
+        // Search query log analytics and knowledge gap reports
+        .merge(search_analytics::routes(state.clone()))
+        // Question answering with citations
+        .merge(ask::routes(state.clone()))
+        // Agent transcript ingestion
+        .merge(transcripts::routes(state.clone()))
+        // Team presence
+        .merge(team::routes(state.clone()))
+        // Contradictions between memories and existing decisions
+        .merge(conflicts::routes(state.clone()))
+        // Merging near-duplicate memories
+        .merge(consolidation::routes(state.clone()))
+        // Retention policies and archived memories
+        .merge(retention::routes(state.clone()))
+        // Provider usage and daily quotas
+        .merge(usage::routes(state.clone()))
+        // Outbound webhooks for memory and link events
+        .merge(outbound_webhooks::routes(state.clone()))
+        // Whole-project export and import
+        .merge(project_archive::routes(state.clone()))
+        // Obsidian/Logseq layout and vault import
+        .merge(vault::routes(state.clone()))
+        // Per-project LLM and embedding provider chains
+        .merge(project_providers::routes(state.clone()))
         // Apply token authentication to all protected routes
         .layer(axum::middleware::from_fn_with_state(state, require_token))
 }
@@ -81,14 +140,18 @@ pub fn admin_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         // Provider management (LLM and embedding providers)
         .merge(providers::routes())
+        .layer(axum::middleware::from_fn(require_providers_admin_scope))
         // Use token auth for API access (same as other protected routes)
         .layer(axum::middleware::from_fn_with_state(state, require_token))
 }
 
-/// Global memories routes (cross-project).
+/// Global memories routes (cross-project listing and search).
 fn global_memories_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         .merge(memories::global_routes())
+        // Federated search across readable projects
+        .merge(search::global_routes(state.clone()))
+        .layer(axum::middleware::from_fn(require_read_scope))
         .layer(axum::middleware::from_fn_with_state(state, require_token))
 }
 
@@ -96,6 +159,7 @@ fn global_memories_routes(state: AppState) -> Router<AppState> {
 fn users_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         .merge(users::routes(state.clone()))
+        .layer(axum::middleware::from_fn(require_users_admin_scope))
         .layer(axum::middleware::from_fn_with_state(state, require_auth))
 }
 
@@ -103,6 +167,15 @@ fn users_routes(state: AppState) -> Router<AppState> {
 fn groups_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         .merge(groups::routes(state.clone()))
+        .layer(axum::middleware::from_fn(require_users_admin_scope))
+        .layer(axum::middleware::from_fn_with_state(state, require_auth))
+}
+
+/// Audit log routes (admin only, token or session).
+fn audit_routes(state: AppState) -> Router<AppState> {
+    Router::<AppState>::new()
+        .merge(audit::routes())
+        .layer(axum::middleware::from_fn(require_audit_scope))
         .layer(axum::middleware::from_fn_with_state(state, require_auth))
 }
 
@@ -110,6 +183,7 @@ fn groups_routes(state: AppState) -> Router<AppState> {
 fn status_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         .merge(status::protected_routes())
+        .layer(axum::middleware::from_fn(require_read_scope))
         .layer(axum::middleware::from_fn_with_state(state, require_token))
 }
 
@@ -117,5 +191,112 @@ fn status_routes(state: AppState) -> Router<AppState> {
 fn events_routes(state: AppState) -> Router<AppState> {
     Router::<AppState>::new()
         .merge(events::routes())
+        .layer(axum::middleware::from_fn(require_read_scope))
         .layer(axum::middleware::from_fn_with_state(state, require_token))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::db::{self, CreateProject, CreateUser, UserRole};
+
+    /// A project member's token limited to `memories:read`.
+    async fn read_only_token(state: &AppState) -> String {
+        db::create_user(
+            &state.db,
+            CreateUser {
+                id: "user-1".to_string(),
+                provider: "local".to_string(),
+                subject: "user-1".to_string(),
+                email: None,
+                display_name: None,
+                avatar_url: None,
+                role: UserRole::Member,
+            },
+        )
+        .await
+        .unwrap();
+
+        db::create_project(
+            &state.db,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+        db::add_project_member(&state.db, "proj-1", "user-1", "member", None)
+            .await
+            .unwrap();
+
+        let (_, token) = state
+            .auth
+            .create_api_token("user-1", "read", vec!["memories:read".to_string()], None)
+            .await
+            .unwrap();
+        token
+    }
+
+    fn request(method: Method, uri: &str, token: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_scope_reaches_read_routes() {
+        let state = AppState::for_tests().await.unwrap();
+        let token = read_only_token(&state).await;
+        let app = routes(state.clone()).with_state(state);
+
+        // Search gets past access checks; it may still fail without Qdrant
+        let response = app
+            .clone()
+            .oneshot(request(
+                Method::POST,
+                "/projects/proj-1/memories/search",
+                &token,
+                r#"{"query": "auth"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(request(Method::GET, "/projects/proj-1/team", &token, ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Writes still need memories:write
+        let response = app
+            .oneshot(request(
+                Method::PUT,
+                "/projects/proj-1/team/user-1",
+                &token,
+                r#"{"status": "active"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
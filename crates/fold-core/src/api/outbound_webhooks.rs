@@ -0,0 +1,340 @@
+//! Outbound Webhook Routes
+//!
+//! Per-project webhooks that receive signed memory and link events, e.g.
+//! to post new decisions to a chat channel. Requests carry
+//! `X-Fold-Event`, `X-Fold-Delivery` and `X-Fold-Signature-256`
+//! (`sha256=` HMAC of the body with the webhook secret).
+//!
+//! Routes:
+//! - GET /projects/:project_id/webhooks - List webhooks
+//! - POST /projects/:project_id/webhooks - Register a webhook (secret returned once)
+//!   Targets must be public addresses
+//! - PUT /projects/:project_id/webhooks/:webhook_id - Update URL, events or active flag
+//! - DELETE /projects/:project_id/webhooks/:webhook_id - Delete a webhook
+//! - GET /projects/:project_id/webhooks/:webhook_id/deliveries - Recent delivery attempts
+
+use axum::{
+    extract::{Path, Query, State},
+    middleware,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::db::{self, AuditAction, CreateOutboundWebhook, UpdateOutboundWebhook, WebhookDelivery};
+use crate::middleware::{
+    require_project_read, require_project_write, require_projects_admin_scope,
+};
+use crate::models::WebhookRegistration;
+use crate::services::{check_target_url, AuditActor, WEBHOOK_EVENT_TYPES};
+use crate::{AppState, Error, Result};
+
+/// Build outbound webhook routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/webhooks", get(list_webhooks))
+        .route(
+            "/:project_id/webhooks/:webhook_id/deliveries",
+            get(list_deliveries),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    // Decides where project events are sent, so it is limited to project admins
+    let write = Router::new()
+        .route("/:project_id/webhooks", post(create_webhook))
+        .route(
+            "/:project_id/webhooks/:webhook_id",
+            put(update_webhook).delete(delete_webhook),
+        )
+        .layer(middleware::from_fn(require_projects_admin_scope))
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Register a webhook.
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    /// http(s) URL events are POSTed to
+    pub url: String,
+    /// Event types, e.g. `memory:created`, `link:*` or `*` (default: all)
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Signing secret (generated when omitted)
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Outbound webhook. The secret is only returned when the webhook is created.
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub url: Option<String>,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl From<WebhookRegistration> for WebhookResponse {
+    fn from(w: WebhookRegistration) -> Self {
+        Self {
+            events: w.events_vec(),
+            id: w.id,
+            project_id: w.project_id,
+            url: w.target_url,
+            active: w.active,
+            created_by: w.created_by,
+            created_at: w.created_at.to_rfc3339(),
+            updated_at: w.updated_at.to_rfc3339(),
+            secret: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListWebhooksResponse {
+    pub webhooks: Vec<WebhookResponse>,
+    pub event_types: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDeliveriesResponse {
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookPath {
+    pub project_id: String,
+    pub webhook_id: String,
+}
+
+fn validate_events(events: &[String]) -> Result<()> {
+    for event in events {
+        let known = match event.strip_suffix('*') {
+            Some(prefix) => WEBHOOK_EVENT_TYPES.iter().any(|t| t.starts_with(prefix)),
+            None => WEBHOOK_EVENT_TYPES.contains(&event.as_str()),
+        };
+        if !known {
+            return Err(Error::Validation(format!(
+                "Unknown webhook event: {} (expected one of {})",
+                event,
+                WEBHOOK_EVENT_TYPES.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Load a project's webhook, treating other projects' webhooks as missing.
+async fn get_project_webhook(state: &AppState, path: &WebhookPath) -> Result<WebhookRegistration> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let webhook = db::get_webhook_registration(&state.db, &path.webhook_id).await?;
+
+    if !webhook.is_outbound() || webhook.project_id.as_deref() != Some(project.id.as_str()) {
+        return Err(Error::NotFound(format!(
+            "Webhook not found: {}",
+            path.webhook_id
+        )));
+    }
+
+    Ok(webhook)
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List a project's webhooks.
+///
+/// GET /projects/:project_id/webhooks
+async fn list_webhooks(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+) -> Result<Json<ListWebhooksResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let webhooks = db::list_outbound_webhooks(&state.db, &project.id).await?;
+
+    Ok(Json(ListWebhooksResponse {
+        webhooks: webhooks.into_iter().map(Into::into).collect(),
+        event_types: WEBHOOK_EVENT_TYPES.to_vec(),
+    }))
+}
+
+/// Register a webhook.
+///
+/// POST /projects/:project_id/webhooks
+async fn create_webhook(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    actor: AuditActor,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<Json<WebhookResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    check_target_url(&request.url).await?;
+    validate_events(&request.events)?;
+    let secret = match request.secret {
+        Some(secret) if secret.len() < 16 => {
+            return Err(Error::Validation(
+                "Webhook secret must be at least 16 characters".to_string(),
+            ));
+        }
+        Some(secret) => secret,
+        None => nanoid::nanoid!(32),
+    };
+
+    let webhook = db::create_outbound_webhook(
+        &state.db,
+        CreateOutboundWebhook {
+            id: crate::models::new_id(),
+            project_id: project.id.clone(),
+            target_url: request.url,
+            secret: secret.clone(),
+            events: request.events,
+            created_by: actor.user_id.clone(),
+        },
+    )
+    .await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::WebhookCreate,
+            Some(&project.id),
+            Some(&webhook.id),
+            json!({ "url": webhook.target_url, "events": webhook.events_vec() }),
+        )
+        .await;
+
+    let mut response = WebhookResponse::from(webhook);
+    response.secret = Some(secret);
+    Ok(Json(response))
+}
+
+/// Update a webhook's URL, events or active flag.
+///
+/// PUT /projects/:project_id/webhooks/:webhook_id
+async fn update_webhook(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookPath>,
+    actor: AuditActor,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookResponse>> {
+    let existing = get_project_webhook(&state, &path).await?;
+
+    if let Some(ref url) = request.url {
+        check_target_url(url).await?;
+    }
+    if let Some(ref events) = request.events {
+        validate_events(events)?;
+    }
+
+    let changed: Vec<&str> = [
+        ("url", request.url.is_some()),
+        ("events", request.events.is_some()),
+        ("active", request.active.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect();
+
+    let webhook = db::update_outbound_webhook(
+        &state.db,
+        &existing.id,
+        UpdateOutboundWebhook {
+            target_url: request.url,
+            events: request.events,
+            active: request.active,
+        },
+    )
+    .await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::WebhookUpdate,
+            webhook.project_id.as_deref(),
+            Some(&webhook.id),
+            json!({ "fields": changed }),
+        )
+        .await;
+
+    Ok(Json(webhook.into()))
+}
+
+/// Delete a webhook and its delivery history.
+///
+/// DELETE /projects/:project_id/webhooks/:webhook_id
+async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookPath>,
+    actor: AuditActor,
+) -> Result<Json<serde_json::Value>> {
+    let webhook = get_project_webhook(&state, &path).await?;
+
+    db::delete_webhook_registration(&state.db, &webhook.id).await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::WebhookDelete,
+            webhook.project_id.as_deref(),
+            Some(&webhook.id),
+            json!({ "url": webhook.target_url }),
+        )
+        .await;
+
+    Ok(Json(json!({ "deleted": true, "id": webhook.id })))
+}
+
+/// List recent delivery attempts for a webhook.
+///
+/// GET /projects/:project_id/webhooks/:webhook_id/deliveries
+async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookPath>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<Json<ListDeliveriesResponse>> {
+    let webhook = get_project_webhook(&state, &path).await?;
+    let deliveries =
+        db::list_webhook_deliveries(&state.db, &webhook.id, query.limit.clamp(1, 500)).await?;
+
+    Ok(Json(ListDeliveriesResponse { deliveries }))
+}
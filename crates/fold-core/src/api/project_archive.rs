@@ -0,0 +1,172 @@
+//! Project Archive Routes
+//!
+//! Export a whole project as a portable JSONL archive and import an archive
+//! as a new project. See `services::project_archive` for the format.
+//!
+//! Routes:
+//! - GET /projects/:project_id/export - Download the archive (`?vectors=true` includes vectors)
+//! - POST /projects/import - Create a project from an archive in the request body
+
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::db::{self, AuditAction};
+use crate::middleware::{require_project_read, require_projects_admin_scope};
+use crate::services::{AuditActor, ImportOptions, ImportResult};
+use crate::{AppState, Error, Result};
+
+use super::projects::is_valid_slug;
+
+/// Largest archive accepted by the import endpoint.
+const MAX_ARCHIVE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Build project archive routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/:project_id/export", get(export_project))
+        .layer(middleware::from_fn_with_state(state, require_project_read))
+        .route(
+            "/import",
+            post(import_project).layer(DefaultBodyLimit::max(MAX_ARCHIVE_BYTES)),
+        )
+        .layer(middleware::from_fn(require_projects_admin_scope))
+}
+
+// ============================================================================
+// Request Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// Include stored vectors so the import can skip re-embedding
+    #[serde(default)]
+    pub vectors: bool,
+}
+
+/// Where to create the imported project.
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Slug for the new project (default: the archived slug)
+    pub slug: Option<String>,
+    /// Name for the new project (default: the archived name)
+    pub name: Option<String>,
+    /// Local path where the new project's fold/ lives
+    pub root_path: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Download a project archive.
+///
+/// GET /projects/:project_id/export
+async fn export_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Query(query): Query<ExportQuery>,
+    actor: AuditActor,
+) -> Result<Response> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+
+    let archive = state
+        .project_archive
+        .export(&project, query.vectors)
+        .await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectExport,
+            Some(&project.id),
+            Some(&project.id),
+            json!({ "vectors": query.vectors, "bytes": archive.len() }),
+        )
+        .await;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.fold.jsonl\"", project.slug),
+        )
+        .body(Body::from(archive))
+        .map_err(|e| Error::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// Create a project from an archive.
+///
+/// POST /projects/import?root_path=...&slug=...
+///
+/// IDs already used on this server are remapped. Vectors are reused when
+/// the archive was embedded with the current model, otherwise recomputed.
+async fn import_project(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    actor: AuditActor,
+    body: String,
+) -> Result<Json<ImportResult>> {
+    if let Some(ref slug) = query.slug {
+        if !is_valid_slug(slug) {
+            return Err(Error::Validation(
+                "Slug must be lowercase alphanumeric with hyphens only".into(),
+            ));
+        }
+    }
+    if query.root_path.trim().is_empty() {
+        return Err(Error::Validation("root_path is required".into()));
+    }
+
+    let result = state
+        .project_archive
+        .import(
+            &body,
+            ImportOptions {
+                slug: query.slug,
+                name: query.name,
+                root_path: query.root_path,
+            },
+        )
+        .await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectImport,
+            Some(&result.project_id),
+            Some(&result.project_id),
+            json!({
+                "slug": result.project_slug,
+                "memories": result.memories,
+                "remapped": result.remapped,
+                "reembedded": result.reembedded,
+            }),
+        )
+        .await;
+
+    // The importing user gets write access, as when creating a project
+    if let Some(ref user_id) = actor.user_id {
+        let _ = db::add_project_member(
+            &state.db,
+            &result.project_id,
+            user_id,
+            "member",
+            Some(user_id),
+        )
+        .await;
+    }
+
+    Ok(Json(result))
+}
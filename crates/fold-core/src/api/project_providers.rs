@@ -0,0 +1,230 @@
+//! Project Provider Override Routes
+//!
+//! Lets a project pin its own LLM and embedding provider chain instead of the
+//! global one under `/providers`. A pinned chain is used for every LLM and
+//! embedding call made for the project and never falls back to the global
+//! providers. API keys are encrypted at rest and never returned.
+//!
+//! Routes:
+//! - GET /projects/:project_id/providers - Current overrides
+//! - PUT /projects/:project_id/providers/:kind - Replace the llm or embedding chain
+//! - DELETE /projects/:project_id/providers/:kind - Revert to the global chain
+
+use axum::{
+    extract::{Path, State},
+    middleware,
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::db::{self, AuditAction, ProjectProviderInput, ProjectProviderKind, ProjectProviderRow};
+use crate::middleware::{
+    require_project_read, require_project_write, require_projects_admin_scope,
+};
+use crate::services::AuditActor;
+use crate::{AppState, Error, Result};
+
+/// LLM providers a project chain may use.
+const LLM_PROVIDERS: &[&str] = &["gemini", "anthropic", "openai", "openrouter", "ollama"];
+
+/// Embedding providers a project chain may use.
+const EMBEDDING_PROVIDERS: &[&str] = &["gemini", "openai", "ollama"];
+
+/// Build project provider override routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/providers", get(get_providers))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/providers/:kind",
+            // Decides where project content is sent and whose keys pay for it
+            put(set_providers)
+                .delete(clear_providers)
+                .layer(middleware::from_fn(require_projects_admin_scope)),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SetProvidersRequest {
+    /// Providers in the order they are tried. Empty reverts to the global chain.
+    pub providers: Vec<ProjectProviderInput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectProviderResponse {
+    pub id: String,
+    pub name: String,
+    pub priority: i32,
+    pub has_api_key: bool,
+    pub config: JsonValue,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<ProjectProviderRow> for ProjectProviderResponse {
+    fn from(row: ProjectProviderRow) -> Self {
+        Self {
+            config: row.config_json().unwrap_or(json!({})),
+            has_api_key: row.api_key.is_some(),
+            id: row.id,
+            name: row.name,
+            priority: row.priority,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectProvidersResponse {
+    /// LLM chain override (empty: global chain)
+    pub llm: Vec<ProjectProviderResponse>,
+    /// Embedding chain override (empty: global chain)
+    pub embedding: Vec<ProjectProviderResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetProvidersResponse {
+    pub kind: ProjectProviderKind,
+    pub providers: Vec<ProjectProviderResponse>,
+    /// Embedding changes need a reindex so stored vectors match the new model
+    pub reindex_required: bool,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Get a project's provider overrides.
+///
+/// GET /projects/:project_id/providers
+async fn get_providers(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> Result<Json<ProjectProvidersResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+
+    let llm = db::list_project_providers(&state.db, &project.id, ProjectProviderKind::Llm).await?;
+    let embedding =
+        db::list_project_providers(&state.db, &project.id, ProjectProviderKind::Embedding).await?;
+
+    Ok(Json(ProjectProvidersResponse {
+        llm: llm.into_iter().map(Into::into).collect(),
+        embedding: embedding.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Replace a project's llm or embedding chain.
+///
+/// PUT /projects/:project_id/providers/:kind
+async fn set_providers(
+    State(state): State<AppState>,
+    Path((project_id, kind)): Path<(String, String)>,
+    actor: AuditActor,
+    Json(request): Json<SetProvidersRequest>,
+) -> Result<Json<SetProvidersResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let kind = parse_kind(&kind)?;
+
+    let allowed = match kind {
+        ProjectProviderKind::Llm => LLM_PROVIDERS,
+        ProjectProviderKind::Embedding => EMBEDDING_PROVIDERS,
+    };
+    for provider in &request.providers {
+        if !allowed.contains(&provider.name.as_str()) {
+            return Err(Error::Validation(format!(
+                "Unknown {} provider '{}'. Supported: {}",
+                kind.as_str(),
+                provider.name,
+                allowed.join(", ")
+            )));
+        }
+        if !provider.config.is_object() {
+            return Err(Error::Validation(format!(
+                "Config for provider '{}' must be an object",
+                provider.name
+            )));
+        }
+    }
+
+    let providers =
+        db::replace_project_providers(&state.db, &project.id, kind, request.providers).await?;
+    state.provider_chains.invalidate(&project.id).await;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectProvidersUpdate,
+            Some(&project.id),
+            Some(&project.id),
+            json!({
+                "kind": kind,
+                "providers": providers.iter().map(|p| &p.name).collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+
+    Ok(Json(SetProvidersResponse {
+        kind,
+        providers: providers.into_iter().map(Into::into).collect(),
+        reindex_required: kind == ProjectProviderKind::Embedding,
+    }))
+}
+
+/// Remove a project's llm or embedding override.
+///
+/// DELETE /projects/:project_id/providers/:kind
+async fn clear_providers(
+    State(state): State<AppState>,
+    Path((project_id, kind)): Path<(String, String)>,
+    actor: AuditActor,
+) -> Result<Json<SetProvidersResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let kind = parse_kind(&kind)?;
+
+    let removed = db::delete_project_providers(&state.db, &project.id, kind).await?;
+    state.provider_chains.invalidate(&project.id).await;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectProvidersUpdate,
+            Some(&project.id),
+            Some(&project.id),
+            json!({ "kind": kind, "providers": [] }),
+        )
+        .await;
+
+    Ok(Json(SetProvidersResponse {
+        kind,
+        providers: Vec::new(),
+        reindex_required: kind == ProjectProviderKind::Embedding && removed > 0,
+    }))
+}
+
+fn parse_kind(kind: &str) -> Result<ProjectProviderKind> {
+    match kind {
+        "llm" => Ok(ProjectProviderKind::Llm),
+        "embedding" => Ok(ProjectProviderKind::Embedding),
+        _ => Err(Error::Validation(format!(
+            "Unknown provider kind '{}'. Expected llm or embedding",
+            kind
+        ))),
+    }
+}
@@ -9,6 +9,8 @@
 //! - PUT /projects/:id - Update project
 //! - DELETE /projects/:id - Delete project
 
+use std::collections::HashMap;
+
 use axum::{
     extract::{Path, Query, State},
     routing::{get, post},
@@ -19,6 +21,9 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::db::{AuditAction, DecayModel, ScoringSettings, UpdateScoringSettings};
+use crate::models::MemoryType;
+use crate::services::{AuditActor, MAX_EXPANSION_COUNT};
 use crate::{AppState, Error, Result};
 
 /// Build project routes.
@@ -33,6 +38,9 @@ pub fn routes(state: AppState) -> Router<AppState> {
         .route("/:id/status", get(get_project_status))
         .route("/:id/reindex", post(reindex_project))
         .route("/:id/sync", post(sync_project))
+        .layer(axum::middleware::from_fn(
+            crate::middleware::require_projects_admin_scope,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state,
             crate::middleware::require_auth,
@@ -174,6 +182,8 @@ pub struct ProjectStatsResponse {
     pub total_links: u64,
     /// Total number of vectors in Qdrant.
     pub total_vectors: u64,
+    /// Memories moved to cold storage by retention policies.
+    pub archived_memories: u64,
 }
 
 /// Memory counts by type.
@@ -271,6 +281,8 @@ pub struct DatabaseStats {
     pub total_links: u64,
     /// Total attachments
     pub total_attachments: u64,
+    /// Memories in cold storage (not in the live vector collection)
+    pub archived_memories: u64,
     /// Database size in bytes (estimated)
     pub estimated_size_bytes: u64,
 }
@@ -339,6 +351,10 @@ pub struct JobTypeCounts {
     pub sync_metadata: u64,
     pub process_webhook: u64,
     pub generate_summary: u64,
+    pub consolidate_memories: u64,
+    pub apply_retention: u64,
+    pub evaluate_search: u64,
+    pub knowledge_gap_report: u64,
     pub custom: u64,
 }
 
@@ -529,6 +545,7 @@ async fn list_projects(
 async fn create_project(
     State(state): State<AppState>,
     axum::extract::Extension(auth): axum::extract::Extension<crate::middleware::AuthUser>,
+    actor: AuditActor,
     Json(request): Json<CreateProjectRequest>,
 ) -> Result<Json<ProjectResponse>> {
     // Validate slug format
@@ -571,6 +588,17 @@ async fn create_project(
 
     let project = crate::db::create_project(&state.db, input).await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectCreate,
+            Some(&project.id),
+            Some(&project.id),
+            serde_json::json!({ "slug": project.slug, "provider": project.provider }),
+        )
+        .await;
+
     // Add the creating user as a member with write access
     let _ = crate::db::add_project_member(
         &state.db,
@@ -665,11 +693,25 @@ async fn get_project(
 async fn update_project(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
     Json(request): Json<UpdateProjectRequest>,
 ) -> Result<Json<ProjectResponse>> {
     // First fetch the project to get its actual ID (in case user passed a slug)
     let existing = crate::db::get_project_by_id_or_slug(&state.db, &id).await?;
 
+    // Record which fields changed
+    let changed: Vec<&str> = [
+        ("name", request.name.is_some()),
+        ("description", request.description.is_some()),
+        (
+            "ignored_commit_authors",
+            request.ignored_commit_authors.is_some(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, set)| set.then_some(field))
+    .collect();
+
     // Serialize ignored_commit_authors if provided
     let ignored_authors_json = request
         .ignored_commit_authors
@@ -685,6 +727,17 @@ async fn update_project(
 
     let project = crate::db::update_project(&state.db, &existing.id, input).await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectUpdate,
+            Some(&project.id),
+            Some(&project.id),
+            serde_json::json!({ "fields": changed }),
+        )
+        .await;
+
     let ignored_authors = project
         .ignored_commit_authors
         .as_ref()
@@ -728,6 +781,7 @@ async fn update_project(
 async fn delete_project(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
 ) -> Result<Json<serde_json::Value>> {
     // First fetch the project to get its actual ID (in case user passed a slug)
     let existing = crate::db::get_project_by_id_or_slug(&state.db, &id).await?;
@@ -750,6 +804,17 @@ async fn delete_project(
     // This deletes: memories, memory_links, chunks, project_members, etc.
     crate::db::delete_project(&state.db, &existing.id).await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectDelete,
+            Some(&existing.id),
+            Some(&existing.id),
+            serde_json::json!({ "slug": existing.slug, "name": existing.name }),
+        )
+        .await;
+
     // 4. Delete Qdrant collection (vector database cleanup)
     match state.qdrant.delete_collection(&existing.slug).await {
         Ok(()) => info!(slug = %existing.slug, "Deleted Qdrant collection"),
@@ -823,6 +888,9 @@ async fn get_project_stats(
         .map(|info| info.points_count)
         .unwrap_or(0);
 
+    let archived_memories =
+        crate::db::count_archived_memories(&state.db, &project.id).await.unwrap_or(0) as u64;
+
     Ok(Json(ProjectStatsResponse {
         project_id: project.id.parse().unwrap_or_default(),
         project_slug: project.slug,
@@ -832,6 +900,7 @@ async fn get_project_stats(
         total_chunks,
         total_links,
         total_vectors,
+        archived_memories,
     }))
 }
 
@@ -899,6 +968,8 @@ async fn get_project_status(
         crate::db::count_project_links(&state.db, &project.id).await.unwrap_or(0) as u64;
     let total_attachments =
         crate::db::count_project_attachments(&state.db, &project.id).await.unwrap_or(0) as u64;
+    let archived_memories =
+        crate::db::count_archived_memories(&state.db, &project.id).await.unwrap_or(0) as u64;
 
     // Estimate database size (rough calculation based on row counts)
     let estimated_db_size = (total_memories * 2048) + (total_chunks * 512) + (total_links * 128);
@@ -910,6 +981,7 @@ async fn get_project_status(
         total_chunks,
         total_links,
         total_attachments,
+        archived_memories,
         estimated_size_bytes: estimated_db_size,
     };
 
@@ -923,8 +995,9 @@ async fn get_project_status(
     };
 
     // Vectors = memory embeddings + chunk embeddings
-    // So expected_vectors = memories + chunks
-    let expected_vectors = total_memories + total_chunks;
+    // So expected_vectors = memories + chunks (archived memories live in the cold collection)
+    let live_memories = total_memories.saturating_sub(archived_memories);
+    let expected_vectors = live_memories + total_chunks;
     let vector_sync_status = VectorSyncStatus {
         expected_count: expected_vectors,
         vector_count: total_vectors,
@@ -936,7 +1009,7 @@ async fn get_project_status(
         collection_name: format!("fold_{}", project.slug),
         exists: collection_exists,
         total_vectors,
-        memory_vectors: total_memories,
+        memory_vectors: live_memories,
         chunk_vectors: total_chunks,
         dimension,
         sync_status: vector_sync_status,
@@ -955,6 +1028,10 @@ async fn get_project_status(
         sync_metadata: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::SyncMetadata).await.unwrap_or(0) as u64,
         process_webhook: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::ProcessWebhook).await.unwrap_or(0) as u64,
         generate_summary: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::GenerateSummary).await.unwrap_or(0) as u64,
+        consolidate_memories: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::ConsolidateMemories).await.unwrap_or(0) as u64,
+        apply_retention: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::ApplyRetention).await.unwrap_or(0) as u64,
+        evaluate_search: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::EvaluateSearch).await.unwrap_or(0) as u64,
+        knowledge_gap_report: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::KnowledgeGapReport).await.unwrap_or(0) as u64,
         custom: crate::db::count_project_jobs_by_type(&state.db, &project.id, JobType::Custom).await.unwrap_or(0) as u64,
     };
 
@@ -1235,7 +1312,7 @@ pub enum ProjectIdent {
 }
 
 /// Check if a string is a valid project slug.
-fn is_valid_slug(s: &str) -> bool {
+pub(super) fn is_valid_slug(s: &str) -> bool {
     !s.is_empty()
         && s.len() <= 64
         && s.chars()
@@ -1359,6 +1436,7 @@ async fn add_member(
     State(state): State<AppState>,
     Path(project_id): Path<String>,
     axum::Extension(auth): axum::Extension<crate::middleware::AuthContext>,
+    actor: AuditActor,
     Json(request): Json<AddMemberRequest>,
 ) -> Result<Json<MemberResponse>> {
     // Verify project exists
@@ -1385,6 +1463,17 @@ async fn add_member(
     )
     .await?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemberAdd,
+            Some(&project.id),
+            Some(&member.user_id),
+            serde_json::json!({ "role": member.role }),
+        )
+        .await;
+
     Ok(Json(MemberResponse {
         user_id: member.user_id,
         project_id: member.project_id,
@@ -1433,6 +1522,7 @@ async fn get_member(
 async fn update_member(
     State(state): State<AppState>,
     Path((project_id, user_id)): Path<(String, String)>,
+    actor: AuditActor,
     Json(request): Json<UpdateMemberRequest>,
 ) -> Result<Json<MemberResponse>> {
     // Verify project exists
@@ -1450,6 +1540,17 @@ async fn update_member(
             .await?
             .ok_or_else(|| Error::NotFound(format!("Member not found: {}", user_id)))?;
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemberUpdate,
+            Some(&project.id),
+            Some(&member.user_id),
+            serde_json::json!({ "role": member.role }),
+        )
+        .await;
+
     Ok(Json(MemberResponse {
         user_id: member.user_id,
         project_id: member.project_id,
@@ -1470,6 +1571,7 @@ async fn update_member(
 async fn remove_member(
     State(state): State<AppState>,
     Path((project_id, user_id)): Path<(String, String)>,
+    actor: AuditActor,
 ) -> Result<Json<serde_json::Value>> {
     // Verify project exists
     let project = crate::db::get_project_by_id_or_slug(&state.db, &project_id).await?;
@@ -1480,6 +1582,17 @@ async fn remove_member(
         return Err(Error::NotFound(format!("Member not found: {}", user_id)));
     }
 
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::MemberRemove,
+            Some(&project.id),
+            Some(&user_id),
+            serde_json::Value::Null,
+        )
+        .await;
+
     Ok(Json(serde_json::json!({
         "removed": true,
         "user_id": user_id,
@@ -1517,6 +1630,47 @@ pub struct AlgorithmConfigResponse {
     /// Author patterns to ignore during webhook processing
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ignored_commit_authors: Vec<String>,
+    /// Search LLM paraphrases of each query
+    pub query_expansion: bool,
+    /// Search a hypothetical answer passage for each query (HyDE)
+    pub hyde: bool,
+    /// Paraphrases generated per query when expansion is on
+    pub expansion_count: i64,
+    /// How strength decays: "exponential", "power_law" or "none"
+    pub decay_model: DecayModel,
+    /// Half-life overrides by memory type; null means the type never decays
+    pub type_half_life_days: HashMap<String, Option<f64>>,
+    /// ACT-R decay exponent for the power-law model
+    pub actr_decay: f64,
+    /// Weight of link rank added to the combined score (0.0 disables)
+    pub link_boost_weight: f64,
+}
+
+impl AlgorithmConfigResponse {
+    fn new(
+        project: &crate::db::Project,
+        search: &crate::db::SearchSettings,
+        scoring: &ScoringSettings,
+    ) -> Self {
+        let ignored_authors = project
+            .ignored_commit_authors
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+
+        Self {
+            strength_weight: project.decay_strength_weight.unwrap_or(0.3),
+            decay_half_life_days: project.decay_half_life_days.unwrap_or(30.0),
+            ignored_commit_authors: ignored_authors,
+            query_expansion: search.is_query_expansion(),
+            hyde: search.is_hyde(),
+            expansion_count: search.expansion_count,
+            decay_model: scoring.model(),
+            type_half_life_days: scoring.type_half_lives(),
+            actr_decay: scoring.actr_decay,
+            link_boost_weight: scoring.link_boost_weight,
+        }
+    }
 }
 
 /// Request to update algorithm configuration.
@@ -1528,6 +1682,20 @@ pub struct UpdateAlgorithmConfigRequest {
     pub decay_half_life_days: Option<f64>,
     /// Author patterns to ignore during webhook processing
     pub ignored_commit_authors: Option<Vec<String>>,
+    /// Search LLM paraphrases of each query
+    pub query_expansion: Option<bool>,
+    /// Search a hypothetical answer passage for each query (HyDE)
+    pub hyde: Option<bool>,
+    /// Paraphrases generated per query when expansion is on
+    pub expansion_count: Option<i64>,
+    /// How strength decays: "exponential", "power_law" or "none"
+    pub decay_model: Option<DecayModel>,
+    /// Half-life overrides by memory type, replacing any set before
+    pub type_half_life_days: Option<HashMap<String, Option<f64>>>,
+    /// ACT-R decay exponent for the power-law model (0.0-1.0, exclusive)
+    pub actr_decay: Option<f64>,
+    /// Weight of link rank added to the combined score (0.0-1.0)
+    pub link_boost_weight: Option<f64>,
 }
 
 // ============================================================================
@@ -1538,32 +1706,26 @@ pub struct UpdateAlgorithmConfigRequest {
 ///
 /// GET /projects/:project_id/config/algorithm
 ///
-/// Returns the decay algorithm parameters and ignored commit authors.
+/// Returns the decay algorithm parameters, scoring model, ignored commit
+/// authors and query rewriting settings.
 #[axum::debug_handler]
 async fn get_algorithm_config(
     State(state): State<AppState>,
     Path(project_id): Path<String>,
 ) -> Result<Json<AlgorithmConfigResponse>> {
     let project = crate::db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let search = crate::db::get_search_settings(&state.db, &project.id).await?;
+    let scoring = crate::db::get_scoring_settings(&state.db, &project.id).await?;
 
-    let ignored_authors = project
-        .ignored_commit_authors
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
-
-    Ok(Json(AlgorithmConfigResponse {
-        strength_weight: project.decay_strength_weight.unwrap_or(0.3),
-        decay_half_life_days: project.decay_half_life_days.unwrap_or(30.0),
-        ignored_commit_authors: ignored_authors,
-    }))
+    Ok(Json(AlgorithmConfigResponse::new(&project, &search, &scoring)))
 }
 
 /// Update algorithm configuration for a project.
 ///
 /// PUT /projects/:project_id/config/algorithm
 ///
-/// Updates the decay algorithm parameters and/or ignored commit authors.
+/// Updates the decay algorithm parameters, scoring model, ignored commit
+/// authors and/or query rewriting settings.
 #[axum::debug_handler]
 async fn update_algorithm_config(
     State(state): State<AppState>,
@@ -1590,6 +1752,52 @@ async fn update_algorithm_config(
         }
     }
 
+    // Validate expansion_count
+    if let Some(count) = request.expansion_count {
+        if !(1..=MAX_EXPANSION_COUNT).contains(&count) {
+            return Err(Error::Validation(format!(
+                "expansion_count must be between 1 and {}",
+                MAX_EXPANSION_COUNT
+            )));
+        }
+    }
+
+    // Validate per-type half-lives
+    if let Some(half_lives) = &request.type_half_life_days {
+        for (memory_type, half_life) in half_lives {
+            if MemoryType::from_str(memory_type).is_none() {
+                return Err(Error::Validation(format!(
+                    "Unknown memory type in type_half_life_days: {}",
+                    memory_type
+                )));
+            }
+            if half_life.is_some_and(|h| h < 1.0) {
+                return Err(Error::Validation(format!(
+                    "Half-life for {} must be at least 1.0 or null",
+                    memory_type
+                )));
+            }
+        }
+    }
+
+    // Validate actr_decay
+    if let Some(decay) = request.actr_decay {
+        if decay <= 0.0 || decay >= 1.0 {
+            return Err(Error::Validation(
+                "actr_decay must be between 0.0 and 1.0".into(),
+            ));
+        }
+    }
+
+    // Validate link_boost_weight
+    if let Some(weight) = request.link_boost_weight {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(Error::Validation(
+                "link_boost_weight must be between 0.0 and 1.0".into(),
+            ));
+        }
+    }
+
     // Build update
     let input = crate::db::UpdateAlgorithmConfig {
         decay_strength_weight: request.strength_weight,
@@ -1602,17 +1810,30 @@ async fn update_algorithm_config(
 
     let updated = crate::db::update_algorithm_config(&state.db, &project.id, input).await?;
 
-    let ignored_authors = updated
-        .ignored_commit_authors
-        .as_ref()
-        .and_then(|s| serde_json::from_str(s).ok())
-        .unwrap_or_default();
+    let search = crate::db::update_search_settings(
+        &state.db,
+        &project.id,
+        crate::db::UpdateSearchSettings {
+            query_expansion: request.query_expansion,
+            hyde: request.hyde,
+            expansion_count: request.expansion_count,
+        },
+    )
+    .await?;
 
-    Ok(Json(AlgorithmConfigResponse {
-        strength_weight: updated.decay_strength_weight.unwrap_or(0.3),
-        decay_half_life_days: updated.decay_half_life_days.unwrap_or(30.0),
-        ignored_commit_authors: ignored_authors,
-    }))
+    let scoring = crate::db::update_scoring_settings(
+        &state.db,
+        &project.id,
+        UpdateScoringSettings {
+            decay_model: request.decay_model,
+            type_half_lives: request.type_half_life_days,
+            actr_decay: request.actr_decay,
+            link_boost_weight: request.link_boost_weight,
+        },
+    )
+    .await?;
+
+    Ok(Json(AlgorithmConfigResponse::new(&updated, &search, &scoring)))
 }
 
 // ============================================================================
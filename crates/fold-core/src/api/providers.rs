@@ -42,10 +42,10 @@ use crate::db::{
     get_embedding_provider, get_embedding_provider_by_name, get_llm_provider,
     get_llm_provider_by_name, get_valid_provider_oauth_state, list_embedding_providers,
     list_llm_providers, seed_claudecode_provider_async, update_embedding_provider,
-    update_llm_provider, CreateEmbeddingProvider, CreateLlmProvider, CreateProviderOAuthState,
-    LlmProviderRow, UpdateEmbeddingProvider, UpdateLlmProvider,
+    update_llm_provider, AuditAction, CreateEmbeddingProvider, CreateLlmProvider,
+    CreateProviderOAuthState, LlmProviderRow, UpdateEmbeddingProvider, UpdateLlmProvider,
 };
-use crate::services::{ClaudeCodeInfo, ClaudeCodeService};
+use crate::services::{AuditActor, ClaudeCodeInfo, ClaudeCodeService};
 use crate::{AppState, Error, Result};
 
 // ============================================================================
@@ -144,6 +144,21 @@ pub struct UpdateLlmProviderRequest {
     pub config: Option<JsonValue>,
 }
 
+impl UpdateLlmProviderRequest {
+    /// Names of the fields being changed, for the audit log (never values).
+    fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("enabled", self.enabled.is_some()),
+            ("priority", self.priority.is_some()),
+            ("api_key", self.api_key.is_some()),
+            ("config", self.config.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect()
+    }
+}
+
 /// Request to create a new embedding provider.
 #[derive(Debug, Deserialize)]
 pub struct CreateEmbeddingProviderRequest {
@@ -175,6 +190,22 @@ pub struct UpdateEmbeddingProviderRequest {
     pub config: Option<JsonValue>,
 }
 
+impl UpdateEmbeddingProviderRequest {
+    /// Names of the fields being changed, for the audit log (never values).
+    fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("enabled", self.enabled.is_some()),
+            ("priority", self.priority.is_some()),
+            ("search_priority", self.search_priority.is_some()),
+            ("api_key", self.api_key.is_some()),
+            ("config", self.config.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect()
+    }
+}
+
 /// Request to import a Claude Code OAuth token.
 /// Users can paste their token from `claude code setup-token` or ~/.claude/.credentials.json
 #[derive(Debug, Deserialize)]
@@ -346,6 +377,7 @@ async fn list_llm(State(state): State<AppState>) -> Result<Json<Vec<LlmProviderR
 #[axum::debug_handler]
 async fn create_llm(
     State(state): State<AppState>,
+    actor: AuditActor,
     Json(req): Json<CreateLlmProviderRequest>,
 ) -> Result<Json<LlmProviderResponse>> {
     // Validate provider name
@@ -371,6 +403,16 @@ async fn create_llm(
     .await?;
 
     info!(provider_id = %provider.id, name = %provider.name, "Created LLM provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderCreate,
+            None,
+            Some(&provider.id),
+            json!({ "kind": "llm", "name": provider.name }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so next operation uses new provider
     if let Err(e) = state.llm.refresh_providers().await {
@@ -448,8 +490,10 @@ async fn get_llm(
 async fn update_llm(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
     Json(req): Json<UpdateLlmProviderRequest>,
 ) -> Result<Json<LlmProviderResponse>> {
+    let changed = req.changed_fields();
     let provider = update_llm_provider(
         &state.db,
         &id,
@@ -464,6 +508,16 @@ async fn update_llm(
     .await?;
 
     info!(provider_id = %provider.id, "Updated LLM provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderUpdate,
+            None,
+            Some(&provider.id),
+            json!({ "kind": "llm", "fields": changed }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so next operation uses updated config
     if let Err(e) = state.llm.refresh_providers().await {
@@ -503,9 +557,20 @@ async fn update_llm(
 async fn delete_llm(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
 ) -> Result<Json<JsonValue>> {
     delete_llm_provider(&state.db, &id).await?;
     info!(provider_id = %id, "Deleted LLM provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderDelete,
+            None,
+            Some(&id),
+            json!({ "kind": "llm" }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so deleted provider is no longer used
     if let Err(e) = state.llm.refresh_providers().await {
@@ -919,6 +984,7 @@ async fn list_embedding(
 #[axum::debug_handler]
 async fn create_embedding(
     State(state): State<AppState>,
+    actor: AuditActor,
     Json(req): Json<CreateEmbeddingProviderRequest>,
 ) -> Result<Json<EmbeddingProviderResponse>> {
     // Validate provider name
@@ -945,6 +1011,16 @@ async fn create_embedding(
     .await?;
 
     info!(provider_id = %provider.id, name = %provider.name, "Created embedding provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderCreate,
+            None,
+            Some(&provider.id),
+            json!({ "kind": "embedding", "name": provider.name }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so next indexing uses new provider
     if let Err(e) = state.embeddings.refresh_providers().await {
@@ -1026,8 +1102,10 @@ async fn get_embedding(
 async fn update_embedding(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
     Json(req): Json<UpdateEmbeddingProviderRequest>,
 ) -> Result<Json<EmbeddingProviderResponse>> {
+    let changed = req.changed_fields();
     let provider = update_embedding_provider(
         &state.db,
         &id,
@@ -1043,6 +1121,16 @@ async fn update_embedding(
     .await?;
 
     info!(provider_id = %provider.id, "Updated embedding provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderUpdate,
+            None,
+            Some(&provider.id),
+            json!({ "kind": "embedding", "fields": changed }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so next indexing uses updated config
     if let Err(e) = state.embeddings.refresh_providers().await {
@@ -1084,9 +1172,20 @@ async fn update_embedding(
 async fn delete_embedding(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    actor: AuditActor,
 ) -> Result<Json<JsonValue>> {
     delete_embedding_provider(&state.db, &id).await?;
     info!(provider_id = %id, "Deleted embedding provider");
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProviderDelete,
+            None,
+            Some(&id),
+            json!({ "kind": "embedding" }),
+        )
+        .await;
 
     // Refresh in-memory provider cache so deleted provider is no longer used
     if let Err(e) = state.embeddings.refresh_providers().await {
@@ -0,0 +1,377 @@
+//! Retention and Archive Routes
+//!
+//! Manage per-project retention policies and the memories they move to
+//! cold storage.
+//!
+//! Routes:
+//! - GET /projects/:project_id/retention/policies - List retention policies
+//! - POST /projects/:project_id/retention/policies - Create a retention policy
+//! - DELETE /projects/:project_id/retention/policies/:policy_id - Delete a policy
+//! - POST /projects/:project_id/retention/run - Queue a retention job
+//! - GET /projects/:project_id/archive - List archived memories
+//! - POST /projects/:project_id/archive/:memory_id/restore - Restore an archived memory
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ArchivedMemory, JobType, RetentionPolicy};
+use crate::middleware::{require_project_read, require_project_write};
+use crate::models::{MemorySource, MemoryType};
+use crate::{AppState, Error, Result};
+
+/// Build retention routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/retention/policies", get(list_policies))
+        .route("/:project_id/archive", get(list_archived))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route("/:project_id/retention/policies", post(create_policy))
+        .route(
+            "/:project_id/retention/policies/:policy_id",
+            delete(delete_policy),
+        )
+        .route("/:project_id/retention/run", post(run_retention))
+        .route(
+            "/:project_id/archive/:memory_id/restore",
+            post(restore_memory),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Retention policy.
+#[derive(Debug, Serialize)]
+pub struct PolicyResponse {
+    pub id: String,
+    pub name: String,
+    /// Memory type the policy applies to (null = any)
+    pub memory_type: Option<String>,
+    /// Memory source the policy applies to (null = any)
+    pub source: Option<String>,
+    /// Archive when decay strength is below this value
+    pub max_strength: Option<f64>,
+    /// Archive only memories at least this many days old
+    pub min_age_days: i64,
+    /// Protect matching memories from every other policy
+    pub never_archive: bool,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+impl From<RetentionPolicy> for PolicyResponse {
+    fn from(p: RetentionPolicy) -> Self {
+        Self {
+            never_archive: p.is_never_archive(),
+            enabled: p.is_enabled(),
+            id: p.id,
+            name: p.name,
+            memory_type: p.memory_type,
+            source: p.source,
+            max_strength: p.max_strength,
+            min_age_days: p.min_age_days,
+            created_at: p.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPoliciesResponse {
+    pub policies: Vec<PolicyResponse>,
+}
+
+/// Create policy request.
+#[derive(Debug, Deserialize)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub memory_type: Option<String>,
+    pub source: Option<String>,
+    pub max_strength: Option<f64>,
+    #[serde(default)]
+    pub min_age_days: i64,
+    #[serde(default)]
+    pub never_archive: bool,
+}
+
+/// Run request.
+#[derive(Debug, Deserialize, Default)]
+pub struct RunRequest {
+    /// Report what would be archived without moving anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunResponse {
+    pub job_id: String,
+    pub dry_run: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListArchivedQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Archived memory.
+#[derive(Debug, Serialize)]
+pub struct ArchivedMemoryResponse {
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub memory_type: Option<String>,
+    pub policy_id: Option<String>,
+    /// Decay strength when archived
+    pub strength: Option<f64>,
+    pub reason: Option<String>,
+    pub archived_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListArchivedResponse {
+    pub memories: Vec<ArchivedMemoryResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreResponse {
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub restored: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PolicyPath {
+    pub project_id: String,
+    pub policy_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemoryPath {
+    pub project_id: String,
+    pub memory_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List retention policies.
+///
+/// GET /projects/:project_id/retention/policies
+async fn list_policies(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+) -> Result<Json<ListPoliciesResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let policies = db::list_retention_policies(&state.db, &project.id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ListPoliciesResponse { policies }))
+}
+
+/// Create a retention policy.
+///
+/// POST /projects/:project_id/retention/policies
+async fn create_policy(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Json(request): Json<CreatePolicyRequest>,
+) -> Result<(StatusCode, Json<PolicyResponse>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    if request.name.trim().is_empty() {
+        return Err(Error::Validation("Policy name cannot be empty".into()));
+    }
+    if let Some(ref t) = request.memory_type {
+        if MemoryType::from_str(t).is_none() {
+            return Err(Error::Validation(format!("Invalid memory type: {}", t)));
+        }
+    }
+    if let Some(ref s) = request.source {
+        if MemorySource::from_str(s).is_none() {
+            return Err(Error::Validation(format!("Invalid memory source: {}", s)));
+        }
+    }
+    if let Some(max) = request.max_strength {
+        if !(0.0..=1.0).contains(&max) {
+            return Err(Error::Validation(
+                "max_strength must be between 0.0 and 1.0".into(),
+            ));
+        }
+    }
+    if request.min_age_days < 0 {
+        return Err(Error::Validation("min_age_days cannot be negative".into()));
+    }
+    // An archive policy with no thresholds would archive everything it matches
+    if !request.never_archive && request.max_strength.is_none() && request.min_age_days == 0 {
+        return Err(Error::Validation(
+            "Archive policies need max_strength or min_age_days".into(),
+        ));
+    }
+
+    let policy = db::create_retention_policy(
+        &state.db,
+        db::CreateRetentionPolicy {
+            id: crate::models::new_id(),
+            project_id: project.id,
+            name: request.name.trim().to_string(),
+            memory_type: request.memory_type,
+            source: request.source,
+            max_strength: request.max_strength,
+            min_age_days: request.min_age_days,
+            never_archive: request.never_archive,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(policy.into())))
+}
+
+/// Delete a retention policy. Archived memories stay archived.
+///
+/// DELETE /projects/:project_id/retention/policies/:policy_id
+async fn delete_policy(
+    State(state): State<AppState>,
+    Path(path): Path<PolicyPath>,
+) -> Result<StatusCode> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let policy = db::get_retention_policy(&state.db, &path.policy_id).await?;
+    if policy.project_id != project.id {
+        return Err(Error::NotFound(format!(
+            "Retention policy not found: {}",
+            path.policy_id
+        )));
+    }
+
+    db::delete_retention_policy(&state.db, &policy.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Queue a retention job. The job's metadata holds the report.
+///
+/// POST /projects/:project_id/retention/run
+async fn run_retention(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    request: Option<Json<RunRequest>>,
+) -> Result<(StatusCode, Json<RunResponse>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let job_id = crate::models::new_id();
+    db::create_job(
+        &state.db,
+        db::CreateJob::new(job_id.clone(), JobType::ApplyRetention)
+            .with_project(project.id.clone())
+            .with_payload(serde_json::json!({ "dry_run": request.dry_run })),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RunResponse {
+            job_id,
+            dry_run: request.dry_run,
+            message: if request.dry_run {
+                "Dry run queued; results will be in the job metadata".to_string()
+            } else {
+                "Retention run queued".to_string()
+            },
+        }),
+    ))
+}
+
+/// List archived memories.
+///
+/// GET /projects/:project_id/archive
+async fn list_archived(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListArchivedQuery>,
+) -> Result<Json<ListArchivedResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let limit = query.limit.clamp(1, 200);
+    let offset = query.offset.max(0);
+
+    let records = db::list_archived_memories(&state.db, &project.id, limit, offset).await?;
+    let total = db::count_archived_memories(&state.db, &project.id).await?;
+
+    let mut memories = Vec::with_capacity(records.len());
+    for record in records {
+        memories.push(archived_response(&state, record).await);
+    }
+
+    Ok(Json(ListArchivedResponse {
+        memories,
+        total,
+        limit,
+        offset,
+    }))
+}
+
+/// Restore an archived memory to the live index.
+///
+/// POST /projects/:project_id/archive/:memory_id/restore
+async fn restore_memory(
+    State(state): State<AppState>,
+    Path(path): Path<MemoryPath>,
+) -> Result<Json<RestoreResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let memory = state
+        .memory
+        .restore(&project.id, &project.slug, &path.memory_id)
+        .await?;
+
+    Ok(Json(RestoreResponse {
+        memory_id: memory.id,
+        title: memory.title,
+        restored: true,
+    }))
+}
+
+/// Attach memory title and type to an archive record.
+async fn archived_response(state: &AppState, record: ArchivedMemory) -> ArchivedMemoryResponse {
+    let memory = db::get_memory(&state.db, &record.memory_id).await.ok();
+
+    ArchivedMemoryResponse {
+        title: memory.as_ref().and_then(|m| m.title.clone()),
+        memory_type: memory.map(|m| m.memory_type),
+        memory_id: record.memory_id,
+        policy_id: record.policy_id,
+        strength: record.strength,
+        reason: record.reason,
+        archived_at: record.archived_at,
+    }
+}
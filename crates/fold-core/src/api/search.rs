@@ -4,20 +4,35 @@
 //!
 //! Routes:
 //! - POST /projects/:project_id/search - Unified semantic search
+//!   (`expand` / `hyde` switch LLM query rewriting on or off,
+//!   `explain` reports why each result ranked where it did)
 //! - POST /projects/:project_id/context - Get context for a task
+//!
+//! Both project routes take `as_of` (a timestamp, date or indexed commit SHA)
+//! to answer from memories as they were at that point.
+//! - POST /memories/search - Federated search across readable projects
+//!
+//! All routes are rate limited per token.
+
+use std::collections::HashSet;
 
 use axum::{
     extract::{Path, State},
     middleware,
     routing::post,
-    Json, Router,
+    Extension, Json, Router,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::middleware::require_project_read;
-use crate::models::{ChunkMatch, MemorySource};
+use crate::middleware::{rate_limit_search, require_project_read, AuthContext};
+use crate::models::{ChunkMatch, MemorySource, MemoryType, ScoreBreakdown};
+use crate::services::context_packer::PackedContext;
+use crate::services::federated_search::FederatedSearchResults;
+use crate::services::point_in_time::{self, AsOf};
+use crate::services::search_explain::{self, ResultExplanation, SearchExplanation};
+use crate::services::{FederatedSearchOptions, QueryRewrite};
 use crate::{db, AppState, Error, Result};
 
 /// Build search routes.
@@ -25,9 +40,22 @@ pub fn routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/:project_id/search", post(search))
         .route("/:project_id/context", post(get_context))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_search,
+        ))
         .layer(middleware::from_fn_with_state(state, require_project_read))
 }
 
+/// Build cross-project search routes.
+///
+/// These are mounted under /memories
+pub fn global_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/search", post(federated_search))
+        .layer(middleware::from_fn_with_state(state, rate_limit_search))
+}
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -71,6 +99,23 @@ pub struct SearchRequest {
     /// Include matched chunks (function/class/heading level) in results
     #[serde(default)]
     pub include_chunks: bool,
+
+    /// Also search memories archived by retention policies
+    #[serde(default)]
+    pub include_archived: bool,
+
+    /// Search LLM paraphrases of the query (overrides the project setting)
+    pub expand: Option<bool>,
+
+    /// Search a hypothetical answer passage (overrides the project setting)
+    pub hyde: Option<bool>,
+
+    /// Explain each result's score and the effect of each filter
+    #[serde(default)]
+    pub explain: bool,
+
+    /// Search memories as they were at this timestamp, date or commit SHA
+    pub as_of: Option<String>,
 }
 
 fn default_limit() -> u32 {
@@ -98,6 +143,17 @@ pub struct ContextRequest {
     /// Whether to include similar memories
     #[serde(default = "default_true")]
     pub include_similar: bool,
+
+    /// Token budget. When set, the response carries a packed markdown
+    /// bundle instead of the item list.
+    pub max_tokens: Option<usize>,
+
+    /// Memory types to pack (default: codebase, spec, decision, session)
+    pub types: Option<Vec<MemoryType>>,
+
+    /// Build context from memories as they were at this timestamp, date or
+    /// commit SHA
+    pub as_of: Option<String>,
 }
 
 fn default_context_limit() -> u32 {
@@ -108,6 +164,47 @@ fn default_true() -> bool {
     true
 }
 
+/// Largest token budget for a packed context bundle.
+const MAX_CONTEXT_TOKENS: usize = 32_000;
+
+/// Federated search request.
+#[derive(Debug, Deserialize)]
+pub struct FederatedSearchRequest {
+    /// Query text for semantic search
+    pub query: String,
+
+    /// Maximum results overall
+    #[serde(default = "default_federated_limit")]
+    pub limit: usize,
+
+    /// Maximum results from any one project
+    #[serde(default = "default_per_project_limit")]
+    pub per_project_limit: usize,
+
+    /// Minimum similarity score (0.0 - 1.0)
+    #[serde(default = "default_min_score")]
+    pub min_score: f32,
+
+    /// Only search these projects (IDs or slugs)
+    #[serde(default)]
+    pub projects: Vec<String>,
+
+    /// Skip these projects (IDs or slugs)
+    #[serde(default)]
+    pub exclude_projects: Vec<String>,
+}
+
+fn default_federated_limit() -> usize {
+    20
+}
+
+fn default_per_project_limit() -> usize {
+    10
+}
+
+/// Largest result count for a federated search.
+const MAX_FEDERATED_LIMIT: usize = 100;
+
 /// Search result item.
 #[derive(Debug, Serialize)]
 pub struct SearchResultItem {
@@ -119,13 +216,22 @@ pub struct SearchResultItem {
     pub score: f32,
     /// Retrieval strength from decay algorithm (0.0-1.0)
     pub strength: f32,
-    /// Combined score: (1-weight)*score + weight*strength
+    /// Combined score: (1-weight)*score + weight*strength, plus any link boost
     pub combined_score: f32,
+    /// How strength and the combined score were computed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoreBreakdown>,
     pub metadata: SearchResultMetadata,
     pub created_at: DateTime<Utc>,
     /// Matched chunks within this memory (when include_chunks=true)
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub matched_chunks: Vec<ChunkMatch>,
+    /// Whether the memory comes from the archive (when include_archived=true)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Why the result ranked here (when explain=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ResultExplanation>,
 }
 
 #[derive(Debug, Serialize)]
@@ -153,6 +259,12 @@ pub struct SearchResponse {
     pub results: Vec<SearchResultItem>,
     pub total: u32,
     pub took_ms: u64,
+    /// Candidate and filter counts (when explain=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<SearchExplanation>,
+    /// Point in time searched (when as_of is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<AsOf>,
 }
 
 /// Context item.
@@ -183,6 +295,12 @@ pub struct ContextResponse {
     pub context: Vec<ContextItem>,
     pub summary: Option<String>,
     pub suggestions: Vec<String>,
+    /// Packed bundle (when max_tokens is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<PackedContext>,
+    /// Point in time the context was built for (when as_of is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<AsOf>,
 }
 
 // ============================================================================
@@ -206,6 +324,7 @@ pub struct ProjectPath {
 #[axum::debug_handler]
 async fn search(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
     Path(path): Path<ProjectPath>,
     Json(request): Json<SearchRequest>,
 ) -> Result<Json<SearchResponse>> {
@@ -219,8 +338,59 @@ async fn search(
         return Err(Error::Validation("Query cannot be empty".into()));
     }
 
+    let as_of = match &request.as_of {
+        Some(value) => Some(point_in_time::resolve_as_of(&state.db, &project.id, value).await?),
+        None => None,
+    };
+    if as_of.is_some() && request.include_chunks {
+        return Err(Error::Validation(
+            "as_of cannot be combined with include_chunks".into(),
+        ));
+    }
+
+    // Query rewriting: project setting, overridden per request. Point-in-time
+    // search is not rewritten.
+    let settings = db::get_search_settings(&state.db, &project.id).await?;
+    let mut rewrite =
+        QueryRewrite::from_settings(&settings).with_overrides(request.expand, request.hyde);
+    if as_of.is_some() {
+        rewrite = rewrite.with_overrides(Some(false), Some(false));
+    }
+    if rewrite.is_enabled() {
+        // Rewriting calls the LLM, which the search route class does not cover
+        state
+            .rate_limits
+            .check_quota(&project.id, db::UsageKind::Llm)
+            .await?;
+    }
+
     // Use MemoryService for search - with or without chunks
-    let search_results = if request.include_chunks {
+    let mut search_results = if let Some(as_of) = &as_of {
+        state
+            .memory
+            .search_as_of(
+                &project.id,
+                &project.slug,
+                &request.query,
+                None,
+                request.limit as usize * 2,
+                as_of.at,
+            )
+            .await?
+    } else if rewrite.is_enabled() {
+        state
+            .memory
+            .search_rewritten(
+                &project.id,
+                &project.slug,
+                &request.query,
+                None,
+                request.limit as usize * 2,
+                request.include_chunks,
+                rewrite,
+            )
+            .await?
+    } else if request.include_chunks {
         state
             .memory
             .search_with_chunks(
@@ -243,39 +413,70 @@ async fn search(
             .await?
     };
 
-    // If no results, return empty
-    if search_results.is_empty() {
-        let took_ms = start.elapsed().as_millis() as u64;
-        return Ok(Json(SearchResponse {
-            query: request.query,
-            total: 0,
-            results: vec![],
-            took_ms,
-        }));
+    // Archived memories are only searched on request
+    let mut archived_ids = HashSet::new();
+    if request.include_archived {
+        let archived = state
+            .memory
+            .search_archived(
+                &project.id,
+                &project.slug,
+                &request.query,
+                None,
+                request.limit as usize * 2,
+            )
+            .await?;
+        // Memories archived since are kept if they existed at as_of
+        let archived: Vec<_> = archived
+            .into_iter()
+            .filter(|r| as_of.as_ref().is_none_or(|a| r.memory.created_at <= a.at))
+            .collect();
+        archived_ids.extend(archived.iter().map(|r| r.memory.id.clone()));
+        search_results.extend(archived);
+    }
+    let top_score = search_results.iter().map(|r| r.score).reduce(f32::max);
+
+    let mut explain = request
+        .explain
+        .then(|| SearchExplanation::new(search_results.len(), "relevance", &rewrite));
+    if let Some(explain) = explain.as_mut() {
+        if request.source.is_some() {
+            explain.applied("source");
+        }
+        explain.applied("min_score");
     }
 
-    // Build results with filtering
-    let mut results: Vec<SearchResultItem> = search_results
+    // Build results with filtering, keeping each memory ID for the search log
+    let mut results: Vec<(String, SearchResultItem)> = search_results
         .into_iter()
         .filter_map(|result| {
+            let explanation = request
+                .explain
+                .then(|| ResultExplanation::from_result(&result));
             let memory = result.memory;
 
             // Apply source filter
             if let Some(source_filter) = &request.source {
                 let memory_source = memory.source.as_deref().and_then(MemorySource::from_str);
                 if memory_source != Some(*source_filter) {
+                    if let Some(explain) = explain.as_mut() {
+                        explain.removed("source");
+                    }
                     return None;
                 }
             }
 
             // Apply score filter
             if result.score < request.min_score {
+                if let Some(explain) = explain.as_mut() {
+                    explain.removed("min_score");
+                }
                 return None;
             }
 
             let source = memory.source.as_deref().and_then(MemorySource::from_str);
 
-            Some(SearchResultItem {
+            let item = SearchResultItem {
                 id: Uuid::parse_str(&memory.id).unwrap_or_else(|_| Uuid::new_v4()),
                 title: memory.title.clone(),
                 content: memory.content.clone().unwrap_or_default(),
@@ -283,6 +484,7 @@ async fn search(
                 score: result.score,
                 strength: result.strength,
                 combined_score: result.combined_score,
+                scoring: result.breakdown,
                 metadata: SearchResultMetadata {
                     source,
                     file_path: memory.file_path.clone(),
@@ -292,20 +494,50 @@ async fn search(
                 },
                 created_at: memory.created_at,
                 matched_chunks: result.matched_chunks,
-            })
+                archived: archived_ids.contains(&memory.id),
+                explanation,
+            };
+            Some((memory.id, item))
         })
         .collect();
 
     // Sort by score descending
-    results.sort_by(|a, b| {
+    results.sort_by(|(_, a), (_, b)| {
         b.score
             .partial_cmp(&a.score)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
     // Apply limit
+    if let Some(explain) = explain.as_mut() {
+        explain.truncated = results.len().saturating_sub(request.limit as usize);
+    }
     results.truncate(request.limit as usize);
 
+    for (rank, (memory_id, item)) in results.iter_mut().enumerate() {
+        if let Some(explanation) = item.explanation.as_mut() {
+            search_explain::explain_links(&state.db, explanation, memory_id, rank + 1).await?;
+        }
+    }
+    let (result_ids, results): (Vec<String>, Vec<SearchResultItem>) = results.into_iter().unzip();
+
+    state.search_analytics.log(
+        db::LogSearchQuery::new(&project.id, "api", &request.query)
+            .with_token(&auth.token_id)
+            .with_filters(serde_json::json!({
+                "source": request.source,
+                "limit": request.limit,
+                "min_score": request.min_score,
+                "include_chunks": request.include_chunks,
+                "include_archived": request.include_archived,
+                "expand": rewrite.expand,
+                "hyde": rewrite.hyde,
+                "as_of": as_of.as_ref().map(|a| a.at),
+            }))
+            .with_results(result_ids, top_score)
+            .with_latency(start.elapsed()),
+    );
+
     let took_ms = start.elapsed().as_millis() as u64;
 
     Ok(Json(SearchResponse {
@@ -313,6 +545,8 @@ async fn search(
         total: results.len() as u32,
         results,
         took_ms,
+        explain,
+        as_of,
     }))
 }
 
@@ -320,7 +554,8 @@ async fn search(
 ///
 /// POST /projects/:project_id/context
 ///
-/// Returns curated context items relevant to the given task.
+/// Returns curated context items relevant to the given task, or with
+/// `max_tokens` a markdown bundle packed to that budget.
 #[axum::debug_handler]
 async fn get_context(
     State(state): State<AppState>,
@@ -335,16 +570,68 @@ async fn get_context(
         return Err(Error::Validation("Task cannot be empty".into()));
     }
 
+    let as_of = match &request.as_of {
+        Some(value) => Some(point_in_time::resolve_as_of(&state.db, &project.id, value).await?),
+        None => None,
+    };
+
+    if let Some(max_tokens) = request.max_tokens {
+        if max_tokens == 0 || max_tokens > MAX_CONTEXT_TOKENS {
+            return Err(Error::Validation(format!(
+                "max_tokens must be between 1 and {}",
+                MAX_CONTEXT_TOKENS
+            )));
+        }
+
+        let bundle = state
+            .memory
+            .get_context_for_task(
+                &project.id,
+                &project.slug,
+                &request.task,
+                request.types,
+                max_tokens,
+                as_of.as_ref().map(|a| a.at),
+            )
+            .await?;
+
+        return Ok(Json(ContextResponse {
+            task: request.task,
+            context: Vec::new(),
+            summary: None,
+            suggestions: Vec::new(),
+            bundle: Some(bundle),
+            as_of,
+        }));
+    }
+
     // Search for relevant memories
-    let search_results = state
-        .memory
-        .search(
-            &project.id,
-            &project.slug,
-            &request.task,
-            request.limit as usize * 2,
-        )
-        .await?;
+    let search_results = match &as_of {
+        Some(as_of) => {
+            state
+                .memory
+                .search_as_of(
+                    &project.id,
+                    &project.slug,
+                    &request.task,
+                    None,
+                    request.limit as usize * 2,
+                    as_of.at,
+                )
+                .await?
+        }
+        None => {
+            state
+                .memory
+                .search(
+                    &project.id,
+                    &project.slug,
+                    &request.task,
+                    request.limit as usize * 2,
+                )
+                .await?
+        }
+    };
 
     // Build context items with source filtering
     let mut context: Vec<ContextItem> = search_results
@@ -410,9 +697,56 @@ async fn get_context(
         context,
         summary,
         suggestions,
+        bundle: None,
+        as_of,
     }))
 }
 
+/// Search every project the caller can read.
+///
+/// POST /memories/search
+///
+/// Scores are normalised per project before merging, and every hit names
+/// its project.
+#[axum::debug_handler]
+async fn federated_search(
+    State(state): State<AppState>,
+    axum::Extension(auth): axum::Extension<AuthContext>,
+    Json(request): Json<FederatedSearchRequest>,
+) -> Result<Json<FederatedSearchResults>> {
+    if request.query.trim().is_empty() {
+        return Err(Error::Validation("Query cannot be empty".into()));
+    }
+    if request.limit == 0 || request.limit > MAX_FEDERATED_LIMIT {
+        return Err(Error::Validation(format!(
+            "limit must be between 1 and {}",
+            MAX_FEDERATED_LIMIT
+        )));
+    }
+    if request.per_project_limit == 0 || request.per_project_limit > MAX_FEDERATED_LIMIT {
+        return Err(Error::Validation(format!(
+            "per_project_limit must be between 1 and {}",
+            MAX_FEDERATED_LIMIT
+        )));
+    }
+
+    let role = if auth.is_admin { "admin" } else { "member" };
+    let options = FederatedSearchOptions {
+        limit: request.limit,
+        per_project_limit: request.per_project_limit,
+        min_score: request.min_score,
+        include: request.projects,
+        exclude: request.exclude_projects,
+    };
+
+    let results = state
+        .federated_search
+        .search(&auth.user_id, role, &request.query, &options)
+        .await?;
+
+    Ok(Json(results))
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -0,0 +1,215 @@
+//! Search Analytics Routes
+//!
+//! What agents search for and how well it is served. Searches are logged by
+//! the search endpoints; these routes summarise the log and manage knowledge
+//! gap reports built from poorly served queries.
+//!
+//! Routes:
+//! - GET /projects/:project_id/analytics/search - Query log summary
+//! - GET /projects/:project_id/analytics/gaps - List knowledge gap reports
+//! - POST /projects/:project_id/analytics/gaps - Queue a knowledge gap report
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, JobType, KnowledgeGapReportRow};
+use crate::middleware::{require_project_read, require_project_write};
+use crate::services::search_analytics::{
+    DEFAULT_GAP_WINDOW_DAYS, DEFAULT_WINDOW_DAYS, LOW_SCORE_THRESHOLD, MAX_WINDOW_DAYS,
+};
+use crate::services::SearchAnalytics;
+use crate::{AppState, Error, Result};
+
+/// Build search analytics routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/analytics/search", get(search_analytics))
+        .route("/:project_id/analytics/gaps", get(list_gap_reports))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route("/:project_id/analytics/gaps", post(queue_gap_report))
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsQuery {
+    #[serde(default = "default_window_days")]
+    pub days: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// Best score below which a search counts as poorly served
+    #[serde(default = "default_low_score")]
+    pub low_score: f64,
+}
+
+fn default_window_days() -> i64 {
+    DEFAULT_WINDOW_DAYS
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+fn default_low_score() -> f64 {
+    LOW_SCORE_THRESHOLD
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListReportsQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Queue knowledge gap report request.
+#[derive(Debug, Deserialize, Default)]
+pub struct GapReportRequest {
+    /// Days of searches to consider (default 7)
+    pub days: Option<i64>,
+    /// Group queries into topics with the LLM (default true)
+    pub cluster: Option<bool>,
+}
+
+/// Stored knowledge gap report.
+#[derive(Debug, Serialize)]
+pub struct GapReportResponse {
+    pub id: String,
+    pub job_id: Option<String>,
+    pub window_days: i64,
+    pub clustered: bool,
+    pub report: serde_json::Value,
+    pub created_at: String,
+}
+
+impl From<KnowledgeGapReportRow> for GapReportResponse {
+    fn from(r: KnowledgeGapReportRow) -> Self {
+        Self {
+            report: serde_json::from_str(&r.report).unwrap_or_default(),
+            id: r.id,
+            job_id: r.job_id,
+            window_days: r.window_days,
+            clustered: r.clustered != 0,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListReportsResponse {
+    pub reports: Vec<GapReportResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueReportResponse {
+    pub job_id: String,
+    pub days: i64,
+    pub cluster: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Summarise a project's searches: volume, frequent and poorly served
+/// queries, and the memories returned most often.
+///
+/// GET /projects/:project_id/analytics/search
+async fn search_analytics(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<SearchAnalytics>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    if !(0.0..=1.0).contains(&query.low_score) {
+        return Err(Error::Validation(
+            "low_score must be between 0.0 and 1.0".into(),
+        ));
+    }
+
+    let analytics = state
+        .search_analytics
+        .analytics(&project.id, query.days, query.limit, query.low_score)
+        .await?;
+
+    Ok(Json(analytics))
+}
+
+/// List knowledge gap reports, newest first.
+///
+/// GET /projects/:project_id/analytics/gaps
+async fn list_gap_reports(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListReportsQuery>,
+) -> Result<Json<ListReportsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let reports = db::list_knowledge_gap_reports(&state.db, &project.id, query.limit.clamp(1, 100))
+        .await?
+        .into_iter()
+        .map(GapReportResponse::from)
+        .collect();
+
+    Ok(Json(ListReportsResponse { reports }))
+}
+
+/// Queue a knowledge gap report. Reports are also built weekly for every
+/// project that is searched.
+///
+/// POST /projects/:project_id/analytics/gaps
+async fn queue_gap_report(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    request: Option<Json<GapReportRequest>>,
+) -> Result<(StatusCode, Json<QueueReportResponse>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let days = request.days.unwrap_or(DEFAULT_GAP_WINDOW_DAYS);
+    if !(1..=MAX_WINDOW_DAYS).contains(&days) {
+        return Err(Error::Validation(format!(
+            "days must be between 1 and {}",
+            MAX_WINDOW_DAYS
+        )));
+    }
+    let cluster = request.cluster.unwrap_or(true);
+
+    let job_id = crate::models::new_id();
+    db::create_job(
+        &state.db,
+        db::CreateJob::new(job_id.clone(), JobType::KnowledgeGapReport)
+            .with_project(project.id)
+            .with_payload(serde_json::json!({ "days": days, "cluster": cluster })),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(QueueReportResponse {
+            job_id,
+            days,
+            cluster,
+            message: "Knowledge gap report queued".to_string(),
+        }),
+    ))
+}
@@ -0,0 +1,473 @@
+//! Search Evaluation Routes
+//!
+//! Golden query sets and search quality metrics. A golden set lists queries
+//! with the memories or files each should find; evaluations score search
+//! against it with recall@k, MRR and nDCG.
+//!
+//! Routes:
+//! - GET /projects/:project_id/eval/sets - List golden sets
+//! - POST /projects/:project_id/eval/sets - Create a golden set
+//! - GET /projects/:project_id/eval/sets/:set_id - Get a golden set with its queries
+//! - DELETE /projects/:project_id/eval/sets/:set_id - Delete a golden set
+//! - POST /projects/:project_id/eval/sets/:set_id/queries - Add a golden query
+//! - DELETE /projects/:project_id/eval/sets/:set_id/queries/:query_id - Remove a golden query
+//! - POST /projects/:project_id/eval/sets/:set_id/run - Queue an evaluation job
+//! - POST /projects/:project_id/eval/sets/:set_id/compare - Compare two scoring configs
+//! - GET /projects/:project_id/eval/history - List past evaluation runs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, GoldenQuery, GoldenSet, JobType, Project, SearchEvaluation};
+use crate::middleware::{rate_limit_search, require_project_read, require_project_write};
+use crate::services::search_eval::{EvalComparison, DEFAULT_K, MAX_K};
+use crate::services::EvalConfig;
+use crate::{AppState, Error, Result};
+
+/// Build search evaluation routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route(
+            "/:project_id/eval/sets/:set_id/compare",
+            post(compare).layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_search,
+            )),
+        )
+        .route("/:project_id/eval/sets", get(list_sets))
+        .route("/:project_id/eval/sets/:set_id", get(get_set))
+        .route("/:project_id/eval/history", get(list_history))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route("/:project_id/eval/sets", post(create_set))
+        .route("/:project_id/eval/sets/:set_id", delete(delete_set))
+        .route("/:project_id/eval/sets/:set_id/queries", post(add_query))
+        .route(
+            "/:project_id/eval/sets/:set_id/queries/:query_id",
+            delete(delete_query),
+        )
+        .route("/:project_id/eval/sets/:set_id/run", post(run_evaluation))
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ListSetsResponse {
+    pub sets: Vec<GoldenSet>,
+}
+
+/// Golden set with its queries.
+#[derive(Debug, Serialize)]
+pub struct SetResponse {
+    #[serde(flatten)]
+    pub set: GoldenSet,
+    pub queries: Vec<GoldenQueryResponse>,
+}
+
+/// Golden query.
+#[derive(Debug, Serialize)]
+pub struct GoldenQueryResponse {
+    pub id: String,
+    pub query: String,
+    pub expected_memory_ids: Vec<String>,
+    pub expected_file_paths: Vec<String>,
+    pub created_at: String,
+}
+
+impl From<GoldenQuery> for GoldenQueryResponse {
+    fn from(q: GoldenQuery) -> Self {
+        Self {
+            expected_memory_ids: q.expected_memory_ids_vec(),
+            expected_file_paths: q.expected_file_paths_vec(),
+            id: q.id,
+            query: q.query,
+            created_at: q.created_at,
+        }
+    }
+}
+
+/// Create golden set request.
+#[derive(Debug, Deserialize)]
+pub struct CreateSetRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Add golden query request.
+#[derive(Debug, Deserialize)]
+pub struct AddQueryRequest {
+    pub query: String,
+    #[serde(default)]
+    pub expected_memory_ids: Vec<String>,
+    #[serde(default)]
+    pub expected_file_paths: Vec<String>,
+}
+
+/// Run request.
+#[derive(Debug, Deserialize, Default)]
+pub struct RunRequest {
+    /// Cut-off rank for metrics
+    pub k: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunResponse {
+    pub job_id: String,
+    pub set_id: String,
+    pub k: usize,
+    pub message: String,
+}
+
+/// Compare request.
+#[derive(Debug, Deserialize)]
+pub struct CompareRequest {
+    /// Baseline config (defaults to the project's current settings)
+    pub a: Option<EvalConfig>,
+    /// Candidate config
+    pub b: EvalConfig,
+    /// Cut-off rank for metrics
+    pub k: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub set_id: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+/// Past evaluation run.
+#[derive(Debug, Serialize)]
+pub struct EvaluationResponse {
+    pub id: String,
+    pub set_id: String,
+    pub job_id: Option<String>,
+    pub k: i64,
+    pub config: serde_json::Value,
+    pub query_count: i64,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+    /// Per-query metrics
+    pub queries: serde_json::Value,
+    pub created_at: String,
+}
+
+impl From<SearchEvaluation> for EvaluationResponse {
+    fn from(e: SearchEvaluation) -> Self {
+        Self {
+            config: serde_json::from_str(&e.config).unwrap_or_default(),
+            queries: serde_json::from_str(&e.details).unwrap_or_default(),
+            id: e.id,
+            set_id: e.set_id,
+            job_id: e.job_id,
+            k: e.k,
+            query_count: e.query_count,
+            recall_at_k: e.recall_at_k,
+            mrr: e.mrr,
+            ndcg: e.ndcg,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryResponse {
+    pub evaluations: Vec<EvaluationResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetPath {
+    pub project_id: String,
+    pub set_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryPath {
+    pub project_id: String,
+    pub set_id: String,
+    pub query_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List golden sets.
+///
+/// GET /projects/:project_id/eval/sets
+async fn list_sets(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+) -> Result<Json<ListSetsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let sets = db::list_golden_sets(&state.db, &project.id).await?;
+
+    Ok(Json(ListSetsResponse { sets }))
+}
+
+/// Create a golden set.
+///
+/// POST /projects/:project_id/eval/sets
+async fn create_set(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Json(request): Json<CreateSetRequest>,
+) -> Result<(StatusCode, Json<GoldenSet>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    if request.name.trim().is_empty() {
+        return Err(Error::Validation("Golden set name cannot be empty".into()));
+    }
+
+    let set = db::create_golden_set(
+        &state.db,
+        db::CreateGoldenSet {
+            id: crate::models::new_id(),
+            project_id: project.id,
+            name: request.name.trim().to_string(),
+            description: request.description,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(set)))
+}
+
+/// Get a golden set with its queries.
+///
+/// GET /projects/:project_id/eval/sets/:set_id
+async fn get_set(
+    State(state): State<AppState>,
+    Path(path): Path<SetPath>,
+) -> Result<Json<SetResponse>> {
+    let (_, set) = project_set(&state, &path).await?;
+    let queries = db::list_golden_queries(&state.db, &set.id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(SetResponse { set, queries }))
+}
+
+/// Delete a golden set with its queries and evaluation history.
+///
+/// DELETE /projects/:project_id/eval/sets/:set_id
+async fn delete_set(
+    State(state): State<AppState>,
+    Path(path): Path<SetPath>,
+) -> Result<StatusCode> {
+    let (_, set) = project_set(&state, &path).await?;
+    db::delete_golden_set(&state.db, &set.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Add a query to a golden set.
+///
+/// POST /projects/:project_id/eval/sets/:set_id/queries
+async fn add_query(
+    State(state): State<AppState>,
+    Path(path): Path<SetPath>,
+    Json(request): Json<AddQueryRequest>,
+) -> Result<(StatusCode, Json<GoldenQueryResponse>)> {
+    let (_, set) = project_set(&state, &path).await?;
+
+    if request.query.trim().is_empty() {
+        return Err(Error::Validation("Query cannot be empty".into()));
+    }
+    if request.expected_memory_ids.is_empty() && request.expected_file_paths.is_empty() {
+        return Err(Error::Validation(
+            "Golden queries need expected_memory_ids or expected_file_paths".into(),
+        ));
+    }
+
+    let query = db::create_golden_query(
+        &state.db,
+        db::CreateGoldenQuery {
+            id: crate::models::new_id(),
+            set_id: set.id,
+            query: request.query.trim().to_string(),
+            expected_memory_ids: request.expected_memory_ids,
+            expected_file_paths: request.expected_file_paths,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(query.into())))
+}
+
+/// Remove a query from a golden set.
+///
+/// DELETE /projects/:project_id/eval/sets/:set_id/queries/:query_id
+async fn delete_query(
+    State(state): State<AppState>,
+    Path(path): Path<QueryPath>,
+) -> Result<StatusCode> {
+    let set_path = SetPath {
+        project_id: path.project_id,
+        set_id: path.set_id,
+    };
+    let (_, set) = project_set(&state, &set_path).await?;
+
+    if !db::delete_golden_query(&state.db, &set.id, &path.query_id).await? {
+        return Err(Error::NotFound(format!(
+            "Golden query not found: {}",
+            path.query_id
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Queue an evaluation of a golden set with the project's current settings.
+/// The job's metadata holds the metrics, and the run is added to the history.
+///
+/// POST /projects/:project_id/eval/sets/:set_id/run
+async fn run_evaluation(
+    State(state): State<AppState>,
+    Path(path): Path<SetPath>,
+    request: Option<Json<RunRequest>>,
+) -> Result<(StatusCode, Json<RunResponse>)> {
+    let (project, set) = project_set(&state, &path).await?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let k = validate_k(request.k)?;
+
+    let job_id = crate::models::new_id();
+    db::create_job(
+        &state.db,
+        db::CreateJob::new(job_id.clone(), JobType::EvaluateSearch)
+            .with_project(project.id)
+            .with_payload(serde_json::json!({ "set_id": set.id, "k": k })),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RunResponse {
+            job_id,
+            set_id: set.id,
+            k,
+            message: "Evaluation queued; results will be in the job metadata".to_string(),
+        }),
+    ))
+}
+
+/// Evaluate a golden set under two scoring configs side by side.
+///
+/// Nothing is recorded in the history.
+///
+/// POST /projects/:project_id/eval/sets/:set_id/compare
+async fn compare(
+    State(state): State<AppState>,
+    Path(path): Path<SetPath>,
+    Json(request): Json<CompareRequest>,
+) -> Result<Json<EvalComparison>> {
+    let (project, set) = project_set(&state, &path).await?;
+    let k = validate_k(request.k)?;
+
+    let a = request
+        .a
+        .unwrap_or_else(|| EvalConfig::for_project(&project));
+    for config in [&a, &request.b] {
+        validate_config(config)?;
+    }
+
+    let comparison = state
+        .search_eval
+        .compare(&project, &set.id, a, request.b, k)
+        .await?;
+
+    Ok(Json(comparison))
+}
+
+/// List past evaluation runs, newest first.
+///
+/// GET /projects/:project_id/eval/history
+async fn list_history(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let evaluations = db::list_search_evaluations(
+        &state.db,
+        &project.id,
+        query.set_id.as_deref(),
+        query.limit.clamp(1, 100),
+    )
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect();
+
+    Ok(Json(HistoryResponse { evaluations }))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Resolve the project and a golden set that belongs to it.
+async fn project_set(state: &AppState, path: &SetPath) -> Result<(Project, GoldenSet)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let set = db::get_golden_set(&state.db, &path.set_id).await?;
+    if set.project_id != project.id {
+        return Err(Error::NotFound(format!(
+            "Golden set not found: {}",
+            path.set_id
+        )));
+    }
+
+    Ok((project, set))
+}
+
+fn validate_k(k: Option<usize>) -> Result<usize> {
+    let k = k.unwrap_or(DEFAULT_K);
+    if !(1..=MAX_K).contains(&k) {
+        return Err(Error::Validation(format!(
+            "k must be between 1 and {}",
+            MAX_K
+        )));
+    }
+    Ok(k)
+}
+
+fn validate_config(config: &EvalConfig) -> Result<()> {
+    if !(0.0..=1.0).contains(&config.strength_weight) {
+        return Err(Error::Validation(
+            "strength_weight must be between 0.0 and 1.0".into(),
+        ));
+    }
+    if config.decay_half_life_days < 1.0 {
+        return Err(Error::Validation(
+            "decay_half_life_days must be at least 1".into(),
+        ));
+    }
+    Ok(())
+}
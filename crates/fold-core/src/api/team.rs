@@ -0,0 +1,210 @@
+//! Team Presence Routes
+//!
+//! Who is working on what within a project.
+//!
+//! Routes:
+//! - GET /projects/:project_id/team - List members (optionally check file conflicts)
+//! - GET /projects/:project_id/team/:username - Get a member's status
+//! - PUT /projects/:project_id/team/:username - Update a member's status
+//! - DELETE /projects/:project_id/team/:username - Remove a member
+//!
+//! Members update and remove only their own presence; admins may change
+//! anyone's.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, TeamMemberStatus, TeamStatus, UpdateTeamStatus};
+use crate::middleware::{require_project_read, require_project_write, AuthContext};
+use crate::services::FileConflict;
+use crate::{AppState, Result};
+
+/// Build team presence routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/team", get(list_team))
+        .route("/:project_id/team/:username", get(get_member))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/team/:username",
+            put(update_member).delete(remove_member),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ListTeamQuery {
+    /// Only include active members
+    #[serde(default)]
+    pub active: bool,
+    /// Comma-separated file paths to check for conflicts
+    pub files: Option<String>,
+    /// Exclude this member from conflict results (usually the caller)
+    pub exclude: Option<String>,
+}
+
+/// Status update request.
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub status: TeamMemberStatus,
+    pub current_task: Option<String>,
+    #[serde(default)]
+    pub current_files: Option<Vec<String>>,
+}
+
+/// Team member presence.
+#[derive(Debug, Serialize)]
+pub struct TeamMemberResponse {
+    pub username: String,
+    pub status: String,
+    pub current_task: Option<String>,
+    pub current_files: Vec<String>,
+    pub last_seen: String,
+    pub session_start: Option<String>,
+}
+
+impl From<TeamStatus> for TeamMemberResponse {
+    fn from(s: TeamStatus) -> Self {
+        Self {
+            current_files: s.current_files_vec(),
+            username: s.username,
+            status: s.status,
+            current_task: s.current_task,
+            last_seen: s.last_seen,
+            session_start: s.session_start,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListTeamResponse {
+    pub members: Vec<TeamMemberResponse>,
+    /// Active members already working on the requested files
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<FileConflict>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemberPath {
+    pub project_id: String,
+    pub username: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List team members.
+///
+/// GET /projects/:project_id/team
+async fn list_team(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListTeamQuery>,
+) -> Result<Json<ListTeamResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let members = state.team.list(&project.id, query.active).await?;
+
+    let files: Vec<String> = query
+        .files
+        .as_deref()
+        .map(|f| {
+            f.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let conflicts = state
+        .team
+        .find_conflicts(&project.id, &files, query.exclude.as_deref())
+        .await?;
+
+    Ok(Json(ListTeamResponse {
+        members: members.into_iter().map(Into::into).collect(),
+        conflicts,
+    }))
+}
+
+/// Get a member's status.
+///
+/// GET /projects/:project_id/team/:username
+async fn get_member(
+    State(state): State<AppState>,
+    Path(path): Path<MemberPath>,
+) -> Result<Json<TeamMemberResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let member = state.team.get(&project.id, &path.username).await?;
+    Ok(Json(member.into()))
+}
+
+/// Update a member's status (also serves as a presence heartbeat).
+///
+/// PUT /projects/:project_id/team/:username
+async fn update_member(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(path): Path<MemberPath>,
+    Json(request): Json<UpdateMemberRequest>,
+) -> Result<Json<TeamMemberResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let username = state
+        .team
+        .acting_member(&auth.user_id, auth.is_admin, Some(&path.username))
+        .await?;
+
+    let member = state
+        .team
+        .update_status(
+            &project.id,
+            &username,
+            UpdateTeamStatus {
+                status: request.status,
+                current_task: request.current_task,
+                current_files: request.current_files,
+            },
+        )
+        .await?;
+
+    Ok(Json(member.into()))
+}
+
+/// Remove a member from the presence list.
+///
+/// DELETE /projects/:project_id/team/:username
+async fn remove_member(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(path): Path<MemberPath>,
+) -> Result<StatusCode> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let username = state
+        .team
+        .acting_member(&auth.user_id, auth.is_admin, Some(&path.username))
+        .await?;
+    state.team.leave(&project.id, &username).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
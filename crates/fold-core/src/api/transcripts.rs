@@ -0,0 +1,162 @@
+//! Transcript Routes
+//!
+//! Ingest agent session transcripts (Claude Code JSONL) as session memories.
+//!
+//! Routes:
+//! - GET /projects/:project_id/transcripts - List imported transcripts
+//! - POST /projects/:project_id/transcripts - Ingest a transcript (LLM rate limit)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+use crate::middleware::{rate_limit_llm, require_project_read, require_project_write};
+use crate::services::TranscriptImportResult;
+use crate::{AppState, Error, Result};
+
+/// Build transcript routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/transcripts", get(list_transcripts))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/transcripts",
+            post(ingest_transcript).layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_llm,
+            )),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Transcript ingestion request.
+#[derive(Debug, Deserialize)]
+pub struct IngestTranscriptRequest {
+    /// Raw JSONL transcript content
+    pub content: String,
+
+    /// Dedupe key; defaults to the transcript's sessionId
+    pub transcript_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListTranscriptsQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Imported transcript summary.
+#[derive(Debug, Serialize)]
+pub struct TranscriptImportResponse {
+    pub id: String,
+    pub transcript_id: String,
+    pub memory_id: Option<String>,
+    pub source_path: Option<String>,
+    pub message_count: i64,
+    pub files_touched: Vec<String>,
+    pub imported_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListTranscriptsResponse {
+    pub transcripts: Vec<TranscriptImportResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Ingest a transcript.
+///
+/// POST /projects/:project_id/transcripts
+///
+/// Returns 201 for a new import and 200 when the transcript was already
+/// imported (the original memory ID is returned).
+async fn ingest_transcript(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Json(request): Json<IngestTranscriptRequest>,
+) -> Result<(StatusCode, Json<TranscriptImportResult>)> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    if request.content.trim().is_empty() {
+        return Err(Error::Validation("Transcript content cannot be empty".into()));
+    }
+
+    let result = state
+        .transcripts
+        .ingest(
+            &project.id,
+            request.transcript_id.as_deref(),
+            &request.content,
+            None,
+        )
+        .await?;
+
+    let status = if result.already_imported {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    Ok((status, Json(result)))
+}
+
+/// List imported transcripts.
+///
+/// GET /projects/:project_id/transcripts
+async fn list_transcripts(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<ListTranscriptsQuery>,
+) -> Result<Json<ListTranscriptsResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    let imports =
+        db::list_transcript_imports(&state.db, &project.id, query.limit.clamp(1, 500)).await?;
+
+    let transcripts: Vec<TranscriptImportResponse> = imports
+        .into_iter()
+        .map(|i| TranscriptImportResponse {
+            files_touched: i.files_touched_vec(),
+            id: i.id,
+            transcript_id: i.transcript_id,
+            memory_id: i.memory_id,
+            source_path: i.source_path,
+            message_count: i.message_count,
+            imported_at: i.imported_at,
+        })
+        .collect();
+
+    Ok(Json(ListTranscriptsResponse {
+        total: transcripts.len(),
+        transcripts,
+    }))
+}
@@ -0,0 +1,187 @@
+//! Usage and Quota Routes
+//!
+//! Daily embedding and LLM call counts per project, and admin overrides of
+//! the configured daily quotas.
+//!
+//! Routes:
+//! - GET /projects/:project_id/usage - Today's usage, limits and recent history
+//! - PUT /projects/:project_id/quotas - Set quota overrides (admin only)
+
+use axum::{
+    extract::{Path, Query, State},
+    middleware,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ProjectQuota, ProjectUsage, UsageKind};
+use crate::middleware::{
+    require_project_read, require_project_write, require_projects_admin_scope, ProjectAccessContext,
+};
+use crate::services::effective_limit;
+use crate::{AppState, Error, Result};
+
+/// Build usage routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/usage", get(get_usage))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route(
+            "/:project_id/quotas",
+            put(set_quotas).layer(middleware::from_fn(require_projects_admin_scope)),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Days of history to return
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+fn default_days() -> i64 {
+    30
+}
+
+/// Calls made today against a daily limit.
+#[derive(Debug, Serialize)]
+pub struct QuotaStatus {
+    pub used: i64,
+    /// Daily limit (null = unlimited)
+    pub limit: Option<i64>,
+    pub remaining: Option<i64>,
+}
+
+impl QuotaStatus {
+    fn new(used: i64, limit: Option<i64>) -> Self {
+        Self {
+            used,
+            limit,
+            remaining: limit.map(|l| (l - used).max(0)),
+        }
+    }
+}
+
+/// Per-token request limits, per minute (0 = unlimited).
+#[derive(Debug, Serialize)]
+pub struct RateLimits {
+    pub enabled: bool,
+    pub search_per_minute: u32,
+    pub write_per_minute: u32,
+    pub llm_per_minute: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageResponse {
+    pub project_id: String,
+    pub day: String,
+    pub embedding: QuotaStatus,
+    pub llm: QuotaStatus,
+    pub rate_limits: RateLimits,
+    /// Project overrides of the configured quotas, if any
+    pub quota_overrides: Option<ProjectQuota>,
+    /// Daily usage, most recent first
+    pub history: Vec<ProjectUsage>,
+}
+
+/// Set quota overrides. Omit a field to use the configured default, 0 for unlimited.
+#[derive(Debug, Deserialize)]
+pub struct SetQuotasRequest {
+    pub daily_embedding_calls: Option<i64>,
+    pub daily_llm_calls: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectPath {
+    pub project_id: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Get today's provider usage and limits for a project.
+///
+/// GET /projects/:project_id/usage
+async fn get_usage(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+    let config = state.rate_limits.config();
+
+    let today = db::get_usage_today(&state.db, &project.id).await?;
+    let quota = db::get_project_quota(&state.db, &project.id).await?;
+    let history = db::list_usage(&state.db, &project.id, query.days.clamp(1, 365)).await?;
+
+    Ok(Json(UsageResponse {
+        embedding: QuotaStatus::new(
+            today.calls(UsageKind::Embedding),
+            effective_limit(config, quota.as_ref(), UsageKind::Embedding),
+        ),
+        llm: QuotaStatus::new(
+            today.calls(UsageKind::Llm),
+            effective_limit(config, quota.as_ref(), UsageKind::Llm),
+        ),
+        rate_limits: RateLimits {
+            enabled: config.enabled,
+            search_per_minute: config.search_per_minute,
+            write_per_minute: config.write_per_minute,
+            llm_per_minute: config.llm_per_minute,
+        },
+        project_id: project.id,
+        day: today.day,
+        quota_overrides: quota,
+        history,
+    }))
+}
+
+/// Set daily quota overrides for a project. Admin only, since quotas guard
+/// shared provider credit.
+///
+/// PUT /projects/:project_id/quotas
+async fn set_quotas(
+    State(state): State<AppState>,
+    Path(path): Path<ProjectPath>,
+    Extension(access): Extension<ProjectAccessContext>,
+    Json(request): Json<SetQuotasRequest>,
+) -> Result<Json<ProjectQuota>> {
+    if access.user_role != "admin" {
+        return Err(Error::Forbidden);
+    }
+
+    let project = db::get_project_by_id_or_slug(&state.db, &path.project_id).await?;
+
+    for (name, value) in [
+        ("daily_embedding_calls", request.daily_embedding_calls),
+        ("daily_llm_calls", request.daily_llm_calls),
+    ] {
+        if value.is_some_and(|v| v < 0) {
+            return Err(Error::Validation(format!("{} cannot be negative", name)));
+        }
+    }
+
+    let quota = db::set_project_quota(
+        &state.db,
+        &project.id,
+        request.daily_embedding_calls,
+        request.daily_llm_calls,
+    )
+    .await?;
+
+    Ok(Json(quota))
+}
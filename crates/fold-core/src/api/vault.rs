@@ -0,0 +1,190 @@
+//! Vault Interop Routes
+//!
+//! Obsidian/Logseq interop for fold/ memories: switching a project's fold/
+//! directory to the human-readable layout, and importing an existing vault
+//! as memories.
+//!
+//! Routes:
+//! - GET /projects/:project_id/fold/layout - Current fold/ layout
+//! - PUT /projects/:project_id/fold/layout - Switch layout, moving existing files
+//! - POST /projects/:project_id/import/vault - Import a vault directory on the server
+
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::{
+    extract::{Path, State},
+    middleware,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::db::{self, AuditAction};
+use crate::middleware::{
+    require_project_read, require_project_write, require_projects_admin_scope,
+};
+use crate::services::fold_storage::{storage_error_to_error, StorageLayout};
+use crate::services::{AuditActor, VaultImportResult};
+use crate::{AppState, Error, Result};
+
+/// Build vault interop routes.
+pub fn routes(state: AppState) -> Router<AppState> {
+    let read = Router::new()
+        .route("/:project_id/fold/layout", get(get_layout))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_project_read,
+        ));
+
+    let write = Router::new()
+        .route("/:project_id/fold/layout", put(set_layout))
+        .route(
+            "/:project_id/import/vault",
+            // Reads a directory on the server, so it needs the projects admin
+            // scope and the directory must lie under the project root
+            post(import_vault).layer(middleware::from_fn(require_projects_admin_scope)),
+        )
+        .layer(middleware::from_fn_with_state(state, require_project_write));
+
+    read.merge(write)
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SetLayoutRequest {
+    /// "hashed" (fold/a/b/{id}.md) or "readable" (fold/{type}/{slug}.md)
+    pub layout: StorageLayout,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LayoutResponse {
+    pub layout: StorageLayout,
+    /// Memory files moved by the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportVaultRequest {
+    /// Vault directory on the server, relative to the project root or an
+    /// absolute path under it
+    pub vault_path: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Get the project's fold/ layout.
+///
+/// GET /projects/:project_id/fold/layout
+async fn get_layout(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> Result<Json<LayoutResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let layout = state
+        .fold_storage
+        .layout(FsPath::new(&project.root_path))
+        .await;
+
+    Ok(Json(LayoutResponse {
+        layout,
+        moved: None,
+    }))
+}
+
+/// Switch the project's fold/ layout.
+///
+/// PUT /projects/:project_id/fold/layout
+async fn set_layout(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    actor: AuditActor,
+    Json(request): Json<SetLayoutRequest>,
+) -> Result<Json<LayoutResponse>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let project_root = FsPath::new(&project.root_path);
+
+    if !state.fold_storage.is_initialised(project_root).await {
+        return Err(Error::Validation(
+            "Project has no fold/ directory yet".into(),
+        ));
+    }
+
+    let moved = state
+        .fold_storage
+        .set_layout(project_root, request.layout)
+        .await
+        .map_err(storage_error_to_error)?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectFoldLayout,
+            Some(&project.id),
+            Some(&project.id),
+            json!({ "layout": request.layout, "moved": moved }),
+        )
+        .await;
+
+    Ok(Json(LayoutResponse {
+        layout: request.layout,
+        moved: Some(moved),
+    }))
+}
+
+/// Import an Obsidian/Logseq vault as memories.
+///
+/// POST /projects/:project_id/import/vault
+async fn import_vault(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    actor: AuditActor,
+    Json(request): Json<ImportVaultRequest>,
+) -> Result<Json<VaultImportResult>> {
+    let project = db::get_project_by_id_or_slug(&state.db, &project_id).await?;
+    let requested = request.vault_path.trim();
+    if requested.is_empty() {
+        return Err(Error::Validation("vault_path is required".into()));
+    }
+
+    // Security: ensure the resolved path is within the project root
+    let project_root = PathBuf::from(&project.root_path);
+    let canonical_root = project_root
+        .canonicalize()
+        .map_err(|e| Error::Internal(format!("Failed to resolve project root: {}", e)))?;
+    let vault_path = project_root
+        .join(requested)
+        .canonicalize()
+        .map_err(|_| Error::Validation(format!("Vault path not found: {}", requested)))?;
+    if !vault_path.starts_with(&canonical_root) {
+        return Err(Error::Validation(
+            "Vault path must be inside the project root".into(),
+        ));
+    }
+
+    let result = state.vault_import.import(&project.id, &vault_path).await?;
+
+    state
+        .audit
+        .record(
+            &actor,
+            AuditAction::ProjectVaultImport,
+            Some(&project.id),
+            Some(&project.id),
+            json!({
+                "vault_path": vault_path.display().to_string(),
+                "imported": result.imported,
+                "links_created": result.links_created,
+            }),
+        )
+        .await;
+
+    Ok(Json(result))
+}
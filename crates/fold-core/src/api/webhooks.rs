@@ -23,6 +23,8 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use uuid::Uuid;
 
+use crate::services::fold_storage::memory_id_from_path;
+use crate::services::{FoldChanges, GitSyncService};
 use crate::{db, AppState, Error, Result};
 
 type HmacSha256 = Hmac<Sha256>;
@@ -57,6 +59,8 @@ pub struct GitHubWebhookPayload {
     pub action: Option<String>,
     #[serde(rename = "ref")]
     pub git_ref: Option<String>,
+    /// Head commit SHA after a push
+    pub after: Option<String>,
     pub repository: Option<GitHubRepository>,
     pub sender: Option<GitHubUser>,
     pub commits: Option<Vec<GitHubCommit>>,
@@ -123,6 +127,8 @@ pub struct GitLabWebhookPayload {
     pub event_type: Option<String>,
     #[serde(rename = "ref")]
     pub git_ref: Option<String>,
+    /// Head commit SHA after a push
+    pub after: Option<String>,
     pub project: Option<GitLabProject>,
     pub user: Option<GitLabUser>,
     pub commits: Option<Vec<GitLabCommit>>,
@@ -404,18 +410,30 @@ async fn process_github_push(
         return Ok(None);
     }
 
-    // Collect changed files
+    // Collect changed files, and edits to fold/ memory files by people
+    let ignored_authors = project.ignored_commit_authors_vec();
     let mut changed_files: Vec<String> = Vec::new();
+    let mut fold_changes = FoldChanges::default();
     if let Some(commits) = &payload.commits {
         for commit in commits {
-            if let Some(added) = &commit.added {
-                changed_files.extend(added.clone());
-            }
-            if let Some(modified) = &commit.modified {
-                changed_files.extend(modified.clone());
+            let added = commit.added.as_deref().unwrap_or_default();
+            let modified = commit.modified.as_deref().unwrap_or_default();
+            let removed = commit.removed.as_deref().unwrap_or_default();
+
+            changed_files.extend(
+                added
+                    .iter()
+                    .chain(modified)
+                    .filter(|path| memory_id_from_path(path).is_none())
+                    .cloned(),
+            );
+            if !GitSyncService::is_ignored_author(&commit.author.name, &ignored_authors) {
+                fold_changes.record_commit(added, modified, removed);
             }
         }
     }
+    changed_files.sort();
+    changed_files.dedup();
 
     // Create indexing job in database
     let job_id = crate::models::new_id();
@@ -423,6 +441,11 @@ async fn process_github_push(
         &state.db,
         db::CreateJob::new(job_id.clone(), db::JobType::IndexRepo)
             .with_project(project.id.clone())
+            .with_payload(serde_json::json!({
+                "files": changed_files,
+                "fold": fold_changes,
+                "commit_sha": payload.after,
+            }))
             .with_total_items(changed_files.len() as i32),
     )
     .await?;
@@ -677,18 +700,30 @@ async fn process_gitlab_push(
         return Ok(None);
     }
 
-    // Collect changed files
+    // Collect changed files, and edits to fold/ memory files by people
+    let ignored_authors = project.ignored_commit_authors_vec();
     let mut changed_files: Vec<String> = Vec::new();
+    let mut fold_changes = FoldChanges::default();
     if let Some(commits) = &payload.commits {
         for commit in commits {
-            if let Some(added) = &commit.added {
-                changed_files.extend(added.clone());
-            }
-            if let Some(modified) = &commit.modified {
-                changed_files.extend(modified.clone());
+            let added = commit.added.as_deref().unwrap_or_default();
+            let modified = commit.modified.as_deref().unwrap_or_default();
+            let removed = commit.removed.as_deref().unwrap_or_default();
+
+            changed_files.extend(
+                added
+                    .iter()
+                    .chain(modified)
+                    .filter(|path| memory_id_from_path(path).is_none())
+                    .cloned(),
+            );
+            if !GitSyncService::is_ignored_author(&commit.author.name, &ignored_authors) {
+                fold_changes.record_commit(added, modified, removed);
             }
         }
     }
+    changed_files.sort();
+    changed_files.dedup();
 
     // Create indexing job in database
     let job_id = crate::models::new_id();
@@ -696,6 +731,11 @@ async fn process_gitlab_push(
         &state.db,
         db::CreateJob::new(job_id.clone(), db::JobType::IndexRepo)
             .with_project(project.id.clone())
+            .with_payload(serde_json::json!({
+                "files": changed_files,
+                "fold": fold_changes,
+                "commit_sha": payload.after,
+            }))
             .with_total_items(changed_files.len() as i32),
     )
     .await?;
@@ -35,6 +35,11 @@ pub struct Config {
     pub session: SessionConfig,
     pub storage: StorageConfig,
     pub indexing: IndexingConfig,
+    pub transcripts: TranscriptConfig,
+    pub team: TeamConfig,
+    pub rate_limit: RateLimitConfig,
+    pub audit: AuditConfig,
+    pub secrets: SecretsConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -150,6 +155,59 @@ pub struct IndexingConfig {
     pub concurrency_limit: usize,
 }
 
+#[derive(Debug, Clone)]
+pub struct TranscriptConfig {
+    /// Watch the Claude Code projects directory for finished transcripts (default: false)
+    pub watch_enabled: bool,
+    /// Directory containing per-project transcript folders (default: "~/.claude/projects")
+    pub watch_path: String,
+    /// Seconds between directory scans (default: 60)
+    pub poll_interval_secs: u64,
+    /// Seconds a transcript must be unmodified before it is imported (default: 300)
+    pub idle_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamConfig {
+    /// Minutes without an update before an active member is marked idle (default: 15)
+    pub idle_minutes: i64,
+    /// Seconds between idle sweeps (default: 60)
+    pub sweep_interval_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Enforce per-token request limits (default: true)
+    pub enabled: bool,
+    /// Search and context requests per minute per token (default: 120)
+    pub search_per_minute: u32,
+    /// Memory writes per minute per token (default: 60)
+    pub write_per_minute: u32,
+    /// LLM-backed requests per minute per token (default: 20)
+    pub llm_per_minute: u32,
+    /// Embedding calls per project per day, 0 for unlimited (default: 0)
+    pub daily_embedding_calls: i64,
+    /// LLM calls per project per day, 0 for unlimited (default: 0)
+    pub daily_llm_calls: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    /// Also append audit events as JSON lines to this file (default: none)
+    pub jsonl_path: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretsConfig {
+    /// Base64-encoded 32-byte key for encrypting stored secrets (default: none)
+    pub master_key: Option<String>,
+    /// File holding the master key, used when `FOLD_MASTER_KEY` is unset (default: none).
+    /// Further lines hold retired keys, as for `previous_master_keys`.
+    pub master_key_file: Option<String>,
+    /// Retired master keys still accepted for decryption during rotation (default: none)
+    pub previous_master_keys: Vec<String>,
+}
+
 impl Config {
     pub fn from_env() -> Self {
         dotenvy::dotenv().ok();
@@ -195,6 +253,58 @@ impl Config {
             indexing: IndexingConfig {
                 concurrency_limit: env_or("INDEXING_CONCURRENCY", "4").parse().unwrap_or(4),
             },
+            transcripts: TranscriptConfig {
+                watch_enabled: env_or("TRANSCRIPT_WATCH_ENABLED", "false")
+                    .parse()
+                    .unwrap_or(false),
+                watch_path: env::var("TRANSCRIPT_WATCH_PATH").unwrap_or_else(|_| {
+                    dirs::home_dir()
+                        .unwrap_or_else(|| std::path::PathBuf::from("."))
+                        .join(".claude")
+                        .join("projects")
+                        .to_string_lossy()
+                        .to_string()
+                }),
+                poll_interval_secs: env_or("TRANSCRIPT_WATCH_INTERVAL", "60")
+                    .parse()
+                    .unwrap_or(60),
+                idle_secs: env_or("TRANSCRIPT_IDLE_SECS", "300").parse().unwrap_or(300),
+            },
+            team: TeamConfig {
+                idle_minutes: env_or("TEAM_IDLE_MINUTES", "15").parse().unwrap_or(15),
+                sweep_interval_secs: env_or("TEAM_SWEEP_INTERVAL", "60").parse().unwrap_or(60),
+            },
+            rate_limit: RateLimitConfig {
+                enabled: env_or("RATE_LIMIT_ENABLED", "true").parse().unwrap_or(true),
+                search_per_minute: env_or("RATE_LIMIT_SEARCH_PER_MINUTE", "120")
+                    .parse()
+                    .unwrap_or(120),
+                write_per_minute: env_or("RATE_LIMIT_WRITE_PER_MINUTE", "60")
+                    .parse()
+                    .unwrap_or(60),
+                llm_per_minute: env_or("RATE_LIMIT_LLM_PER_MINUTE", "20")
+                    .parse()
+                    .unwrap_or(20),
+                daily_embedding_calls: env_or("QUOTA_DAILY_EMBEDDING_CALLS", "0")
+                    .parse()
+                    .unwrap_or(0),
+                daily_llm_calls: env_or("QUOTA_DAILY_LLM_CALLS", "0").parse().unwrap_or(0),
+            },
+            audit: AuditConfig {
+                jsonl_path: env::var("AUDIT_LOG_PATH").ok(),
+            },
+            secrets: SecretsConfig {
+                master_key: env::var("FOLD_MASTER_KEY").ok(),
+                master_key_file: env::var("FOLD_MASTER_KEY_FILE").ok(),
+                previous_master_keys: env::var("FOLD_MASTER_KEY_PREVIOUS")
+                    .map(|v| {
+                        v.split(',')
+                            .map(|k| k.trim().to_string())
+                            .filter(|k| !k.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
         }
     }
 
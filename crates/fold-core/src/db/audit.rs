@@ -0,0 +1,380 @@
+//! Audit event database queries.
+//!
+//! Append-only record of security-relevant and data-changing operations:
+//! who (user and token) did what to which resource. There are no update or
+//! delete queries on purpose.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Audited operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    #[serde(rename = "memory.create")]
+    MemoryCreate,
+    #[serde(rename = "memory.update")]
+    MemoryUpdate,
+    #[serde(rename = "memory.delete")]
+    MemoryDelete,
+    #[serde(rename = "project.create")]
+    ProjectCreate,
+    #[serde(rename = "project.update")]
+    ProjectUpdate,
+    #[serde(rename = "project.delete")]
+    ProjectDelete,
+    #[serde(rename = "project.export")]
+    ProjectExport,
+    #[serde(rename = "project.import")]
+    ProjectImport,
+    #[serde(rename = "project.vault_import")]
+    ProjectVaultImport,
+    #[serde(rename = "project.fold_layout")]
+    ProjectFoldLayout,
+    #[serde(rename = "project.providers_update")]
+    ProjectProvidersUpdate,
+    #[serde(rename = "member.add")]
+    MemberAdd,
+    #[serde(rename = "member.update")]
+    MemberUpdate,
+    #[serde(rename = "member.remove")]
+    MemberRemove,
+    #[serde(rename = "token.create")]
+    TokenCreate,
+    #[serde(rename = "token.revoke")]
+    TokenRevoke,
+    #[serde(rename = "provider.create")]
+    ProviderCreate,
+    #[serde(rename = "provider.update")]
+    ProviderUpdate,
+    #[serde(rename = "provider.delete")]
+    ProviderDelete,
+    #[serde(rename = "webhook.create")]
+    WebhookCreate,
+    #[serde(rename = "webhook.update")]
+    WebhookUpdate,
+    #[serde(rename = "webhook.delete")]
+    WebhookDelete,
+    /// An MCP tool call that changed data
+    #[serde(rename = "mcp.tool_call")]
+    McpToolCall,
+    /// An MCP tool call refused for lack of permission or scope
+    #[serde(rename = "mcp.tool_denied")]
+    McpToolDenied,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MemoryCreate => "memory.create",
+            Self::MemoryUpdate => "memory.update",
+            Self::MemoryDelete => "memory.delete",
+            Self::ProjectCreate => "project.create",
+            Self::ProjectUpdate => "project.update",
+            Self::ProjectDelete => "project.delete",
+            Self::ProjectExport => "project.export",
+            Self::ProjectImport => "project.import",
+            Self::ProjectVaultImport => "project.vault_import",
+            Self::ProjectFoldLayout => "project.fold_layout",
+            Self::ProjectProvidersUpdate => "project.providers_update",
+            Self::MemberAdd => "member.add",
+            Self::MemberUpdate => "member.update",
+            Self::MemberRemove => "member.remove",
+            Self::TokenCreate => "token.create",
+            Self::TokenRevoke => "token.revoke",
+            Self::ProviderCreate => "provider.create",
+            Self::ProviderUpdate => "provider.update",
+            Self::ProviderDelete => "provider.delete",
+            Self::WebhookCreate => "webhook.create",
+            Self::WebhookUpdate => "webhook.update",
+            Self::WebhookDelete => "webhook.delete",
+            Self::McpToolCall => "mcp.tool_call",
+            Self::McpToolDenied => "mcp.tool_denied",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "memory.create" => Some(Self::MemoryCreate),
+            "memory.update" => Some(Self::MemoryUpdate),
+            "memory.delete" => Some(Self::MemoryDelete),
+            "project.create" => Some(Self::ProjectCreate),
+            "project.update" => Some(Self::ProjectUpdate),
+            "project.delete" => Some(Self::ProjectDelete),
+            "project.export" => Some(Self::ProjectExport),
+            "project.import" => Some(Self::ProjectImport),
+            "project.vault_import" => Some(Self::ProjectVaultImport),
+            "project.fold_layout" => Some(Self::ProjectFoldLayout),
+            "project.providers_update" => Some(Self::ProjectProvidersUpdate),
+            "member.add" => Some(Self::MemberAdd),
+            "member.update" => Some(Self::MemberUpdate),
+            "member.remove" => Some(Self::MemberRemove),
+            "token.create" => Some(Self::TokenCreate),
+            "token.revoke" => Some(Self::TokenRevoke),
+            "provider.create" => Some(Self::ProviderCreate),
+            "provider.update" => Some(Self::ProviderUpdate),
+            "provider.delete" => Some(Self::ProviderDelete),
+            "webhook.create" => Some(Self::WebhookCreate),
+            "webhook.update" => Some(Self::WebhookUpdate),
+            "webhook.delete" => Some(Self::WebhookDelete),
+            "mcp.tool_call" => Some(Self::McpToolCall),
+            "mcp.tool_denied" => Some(Self::McpToolDenied),
+            _ => None,
+        }
+    }
+
+    /// Type of resource the action applies to.
+    pub fn resource_type(&self) -> &'static str {
+        match self {
+            Self::MemoryCreate | Self::MemoryUpdate | Self::MemoryDelete => "memory",
+            Self::ProjectCreate
+            | Self::ProjectUpdate
+            | Self::ProjectDelete
+            | Self::ProjectExport
+            | Self::ProjectImport
+            | Self::ProjectVaultImport
+            | Self::ProjectFoldLayout
+            | Self::ProjectProvidersUpdate => "project",
+            Self::MemberAdd | Self::MemberUpdate | Self::MemberRemove => "member",
+            Self::TokenCreate | Self::TokenRevoke => "token",
+            Self::ProviderCreate | Self::ProviderUpdate | Self::ProviderDelete => "provider",
+            Self::WebhookCreate | Self::WebhookUpdate | Self::WebhookDelete => "webhook",
+            Self::McpToolCall | Self::McpToolDenied => "mcp_tool",
+        }
+    }
+}
+
+/// A recorded audit event.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub project_id: Option<String>,
+    pub actor_user_id: Option<String>,
+    pub actor_token_id: Option<String>,
+    /// JSON object with action-specific details
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+impl AuditEvent {
+    /// Parse details as JSON.
+    pub fn details_json(&self) -> Option<serde_json::Value> {
+        self.details
+            .as_deref()
+            .and_then(|d| serde_json::from_str(d).ok())
+    }
+}
+
+/// Input for recording an audit event.
+#[derive(Debug, Clone)]
+pub struct CreateAuditEvent {
+    pub id: String,
+    pub action: AuditAction,
+    pub resource_id: Option<String>,
+    pub project_id: Option<String>,
+    pub actor_user_id: Option<String>,
+    pub actor_token_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Filter for listing audit events.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub project_id: Option<String>,
+    pub actor_user_id: Option<String>,
+    pub actor_token_id: Option<String>,
+    /// Filter by created_at >= this time (`YYYY-MM-DD HH:MM:SS`)
+    pub since: Option<String>,
+    /// Filter by created_at < this time (`YYYY-MM-DD HH:MM:SS`)
+    pub until: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Record an audit event.
+pub async fn insert_audit_event(pool: &DbPool, input: CreateAuditEvent) -> Result<AuditEvent> {
+    sqlx::query_as::<_, AuditEvent>(
+        r#"
+        INSERT INTO audit_events (
+            id, action, resource_type, resource_id, project_id,
+            actor_user_id, actor_token_id, details
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(input.action.as_str())
+    .bind(input.action.resource_type())
+    .bind(&input.resource_id)
+    .bind(&input.project_id)
+    .bind(&input.actor_user_id)
+    .bind(&input.actor_token_id)
+    .bind(input.details.as_ref().map(|d| d.to_string()))
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+fn filter_clause(filter: &AuditFilter) -> (String, Vec<String>) {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut bindings: Vec<String> = Vec::new();
+
+    let columns = [
+        ("action = ?", &filter.action),
+        ("resource_type = ?", &filter.resource_type),
+        ("resource_id = ?", &filter.resource_id),
+        ("project_id = ?", &filter.project_id),
+        ("actor_user_id = ?", &filter.actor_user_id),
+        ("actor_token_id = ?", &filter.actor_token_id),
+        ("created_at >= ?", &filter.since),
+        ("created_at < ?", &filter.until),
+    ];
+    for (condition, value) in columns {
+        if let Some(value) = value {
+            conditions.push(condition);
+            bindings.push(value.clone());
+        }
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    (where_clause, bindings)
+}
+
+/// List audit events matching a filter. Most recent first.
+pub async fn list_audit_events(pool: &DbPool, filter: &AuditFilter) -> Result<Vec<AuditEvent>> {
+    let (where_clause, bindings) = filter_clause(filter);
+
+    let query = format!(
+        r#"
+        SELECT * FROM audit_events
+        {}
+        ORDER BY created_at DESC, rowid DESC
+        LIMIT ? OFFSET ?
+        "#,
+        where_clause
+    );
+
+    let mut q = sqlx::query_as::<_, AuditEvent>(&query);
+    for binding in &bindings {
+        q = q.bind(binding);
+    }
+    q = q
+        .bind(filter.limit.unwrap_or(100))
+        .bind(filter.offset.unwrap_or(0));
+
+    q.fetch_all(pool).await.map_err(Error::Database)
+}
+
+/// Count audit events matching a filter, ignoring limit and offset.
+pub async fn count_audit_events(pool: &DbPool, filter: &AuditFilter) -> Result<i64> {
+    let (where_clause, bindings) = filter_clause(filter);
+    let query = format!("SELECT COUNT(*) FROM audit_events {}", where_clause);
+
+    let mut q = sqlx::query_as::<_, (i64,)>(&query);
+    for binding in &bindings {
+        q = q.bind(binding);
+    }
+
+    let (count,) = q.fetch_one(pool).await?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{init_pool, migrate};
+    use serde_json::json;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+        pool
+    }
+
+    fn event(id: &str, action: AuditAction, user: &str) -> CreateAuditEvent {
+        CreateAuditEvent {
+            id: id.to_string(),
+            action,
+            resource_id: Some(format!("res-{}", id)),
+            project_id: Some("proj-1".to_string()),
+            actor_user_id: Some(user.to_string()),
+            actor_token_id: None,
+            details: Some(json!({ "title": "Test" })),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_filter_audit_events() {
+        let pool = setup_test_db().await;
+
+        let recorded = insert_audit_event(&pool, event("a1", AuditAction::MemoryCreate, "user-1"))
+            .await
+            .unwrap();
+        assert_eq!(recorded.action, "memory.create");
+        assert_eq!(recorded.resource_type, "memory");
+        assert_eq!(recorded.details_json().unwrap()["title"], "Test");
+
+        insert_audit_event(&pool, event("a2", AuditAction::MemoryDelete, "user-1"))
+            .await
+            .unwrap();
+        insert_audit_event(&pool, event("a3", AuditAction::TokenCreate, "user-2"))
+            .await
+            .unwrap();
+
+        let all = list_audit_events(&pool, &AuditFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        // Most recent first
+        assert_eq!(all[0].id, "a3");
+
+        let filter = AuditFilter {
+            actor_user_id: Some("user-1".to_string()),
+            resource_type: Some("memory".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(count_audit_events(&pool, &filter).await.unwrap(), 2);
+
+        let filter = AuditFilter {
+            action: Some("token.create".to_string()),
+            ..Default::default()
+        };
+        let tokens = list_audit_events(&pool, &filter).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].actor_user_id.as_deref(), Some("user-2"));
+    }
+
+    #[test]
+    fn test_audit_action_round_trip() {
+        for action in [
+            AuditAction::MemberAdd,
+            AuditAction::ProviderDelete,
+            AuditAction::McpToolDenied,
+        ] {
+            assert_eq!(AuditAction::from_str(action.as_str()), Some(action));
+        }
+        assert_eq!(AuditAction::from_str("memory.read"), None);
+    }
+}
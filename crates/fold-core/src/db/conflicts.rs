@@ -0,0 +1,318 @@
+//! Memory conflict database queries.
+//!
+//! A conflict records that a new memory contradicts an existing decision.
+//! Conflicts stay open until a human resolves or dismisses them.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Conflict status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+impl ConflictStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Resolved => "resolved",
+            Self::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(Self::Open),
+            "resolved" => Some(Self::Resolved),
+            "dismissed" => Some(Self::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// Memory conflict record.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MemoryConflict {
+    pub id: String,
+    pub project_id: String,
+    /// The newer memory
+    pub memory_id: String,
+    /// The existing decision it contradicts
+    pub conflicting_memory_id: String,
+    pub reason: Option<String>,
+    pub confidence: Option<f64>,
+    pub status: String,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+impl MemoryConflict {
+    /// Get the status as enum.
+    pub fn status_enum(&self) -> ConflictStatus {
+        ConflictStatus::from_str(&self.status).unwrap_or(ConflictStatus::Open)
+    }
+}
+
+/// Input for recording a conflict.
+#[derive(Debug, Clone)]
+pub struct CreateMemoryConflict {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub conflicting_memory_id: String,
+    pub reason: Option<String>,
+    pub confidence: Option<f64>,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Record a conflict. Re-detecting an open pair updates its reason; a pair a
+/// human already resolved or dismissed is left alone and `None` is returned.
+pub async fn create_memory_conflict(
+    pool: &DbPool,
+    input: CreateMemoryConflict,
+) -> Result<Option<MemoryConflict>> {
+    sqlx::query_as::<_, MemoryConflict>(
+        r#"
+        INSERT INTO memory_conflicts (
+            id, project_id, memory_id, conflicting_memory_id, reason, confidence
+        )
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(memory_id, conflicting_memory_id) DO UPDATE SET
+            reason = excluded.reason,
+            confidence = excluded.confidence
+        WHERE memory_conflicts.status = 'open'
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.memory_id)
+    .bind(&input.conflicting_memory_id)
+    .bind(&input.reason)
+    .bind(input.confidence)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get a conflict by ID.
+pub async fn get_memory_conflict(pool: &DbPool, id: &str) -> Result<MemoryConflict> {
+    sqlx::query_as::<_, MemoryConflict>("SELECT * FROM memory_conflicts WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Conflict not found: {}", id)))
+}
+
+/// List conflicts for a project, optionally filtered by status. Newest first.
+pub async fn list_project_conflicts(
+    pool: &DbPool,
+    project_id: &str,
+    status: Option<ConflictStatus>,
+) -> Result<Vec<MemoryConflict>> {
+    sqlx::query_as::<_, MemoryConflict>(
+        r#"
+        SELECT * FROM memory_conflicts
+        WHERE project_id = ? AND (? IS NULL OR status = ?)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(status.map(|s| s.as_str()))
+    .bind(status.map(|s| s.as_str()))
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Count open conflicts for a project.
+pub async fn count_open_conflicts(pool: &DbPool, project_id: &str) -> Result<i64> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM memory_conflicts WHERE project_id = ? AND status = 'open'",
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count)
+}
+
+/// Close a conflict as resolved or dismissed.
+pub async fn resolve_memory_conflict(
+    pool: &DbPool,
+    id: &str,
+    status: ConflictStatus,
+    resolution: Option<&str>,
+    resolved_by: Option<&str>,
+) -> Result<MemoryConflict> {
+    if status == ConflictStatus::Open {
+        return Err(Error::Validation(
+            "Resolution status must be 'resolved' or 'dismissed'".into(),
+        ));
+    }
+
+    sqlx::query_as::<_, MemoryConflict>(
+        r#"
+        UPDATE memory_conflicts SET
+            status = ?,
+            resolution = ?,
+            resolved_by = ?,
+            resolved_at = datetime('now')
+        WHERE id = ?
+        RETURNING *
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(resolution)
+    .bind(resolved_by)
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Conflict not found: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        create_memory, create_project, init_pool, migrate, CreateMemory, CreateProject, MemoryType,
+    };
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for id in ["mem-old", "mem-new"] {
+            create_memory(
+                &pool,
+                CreateMemory {
+                    id: id.to_string(),
+                    project_id: "proj-1".to_string(),
+                    memory_type: MemoryType::Decision,
+                    source: None,
+                    title: Some(id.to_string()),
+                    content: None,
+                    content_hash: None,
+                    content_storage: "filesystem".to_string(),
+                    file_path: None,
+                    language: None,
+                    git_branch: None,
+                    git_commit_sha: None,
+                    author: None,
+                    keywords: None,
+                    tags: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_conflict_lifecycle() {
+        let pool = setup_test_db().await;
+
+        let conflict = create_memory_conflict(
+            &pool,
+            CreateMemoryConflict {
+                id: "c-1".to_string(),
+                project_id: "proj-1".to_string(),
+                memory_id: "mem-new".to_string(),
+                conflicting_memory_id: "mem-old".to_string(),
+                reason: Some("Uses MySQL instead of Postgres".to_string()),
+                confidence: Some(0.9),
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(conflict.status_enum(), ConflictStatus::Open);
+        assert_eq!(count_open_conflicts(&pool, "proj-1").await.unwrap(), 1);
+
+        let resolved = resolve_memory_conflict(
+            &pool,
+            "c-1",
+            ConflictStatus::Resolved,
+            Some("Postgres decision superseded"),
+            Some("user-1"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resolved.status_enum(), ConflictStatus::Resolved);
+        assert!(resolved.resolved_at.is_some());
+
+        let open = list_project_conflicts(&pool, "proj-1", Some(ConflictStatus::Open))
+            .await
+            .unwrap();
+        assert!(open.is_empty());
+        assert_eq!(
+            list_project_conflicts(&pool, "proj-1", None)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Detecting the same pair again leaves the human's resolution alone
+        let redetected = create_memory_conflict(
+            &pool,
+            CreateMemoryConflict {
+                id: "c-2".to_string(),
+                project_id: "proj-1".to_string(),
+                memory_id: "mem-new".to_string(),
+                conflicting_memory_id: "mem-old".to_string(),
+                reason: None,
+                confidence: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(redetected.is_none());
+        let kept = get_memory_conflict(&pool, "c-1").await.unwrap();
+        assert_eq!(kept.status_enum(), ConflictStatus::Resolved);
+        assert_eq!(
+            kept.resolution.as_deref(),
+            Some("Postgres decision superseded")
+        );
+        assert!(
+            resolve_memory_conflict(&pool, "c-1", ConflictStatus::Open, None, None)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,649 @@
+//! Memory consolidation database queries.
+//!
+//! Per-project consolidation settings, merge proposals, and the record of
+//! which memories were folded into which survivor.
+
+use crate::models::Memory;
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Default cosine similarity above which memories are clustered.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.92;
+
+/// Per-project consolidation settings.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ConsolidationSettings {
+    pub project_id: String,
+    pub enabled: i32,
+    pub similarity_threshold: f64,
+    pub auto_apply: i32,
+    pub max_cluster_size: i64,
+    pub interval_hours: i64,
+    pub last_run_at: Option<String>,
+    pub updated_at: String,
+}
+
+impl ConsolidationSettings {
+    /// Settings used when a project has never been configured.
+    pub fn defaults(project_id: &str) -> Self {
+        Self {
+            project_id: project_id.to_string(),
+            enabled: 0,
+            similarity_threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            auto_apply: 0,
+            max_cluster_size: 5,
+            interval_hours: 24,
+            last_run_at: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Check if periodic consolidation is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    /// Check if merges are applied without review.
+    pub fn is_auto_apply(&self) -> bool {
+        self.auto_apply != 0
+    }
+}
+
+/// Input for updating consolidation settings. `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateConsolidationSettings {
+    pub enabled: Option<bool>,
+    pub similarity_threshold: Option<f64>,
+    pub auto_apply: Option<bool>,
+    pub max_cluster_size: Option<i64>,
+    pub interval_hours: Option<i64>,
+}
+
+/// Merge status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStatus {
+    Proposed,
+    Applied,
+    Rejected,
+}
+
+impl MergeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Proposed => "proposed",
+            Self::Applied => "applied",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "proposed" => Some(Self::Proposed),
+            "applied" => Some(Self::Applied),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// A proposed or applied merge of near-duplicate memories.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MemoryMerge {
+    pub id: String,
+    pub project_id: String,
+    pub job_id: Option<String>,
+    pub survivor_id: String,
+    /// JSON array of memory IDs folded into the survivor
+    pub source_ids: String,
+    pub similarity: Option<f64>,
+    pub title: Option<String>,
+    pub content: String,
+    pub status: String,
+    pub created_at: String,
+    pub decided_at: Option<String>,
+}
+
+impl MemoryMerge {
+    /// Parse source IDs from JSON.
+    pub fn source_ids_vec(&self) -> Vec<String> {
+        serde_json::from_str(&self.source_ids).unwrap_or_default()
+    }
+
+    /// Get the status as enum.
+    pub fn status_enum(&self) -> MergeStatus {
+        MergeStatus::from_str(&self.status).unwrap_or(MergeStatus::Proposed)
+    }
+}
+
+/// Input for recording a merge.
+#[derive(Debug, Clone)]
+pub struct CreateMemoryMerge {
+    pub id: String,
+    pub project_id: String,
+    pub job_id: Option<String>,
+    pub survivor_id: String,
+    pub source_ids: Vec<String>,
+    pub similarity: Option<f64>,
+    pub title: Option<String>,
+    pub content: String,
+}
+
+/// New values for a merge's survivor, written when the merge is applied.
+#[derive(Debug, Clone)]
+pub struct MergedSurvivor<'a> {
+    /// The survivor as it was, kept as a revision.
+    pub previous: &'a Memory,
+    /// Its content as it was (from fold/ for agent memories).
+    pub previous_content: &'a str,
+    pub title: Option<String>,
+    /// Stored in SQLite only for file and git memories.
+    pub content: Option<String>,
+    pub content_hash: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Settings Queries
+// ============================================================================
+
+/// Get consolidation settings for a project, falling back to defaults.
+pub async fn get_consolidation_settings(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<ConsolidationSettings> {
+    let settings = sqlx::query_as::<_, ConsolidationSettings>(
+        "SELECT * FROM consolidation_settings WHERE project_id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(settings.unwrap_or_else(|| ConsolidationSettings::defaults(project_id)))
+}
+
+/// Update consolidation settings, creating the row if needed.
+pub async fn update_consolidation_settings(
+    pool: &DbPool,
+    project_id: &str,
+    input: UpdateConsolidationSettings,
+) -> Result<ConsolidationSettings> {
+    sqlx::query_as::<_, ConsolidationSettings>(
+        r#"
+        INSERT INTO consolidation_settings (
+            project_id, enabled, similarity_threshold, auto_apply,
+            max_cluster_size, interval_hours
+        )
+        VALUES (?, COALESCE(?, 0), COALESCE(?, 0.92), COALESCE(?, 0), COALESCE(?, 5), COALESCE(?, 24))
+        ON CONFLICT(project_id) DO UPDATE SET
+            enabled = COALESCE(?, enabled),
+            similarity_threshold = COALESCE(?, similarity_threshold),
+            auto_apply = COALESCE(?, auto_apply),
+            max_cluster_size = COALESCE(?, max_cluster_size),
+            interval_hours = COALESCE(?, interval_hours),
+            updated_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(project_id)
+    .bind(input.enabled)
+    .bind(input.similarity_threshold)
+    .bind(input.auto_apply)
+    .bind(input.max_cluster_size)
+    .bind(input.interval_hours)
+    .bind(input.enabled)
+    .bind(input.similarity_threshold)
+    .bind(input.auto_apply)
+    .bind(input.max_cluster_size)
+    .bind(input.interval_hours)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List projects with consolidation enabled whose interval has elapsed.
+pub async fn list_due_consolidations(pool: &DbPool) -> Result<Vec<ConsolidationSettings>> {
+    sqlx::query_as::<_, ConsolidationSettings>(
+        r#"
+        SELECT * FROM consolidation_settings
+        WHERE enabled = 1
+          AND (last_run_at IS NULL
+               OR last_run_at <= datetime('now', '-' || interval_hours || ' hours'))
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Record that consolidation ran (or was scheduled) for a project.
+pub async fn mark_consolidation_run(pool: &DbPool, project_id: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO consolidation_settings (project_id, last_run_at)
+        VALUES (?, datetime('now'))
+        ON CONFLICT(project_id) DO UPDATE SET last_run_at = datetime('now')
+        "#,
+    )
+    .bind(project_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ============================================================================
+// Merge Queries
+// ============================================================================
+
+/// Record a merge proposal.
+pub async fn create_memory_merge(pool: &DbPool, input: CreateMemoryMerge) -> Result<MemoryMerge> {
+    let source_ids = serde_json::to_string(&input.source_ids)?;
+
+    sqlx::query_as::<_, MemoryMerge>(
+        r#"
+        INSERT INTO memory_merges (
+            id, project_id, job_id, survivor_id, source_ids, similarity, title, content
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.job_id)
+    .bind(&input.survivor_id)
+    .bind(&source_ids)
+    .bind(input.similarity)
+    .bind(&input.title)
+    .bind(&input.content)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get a merge by ID.
+pub async fn get_memory_merge(pool: &DbPool, id: &str) -> Result<MemoryMerge> {
+    sqlx::query_as::<_, MemoryMerge>("SELECT * FROM memory_merges WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Merge not found: {}", id)))
+}
+
+/// List merges for a project, optionally filtered by status. Newest first.
+pub async fn list_memory_merges(
+    pool: &DbPool,
+    project_id: &str,
+    status: Option<MergeStatus>,
+) -> Result<Vec<MemoryMerge>> {
+    sqlx::query_as::<_, MemoryMerge>(
+        r#"
+        SELECT * FROM memory_merges
+        WHERE project_id = ? AND (? IS NULL OR status = ?)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(status.map(|s| s.as_str()))
+    .bind(status.map(|s| s.as_str()))
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Move a proposed merge to applied or rejected.
+///
+/// Only proposed merges can be decided, so applying the same merge twice fails.
+pub async fn decide_memory_merge(
+    pool: &DbPool,
+    id: &str,
+    status: MergeStatus,
+) -> Result<MemoryMerge> {
+    sqlx::query_as::<_, MemoryMerge>(
+        r#"
+        UPDATE memory_merges SET status = ?, decided_at = datetime('now')
+        WHERE id = ? AND status = 'proposed'
+        RETURNING *
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::Validation(format!("Merge {} is not awaiting a decision", id)))
+}
+
+/// Fold memories into a merge's survivor and mark the merge applied.
+///
+/// The merge is claimed (only a proposed merge can be applied), the
+/// survivor is rewritten with its replaced version kept as a revision, links
+/// on the merged memories move to the survivor (duplicates of existing
+/// survivor links are dropped) and the merged memories are recorded, all in
+/// one transaction.
+pub async fn apply_memory_merge(
+    pool: &DbPool,
+    merge: &MemoryMerge,
+    merged_ids: &[String],
+    survivor: &MergedSurvivor<'_>,
+) -> Result<MemoryMerge> {
+    let survivor_id = &merge.survivor_id;
+    let mut tx = pool.begin().await?;
+
+    let applied = sqlx::query_as::<_, MemoryMerge>(
+        r#"
+        UPDATE memory_merges SET status = 'applied', decided_at = datetime('now')
+        WHERE id = ? AND status = 'proposed'
+        RETURNING *
+        "#,
+    )
+    .bind(&merge.id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Validation(format!("Merge {} is not awaiting a decision", merge.id)))?;
+
+    let already_merged: Option<(String,)> =
+        sqlx::query_as("SELECT merged_into FROM merged_memories WHERE memory_id = ?")
+            .bind(survivor_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if already_merged.is_some() {
+        return Err(Error::Validation(format!(
+            "Survivor {} has already been merged into another memory",
+            survivor_id
+        )));
+    }
+
+    super::revisions::insert_revision(
+        &mut tx,
+        survivor.previous,
+        Some(survivor.previous_content),
+        None,
+    )
+    .await?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE memories SET title = ?, content = ?, content_hash = ?, updated_at = ?
+        WHERE id = ? AND project_id = ?
+        "#,
+    )
+    .bind(&survivor.title)
+    .bind(&survivor.content)
+    .bind(&survivor.content_hash)
+    .bind(survivor.updated_at)
+    .bind(survivor_id)
+    .bind(&merge.project_id)
+    .execute(&mut *tx)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Memory {}", survivor_id)));
+    }
+
+    for merged_id in merged_ids.iter().filter(|id| *id != survivor_id) {
+        sqlx::query(
+            r#"
+            UPDATE OR IGNORE memory_links SET source_id = ?
+            WHERE source_id = ? AND project_id = ?
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(merged_id)
+        .bind(&merge.project_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE OR IGNORE memory_links SET target_id = ?
+            WHERE target_id = ? AND project_id = ?
+            "#,
+        )
+        .bind(survivor_id)
+        .bind(merged_id)
+        .bind(&merge.project_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM memory_links WHERE source_id = ? OR target_id = ?")
+            .bind(merged_id)
+            .bind(merged_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO merged_memories (memory_id, merged_into, merge_id)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(merged_id)
+        .bind(survivor_id)
+        .bind(&merge.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Links between cluster members become self-links on the survivor
+    sqlx::query("DELETE FROM memory_links WHERE source_id = ? AND target_id = ?")
+        .bind(survivor_id)
+        .bind(survivor_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(applied)
+}
+
+/// Get the survivor a memory was merged into, if any.
+pub async fn get_merged_into(pool: &DbPool, memory_id: &str) -> Result<Option<String>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT merged_into FROM merged_memories WHERE memory_id = ?")
+            .bind(memory_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.map(|(id,)| id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        create_memory, create_project, init_pool, migrate, CreateMemory, CreateProject, MemoryType,
+    };
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for id in ["mem-a", "mem-b"] {
+            create_memory(
+                &pool,
+                CreateMemory {
+                    id: id.to_string(),
+                    project_id: "proj-1".to_string(),
+                    memory_type: MemoryType::General,
+                    source: None,
+                    title: Some(id.to_string()),
+                    content: None,
+                    content_hash: None,
+                    content_storage: "filesystem".to_string(),
+                    file_path: None,
+                    language: None,
+                    git_branch: None,
+                    git_commit_sha: None,
+                    author: None,
+                    keywords: None,
+                    tags: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_settings_defaults_and_update() {
+        let pool = setup_test_db().await;
+
+        let settings = get_consolidation_settings(&pool, "proj-1").await.unwrap();
+        assert!(!settings.is_enabled());
+        assert_eq!(settings.similarity_threshold, DEFAULT_SIMILARITY_THRESHOLD);
+        assert!(list_due_consolidations(&pool).await.unwrap().is_empty());
+
+        let updated = update_consolidation_settings(
+            &pool,
+            "proj-1",
+            UpdateConsolidationSettings {
+                enabled: Some(true),
+                similarity_threshold: Some(0.88),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(updated.is_enabled());
+        assert_eq!(updated.similarity_threshold, 0.88);
+        assert_eq!(updated.max_cluster_size, 5);
+        assert_eq!(list_due_consolidations(&pool).await.unwrap().len(), 1);
+
+        mark_consolidation_run(&pool, "proj-1").await.unwrap();
+        assert!(list_due_consolidations(&pool).await.unwrap().is_empty());
+
+        // Partial update keeps earlier values
+        let updated = update_consolidation_settings(
+            &pool,
+            "proj-1",
+            UpdateConsolidationSettings {
+                auto_apply: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(updated.is_auto_apply());
+        assert_eq!(updated.similarity_threshold, 0.88);
+    }
+
+    #[tokio::test]
+    async fn test_merge_lifecycle() {
+        let pool = setup_test_db().await;
+
+        let merge = create_memory_merge(
+            &pool,
+            CreateMemoryMerge {
+                id: "merge-1".to_string(),
+                project_id: "proj-1".to_string(),
+                job_id: None,
+                survivor_id: "mem-a".to_string(),
+                source_ids: vec!["mem-b".to_string()],
+                similarity: Some(0.95),
+                title: Some("Merged".to_string()),
+                content: "Merged content".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(merge.status_enum(), MergeStatus::Proposed);
+        assert_eq!(merge.source_ids_vec(), vec!["mem-b"]);
+
+        sqlx::query(
+            r#"
+            INSERT INTO memory_links (id, project_id, source_id, target_id, link_type, created_by)
+            VALUES ('link-1', 'proj-1', 'mem-b', 'mem-a', 'related', 'user')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let previous = sqlx::query_as::<_, Memory>("SELECT * FROM memories WHERE id = 'mem-a'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let survivor = MergedSurvivor {
+            previous: &previous,
+            previous_content: "Original content",
+            title: Some("Merged".to_string()),
+            content: None,
+            content_hash: "hash".to_string(),
+            updated_at: Utc::now(),
+        };
+
+        let applied = apply_memory_merge(&pool, &merge, &["mem-b".to_string()], &survivor)
+            .await
+            .unwrap();
+        assert_eq!(applied.status_enum(), MergeStatus::Applied);
+        assert!(decide_memory_merge(&pool, "merge-1", MergeStatus::Rejected)
+            .await
+            .is_err());
+
+        // A second apply loses the race and leaves the survivor alone
+        let again = MergedSurvivor {
+            title: Some("Applied twice".to_string()),
+            ..survivor.clone()
+        };
+        assert!(
+            apply_memory_merge(&pool, &merge, &["mem-b".to_string()], &again)
+                .await
+                .is_err()
+        );
+
+        let (title, revisions): (Option<String>, i64) = sqlx::query_as(
+            r#"
+            SELECT title, (SELECT COUNT(*) FROM memory_revisions WHERE memory_id = 'mem-a')
+            FROM memories WHERE id = 'mem-a'
+            "#,
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(title.as_deref(), Some("Merged"));
+        assert_eq!(revisions, 1);
+
+        assert_eq!(
+            get_merged_into(&pool, "mem-b").await.unwrap().as_deref(),
+            Some("mem-a")
+        );
+        assert_eq!(get_merged_into(&pool, "mem-a").await.unwrap(), None);
+
+        // The link between the two became a self-link and was dropped
+        let (links,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM memory_links")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(links, 0);
+
+        let proposed = list_memory_merges(&pool, "proj-1", Some(MergeStatus::Proposed))
+            .await
+            .unwrap();
+        assert!(proposed.is_empty());
+    }
+}
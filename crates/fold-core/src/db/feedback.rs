@@ -0,0 +1,398 @@
+//! Memory feedback database queries.
+//!
+//! Explicit verdicts on search results: a result was used, was irrelevant to
+//! the query, or is wrong. Each giver has one verdict per memory and query;
+//! giving feedback again replaces it.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{Error, Result};
+
+use super::{query_hash, DbPool};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Unresolved "wrong" verdicts at which a memory is flagged for review.
+pub const WRONG_FLAG_THRESHOLD: i64 = 3;
+
+/// Verdict on a search result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedbackVerdict {
+    /// The result helped with the task
+    Used,
+    /// The result did not relate to the query
+    Irrelevant,
+    /// The result is incorrect or out of date
+    Wrong,
+}
+
+impl FeedbackVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Used => "used",
+            Self::Irrelevant => "irrelevant",
+            Self::Wrong => "wrong",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "used" => Some(Self::Used),
+            "irrelevant" => Some(Self::Irrelevant),
+            "wrong" => Some(Self::Wrong),
+            _ => None,
+        }
+    }
+}
+
+/// Stored feedback row.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MemoryFeedback {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub query: String,
+    pub query_hash: String,
+    pub verdict: String,
+    pub comment: Option<String>,
+    pub given_by: String,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Input for recording feedback.
+#[derive(Debug, Clone)]
+pub struct RecordFeedback {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub query: String,
+    pub verdict: FeedbackVerdict,
+    pub comment: Option<String>,
+    pub given_by: String,
+}
+
+/// Feedback totals for one memory. Resolved "wrong" verdicts are not counted.
+#[derive(Debug, Clone, Default, FromRow, Serialize)]
+pub struct FeedbackCounts {
+    pub used: i64,
+    pub irrelevant: i64,
+    pub wrong: i64,
+    /// When the memory was last marked used
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct FeedbackCountsRow {
+    memory_id: String,
+    #[sqlx(flatten)]
+    counts: FeedbackCounts,
+}
+
+/// A memory flagged for review by repeated "wrong" verdicts.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct FlaggedMemory {
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub memory_type: Option<String>,
+    pub wrong_count: i64,
+    pub last_flagged_at: String,
+}
+
+const COUNTS_COLUMNS: &str = r#"
+    memory_id,
+    SUM(verdict = 'used') AS used,
+    SUM(verdict = 'irrelevant') AS irrelevant,
+    SUM(verdict = 'wrong' AND resolved_at IS NULL) AS wrong,
+    MAX(CASE WHEN verdict = 'used' THEN created_at END) AS last_used
+"#;
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Record a verdict, replacing the giver's earlier verdict for the same
+/// memory and query.
+pub async fn record_feedback(pool: &DbPool, input: RecordFeedback) -> Result<MemoryFeedback> {
+    sqlx::query_as::<_, MemoryFeedback>(
+        r#"
+        INSERT INTO memory_feedback (
+            id, project_id, memory_id, query, query_hash, verdict, comment, given_by
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(memory_id, query_hash, given_by) DO UPDATE SET
+            verdict = excluded.verdict,
+            comment = excluded.comment,
+            created_at = datetime('now'),
+            resolved_at = NULL
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.memory_id)
+    .bind(&input.query)
+    .bind(query_hash(&input.query))
+    .bind(input.verdict.as_str())
+    .bind(&input.comment)
+    .bind(&input.given_by)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get feedback totals for the given memories. Memories without feedback
+/// are missing from the map.
+pub async fn get_feedback_counts(
+    pool: &DbPool,
+    memory_ids: &[String],
+) -> Result<HashMap<String, FeedbackCounts>> {
+    if memory_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders: Vec<&str> = memory_ids.iter().map(|_| "?").collect();
+    let query = format!(
+        "SELECT {} FROM memory_feedback WHERE memory_id IN ({}) GROUP BY memory_id",
+        COUNTS_COLUMNS,
+        placeholders.join(", ")
+    );
+
+    let mut q = sqlx::query_as::<_, FeedbackCountsRow>(&query);
+    for id in memory_ids {
+        q = q.bind(id);
+    }
+
+    Ok(q.fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.memory_id, row.counts))
+        .collect())
+}
+
+/// Get feedback totals for every memory in a project that has feedback.
+pub async fn get_project_feedback_counts(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<HashMap<String, FeedbackCounts>> {
+    let query = format!(
+        "SELECT {} FROM memory_feedback WHERE project_id = ? GROUP BY memory_id",
+        COUNTS_COLUMNS
+    );
+
+    Ok(sqlx::query_as::<_, FeedbackCountsRow>(&query)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.memory_id, row.counts))
+        .collect())
+}
+
+/// List memories with at least `threshold` unresolved "wrong" verdicts,
+/// most flagged first.
+pub async fn list_flagged_memories(
+    pool: &DbPool,
+    project_id: &str,
+    threshold: i64,
+) -> Result<Vec<FlaggedMemory>> {
+    sqlx::query_as::<_, FlaggedMemory>(
+        r#"
+        SELECT f.memory_id, m.title, m.type AS memory_type,
+               COUNT(*) AS wrong_count,
+               MAX(f.created_at) AS last_flagged_at
+        FROM memory_feedback f
+        JOIN memories m ON m.id = f.memory_id
+        WHERE f.project_id = ? AND f.verdict = 'wrong' AND f.resolved_at IS NULL
+        GROUP BY f.memory_id
+        HAVING COUNT(*) >= ?
+        ORDER BY wrong_count DESC, last_flagged_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(threshold)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List the unresolved "wrong" verdicts on a memory, newest first.
+pub async fn list_wrong_feedback(pool: &DbPool, memory_id: &str) -> Result<Vec<MemoryFeedback>> {
+    sqlx::query_as::<_, MemoryFeedback>(
+        r#"
+        SELECT * FROM memory_feedback
+        WHERE memory_id = ? AND verdict = 'wrong' AND resolved_at IS NULL
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(memory_id)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Mark a memory's "wrong" verdicts as reviewed. Returns how many were
+/// resolved.
+pub async fn resolve_wrong_feedback(pool: &DbPool, memory_id: &str) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE memory_feedback SET resolved_at = datetime('now')
+        WHERE memory_id = ? AND verdict = 'wrong' AND resolved_at IS NULL
+        "#,
+    )
+    .bind(memory_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for id in ["mem-1", "mem-2"] {
+            sqlx::query(
+                "INSERT INTO memories (id, project_id, type, title) VALUES (?, 'proj-1', 'general', ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        pool
+    }
+
+    async fn give(pool: &DbPool, memory_id: &str, query: &str, by: &str, verdict: FeedbackVerdict) {
+        record_feedback(
+            pool,
+            RecordFeedback {
+                id: crate::models::new_id(),
+                project_id: "proj-1".to_string(),
+                memory_id: memory_id.to_string(),
+                query: query.to_string(),
+                verdict,
+                comment: None,
+                given_by: by.to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_feedback_replaces_and_counts() {
+        let pool = setup_test_db().await;
+
+        give(
+            &pool,
+            "mem-1",
+            "retry policy",
+            "tok-1",
+            FeedbackVerdict::Irrelevant,
+        )
+        .await;
+        // Same giver and query (modulo case) replaces the verdict
+        give(
+            &pool,
+            "mem-1",
+            "Retry Policy",
+            "tok-1",
+            FeedbackVerdict::Used,
+        )
+        .await;
+        give(
+            &pool,
+            "mem-1",
+            "retry policy",
+            "tok-2",
+            FeedbackVerdict::Used,
+        )
+        .await;
+        give(
+            &pool,
+            "mem-2",
+            "retry policy",
+            "tok-1",
+            FeedbackVerdict::Irrelevant,
+        )
+        .await;
+
+        let counts = get_feedback_counts(&pool, &["mem-1".to_string(), "mem-2".to_string()])
+            .await
+            .unwrap();
+        let mem1 = &counts["mem-1"];
+        assert_eq!((mem1.used, mem1.irrelevant, mem1.wrong), (2, 0, 0));
+        assert!(mem1.last_used.is_some());
+        assert_eq!(counts["mem-2"].irrelevant, 1);
+        assert!(counts["mem-2"].last_used.is_none());
+
+        let project = get_project_feedback_counts(&pool, "proj-1").await.unwrap();
+        assert_eq!(project.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_flagging_and_resolution() {
+        let pool = setup_test_db().await;
+
+        for (query, by) in [("a", "tok-1"), ("b", "tok-1"), ("a", "tok-2")] {
+            give(&pool, "mem-1", query, by, FeedbackVerdict::Wrong).await;
+        }
+        give(&pool, "mem-2", "a", "tok-1", FeedbackVerdict::Wrong).await;
+
+        let flagged = list_flagged_memories(&pool, "proj-1", WRONG_FLAG_THRESHOLD)
+            .await
+            .unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].memory_id, "mem-1");
+        assert_eq!(flagged[0].wrong_count, 3);
+
+        assert_eq!(resolve_wrong_feedback(&pool, "mem-1").await.unwrap(), 3);
+        assert!(list_flagged_memories(&pool, "proj-1", WRONG_FLAG_THRESHOLD)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(list_wrong_feedback(&pool, "mem-1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let counts = get_feedback_counts(&pool, &["mem-1".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(counts["mem-1"].wrong, 0);
+    }
+}
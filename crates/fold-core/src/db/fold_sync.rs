@@ -0,0 +1,416 @@
+//! Fold sync database queries.
+//!
+//! Tracks the last version of each fold/ memory file applied from a git
+//! push, and records pushed edits that were held back because the memory
+//! also changed in Fold since that sync. Also indexes where readable-layout
+//! memory files live, since their paths cannot be derived from the ID.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{ConflictStatus, DbPool};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Last applied version of a memory file.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FoldSyncState {
+    pub memory_id: String,
+    pub project_id: String,
+    pub content_hash: String,
+    /// JSON array of related memory IDs
+    pub related_to: Option<String>,
+    pub commit_sha: Option<String>,
+    pub synced_at: String,
+}
+
+impl FoldSyncState {
+    /// Get the related memory IDs as a vector.
+    pub fn related_to_vec(&self) -> Vec<String> {
+        self.related_to
+            .as_ref()
+            .and_then(|r| serde_json::from_str(r).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Input for recording a synced memory file.
+#[derive(Debug, Clone)]
+pub struct UpsertFoldSyncState {
+    pub memory_id: String,
+    pub project_id: String,
+    pub content_hash: String,
+    pub related_to: Vec<String>,
+    pub commit_sha: Option<String>,
+}
+
+/// A pushed edit that was not applied.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FoldSyncConflict {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub file_path: String,
+    /// 'modified' | 'deleted'
+    pub change_type: String,
+    pub commit_sha: Option<String>,
+    /// The pushed file (None for deletions)
+    pub file_content: Option<String>,
+    pub status: String,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Input for recording a fold sync conflict.
+#[derive(Debug, Clone)]
+pub struct CreateFoldSyncConflict {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub file_path: String,
+    pub change_type: String,
+    pub commit_sha: Option<String>,
+    pub file_content: Option<String>,
+}
+
+// ============================================================================
+// Sync State Queries
+// ============================================================================
+
+/// Get the sync state for a memory.
+pub async fn get_fold_sync_state(pool: &DbPool, memory_id: &str) -> Result<Option<FoldSyncState>> {
+    sqlx::query_as::<_, FoldSyncState>("SELECT * FROM fold_sync_state WHERE memory_id = ?")
+        .bind(memory_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(Error::Database)
+}
+
+/// Record that a memory file version has been applied.
+pub async fn upsert_fold_sync_state(
+    pool: &DbPool,
+    input: UpsertFoldSyncState,
+) -> Result<FoldSyncState> {
+    let related_json = serde_json::to_string(&input.related_to)?;
+
+    sqlx::query_as::<_, FoldSyncState>(
+        r#"
+        INSERT INTO fold_sync_state (memory_id, project_id, content_hash, related_to, commit_sha)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(memory_id) DO UPDATE SET
+            content_hash = excluded.content_hash,
+            related_to = excluded.related_to,
+            commit_sha = excluded.commit_sha,
+            synced_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(&input.memory_id)
+    .bind(&input.project_id)
+    .bind(&input.content_hash)
+    .bind(&related_json)
+    .bind(&input.commit_sha)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Forget the sync state for a memory.
+pub async fn delete_fold_sync_state(pool: &DbPool, memory_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM fold_sync_state WHERE memory_id = ?")
+        .bind(memory_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ============================================================================
+// File Path Queries
+// ============================================================================
+
+/// Get the recorded file of a memory, relative to the project root.
+pub async fn get_memory_file_path(
+    pool: &DbPool,
+    project_root: &str,
+    memory_id: &str,
+) -> Result<Option<String>> {
+    sqlx::query_scalar(
+        "SELECT path FROM memory_file_paths WHERE project_root = ? AND memory_id = ?",
+    )
+    .bind(project_root)
+    .bind(memory_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get the memory recorded at a file path relative to the project root.
+pub async fn get_memory_id_at_path(
+    pool: &DbPool,
+    project_root: &str,
+    path: &str,
+) -> Result<Option<String>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT memory_id FROM memory_file_paths
+        WHERE project_root = ? AND path = ?
+        ORDER BY updated_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(project_root)
+    .bind(path)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Record memory file paths, given as (memory ID, relative path) pairs.
+pub async fn upsert_memory_file_paths(
+    pool: &DbPool,
+    project_root: &str,
+    files: &[(String, String)],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for (memory_id, path) in files {
+        sqlx::query(
+            r#"
+            INSERT INTO memory_file_paths (project_root, memory_id, path)
+            VALUES (?, ?, ?)
+            ON CONFLICT(project_root, memory_id) DO UPDATE SET
+                path = excluded.path,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(project_root)
+        .bind(memory_id)
+        .bind(path)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Forget the recorded file of a memory.
+pub async fn delete_memory_file_path(
+    pool: &DbPool,
+    project_root: &str,
+    memory_id: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM memory_file_paths WHERE project_root = ? AND memory_id = ?")
+        .bind(project_root)
+        .bind(memory_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ============================================================================
+// Conflict Queries
+// ============================================================================
+
+/// Record a fold sync conflict.
+pub async fn create_fold_sync_conflict(
+    pool: &DbPool,
+    input: CreateFoldSyncConflict,
+) -> Result<FoldSyncConflict> {
+    sqlx::query_as::<_, FoldSyncConflict>(
+        r#"
+        INSERT INTO fold_sync_conflicts (
+            id, project_id, memory_id, file_path, change_type, commit_sha, file_content
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.memory_id)
+    .bind(&input.file_path)
+    .bind(&input.change_type)
+    .bind(&input.commit_sha)
+    .bind(&input.file_content)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get a fold sync conflict by ID.
+pub async fn get_fold_sync_conflict(pool: &DbPool, id: &str) -> Result<FoldSyncConflict> {
+    sqlx::query_as::<_, FoldSyncConflict>("SELECT * FROM fold_sync_conflicts WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Fold sync conflict not found: {}", id)))
+}
+
+/// List fold sync conflicts for a project, optionally filtered by status. Newest first.
+pub async fn list_fold_sync_conflicts(
+    pool: &DbPool,
+    project_id: &str,
+    status: Option<ConflictStatus>,
+) -> Result<Vec<FoldSyncConflict>> {
+    sqlx::query_as::<_, FoldSyncConflict>(
+        r#"
+        SELECT * FROM fold_sync_conflicts
+        WHERE project_id = ? AND (? IS NULL OR status = ?)
+        ORDER BY created_at DESC, rowid DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(status.map(|s| s.as_str()))
+    .bind(status.map(|s| s.as_str()))
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Close a fold sync conflict as resolved or dismissed.
+pub async fn resolve_fold_sync_conflict(
+    pool: &DbPool,
+    id: &str,
+    status: ConflictStatus,
+    resolution: Option<&str>,
+    resolved_by: Option<&str>,
+) -> Result<FoldSyncConflict> {
+    if status == ConflictStatus::Open {
+        return Err(Error::Validation(
+            "Resolution status must be 'resolved' or 'dismissed'".into(),
+        ));
+    }
+
+    sqlx::query_as::<_, FoldSyncConflict>(
+        r#"
+        UPDATE fold_sync_conflicts SET
+            status = ?,
+            resolution = ?,
+            resolved_by = ?,
+            resolved_at = datetime('now')
+        WHERE id = ?
+        RETURNING *
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(resolution)
+    .bind(resolved_by)
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Fold sync conflict not found: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_sync_state_upsert() {
+        let pool = setup_test_db().await;
+        assert!(get_fold_sync_state(&pool, "mem-1").await.unwrap().is_none());
+
+        let state = UpsertFoldSyncState {
+            memory_id: "mem-1".to_string(),
+            project_id: "proj-1".to_string(),
+            content_hash: "abc".to_string(),
+            related_to: vec!["mem-2".to_string()],
+            commit_sha: Some("sha-1".to_string()),
+        };
+        upsert_fold_sync_state(&pool, state.clone()).await.unwrap();
+
+        let updated = upsert_fold_sync_state(
+            &pool,
+            UpsertFoldSyncState {
+                content_hash: "def".to_string(),
+                related_to: vec![],
+                ..state
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.content_hash, "def");
+        assert!(updated.related_to_vec().is_empty());
+
+        delete_fold_sync_state(&pool, "mem-1").await.unwrap();
+        assert!(get_fold_sync_state(&pool, "mem-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_conflict_lifecycle() {
+        let pool = setup_test_db().await;
+
+        let conflict = create_fold_sync_conflict(
+            &pool,
+            CreateFoldSyncConflict {
+                id: "c-1".to_string(),
+                project_id: "proj-1".to_string(),
+                memory_id: "mem-1".to_string(),
+                file_path: "fold/m/e/mem-1.md".to_string(),
+                change_type: "modified".to_string(),
+                commit_sha: Some("sha-1".to_string()),
+                file_content: Some("---\nid: mem-1\n---\n".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(conflict.status, "open");
+
+        let open = list_fold_sync_conflicts(&pool, "proj-1", Some(ConflictStatus::Open))
+            .await
+            .unwrap();
+        assert_eq!(open.len(), 1);
+
+        assert!(
+            resolve_fold_sync_conflict(&pool, "c-1", ConflictStatus::Open, None, None)
+                .await
+                .is_err()
+        );
+        let resolved = resolve_fold_sync_conflict(
+            &pool,
+            "c-1",
+            ConflictStatus::Dismissed,
+            Some("Kept the Fold copy"),
+            Some("user-1"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resolved.status, "dismissed");
+        assert!(
+            list_fold_sync_conflicts(&pool, "proj-1", Some(ConflictStatus::Open))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -160,6 +160,23 @@ pub async fn get_git_commit_by_sha(
     .map_err(Error::Database)
 }
 
+/// Find commits whose SHA starts with `prefix`. At most two are returned,
+/// enough to tell a unique prefix from an ambiguous one.
+pub async fn find_git_commits_by_prefix(
+    pool: &DbPool,
+    project_id: &str,
+    prefix: &str,
+) -> Result<Vec<GitCommit>> {
+    sqlx::query_as::<_, GitCommit>(
+        "SELECT * FROM git_commits WHERE project_id = ? AND sha LIKE ? || '%' LIMIT 2",
+    )
+    .bind(project_id)
+    .bind(prefix.to_lowercase())
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
 /// List commits for a project.
 pub async fn list_project_commits(
     pool: &DbPool,
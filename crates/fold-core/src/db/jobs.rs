@@ -36,6 +36,10 @@ pub enum JobType {
     SyncMetadata,
     ProcessWebhook,
     GenerateSummary,
+    ConsolidateMemories,
+    ApplyRetention,
+    EvaluateSearch,
+    KnowledgeGapReport,
     Custom,
 }
 
@@ -48,6 +52,10 @@ impl JobType {
             Self::SyncMetadata => "sync_metadata",
             Self::ProcessWebhook => "process_webhook",
             Self::GenerateSummary => "generate_summary",
+            Self::ConsolidateMemories => "consolidate_memories",
+            Self::ApplyRetention => "apply_retention",
+            Self::EvaluateSearch => "evaluate_search",
+            Self::KnowledgeGapReport => "knowledge_gap_report",
             Self::Custom => "custom",
         }
     }
@@ -60,6 +68,10 @@ impl JobType {
             "sync_metadata" => Some(Self::SyncMetadata),
             "process_webhook" => Some(Self::ProcessWebhook),
             "generate_summary" => Some(Self::GenerateSummary),
+            "consolidate_memories" => Some(Self::ConsolidateMemories),
+            "apply_retention" => Some(Self::ApplyRetention),
+            "evaluate_search" => Some(Self::EvaluateSearch),
+            "knowledge_gap_report" => Some(Self::KnowledgeGapReport),
             "custom" => Some(Self::Custom),
             _ => None,
         }
@@ -342,6 +354,8 @@ pub struct CreateJobLog {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct WebhookDelivery {
     pub id: String,
+    /// Outbound webhook registration this delivery belongs to
+    pub webhook_id: Option<String>,
     #[sqlx(rename = "type")]
     pub delivery_type: String,
     pub target_url: String,
@@ -358,6 +372,7 @@ pub struct WebhookDelivery {
 #[derive(Debug, Clone)]
 pub struct CreateWebhookDelivery {
     pub id: String,
+    pub webhook_id: Option<String>,
     pub delivery_type: String,
     pub target_url: String,
     pub payload: serde_json::Value,
@@ -1395,12 +1410,13 @@ pub async fn create_webhook_delivery(
 
     sqlx::query_as::<_, WebhookDelivery>(
         r#"
-        INSERT INTO webhook_deliveries (id, type, target_url, payload)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO webhook_deliveries (id, webhook_id, type, target_url, payload)
+        VALUES (?, ?, ?, ?, ?)
         RETURNING *
         "#,
     )
     .bind(&input.id)
+    .bind(&input.webhook_id)
     .bind(&input.delivery_type)
     .bind(&input.target_url)
     .bind(&payload_json)
@@ -1419,6 +1435,9 @@ pub async fn get_webhook_delivery(pool: &DbPool, id: &str) -> Result<WebhookDeli
 }
 
 /// Update webhook delivery after attempt.
+///
+/// A failed attempt with no `next_attempt_at` has used up its retries and
+/// is marked failed; otherwise it stays pending for the retry.
 pub async fn update_webhook_delivery_attempt(
     pool: &DbPool,
     id: &str,
@@ -1426,12 +1445,16 @@ pub async fn update_webhook_delivery_attempt(
     error: Option<&str>,
     next_attempt_at: Option<&str>,
 ) -> Result<WebhookDelivery> {
-    let _status = if success { "success" } else { "failed" };
+    let status = match (success, next_attempt_at) {
+        (true, _) => "success",
+        (false, Some(_)) => "pending",
+        (false, None) => "failed",
+    };
 
     sqlx::query_as::<_, WebhookDelivery>(
         r#"
         UPDATE webhook_deliveries SET
-            status = CASE WHEN ? THEN 'success' ELSE status END,
+            status = ?,
             attempts = attempts + 1,
             last_attempt_at = datetime('now'),
             next_attempt_at = ?,
@@ -1440,7 +1463,7 @@ pub async fn update_webhook_delivery_attempt(
         RETURNING *
         "#,
     )
-    .bind(success)
+    .bind(status)
     .bind(next_attempt_at)
     .bind(error)
     .bind(id)
@@ -27,6 +27,8 @@ pub enum LinkType {
     Related,
     Parent,
     Child,
+    Contradicts,
+    Refines,
     Custom(String),
 }
 
@@ -43,6 +45,8 @@ impl LinkType {
             Self::Related => "related",
             Self::Parent => "parent",
             Self::Child => "child",
+            Self::Contradicts => "contradicts",
+            Self::Refines => "refines",
             Self::Custom(s) => s,
         }
     }
@@ -59,6 +63,8 @@ impl LinkType {
             "related" => Self::Related,
             "parent" => Self::Parent,
             "child" => Self::Child,
+            "contradicts" => Self::Contradicts,
+            "refines" => Self::Refines,
             other => Self::Custom(other.to_string()),
         }
     }
@@ -413,6 +419,25 @@ pub async fn count_project_links(pool: &DbPool, project_id: &str) -> Result<i64>
     Ok(count)
 }
 
+/// List a project's link edges as (source, target) pairs, for ranking by
+/// link structure. Contradictions are left out: being contradicted is not
+/// an endorsement.
+pub async fn list_project_link_edges(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<Vec<(String, String)>> {
+    sqlx::query_as(
+        r#"
+        SELECT source_id, target_id FROM memory_links
+        WHERE project_id = ? AND link_type != 'contradicts'
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
 /// Traverse graph from a starting node (BFS).
 /// Returns all reachable memories up to max_depth.
 pub async fn traverse_graph(
@@ -643,6 +668,37 @@ pub async fn create_links_batch(pool: &DbPool, links: Vec<CreateLink>) -> Result
     Ok(results)
 }
 
+/// Insert a link exactly as given, keeping its creation time.
+/// Used when importing a project archive. Existing links are left alone.
+pub async fn insert_link_record(pool: &DbPool, link: &MemoryLink) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO memory_links (
+            id, project_id, source_id, target_id, link_type, created_by,
+            confidence, context, change_type, additions, deletions, created_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&link.id)
+    .bind(&link.project_id)
+    .bind(&link.source_id)
+    .bind(&link.target_id)
+    .bind(&link.link_type)
+    .bind(&link.created_by)
+    .bind(link.confidence)
+    .bind(&link.context)
+    .bind(&link.change_type)
+    .bind(link.additions)
+    .bind(link.deletions)
+    .bind(&link.created_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
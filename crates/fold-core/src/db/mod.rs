@@ -4,35 +4,67 @@
 //! for all domain entities.
 
 mod attachments;
+mod audit;
 mod chunks;
+mod conflicts;
+mod consolidation;
+mod feedback;
+mod fold_sync;
 mod git;
 mod groups;
 mod jobs;
 mod links;
 mod memories;
 mod pool;
+mod project_providers;
 mod projects;
 mod providers;
+mod retention;
+mod revisions;
+mod scoring;
+mod search_eval;
+mod search_log;
+mod search_settings;
+mod secrets;
 // mod repositories; // Removed: repository info now lives on projects
 mod sessions;
+mod transcripts;
+mod usage;
 mod users;
+mod webhooks;
 
 // Re-export Qdrant client (actual implementation in services)
 pub mod qdrant;
 
 // Re-export all query modules
 pub use attachments::*;
+pub use audit::*;
 pub use chunks::*;
+pub use conflicts::*;
+pub use consolidation::*;
+pub use feedback::*;
+pub use fold_sync::*;
 pub use git::*;
 pub use groups::*;
 pub use jobs::*;
 pub use links::*;
 pub use memories::*;
+pub use project_providers::*;
 pub use projects::*;
 pub use providers::*;
+pub use retention::*;
+pub use revisions::*;
+pub use scoring::*;
+pub use search_eval::*;
+pub use search_log::*;
+pub use search_settings::*;
+pub use secrets::*;
 // pub use repositories::*; // Removed: repository info now lives on projects
 pub use sessions::*;
+pub use transcripts::*;
+pub use usage::*;
 pub use users::*;
+pub use webhooks::*;
 
 use crate::Result;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
@@ -0,0 +1,281 @@
+//! Per-project provider override queries.
+//!
+//! A project can pin its own LLM and embedding provider chain instead of the
+//! global one configured under `/providers`. API keys are encrypted with the
+//! master key on write and decrypted on read.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+use crate::{secrets, Error, Result};
+
+use super::DbPool;
+
+/// Which chain a provider override belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectProviderKind {
+    Llm,
+    Embedding,
+}
+
+impl ProjectProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Embedding => "embedding",
+        }
+    }
+}
+
+/// Project provider override record. `api_key` is decrypted.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProjectProviderRow {
+    pub id: String,
+    pub project_id: String,
+    pub kind: String,
+    pub name: String,
+    pub priority: i32,
+    pub api_key: Option<String>,
+    pub config: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ProjectProviderRow {
+    pub fn config_json(&self) -> Result<JsonValue> {
+        serde_json::from_str(&self.config).map_err(|e| Error::Internal(e.to_string()))
+    }
+
+    fn decrypted(mut self) -> Result<Self> {
+        self.api_key = secrets::decrypt_opt(self.api_key.as_deref())?;
+        Ok(self)
+    }
+}
+
+/// One entry of a project's provider chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectProviderInput {
+    pub name: String,
+    /// Position in the chain (lower = tried first). Defaults to list order.
+    pub priority: Option<i32>,
+    /// API key. `None` keeps the key of an existing entry with the same name.
+    pub api_key: Option<String>,
+    pub config: JsonValue,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// List a project's provider overrides of one kind, in chain order.
+pub async fn list_project_providers(
+    pool: &DbPool,
+    project_id: &str,
+    kind: ProjectProviderKind,
+) -> Result<Vec<ProjectProviderRow>> {
+    sqlx::query_as::<_, ProjectProviderRow>(
+        r#"
+        SELECT * FROM project_providers
+        WHERE project_id = ? AND kind = ?
+        ORDER BY priority ASC, created_at ASC
+        "#,
+    )
+    .bind(project_id)
+    .bind(kind.as_str())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(ProjectProviderRow::decrypted)
+    .collect()
+}
+
+/// Replace a project's provider chain of one kind.
+///
+/// An empty chain removes the override, so the project falls back to the
+/// global providers.
+pub async fn replace_project_providers(
+    pool: &DbPool,
+    project_id: &str,
+    kind: ProjectProviderKind,
+    providers: Vec<ProjectProviderInput>,
+) -> Result<Vec<ProjectProviderRow>> {
+    let mut tx = pool.begin().await?;
+
+    // Sealed keys of the current chain, kept for entries that omit their key
+    let existing: HashMap<String, Option<String>> = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT name, api_key FROM project_providers WHERE project_id = ? AND kind = ?",
+    )
+    .bind(project_id)
+    .bind(kind.as_str())
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    sqlx::query("DELETE FROM project_providers WHERE project_id = ? AND kind = ?")
+        .bind(project_id)
+        .bind(kind.as_str())
+        .execute(&mut *tx)
+        .await?;
+
+    for (index, provider) in providers.into_iter().enumerate() {
+        let api_key = match provider.api_key.as_deref() {
+            Some(key) => Some(secrets::encrypt(key)?),
+            None => existing.get(&provider.name).cloned().flatten(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO project_providers (id, project_id, kind, name, priority, api_key, config)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(crate::models::new_id())
+        .bind(project_id)
+        .bind(kind.as_str())
+        .bind(&provider.name)
+        .bind(provider.priority.unwrap_or(index as i32))
+        .bind(&api_key)
+        .bind(serde_json::to_string(&provider.config)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    list_project_providers(pool, project_id, kind).await
+}
+
+/// Remove a project's provider overrides of one kind.
+pub async fn delete_project_providers(
+    pool: &DbPool,
+    project_id: &str,
+    kind: ProjectProviderKind,
+) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM project_providers WHERE project_id = ? AND kind = ?")
+        .bind(project_id)
+        .bind(kind.as_str())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+    use serde_json::json;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn provider(name: &str, api_key: Option<&str>) -> ProjectProviderInput {
+        ProjectProviderInput {
+            name: name.to_string(),
+            priority: None,
+            api_key: api_key.map(String::from),
+            config: json!({ "model": "test-model" }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_and_list_chain() {
+        let pool = setup_test_db().await;
+
+        let chain = replace_project_providers(
+            &pool,
+            "proj-1",
+            ProjectProviderKind::Llm,
+            vec![
+                provider("ollama", None),
+                provider("gemini", Some("sk-gemini")),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].name, "ollama");
+        assert_eq!(chain[1].name, "gemini");
+        assert_eq!(chain[1].api_key.as_deref(), Some("sk-gemini"));
+        assert!(
+            list_project_providers(&pool, "proj-1", ProjectProviderKind::Embedding)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Keys are sealed at rest
+        let (stored,): (String,) =
+            sqlx::query_as("SELECT api_key FROM project_providers WHERE name = 'gemini'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(secrets::is_sealed(&stored));
+        assert!(!stored.contains("sk-gemini"));
+    }
+
+    #[tokio::test]
+    async fn test_replace_keeps_omitted_keys() {
+        let pool = setup_test_db().await;
+        let kind = ProjectProviderKind::Embedding;
+
+        replace_project_providers(
+            &pool,
+            "proj-1",
+            kind,
+            vec![provider("openai", Some("sk-1"))],
+        )
+        .await
+        .unwrap();
+        let chain = replace_project_providers(
+            &pool,
+            "proj-1",
+            kind,
+            vec![provider("ollama", None), provider("openai", None)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(chain[1].name, "openai");
+        assert_eq!(chain[1].api_key.as_deref(), Some("sk-1"));
+
+        assert_eq!(
+            delete_project_providers(&pool, "proj-1", kind)
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(list_project_providers(&pool, "proj-1", kind)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Projects are the top-level organizational unit in Fold.
 
-use crate::{Error, Result};
+use crate::{secrets, Error, Result};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -187,6 +187,22 @@ impl Project {
             _ => self.slug.clone(),
         }
     }
+
+    /// Get ignored commit author patterns as a vector.
+    pub fn ignored_commit_authors_vec(&self) -> Vec<String> {
+        self.ignored_commit_authors
+            .as_ref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Decrypt the stored repository tokens and webhook secret.
+    pub fn decrypted(mut self) -> Result<Self> {
+        self.access_token = secrets::decrypt_opt(self.access_token.as_deref())?;
+        self.webhook_secret = secrets::decrypt_opt(self.webhook_secret.as_deref())?;
+        self.metadata_repo_token = secrets::decrypt_opt(self.metadata_repo_token.as_deref())?;
+        Ok(self)
+    }
 }
 
 /// Input for creating a new project.
@@ -237,6 +253,8 @@ pub struct MetadataRepoConfig {
 
 /// Create a new project.
 pub async fn create_project(pool: &DbPool, input: CreateProject) -> Result<Project> {
+    let access_token = secrets::encrypt_opt_if_configured(input.access_token.as_deref())?;
+
     sqlx::query_as::<_, Project>(
         r#"
         INSERT INTO projects (id, slug, name, description, provider, root_path, remote_owner, remote_repo, remote_branch, access_token)
@@ -253,7 +271,7 @@ pub async fn create_project(pool: &DbPool, input: CreateProject) -> Result<Proje
     .bind(&input.remote_owner)
     .bind(&input.remote_repo)
     .bind(&input.remote_branch)
-    .bind(&input.access_token)
+    .bind(&access_token)
     .fetch_one(pool)
     .await
     .map_err(|e| match e {
@@ -261,7 +279,8 @@ pub async fn create_project(pool: &DbPool, input: CreateProject) -> Result<Proje
             Error::AlreadyExists(format!("Project with slug '{}' already exists", input.slug))
         }
         _ => Error::Database(e),
-    })
+    })?
+    .decrypted()
 }
 
 /// Get a project by ID.
@@ -270,7 +289,8 @@ pub async fn get_project(pool: &DbPool, id: &str) -> Result<Project> {
         .bind(id)
         .fetch_optional(pool)
         .await?
-        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))
+        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))?
+        .decrypted()
 }
 
 /// Get a project by slug.
@@ -280,7 +300,9 @@ pub async fn get_project_by_slug(pool: &DbPool, slug: &str) -> Result<Option<Pro
         .bind(slug)
         .fetch_optional(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .map(Project::decrypted)
+        .transpose()
 }
 
 /// Get a project by ID or slug.
@@ -295,7 +317,8 @@ pub async fn get_project_by_id_or_slug(pool: &DbPool, id_or_slug: &str) -> Resul
     .bind(id_or_slug)
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id_or_slug)))
+    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id_or_slug)))?
+    .decrypted()
 }
 
 /// Update a project.
@@ -339,7 +362,8 @@ pub async fn update_project(pool: &DbPool, id: &str, input: UpdateProject) -> Re
 
     q.fetch_optional(pool)
         .await?
-        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))
+        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))?
+        .decrypted()
 }
 
 /// Input for updating algorithm configuration.
@@ -395,7 +419,8 @@ pub async fn update_algorithm_config(
 
     q.fetch_optional(pool)
         .await?
-        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))
+        .ok_or_else(|| Error::NotFound(format!("Project not found: {}", id)))?
+        .decrypted()
 }
 
 /// Configure metadata repository sync.
@@ -404,6 +429,8 @@ pub async fn configure_metadata_repo(
     project_id: &str,
     config: MetadataRepoConfig,
 ) -> Result<Project> {
+    let token = secrets::encrypt_if_configured(&config.token)?;
+
     sqlx::query_as::<_, Project>(
         r#"
         UPDATE projects SET
@@ -427,13 +454,14 @@ pub async fn configure_metadata_repo(
     .bind(&config.owner)
     .bind(&config.name)
     .bind(&config.branch)
-    .bind(&config.token)
+    .bind(&token)
     .bind(&config.source_id)
     .bind(&config.path_prefix)
     .bind(project_id)
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))
+    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))?
+    .decrypted()
 }
 
 /// Disable metadata repository sync.
@@ -450,7 +478,8 @@ pub async fn disable_metadata_repo(pool: &DbPool, project_id: &str) -> Result<Pr
     .bind(project_id)
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))
+    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))?
+    .decrypted()
 }
 
 /// Delete a project and cascade to all related entities.
@@ -472,7 +501,10 @@ pub async fn list_projects(pool: &DbPool) -> Result<Vec<Project>> {
     sqlx::query_as::<_, Project>("SELECT * FROM projects ORDER BY name ASC")
         .fetch_all(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .into_iter()
+        .map(Project::decrypted)
+        .collect()
 }
 
 /// List projects with pagination.
@@ -492,7 +524,10 @@ pub async fn list_projects_paginated(
     .bind(offset)
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// Count total projects.
@@ -518,7 +553,10 @@ pub async fn search_projects(pool: &DbPool, query: &str) -> Result<Vec<Project>>
     .bind(&pattern)
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// Check if a project slug is available.
@@ -542,14 +580,22 @@ pub async fn list_projects_with_metadata_sync(pool: &DbPool) -> Result<Vec<Proje
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
-/// Get webhook secret for a project.
+/// Get the decrypted webhook secret for a project.
 /// Returns None if not set.
 pub async fn get_webhook_secret(pool: &DbPool, project_id: &str) -> Result<Option<String>> {
-    let project = get_project(pool, project_id).await?;
-    Ok(project.webhook_secret)
+    let (secret,): (Option<String>,) =
+        sqlx::query_as("SELECT webhook_secret FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))?;
+    secrets::decrypt_opt(secret.as_deref())
 }
 
 /// Update project sync state after indexing.
@@ -572,7 +618,8 @@ pub async fn update_project_indexed(
     .bind(project_id)
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))
+    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))?
+    .decrypted()
 }
 
 /// List projects that need polling (remote providers with webhook not set up).
@@ -587,7 +634,10 @@ pub async fn list_polling_projects(pool: &DbPool) -> Result<Vec<Project>> {
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// Update project after sync.
@@ -613,7 +663,8 @@ pub async fn update_project_sync(
     .bind(project_id)
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))
+    .ok_or_else(|| Error::NotFound(format!("Project not found: {}", project_id)))?
+    .decrypted()
 }
 
 // ============================================================================
@@ -724,7 +775,10 @@ pub async fn list_user_projects(pool: &DbPool, user_id: &str) -> Result<Vec<Proj
     .bind(user_id)
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// List projects a user can write to (member role).
@@ -740,7 +794,10 @@ pub async fn list_user_writable_projects(pool: &DbPool, user_id: &str) -> Result
     .bind(user_id)
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// Check if a user can access a project.
@@ -967,7 +1024,10 @@ pub async fn list_group_projects(pool: &DbPool, group_id: &str) -> Result<Vec<Pr
     .bind(group_id)
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(Project::decrypted)
+    .collect()
 }
 
 /// Update a group member's role in a project.
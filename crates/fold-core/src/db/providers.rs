@@ -1,8 +1,10 @@
 //! LLM and Embedding provider database queries.
 //!
 //! Handles provider configuration storage, retrieval, and OAuth state management.
+//! API keys, OAuth client secrets and OAuth tokens are encrypted with the
+//! master key on write (when one is configured) and decrypted on read.
 
-use crate::{Error, Result};
+use crate::{secrets, Error, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -72,6 +74,15 @@ impl LlmProviderRow {
         // If no expiry set, consider it not expired
         false
     }
+
+    /// Decrypt the stored credentials.
+    fn decrypted(mut self) -> Result<Self> {
+        self.api_key = secrets::decrypt_opt(self.api_key.as_deref())?;
+        self.oauth_client_secret = secrets::decrypt_opt(self.oauth_client_secret.as_deref())?;
+        self.oauth_access_token = secrets::decrypt_opt(self.oauth_access_token.as_deref())?;
+        self.oauth_refresh_token = secrets::decrypt_opt(self.oauth_refresh_token.as_deref())?;
+        Ok(self)
+    }
 }
 
 /// Input for creating/updating an LLM provider.
@@ -170,6 +181,15 @@ impl EmbeddingProviderRow {
         }
         false
     }
+
+    /// Decrypt the stored credentials.
+    fn decrypted(mut self) -> Result<Self> {
+        self.api_key = secrets::decrypt_opt(self.api_key.as_deref())?;
+        self.oauth_client_secret = secrets::decrypt_opt(self.oauth_client_secret.as_deref())?;
+        self.oauth_access_token = secrets::decrypt_opt(self.oauth_access_token.as_deref())?;
+        self.oauth_refresh_token = secrets::decrypt_opt(self.oauth_refresh_token.as_deref())?;
+        Ok(self)
+    }
 }
 
 /// Input for creating/updating an embedding provider.
@@ -250,6 +270,7 @@ pub async fn create_llm_provider(
 ) -> Result<LlmProviderRow> {
     let id = crate::models::new_id();
     let config_json = serde_json::to_string(&input.config)?;
+    let api_key = secrets::encrypt_opt_if_configured(input.api_key.as_deref())?;
 
     sqlx::query_as::<_, LlmProviderRow>(
         r#"
@@ -263,7 +284,7 @@ pub async fn create_llm_provider(
     .bind(if input.enabled { 1 } else { 0 })
     .bind(input.priority)
     .bind(&input.auth_type)
-    .bind(&input.api_key)
+    .bind(&api_key)
     .bind(&config_json)
     .fetch_one(pool)
     .await
@@ -272,7 +293,8 @@ pub async fn create_llm_provider(
             Error::AlreadyExists(format!("LLM provider '{}' already exists", input.name))
         }
         _ => Error::Database(e),
-    })
+    })?
+    .decrypted()
 }
 
 /// Get an LLM provider by ID.
@@ -281,7 +303,9 @@ pub async fn get_llm_provider(pool: &DbPool, id: &str) -> Result<Option<LlmProvi
         .bind(id)
         .fetch_optional(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .map(LlmProviderRow::decrypted)
+        .transpose()
 }
 
 /// Get an LLM provider by name.
@@ -290,7 +314,9 @@ pub async fn get_llm_provider_by_name(pool: &DbPool, name: &str) -> Result<Optio
         .bind(name)
         .fetch_optional(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .map(LlmProviderRow::decrypted)
+        .transpose()
 }
 
 /// List all LLM providers.
@@ -300,7 +326,10 @@ pub async fn list_llm_providers(pool: &DbPool) -> Result<Vec<LlmProviderRow>> {
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(LlmProviderRow::decrypted)
+    .collect()
 }
 
 /// List enabled LLM providers ordered by priority.
@@ -314,7 +343,10 @@ pub async fn list_enabled_llm_providers(pool: &DbPool) -> Result<Vec<LlmProvider
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(LlmProviderRow::decrypted)
+    .collect()
 }
 
 /// Update an LLM provider.
@@ -340,15 +372,15 @@ pub async fn update_llm_provider(
     }
     if let Some(api_key) = input.api_key {
         updates.push("api_key = ?");
-        bindings.push(api_key);
+        bindings.push(secrets::encrypt_if_configured(&api_key)?);
     }
     if let Some(access_token) = input.oauth_access_token {
         updates.push("oauth_access_token = ?");
-        bindings.push(access_token);
+        bindings.push(secrets::encrypt_if_configured(&access_token)?);
     }
     if let Some(refresh_token) = input.oauth_refresh_token {
         updates.push("oauth_refresh_token = ?");
-        bindings.push(refresh_token);
+        bindings.push(secrets::encrypt_if_configured(&refresh_token)?);
     }
     if let Some(expires_at) = input.oauth_token_expires_at {
         updates.push("oauth_token_expires_at = ?");
@@ -380,7 +412,8 @@ pub async fn update_llm_provider(
 
     q.fetch_optional(pool)
         .await?
-        .ok_or_else(|| Error::NotFound(format!("LLM provider not found: {}", id)))
+        .ok_or_else(|| Error::NotFound(format!("LLM provider not found: {}", id)))?
+        .decrypted()
 }
 
 /// Update LLM provider's last_used_at timestamp.
@@ -413,6 +446,7 @@ pub async fn upsert_llm_provider(
 ) -> Result<LlmProviderRow> {
     let id = crate::models::new_id();
     let config_json = serde_json::to_string(&input.config)?;
+    let api_key = secrets::encrypt_opt_if_configured(input.api_key.as_deref())?;
 
     sqlx::query_as::<_, LlmProviderRow>(
         r#"
@@ -433,11 +467,12 @@ pub async fn upsert_llm_provider(
     .bind(if input.enabled { 1 } else { 0 })
     .bind(input.priority)
     .bind(&input.auth_type)
-    .bind(&input.api_key)
+    .bind(&api_key)
     .bind(&config_json)
     .fetch_one(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .decrypted()
 }
 
 // ============================================================================
@@ -458,6 +493,7 @@ pub async fn create_embedding_provider(
         }
     }
     let config_json = serde_json::to_string(&config)?;
+    let api_key = secrets::encrypt_opt_if_configured(input.api_key.as_deref())?;
 
     sqlx::query_as::<_, EmbeddingProviderRow>(
         r#"
@@ -471,7 +507,7 @@ pub async fn create_embedding_provider(
     .bind(if input.enabled { 1 } else { 0 })
     .bind(input.priority)
     .bind(&input.auth_type)
-    .bind(&input.api_key)
+    .bind(&api_key)
     .bind(&config_json)
     .fetch_one(pool)
     .await
@@ -480,7 +516,8 @@ pub async fn create_embedding_provider(
             format!("Embedding provider '{}' already exists", input.name),
         ),
         _ => Error::Database(e),
-    })
+    })?
+    .decrypted()
 }
 
 /// Get an embedding provider by ID.
@@ -492,7 +529,9 @@ pub async fn get_embedding_provider(
         .bind(id)
         .fetch_optional(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .map(EmbeddingProviderRow::decrypted)
+        .transpose()
 }
 
 /// Get an embedding provider by name.
@@ -504,7 +543,9 @@ pub async fn get_embedding_provider_by_name(
         .bind(name)
         .fetch_optional(pool)
         .await
-        .map_err(Error::Database)
+        .map_err(Error::Database)?
+        .map(EmbeddingProviderRow::decrypted)
+        .transpose()
 }
 
 /// List all embedding providers.
@@ -514,7 +555,10 @@ pub async fn list_embedding_providers(pool: &DbPool) -> Result<Vec<EmbeddingProv
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(EmbeddingProviderRow::decrypted)
+    .collect()
 }
 
 /// List enabled embedding providers ordered by priority.
@@ -528,7 +572,10 @@ pub async fn list_enabled_embedding_providers(pool: &DbPool) -> Result<Vec<Embed
     )
     .fetch_all(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .into_iter()
+    .map(EmbeddingProviderRow::decrypted)
+    .collect()
 }
 
 /// Update an embedding provider.
@@ -554,15 +601,15 @@ pub async fn update_embedding_provider(
     }
     if let Some(api_key) = input.api_key {
         updates.push("api_key = ?");
-        bindings.push(api_key);
+        bindings.push(secrets::encrypt_if_configured(&api_key)?);
     }
     if let Some(access_token) = input.oauth_access_token {
         updates.push("oauth_access_token = ?");
-        bindings.push(access_token);
+        bindings.push(secrets::encrypt_if_configured(&access_token)?);
     }
     if let Some(refresh_token) = input.oauth_refresh_token {
         updates.push("oauth_refresh_token = ?");
-        bindings.push(refresh_token);
+        bindings.push(secrets::encrypt_if_configured(&refresh_token)?);
     }
     if let Some(expires_at) = input.oauth_token_expires_at {
         updates.push("oauth_token_expires_at = ?");
@@ -621,7 +668,8 @@ pub async fn update_embedding_provider(
 
     q.fetch_optional(pool)
         .await?
-        .ok_or_else(|| Error::NotFound(format!("Embedding provider not found: {}", id)))
+        .ok_or_else(|| Error::NotFound(format!("Embedding provider not found: {}", id)))?
+        .decrypted()
 }
 
 /// Update embedding provider's last_used_at timestamp.
@@ -664,6 +712,7 @@ pub async fn upsert_embedding_provider(
         }
     }
     let config_json = serde_json::to_string(&config)?;
+    let api_key = secrets::encrypt_opt_if_configured(input.api_key.as_deref())?;
 
     sqlx::query_as::<_, EmbeddingProviderRow>(
         r#"
@@ -684,11 +733,12 @@ pub async fn upsert_embedding_provider(
     .bind(if input.enabled { 1 } else { 0 })
     .bind(input.priority)
     .bind(&input.auth_type)
-    .bind(&input.api_key)
+    .bind(&api_key)
     .bind(&config_json)
     .fetch_one(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .decrypted()
 }
 
 // ============================================================================
@@ -875,10 +925,11 @@ pub async fn seed_claudecode_provider_async(
         RETURNING *
         "#,
     )
-    .bind(access_token)
+    .bind(secrets::encrypt_if_configured(access_token)?)
     .fetch_one(pool)
     .await
-    .map_err(Error::Database)
+    .map_err(Error::Database)?
+    .decrypted()
 }
 
 // ============================================================================
@@ -0,0 +1,395 @@
+//! Retention policy database queries.
+//!
+//! Per-project rules for archiving weak memories, and the record of which
+//! memories have been moved to cold storage.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Retention policy record.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// Memory type this policy applies to (None = any)
+    pub memory_type: Option<String>,
+    /// Memory source this policy applies to (None = any)
+    pub source: Option<String>,
+    /// Archive when decay strength is below this value
+    pub max_strength: Option<f64>,
+    /// Archive only memories at least this many days old
+    pub min_age_days: i64,
+    pub never_archive: i32,
+    pub enabled: i32,
+    pub created_at: String,
+}
+
+impl RetentionPolicy {
+    /// Check if this policy protects matching memories from archival.
+    pub fn is_never_archive(&self) -> bool {
+        self.never_archive != 0
+    }
+
+    /// Check if the policy is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+}
+
+/// Input for creating a retention policy.
+#[derive(Debug, Clone)]
+pub struct CreateRetentionPolicy {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub memory_type: Option<String>,
+    pub source: Option<String>,
+    pub max_strength: Option<f64>,
+    pub min_age_days: i64,
+    pub never_archive: bool,
+}
+
+/// Archived memory record.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ArchivedMemory {
+    pub memory_id: String,
+    pub project_id: String,
+    /// Policy that archived the memory (None once the policy is deleted)
+    pub policy_id: Option<String>,
+    /// Decay strength at archival time
+    pub strength: Option<f64>,
+    pub reason: Option<String>,
+    pub archived_at: String,
+}
+
+/// Input for recording an archived memory.
+#[derive(Debug, Clone)]
+pub struct CreateArchivedMemory {
+    pub memory_id: String,
+    pub project_id: String,
+    pub policy_id: Option<String>,
+    pub strength: Option<f64>,
+    pub reason: Option<String>,
+}
+
+// ============================================================================
+// Policy Queries
+// ============================================================================
+
+/// Create a retention policy.
+pub async fn create_retention_policy(
+    pool: &DbPool,
+    input: CreateRetentionPolicy,
+) -> Result<RetentionPolicy> {
+    sqlx::query_as::<_, RetentionPolicy>(
+        r#"
+        INSERT INTO retention_policies (
+            id, project_id, name, memory_type, source, max_strength, min_age_days, never_archive
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.name)
+    .bind(&input.memory_type)
+    .bind(&input.source)
+    .bind(input.max_strength)
+    .bind(input.min_age_days)
+    .bind(input.never_archive as i32)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get a retention policy by ID.
+pub async fn get_retention_policy(pool: &DbPool, id: &str) -> Result<RetentionPolicy> {
+    sqlx::query_as::<_, RetentionPolicy>("SELECT * FROM retention_policies WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Retention policy not found: {}", id)))
+}
+
+/// List retention policies for a project. Oldest first.
+pub async fn list_retention_policies(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<Vec<RetentionPolicy>> {
+    sqlx::query_as::<_, RetentionPolicy>(
+        r#"
+        SELECT * FROM retention_policies
+        WHERE project_id = ?
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Delete a retention policy.
+pub async fn delete_retention_policy(pool: &DbPool, id: &str) -> Result<()> {
+    let result = sqlx::query("DELETE FROM retention_policies WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!(
+            "Retention policy not found: {}",
+            id
+        )));
+    }
+
+    Ok(())
+}
+
+/// List projects with enabled archive policies and no retention job in the interval.
+pub async fn list_projects_due_retention(
+    pool: &DbPool,
+    interval_hours: i64,
+) -> Result<Vec<String>> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT p.project_id FROM retention_policies p
+        WHERE p.enabled = 1 AND p.never_archive = 0
+          AND NOT EXISTS (
+              SELECT 1 FROM jobs j
+              WHERE j.project_id = p.project_id
+                AND j.type = 'apply_retention'
+                AND j.created_at > datetime('now', '-' || ? || ' hours')
+          )
+        "#,
+    )
+    .bind(interval_hours)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+// ============================================================================
+// Archive Queries
+// ============================================================================
+
+/// Record that a memory was archived.
+pub async fn create_archived_memory(
+    pool: &DbPool,
+    input: CreateArchivedMemory,
+) -> Result<ArchivedMemory> {
+    sqlx::query_as::<_, ArchivedMemory>(
+        r#"
+        INSERT INTO archived_memories (memory_id, project_id, policy_id, strength, reason)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(memory_id) DO UPDATE SET
+            policy_id = excluded.policy_id,
+            strength = excluded.strength,
+            reason = excluded.reason,
+            archived_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(&input.memory_id)
+    .bind(&input.project_id)
+    .bind(&input.policy_id)
+    .bind(input.strength)
+    .bind(&input.reason)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get the archive record for a memory, if it is archived.
+pub async fn get_archived_memory(pool: &DbPool, memory_id: &str) -> Result<Option<ArchivedMemory>> {
+    sqlx::query_as::<_, ArchivedMemory>("SELECT * FROM archived_memories WHERE memory_id = ?")
+        .bind(memory_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(Error::Database)
+}
+
+/// Remove the archive record for a memory.
+pub async fn delete_archived_memory(pool: &DbPool, memory_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM archived_memories WHERE memory_id = ?")
+        .bind(memory_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// List archived memories for a project. Most recently archived first.
+pub async fn list_archived_memories(
+    pool: &DbPool,
+    project_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ArchivedMemory>> {
+    sqlx::query_as::<_, ArchivedMemory>(
+        r#"
+        SELECT * FROM archived_memories
+        WHERE project_id = ?
+        ORDER BY archived_at DESC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List IDs of all archived memories for a project.
+pub async fn list_archived_memory_ids(pool: &DbPool, project_id: &str) -> Result<Vec<String>> {
+    let rows: Vec<(String,)> =
+        sqlx::query_as("SELECT memory_id FROM archived_memories WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+/// Count archived memories for a project.
+pub async fn count_archived_memories(pool: &DbPool, project_id: &str) -> Result<i64> {
+    let (count,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM archived_memories WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_one(pool)
+            .await?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        create_memory, create_project, init_pool, migrate, CreateMemory, CreateProject, MemoryType,
+    };
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        create_memory(
+            &pool,
+            CreateMemory {
+                id: "mem-1".to_string(),
+                project_id: "proj-1".to_string(),
+                memory_type: MemoryType::General,
+                source: None,
+                title: Some("Old note".to_string()),
+                content: None,
+                content_hash: None,
+                content_storage: "filesystem".to_string(),
+                file_path: None,
+                language: None,
+                git_branch: None,
+                git_commit_sha: None,
+                author: None,
+                keywords: None,
+                tags: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_policy_crud_and_due_projects() {
+        let pool = setup_test_db().await;
+
+        let policy = create_retention_policy(
+            &pool,
+            CreateRetentionPolicy {
+                id: "pol-1".to_string(),
+                project_id: "proj-1".to_string(),
+                name: "Weak agent memories".to_string(),
+                memory_type: None,
+                source: Some("agent".to_string()),
+                max_strength: Some(0.05),
+                min_age_days: 180,
+                never_archive: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(policy.is_enabled());
+        assert!(!policy.is_never_archive());
+
+        assert_eq!(
+            list_projects_due_retention(&pool, 24).await.unwrap(),
+            vec!["proj-1".to_string()]
+        );
+
+        delete_retention_policy(&pool, "pol-1").await.unwrap();
+        assert!(list_retention_policies(&pool, "proj-1")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(delete_retention_policy(&pool, "pol-1").await.is_err());
+        assert!(list_projects_due_retention(&pool, 24)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_records() {
+        let pool = setup_test_db().await;
+
+        let archived = create_archived_memory(
+            &pool,
+            CreateArchivedMemory {
+                memory_id: "mem-1".to_string(),
+                project_id: "proj-1".to_string(),
+                policy_id: None,
+                strength: Some(0.02),
+                reason: Some("manual".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(archived.strength, Some(0.02));
+        assert_eq!(count_archived_memories(&pool, "proj-1").await.unwrap(), 1);
+        assert_eq!(
+            list_archived_memory_ids(&pool, "proj-1").await.unwrap(),
+            vec!["mem-1".to_string()]
+        );
+
+        delete_archived_memory(&pool, "mem-1").await.unwrap();
+        assert!(get_archived_memory(&pool, "mem-1").await.unwrap().is_none());
+        assert_eq!(count_archived_memories(&pool, "proj-1").await.unwrap(), 0);
+    }
+}
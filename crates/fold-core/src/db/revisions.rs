@@ -0,0 +1,321 @@
+//! Memory revision queries.
+//!
+//! Before a memory is updated, the version being replaced is kept with the
+//! time range it was current. Point-in-time search uses these to show a
+//! memory as it was at a past time. Deleting a memory keeps a tombstone: its
+//! last version together with the deleted memory itself.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::models::Memory;
+use crate::{Error, Result};
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// An earlier version of a memory.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MemoryRevision {
+    pub id: String,
+    pub project_id: String,
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub content_hash: Option<String>,
+    pub keywords: Option<String>,
+    pub tags: Option<String>,
+    pub context: Option<String>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: DateTime<Utc>,
+    /// The deleted memory as JSON, set only on tombstones.
+    pub snapshot: Option<String>,
+    pub created_at: String,
+}
+
+impl MemoryRevision {
+    /// The memory as it was while this revision was current.
+    pub fn apply(self, memory: Memory) -> Memory {
+        Memory {
+            title: self.title,
+            content: self.content.or(memory.content),
+            content_hash: self.content_hash,
+            keywords: self.keywords,
+            tags: self.tags,
+            context: self.context,
+            updated_at: self.valid_from,
+            ..memory
+        }
+    }
+
+    /// The deleted memory, if this is a tombstone.
+    pub fn deleted_memory(&self) -> Option<Memory> {
+        self.snapshot
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Keep the current version of a memory before it is replaced.
+///
+/// `content` overrides the stored content, for agent memories whose content
+/// lives in fold/ rather than SQLite.
+pub async fn record_memory_revision(
+    pool: &DbPool,
+    memory: &Memory,
+    content: Option<&str>,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    insert_revision(&mut conn, memory, content, None).await
+}
+
+/// Keep a tombstone for a memory about to be deleted.
+///
+/// `content` is as for [`record_memory_revision`].
+pub async fn record_memory_tombstone(
+    pool: &DbPool,
+    memory: &Memory,
+    content: Option<&str>,
+) -> Result<()> {
+    let snapshot = serde_json::to_string(memory)?;
+    let mut conn = pool.acquire().await?;
+    insert_revision(&mut conn, memory, content, Some(snapshot)).await
+}
+
+/// Write a revision on a caller's connection, so it can share a transaction.
+pub(crate) async fn insert_revision(
+    conn: &mut SqliteConnection,
+    memory: &Memory,
+    content: Option<&str>,
+    snapshot: Option<String>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO memory_revisions (
+            id, project_id, memory_id, title, content, content_hash, keywords,
+            tags, context, valid_from, valid_to, snapshot
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(crate::models::new_id())
+    .bind(&memory.project_id)
+    .bind(&memory.id)
+    .bind(&memory.title)
+    .bind(content.or(memory.content.as_deref()))
+    .bind(&memory.content_hash)
+    .bind(&memory.keywords)
+    .bind(&memory.tags)
+    .bind(&memory.context)
+    .bind(memory.updated_at)
+    .bind(Utc::now())
+    .bind(snapshot)
+    .execute(conn)
+    .await
+    .map_err(Error::Database)?;
+    Ok(())
+}
+
+/// Get the revision of a memory that was current at `at`, if it has since
+/// been replaced.
+pub async fn get_memory_revision_at(
+    pool: &DbPool,
+    memory_id: &str,
+    at: DateTime<Utc>,
+) -> Result<Option<MemoryRevision>> {
+    sqlx::query_as::<_, MemoryRevision>(
+        r#"
+        SELECT * FROM memory_revisions
+        WHERE memory_id = ?
+          AND datetime(valid_from) <= datetime(?)
+          AND datetime(valid_to) > datetime(?)
+        ORDER BY valid_to ASC
+        LIMIT 1
+        "#,
+    )
+    .bind(memory_id)
+    .bind(at)
+    .bind(at)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Get the tombstone of a deleted memory.
+pub async fn get_memory_tombstone(
+    pool: &DbPool,
+    memory_id: &str,
+) -> Result<Option<MemoryRevision>> {
+    sqlx::query_as::<_, MemoryRevision>(
+        r#"
+        SELECT * FROM memory_revisions
+        WHERE memory_id = ? AND snapshot IS NOT NULL
+        ORDER BY valid_to DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(memory_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List tombstones of a project's memories deleted after `at`, earliest
+/// deletion first.
+pub async fn list_memory_tombstones_after(
+    pool: &DbPool,
+    project_id: &str,
+    at: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<MemoryRevision>> {
+    sqlx::query_as::<_, MemoryRevision>(
+        r#"
+        SELECT * FROM memory_revisions
+        WHERE project_id = ? AND snapshot IS NOT NULL
+          AND datetime(valid_to) > datetime(?)
+        ORDER BY valid_to ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(at)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, update_memory, CreateProject};
+    use chrono::Duration;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO memories (id, project_id, type, title, content, updated_at)
+            VALUES ('mem-1', 'proj-1', 'decision', 'Use Postgres', 'We use Postgres', ?)
+            "#,
+        )
+        .bind(Utc::now() - Duration::days(10))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn load_memory(pool: &DbPool) -> Memory {
+        sqlx::query_as::<_, Memory>("SELECT * FROM memories WHERE id = 'mem-1'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revision_at() {
+        let pool = setup_test_db().await;
+        let original = load_memory(&pool).await;
+
+        record_memory_revision(&pool, &original, None)
+            .await
+            .unwrap();
+        update_memory(
+            &pool,
+            "mem-1",
+            crate::db::UpdateMemory {
+                title: Some("Use SQLite".to_string()),
+                content: Some("We use SQLite".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Five days ago the original version was current
+        let revision = get_memory_revision_at(&pool, "mem-1", Utc::now() - Duration::days(5))
+            .await
+            .unwrap()
+            .unwrap();
+        let current = load_memory(&pool).await;
+        let then = revision.apply(current);
+        assert_eq!(then.title.as_deref(), Some("Use Postgres"));
+        assert_eq!(then.content.as_deref(), Some("We use Postgres"));
+        assert_eq!(then.updated_at.timestamp(), original.updated_at.timestamp());
+
+        // Before the memory existed, or now, no revision applies
+        assert!(
+            get_memory_revision_at(&pool, "mem-1", Utc::now() - Duration::days(20))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            get_memory_revision_at(&pool, "mem-1", Utc::now() + Duration::seconds(5))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+    #[tokio::test]
+    async fn test_tombstone() {
+        let pool = setup_test_db().await;
+        let memory = load_memory(&pool).await;
+
+        record_memory_tombstone(&pool, &memory, None).await.unwrap();
+        crate::db::delete_memory(&pool, "mem-1").await.unwrap();
+
+        // The revision survives the memory and holds it
+        let tombstone = get_memory_tombstone(&pool, "mem-1").await.unwrap().unwrap();
+        let deleted = tombstone.deleted_memory().unwrap();
+        assert_eq!(deleted.id, "mem-1");
+        assert_eq!(deleted.memory_type, "decision");
+
+        let then = get_memory_revision_at(&pool, "mem-1", Utc::now() - Duration::days(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(then.apply(deleted).title.as_deref(), Some("Use Postgres"));
+
+        let listed =
+            list_memory_tombstones_after(&pool, "proj-1", Utc::now() - Duration::days(5), 10)
+                .await
+                .unwrap();
+        assert_eq!(listed.len(), 1);
+        let later = Utc::now() + Duration::seconds(5);
+        let listed = list_memory_tombstones_after(&pool, "proj-1", later, 10)
+            .await
+            .unwrap();
+        assert!(listed.is_empty());
+    }
+}
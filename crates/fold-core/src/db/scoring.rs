@@ -0,0 +1,401 @@
+//! Scoring settings and memory access history queries.
+//!
+//! Per-project choice of decay model, half-lives per memory type and the
+//! weight of link structure in ranking, plus the retrieval history the
+//! power-law model computes activation from.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{Error, Result};
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Default ACT-R decay exponent.
+pub const DEFAULT_ACTR_DECAY: f64 = 0.5;
+
+/// How memory strength decays with time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayModel {
+    /// Exponential half-life decay from the last use, boosted by use count
+    Exponential,
+    /// ACT-R base-level activation over the retrieval history
+    PowerLaw,
+    /// Strength depends on feedback only
+    #[serde(rename = "none")]
+    NoDecay,
+}
+
+impl DecayModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exponential => "exponential",
+            Self::PowerLaw => "power_law",
+            Self::NoDecay => "none",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "exponential" => Some(Self::Exponential),
+            "power_law" => Some(Self::PowerLaw),
+            "none" => Some(Self::NoDecay),
+            _ => None,
+        }
+    }
+}
+
+/// Per-project scoring settings.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ScoringSettings {
+    pub project_id: String,
+    pub decay_model: String,
+    /// JSON object of memory type to half-life in days; null never decays
+    pub type_half_lives: String,
+    pub actr_decay: f64,
+    pub link_boost_weight: f64,
+    pub updated_at: String,
+}
+
+impl ScoringSettings {
+    /// Settings used when a project has never been configured.
+    pub fn defaults(project_id: &str) -> Self {
+        Self {
+            project_id: project_id.to_string(),
+            decay_model: DecayModel::Exponential.as_str().to_string(),
+            type_half_lives: "{}".to_string(),
+            actr_decay: DEFAULT_ACTR_DECAY,
+            link_boost_weight: 0.0,
+            updated_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// The decay model, exponential if unrecognised.
+    pub fn model(&self) -> DecayModel {
+        DecayModel::from_str(&self.decay_model).unwrap_or(DecayModel::Exponential)
+    }
+
+    /// Half-life overrides by memory type. `None` means the type never decays.
+    pub fn type_half_lives(&self) -> HashMap<String, Option<f64>> {
+        serde_json::from_str(&self.type_half_lives).unwrap_or_default()
+    }
+}
+
+/// Input for updating scoring settings. `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateScoringSettings {
+    pub decay_model: Option<DecayModel>,
+    /// Replaces every per-type override
+    pub type_half_lives: Option<HashMap<String, Option<f64>>>,
+    pub actr_decay: Option<f64>,
+    pub link_boost_weight: Option<f64>,
+}
+
+/// Kind of memory access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// The memory was fetched directly
+    Read,
+    /// A search result was marked used
+    Used,
+}
+
+impl AccessKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Used => "used",
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct AccessRow {
+    memory_id: String,
+    accessed_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Settings Queries
+// ============================================================================
+
+/// Get scoring settings for a project, falling back to defaults.
+pub async fn get_scoring_settings(pool: &DbPool, project_id: &str) -> Result<ScoringSettings> {
+    let settings =
+        sqlx::query_as::<_, ScoringSettings>("SELECT * FROM scoring_settings WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(settings.unwrap_or_else(|| ScoringSettings::defaults(project_id)))
+}
+
+/// Update scoring settings, creating the row if needed.
+pub async fn update_scoring_settings(
+    pool: &DbPool,
+    project_id: &str,
+    input: UpdateScoringSettings,
+) -> Result<ScoringSettings> {
+    let model = input.decay_model.map(|m| m.as_str());
+    let type_half_lives = input
+        .type_half_lives
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+
+    sqlx::query_as::<_, ScoringSettings>(
+        r#"
+        INSERT INTO scoring_settings (
+            project_id, decay_model, type_half_lives, actr_decay, link_boost_weight
+        )
+        VALUES (?, COALESCE(?, 'exponential'), COALESCE(?, '{}'), COALESCE(?, 0.5), COALESCE(?, 0.0))
+        ON CONFLICT(project_id) DO UPDATE SET
+            decay_model = COALESCE(?, decay_model),
+            type_half_lives = COALESCE(?, type_half_lives),
+            actr_decay = COALESCE(?, actr_decay),
+            link_boost_weight = COALESCE(?, link_boost_weight),
+            updated_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(project_id)
+    .bind(model)
+    .bind(&type_half_lives)
+    .bind(input.actr_decay)
+    .bind(input.link_boost_weight)
+    .bind(model)
+    .bind(&type_half_lives)
+    .bind(input.actr_decay)
+    .bind(input.link_boost_weight)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+// ============================================================================
+// Access History Queries
+// ============================================================================
+
+/// Record an access to a memory.
+pub async fn record_memory_access(
+    pool: &DbPool,
+    project_id: &str,
+    memory_id: &str,
+    kind: AccessKind,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO memory_accesses (id, project_id, memory_id, kind) VALUES (?, ?, ?, ?)",
+    )
+    .bind(crate::models::new_id())
+    .bind(project_id)
+    .bind(memory_id)
+    .bind(kind.as_str())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Get the most recent accesses (at most `per_memory` each) for the given
+/// memories, newest first. Memories never accessed are missing from the map.
+pub async fn get_memory_accesses(
+    pool: &DbPool,
+    memory_ids: &[String],
+    per_memory: i64,
+) -> Result<HashMap<String, Vec<DateTime<Utc>>>> {
+    if memory_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders: Vec<&str> = memory_ids.iter().map(|_| "?").collect();
+    let query = format!(
+        r#"
+        SELECT memory_id, accessed_at FROM (
+            SELECT memory_id, accessed_at,
+                   ROW_NUMBER() OVER (PARTITION BY memory_id ORDER BY accessed_at DESC) AS rn
+            FROM memory_accesses
+            WHERE memory_id IN ({})
+        )
+        WHERE rn <= ?
+        ORDER BY accessed_at DESC
+        "#,
+        placeholders.join(", ")
+    );
+
+    let mut q = sqlx::query_as::<_, AccessRow>(&query);
+    for id in memory_ids {
+        q = q.bind(id);
+    }
+
+    let mut accesses: HashMap<String, Vec<DateTime<Utc>>> = HashMap::new();
+    for row in q.bind(per_memory).fetch_all(pool).await? {
+        accesses
+            .entry(row.memory_id)
+            .or_default()
+            .push(row.accessed_at);
+    }
+
+    Ok(accesses)
+}
+
+/// Delete all but the `keep_per_memory` most recent accesses of each memory.
+pub async fn prune_memory_accesses(pool: &DbPool, keep_per_memory: i64) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM memory_accesses WHERE id IN (
+            SELECT id FROM (
+                SELECT id,
+                       ROW_NUMBER() OVER (
+                           PARTITION BY memory_id ORDER BY accessed_at DESC, id DESC
+                       ) AS rn
+                FROM memory_accesses
+            )
+            WHERE rn > ?
+        )
+        "#,
+    )
+    .bind(keep_per_memory)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for id in ["mem-1", "mem-2"] {
+            sqlx::query(
+                "INSERT INTO memories (id, project_id, type, title) VALUES (?, 'proj-1', 'general', ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_scoring_settings() {
+        let pool = setup_test_db().await;
+
+        let defaults = get_scoring_settings(&pool, "proj-1").await.unwrap();
+        assert_eq!(defaults.model(), DecayModel::Exponential);
+        assert!(defaults.type_half_lives().is_empty());
+
+        let mut half_lives = HashMap::new();
+        half_lives.insert("decision".to_string(), None);
+        half_lives.insert("session".to_string(), Some(7.0));
+        update_scoring_settings(
+            &pool,
+            "proj-1",
+            UpdateScoringSettings {
+                decay_model: Some(DecayModel::PowerLaw),
+                type_half_lives: Some(half_lives),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Unset fields are left alone
+        let updated = update_scoring_settings(
+            &pool,
+            "proj-1",
+            UpdateScoringSettings {
+                link_boost_weight: Some(0.1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.model(), DecayModel::PowerLaw);
+        assert_eq!(updated.link_boost_weight, 0.1);
+        let half_lives = updated.type_half_lives();
+        assert_eq!(half_lives["decision"], None);
+        assert_eq!(half_lives["session"], Some(7.0));
+    }
+
+    #[tokio::test]
+    async fn test_memory_accesses() {
+        let pool = setup_test_db().await;
+
+        for _ in 0..3 {
+            record_memory_access(&pool, "proj-1", "mem-1", AccessKind::Read)
+                .await
+                .unwrap();
+        }
+        record_memory_access(&pool, "proj-1", "mem-2", AccessKind::Used)
+            .await
+            .unwrap();
+
+        let accesses = get_memory_accesses(&pool, &["mem-1".to_string(), "mem-2".to_string()], 2)
+            .await
+            .unwrap();
+        assert_eq!(accesses["mem-1"].len(), 2);
+        assert_eq!(accesses["mem-2"].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prune_memory_accesses() {
+        let pool = setup_test_db().await;
+
+        for days_ago in 0..5 {
+            sqlx::query(
+                "INSERT INTO memory_accesses (id, project_id, memory_id, kind, accessed_at) VALUES (?, 'proj-1', 'mem-1', 'read', datetime('now', ?))",
+            )
+            .bind(crate::models::new_id())
+            .bind(format!("-{} days", days_ago))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        record_memory_access(&pool, "proj-1", "mem-2", AccessKind::Used)
+            .await
+            .unwrap();
+
+        assert_eq!(prune_memory_accesses(&pool, 2).await.unwrap(), 3);
+
+        // The newest accesses survive, and other memories are untouched
+        let accesses = get_memory_accesses(&pool, &["mem-1".to_string(), "mem-2".to_string()], 10)
+            .await
+            .unwrap();
+        assert_eq!(accesses["mem-1"].len(), 2);
+        assert!(accesses["mem-1"][0] > Utc::now() - chrono::Duration::hours(1));
+        assert_eq!(accesses["mem-2"].len(), 1);
+    }
+}
@@ -0,0 +1,418 @@
+//! Search evaluation database queries.
+//!
+//! Golden query sets (queries with the memories or files they should find)
+//! and the history of evaluation runs against them.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Named set of golden queries for a project.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GoldenSet {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input for creating a golden set.
+#[derive(Debug, Clone)]
+pub struct CreateGoldenSet {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A query and the results it is expected to return.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GoldenQuery {
+    pub id: String,
+    pub set_id: String,
+    pub query: String,
+    /// JSON array of memory IDs
+    pub expected_memory_ids: String,
+    /// JSON array of file paths
+    pub expected_file_paths: String,
+    pub created_at: String,
+}
+
+impl GoldenQuery {
+    /// Parse expected memory IDs from JSON.
+    pub fn expected_memory_ids_vec(&self) -> Vec<String> {
+        serde_json::from_str(&self.expected_memory_ids).unwrap_or_default()
+    }
+
+    /// Parse expected file paths from JSON.
+    pub fn expected_file_paths_vec(&self) -> Vec<String> {
+        serde_json::from_str(&self.expected_file_paths).unwrap_or_default()
+    }
+}
+
+/// Input for adding a golden query.
+#[derive(Debug, Clone)]
+pub struct CreateGoldenQuery {
+    pub id: String,
+    pub set_id: String,
+    pub query: String,
+    pub expected_memory_ids: Vec<String>,
+    pub expected_file_paths: Vec<String>,
+}
+
+/// Stored result of an evaluation run.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SearchEvaluation {
+    pub id: String,
+    pub project_id: String,
+    pub set_id: String,
+    pub job_id: Option<String>,
+    pub k: i64,
+    /// JSON object of the scoring settings evaluated
+    pub config: String,
+    pub query_count: i64,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+    /// JSON array of per-query metrics
+    pub details: String,
+    pub created_at: String,
+}
+
+/// Input for recording an evaluation run.
+#[derive(Debug, Clone)]
+pub struct CreateSearchEvaluation {
+    pub id: String,
+    pub project_id: String,
+    pub set_id: String,
+    pub job_id: Option<String>,
+    pub k: i64,
+    pub config: serde_json::Value,
+    pub query_count: i64,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+    pub details: serde_json::Value,
+}
+
+// ============================================================================
+// Golden Set Queries
+// ============================================================================
+
+/// Create a golden set.
+pub async fn create_golden_set(pool: &DbPool, input: CreateGoldenSet) -> Result<GoldenSet> {
+    sqlx::query_as::<_, GoldenSet>(
+        r#"
+        INSERT INTO golden_sets (id, project_id, name, description)
+        VALUES (?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.name)
+    .bind(&input.description)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+            Error::AlreadyExists(format!("Golden set already exists: {}", input.name))
+        }
+        e => Error::Database(e),
+    })
+}
+
+/// Get a golden set by ID.
+pub async fn get_golden_set(pool: &DbPool, id: &str) -> Result<GoldenSet> {
+    sqlx::query_as::<_, GoldenSet>("SELECT * FROM golden_sets WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Golden set not found: {}", id)))
+}
+
+/// List golden sets for a project, by name.
+pub async fn list_golden_sets(pool: &DbPool, project_id: &str) -> Result<Vec<GoldenSet>> {
+    sqlx::query_as::<_, GoldenSet>("SELECT * FROM golden_sets WHERE project_id = ? ORDER BY name")
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(Error::Database)
+}
+
+/// Delete a golden set with its queries and evaluation history.
+pub async fn delete_golden_set(pool: &DbPool, id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM golden_sets WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ============================================================================
+// Golden Query Queries
+// ============================================================================
+
+/// Add a query to a golden set.
+pub async fn create_golden_query(pool: &DbPool, input: CreateGoldenQuery) -> Result<GoldenQuery> {
+    let query = sqlx::query_as::<_, GoldenQuery>(
+        r#"
+        INSERT INTO golden_queries (id, set_id, query, expected_memory_ids, expected_file_paths)
+        VALUES (?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.set_id)
+    .bind(&input.query)
+    .bind(serde_json::to_string(&input.expected_memory_ids)?)
+    .bind(serde_json::to_string(&input.expected_file_paths)?)
+    .fetch_one(pool)
+    .await?;
+
+    sqlx::query("UPDATE golden_sets SET updated_at = datetime('now') WHERE id = ?")
+        .bind(&input.set_id)
+        .execute(pool)
+        .await?;
+
+    Ok(query)
+}
+
+/// List the queries in a golden set. Oldest first.
+pub async fn list_golden_queries(pool: &DbPool, set_id: &str) -> Result<Vec<GoldenQuery>> {
+    sqlx::query_as::<_, GoldenQuery>(
+        "SELECT * FROM golden_queries WHERE set_id = ? ORDER BY created_at, id",
+    )
+    .bind(set_id)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Remove a query from a golden set. Returns false if it was not in the set.
+pub async fn delete_golden_query(pool: &DbPool, set_id: &str, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM golden_queries WHERE id = ? AND set_id = ?")
+        .bind(id)
+        .bind(set_id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() > 0 {
+        sqlx::query("UPDATE golden_sets SET updated_at = datetime('now') WHERE id = ?")
+            .bind(set_id)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(result.rows_affected() > 0)
+}
+
+// ============================================================================
+// Evaluation History Queries
+// ============================================================================
+
+/// Record an evaluation run.
+pub async fn create_search_evaluation(
+    pool: &DbPool,
+    input: CreateSearchEvaluation,
+) -> Result<SearchEvaluation> {
+    sqlx::query_as::<_, SearchEvaluation>(
+        r#"
+        INSERT INTO search_evaluations (
+            id, project_id, set_id, job_id, k, config, query_count,
+            recall_at_k, mrr, ndcg, details
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.set_id)
+    .bind(&input.job_id)
+    .bind(input.k)
+    .bind(input.config.to_string())
+    .bind(input.query_count)
+    .bind(input.recall_at_k)
+    .bind(input.mrr)
+    .bind(input.ndcg)
+    .bind(input.details.to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List evaluation runs for a project, newest first, optionally for one set.
+pub async fn list_search_evaluations(
+    pool: &DbPool,
+    project_id: &str,
+    set_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<SearchEvaluation>> {
+    sqlx::query_as::<_, SearchEvaluation>(
+        r#"
+        SELECT * FROM search_evaluations
+        WHERE project_id = ? AND (? IS NULL OR set_id = ?)
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(set_id)
+    .bind(set_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_golden_set_and_queries() {
+        let pool = setup_test_db().await;
+
+        let set = create_golden_set(
+            &pool,
+            CreateGoldenSet {
+                id: "set-1".to_string(),
+                project_id: "proj-1".to_string(),
+                name: "auth".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let duplicate = create_golden_set(
+            &pool,
+            CreateGoldenSet {
+                id: "set-2".to_string(),
+                project_id: "proj-1".to_string(),
+                name: "auth".to_string(),
+                description: None,
+            },
+        )
+        .await;
+        assert!(matches!(duplicate, Err(Error::AlreadyExists(_))));
+
+        create_golden_query(
+            &pool,
+            CreateGoldenQuery {
+                id: "q-1".to_string(),
+                set_id: set.id.clone(),
+                query: "how are tokens refreshed".to_string(),
+                expected_memory_ids: vec!["mem-a".to_string()],
+                expected_file_paths: vec!["src/auth.rs".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+
+        let queries = list_golden_queries(&pool, &set.id).await.unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].expected_memory_ids_vec(), vec!["mem-a"]);
+        assert_eq!(queries[0].expected_file_paths_vec(), vec!["src/auth.rs"]);
+
+        assert!(!delete_golden_query(&pool, "other-set", "q-1")
+            .await
+            .unwrap());
+        assert!(delete_golden_query(&pool, &set.id, "q-1").await.unwrap());
+        assert!(list_golden_queries(&pool, &set.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_history() {
+        let pool = setup_test_db().await;
+
+        create_golden_set(
+            &pool,
+            CreateGoldenSet {
+                id: "set-1".to_string(),
+                project_id: "proj-1".to_string(),
+                name: "auth".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for (id, recall) in [("eval-1", 0.5), ("eval-2", 0.75)] {
+            create_search_evaluation(
+                &pool,
+                CreateSearchEvaluation {
+                    id: id.to_string(),
+                    project_id: "proj-1".to_string(),
+                    set_id: "set-1".to_string(),
+                    job_id: None,
+                    k: 10,
+                    config: serde_json::json!({ "strength_weight": 0.3 }),
+                    query_count: 4,
+                    recall_at_k: recall,
+                    mrr: 0.5,
+                    ndcg: 0.6,
+                    details: serde_json::json!([]),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let history = list_search_evaluations(&pool, "proj-1", Some("set-1"), 10)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, "eval-2");
+
+        let none = list_search_evaluations(&pool, "proj-1", Some("set-x"), 10)
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+
+        // History goes with the set
+        delete_golden_set(&pool, "set-1").await.unwrap();
+        let history = list_search_evaluations(&pool, "proj-1", None, 10)
+            .await
+            .unwrap();
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,477 @@
+//! Search query log database queries.
+//!
+//! Every project search is logged with its filters, result count, best
+//! score and latency, so analytics can show what agents look for and fail
+//! to find. Knowledge gap reports built from the log are stored alongside.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{query_hash, DbPool};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Input for logging a search.
+#[derive(Debug, Clone)]
+pub struct LogSearchQuery {
+    pub project_id: String,
+    /// Where the search came from: "api" or "mcp"
+    pub source: String,
+    pub query: String,
+    pub token_id: Option<String>,
+    pub filters: serde_json::Value,
+    pub result_count: i64,
+    /// Best raw similarity among candidates, before filtering
+    pub top_score: Option<f64>,
+    pub result_ids: Vec<String>,
+    pub latency_ms: i64,
+}
+
+impl LogSearchQuery {
+    /// Log entry for a search with no results yet.
+    pub fn new(
+        project_id: impl Into<String>,
+        source: impl Into<String>,
+        query: impl Into<String>,
+    ) -> Self {
+        Self {
+            project_id: project_id.into(),
+            source: source.into(),
+            query: query.into(),
+            token_id: None,
+            filters: serde_json::json!({}),
+            result_count: 0,
+            top_score: None,
+            result_ids: Vec::new(),
+            latency_ms: 0,
+        }
+    }
+
+    /// Set the token that searched.
+    pub fn with_token(mut self, token_id: impl Into<String>) -> Self {
+        self.token_id = Some(token_id.into());
+        self
+    }
+
+    /// Set the filters the search ran with.
+    pub fn with_filters(mut self, filters: serde_json::Value) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Set the returned memory IDs, in rank order, and the best raw score.
+    pub fn with_results(mut self, result_ids: Vec<String>, top_score: Option<f32>) -> Self {
+        self.result_count = result_ids.len() as i64;
+        self.result_ids = result_ids;
+        self.top_score = top_score.map(f64::from);
+        self
+    }
+
+    /// Set the time taken.
+    pub fn with_latency(mut self, elapsed: std::time::Duration) -> Self {
+        self.latency_ms = elapsed.as_millis() as i64;
+        self
+    }
+}
+
+/// Totals over a window of the search log.
+#[derive(Debug, Clone, Default, FromRow, Serialize)]
+pub struct SearchLogTotals {
+    pub total_queries: i64,
+    pub zero_result_queries: i64,
+    /// Queries that returned results, but none scoring above the threshold
+    pub low_score_queries: i64,
+    pub avg_latency_ms: Option<f64>,
+}
+
+/// A query (normalised for case and whitespace) and how it fared.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct QueryStat {
+    pub query: String,
+    pub count: i64,
+    pub zero_results: i64,
+    pub avg_top_score: Option<f64>,
+    pub last_seen: String,
+}
+
+/// A memory and how often searches returned it.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RetrievedMemory {
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub memory_type: Option<String>,
+    pub count: i64,
+}
+
+/// Stored knowledge gap report.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct KnowledgeGapReportRow {
+    pub id: String,
+    pub project_id: String,
+    pub job_id: Option<String>,
+    pub window_days: i64,
+    pub clustered: i32,
+    /// JSON report
+    pub report: String,
+    pub created_at: String,
+}
+
+/// Input for storing a knowledge gap report.
+#[derive(Debug, Clone)]
+pub struct CreateKnowledgeGapReport {
+    pub id: String,
+    pub project_id: String,
+    pub job_id: Option<String>,
+    pub window_days: i64,
+    pub clustered: bool,
+    pub report: serde_json::Value,
+}
+
+fn window(days: i64) -> String {
+    format!("-{} days", days)
+}
+
+// ============================================================================
+// Query Log
+// ============================================================================
+
+/// Log a search.
+pub async fn log_search_query(pool: &DbPool, input: LogSearchQuery) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO search_queries (
+            id, project_id, token_id, source, query, query_hash, filters,
+            result_count, top_score, result_ids, latency_ms
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(crate::models::new_id())
+    .bind(&input.project_id)
+    .bind(&input.token_id)
+    .bind(&input.source)
+    .bind(&input.query)
+    .bind(query_hash(&input.query))
+    .bind(input.filters.to_string())
+    .bind(input.result_count)
+    .bind(input.top_score)
+    .bind(serde_json::to_string(&input.result_ids)?)
+    .bind(input.latency_ms)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete log entries older than the given number of days.
+pub async fn purge_search_queries(pool: &DbPool, older_than_days: i64) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM search_queries WHERE created_at < datetime('now', ?)")
+        .bind(window(older_than_days))
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+// ============================================================================
+// Analytics
+// ============================================================================
+
+/// Totals for a project's searches in the last `days` days.
+pub async fn get_search_log_totals(
+    pool: &DbPool,
+    project_id: &str,
+    days: i64,
+    low_score: f64,
+) -> Result<SearchLogTotals> {
+    sqlx::query_as::<_, SearchLogTotals>(
+        r#"
+        SELECT
+            COUNT(*) AS total_queries,
+            COALESCE(SUM(result_count = 0), 0) AS zero_result_queries,
+            COALESCE(SUM(result_count > 0 AND top_score < ?), 0) AS low_score_queries,
+            AVG(latency_ms) AS avg_latency_ms
+        FROM search_queries
+        WHERE project_id = ? AND created_at > datetime('now', ?)
+        "#,
+    )
+    .bind(low_score)
+    .bind(project_id)
+    .bind(window(days))
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Most frequent queries in the last `days` days.
+pub async fn list_top_queries(
+    pool: &DbPool,
+    project_id: &str,
+    days: i64,
+    limit: i64,
+) -> Result<Vec<QueryStat>> {
+    sqlx::query_as::<_, QueryStat>(
+        r#"
+        SELECT MAX(query) AS query, COUNT(*) AS count,
+               SUM(result_count = 0) AS zero_results,
+               AVG(top_score) AS avg_top_score,
+               MAX(created_at) AS last_seen
+        FROM search_queries
+        WHERE project_id = ? AND created_at > datetime('now', ?)
+        GROUP BY query_hash
+        ORDER BY count DESC, last_seen DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(window(days))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Queries in the last `days` days that found nothing, or nothing scoring
+/// at least `low_score`. Most frequent first.
+pub async fn list_poor_queries(
+    pool: &DbPool,
+    project_id: &str,
+    days: i64,
+    low_score: f64,
+    limit: i64,
+) -> Result<Vec<QueryStat>> {
+    sqlx::query_as::<_, QueryStat>(
+        r#"
+        SELECT MAX(query) AS query, COUNT(*) AS count,
+               SUM(result_count = 0) AS zero_results,
+               AVG(top_score) AS avg_top_score,
+               MAX(created_at) AS last_seen
+        FROM search_queries
+        WHERE project_id = ? AND created_at > datetime('now', ?)
+          AND (result_count = 0 OR top_score IS NULL OR top_score < ?)
+        GROUP BY query_hash
+        ORDER BY count DESC, last_seen DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(window(days))
+    .bind(low_score)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Memories returned most often in the last `days` days.
+pub async fn list_top_retrieved_memories(
+    pool: &DbPool,
+    project_id: &str,
+    days: i64,
+    limit: i64,
+) -> Result<Vec<RetrievedMemory>> {
+    sqlx::query_as::<_, RetrievedMemory>(
+        r#"
+        SELECT r.value AS memory_id, m.title, m.type AS memory_type, COUNT(*) AS count
+        FROM search_queries q, json_each(q.result_ids) r
+        JOIN memories m ON m.id = r.value
+        WHERE q.project_id = ? AND q.created_at > datetime('now', ?)
+        GROUP BY r.value
+        ORDER BY count DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(window(days))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+// ============================================================================
+// Knowledge Gap Reports
+// ============================================================================
+
+/// Store a knowledge gap report.
+pub async fn create_knowledge_gap_report(
+    pool: &DbPool,
+    input: CreateKnowledgeGapReport,
+) -> Result<KnowledgeGapReportRow> {
+    sqlx::query_as::<_, KnowledgeGapReportRow>(
+        r#"
+        INSERT INTO knowledge_gap_reports (id, project_id, job_id, window_days, clustered, report)
+        VALUES (?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.job_id)
+    .bind(input.window_days)
+    .bind(input.clustered)
+    .bind(input.report.to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// List a project's knowledge gap reports, newest first.
+pub async fn list_knowledge_gap_reports(
+    pool: &DbPool,
+    project_id: &str,
+    limit: i64,
+) -> Result<Vec<KnowledgeGapReportRow>> {
+    sqlx::query_as::<_, KnowledgeGapReportRow>(
+        r#"
+        SELECT * FROM knowledge_gap_reports
+        WHERE project_id = ?
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Projects searched in the last `interval_days` days without a knowledge
+/// gap report job in that time.
+pub async fn list_projects_due_gap_report(
+    pool: &DbPool,
+    interval_days: i64,
+) -> Result<Vec<String>> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT q.project_id FROM search_queries q
+        WHERE q.created_at > datetime('now', ?)
+          AND NOT EXISTS (
+              SELECT 1 FROM jobs j
+              WHERE j.project_id = q.project_id
+                AND j.type = 'knowledge_gap_report'
+                AND j.created_at > datetime('now', ?)
+          )
+        "#,
+    )
+    .bind(window(interval_days))
+    .bind(window(interval_days))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO memories (id, project_id, type, title) VALUES ('mem-1', 'proj-1', 'decision', 'Retry policy')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn log(pool: &DbPool, query: &str, top_score: Option<f32>, result_ids: &[&str]) {
+        let ids = result_ids.iter().map(|s| s.to_string()).collect();
+        log_search_query(
+            pool,
+            LogSearchQuery::new("proj-1", "api", query).with_results(ids, top_score),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_analytics() {
+        let pool = setup_test_db().await;
+
+        log(&pool, "retry policy", Some(0.82), &["mem-1"]).await;
+        log(&pool, "Retry  Policy", Some(0.80), &["mem-1"]).await;
+        log(&pool, "billing webhooks", Some(0.31), &[]).await;
+        log(&pool, "oauth scopes", Some(0.45), &["mem-1"]).await;
+
+        let totals = get_search_log_totals(&pool, "proj-1", 30, 0.5)
+            .await
+            .unwrap();
+        assert_eq!(totals.total_queries, 4);
+        assert_eq!(totals.zero_result_queries, 1);
+        assert_eq!(totals.low_score_queries, 1);
+
+        // Case and whitespace variants group together
+        let top = list_top_queries(&pool, "proj-1", 30, 10).await.unwrap();
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].count, 2);
+
+        let poor = list_poor_queries(&pool, "proj-1", 30, 0.5, 10)
+            .await
+            .unwrap();
+        let mut queries: Vec<&str> = poor.iter().map(|q| q.query.as_str()).collect();
+        queries.sort();
+        assert_eq!(queries, vec!["billing webhooks", "oauth scopes"]);
+
+        let memories = list_top_retrieved_memories(&pool, "proj-1", 30, 10)
+            .await
+            .unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].count, 3);
+        assert_eq!(memories[0].title.as_deref(), Some("Retry policy"));
+
+        let due = list_projects_due_gap_report(&pool, 7).await.unwrap();
+        assert_eq!(due, vec!["proj-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_gap_reports() {
+        let pool = setup_test_db().await;
+
+        create_knowledge_gap_report(
+            &pool,
+            CreateKnowledgeGapReport {
+                id: "gap-1".to_string(),
+                project_id: "proj-1".to_string(),
+                job_id: None,
+                window_days: 7,
+                clustered: false,
+                report: serde_json::json!({ "gaps": [] }),
+            },
+        )
+        .await
+        .unwrap();
+
+        let reports = list_knowledge_gap_reports(&pool, "proj-1", 10)
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].window_days, 7);
+    }
+}
@@ -0,0 +1,332 @@
+//! Search settings database queries.
+//!
+//! Per-project switches for LLM query rewriting and the cache of rewrites
+//! already generated for a query.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Default number of paraphrases generated by query expansion.
+pub const DEFAULT_EXPANSION_COUNT: i64 = 3;
+
+/// Cached rewrites older than this are regenerated.
+const REWRITE_CACHE_DAYS: i64 = 30;
+
+/// Per-project search settings.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SearchSettings {
+    pub project_id: String,
+    pub query_expansion: i32,
+    pub hyde: i32,
+    pub expansion_count: i64,
+    pub updated_at: String,
+}
+
+impl SearchSettings {
+    /// Settings used when a project has never been configured.
+    pub fn defaults(project_id: &str) -> Self {
+        Self {
+            project_id: project_id.to_string(),
+            query_expansion: 0,
+            hyde: 0,
+            expansion_count: DEFAULT_EXPANSION_COUNT,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Check if multi-query expansion is enabled.
+    pub fn is_query_expansion(&self) -> bool {
+        self.query_expansion != 0
+    }
+
+    /// Check if HyDE (hypothetical document embedding) is enabled.
+    pub fn is_hyde(&self) -> bool {
+        self.hyde != 0
+    }
+}
+
+/// Input for updating search settings. `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateSearchSettings {
+    pub query_expansion: Option<bool>,
+    pub hyde: Option<bool>,
+    pub expansion_count: Option<i64>,
+}
+
+/// Kind of query rewrite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RewriteKind {
+    /// Paraphrases of the query
+    Expand,
+    /// A hypothetical passage answering the query
+    Hyde,
+}
+
+impl RewriteKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Expand => "expand",
+            Self::Hyde => "hyde",
+        }
+    }
+}
+
+/// Cache key for a query: whitespace and case differences map to the same key.
+pub fn query_hash(query: &str) -> String {
+    let normalized = query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+// ============================================================================
+// Settings Queries
+// ============================================================================
+
+/// Get search settings for a project, falling back to defaults.
+pub async fn get_search_settings(pool: &DbPool, project_id: &str) -> Result<SearchSettings> {
+    let settings =
+        sqlx::query_as::<_, SearchSettings>("SELECT * FROM search_settings WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(settings.unwrap_or_else(|| SearchSettings::defaults(project_id)))
+}
+
+/// Update search settings, creating the row if needed.
+pub async fn update_search_settings(
+    pool: &DbPool,
+    project_id: &str,
+    input: UpdateSearchSettings,
+) -> Result<SearchSettings> {
+    sqlx::query_as::<_, SearchSettings>(
+        r#"
+        INSERT INTO search_settings (project_id, query_expansion, hyde, expansion_count)
+        VALUES (?, COALESCE(?, 0), COALESCE(?, 0), COALESCE(?, 3))
+        ON CONFLICT(project_id) DO UPDATE SET
+            query_expansion = COALESCE(?, query_expansion),
+            hyde = COALESCE(?, hyde),
+            expansion_count = COALESCE(?, expansion_count),
+            updated_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(project_id)
+    .bind(input.query_expansion)
+    .bind(input.hyde)
+    .bind(input.expansion_count)
+    .bind(input.query_expansion)
+    .bind(input.hyde)
+    .bind(input.expansion_count)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+// ============================================================================
+// Rewrite Cache Queries
+// ============================================================================
+
+/// Get cached rewrites for a query, if fresh.
+pub async fn get_query_rewrites(
+    pool: &DbPool,
+    project_id: &str,
+    query: &str,
+    kind: RewriteKind,
+) -> Result<Option<Vec<String>>> {
+    let rewrites: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT rewrites FROM query_rewrites
+        WHERE project_id = ? AND query_hash = ? AND kind = ?
+          AND created_at > datetime('now', ?)
+        "#,
+    )
+    .bind(project_id)
+    .bind(query_hash(query))
+    .bind(kind.as_str())
+    .bind(format!("-{} days", REWRITE_CACHE_DAYS))
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rewrites.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+/// Cache rewrites for a query, replacing any earlier entry.
+pub async fn store_query_rewrites(
+    pool: &DbPool,
+    project_id: &str,
+    query: &str,
+    kind: RewriteKind,
+    rewrites: &[String],
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO query_rewrites (project_id, query_hash, kind, query, rewrites)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(project_id, query_hash, kind) DO UPDATE SET
+            query = excluded.query,
+            rewrites = excluded.rewrites,
+            created_at = datetime('now')
+        "#,
+    )
+    .bind(project_id)
+    .bind(query_hash(query))
+    .bind(kind.as_str())
+    .bind(query)
+    .bind(serde_json::to_string(rewrites)?)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete cached rewrites too old to be served.
+pub async fn purge_query_rewrites(pool: &DbPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM query_rewrites WHERE created_at <= datetime('now', ?)")
+        .bind(format!("-{} days", REWRITE_CACHE_DAYS))
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_settings_default_and_update() {
+        let pool = setup_test_db().await;
+
+        let settings = get_search_settings(&pool, "proj-1").await.unwrap();
+        assert!(!settings.is_query_expansion());
+        assert!(!settings.is_hyde());
+        assert_eq!(settings.expansion_count, DEFAULT_EXPANSION_COUNT);
+
+        let settings = update_search_settings(
+            &pool,
+            "proj-1",
+            UpdateSearchSettings {
+                hyde: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(settings.is_hyde());
+        assert!(!settings.is_query_expansion());
+
+        let settings = update_search_settings(
+            &pool,
+            "proj-1",
+            UpdateSearchSettings {
+                query_expansion: Some(true),
+                expansion_count: Some(5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(settings.is_hyde());
+        assert!(settings.is_query_expansion());
+        assert_eq!(settings.expansion_count, 5);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_cache_round_trip() {
+        let pool = setup_test_db().await;
+        let rewrites = vec![
+            "auth token refresh".to_string(),
+            "session renewal".to_string(),
+        ];
+
+        store_query_rewrites(
+            &pool,
+            "proj-1",
+            "How does login work?",
+            RewriteKind::Expand,
+            &rewrites,
+        )
+        .await
+        .unwrap();
+
+        // Case and whitespace differences hit the same entry
+        let cached = get_query_rewrites(
+            &pool,
+            "proj-1",
+            "how does  LOGIN work?",
+            RewriteKind::Expand,
+        )
+        .await
+        .unwrap();
+        assert_eq!(cached, Some(rewrites));
+
+        let other_kind =
+            get_query_rewrites(&pool, "proj-1", "How does login work?", RewriteKind::Hyde)
+                .await
+                .unwrap();
+        assert!(other_kind.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_purge_query_rewrites() {
+        let pool = setup_test_db().await;
+        let rewrites = vec!["session renewal".to_string()];
+
+        for query in ["fresh query", "stale query"] {
+            store_query_rewrites(&pool, "proj-1", query, RewriteKind::Expand, &rewrites)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "UPDATE query_rewrites SET created_at = datetime('now', ?) WHERE query = 'stale query'",
+        )
+        .bind(format!("-{} days", REWRITE_CACHE_DAYS + 1))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(purge_query_rewrites(&pool).await.unwrap(), 1);
+
+        let fresh = get_query_rewrites(&pool, "proj-1", "fresh query", RewriteKind::Expand)
+            .await
+            .unwrap();
+        assert_eq!(fresh, Some(rewrites));
+    }
+}
@@ -0,0 +1,231 @@
+//! Encryption of stored secrets at rest.
+//!
+//! Secrets written before a master key was configured are stored in
+//! plaintext, and secrets sealed under a retired master key must be moved to
+//! the current one. `encrypt_stored_secrets` runs on startup and brings every
+//! secret column under the current key.
+
+use tracing::{info, warn};
+
+use crate::secrets::{self, Keyring};
+use crate::Result;
+
+use super::DbPool;
+
+/// Tables and the secret columns they hold.
+const SECRET_COLUMNS: &[(&str, &[&str])] = &[
+    (
+        "llm_providers",
+        &[
+            "api_key",
+            "oauth_client_secret",
+            "oauth_access_token",
+            "oauth_refresh_token",
+        ],
+    ),
+    (
+        "embedding_providers",
+        &[
+            "api_key",
+            "oauth_client_secret",
+            "oauth_access_token",
+            "oauth_refresh_token",
+        ],
+    ),
+    ("project_providers", &["api_key"]),
+    (
+        "projects",
+        &["access_token", "metadata_repo_token", "webhook_secret"],
+    ),
+    ("webhook_registrations", &["secret"]),
+];
+
+/// Encrypt plaintext secrets and re-wrap secrets sealed under retired keys.
+///
+/// Does nothing (beyond a warning when plaintext secrets exist) if no master
+/// key is configured.
+pub async fn encrypt_stored_secrets(pool: &DbPool) -> Result<()> {
+    let Some(keyring) = secrets::keyring() else {
+        if count_plaintext_secrets(pool).await? > 0 {
+            warn!("Secrets are stored in plaintext. Set FOLD_MASTER_KEY to encrypt them at rest");
+        }
+        return Ok(());
+    };
+
+    let updated = reencrypt_secrets(pool, keyring).await?;
+    if updated > 0 {
+        info!(
+            updated,
+            key_id = keyring.current_id(),
+            "Encrypted stored secrets under the current master key"
+        );
+    }
+
+    Ok(())
+}
+
+/// Bring every stored secret under the keyring's current key.
+///
+/// Returns the number of values rewritten.
+pub async fn reencrypt_secrets(pool: &DbPool, keyring: &Keyring) -> Result<usize> {
+    let mut updated = 0;
+    let mut tx = pool.begin().await?;
+
+    for (table, columns) in SECRET_COLUMNS {
+        for column in *columns {
+            let rows: Vec<(String, String)> = sqlx::query_as(&format!(
+                "SELECT id, {column} FROM {table} WHERE {column} IS NOT NULL AND {column} != ''"
+            ))
+            .fetch_all(&mut *tx)
+            .await?;
+
+            for (id, value) in rows {
+                if !keyring.needs_rewrap(&value) {
+                    continue;
+                }
+
+                sqlx::query(&format!("UPDATE {table} SET {column} = ? WHERE id = ?"))
+                    .bind(keyring.rewrap(&value)?)
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+                updated += 1;
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+/// Count secret values that are not encrypted.
+async fn count_plaintext_secrets(pool: &DbPool) -> Result<usize> {
+    let mut count = 0;
+
+    for (table, columns) in SECRET_COLUMNS {
+        for column in *columns {
+            let values: Vec<(String,)> = sqlx::query_as(&format!(
+                "SELECT {column} FROM {table} WHERE {column} IS NOT NULL AND {column} != ''"
+            ))
+            .fetch_all(pool)
+            .await?;
+            count += values.iter().filter(|(v,)| !secrets::is_sealed(v)).count();
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        create_project, get_llm_provider_by_name, get_project, get_webhook_secret, init_pool,
+        migrate, CreateProject,
+    };
+    use crate::secrets::MasterKey;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn stored(pool: &DbPool, query: &str) -> String {
+        let (value,): (String,) = sqlx::query_as(query).fetch_one(pool).await.unwrap();
+        value
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_plaintext_rows() {
+        let pool = setup_test_db().await;
+
+        // Rows written before a master key was configured
+        sqlx::query(
+            "UPDATE projects SET access_token = 'ghp_plain', webhook_secret = 'whsec_plain' \
+             WHERE id = 'proj-1'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO llm_providers (id, name, auth_type, api_key, config) \
+             VALUES ('llm-1', 'openai', 'api_key', 'sk-plain', '{}')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let keyring = secrets::keyring().unwrap();
+        assert_eq!(reencrypt_secrets(&pool, keyring).await.unwrap(), 3);
+        // Idempotent
+        assert_eq!(reencrypt_secrets(&pool, keyring).await.unwrap(), 0);
+
+        let token = stored(&pool, "SELECT access_token FROM projects").await;
+        assert!(secrets::is_sealed(&token));
+        assert!(!token.contains("ghp_plain"));
+        let webhook_secret = stored(&pool, "SELECT webhook_secret FROM projects").await;
+        assert!(secrets::is_sealed(&webhook_secret));
+
+        // Reads decrypt transparently
+        let project = get_project(&pool, "proj-1").await.unwrap();
+        assert_eq!(project.access_token.as_deref(), Some("ghp_plain"));
+        assert_eq!(
+            get_webhook_secret(&pool, "proj-1")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("whsec_plain")
+        );
+        let provider = get_llm_provider_by_name(&pool, "openai")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(provider.api_key.as_deref(), Some("sk-plain"));
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_after_rotation() {
+        let pool = setup_test_db().await;
+        let old = Keyring::new(MasterKey::from_bytes(&[1u8; 32]).unwrap(), Vec::new());
+
+        sqlx::query("UPDATE projects SET access_token = ? WHERE id = 'proj-1'")
+            .bind(old.encrypt("ghp_rotated").unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let rotated = Keyring::new(
+            MasterKey::from_bytes(&[2u8; 32]).unwrap(),
+            vec![MasterKey::from_bytes(&[1u8; 32]).unwrap()],
+        );
+        assert_eq!(reencrypt_secrets(&pool, &rotated).await.unwrap(), 1);
+
+        let token = stored(&pool, "SELECT access_token FROM projects").await;
+        assert!(!rotated.needs_rewrap(&token));
+        assert!(old.decrypt(&token).is_err());
+
+        let current_only = Keyring::new(MasterKey::from_bytes(&[2u8; 32]).unwrap(), Vec::new());
+        assert_eq!(current_only.decrypt(&token).unwrap(), "ghp_rotated");
+    }
+}
@@ -50,7 +50,10 @@ pub struct AiSession {
     pub project_id: String,
     pub task: String,
     pub status: String,
+    /// Not stored on ai_sessions; only filled by queries that join it in.
+    #[sqlx(default)]
     pub local_root: Option<String>,
+    #[sqlx(default)]
     pub repository_id: Option<String>,
     pub summary: Option<String>,
     pub next_steps: Option<String>, // JSON array
@@ -444,6 +447,35 @@ pub async fn list_ai_sessions_by_status(
     .map_err(Error::Database)
 }
 
+/// Insert an AI session exactly as given, keeping its status and timestamps.
+/// Used when importing a project archive. Existing sessions are left alone.
+pub async fn insert_ai_session_record(pool: &DbPool, session: &AiSession) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO ai_sessions (
+            id, project_id, task, status, summary, next_steps, agent_type,
+            created_at, updated_at, ended_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&session.id)
+    .bind(&session.project_id)
+    .bind(&session.task)
+    .bind(&session.status)
+    .bind(&session.summary)
+    .bind(&session.next_steps)
+    .bind(&session.agent_type)
+    .bind(&session.created_at)
+    .bind(&session.updated_at)
+    .bind(&session.ended_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 // ============================================================================
 // Session Note Queries
 // ============================================================================
@@ -466,6 +498,27 @@ pub async fn create_session_note(pool: &DbPool, input: CreateSessionNote) -> Res
     .map_err(Error::Database)
 }
 
+/// Insert a session note exactly as given, keeping its creation time.
+/// Used when importing a project archive. Existing notes are left alone.
+pub async fn insert_session_note_record(pool: &DbPool, note: &AiSessionNote) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO ai_session_notes (id, session_id, type, content, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&note.id)
+    .bind(&note.session_id)
+    .bind(&note.note_type)
+    .bind(&note.content)
+    .bind(&note.created_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 /// Get a session note by ID.
 pub async fn get_session_note(pool: &DbPool, id: &str) -> Result<AiSessionNote> {
     sqlx::query_as::<_, AiSessionNote>("SELECT * FROM ai_session_notes WHERE id = ?")
@@ -730,6 +783,21 @@ pub async fn delete_team_status(pool: &DbPool, project_id: &str, username: &str)
     Ok(())
 }
 
+/// List active team members not seen in the given number of minutes.
+pub async fn list_stale_team_members(pool: &DbPool, minutes: i64) -> Result<Vec<TeamStatus>> {
+    sqlx::query_as::<_, TeamStatus>(
+        r#"
+        SELECT * FROM team_status
+        WHERE status = 'active'
+        AND datetime(last_seen, '+' || ? || ' minutes') < datetime('now')
+        "#,
+    )
+    .bind(minutes)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
 /// Mark stale team members as idle (not seen in given minutes).
 pub async fn mark_stale_members_idle(pool: &DbPool, minutes: i64) -> Result<u64> {
     let result = sqlx::query(
@@ -933,4 +1001,39 @@ mod tests {
         let active = list_active_team_members(&pool, "proj-1").await.unwrap();
         assert_eq!(active.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_stale_team_members() {
+        let pool = setup_test_db().await;
+
+        upsert_team_status(
+            &pool,
+            "proj-1",
+            "bob",
+            UpdateTeamStatus {
+                status: TeamMemberStatus::Active,
+                current_task: None,
+                current_files: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(list_stale_team_members(&pool, 15).await.unwrap().is_empty());
+
+        sqlx::query("UPDATE team_status SET last_seen = datetime('now', '-1 hour')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stale = list_stale_team_members(&pool, 15).await.unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].username, "bob");
+
+        assert_eq!(mark_stale_members_idle(&pool, 15).await.unwrap(), 1);
+        assert!(list_active_team_members(&pool, "proj-1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,220 @@
+//! Transcript import database queries.
+//!
+//! Records which agent transcripts have already been ingested so that
+//! re-uploads and repeated watcher scans are idempotent.
+
+use std::collections::HashMap;
+
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Transcript import record.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TranscriptImport {
+    pub id: String,
+    pub project_id: String,
+    pub transcript_id: String,
+    pub memory_id: Option<String>,
+    pub source_path: Option<String>,
+    pub message_count: i64,
+    pub files_touched: Option<String>, // JSON array
+    pub imported_at: String,
+}
+
+impl TranscriptImport {
+    /// Parse files touched from JSON.
+    pub fn files_touched_vec(&self) -> Vec<String> {
+        self.files_touched
+            .as_ref()
+            .and_then(|f| serde_json::from_str(f).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Input for recording a transcript import.
+#[derive(Debug, Clone)]
+pub struct CreateTranscriptImport {
+    pub id: String,
+    pub project_id: String,
+    pub transcript_id: String,
+    pub memory_id: Option<String>,
+    pub source_path: Option<String>,
+    pub message_count: i64,
+    pub files_touched: Vec<String>,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Record a transcript import.
+pub async fn create_transcript_import(
+    pool: &DbPool,
+    input: CreateTranscriptImport,
+) -> Result<TranscriptImport> {
+    let files_touched = serde_json::to_string(&input.files_touched).ok();
+
+    sqlx::query_as::<_, TranscriptImport>(
+        r#"
+        INSERT INTO transcript_imports (
+            id, project_id, transcript_id, memory_id, source_path,
+            message_count, files_touched
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&input.transcript_id)
+    .bind(&input.memory_id)
+    .bind(&input.source_path)
+    .bind(input.message_count)
+    .bind(files_touched)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => Error::AlreadyExists(
+            format!("Transcript already imported: {}", input.transcript_id),
+        ),
+        _ => Error::Database(e),
+    })
+}
+
+/// Get the import record for a transcript, if it has been imported.
+pub async fn get_transcript_import(
+    pool: &DbPool,
+    project_id: &str,
+    transcript_id: &str,
+) -> Result<Option<TranscriptImport>> {
+    sqlx::query_as::<_, TranscriptImport>(
+        "SELECT * FROM transcript_imports WHERE project_id = ? AND transcript_id = ?",
+    )
+    .bind(project_id)
+    .bind(transcript_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+/// When each transcript was last imported into any project, by transcript ID.
+///
+/// Lets the watcher skip files it has already imported without reading them.
+pub async fn list_transcript_import_times(pool: &DbPool) -> Result<HashMap<String, DateTime<Utc>>> {
+    let rows = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+        r#"
+        SELECT transcript_id, MAX(imported_at)
+        FROM transcript_imports
+        GROUP BY transcript_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// List transcript imports for a project, newest first.
+pub async fn list_transcript_imports(
+    pool: &DbPool,
+    project_id: &str,
+    limit: i64,
+) -> Result<Vec<TranscriptImport>> {
+    sqlx::query_as::<_, TranscriptImport>(
+        r#"
+        SELECT * FROM transcript_imports
+        WHERE project_id = ?
+        ORDER BY imported_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_transcript_import_is_unique_per_project() {
+        let pool = setup_test_db().await;
+
+        let input = CreateTranscriptImport {
+            id: "imp-1".to_string(),
+            project_id: "proj-1".to_string(),
+            transcript_id: "session-abc".to_string(),
+            memory_id: None,
+            source_path: Some("/tmp/session-abc.jsonl".to_string()),
+            message_count: 12,
+            files_touched: vec!["src/main.rs".to_string()],
+        };
+
+        let created = create_transcript_import(&pool, input.clone()).await.unwrap();
+        assert_eq!(created.files_touched_vec(), vec!["src/main.rs"]);
+
+        let duplicate = create_transcript_import(
+            &pool,
+            CreateTranscriptImport {
+                id: "imp-2".to_string(),
+                ..input
+            },
+        )
+        .await;
+        assert!(matches!(duplicate, Err(Error::AlreadyExists(_))));
+
+        let fetched = get_transcript_import(&pool, "proj-1", "session-abc")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.id, "imp-1");
+        assert_eq!(fetched.message_count, 12);
+
+        assert!(get_transcript_import(&pool, "proj-1", "other")
+            .await
+            .unwrap()
+            .is_none());
+
+        let times = list_transcript_import_times(&pool).await.unwrap();
+        assert_eq!(times.len(), 1);
+        assert!(times.contains_key("session-abc"));
+    }
+}
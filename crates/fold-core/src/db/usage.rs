@@ -0,0 +1,256 @@
+//! Provider usage database queries.
+//!
+//! Daily counts of embedding and LLM calls per project, and per-project
+//! overrides of the configured daily quotas.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DbPool;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Kind of paid provider call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageKind {
+    Embedding,
+    Llm,
+}
+
+impl UsageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Embedding => "embedding",
+            Self::Llm => "llm",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "embedding" => Some(Self::Embedding),
+            "llm" => Some(Self::Llm),
+            _ => None,
+        }
+    }
+}
+
+/// Provider calls made for a project on one day.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProjectUsage {
+    pub project_id: String,
+    /// UTC date, YYYY-MM-DD
+    pub day: String,
+    pub embedding_calls: i64,
+    pub llm_calls: i64,
+}
+
+impl ProjectUsage {
+    /// Number of calls of the given kind.
+    pub fn calls(&self, kind: UsageKind) -> i64 {
+        match kind {
+            UsageKind::Embedding => self.embedding_calls,
+            UsageKind::Llm => self.llm_calls,
+        }
+    }
+}
+
+/// Per-project quota overrides. `None` falls back to the configured default,
+/// `Some(0)` means unlimited.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProjectQuota {
+    pub project_id: String,
+    pub daily_embedding_calls: Option<i64>,
+    pub daily_llm_calls: Option<i64>,
+    pub updated_at: String,
+}
+
+impl ProjectQuota {
+    /// Override for the given kind, if set.
+    pub fn limit(&self, kind: UsageKind) -> Option<i64> {
+        match kind {
+            UsageKind::Embedding => self.daily_embedding_calls,
+            UsageKind::Llm => self.daily_llm_calls,
+        }
+    }
+}
+
+// ============================================================================
+// Usage Queries
+// ============================================================================
+
+/// Add calls to today's usage for a project.
+pub async fn record_usage(
+    pool: &DbPool,
+    project_id: &str,
+    kind: UsageKind,
+    count: i64,
+) -> Result<()> {
+    let (embedding_calls, llm_calls) = match kind {
+        UsageKind::Embedding => (count, 0),
+        UsageKind::Llm => (0, count),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO project_usage (project_id, day, embedding_calls, llm_calls)
+        VALUES (?, date('now'), ?, ?)
+        ON CONFLICT(project_id, day) DO UPDATE SET
+            embedding_calls = embedding_calls + excluded.embedding_calls,
+            llm_calls = llm_calls + excluded.llm_calls
+        "#,
+    )
+    .bind(project_id)
+    .bind(embedding_calls)
+    .bind(llm_calls)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get today's usage for a project.
+pub async fn get_usage_today(pool: &DbPool, project_id: &str) -> Result<ProjectUsage> {
+    let usage = sqlx::query_as::<_, ProjectUsage>(
+        "SELECT * FROM project_usage WHERE project_id = ? AND day = date('now')",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(usage.unwrap_or_else(|| ProjectUsage {
+        project_id: project_id.to_string(),
+        day: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        embedding_calls: 0,
+        llm_calls: 0,
+    }))
+}
+
+/// List daily usage for a project over the last `days` days. Most recent first.
+pub async fn list_usage(pool: &DbPool, project_id: &str, days: i64) -> Result<Vec<ProjectUsage>> {
+    sqlx::query_as::<_, ProjectUsage>(
+        r#"
+        SELECT * FROM project_usage
+        WHERE project_id = ?
+          AND day > date('now', '-' || ? || ' days')
+        ORDER BY day DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(days)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+// ============================================================================
+// Quota Queries
+// ============================================================================
+
+/// Get quota overrides for a project, if any.
+pub async fn get_project_quota(pool: &DbPool, project_id: &str) -> Result<Option<ProjectQuota>> {
+    sqlx::query_as::<_, ProjectQuota>("SELECT * FROM project_quotas WHERE project_id = ?")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(Error::Database)
+}
+
+/// Set quota overrides for a project, replacing existing ones.
+pub async fn set_project_quota(
+    pool: &DbPool,
+    project_id: &str,
+    daily_embedding_calls: Option<i64>,
+    daily_llm_calls: Option<i64>,
+) -> Result<ProjectQuota> {
+    sqlx::query_as::<_, ProjectQuota>(
+        r#"
+        INSERT INTO project_quotas (project_id, daily_embedding_calls, daily_llm_calls)
+        VALUES (?, ?, ?)
+        ON CONFLICT(project_id) DO UPDATE SET
+            daily_embedding_calls = excluded.daily_embedding_calls,
+            daily_llm_calls = excluded.daily_llm_calls,
+            updated_at = datetime('now')
+        RETURNING *
+        "#,
+    )
+    .bind(project_id)
+    .bind(daily_embedding_calls)
+    .bind(daily_llm_calls)
+    .fetch_one(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_record_usage() {
+        let pool = setup_test_db().await;
+
+        let usage = get_usage_today(&pool, "proj-1").await.unwrap();
+        assert_eq!(usage.calls(UsageKind::Embedding), 0);
+
+        record_usage(&pool, "proj-1", UsageKind::Embedding, 1)
+            .await
+            .unwrap();
+        record_usage(&pool, "proj-1", UsageKind::Embedding, 2)
+            .await
+            .unwrap();
+        record_usage(&pool, "proj-1", UsageKind::Llm, 1)
+            .await
+            .unwrap();
+
+        let usage = get_usage_today(&pool, "proj-1").await.unwrap();
+        assert_eq!(usage.embedding_calls, 3);
+        assert_eq!(usage.llm_calls, 1);
+        assert_eq!(list_usage(&pool, "proj-1", 7).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_project_quota() {
+        let pool = setup_test_db().await;
+
+        assert!(get_project_quota(&pool, "proj-1").await.unwrap().is_none());
+
+        set_project_quota(&pool, "proj-1", Some(1000), None)
+            .await
+            .unwrap();
+        let quota = set_project_quota(&pool, "proj-1", Some(500), Some(0))
+            .await
+            .unwrap();
+        assert_eq!(quota.limit(UsageKind::Embedding), Some(500));
+        assert_eq!(quota.limit(UsageKind::Llm), Some(0));
+    }
+}
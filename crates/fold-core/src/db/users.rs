@@ -550,6 +550,38 @@ pub async fn delete_api_token(pool: &DbPool, id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Get the scopes granted to an API token.
+///
+/// Returns None for tokens created before scopes existed, which keep full access.
+pub async fn get_api_token_scopes(pool: &DbPool, token_id: &str) -> Result<Option<Vec<String>>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT scopes FROM api_token_scopes WHERE token_id = ?")
+            .bind(token_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(row.map(|(scopes,)| serde_json::from_str(&scopes).unwrap_or_default()))
+}
+
+/// Set the scopes granted to an API token, replacing any existing scopes.
+pub async fn set_api_token_scopes(pool: &DbPool, token_id: &str, scopes: &[String]) -> Result<()> {
+    let scopes_json = serde_json::to_string(scopes)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO api_token_scopes (token_id, scopes)
+        VALUES (?, ?)
+        ON CONFLICT(token_id) DO UPDATE SET scopes = excluded.scopes
+        "#,
+    )
+    .bind(token_id)
+    .bind(&scopes_json)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 /// List all API tokens for a user.
 /// Uses idx_api_tokens_user index.
 pub async fn list_user_api_tokens(pool: &DbPool, user_id: &str) -> Result<Vec<ApiToken>> {
@@ -668,4 +700,60 @@ mod tests {
         assert_eq!(sess.id, "session-1");
         assert_eq!(usr.id, user.id);
     }
+
+    #[tokio::test]
+    async fn test_api_token_scopes() {
+        let pool = setup_test_db().await;
+
+        create_user(
+            &pool,
+            CreateUser {
+                id: "user-1".to_string(),
+                provider: "google".to_string(),
+                subject: "sub-123".to_string(),
+                email: None,
+                display_name: None,
+                avatar_url: None,
+                role: UserRole::Member,
+            },
+        )
+        .await
+        .unwrap();
+
+        create_api_token(
+            &pool,
+            CreateApiToken {
+                id: "token-1".to_string(),
+                user_id: "user-1".to_string(),
+                name: "CI".to_string(),
+                token_hash: "hash-1".to_string(),
+                token_prefix: "abcd1234".to_string(),
+                project_ids: vec![],
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Legacy tokens have no scopes row
+        assert!(get_api_token_scopes(&pool, "token-1")
+            .await
+            .unwrap()
+            .is_none());
+
+        set_api_token_scopes(&pool, "token-1", &["memories:read".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            get_api_token_scopes(&pool, "token-1").await.unwrap(),
+            Some(vec!["memories:read".to_string()])
+        );
+
+        // Scopes go with the token
+        delete_api_token(&pool, "token-1").await.unwrap();
+        assert!(get_api_token_scopes(&pool, "token-1")
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
@@ -0,0 +1,382 @@
+//! Webhook registration database queries.
+//!
+//! Registrations cover both git provider hooks and user-registered outbound
+//! webhooks that receive a project's memory and link events. Delivery
+//! attempts live in `webhook_deliveries` (see the job queries).
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::FromRow;
+
+use crate::models::WebhookRegistration;
+use crate::{secrets, Error, Result};
+
+use super::{DbPool, WebhookDelivery};
+
+/// Database row for webhook registrations
+#[derive(Debug, FromRow)]
+struct WebhookRegistrationRow {
+    id: String,
+    project_id: Option<String>,
+    repository_id: Option<String>,
+    provider: String,
+    webhook_id: String,
+    target_url: Option<String>,
+    secret: String,
+    events: String,
+    active: bool,
+    created_by: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Parse a SQLite `datetime('now')` timestamp.
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|dt| dt.and_utc())
+        .or_else(|_| DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc)))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+impl TryFrom<WebhookRegistrationRow> for WebhookRegistration {
+    type Error = Error;
+
+    /// Decrypts the stored secret.
+    fn try_from(row: WebhookRegistrationRow) -> Result<Self> {
+        Ok(Self {
+            id: row.id,
+            project_id: row.project_id,
+            repository_id: row.repository_id,
+            provider: row.provider,
+            webhook_id: row.webhook_id,
+            target_url: row.target_url,
+            secret: secrets::decrypt(&row.secret)?,
+            events: row.events,
+            active: row.active,
+            created_by: row.created_by,
+            created_at: parse_timestamp(&row.created_at),
+            updated_at: parse_timestamp(&row.updated_at),
+        })
+    }
+}
+
+/// Input for registering an outbound webhook.
+#[derive(Debug, Clone)]
+pub struct CreateOutboundWebhook {
+    pub id: String,
+    pub project_id: String,
+    pub target_url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub created_by: Option<String>,
+}
+
+/// Input for updating an outbound webhook. `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOutboundWebhook {
+    pub target_url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Register an outbound webhook for a project.
+pub async fn create_outbound_webhook(
+    pool: &DbPool,
+    input: CreateOutboundWebhook,
+) -> Result<WebhookRegistration> {
+    let events_json = serde_json::to_string(&input.events)?;
+    let secret = secrets::encrypt_if_configured(&input.secret)?;
+
+    sqlx::query_as::<_, WebhookRegistrationRow>(
+        r#"
+        INSERT INTO webhook_registrations (
+            id, project_id, provider, webhook_id, target_url, secret, events, created_by
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(WebhookRegistration::OUTBOUND)
+    .bind(&input.id)
+    .bind(&input.target_url)
+    .bind(&secret)
+    .bind(&events_json)
+    .bind(&input.created_by)
+    .fetch_one(pool)
+    .await?
+    .try_into()
+}
+
+/// Get a webhook registration by ID.
+pub async fn get_webhook_registration(pool: &DbPool, id: &str) -> Result<WebhookRegistration> {
+    sqlx::query_as::<_, WebhookRegistrationRow>("SELECT * FROM webhook_registrations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Webhook not found: {}", id)))?
+        .try_into()
+}
+
+/// List a project's outbound webhooks, newest first.
+pub async fn list_outbound_webhooks(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<Vec<WebhookRegistration>> {
+    let rows = sqlx::query_as::<_, WebhookRegistrationRow>(
+        r#"
+        SELECT * FROM webhook_registrations
+        WHERE project_id = ? AND provider = ?
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .bind(WebhookRegistration::OUTBOUND)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(TryInto::try_into).collect()
+}
+
+/// List a project's active outbound webhooks.
+/// Uses idx_webhook_registrations_project index.
+pub async fn list_active_outbound_webhooks(
+    pool: &DbPool,
+    project_id: &str,
+) -> Result<Vec<WebhookRegistration>> {
+    let rows = sqlx::query_as::<_, WebhookRegistrationRow>(
+        r#"
+        SELECT * FROM webhook_registrations
+        WHERE project_id = ? AND provider = ? AND active = 1
+        "#,
+    )
+    .bind(project_id)
+    .bind(WebhookRegistration::OUTBOUND)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(TryInto::try_into).collect()
+}
+
+/// Update an outbound webhook.
+pub async fn update_outbound_webhook(
+    pool: &DbPool,
+    id: &str,
+    input: UpdateOutboundWebhook,
+) -> Result<WebhookRegistration> {
+    let events_json = input
+        .events
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+
+    sqlx::query_as::<_, WebhookRegistrationRow>(
+        r#"
+        UPDATE webhook_registrations SET
+            target_url = COALESCE(?, target_url),
+            events = COALESCE(?, events),
+            active = COALESCE(?, active),
+            updated_at = datetime('now')
+        WHERE id = ? AND provider = ?
+        RETURNING *
+        "#,
+    )
+    .bind(&input.target_url)
+    .bind(&events_json)
+    .bind(input.active)
+    .bind(id)
+    .bind(WebhookRegistration::OUTBOUND)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound(format!("Webhook not found: {}", id)))?
+    .try_into()
+}
+
+/// Delete a webhook registration. Its deliveries are removed with it.
+pub async fn delete_webhook_registration(pool: &DbPool, id: &str) -> Result<()> {
+    let result = sqlx::query("DELETE FROM webhook_registrations WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(Error::NotFound(format!("Webhook not found: {}", id)));
+    }
+
+    Ok(())
+}
+
+/// List recent deliveries for a webhook, newest first.
+pub async fn list_webhook_deliveries(
+    pool: &DbPool,
+    webhook_id: &str,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        SELECT * FROM webhook_deliveries
+        WHERE webhook_id = ?
+        ORDER BY created_at DESC, rowid DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(webhook_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(Error::Database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        create_project, create_webhook_delivery, init_pool, list_pending_webhook_deliveries,
+        migrate, update_webhook_delivery_attempt, CreateProject, CreateWebhookDelivery,
+    };
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn webhook(id: &str, events: &[&str]) -> CreateOutboundWebhook {
+        CreateOutboundWebhook {
+            id: id.to_string(),
+            project_id: "proj-1".to_string(),
+            target_url: "https://chat.example.com/hook".to_string(),
+            secret: "secret".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            created_by: Some("user-1".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outbound_webhook_crud() {
+        let pool = setup_test_db().await;
+
+        let created = create_outbound_webhook(&pool, webhook("wh-1", &["memory:*"]))
+            .await
+            .unwrap();
+        assert!(created.is_outbound());
+        assert!(created.active);
+        assert!(created.subscribes_to("memory:created"));
+        assert!(!created.subscribes_to("link:created"));
+
+        // The secret is encrypted at rest and decrypted on read
+        let (stored,): (String,) =
+            sqlx::query_as("SELECT secret FROM webhook_registrations WHERE id = 'wh-1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(crate::secrets::is_sealed(&stored));
+        assert_eq!(created.secret, "secret");
+
+        let updated = update_outbound_webhook(
+            &pool,
+            "wh-1",
+            UpdateOutboundWebhook {
+                active: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!updated.active);
+        assert_eq!(updated.events_vec(), vec!["memory:*"]);
+
+        create_outbound_webhook(&pool, webhook("wh-2", &[]))
+            .await
+            .unwrap();
+        assert_eq!(
+            list_outbound_webhooks(&pool, "proj-1").await.unwrap().len(),
+            2
+        );
+        let active = list_active_outbound_webhooks(&pool, "proj-1")
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, "wh-2");
+
+        delete_webhook_registration(&pool, "wh-1").await.unwrap();
+        assert!(get_webhook_registration(&pool, "wh-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retry_states() {
+        let pool = setup_test_db().await;
+        create_outbound_webhook(&pool, webhook("wh-1", &[]))
+            .await
+            .unwrap();
+
+        create_webhook_delivery(
+            &pool,
+            CreateWebhookDelivery {
+                id: "d-1".to_string(),
+                webhook_id: Some("wh-1".to_string()),
+                delivery_type: "memory:created".to_string(),
+                target_url: "https://chat.example.com/hook".to_string(),
+                payload: serde_json::json!({ "event": "memory:created" }),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            list_pending_webhook_deliveries(&pool, 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Retry scheduled in the future: still pending, not yet due
+        let retried = update_webhook_delivery_attempt(
+            &pool,
+            "d-1",
+            false,
+            Some("502"),
+            Some("2999-01-01 00:00:00"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(retried.status, "pending");
+        assert!(list_pending_webhook_deliveries(&pool, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Out of retries
+        let failed = update_webhook_delivery_attempt(&pool, "d-1", false, Some("502"), None)
+            .await
+            .unwrap();
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.attempts, 2);
+
+        let deliveries = list_webhook_deliveries(&pool, "wh-1", 10).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+    }
+}
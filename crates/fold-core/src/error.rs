@@ -3,7 +3,7 @@
 //! Uses thiserror for ergonomic error definitions that integrate
 //! with axum's response system.
 
-use axum::http::StatusCode;
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde_json::json;
@@ -81,6 +81,15 @@ pub enum Error {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    #[error("Too many requests, retry after {retry_after_secs} seconds")]
+    RateLimited { retry_after_secs: u64 },
+
+    #[error("Daily {quota} quota exceeded")]
+    QuotaExceeded {
+        quota: String,
+        retry_after_secs: u64,
+    },
+
     // Generic errors
     #[error("Internal error: {0}")]
     Internal(String),
@@ -118,7 +127,9 @@ impl Error {
             Self::FileTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
 
             // 429
-            Self::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            Self::RateLimitExceeded | Self::RateLimited { .. } | Self::QuotaExceeded { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
 
             // 501
             Self::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
@@ -159,11 +170,24 @@ impl Error {
             Self::InvalidFileType(_) => "INVALID_FILE_TYPE",
             Self::FileNotFound(_) => "FILE_NOT_FOUND",
             Self::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            Self::RateLimited { .. } => "RATE_LIMITED",
+            Self::QuotaExceeded { .. } => "QUOTA_EXCEEDED",
             Self::Internal(_) => "INTERNAL_ERROR",
             Self::NotImplemented(_) => "NOT_IMPLEMENTED",
             Self::Other(_) => "UNKNOWN_ERROR",
         }
     }
+
+    /// Seconds a client should wait before retrying, for 429 responses.
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::RateLimited { retry_after_secs }
+            | Self::QuotaExceeded {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
 }
 
 impl IntoResponse for Error {
@@ -171,6 +195,7 @@ impl IntoResponse for Error {
         let status = self.status_code();
         let code = self.error_code();
         let message = self.to_string();
+        let retry_after = self.retry_after();
 
         let body = Json(json!({
             "error": {
@@ -179,7 +204,13 @@ impl IntoResponse for Error {
             }
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -208,6 +239,16 @@ impl From<fold_qdrant::Error> for Error {
     }
 }
 
+impl From<fold_llm::Error> for Error {
+    fn from(err: fold_llm::Error) -> Self {
+        match err {
+            fold_llm::Error::RateLimitExceeded => Self::RateLimitExceeded,
+            fold_llm::Error::Llm(msg) => Self::Llm(msg),
+            other => Self::Llm(other.to_string()),
+        }
+    }
+}
+
 impl From<fold_storage::Error> for Error {
     fn from(err: fold_storage::Error) -> Self {
         match err {
@@ -8,6 +8,7 @@ pub mod db;
 pub mod error;
 pub mod middleware;
 pub mod models;
+pub mod secrets;
 pub mod services;
 pub mod state;
 
@@ -17,6 +17,7 @@ mod db;
 mod error;
 mod middleware;
 mod models;
+mod secrets;
 mod services;
 mod state;
 
@@ -69,6 +70,21 @@ async fn main() -> Result<()> {
     let _job_worker_handle = job_worker.start().await;
     tracing::info!("Background job worker started");
 
+    // Start transcript watcher (imports finished Claude Code sessions)
+    if config.transcripts.watch_enabled {
+        let _watcher_handle = state
+            .transcripts
+            .start_watcher(config.transcripts.clone());
+    }
+
+    // Start team presence sweeper (ages out idle members)
+    let _team_sweeper_handle = state
+        .team
+        .start_presence_sweeper(config.team.idle_minutes, config.team.sweep_interval_secs);
+
+    // Start outbound webhook delivery (memory and link events)
+    let _webhooks_handle = state.webhooks.start();
+
     // Start MCP session cleanup task
     api::mcp::start_session_cleanup();
     tracing::debug!("MCP session cleanup task started");
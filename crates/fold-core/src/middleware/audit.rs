@@ -0,0 +1,42 @@
+//! Audit actor extraction.
+//!
+//! Lets handlers take an `AuditActor` argument naming the user, and the
+//! token when one was used, from whichever auth middleware ran.
+
+use std::convert::Infallible;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::services::AuditActor;
+
+use super::{token_auth::AuthContext, AuthUser, ProjectAccessContext, SessionUser};
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AuditActor {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let extensions = &parts.extensions;
+
+        if let Some(auth) = extensions.get::<AuthContext>() {
+            return Ok(AuditActor::token(&auth.user_id, &auth.token_id));
+        }
+        if let Some(user) = extensions.get::<AuthUser>() {
+            return Ok(AuditActor::user(&user.user_id));
+        }
+        if let Some(user) = extensions.get::<SessionUser>() {
+            return Ok(AuditActor::user(&user.user_id));
+        }
+
+        Ok(extensions
+            .get::<ProjectAccessContext>()
+            .map(|ctx| AuditActor::user(&ctx.user_id))
+            .unwrap_or_default())
+    }
+}
+
+impl From<&AuthContext> for AuditActor {
+    fn from(auth: &AuthContext) -> Self {
+        AuditActor::token(&auth.user_id, &auth.token_id)
+    }
+}
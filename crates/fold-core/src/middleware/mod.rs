@@ -4,8 +4,14 @@
 //! - `token_auth` - API token validation for programmatic access (MCP, CLI, webhooks)
 //! - `session_auth` - Session/cookie validation for web UI access
 //! - `project_auth` - Project-level access control based on user/group membership
+//! - `scopes` - API token scope vocabulary and scope checks
+//! - `rate_limit` - Per-token request buckets and per-project provider quotas
+//! - `audit` - `AuditActor` extraction for audit logging
 
+mod audit;
 mod project_auth;
+mod rate_limit;
+pub mod scopes;
 mod session_auth;
 mod token_auth;
 
@@ -13,6 +19,11 @@ pub use project_auth::{
     require_admin, require_project_read, require_project_write, ProjectAccessContext,
     ProjectIdParams,
 };
+pub use rate_limit::{rate_limit_key, rate_limit_llm, rate_limit_search, rate_limit_writes};
+pub use scopes::{
+    require_audit_scope, require_projects_admin_scope, require_providers_admin_scope,
+    require_read_scope, require_users_admin_scope, TokenScopes,
+};
 pub use session_auth::{require_session, SessionUser, SESSION_COOKIE_NAME};
 pub use token_auth::{require_token, AuthContext};
 
@@ -116,6 +127,8 @@ pub async fn require_auth(
                             role, // Use user's actual role (supports admin API tokens)
                         };
                         req.extensions_mut().insert(auth_user);
+                        // Keep the token context so scope checks apply
+                        req.extensions_mut().insert(auth_context);
                         return Ok(next.run(req).await);
                     }
                 }
@@ -256,10 +269,15 @@ async fn validate_token_internal(state: &AppState, token: &str) -> Result<AuthCo
     // Look up user to get role
     let user = crate::db::get_user(&state.db, &token_row.user_id).await?;
 
+    let scopes = crate::db::get_api_token_scopes(&state.db, &token_row.id)
+        .await?
+        .map_or_else(TokenScopes::unrestricted, TokenScopes::new);
+
     Ok(AuthContext {
         token_id: token_row.id,
         user_id: token_row.user_id,
         is_admin: user.is_admin(),
+        scopes,
     })
 }
 
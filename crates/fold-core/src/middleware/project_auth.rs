@@ -13,7 +13,12 @@ use serde::Deserialize;
 use sqlx::FromRow;
 use tracing::{debug, warn};
 
-use crate::{error::Error, middleware::token_auth, services::PermissionService, AppState};
+use crate::{
+    error::Error,
+    middleware::{scopes, token_auth},
+    services::PermissionService,
+    AppState,
+};
 
 /// Context injected into requests for project-scoped operations.
 #[derive(Clone, Debug)]
@@ -34,12 +39,14 @@ pub struct ProjectIdParams {
 ///
 /// Extracts project ID from path params (supports both `project_id` and `id`)
 /// and checks if the user has read access via the permission service.
+/// Token requests also need the `memories:read` scope.
 ///
 /// Injects `ProjectAccessContext` into request extensions.
 ///
 /// # Errors
 ///
-/// Returns 403 Forbidden if the user lacks read access to the project.
+/// Returns 403 Forbidden if the user lacks read access to the project or the
+/// token lacks the scope.
 /// Returns 400 Bad Request if project_id cannot be extracted from path.
 pub async fn require_project_read(
     State(state): State<AppState>,
@@ -47,6 +54,8 @@ pub async fn require_project_read(
     mut req: Request<Body>,
     next: Next,
 ) -> Result<Response, Error> {
+    scopes::check_scope(&req, scopes::MEMORIES_READ)?;
+
     // Try to get AuthUser first (from session auth)
     let auth_user = if let Some(user) = req.extensions().get::<crate::middleware::AuthUser>() {
         user.clone()
@@ -113,12 +122,14 @@ pub async fn require_project_read(
 ///
 /// Extracts project ID from path params (supports both `project_id` and `id`)
 /// and checks if the user has write access via the permission service.
+/// Token requests also need the `memories:write` scope.
 ///
 /// Injects `ProjectAccessContext` into request extensions.
 ///
 /// # Errors
 ///
-/// Returns 403 Forbidden if the user lacks write access to the project.
+/// Returns 403 Forbidden if the user lacks write access to the project or the
+/// token lacks the scope.
 /// Returns 400 Bad Request if project_id cannot be extracted from path.
 pub async fn require_project_write(
     State(state): State<AppState>,
@@ -126,6 +137,8 @@ pub async fn require_project_write(
     mut req: Request<Body>,
     next: Next,
 ) -> Result<Response, Error> {
+    scopes::check_scope(&req, scopes::MEMORIES_WRITE)?;
+
     // Try to get AuthUser first (from session auth)
     let auth_user = if let Some(user) = req.extensions().get::<crate::middleware::AuthUser>() {
         user.clone()
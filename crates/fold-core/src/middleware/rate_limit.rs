@@ -0,0 +1,103 @@
+//! Rate limiting middleware.
+//!
+//! Applies `RateLimitService` buckets and daily provider quotas to a route
+//! class. Buckets are keyed by API token, or by user for session requests.
+//!
+//! Must run inside `require_project_read`/`require_project_write` so the
+//! project is known; without a project only the bucket is checked.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    error::Error,
+    middleware::{token_auth::AuthContext, AuthUser, ProjectAccessContext},
+    services::RouteClass,
+    AppState,
+};
+
+/// Bucket key for the caller: the token when one was used, otherwise the user.
+pub fn rate_limit_key(req: &Request<Body>) -> Option<String> {
+    let extensions = req.extensions();
+
+    if let Some(auth) = extensions.get::<AuthContext>() {
+        return Some(format!("token:{}", auth.token_id));
+    }
+    if let Some(user) = extensions.get::<AuthUser>() {
+        return Some(format!("user:{}", user.user_id));
+    }
+    extensions
+        .get::<ProjectAccessContext>()
+        .map(|ctx| format!("user:{}", ctx.user_id))
+}
+
+/// Caller key and project reference for a request.
+///
+/// Read up front because the request body is not `Sync`, so the request
+/// cannot be borrowed across an await.
+fn caller(req: &Request<Body>) -> (Option<String>, Option<String>) {
+    let project_ref = req
+        .extensions()
+        .get::<ProjectAccessContext>()
+        .map(|ctx| ctx.project_id.clone());
+
+    (rate_limit_key(req), project_ref)
+}
+
+async fn enforce(
+    state: &AppState,
+    class: RouteClass,
+    (key, project_ref): (Option<String>, Option<String>),
+) -> Result<(), Error> {
+    // The access context holds the ID or slug from the path
+    let project_id = match project_ref {
+        Some(project_ref) => Some(
+            crate::db::get_project_by_id_or_slug(&state.db, &project_ref)
+                .await?
+                .id,
+        ),
+        None => None,
+    };
+
+    state
+        .rate_limits
+        .check_request(class, key.as_deref(), project_id.as_deref())
+        .await
+}
+
+/// Middleware for search and context routes.
+pub async fn rate_limit_search(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, Error> {
+    let caller = caller(&req);
+    enforce(&state, RouteClass::Search, caller).await?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for memory write routes.
+pub async fn rate_limit_writes(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, Error> {
+    let caller = caller(&req);
+    enforce(&state, RouteClass::Write, caller).await?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for routes that call the LLM synchronously.
+pub async fn rate_limit_llm(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, Error> {
+    let caller = caller(&req);
+    enforce(&state, RouteClass::Llm, caller).await?;
+    Ok(next.run(req).await)
+}
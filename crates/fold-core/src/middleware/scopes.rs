@@ -0,0 +1,233 @@
+//! API token scopes.
+//!
+//! Scopes limit what a token may do on top of the owner's project
+//! permissions. A token never gains access its owner lacks.
+//!
+//! Vocabulary:
+//! - `memories:read` - List, read and search memories and project data
+//! - `memories:write` - Create, update and delete memories (implies `memories:read`)
+//! - `projects:admin` - Create, update and delete projects, members, config,
+//!   reindex and sync (implies `memories:write`)
+//! - `providers:admin` - Manage LLM and embedding providers
+//! - `users:admin` - Manage users and groups
+//! - `audit:read` - Read the audit log (admins only)
+//! - `mcp:tools:<name>` - Call a single MCP tool (`mcp:tools:*` for all)
+//! - `*` - Everything
+//!
+//! Tokens created before scopes existed have no scope record and keep full
+//! access. Session (web UI) requests are not scoped.
+
+use axum::{body::Body, extract::Request, http::Method, middleware::Next, response::Response};
+use tracing::warn;
+
+use crate::error::Error;
+
+use super::token_auth::AuthContext;
+
+pub const MEMORIES_READ: &str = "memories:read";
+pub const MEMORIES_WRITE: &str = "memories:write";
+pub const PROJECTS_ADMIN: &str = "projects:admin";
+pub const PROVIDERS_ADMIN: &str = "providers:admin";
+pub const USERS_ADMIN: &str = "users:admin";
+pub const AUDIT_READ: &str = "audit:read";
+pub const MCP_TOOLS_PREFIX: &str = "mcp:tools:";
+pub const ALL: &str = "*";
+
+/// Fixed scopes, excluding per-tool MCP scopes.
+pub const KNOWN_SCOPES: &[&str] = &[
+    MEMORIES_READ,
+    MEMORIES_WRITE,
+    PROJECTS_ADMIN,
+    PROVIDERS_ADMIN,
+    USERS_ADMIN,
+    AUDIT_READ,
+    ALL,
+];
+
+/// Check whether a string is a valid scope.
+pub fn is_valid_scope(scope: &str) -> bool {
+    KNOWN_SCOPES.contains(&scope)
+        || scope
+            .strip_prefix(MCP_TOOLS_PREFIX)
+            .is_some_and(|tool| !tool.is_empty())
+}
+
+/// Check whether a granted scope covers a required one.
+fn implies(granted: &str, required: &str) -> bool {
+    if granted == ALL || granted == required {
+        return true;
+    }
+
+    match granted {
+        PROJECTS_ADMIN => required == MEMORIES_WRITE || required == MEMORIES_READ,
+        MEMORIES_WRITE => required == MEMORIES_READ,
+        "mcp:tools:*" => required.starts_with(MCP_TOOLS_PREFIX),
+        _ => false,
+    }
+}
+
+/// Data scope an MCP tool needs when no per-tool scope is granted.
+pub fn tool_scope(tool: &str) -> &'static str {
+    match tool {
+        "github_project_create" => PROJECTS_ADMIN,
+        "memory_add" | "memory_update" | "memory_delete" | "memory_feedback" => MEMORIES_WRITE,
+        _ => MEMORIES_READ,
+    }
+}
+
+/// Scopes granted to a token.
+#[derive(Clone, Debug)]
+pub struct TokenScopes(Option<Vec<String>>);
+
+impl TokenScopes {
+    /// Full access, for tokens without a scope record.
+    pub fn unrestricted() -> Self {
+        Self(None)
+    }
+
+    /// Access limited to the given scopes.
+    pub fn new(scopes: Vec<String>) -> Self {
+        Self(Some(scopes))
+    }
+
+    /// Check whether a scope is granted, directly or by implication.
+    pub fn allows(&self, scope: &str) -> bool {
+        match &self.0 {
+            None => true,
+            Some(granted) => granted.iter().any(|g| implies(g, scope)),
+        }
+    }
+
+    /// Check whether an MCP tool may be called, either through its own
+    /// `mcp:tools:<name>` scope or the data scope the call needs.
+    pub fn allows_tool(&self, tool: &str, required: &str) -> bool {
+        self.allows(&format!("{}{}", MCP_TOOLS_PREFIX, tool)) || self.allows(required)
+    }
+}
+
+/// Check a request's token against a scope.
+///
+/// Requests without a token context (web UI sessions) pass.
+pub fn check_scope(req: &Request<Body>, scope: &str) -> Result<(), Error> {
+    let Some(auth) = req.extensions().get::<AuthContext>() else {
+        return Ok(());
+    };
+
+    if !auth.scopes.allows(scope) {
+        warn!(token_id = %auth.token_id, scope, "Access denied: token lacks scope");
+        return Err(Error::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Pick the scope for a request: `read` for GET and HEAD, `write` otherwise.
+fn scope_for_method<'a>(method: &Method, read: &'a str, write: &'a str) -> &'a str {
+    if method == Method::GET || method == Method::HEAD {
+        read
+    } else {
+        write
+    }
+}
+
+/// Middleware for project management routes.
+///
+/// Reads need `memories:read`, changes need `projects:admin`.
+pub async fn require_projects_admin_scope(
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, Error> {
+    check_scope(
+        &req,
+        scope_for_method(req.method(), MEMORIES_READ, PROJECTS_ADMIN),
+    )?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for provider management routes. Needs `providers:admin`.
+pub async fn require_providers_admin_scope(
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, Error> {
+    check_scope(&req, PROVIDERS_ADMIN)?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for user and group routes.
+///
+/// Reads need `memories:read`, changes need `users:admin`.
+pub async fn require_users_admin_scope(req: Request<Body>, next: Next) -> Result<Response, Error> {
+    check_scope(
+        &req,
+        scope_for_method(req.method(), MEMORIES_READ, USERS_ADMIN),
+    )?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for audit log routes. Needs `audit:read`.
+pub async fn require_audit_scope(req: Request<Body>, next: Next) -> Result<Response, Error> {
+    check_scope(&req, AUDIT_READ)?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware for read-only routes outside a project. Needs `memories:read`.
+pub async fn require_read_scope(req: Request<Body>, next: Next) -> Result<Response, Error> {
+    check_scope(&req, MEMORIES_READ)?;
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(s: &[&str]) -> TokenScopes {
+        TokenScopes::new(s.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn test_scope_implication() {
+        let read = scopes(&[MEMORIES_READ]);
+        assert!(read.allows(MEMORIES_READ));
+        assert!(!read.allows(MEMORIES_WRITE));
+        assert!(!read.allows(PROVIDERS_ADMIN));
+
+        let admin = scopes(&[PROJECTS_ADMIN]);
+        assert!(admin.allows(MEMORIES_READ));
+        assert!(admin.allows(MEMORIES_WRITE));
+        assert!(!admin.allows(PROVIDERS_ADMIN));
+
+        assert!(scopes(&[ALL]).allows(USERS_ADMIN));
+        assert!(TokenScopes::unrestricted().allows(PROVIDERS_ADMIN));
+        assert!(!scopes(&[]).allows(MEMORIES_READ));
+    }
+
+    #[test]
+    fn test_tool_scopes() {
+        // Read-only tokens can use read tools
+        let read = scopes(&[MEMORIES_READ]);
+        assert!(read.allows_tool("memory_search", tool_scope("memory_search")));
+        assert!(!read.allows_tool("memory_add", tool_scope("memory_add")));
+        assert!(!read.allows_tool("memory_feedback", tool_scope("memory_feedback")));
+        assert!(!read.allows_tool("github_project_create", tool_scope("github_project_create")));
+
+        // Per-tool scopes grant only that tool
+        let search_only = scopes(&["mcp:tools:memory_search"]);
+        assert!(search_only.allows_tool("memory_search", tool_scope("memory_search")));
+        assert!(!search_only.allows_tool("memory_list", tool_scope("memory_list")));
+        assert!(!search_only.allows(MEMORIES_READ));
+
+        let all_tools = scopes(&["mcp:tools:*"]);
+        assert!(all_tools.allows_tool("memory_delete", tool_scope("memory_delete")));
+        assert!(!all_tools.allows(MEMORIES_READ));
+    }
+
+    #[test]
+    fn test_is_valid_scope() {
+        assert!(is_valid_scope("memories:read"));
+        assert!(is_valid_scope("mcp:tools:memory_search"));
+        assert!(is_valid_scope("mcp:tools:*"));
+        assert!(is_valid_scope("*"));
+        assert!(!is_valid_scope("mcp:tools:"));
+        assert!(!is_valid_scope("memories:delete"));
+    }
+}
@@ -15,6 +15,7 @@
 //! - Tokens are looked up by prefix (fast index lookup)
 //! - Full token is verified against stored hash (timing-safe comparison)
 //! - Each token can be scoped to specific projects
+//! - Each token can be limited to a set of scopes (see `scopes`)
 //! - Tokens can be revoked or expired
 //! - Query string tokens are URL-decoded before validation
 
@@ -28,6 +29,7 @@ use axum::{
 use sha2::{Digest, Sha256};
 use sqlx::FromRow;
 
+use super::scopes::TokenScopes;
 use crate::{error::Error, AppState};
 
 /// Extract token from Authorization header or query string.
@@ -70,6 +72,8 @@ pub struct AuthContext {
     pub user_id: String,
     /// Whether the user has admin role
     pub is_admin: bool,
+    /// Scopes granted to the token
+    pub scopes: TokenScopes,
 }
 
 /// Database row for API tokens.
@@ -254,10 +258,15 @@ async fn validate_token(state: &AppState, token: &str) -> Result<AuthContext, Er
     // Look up user to get role
     let user = crate::db::get_user(&state.db, &token_row.user_id).await?;
 
+    let scopes = crate::db::get_api_token_scopes(&state.db, &token_row.id)
+        .await?
+        .map_or_else(TokenScopes::unrestricted, TokenScopes::new);
+
     Ok(AuthContext {
         token_id: token_row.id,
         user_id: token_row.user_id,
         is_admin: user.is_admin(),
+        scopes,
     })
 }
 
@@ -0,0 +1,462 @@
+//! Envelope encryption of secrets stored in SQLite.
+//!
+//! Each secret is sealed with its own random data key (AES-256-GCM), and the
+//! data key is in turn sealed with the master key. Sealed values are stored as
+//! `enc:v2:<key id>:<base64 wrapped data key>:<base64 nonce || ciphertext>`.
+//! Values without an `enc:` prefix are plaintext rows written before a master
+//! key was configured; they are read as-is and encrypted on the next start.
+//!
+//! The master key is base64 of 32 bytes, read from `FOLD_MASTER_KEY` or from
+//! the first line of `FOLD_MASTER_KEY_FILE`. To rotate, make the new key
+//! current and list the old one in `FOLD_MASTER_KEY_PREVIOUS` (or on a later
+//! line of the key file). On start, data keys sealed under a previous key are
+//! re-wrapped under the current one, after which the old key can be dropped.
+//! A key that is set but cannot be read or decoded stops the server on start.
+
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use sha2::{Digest, Sha256};
+
+use crate::config::SecretsConfig;
+use crate::{config, Error, Result};
+
+/// Prefix of values sealed directly with the master key (no key id).
+const V1_PREFIX: &str = "enc:v1:";
+
+/// Prefix of envelope-encrypted values.
+const V2_PREFIX: &str = "enc:v2:";
+
+/// Length of an AES-256 key.
+const KEY_LEN: usize = 32;
+
+static KEYRING: OnceLock<Option<Keyring>> = OnceLock::new();
+
+/// An AES-256-GCM key, used both as a master key and as a data key.
+pub struct MasterKey {
+    id: String,
+    key: LessSafeKey,
+}
+
+impl MasterKey {
+    /// Build a key from raw bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| Error::Validation("Master key must be 32 bytes".into()))?;
+        Ok(Self {
+            id: hex::encode(&Sha256::digest(bytes)[..4]),
+            key: LessSafeKey::new(key),
+        })
+    }
+
+    /// Build a key from its base64 encoding.
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| Error::Validation(format!("Master key is not valid base64: {}", e)))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Short fingerprint identifying the key in sealed values.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Seal bytes, returning `nonce || ciphertext`.
+    fn seal_bytes(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce_bytes)
+            .map_err(|_| Error::Internal("Failed to generate nonce".into()))?;
+
+        let mut in_out = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| Error::Internal("Failed to encrypt secret".into()))?;
+
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    /// Open `nonce || ciphertext` produced by `seal_bytes`.
+    fn open_bytes(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            return Err(Error::Internal("Sealed secret is truncated".into()));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| Error::Internal("Sealed secret has an invalid nonce".into()))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| Error::Internal("Failed to decrypt secret - wrong master key?".into()))?;
+
+        Ok(plaintext.to_vec())
+    }
+}
+
+/// The current master key plus retired keys still accepted for decryption.
+pub struct Keyring {
+    current: MasterKey,
+    previous: Vec<MasterKey>,
+}
+
+impl Keyring {
+    /// Build a keyring from the current key and any retired keys.
+    pub fn new(current: MasterKey, previous: Vec<MasterKey>) -> Self {
+        Self { current, previous }
+    }
+
+    /// Build the keyring from config. `None` when no master key is set.
+    pub fn from_config(config: &SecretsConfig) -> Result<Option<Self>> {
+        let mut encoded: Vec<String> = Vec::new();
+        if let Some(key) = &config.master_key {
+            encoded.push(key.clone());
+        } else if let Some(path) = &config.master_key_file {
+            let contents = std::fs::read_to_string(path).map_err(|e| {
+                Error::Validation(format!("Failed to read master key file {}: {}", path, e))
+            })?;
+            encoded.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(String::from),
+            );
+            if encoded.is_empty() {
+                return Err(Error::Validation(format!(
+                    "Master key file {} holds no key",
+                    path
+                )));
+            }
+        }
+        encoded.extend(config.previous_master_keys.iter().cloned());
+
+        let mut keys = encoded.iter().map(|k| MasterKey::from_base64(k));
+        let Some(current) = keys.next().transpose()? else {
+            return Ok(None);
+        };
+        let previous = keys.collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Self::new(current, previous)))
+    }
+
+    /// Id of the key new secrets are sealed under.
+    pub fn current_id(&self) -> &str {
+        self.current.id()
+    }
+
+    fn key(&self, id: &str) -> Option<&MasterKey> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|k| k.id() == id)
+    }
+
+    /// Seal a secret under a fresh data key wrapped by the current master key.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut data_key = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut data_key)
+            .map_err(|_| Error::Internal("Failed to generate data key".into()))?;
+
+        let payload = MasterKey::from_bytes(&data_key)?.seal_bytes(plaintext.as_bytes())?;
+        let wrapped = self.current.seal_bytes(&data_key)?;
+
+        Ok(format!(
+            "{}{}:{}:{}",
+            V2_PREFIX,
+            self.current.id(),
+            STANDARD.encode(wrapped),
+            STANDARD.encode(payload)
+        ))
+    }
+
+    /// Decrypt a stored secret. Plaintext values are returned unchanged.
+    pub fn decrypt(&self, stored: &str) -> Result<String> {
+        let plaintext = if let Some(sealed) = stored.strip_prefix(V1_PREFIX) {
+            let sealed = decode(sealed)?;
+            std::iter::once(&self.current)
+                .chain(&self.previous)
+                .find_map(|k| k.open_bytes(&sealed).ok())
+                .ok_or_else(|| {
+                    Error::Internal("Failed to decrypt secret - wrong master key?".into())
+                })?
+        } else if stored.starts_with(V2_PREFIX) {
+            let envelope = Envelope::parse(stored)?;
+            let master = self.key(envelope.key_id).ok_or_else(|| {
+                Error::Internal(format!(
+                    "Secret is sealed with unknown master key {}",
+                    envelope.key_id
+                ))
+            })?;
+            let data_key = master.open_bytes(&decode(envelope.wrapped_key)?)?;
+            MasterKey::from_bytes(&data_key)?.open_bytes(&decode(envelope.payload)?)?
+        } else {
+            return Ok(stored.to_string());
+        };
+
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::Internal("Decrypted secret is not UTF-8".into()))
+    }
+
+    /// Whether a stored value is plaintext, or not sealed under the current key.
+    pub fn needs_rewrap(&self, stored: &str) -> bool {
+        match Envelope::parse(stored) {
+            Ok(envelope) => envelope.key_id != self.current.id(),
+            Err(_) => true,
+        }
+    }
+
+    /// Bring a stored value under the current master key.
+    ///
+    /// Envelopes under a retired key only have their data key re-wrapped, so
+    /// the secret itself is never re-encrypted.
+    pub fn rewrap(&self, stored: &str) -> Result<String> {
+        let Ok(envelope) = Envelope::parse(stored) else {
+            return self.encrypt(&self.decrypt(stored)?);
+        };
+        if envelope.key_id == self.current.id() {
+            return Ok(stored.to_string());
+        }
+
+        let master = self.key(envelope.key_id).ok_or_else(|| {
+            Error::Internal(format!(
+                "Secret is sealed with unknown master key {}",
+                envelope.key_id
+            ))
+        })?;
+        let data_key = master.open_bytes(&decode(envelope.wrapped_key)?)?;
+        let wrapped = self.current.seal_bytes(&data_key)?;
+
+        Ok(format!(
+            "{}{}:{}:{}",
+            V2_PREFIX,
+            self.current.id(),
+            STANDARD.encode(wrapped),
+            envelope.payload
+        ))
+    }
+}
+
+/// The parts of an `enc:v2:` value.
+struct Envelope<'a> {
+    key_id: &'a str,
+    wrapped_key: &'a str,
+    payload: &'a str,
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(stored: &'a str) -> Result<Self> {
+        let mut parts = stored
+            .strip_prefix(V2_PREFIX)
+            .ok_or_else(|| Error::Internal("Secret is not envelope-encrypted".into()))?
+            .splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(key_id), Some(wrapped_key), Some(payload)) => Ok(Self {
+                key_id,
+                wrapped_key,
+                payload,
+            }),
+            _ => Err(Error::Internal("Malformed encrypted secret".into())),
+        }
+    }
+}
+
+fn decode(encoded: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(encoded)
+        .map_err(|_| Error::Internal("Sealed secret is not valid base64".into()))
+}
+
+/// Whether a stored value is sealed.
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(V1_PREFIX) || value.starts_with(V2_PREFIX)
+}
+
+/// Load the master key configuration.
+///
+/// Called on startup so that a master key which is set but invalid stops the
+/// server, rather than secrets being written in plaintext.
+pub fn init_keyring() -> Result<()> {
+    if KEYRING.get().is_none() {
+        let _ = KEYRING.set(load_keyring()?);
+    }
+    Ok(())
+}
+
+/// The configured keyring, if any.
+///
+/// Panics if a master key is configured but invalid; `init_keyring` reports
+/// that as an error on startup instead.
+pub fn keyring() -> Option<&'static Keyring> {
+    KEYRING
+        .get_or_init(|| load_keyring().expect("Invalid master key configuration"))
+        .as_ref()
+}
+
+/// Encrypt a secret for storage.
+///
+/// Fails when no master key is configured, so secrets are never silently
+/// written in plaintext.
+pub fn encrypt(plaintext: &str) -> Result<String> {
+    keyring()
+        .ok_or_else(|| {
+            Error::Validation(
+                "FOLD_MASTER_KEY or FOLD_MASTER_KEY_FILE must be set to store secrets".into(),
+            )
+        })?
+        .encrypt(plaintext)
+}
+
+/// Encrypt a secret if a master key is configured, otherwise store it as-is.
+///
+/// Used for columns that predate encryption, so deployments without a master
+/// key keep working.
+pub fn encrypt_if_configured(plaintext: &str) -> Result<String> {
+    match keyring() {
+        Some(keyring) => keyring.encrypt(plaintext),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// `encrypt_if_configured` for an optional secret.
+pub fn encrypt_opt_if_configured(plaintext: Option<&str>) -> Result<Option<String>> {
+    plaintext.map(encrypt_if_configured).transpose()
+}
+
+/// Decrypt a stored secret. Plaintext values are returned unchanged.
+pub fn decrypt(stored: &str) -> Result<String> {
+    if !is_sealed(stored) {
+        return Ok(stored.to_string());
+    }
+
+    keyring()
+        .ok_or_else(|| {
+            Error::Internal("Secret is encrypted but no master key is configured".into())
+        })?
+        .decrypt(stored)
+}
+
+/// Decrypt an optional stored secret.
+pub fn decrypt_opt(stored: Option<&str>) -> Result<Option<String>> {
+    stored.map(decrypt).transpose()
+}
+
+/// Load the keyring from config. Fails if a key is set but invalid.
+fn load_keyring() -> Result<Option<Keyring>> {
+    if cfg!(test) {
+        return MasterKey::from_bytes(&[7u8; KEY_LEN])
+            .map(|key| Some(Keyring::new(key, Vec::new())));
+    }
+
+    Keyring::from_config(&config().secrets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring(current: u8, previous: &[u8]) -> Keyring {
+        Keyring::new(
+            MasterKey::from_bytes(&[current; KEY_LEN]).unwrap(),
+            previous
+                .iter()
+                .map(|b| MasterKey::from_bytes(&[*b; KEY_LEN]).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let keys = keyring(1, &[]);
+        let sealed = keys.encrypt("sk-secret").unwrap();
+
+        assert!(is_sealed(&sealed));
+        assert!(sealed.starts_with(&format!("enc:v2:{}:", keys.current_id())));
+        assert!(!sealed.contains("sk-secret"));
+        assert_eq!(keys.decrypt(&sealed).unwrap(), "sk-secret");
+        // Fresh data key and nonce per seal
+        assert_ne!(keys.encrypt("sk-secret").unwrap(), sealed);
+        assert!(!keys.needs_rewrap(&sealed));
+    }
+
+    #[test]
+    fn test_invalid_key_config_fails() {
+        let config = |master_key: Option<&str>, master_key_file: Option<&str>| SecretsConfig {
+            master_key: master_key.map(String::from),
+            master_key_file: master_key_file.map(String::from),
+            previous_master_keys: Vec::new(),
+        };
+
+        assert!(Keyring::from_config(&config(None, None)).unwrap().is_none());
+        let valid = STANDARD.encode([1u8; KEY_LEN]);
+        assert!(Keyring::from_config(&config(Some(&valid), None))
+            .unwrap()
+            .is_some());
+
+        assert!(Keyring::from_config(&config(Some("not-a-key"), None)).is_err());
+        assert!(Keyring::from_config(&config(None, Some("/nonexistent/fold-master-key"))).is_err());
+
+        let path = std::env::temp_dir().join(format!("fold-key-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "# no key yet\n").unwrap();
+        assert!(Keyring::from_config(&config(None, path.to_str())).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_key_fails() {
+        let sealed = keyring(1, &[]).encrypt("sk-secret").unwrap();
+
+        assert!(keyring(2, &[]).decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn test_rotation_rewraps_data_key() {
+        let old = keyring(1, &[]);
+        let sealed = old.encrypt("sk-secret").unwrap();
+
+        let rotated = keyring(2, &[1]);
+        assert_eq!(rotated.decrypt(&sealed).unwrap(), "sk-secret");
+        assert!(rotated.needs_rewrap(&sealed));
+
+        let rewrapped = rotated.rewrap(&sealed).unwrap();
+        assert!(!rotated.needs_rewrap(&rewrapped));
+        // Only the wrapped data key changes
+        assert_eq!(
+            sealed.rsplit(':').next().unwrap(),
+            rewrapped.rsplit(':').next().unwrap()
+        );
+
+        // The retired key is no longer needed
+        assert_eq!(keyring(2, &[]).decrypt(&rewrapped).unwrap(), "sk-secret");
+    }
+
+    #[test]
+    fn test_plaintext_and_v1_values() {
+        let keys = keyring(1, &[]);
+        assert_eq!(keys.decrypt("plain-token").unwrap(), "plain-token");
+        assert!(keys.needs_rewrap("plain-token"));
+        assert!(keys.decrypt(&keys.rewrap("plain-token").unwrap()).is_ok());
+
+        let key = MasterKey::from_bytes(&[1u8; KEY_LEN]).unwrap();
+        let v1 = format!(
+            "{}{}",
+            V1_PREFIX,
+            STANDARD.encode(key.seal_bytes(b"sk-old").unwrap())
+        );
+        assert_eq!(keys.decrypt(&v1).unwrap(), "sk-old");
+        assert!(keys.needs_rewrap(&v1));
+
+        assert!(MasterKey::from_base64("not base64!").is_err());
+        assert!(MasterKey::from_bytes(&[0u8; 16]).is_err());
+    }
+}
@@ -0,0 +1,604 @@
+//! Question answering over a project's memories.
+//!
+//! A question is answered from the project's own knowledge only. Sources are
+//! retrieved with chunk-level search, widened by one hop through memory
+//! links, and packed into a token budget. The LLM is told to answer from
+//! those sources alone and to cite them inline as `[n]`. Answers that cite
+//! nothing, or that the model itself declines, are refused rather than
+//! passed on ungrounded.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tracing::debug;
+
+use crate::db::{self, DbPool, Project, UsageKind};
+use crate::error::Result;
+use crate::models::{Memory, MemorySearchResult};
+
+use super::context_packer::estimate_tokens;
+use super::{ChatRequest, ChatResponse, LlmUsage, MemoryService, StreamEvent};
+
+/// Default token budget for packed sources.
+pub const DEFAULT_CONTEXT_TOKENS: usize = 6000;
+
+/// Largest token budget a caller may ask for.
+pub const MAX_CONTEXT_TOKENS: usize = 32000;
+
+/// Chunks of one memory used as separate sources.
+const MAX_CHUNKS_PER_MEMORY: usize = 2;
+
+/// Linked memories pulled in for each search hit.
+const MAX_LINKS_PER_HIT: usize = 3;
+
+/// Score of a linked memory relative to the hit it was reached from.
+const LINK_SCORE_FACTOR: f32 = 0.8;
+
+/// A source is only cut to fit when at least this many tokens remain.
+const MIN_PARTIAL_TOKENS: usize = 200;
+
+/// Tokens reserved for the answer.
+const ANSWER_MAX_TOKENS: u32 = 1024;
+
+/// What the model replies when the sources do not answer the question.
+const NO_ANSWER: &str = "NO_ANSWER";
+
+/// Answer returned when the question cannot be grounded in the sources.
+const REFUSAL: &str =
+    "I couldn't find an answer to this in the project's memories, so I won't guess.";
+
+const SYSTEM_PROMPT: &str = "You answer questions about a software project using only the \
+numbered sources provided. Cite the source of every statement inline with its number in square \
+brackets, for example [1] or [2][3]. Do not use outside knowledge and do not cite sources that \
+are not listed. If the sources do not contain the answer, reply with exactly NO_ANSWER.";
+
+/// Retrieval and packing options.
+#[derive(Debug, Clone)]
+pub struct AskOptions {
+    /// Search hits to retrieve
+    pub limit: usize,
+    /// Minimum similarity for a hit to be used
+    pub min_score: f32,
+    /// Token budget for the packed sources
+    pub max_context_tokens: usize,
+    /// Pull in memories one link away from each hit
+    pub follow_links: bool,
+}
+
+impl Default for AskOptions {
+    fn default() -> Self {
+        Self {
+            limit: 10,
+            min_score: 0.4,
+            max_context_tokens: DEFAULT_CONTEXT_TOKENS,
+            follow_links: true,
+        }
+    }
+}
+
+/// A numbered source given to the LLM.
+#[derive(Debug, Clone, Serialize)]
+pub struct AskSource {
+    /// Number the answer cites this source by
+    pub index: usize,
+    pub memory_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+    pub score: f32,
+    /// Link type, when the source was reached through a link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via_link: Option<String>,
+    /// Whether the content was cut to fit the budget
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    #[serde(skip)]
+    content: String,
+}
+
+/// A grounded (or refused) answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct AskAnswer {
+    pub question: String,
+    /// Answer text with inline `[n]` citations
+    pub answer: String,
+    /// False when the answer was refused for lack of grounding
+    pub grounded: bool,
+    /// Sources the answer cites
+    pub citations: Vec<AskSource>,
+    /// Every source given to the LLM
+    pub sources: Vec<AskSource>,
+    /// Sources left out because they did not fit the budget
+    pub omitted: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<LlmUsage>,
+}
+
+/// Progress of a streamed answer.
+#[derive(Debug, Clone)]
+pub enum AskEvent {
+    /// Sources the answer will draw on, sent first
+    Sources(Vec<AskSource>),
+    /// Next piece of answer text
+    Delta(String),
+    /// Final answer with citations. Its text replaces the deltas when the
+    /// answer was refused.
+    Done(AskAnswer),
+}
+
+/// Sources packed for one question.
+struct PackedSources {
+    sources: Vec<AskSource>,
+    omitted: usize,
+}
+
+/// Service answering questions from project memories.
+#[derive(Clone)]
+pub struct AskService {
+    db: DbPool,
+    memory: MemoryService,
+}
+
+impl AskService {
+    /// Create a new ask service.
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self { db, memory }
+    }
+
+    /// Answer a question from the project's memories.
+    pub async fn ask(
+        &self,
+        project: &Project,
+        question: &str,
+        options: &AskOptions,
+    ) -> Result<AskAnswer> {
+        let packed = self.retrieve(project, question, options).await?;
+        if packed.sources.is_empty() {
+            return Ok(refusal(question, packed));
+        }
+
+        let llm = self.memory.llm_for(&project.id).await?;
+        let response = llm.chat(&build_request(question, &packed.sources)).await?;
+        self.record_llm_usage(&project.id).await;
+
+        Ok(finish(question, packed, response))
+    }
+
+    /// Answer a question, streaming the answer as it is generated.
+    ///
+    /// Fails up front if no LLM provider accepts the request. Errors after
+    /// that are sent on the stream.
+    pub async fn ask_stream(
+        &self,
+        project: &Project,
+        question: &str,
+        options: &AskOptions,
+    ) -> Result<ReceiverStream<Result<AskEvent>>> {
+        let packed = self.retrieve(project, question, options).await?;
+        let (tx, rx) = mpsc::channel(32);
+        let question = question.to_string();
+
+        if packed.sources.is_empty() {
+            let _ = tx.send(Ok(AskEvent::Sources(Vec::new()))).await;
+            let _ = tx
+                .send(Ok(AskEvent::Done(refusal(&question, packed))))
+                .await;
+            return Ok(ReceiverStream::new(rx));
+        }
+
+        let llm = self.memory.llm_for(&project.id).await?;
+        let mut stream = llm
+            .chat_stream(&build_request(&question, &packed.sources))
+            .await?;
+        self.record_llm_usage(&project.id).await;
+
+        tokio::spawn(async move {
+            if tx
+                .send(Ok(AskEvent::Sources(packed.sources.clone())))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            while let Some(event) = stream.next().await {
+                let event = match event {
+                    Ok(StreamEvent::Delta(text)) => Ok(AskEvent::Delta(text)),
+                    Ok(StreamEvent::Done(response)) => {
+                        let answer = finish(&question, packed, response);
+                        let _ = tx.send(Ok(AskEvent::Done(answer))).await;
+                        return;
+                    }
+                    Err(e) => Err(e),
+                };
+                if tx.send(event).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    /// Retrieve, widen and pack the sources for a question.
+    async fn retrieve(
+        &self,
+        project: &Project,
+        question: &str,
+        options: &AskOptions,
+    ) -> Result<PackedSources> {
+        let hits: Vec<MemorySearchResult> = self
+            .memory
+            .search_with_chunks(&project.id, &project.slug, question, None, options.limit)
+            .await?
+            .into_iter()
+            .filter(|hit| hit.score >= options.min_score)
+            .collect();
+
+        let mut seen: HashSet<String> = hits.iter().map(|hit| hit.memory.id.clone()).collect();
+        let mut candidates = Vec::new();
+
+        for hit in &hits {
+            candidates.extend(self.hit_sources(hit).await?);
+        }
+
+        if options.follow_links {
+            for hit in &hits {
+                let mut links = db::list_memory_links(&self.db, &hit.memory.id).await?;
+                links.sort_by(|a, b| {
+                    b.confidence
+                        .unwrap_or(1.0)
+                        .partial_cmp(&a.confidence.unwrap_or(1.0))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+                for link in links.into_iter().take(MAX_LINKS_PER_HIT) {
+                    let other = if link.source_id == hit.memory.id {
+                        link.target_id
+                    } else {
+                        link.source_id
+                    };
+                    if !seen.insert(other.clone())
+                        || db::get_archived_memory(&self.db, &other).await?.is_some()
+                    {
+                        continue;
+                    }
+
+                    let Some(memory) = self.memory.get(&project.id, &other).await? else {
+                        continue;
+                    };
+                    let score =
+                        hit.score * LINK_SCORE_FACTOR * link.confidence.unwrap_or(1.0) as f32;
+                    let mut source = memory_source(&memory, score);
+                    source.via_link = Some(link.link_type);
+                    candidates.push(source);
+                }
+            }
+        }
+
+        Ok(pack(candidates, options.max_context_tokens))
+    }
+
+    /// Sources for a search hit: its best matching chunks, or the whole
+    /// memory when no chunk matched.
+    async fn hit_sources(&self, hit: &MemorySearchResult) -> Result<Vec<AskSource>> {
+        let mut sources = Vec::new();
+
+        for chunk_match in hit.matched_chunks.iter().take(MAX_CHUNKS_PER_MEMORY) {
+            let Some(chunk) = db::get_chunk(&self.db, &chunk_match.id).await? else {
+                continue;
+            };
+            let mut source = memory_source(&hit.memory, chunk_match.score);
+            source.start_line = Some(chunk.start_line);
+            source.end_line = Some(chunk.end_line);
+            source.content = chunk.content;
+            sources.push(source);
+        }
+
+        if sources.is_empty() {
+            sources.push(memory_source(&hit.memory, hit.score));
+        }
+
+        Ok(sources)
+    }
+
+    async fn record_llm_usage(&self, project_id: &str) {
+        super::rate_limit::record_usage(&self.db, project_id, UsageKind::Llm, 1).await;
+    }
+}
+
+/// Source holding a whole memory.
+fn memory_source(memory: &Memory, score: f32) -> AskSource {
+    AskSource {
+        index: 0,
+        memory_id: memory.id.clone(),
+        title: memory.title.clone(),
+        file_path: memory.file_path.clone(),
+        start_line: None,
+        end_line: None,
+        score,
+        via_link: None,
+        truncated: false,
+        content: memory.content.clone().unwrap_or_default(),
+    }
+}
+
+/// Find the largest byte index <= `index` that is on a char boundary.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        s.len()
+    } else {
+        let mut i = index;
+        while i > 0 && !s.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    }
+}
+
+/// Fill the token budget with the highest scoring sources and number them.
+///
+/// A source that does not fit is cut down when enough budget remains,
+/// otherwise it is omitted and smaller sources further down still get a
+/// chance.
+fn pack(mut candidates: Vec<AskSource>, budget: usize) -> PackedSources {
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut sources: Vec<AskSource> = Vec::new();
+    let mut omitted = 0;
+    let mut remaining = budget;
+
+    for mut source in candidates {
+        if source.content.trim().is_empty() {
+            continue;
+        }
+
+        source.index = sources.len() + 1;
+        let header_tokens = estimate_tokens(&source_header(&source));
+        let tokens = header_tokens + estimate_tokens(&source.content);
+
+        if tokens > remaining {
+            if remaining < MIN_PARTIAL_TOKENS.max(header_tokens + 1) {
+                omitted += 1;
+                continue;
+            }
+            let keep = floor_char_boundary(&source.content, (remaining - header_tokens) * 4);
+            source.content.truncate(keep);
+            source.truncated = true;
+        }
+
+        remaining -= header_tokens + estimate_tokens(&source.content);
+        sources.push(source);
+    }
+
+    PackedSources { sources, omitted }
+}
+
+/// First line of a source block: its number and where it comes from.
+fn source_header(source: &AskSource) -> String {
+    let mut header = format!("[{}]", source.index);
+    if let Some(path) = &source.file_path {
+        header.push(' ');
+        header.push_str(path);
+        if let (Some(start), Some(end)) = (source.start_line, source.end_line) {
+            header.push_str(&format!(" lines {}-{}", start, end));
+        }
+    }
+    if let Some(title) = &source.title {
+        header.push_str(&format!(" \"{}\"", title));
+    }
+    header.push_str(&format!(" (memory {})", source.memory_id));
+    header
+}
+
+fn build_request(question: &str, sources: &[AskSource]) -> ChatRequest {
+    let blocks: Vec<String> = sources
+        .iter()
+        .map(|s| format!("{}\n{}", source_header(s), s.content))
+        .collect();
+
+    ChatRequest::prompt(format!(
+        "Sources:\n\n{}\n\nQuestion: {}",
+        blocks.join("\n\n"),
+        question
+    ))
+    .with_system(SYSTEM_PROMPT)
+    .with_max_tokens(ANSWER_MAX_TOKENS)
+    .with_temperature(0.1)
+}
+
+/// Source numbers cited in an answer, in order of first citation.
+///
+/// Accepts `[1]`, `[1][2]` and `[1, 2]`.
+fn cited_indices(answer: &str) -> Vec<usize> {
+    let mut cited = Vec::new();
+    let mut rest = answer;
+
+    while let Some(open) = rest.find('[') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+        let inner = &rest[..close];
+        let numbers: Option<Vec<usize>> = inner
+            .split(',')
+            .map(|n| n.trim().parse::<usize>().ok())
+            .collect();
+        for n in numbers.unwrap_or_default() {
+            if !cited.contains(&n) {
+                cited.push(n);
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+
+    cited
+}
+
+/// Turn the model's reply into an answer, refusing it when ungrounded.
+fn finish(question: &str, packed: PackedSources, response: ChatResponse) -> AskAnswer {
+    let content = response.content.trim();
+    let citations: Vec<AskSource> = cited_indices(content)
+        .into_iter()
+        .filter_map(|n| packed.sources.iter().find(|s| s.index == n).cloned())
+        .collect();
+
+    let grounded = !content.contains(NO_ANSWER) && !citations.is_empty();
+    if !grounded {
+        debug!(question, "Refusing ungrounded answer");
+    }
+
+    AskAnswer {
+        question: question.to_string(),
+        answer: if grounded {
+            content.to_string()
+        } else {
+            REFUSAL.to_string()
+        },
+        grounded,
+        citations: if grounded { citations } else { Vec::new() },
+        sources: packed.sources,
+        omitted: packed.omitted,
+        provider: Some(response.provider),
+        model: Some(response.model),
+        usage: Some(response.usage),
+    }
+}
+
+/// Answer for a question with no usable sources.
+fn refusal(question: &str, packed: PackedSources) -> AskAnswer {
+    AskAnswer {
+        question: question.to_string(),
+        answer: REFUSAL.to_string(),
+        grounded: false,
+        citations: Vec::new(),
+        sources: packed.sources,
+        omitted: packed.omitted,
+        provider: None,
+        model: None,
+        usage: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(memory_id: &str, score: f32, content: &str) -> AskSource {
+        AskSource {
+            index: 0,
+            memory_id: memory_id.to_string(),
+            title: None,
+            file_path: Some("src/auth.rs".to_string()),
+            start_line: Some(10),
+            end_line: Some(42),
+            score,
+            via_link: None,
+            truncated: false,
+            content: content.to_string(),
+        }
+    }
+
+    fn response(content: &str) -> ChatResponse {
+        ChatResponse {
+            content: content.to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            usage: LlmUsage {
+                input_tokens: 100,
+                output_tokens: 20,
+            },
+        }
+    }
+
+    #[test]
+    fn test_cited_indices() {
+        assert_eq!(
+            cited_indices("Tokens expire [2]. Refresh [1][2]."),
+            vec![2, 1]
+        );
+        assert_eq!(cited_indices("See [1, 3] and [link](x)."), vec![1, 3]);
+        assert!(cited_indices("No citations here").is_empty());
+    }
+
+    #[test]
+    fn test_pack_orders_by_score_and_respects_budget() {
+        let big = "x".repeat(4000);
+        let packed = pack(
+            vec![
+                source("low", 0.5, "small"),
+                source("high", 0.9, &big),
+                source("mid", 0.7, &big),
+            ],
+            1200,
+        );
+
+        let ids: Vec<_> = packed
+            .sources
+            .iter()
+            .map(|s| s.memory_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["high", "low"]);
+        assert_eq!(packed.sources[0].index, 1);
+        assert_eq!(packed.sources[1].index, 2);
+        assert!(!packed.sources[0].truncated);
+        assert_eq!(packed.omitted, 1);
+
+        let used: usize = packed
+            .sources
+            .iter()
+            .map(|s| estimate_tokens(&source_header(s)) + estimate_tokens(&s.content))
+            .sum();
+        assert!(used <= 1200);
+    }
+
+    #[test]
+    fn test_pack_truncates_to_fit() {
+        let packed = pack(vec![source("a", 0.9, &"é".repeat(3000))], 500);
+        assert_eq!(packed.sources.len(), 1);
+        assert!(packed.sources[0].truncated);
+        assert!(packed.sources[0].content.len() < 6000);
+    }
+
+    #[test]
+    fn test_finish_keeps_grounded_answer() {
+        let packed = pack(vec![source("a", 0.9, "fn refresh()")], 1000);
+        let answer = finish("how?", packed, response("Call refresh [1]. Not [7]."));
+
+        assert!(answer.grounded);
+        assert_eq!(answer.answer, "Call refresh [1]. Not [7].");
+        assert_eq!(answer.citations.len(), 1);
+        assert_eq!(answer.citations[0].memory_id, "a");
+        assert_eq!(answer.citations[0].start_line, Some(10));
+    }
+
+    #[test]
+    fn test_finish_refuses_ungrounded_answer() {
+        let packed = pack(vec![source("a", 0.9, "fn refresh()")], 1000);
+        let answer = finish("how?", packed, response("Probably OAuth."));
+        assert!(!answer.grounded);
+        assert_eq!(answer.answer, REFUSAL);
+        assert!(answer.citations.is_empty());
+
+        let packed = pack(vec![source("a", 0.9, "fn refresh()")], 1000);
+        let answer = finish("how?", packed, response("NO_ANSWER"));
+        assert!(!answer.grounded);
+        assert_eq!(answer.sources.len(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! Audit log service.
+//!
+//! Records who created, changed or deleted what, for compliance review.
+//! Events go to the append-only `audit_events` table and, when
+//! `AUDIT_LOG_PATH` is set, are also appended to a JSON lines file so they
+//! can be shipped to external log storage.
+//!
+//! Recording never fails the operation being audited: errors are logged.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::config::AuditConfig;
+use crate::db::{self, AuditAction, AuditEvent, CreateAuditEvent, DbPool};
+
+/// Who performed an audited operation.
+///
+/// Extracted from the request's auth context in handlers; see
+/// `middleware::audit`.
+#[derive(Debug, Clone, Default)]
+pub struct AuditActor {
+    pub user_id: Option<String>,
+    /// Set when the request used an API token
+    pub token_id: Option<String>,
+}
+
+impl AuditActor {
+    /// Actor for a token request.
+    pub fn token(user_id: &str, token_id: &str) -> Self {
+        Self {
+            user_id: Some(user_id.to_string()),
+            token_id: Some(token_id.to_string()),
+        }
+    }
+
+    /// Actor for a session (web UI) request.
+    pub fn user(user_id: &str) -> Self {
+        Self {
+            user_id: Some(user_id.to_string()),
+            token_id: None,
+        }
+    }
+}
+
+/// Service for recording audit events.
+#[derive(Clone)]
+pub struct AuditService {
+    db: DbPool,
+    /// JSONL file sink. The lock keeps concurrent lines from interleaving.
+    sink: Option<Arc<(PathBuf, Mutex<()>)>>,
+}
+
+impl AuditService {
+    /// Create a new audit service.
+    pub fn new(db: DbPool, config: &AuditConfig) -> Self {
+        Self {
+            db,
+            sink: config
+                .jsonl_path
+                .as_ref()
+                .map(|path| Arc::new((PathBuf::from(path), Mutex::new(())))),
+        }
+    }
+
+    /// Record an audit event.
+    ///
+    /// `project_id` is the project the resource belongs to, if any.
+    pub async fn record(
+        &self,
+        actor: &AuditActor,
+        action: AuditAction,
+        project_id: Option<&str>,
+        resource_id: Option<&str>,
+        details: Value,
+    ) {
+        let input = CreateAuditEvent {
+            id: crate::models::new_id(),
+            action,
+            resource_id: resource_id.map(String::from),
+            project_id: project_id.map(String::from),
+            actor_user_id: actor.user_id.clone(),
+            actor_token_id: actor.token_id.clone(),
+            details: (!details.is_null()).then_some(details),
+        };
+
+        match db::insert_audit_event(&self.db, input).await {
+            Ok(event) => self.write_sink(&event).await,
+            Err(e) => {
+                warn!(error = %e, action = action.as_str(), "Failed to record audit event");
+            }
+        }
+    }
+
+    async fn write_sink(&self, event: &AuditEvent) {
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        let (path, lock) = sink.as_ref();
+
+        let mut line = serde_json::json!({
+            "id": event.id,
+            "action": event.action,
+            "resource_type": event.resource_type,
+            "resource_id": event.resource_id,
+            "project_id": event.project_id,
+            "actor_user_id": event.actor_user_id,
+            "actor_token_id": event.actor_token_id,
+            "details": event.details_json(),
+            "created_at": event.created_at,
+        })
+        .to_string();
+        line.push('\n');
+
+        let _guard = lock.lock().await;
+        let result = async {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(line.as_bytes()).await?;
+            // tokio writes in the background; flush so the line is on disk on return
+            file.flush().await
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!(error = %e, path = %path.display(), "Failed to write audit log file");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{init_pool, migrate, AuditFilter};
+
+    #[tokio::test]
+    async fn test_record_writes_table_and_sink() {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("fold-audit-{}.jsonl", crate::models::new_id()));
+        let service = AuditService::new(
+            pool.clone(),
+            &AuditConfig {
+                jsonl_path: Some(path.to_string_lossy().to_string()),
+            },
+        );
+
+        let actor = AuditActor::token("user-1", "token-1");
+        service
+            .record(
+                &actor,
+                AuditAction::MemoryCreate,
+                Some("proj-1"),
+                Some("mem-1"),
+                serde_json::json!({ "title": "Test" }),
+            )
+            .await;
+        service
+            .record(
+                &actor,
+                AuditAction::TokenRevoke,
+                None,
+                Some("token-2"),
+                Value::Null,
+            )
+            .await;
+
+        let events = db::list_audit_events(&pool, &AuditFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|e| e.actor_token_id.as_deref() == Some("token-1")));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["action"], "memory.create");
+        assert_eq!(lines[0]["details"]["title"], "Test");
+        assert!(lines[1]["details"].is_null());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
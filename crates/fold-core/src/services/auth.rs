@@ -535,7 +535,9 @@ impl AuthService {
 
         let api_token: ApiToken = sqlx::query_as(
             r#"
-            SELECT * FROM api_tokens WHERE token_hash = ?
+            SELECT t.*, s.scopes FROM api_tokens t
+            LEFT JOIN api_token_scopes s ON s.token_id = t.id
+            WHERE t.token_hash = ?
             "#,
         )
         .bind(&token_hash)
@@ -563,6 +565,8 @@ impl AuthService {
     }
 
     /// Create an API token for a user.
+    ///
+    /// An empty `scopes` list creates a token with full access.
     pub async fn create_api_token(
         &self,
         user_id: &str,
@@ -572,6 +576,8 @@ impl AuthService {
     ) -> Result<(ApiToken, String)> {
         let token = format!("fold_{}", nanoid::nanoid!(48));
         let token_hash = self.hash_token(&token);
+        // Token middleware looks tokens up by the 8 chars after "fold_"
+        let token_prefix = token[5..13].to_string();
         let now = Utc::now();
         let expires_at = expires_in_days.map(|d| now + chrono::Duration::days(d));
 
@@ -580,7 +586,7 @@ impl AuthService {
             user_id: user_id.to_string(),
             name: name.to_string(),
             token_hash,
-            scopes: Some(serde_json::to_string(&scopes).unwrap()),
+            scopes: (!scopes.is_empty()).then(|| serde_json::to_string(&scopes).unwrap()),
             last_used: None,
             expires_at,
             created_at: now,
@@ -589,20 +595,24 @@ impl AuthService {
         sqlx::query(
             r#"
             INSERT INTO api_tokens (
-                id, user_id, name, token_hash, scopes, expires_at, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                id, user_id, name, token_hash, token_prefix, project_ids, expires_at, created_at
+            ) VALUES (?, ?, ?, ?, ?, '[]', ?, ?)
             "#,
         )
         .bind(&api_token.id)
         .bind(&api_token.user_id)
         .bind(&api_token.name)
         .bind(&api_token.token_hash)
-        .bind(&api_token.scopes)
+        .bind(&token_prefix)
         .bind(api_token.expires_at)
         .bind(api_token.created_at)
         .execute(&self.db)
         .await?;
 
+        if !scopes.is_empty() {
+            crate::db::set_api_token_scopes(&self.db, &api_token.id, &scopes).await?;
+        }
+
         Ok((api_token, token))
     }
 
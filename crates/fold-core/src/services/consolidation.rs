@@ -0,0 +1,469 @@
+//! Memory consolidation service.
+//!
+//! Agents often store the same fact several times with slightly different
+//! phrasing. Consolidation clusters agent memories whose embeddings are
+//! nearly identical, asks the LLM to write one merged memory per cluster,
+//! and folds the cluster into a single survivor. Originals are marked as
+//! merged rather than deleted.
+//!
+//! Merges are proposed for review unless the project enables auto-apply.
+//! Dry runs report what would be merged without writing anything.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
+
+use crate::db::{self, DbPool, MemoryMerge, MergeStatus, Project, UsageKind};
+use crate::error::{Error, Result};
+use crate::models::Memory;
+
+use super::{ChatRequest, LlmService, MemoryService};
+
+/// Maximum agent memories considered in one run.
+const MAX_CANDIDATES: i64 = 1000;
+
+/// Texts embedded per request while clustering.
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// A group of near-duplicate memories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryCluster {
+    /// Indices into the candidate list
+    pub members: Vec<usize>,
+    /// Lowest pairwise similarity within the cluster
+    pub min_similarity: f32,
+}
+
+/// One merge found during a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeProposal {
+    /// Persisted merge ID (absent for dry runs)
+    pub merge_id: Option<String>,
+    pub survivor_id: String,
+    pub source_ids: Vec<String>,
+    pub similarity: f32,
+    pub title: Option<String>,
+    pub content: String,
+    pub applied: bool,
+}
+
+/// Outcome of a consolidation run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsolidationReport {
+    pub dry_run: bool,
+    pub candidates: usize,
+    pub clusters: usize,
+    pub applied: usize,
+    pub proposals: Vec<MergeProposal>,
+}
+
+/// LLM-written merged memory.
+#[derive(Debug, Clone, Deserialize)]
+struct MergedMemory {
+    #[serde(default)]
+    title: Option<String>,
+    content: String,
+}
+
+/// JSON schema for `MergedMemory`.
+fn merged_memory_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "title": {"type": "string", "description": "Short descriptive title"},
+            "content": {"type": "string", "description": "Merged content in markdown"}
+        },
+        "required": ["title", "content"]
+    })
+}
+
+/// Service for consolidating near-duplicate memories.
+#[derive(Clone)]
+pub struct ConsolidationService {
+    db: DbPool,
+    memory: MemoryService,
+}
+
+impl ConsolidationService {
+    /// Create a new consolidation service.
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self { db, memory }
+    }
+
+    /// Run consolidation for a project using its configured thresholds.
+    ///
+    /// With `dry_run`, clusters and merged text are reported but nothing is
+    /// stored. Otherwise each merge is recorded as a proposal and applied
+    /// straight away when the project has auto-apply enabled.
+    pub async fn run(
+        &self,
+        project_id: &str,
+        job_id: Option<&str>,
+        dry_run: bool,
+    ) -> Result<ConsolidationReport> {
+        let settings = db::get_consolidation_settings(&self.db, project_id).await?;
+
+        let llm = self.memory.llm_for(project_id).await?;
+        if !llm.is_available().await {
+            return Err(Error::Internal(
+                "LLM provider required to write merged memories".into(),
+            ));
+        }
+
+        let project = db::get_project(&self.db, project_id).await?;
+        let candidates = self.list_candidates(&project).await?;
+        let mut report = ConsolidationReport {
+            dry_run,
+            candidates: candidates.len(),
+            ..Default::default()
+        };
+
+        if candidates.len() < 2 {
+            return Ok(report);
+        }
+
+        let vectors = self.candidate_vectors(&project, &candidates).await?;
+
+        let clusters = find_clusters(
+            &vectors,
+            settings.similarity_threshold as f32,
+            settings.max_cluster_size.max(2) as usize,
+        );
+        report.clusters = clusters.len();
+
+        for cluster in clusters {
+            let members: Vec<&Memory> = cluster.members.iter().map(|&i| &candidates[i]).collect();
+
+            super::rate_limit::check_quota(&self.db, project_id, UsageKind::Llm).await?;
+            super::rate_limit::record_usage(&self.db, project_id, UsageKind::Llm, 1).await;
+            let Some(merged) = self.write_merged(&llm, &members).await else {
+                continue;
+            };
+
+            let survivor = choose_survivor(&members);
+            let source_ids: Vec<String> = members
+                .iter()
+                .filter(|m| m.id != survivor.id)
+                .map(|m| m.id.clone())
+                .collect();
+
+            let mut proposal = MergeProposal {
+                merge_id: None,
+                survivor_id: survivor.id.clone(),
+                source_ids,
+                similarity: cluster.min_similarity,
+                title: merged.title,
+                content: merged.content,
+                applied: false,
+            };
+
+            if !dry_run {
+                let merge = db::create_memory_merge(
+                    &self.db,
+                    db::CreateMemoryMerge {
+                        id: crate::models::new_id(),
+                        project_id: project_id.to_string(),
+                        job_id: job_id.map(String::from),
+                        survivor_id: proposal.survivor_id.clone(),
+                        source_ids: proposal.source_ids.clone(),
+                        similarity: Some(proposal.similarity as f64),
+                        title: proposal.title.clone(),
+                        content: proposal.content.clone(),
+                    },
+                )
+                .await?;
+                proposal.merge_id = Some(merge.id.clone());
+
+                if settings.is_auto_apply() {
+                    match self.apply(&merge.id).await {
+                        Ok(_) => {
+                            proposal.applied = true;
+                            report.applied += 1;
+                        }
+                        Err(e) => warn!(error = %e, merge_id = %merge.id, "Failed to apply merge"),
+                    }
+                }
+            }
+
+            report.proposals.push(proposal);
+        }
+
+        if !dry_run {
+            db::mark_consolidation_run(&self.db, project_id).await?;
+        }
+
+        info!(
+            project_id,
+            dry_run,
+            clusters = report.clusters,
+            applied = report.applied,
+            "Memory consolidation finished"
+        );
+
+        Ok(report)
+    }
+
+    /// Apply a proposed merge: rewrite the survivor with the merged text,
+    /// move links onto it and mark the other memories as merged.
+    pub async fn apply(&self, merge_id: &str) -> Result<MemoryMerge> {
+        let merge = db::get_memory_merge(&self.db, merge_id).await?;
+        if merge.status_enum() != MergeStatus::Proposed {
+            return Err(Error::Validation(format!(
+                "Merge {} is already {}",
+                merge_id, merge.status
+            )));
+        }
+
+        // A memory may have been merged elsewhere since this was proposed
+        let mut source_ids = Vec::new();
+        for id in merge.source_ids_vec() {
+            if db::get_merged_into(&self.db, &id).await?.is_none() {
+                source_ids.push(id);
+            }
+        }
+
+        let project = db::get_project(&self.db, &merge.project_id).await?;
+        let merge = self
+            .memory
+            .merge_into(&project.slug, &merge, &source_ids)
+            .await?;
+        debug!(merge_id = %merge.id, survivor = %merge.survivor_id, "Applied memory merge");
+        Ok(merge)
+    }
+
+    /// Reject a proposed merge. The memories are left untouched.
+    pub async fn reject(&self, merge_id: &str) -> Result<MemoryMerge> {
+        db::decide_memory_merge(&self.db, merge_id, MergeStatus::Rejected).await
+    }
+
+    /// Vectors for the candidates, in order. Stored vectors are reused and
+    /// only memories missing from the index are embedded.
+    async fn candidate_vectors(
+        &self,
+        project: &Project,
+        candidates: &[Memory],
+    ) -> Result<Vec<Vec<f32>>> {
+        let ids: Vec<String> = candidates.iter().map(|m| m.id.clone()).collect();
+        let mut stored = self
+            .memory
+            .stored_vectors(&project.slug, &ids, false)
+            .await?;
+
+        let missing: Vec<&Memory> = candidates
+            .iter()
+            .filter(|m| !stored.contains_key(&m.id))
+            .collect();
+        let embeddings = self.memory.embeddings_for(&project.id).await?;
+        for batch in missing.chunks(EMBED_BATCH_SIZE) {
+            let texts = batch
+                .iter()
+                .map(|m| {
+                    format!(
+                        "{}\n{}",
+                        m.title.as_deref().unwrap_or(""),
+                        m.content.as_deref().unwrap_or("")
+                    )
+                })
+                .collect();
+            super::rate_limit::check_quota(&self.db, &project.id, UsageKind::Embedding).await?;
+            let embedded = embeddings.embed(texts).await?;
+            super::rate_limit::record_usage(&self.db, &project.id, UsageKind::Embedding, 1).await;
+            for (memory, vector) in batch.iter().zip(embedded) {
+                stored.insert(memory.id.clone(), vector);
+            }
+        }
+
+        Ok(candidates
+            .iter()
+            .map(|m| stored.remove(&m.id).unwrap_or_default())
+            .collect())
+    }
+
+    /// Agent memories that are not merged and not part of a pending proposal.
+    async fn list_candidates(&self, project: &Project) -> Result<Vec<Memory>> {
+        let memories = sqlx::query_as::<_, Memory>(
+            r#"
+            SELECT * FROM memories
+            WHERE project_id = ?
+              AND (source IS NULL OR source = 'agent')
+              AND COALESCE(type, 'general') NOT IN ('codebase', 'commit', 'pr')
+              AND id NOT IN (SELECT memory_id FROM merged_memories)
+              AND id NOT IN (SELECT memory_id FROM archived_memories)
+              AND id NOT IN (
+                  SELECT survivor_id FROM memory_merges
+                  WHERE project_id = ? AND status = 'proposed'
+              )
+              AND id NOT IN (
+                  SELECT j.value FROM memory_merges mm, json_each(mm.source_ids) j
+                  WHERE mm.project_id = ? AND mm.status = 'proposed'
+              )
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&project.id)
+        .bind(&project.id)
+        .bind(&project.id)
+        .bind(MAX_CANDIDATES)
+        .fetch_all(&self.db)
+        .await?;
+
+        let memories = self
+            .memory
+            .resolve_content_for_memories(memories, &project.slug, Some(&project.root_path))
+            .await?;
+
+        Ok(memories
+            .into_iter()
+            .filter(|m| m.content.as_deref().is_some_and(|c| !c.trim().is_empty()))
+            .collect())
+    }
+
+    /// Ask the LLM to combine a cluster into a single memory.
+    async fn write_merged(&self, llm: &LlmService, members: &[&Memory]) -> Option<MergedMemory> {
+        let mut sources = String::new();
+        for (i, m) in members.iter().enumerate() {
+            sources.push_str(&format!(
+                "--- Memory {} ---\nTitle: {}\n{}\n\n",
+                i + 1,
+                m.title.as_deref().unwrap_or(""),
+                m.content.as_deref().unwrap_or(""),
+            ));
+        }
+
+        let prompt = format!(
+            r#"The following memories are near-duplicates describing the same knowledge.
+Write ONE memory that preserves every distinct fact, detail and caveat from all of them.
+Do not invent information. Prefer the most specific wording.
+
+{}"#,
+            sources
+        );
+
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(1500)
+            .with_json_schema("merged_memory", merged_memory_schema());
+
+        match llm.chat_json::<MergedMemory>(&request).await {
+            Ok(merged) if !merged.content.trim().is_empty() => Some(merged),
+            Ok(_) => {
+                warn!("LLM returned an empty merged memory");
+                None
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to write merged memory");
+                None
+            }
+        }
+    }
+}
+
+/// Cosine similarity between two vectors (0.0 when either is empty).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Group vectors into clusters where every pair is at least `threshold`
+/// similar (complete linkage, so A~B and B~C does not pull in a distant C).
+/// Only clusters of two or more are returned.
+pub fn find_clusters(vectors: &[Vec<f32>], threshold: f32, max_size: usize) -> Vec<MemoryCluster> {
+    let mut assigned = vec![false; vectors.len()];
+    let mut clusters = Vec::new();
+
+    for i in 0..vectors.len() {
+        if assigned[i] {
+            continue;
+        }
+
+        let mut members = vec![i];
+        let mut min_similarity = 1.0f32;
+
+        for j in (i + 1)..vectors.len() {
+            if assigned[j] || members.len() >= max_size {
+                continue;
+            }
+
+            let sims: Vec<f32> = members
+                .iter()
+                .map(|&m| cosine_similarity(&vectors[m], &vectors[j]))
+                .collect();
+
+            if sims.iter().all(|&s| s >= threshold) {
+                members.push(j);
+                min_similarity = sims.into_iter().fold(min_similarity, f32::min);
+            }
+        }
+
+        if members.len() > 1 {
+            for &m in &members {
+                assigned[m] = true;
+            }
+            clusters.push(MemoryCluster {
+                members,
+                min_similarity,
+            });
+        }
+    }
+
+    clusters
+}
+
+/// Keep the most retrieved memory, falling back to the oldest.
+fn choose_survivor<'a>(members: &[&'a Memory]) -> &'a Memory {
+    members
+        .iter()
+        .copied()
+        .max_by(|a, b| {
+            a.retrieval_count
+                .cmp(&b.retrieval_count)
+                .then(b.created_at.cmp(&a.created_at))
+        })
+        .expect("cluster has members")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_find_clusters_complete_linkage() {
+        let vectors = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.99, 0.14, 0.0],
+            vec![0.0, 0.0, 1.0],
+            vec![0.98, 0.2, 0.0],
+            vec![0.0, 0.05, 1.0],
+        ];
+
+        let clusters = find_clusters(&vectors, 0.95, 5);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, vec![0, 1, 3]);
+        assert!(clusters[0].min_similarity >= 0.95);
+        assert_eq!(clusters[1].members, vec![2, 4]);
+
+        // Cluster size is capped
+        let capped = find_clusters(&vectors, 0.95, 2);
+        assert_eq!(capped[0].members, vec![0, 1]);
+
+        // Nothing is similar enough
+        assert!(find_clusters(&vectors, 0.9999, 5).is_empty());
+    }
+}
@@ -0,0 +1,454 @@
+//! Token-budgeted context packing.
+//!
+//! Turns ranked search candidates into a markdown bundle an agent can paste
+//! into its window. The budget is shared between code, decisions, specs and
+//! sessions in proportion to each kind's best match, and whatever a kind
+//! leaves unused goes to the best remaining candidates of any kind.
+//!
+//! Candidates are packed whole: code arrives as complete AST chunks and is
+//! never cut mid-function. Candidates overlapping a better one in the same
+//! file are dropped, and everything left out is listed in a manifest.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::models::MemoryType;
+
+/// Tokens kept back for the bundle title and section headings.
+const HEADING_RESERVE: usize = 50;
+
+/// Omitted items listed by name in the markdown manifest.
+const MAX_MANIFEST_LINES: usize = 20;
+
+/// Rough token count (about four bytes per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// Section a candidate is packed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextKind {
+    Code,
+    Decision,
+    Spec,
+    Session,
+    Other,
+}
+
+impl ContextKind {
+    /// Section order in the bundle.
+    const ALL: [ContextKind; 5] = [
+        ContextKind::Code,
+        ContextKind::Decision,
+        ContextKind::Spec,
+        ContextKind::Session,
+        ContextKind::Other,
+    ];
+
+    pub fn from_memory_type(memory_type: MemoryType) -> Self {
+        match memory_type {
+            MemoryType::Codebase => Self::Code,
+            MemoryType::Decision => Self::Decision,
+            MemoryType::Spec => Self::Spec,
+            MemoryType::Session => Self::Session,
+            _ => Self::Other,
+        }
+    }
+
+    fn heading(&self) -> &'static str {
+        match self {
+            Self::Code => "Code",
+            Self::Decision => "Decisions",
+            Self::Spec => "Specs",
+            Self::Session => "Sessions",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// A piece of content that may go into the bundle.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextCandidate {
+    pub memory_id: String,
+    pub kind: ContextKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Line range when the candidate is a chunk rather than a whole memory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+    /// Function, class or heading name of a chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    pub score: f32,
+    #[serde(skip)]
+    pub content: String,
+}
+
+impl ContextCandidate {
+    /// Whether two candidates cover some of the same content.
+    ///
+    /// A candidate without a line range covers its whole file or memory.
+    fn overlaps(&self, other: &ContextCandidate) -> bool {
+        let same_source = match (&self.file_path, &other.file_path) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.memory_id == other.memory_id,
+        };
+        if !same_source {
+            return false;
+        }
+
+        match (self.range(), other.range()) {
+            (Some((a_start, a_end)), Some((b_start, b_end))) => {
+                a_start <= b_end && b_start <= a_end
+            }
+            _ => true,
+        }
+    }
+
+    fn range(&self) -> Option<(i32, i32)> {
+        self.start_line.zip(self.end_line)
+    }
+
+    /// Markdown block for the candidate.
+    fn render(&self) -> String {
+        let mut heading = match (&self.file_path, self.range()) {
+            (Some(path), Some((start, end))) => format!("{}:{}-{}", path, start, end),
+            (Some(path), None) => path.clone(),
+            (None, _) => self.title.clone().unwrap_or_else(|| self.memory_id.clone()),
+        };
+        if let Some(name) = &self.node_name {
+            heading.push_str(&format!(" ({})", name));
+        } else if self.file_path.is_some() {
+            if let Some(title) = &self.title {
+                heading.push_str(&format!(" - {}", title));
+            }
+        }
+
+        let body = self.content.trim_end();
+        if self.kind == ContextKind::Code {
+            format!(
+                "### {}\n\n```{}\n{}\n```\n",
+                heading,
+                self.language.as_deref().unwrap_or(""),
+                body
+            )
+        } else {
+            format!("### {}\n\n{}\n", heading, body)
+        }
+    }
+}
+
+/// A candidate included in the bundle.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackedItem {
+    #[serde(flatten)]
+    pub candidate: ContextCandidate,
+    pub tokens: usize,
+}
+
+/// Why a candidate was left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OmitReason {
+    /// Covers lines already included from a better match
+    Overlap,
+    /// Did not fit the remaining budget
+    Budget,
+}
+
+/// A candidate left out of the bundle.
+#[derive(Debug, Clone, Serialize)]
+pub struct OmittedItem {
+    #[serde(flatten)]
+    pub candidate: ContextCandidate,
+    pub tokens: usize,
+    pub reason: OmitReason,
+}
+
+/// Context packed for a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackedContext {
+    pub task: String,
+    pub budget_tokens: usize,
+    /// Estimated tokens of the included items
+    pub used_tokens: usize,
+    pub items: Vec<PackedItem>,
+    pub omitted: Vec<OmittedItem>,
+    /// Ready-to-paste bundle, ending with a manifest of omitted items
+    pub markdown: String,
+}
+
+/// Pack candidates into a token budget.
+pub fn pack(task: &str, mut candidates: Vec<ContextCandidate>, budget: usize) -> PackedContext {
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Drop empty candidates and those overlapping a better one
+    let mut kept: Vec<(ContextCandidate, usize)> = Vec::new();
+    let mut omitted = Vec::new();
+    for candidate in candidates {
+        if candidate.content.trim().is_empty() {
+            continue;
+        }
+        let tokens = estimate_tokens(&candidate.render());
+        if kept.iter().any(|(k, _)| k.overlaps(&candidate)) {
+            omitted.push(OmittedItem {
+                candidate,
+                tokens,
+                reason: OmitReason::Overlap,
+            });
+        } else {
+            kept.push((candidate, tokens));
+        }
+    }
+
+    // Share the budget between kinds by their best score, so many middling
+    // code matches cannot crowd out a strong decision
+    let available = budget.saturating_sub(HEADING_RESERVE);
+    let best_score = |kind: ContextKind| {
+        kept.iter()
+            .filter(|(c, _)| c.kind == kind)
+            .map(|(c, _)| c.score.max(0.0))
+            .fold(0.0f32, f32::max)
+    };
+    let total_score: f32 = ContextKind::ALL.iter().map(|k| best_score(*k)).sum();
+    let mut included = vec![false; kept.len()];
+    let mut used = 0;
+
+    if total_score > 0.0 {
+        for kind in ContextKind::ALL {
+            let kind_score = best_score(kind);
+            let share = (available as f32 * kind_score / total_score) as usize;
+            let mut kind_used = 0;
+
+            for (i, (candidate, tokens)) in kept.iter().enumerate() {
+                if candidate.kind == kind && kind_used + tokens <= share {
+                    included[i] = true;
+                    kind_used += tokens;
+                }
+            }
+            used += kind_used;
+        }
+    }
+
+    // Hand unused budget to the best remaining candidates of any kind
+    for (i, (_, tokens)) in kept.iter().enumerate() {
+        if !included[i] && used + tokens <= available {
+            included[i] = true;
+            used += tokens;
+        }
+    }
+
+    let mut items = Vec::new();
+    for ((candidate, tokens), included) in kept.into_iter().zip(included) {
+        if included {
+            items.push(PackedItem { candidate, tokens });
+        } else {
+            omitted.push(OmittedItem {
+                candidate,
+                tokens,
+                reason: OmitReason::Budget,
+            });
+        }
+    }
+
+    let markdown = render_bundle(task, &items, &omitted);
+
+    PackedContext {
+        task: task.to_string(),
+        budget_tokens: budget,
+        used_tokens: used,
+        items,
+        omitted,
+        markdown,
+    }
+}
+
+fn render_bundle(task: &str, items: &[PackedItem], omitted: &[OmittedItem]) -> String {
+    let mut markdown = format!("# Context: {}\n", task.trim());
+
+    let mut kinds_seen = HashSet::new();
+    for kind in ContextKind::ALL {
+        for item in items.iter().filter(|i| i.candidate.kind == kind) {
+            if kinds_seen.insert(kind) {
+                markdown.push_str(&format!("\n## {}\n", kind.heading()));
+            }
+            markdown.push('\n');
+            markdown.push_str(&item.candidate.render());
+        }
+    }
+
+    if items.is_empty() {
+        markdown.push_str("\nNo relevant context found.\n");
+    }
+
+    let omitted: Vec<&OmittedItem> = omitted
+        .iter()
+        .filter(|o| o.reason == OmitReason::Budget)
+        .collect();
+    if !omitted.is_empty() {
+        markdown.push_str("\n## Omitted\n\n");
+        for item in omitted.iter().take(MAX_MANIFEST_LINES) {
+            let c = &item.candidate;
+            let name = match (&c.file_path, c.range()) {
+                (Some(path), Some((start, end))) => format!("{}:{}-{}", path, start, end),
+                (Some(path), None) => path.clone(),
+                (None, _) => c.title.clone().unwrap_or_else(|| c.memory_id.clone()),
+            };
+            markdown.push_str(&format!(
+                "- {} ({}, ~{} tokens, memory {})\n",
+                name,
+                kind_label(c.kind),
+                item.tokens,
+                c.memory_id
+            ));
+        }
+        if omitted.len() > MAX_MANIFEST_LINES {
+            markdown.push_str(&format!(
+                "- ...and {} more\n",
+                omitted.len() - MAX_MANIFEST_LINES
+            ));
+        }
+    }
+
+    markdown
+}
+
+fn kind_label(kind: ContextKind) -> &'static str {
+    match kind {
+        ContextKind::Code => "code",
+        ContextKind::Decision => "decision",
+        ContextKind::Spec => "spec",
+        ContextKind::Session => "session",
+        ContextKind::Other => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path: &str, start: i32, end: i32, score: f32, size: usize) -> ContextCandidate {
+        ContextCandidate {
+            memory_id: format!("mem-{}", path),
+            kind: ContextKind::Code,
+            title: None,
+            file_path: Some(path.to_string()),
+            language: Some("rust".to_string()),
+            start_line: Some(start),
+            end_line: Some(end),
+            node_name: Some(format!("fn_{}", start)),
+            score,
+            content: "x".repeat(size),
+        }
+    }
+
+    fn note(id: &str, kind: ContextKind, score: f32, size: usize) -> ContextCandidate {
+        ContextCandidate {
+            memory_id: id.to_string(),
+            kind,
+            title: Some(format!("Note {}", id)),
+            file_path: None,
+            language: None,
+            start_line: None,
+            end_line: None,
+            node_name: None,
+            score,
+            content: "y".repeat(size),
+        }
+    }
+
+    #[test]
+    fn test_overlapping_ranges_are_deduped() {
+        let packed = pack(
+            "refresh tokens",
+            vec![
+                chunk("src/auth.rs", 10, 40, 0.9, 100),
+                chunk("src/auth.rs", 30, 60, 0.8, 100),
+                chunk("src/auth.rs", 61, 80, 0.7, 100),
+                chunk("src/other.rs", 30, 60, 0.6, 100),
+            ],
+            10_000,
+        );
+
+        assert_eq!(packed.items.len(), 3);
+        assert_eq!(packed.omitted.len(), 1);
+        assert_eq!(packed.omitted[0].reason, OmitReason::Overlap);
+        assert_eq!(packed.omitted[0].candidate.start_line, Some(30));
+        // Overlaps are not listed as missing context
+        assert!(!packed.markdown.contains("## Omitted"));
+    }
+
+    #[test]
+    fn test_budget_is_shared_by_score() {
+        // Code scores better but would fill the whole budget on its own
+        let mut candidates: Vec<_> = (0..10)
+            .map(|i| chunk(&format!("src/{}.rs", i), 1, 20, 0.9, 400))
+            .collect();
+        candidates.push(note("dec-1", ContextKind::Decision, 0.6, 400));
+
+        let packed = pack("why postgres", candidates, 1000);
+
+        assert!(packed.used_tokens <= 1000);
+        assert!(packed
+            .items
+            .iter()
+            .any(|i| i.candidate.kind == ContextKind::Decision));
+        assert!(packed
+            .items
+            .iter()
+            .any(|i| i.candidate.kind == ContextKind::Code));
+        assert!(packed
+            .omitted
+            .iter()
+            .all(|o| o.reason == OmitReason::Budget));
+    }
+
+    #[test]
+    fn test_unused_share_goes_to_other_kinds() {
+        // The session is too big for its share and for the leftover budget
+        let packed = pack(
+            "task",
+            vec![
+                chunk("src/a.rs", 1, 10, 0.5, 400),
+                chunk("src/b.rs", 1, 10, 0.5, 400),
+                note("sess-1", ContextKind::Session, 0.5, 4000),
+            ],
+            1000,
+        );
+
+        let ids: Vec<_> = packed
+            .items
+            .iter()
+            .map(|i| i.candidate.memory_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["mem-src/a.rs", "mem-src/b.rs"]);
+        assert_eq!(packed.omitted.len(), 1);
+        assert!(packed.markdown.contains("## Omitted"));
+        assert!(packed.markdown.contains("Note sess-1 (session"));
+    }
+
+    #[test]
+    fn test_markdown_keeps_whole_chunks() {
+        let mut code = chunk("src/auth.rs", 10, 12, 0.9, 0);
+        code.content = "fn refresh() {\n    rotate();\n}".to_string();
+        let packed = pack("refresh", vec![code], 1000);
+
+        assert!(packed.markdown.starts_with("# Context: refresh\n"));
+        assert!(packed.markdown.contains("## Code"));
+        assert!(packed.markdown.contains(
+            "### src/auth.rs:10-12 (fn_10)\n\n```rust\nfn refresh() {\n    rotate();\n}\n```"
+        ));
+    }
+}
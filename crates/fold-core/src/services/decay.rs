@@ -1,10 +1,24 @@
 //! Memory decay and retrieval strength calculation.
 //!
-//! Implements an ACT-R inspired decay model where memory strength decays
-//! exponentially over time but is boosted by retrieval frequency.
+//! Strength is computed by one of three models, chosen per project:
+//! - Exponential: half-life decay from the last use, boosted by use count
+//! - Power law: ACT-R base-level activation over the retrieval history
+//! - None: strength depends on feedback only
+//!
+//! Half-lives can be set per memory type, and a type with no half-life does
+//! not decay, so decisions and specs need not fade like session notes.
+//!
+//! Search ranking counts only results that agents marked as used, so being
+//! returned by a search does not make a memory stronger. Memories linked to
+//! by many others can also get a PageRank boost.
+
+use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 
+use crate::db::{DecayModel, FeedbackCounts, Project, ScoringSettings, DEFAULT_ACTR_DECAY};
+use crate::models::{ChunkMatch, Memory, MemorySearchResult, ScoreBreakdown};
+
 /// Default half-life in days for memory decay.
 pub const DEFAULT_HALF_LIFE_DAYS: f64 = 30.0;
 
@@ -17,21 +31,46 @@ pub const MIN_STRENGTH: f64 = 0.01;
 /// Maximum strength cap.
 pub const MAX_STRENGTH: f64 = 1.0;
 
+/// Strength lost per "irrelevant" verdict.
+const IRRELEVANT_PENALTY: f64 = 0.02;
+
+/// Most strength "irrelevant" verdicts can take away. Irrelevance is
+/// specific to a query, so it should not bury a memory.
+const MAX_IRRELEVANT_PENALTY: f64 = 0.1;
+
+/// Strength lost per unresolved "wrong" verdict.
+const WRONG_PENALTY: f64 = 0.15;
+
+/// Shortest time since an access used for activation, so a memory read
+/// moments ago does not get unbounded activation.
+const MIN_ACCESS_AGE_DAYS: f64 = 1.0 / 24.0;
+
+/// Most recent accesses per memory used for activation.
+pub const ACCESS_HISTORY_LIMIT: i64 = 50;
+
+/// Memories per access history query.
+pub const ACCESS_BATCH_SIZE: usize = 200;
+
 /// Configuration for decay calculations.
 #[derive(Debug, Clone)]
 pub struct DecayConfig {
-    /// Half-life in days for exponential decay.
+    /// Half-life in days for memory types without their own.
     pub half_life_days: f64,
     /// Weight for blending strength with semantic score (0.0-1.0).
     pub strength_weight: f64,
+    /// How strength decays with time.
+    pub model: DecayModel,
+    /// Half-life overrides by memory type. `None` never decays.
+    pub type_half_lives: HashMap<String, Option<f64>>,
+    /// ACT-R decay exponent for the power-law model.
+    pub actr_decay: f64,
+    /// Weight of link rank added to the combined score.
+    pub link_boost_weight: f64,
 }
 
 impl Default for DecayConfig {
     fn default() -> Self {
-        Self {
-            half_life_days: DEFAULT_HALF_LIFE_DAYS,
-            strength_weight: DEFAULT_STRENGTH_WEIGHT,
-        }
+        Self::new(DEFAULT_HALF_LIFE_DAYS, DEFAULT_STRENGTH_WEIGHT)
     }
 }
 
@@ -41,16 +80,164 @@ impl DecayConfig {
         Self {
             half_life_days: half_life_days.max(1.0),
             strength_weight: strength_weight.clamp(0.0, 1.0),
+            model: DecayModel::Exponential,
+            type_half_lives: HashMap::new(),
+            actr_decay: DEFAULT_ACTR_DECAY,
+            link_boost_weight: 0.0,
         }
     }
 
     /// Create config for pure semantic search (no decay weighting).
     pub fn pure_semantic() -> Self {
-        Self {
-            half_life_days: DEFAULT_HALF_LIFE_DAYS,
-            strength_weight: 0.0,
+        Self::new(DEFAULT_HALF_LIFE_DAYS, 0.0)
+    }
+
+    /// Apply a project's scoring settings.
+    pub fn with_scoring(mut self, settings: &ScoringSettings) -> Self {
+        self.model = settings.model();
+        self.type_half_lives = settings.type_half_lives();
+        self.actr_decay = settings.actr_decay.clamp(0.1, 1.0);
+        self.link_boost_weight = settings.link_boost_weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Half-life for a memory type, or `None` if memories of the type do
+    /// not decay.
+    pub fn half_life_for(&self, memory_type: &str) -> Option<f64> {
+        if self.model == DecayModel::NoDecay {
+            return None;
+        }
+        match self.type_half_lives.get(memory_type) {
+            Some(half_life) => half_life.map(|h| h.max(1.0)),
+            None => Some(self.half_life_days),
         }
     }
+
+    /// Calculate a memory's retrieval strength with its breakdown.
+    ///
+    /// `accesses` is the memory's retrieval history; only the power-law
+    /// model reads it.
+    pub fn strength(
+        &self,
+        memory_type: &str,
+        updated_at: DateTime<Utc>,
+        feedback: &FeedbackCounts,
+        accesses: &[DateTime<Utc>],
+        now: DateTime<Utc>,
+    ) -> (f64, ScoreBreakdown) {
+        let half_life = self.half_life_for(memory_type);
+        let model = match half_life {
+            Some(_) => self.model,
+            None => DecayModel::NoDecay,
+        };
+
+        let last_used = match feedback.last_used {
+            Some(used) if used > updated_at => used,
+            _ => updated_at,
+        };
+        let last_access = match model {
+            DecayModel::PowerLaw => accesses.iter().copied().fold(updated_at, DateTime::max),
+            _ => last_used,
+        };
+        let age_days = days_between(last_access, now);
+
+        let (decay, activation, access_boost) = match (model, half_life) {
+            (DecayModel::Exponential, Some(half_life)) => (
+                0.5_f64.powf(days_between(last_used, now) / half_life),
+                None,
+                use_boost(feedback.used),
+            ),
+            (DecayModel::PowerLaw, Some(half_life)) => {
+                let mut presentations = accesses.to_vec();
+                presentations.push(updated_at);
+                let activation = base_level_activation(&presentations, self.actr_decay, now);
+                (
+                    activation_strength(activation, half_life, self.actr_decay),
+                    Some(activation),
+                    0.0,
+                )
+            }
+            _ => (MAX_STRENGTH, None, 0.0),
+        };
+
+        let penalty = feedback_penalty(feedback);
+        let strength = ((decay + access_boost).clamp(MIN_STRENGTH, MAX_STRENGTH) - penalty)
+            .clamp(MIN_STRENGTH, MAX_STRENGTH);
+
+        (
+            strength,
+            ScoreBreakdown {
+                model: model.as_str().to_string(),
+                half_life_days: half_life.map(|h| h as f32),
+                age_days: age_days as f32,
+                decay: decay as f32,
+                activation: activation.map(|a| a as f32),
+                access_boost: access_boost as f32,
+                feedback_penalty: penalty as f32,
+                strength_weight: self.strength_weight as f32,
+                link_rank: 0.0,
+                link_boost: 0.0,
+                vector_score: None,
+                rerank: Vec::new(),
+            },
+        )
+    }
+}
+
+/// Decay config from a project's algorithm settings, with defaults for unset values.
+pub fn project_decay_config(project: &Project) -> DecayConfig {
+    DecayConfig::new(
+        project
+            .decay_half_life_days
+            .unwrap_or(DEFAULT_HALF_LIFE_DAYS),
+        project
+            .decay_strength_weight
+            .unwrap_or(DEFAULT_STRENGTH_WEIGHT),
+    )
+}
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to.signed_duration_since(from).num_seconds() as f64 / 86400.0).max(0.0)
+}
+
+/// Strength added for repeated retrievals.
+fn use_boost(retrieval_count: i64) -> f64 {
+    if retrieval_count > 0 {
+        (1.0 + retrieval_count as f64).log2() * 0.1
+    } else {
+        0.0
+    }
+}
+
+/// Strength taken away by "irrelevant" and unresolved "wrong" verdicts.
+fn feedback_penalty(feedback: &FeedbackCounts) -> f64 {
+    (feedback.irrelevant as f64 * IRRELEVANT_PENALTY).min(MAX_IRRELEVANT_PENALTY)
+        + feedback.wrong as f64 * WRONG_PENALTY
+}
+
+/// ACT-R base-level activation: `ln(sum of t^-d)` over the times since each
+/// presentation, in days.
+pub fn base_level_activation(
+    presentations: &[DateTime<Utc>],
+    decay: f64,
+    now: DateTime<Utc>,
+) -> f64 {
+    presentations
+        .iter()
+        .map(|&at| days_between(at, now).max(MIN_ACCESS_AGE_DAYS).powf(-decay))
+        .sum::<f64>()
+        .ln()
+}
+
+/// Map activation to a strength between 0 and 1.
+///
+/// The retrieval threshold is set so a memory presented once, `half_life`
+/// days ago, has strength 0.5, matching the exponential model. Strength then
+/// falls off as `h / (h + t)` rather than exponentially, and each further
+/// retrieval adds to it.
+pub fn activation_strength(activation: f64, half_life_days: f64, decay: f64) -> f64 {
+    let threshold = -decay * half_life_days.ln();
+    1.0 / (1.0 + (-(activation - threshold) / decay).exp())
 }
 
 /// Calculate the retrieval strength of a memory.
@@ -73,16 +260,31 @@ pub fn calculate_strength(
 
     let decay_factor = 0.5_f64.powf(days_elapsed / half_life_days);
 
-    let access_boost = if retrieval_count > 0 {
-        (1.0 + retrieval_count as f64).log2() * 0.1
-    } else {
-        0.0
-    };
+    let access_boost = use_boost(retrieval_count as i64);
 
     let strength = decay_factor + access_boost;
     strength.clamp(MIN_STRENGTH, MAX_STRENGTH)
 }
 
+/// Calculate retrieval strength from explicit feedback.
+///
+/// Only "used" verdicts count as retrievals, and the last one resets decay.
+/// "Irrelevant" and unresolved "wrong" verdicts subtract.
+pub fn feedback_strength(
+    updated_at: DateTime<Utc>,
+    feedback: &FeedbackCounts,
+    half_life_days: f64,
+) -> f64 {
+    let strength = calculate_strength(
+        updated_at,
+        feedback.last_used,
+        feedback.used.min(i32::MAX as i64) as i32,
+        half_life_days,
+    );
+
+    (strength - feedback_penalty(feedback)).clamp(MIN_STRENGTH, MAX_STRENGTH)
+}
+
 /// Blend semantic relevance score with retrieval strength.
 pub fn blend_scores(relevance: f64, strength: f64, strength_weight: f64) -> f64 {
     let weight = strength_weight.clamp(0.0, 1.0);
@@ -122,6 +324,120 @@ pub fn rerank_by_combined_score<T>(results: &mut [ScoredResult<T>]) {
     });
 }
 
+/// Scores search results for one project.
+///
+/// Holds everything strength depends on beyond the memory itself: feedback,
+/// retrieval history and link rank.
+#[derive(Debug, Clone)]
+pub struct Scorer {
+    config: DecayConfig,
+    feedback: HashMap<String, FeedbackCounts>,
+    accesses: HashMap<String, Vec<DateTime<Utc>>>,
+    link_rank: HashMap<String, f64>,
+    now: DateTime<Utc>,
+}
+
+impl Scorer {
+    pub fn new(config: DecayConfig) -> Self {
+        Self {
+            config,
+            feedback: HashMap::new(),
+            accesses: HashMap::new(),
+            link_rank: HashMap::new(),
+            now: Utc::now(),
+        }
+    }
+
+    /// Set feedback totals by memory ID.
+    pub fn with_feedback(mut self, feedback: HashMap<String, FeedbackCounts>) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Set retrieval history by memory ID.
+    pub fn with_accesses(mut self, accesses: HashMap<String, Vec<DateTime<Utc>>>) -> Self {
+        self.accesses = accesses;
+        self
+    }
+
+    /// Set link rank by memory ID.
+    pub fn with_link_rank(mut self, link_rank: HashMap<String, f64>) -> Self {
+        self.link_rank = link_rank;
+        self
+    }
+
+    /// Score as of a past time. Retrievals after `now` are ignored.
+    pub fn at(mut self, now: DateTime<Utc>) -> Self {
+        for times in self.accesses.values_mut() {
+            times.retain(|t| *t <= now);
+        }
+        self.now = now;
+        self
+    }
+
+    pub fn config(&self) -> &DecayConfig {
+        &self.config
+    }
+
+    /// A memory's retrieval strength with its breakdown.
+    pub fn strength(&self, memory: &Memory) -> (f64, ScoreBreakdown) {
+        let no_feedback = FeedbackCounts::default();
+        self.config.strength(
+            &memory.memory_type,
+            memory.updated_at,
+            self.feedback.get(&memory.id).unwrap_or(&no_feedback),
+            self.accesses
+                .get(&memory.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            self.now,
+        )
+    }
+
+    /// Score a memory against its relevance to the query.
+    pub fn result(
+        &self,
+        memory: Memory,
+        relevance: f32,
+        matched_chunks: Vec<ChunkMatch>,
+    ) -> MemorySearchResult {
+        let (strength, mut breakdown) = self.strength(&memory);
+        breakdown.vector_score = Some(relevance);
+
+        let link_rank = self.link_rank.get(&memory.id).copied().unwrap_or(0.0);
+        let link_boost = self.config.link_boost_weight * link_rank;
+        breakdown.link_rank = link_rank as f32;
+        breakdown.link_boost = link_boost as f32;
+
+        let combined_score =
+            blend_scores(relevance as f64, strength, self.config.strength_weight) + link_boost;
+
+        MemorySearchResult::with_chunks(
+            memory,
+            relevance,
+            strength as f32,
+            combined_score as f32,
+            matched_chunks,
+        )
+        .with_breakdown(breakdown)
+    }
+
+    /// Score a memory found only through its chunks, using the best chunk
+    /// score as relevance.
+    pub fn chunk_result(
+        &self,
+        memory: Memory,
+        matched_chunks: Vec<ChunkMatch>,
+    ) -> MemorySearchResult {
+        let relevance = matched_chunks.first().map(|c| c.score).unwrap_or(0.0);
+        let mut result = self.result(memory, relevance, matched_chunks);
+        if let Some(breakdown) = result.breakdown.as_mut() {
+            breakdown.vector_score = None;
+        }
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +505,95 @@ mod tests {
         let strength = calculate_strength(now, Some(now), 1000, 30.0);
         assert!(strength <= MAX_STRENGTH);
     }
+
+    #[test]
+    fn test_feedback_strength() {
+        let now = Utc::now();
+        let thirty_days_ago = now - Duration::days(30);
+        let none = feedback_strength(thirty_days_ago, &FeedbackCounts::default(), 30.0);
+
+        let used = FeedbackCounts {
+            used: 3,
+            last_used: Some(now - Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(feedback_strength(thirty_days_ago, &used, 30.0) > none);
+
+        let wrong = FeedbackCounts {
+            wrong: 2,
+            ..Default::default()
+        };
+        assert!(feedback_strength(thirty_days_ago, &wrong, 30.0) < none - 0.25);
+
+        // Irrelevant verdicts are capped
+        let irrelevant = FeedbackCounts {
+            irrelevant: 100,
+            ..Default::default()
+        };
+        let strength = feedback_strength(thirty_days_ago, &irrelevant, 30.0);
+        assert!((none - strength - MAX_IRRELEVANT_PENALTY).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_exponential_model_matches_feedback_strength() {
+        let now = Utc::now();
+        let config = DecayConfig::new(30.0, 0.3);
+        let feedback = FeedbackCounts {
+            used: 2,
+            irrelevant: 1,
+            last_used: Some(now - Duration::days(5)),
+            ..Default::default()
+        };
+        let updated_at = now - Duration::days(40);
+
+        let (strength, breakdown) = config.strength("general", updated_at, &feedback, &[], now);
+        assert!((strength - feedback_strength(updated_at, &feedback, 30.0)).abs() < 0.001);
+        assert_eq!(breakdown.model, "exponential");
+        assert_eq!(breakdown.half_life_days, Some(30.0));
+    }
+
+    #[test]
+    fn test_type_half_lives() {
+        let now = Utc::now();
+        let mut config = DecayConfig::new(30.0, 0.3);
+        config.type_half_lives.insert("decision".to_string(), None);
+        config
+            .type_half_lives
+            .insert("session".to_string(), Some(7.0));
+        let old = now - Duration::days(60);
+        let feedback = FeedbackCounts::default();
+
+        let (decision, breakdown) = config.strength("decision", old, &feedback, &[], now);
+        assert_eq!(decision, MAX_STRENGTH);
+        assert_eq!(breakdown.model, "none");
+        assert_eq!(breakdown.half_life_days, None);
+
+        let (general, _) = config.strength("general", old, &feedback, &[], now);
+        let (session, _) = config.strength("session", old, &feedback, &[], now);
+        assert!(session < general);
+    }
+
+    #[test]
+    fn test_power_law_model() {
+        let now = Utc::now();
+        let mut config = DecayConfig::new(30.0, 0.3);
+        config.model = DecayModel::PowerLaw;
+        let feedback = FeedbackCounts::default();
+
+        // One presentation a half-life ago gives half strength
+        let (strength, breakdown) =
+            config.strength("general", now - Duration::days(30), &feedback, &[], now);
+        assert!((strength - 0.5).abs() < 0.001);
+        assert!(breakdown.activation.is_some());
+
+        // Decays more slowly than exponential in the tail
+        let old = now - Duration::days(120);
+        let (power, _) = config.strength("general", old, &feedback, &[], now);
+        assert!(power > calculate_strength(old, None, 0, 30.0));
+
+        // Each retrieval adds activation
+        let accesses = [now - Duration::days(10), now - Duration::days(3)];
+        let (accessed, _) = config.strength("general", old, &feedback, &accesses, now);
+        assert!(accessed > power);
+    }
 }
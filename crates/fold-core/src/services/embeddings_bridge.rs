@@ -16,7 +16,7 @@ use tracing::{info, warn};
 use crate::config::EmbeddingConfig;
 use crate::db::{
     list_enabled_embedding_providers, seed_embedding_providers_from_env,
-    update_embedding_provider_last_used, DbPool, EmbeddingProviderRow,
+    update_embedding_provider_last_used, DbPool, EmbeddingProviderRow, ProjectProviderRow,
 };
 use crate::error::{Error, Result};
 
@@ -63,6 +63,37 @@ fn row_to_runtime_provider(row: EmbeddingProviderRow) -> RuntimeEmbeddingProvide
     }
 }
 
+/// Convert a project provider override to a runtime provider.
+fn project_row_to_runtime_provider(row: ProjectProviderRow) -> RuntimeEmbeddingProvider {
+    let config = row.config_json().unwrap_or(json!({}));
+
+    RuntimeEmbeddingProvider {
+        id: row.id,
+        name: row.name.clone(),
+        base_url: config
+            .get("endpoint")
+            .and_then(|e| e.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| default_endpoint(&row.name)),
+        model: config
+            .get("model")
+            .and_then(|m| m.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| default_model(&row.name)),
+        api_key: row.api_key,
+        oauth_access_token: None,
+        dimension: config
+            .get("dimension")
+            .and_then(|d| d.as_u64())
+            .map(|d| d as usize),
+        priority: row.priority,
+        search_priority: config
+            .get("search_priority")
+            .and_then(|p| p.as_i64())
+            .map(|p| p as i32),
+    }
+}
+
 /// Service for generating text embeddings with multi-provider fallback.
 ///
 /// This is a wrapper around `fold_embeddings::EmbeddingService` that adds
@@ -164,6 +195,31 @@ impl EmbeddingService {
         Ok(Self { inner, db: None })
     }
 
+    /// Create an embedding service over a project's pinned provider chain.
+    ///
+    /// The dimension comes from the first provider, falling back to
+    /// `default_dim`. The chain is fixed: it is not reloaded from the global
+    /// providers.
+    pub fn from_project_providers(
+        rows: Vec<ProjectProviderRow>,
+        default_dim: usize,
+    ) -> Result<Self> {
+        let providers: Vec<RuntimeEmbeddingProvider> = rows
+            .into_iter()
+            .map(project_row_to_runtime_provider)
+            .collect();
+
+        let dimension = providers
+            .first()
+            .and_then(|p| p.dimension.or_else(|| Some(default_dimension(&p.model))))
+            .unwrap_or(default_dim);
+
+        let inner = BaseEmbeddingService::from_providers(providers, dimension, None)
+            .map_err(|e| Error::Internal(e.to_string()))?;
+
+        Ok(Self { inner, db: None })
+    }
+
     /// Reload providers from the database.
     pub async fn refresh_providers(&self) -> Result<()> {
         let db = self
@@ -202,6 +258,12 @@ impl EmbeddingService {
         self.inner.has_providers().await
     }
 
+    /// Get the model vectors are currently produced with (`None` for
+    /// hash-based placeholders).
+    pub async fn primary_model(&self) -> Option<String> {
+        self.inner.primary_model().await
+    }
+
     /// Generate embeddings for multiple texts.
     pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
         self.inner
@@ -9,8 +9,9 @@ use tokio::sync::broadcast;
 
 use super::events::{
     FoldEvent, HeartbeatEvent, IndexingEvent, IndexingProgressEvent, JobEvent, JobFailedEvent,
-    JobLogEvent, JobProgressEvent, ProviderEvent,
+    JobLogEvent, JobProgressEvent, LinkEvent, MemoryEvent, ProviderEvent, TeamStatusEvent,
 };
+use crate::models::Memory;
 
 /// Channel capacity for event broadcasting.
 /// Should be large enough to handle bursts without losing events.
@@ -257,6 +258,111 @@ impl EventBroadcaster {
             timestamp: chrono::Utc::now().to_rfc3339(),
         }));
     }
+
+    /// Emit a team presence change event.
+    pub fn team_status_changed(
+        &self,
+        project_id: &str,
+        username: &str,
+        status: &str,
+        current_task: Option<&str>,
+        current_files: Vec<String>,
+    ) {
+        self.emit(FoldEvent::TeamStatusChanged(TeamStatusEvent {
+            project_id: project_id.to_string(),
+            username: username.to_string(),
+            status: status.to_string(),
+            current_task: current_task.map(String::from),
+            current_files,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+
+    /// Emit a team member left event.
+    pub fn team_member_left(&self, project_id: &str, username: &str) {
+        self.emit(FoldEvent::TeamMemberLeft(TeamStatusEvent {
+            project_id: project_id.to_string(),
+            username: username.to_string(),
+            status: "left".to_string(),
+            current_task: None,
+            current_files: Vec::new(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+
+    /// Emit a memory created event.
+    pub fn memory_created(&self, memory: &Memory) {
+        self.emit(FoldEvent::MemoryCreated(memory_event(memory)));
+    }
+
+    /// Emit a memory updated event.
+    pub fn memory_updated(&self, memory: &Memory) {
+        self.emit(FoldEvent::MemoryUpdated(memory_event(memory)));
+    }
+
+    /// Emit a memory deleted event.
+    pub fn memory_deleted(&self, project_id: &str, memory_id: &str) {
+        self.emit(FoldEvent::MemoryDeleted(MemoryEvent {
+            project_id: project_id.to_string(),
+            memory_id: memory_id.to_string(),
+            memory_type: None,
+            title: None,
+            source: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+
+    /// Emit a link created event.
+    pub fn link_created(
+        &self,
+        project_id: &str,
+        link_id: &str,
+        source_id: &str,
+        target_id: &str,
+        link_type: &str,
+        created_by: &str,
+    ) {
+        self.emit(FoldEvent::LinkCreated(LinkEvent {
+            project_id: project_id.to_string(),
+            link_id: link_id.to_string(),
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            link_type: link_type.to_string(),
+            created_by: Some(created_by.to_string()),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+
+    /// Emit a link deleted event.
+    pub fn link_deleted(
+        &self,
+        project_id: &str,
+        link_id: &str,
+        source_id: &str,
+        target_id: &str,
+        link_type: &str,
+    ) {
+        self.emit(FoldEvent::LinkDeleted(LinkEvent {
+            project_id: project_id.to_string(),
+            link_id: link_id.to_string(),
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            link_type: link_type.to_string(),
+            created_by: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+}
+
+fn memory_event(memory: &Memory) -> MemoryEvent {
+    MemoryEvent {
+        project_id: memory.project_id.clone(),
+        memory_id: memory.id.clone(),
+        memory_type: Some(memory.memory_type.clone()),
+        title: memory.title.clone(),
+        source: memory.source.clone(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
 }
 
 impl Default for EventBroadcaster {
@@ -305,6 +411,24 @@ mod tests {
         assert!(matches!(event2, FoldEvent::Heartbeat(_)));
     }
 
+    #[tokio::test]
+    async fn test_memory_lifecycle_events() {
+        let broadcaster = EventBroadcaster::new();
+        let mut receiver = broadcaster.subscribe();
+
+        broadcaster.link_created("proj-1", "link-1", "mem-1", "mem-2", "related", "user");
+        broadcaster.memory_deleted("proj-1", "mem-1");
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type(), "link:created");
+        assert_eq!(event.project_id(), Some("proj-1"));
+        assert!(event.is_memory_lifecycle());
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type(), "memory:deleted");
+        assert_eq!(event.project_id(), Some("proj-1"));
+    }
+
     #[test]
     fn test_no_subscribers_ok() {
         let broadcaster = EventBroadcaster::new();
@@ -1,7 +1,7 @@
 //! Event types for Server-Sent Events (SSE).
 //!
 //! These events are broadcast to connected clients for real-time updates
-//! on job progress, indexing status, and system health.
+//! on job progress, indexing status, memory changes, and system health.
 
 use serde::{Deserialize, Serialize};
 
@@ -42,6 +42,23 @@ pub enum FoldEvent {
 
     /// Job log entry (admin-only)
     JobLog(JobLogEvent),
+
+    /// Team member presence changed (status, task or files)
+    TeamStatusChanged(TeamStatusEvent),
+    /// Team member left the project presence list
+    TeamMemberLeft(TeamStatusEvent),
+
+    /// Memory was created
+    MemoryCreated(MemoryEvent),
+    /// Memory content or metadata was updated
+    MemoryUpdated(MemoryEvent),
+    /// Memory was deleted
+    MemoryDeleted(MemoryEvent),
+
+    /// Link between two memories was created
+    LinkCreated(LinkEvent),
+    /// Link between two memories was deleted
+    LinkDeleted(LinkEvent),
 }
 
 impl FoldEvent {
@@ -62,6 +79,13 @@ impl FoldEvent {
             FoldEvent::HealthStatusChanged(_) => "health:changed",
             FoldEvent::Heartbeat(_) => "heartbeat",
             FoldEvent::JobLog(_) => "job:log",
+            FoldEvent::TeamStatusChanged(_) => "team:status",
+            FoldEvent::TeamMemberLeft(_) => "team:left",
+            FoldEvent::MemoryCreated(_) => "memory:created",
+            FoldEvent::MemoryUpdated(_) => "memory:updated",
+            FoldEvent::MemoryDeleted(_) => "memory:deleted",
+            FoldEvent::LinkCreated(_) => "link:created",
+            FoldEvent::LinkDeleted(_) => "link:deleted",
         }
     }
 
@@ -78,6 +102,13 @@ impl FoldEvent {
             FoldEvent::IndexingProgress(e) => Some(&e.project_id),
             FoldEvent::IndexingCompleted(e) => Some(&e.project_id),
             FoldEvent::JobLog(e) => e.project_id.as_deref(),
+            FoldEvent::TeamStatusChanged(e) => Some(&e.project_id),
+            FoldEvent::TeamMemberLeft(e) => Some(&e.project_id),
+            FoldEvent::MemoryCreated(e) => Some(&e.project_id),
+            FoldEvent::MemoryUpdated(e) => Some(&e.project_id),
+            FoldEvent::MemoryDeleted(e) => Some(&e.project_id),
+            FoldEvent::LinkCreated(e) => Some(&e.project_id),
+            FoldEvent::LinkDeleted(e) => Some(&e.project_id),
             // Provider and health events are global
             FoldEvent::ProviderAvailable(_) => None,
             FoldEvent::ProviderUnavailable(_) => None,
@@ -101,6 +132,20 @@ impl FoldEvent {
     pub fn is_admin_only(&self) -> bool {
         matches!(self, FoldEvent::JobLog(_))
     }
+
+    /// Check if this is a memory or link lifecycle event.
+    ///
+    /// These are the events delivered to outbound webhooks.
+    pub fn is_memory_lifecycle(&self) -> bool {
+        matches!(
+            self,
+            FoldEvent::MemoryCreated(_)
+                | FoldEvent::MemoryUpdated(_)
+                | FoldEvent::MemoryDeleted(_)
+                | FoldEvent::LinkCreated(_)
+                | FoldEvent::LinkDeleted(_)
+        )
+    }
 }
 
 /// Basic job event with minimal information.
@@ -204,3 +249,41 @@ pub struct JobLogEvent {
     pub metadata: Option<serde_json::Value>,
     pub timestamp: String,
 }
+
+/// Team presence event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamStatusEvent {
+    pub project_id: String,
+    pub username: String,
+    /// Status: "active", "idle", or "away"
+    pub status: String,
+    pub current_task: Option<String>,
+    pub current_files: Vec<String>,
+    pub timestamp: String,
+}
+
+/// Memory lifecycle event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEvent {
+    pub project_id: String,
+    pub memory_id: String,
+    /// Memory type, e.g. "decision" (unset for deletions)
+    pub memory_type: Option<String>,
+    pub title: Option<String>,
+    /// Memory source: "agent", "file" or "git" (unset for deletions)
+    pub source: Option<String>,
+    pub timestamp: String,
+}
+
+/// Memory link lifecycle event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkEvent {
+    pub project_id: String,
+    pub link_id: String,
+    pub source_id: String,
+    pub target_id: String,
+    pub link_type: String,
+    /// Who created the link: "user", "ai", "evolution", ...
+    pub created_by: Option<String>,
+    pub timestamp: String,
+}
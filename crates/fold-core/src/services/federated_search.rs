@@ -0,0 +1,338 @@
+//! Federated search across projects.
+//!
+//! Runs one semantic search per project the caller can read and merges the
+//! hits into a single ranked list attributed to their projects. Projects may
+//! embed with different providers, so raw similarities are not comparable
+//! between them; each project's candidates are min-max normalised and blended
+//! with their raw scores before the lists are merged, so a project's best hit
+//! does not outrank a strong hit elsewhere just for being its project's best.
+//! A project whose search fails, or whose embedding quota is used up, is
+//! reported and skipped rather than failing the whole request.
+
+use std::collections::HashSet;
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::db::{self, DbPool, Project, UsageKind};
+use crate::error::Result;
+use crate::models::MemorySearchResult;
+
+use super::{MemoryService, PermissionService};
+
+/// Projects searched at the same time.
+const MAX_PARALLEL_PROJECTS: usize = 8;
+
+/// Weight of the raw combined score in a hit's ranking score; the rest comes
+/// from the within-project normalised score.
+const RAW_SCORE_WEIGHT: f32 = 0.5;
+
+/// Federated search options.
+#[derive(Debug, Clone)]
+pub struct FederatedSearchOptions {
+    /// Results returned overall
+    pub limit: usize,
+    /// Results taken from any one project
+    pub per_project_limit: usize,
+    /// Minimum raw similarity for a hit to be kept
+    pub min_score: f32,
+    /// Only search these projects (IDs or slugs); empty searches all
+    pub include: Vec<String>,
+    /// Never search these projects (IDs or slugs)
+    pub exclude: Vec<String>,
+}
+
+impl Default for FederatedSearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: 20,
+            per_project_limit: 10,
+            min_score: 0.4,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// A search hit and the project it came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct FederatedHit {
+    pub project_id: String,
+    pub project_slug: String,
+    pub project_name: String,
+    /// Score normalised within the project and blended with the raw score
+    /// (0.0 - 1.0), used for ranking
+    pub normalized_score: f32,
+    #[serde(flatten)]
+    pub result: MemorySearchResult,
+}
+
+/// A project whose search failed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectFailure {
+    pub project_id: String,
+    pub project_slug: String,
+    pub error: String,
+}
+
+/// Merged results of a federated search.
+#[derive(Debug, Clone, Serialize)]
+pub struct FederatedSearchResults {
+    pub query: String,
+    /// Number of projects searched
+    pub projects_searched: usize,
+    pub results: Vec<FederatedHit>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<ProjectFailure>,
+}
+
+/// Service for searching every project a user can read.
+#[derive(Clone)]
+pub struct FederatedSearchService {
+    db: DbPool,
+    memory: MemoryService,
+    permissions: PermissionService,
+}
+
+impl FederatedSearchService {
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self {
+            permissions: PermissionService::new(db.clone()),
+            db,
+            memory,
+        }
+    }
+
+    /// Search all projects readable by the user.
+    ///
+    /// Include and exclude entries that do not name a readable project are
+    /// ignored, so the response never reveals projects the user cannot see.
+    pub async fn search(
+        &self,
+        user_id: &str,
+        user_role: &str,
+        query: &str,
+        options: &FederatedSearchOptions,
+    ) -> Result<FederatedSearchResults> {
+        let accessible: HashSet<String> = self
+            .permissions
+            .get_accessible_projects(user_id, user_role)
+            .await?
+            .into_iter()
+            .collect();
+
+        let projects = select_projects(
+            db::list_projects(&self.db).await?,
+            &accessible,
+            &options.include,
+            &options.exclude,
+        );
+        let projects_searched = projects.len();
+
+        // Fetch extra candidates so normalisation sees each project's weak tail
+        let fetch_limit = options.per_project_limit * 2;
+        let outcomes: Vec<(Project, Result<Vec<MemorySearchResult>>)> =
+            stream::iter(projects.into_iter().map(|project| async move {
+                // The request check covers no project, so each is checked here
+                let outcome = match super::rate_limit::check_quota(
+                    &self.db,
+                    &project.id,
+                    UsageKind::Embedding,
+                )
+                .await
+                {
+                    Ok(()) => {
+                        self.memory
+                            .search_with_type(&project.id, &project.slug, query, None, fetch_limit)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                (project, outcome)
+            }))
+            .buffer_unordered(MAX_PARALLEL_PROJECTS)
+            .collect()
+            .await;
+
+        let mut hits = Vec::new();
+        let mut failed = Vec::new();
+
+        for (project, outcome) in outcomes {
+            let results = match outcome {
+                Ok(results) => results,
+                Err(e) => {
+                    warn!(
+                        project_id = %project.id,
+                        error = %e,
+                        "Federated search failed for project"
+                    );
+                    failed.push(ProjectFailure {
+                        project_id: project.id,
+                        project_slug: project.slug,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let normalized = normalize_scores(&results);
+            hits.extend(
+                results
+                    .into_iter()
+                    .zip(normalized)
+                    .filter(|(result, _)| result.score >= options.min_score)
+                    .take(options.per_project_limit)
+                    .map(|(result, normalized_score)| FederatedHit {
+                        project_id: project.id.clone(),
+                        project_slug: project.slug.clone(),
+                        project_name: project.name.clone(),
+                        normalized_score,
+                        result,
+                    }),
+            );
+        }
+
+        hits.sort_by(|a, b| {
+            b.normalized_score
+                .partial_cmp(&a.normalized_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    b.result
+                        .combined_score
+                        .partial_cmp(&a.result.combined_score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+        hits.truncate(options.limit);
+
+        failed.sort_by(|a, b| a.project_slug.cmp(&b.project_slug));
+
+        Ok(FederatedSearchResults {
+            query: query.to_string(),
+            projects_searched,
+            results: hits,
+            failed,
+        })
+    }
+}
+
+/// Readable projects narrowed by include and exclude lists (IDs or slugs).
+fn select_projects(
+    projects: Vec<Project>,
+    accessible: &HashSet<String>,
+    include: &[String],
+    exclude: &[String],
+) -> Vec<Project> {
+    let named = |project: &Project, names: &[String]| {
+        names
+            .iter()
+            .any(|name| name == &project.id || name == &project.slug)
+    };
+
+    projects
+        .into_iter()
+        .filter(|p| accessible.contains(&p.id))
+        .filter(|p| include.is_empty() || named(p, include))
+        .filter(|p| !named(p, exclude))
+        .collect()
+}
+
+/// Min-max normalise one project's combined scores to 0.0 - 1.0 and blend
+/// them with the raw scores by [`RAW_SCORE_WEIGHT`].
+///
+/// A list whose scores are all equal normalises to 1.0 before blending.
+fn normalize_scores(results: &[MemorySearchResult]) -> Vec<f32> {
+    let (min, max) = results.iter().fold((f32::MAX, f32::MIN), |(min, max), r| {
+        (min.min(r.combined_score), max.max(r.combined_score))
+    });
+    let range = max - min;
+
+    results
+        .iter()
+        .map(|r| {
+            let normalized = if range > f32::EPSILON {
+                (r.combined_score - min) / range
+            } else {
+                1.0
+            };
+            let raw = r.combined_score.clamp(0.0, 1.0);
+            (1.0 - RAW_SCORE_WEIGHT) * normalized + RAW_SCORE_WEIGHT * raw
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject};
+    use crate::models::{Memory, MemoryType};
+
+    async fn projects() -> Vec<Project> {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        for (id, slug) in [("p1", "billing"), ("p2", "payments"), ("p3", "secret")] {
+            create_project(
+                &pool,
+                CreateProject {
+                    id: id.to_string(),
+                    slug: slug.to_string(),
+                    name: slug.to_string(),
+                    description: None,
+                    provider: "local".to_string(),
+                    root_path: format!("/tmp/{}", slug),
+                    remote_owner: None,
+                    remote_repo: None,
+                    remote_branch: None,
+                    access_token: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        db::list_projects(&pool).await.unwrap()
+    }
+
+    fn result(score: f32) -> MemorySearchResult {
+        let memory = Memory::new("proj-1".to_string(), MemoryType::General);
+        MemorySearchResult::with_decay(memory, score, 0.5, score)
+    }
+
+    #[tokio::test]
+    async fn test_select_projects() {
+        let projects = projects().await;
+        let accessible: HashSet<String> = ["p1", "p2"].iter().map(|s| s.to_string()).collect();
+
+        let all = select_projects(projects.clone(), &accessible, &[], &[]);
+        assert_eq!(all.len(), 2);
+
+        // Include by slug cannot reach an unreadable project
+        let included = select_projects(
+            projects.clone(),
+            &accessible,
+            &["payments".to_string(), "secret".to_string()],
+            &[],
+        );
+        assert_eq!(included.len(), 1);
+        assert_eq!(included[0].id, "p2");
+
+        let excluded = select_projects(projects, &accessible, &[], &["p1".to_string()]);
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].slug, "payments");
+    }
+
+    #[test]
+    fn test_normalize_scores() {
+        let normalized = normalize_scores(&[result(0.9), result(0.7), result(0.5)]);
+        assert!((normalized[0] - 0.95).abs() < 1e-6);
+        assert!((normalized[1] - 0.6).abs() < 1e-6);
+        assert!((normalized[2] - 0.25).abs() < 1e-6);
+
+        // A weak project's best hit no longer ties a strong project's
+        assert!((normalize_scores(&[result(0.6)])[0] - 0.8).abs() < 1e-6);
+        assert!(normalize_scores(&[result(0.9)])[0] > normalize_scores(&[result(0.3)])[0]);
+        assert!(normalize_scores(&[]).is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+//! Relevance feedback on search results.
+//!
+//! Agents and users mark results as used, irrelevant or wrong. "Used"
+//! verdicts are what make a memory stronger in ranking; being returned by a
+//! search is not. A memory marked wrong by several queries or givers is
+//! flagged for review until someone resolves it.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::db::{
+    self, AccessKind, DbPool, FeedbackVerdict, FlaggedMemory, MemoryFeedback, WRONG_FLAG_THRESHOLD,
+};
+use crate::error::{Error, Result};
+
+/// Most verdicts accepted in one request.
+pub const MAX_FEEDBACK_ENTRIES: usize = 50;
+
+/// A verdict on one search result.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedbackEntry {
+    pub memory_id: String,
+    pub verdict: FeedbackVerdict,
+    pub comment: Option<String>,
+}
+
+/// Outcome of recording feedback.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackOutcome {
+    pub recorded: usize,
+    /// Memories among those given feedback that are now flagged for review
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flagged: Vec<String>,
+}
+
+/// A flagged memory with the verdicts that flagged it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlaggedMemoryReport {
+    #[serde(flatten)]
+    pub memory: FlaggedMemory,
+    pub feedback: Vec<MemoryFeedback>,
+}
+
+/// Service for recording and reviewing relevance feedback.
+#[derive(Clone)]
+pub struct FeedbackService {
+    db: DbPool,
+}
+
+impl FeedbackService {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+
+    /// Record verdicts on the results of a query.
+    ///
+    /// `given_by` identifies the giver (a token ID); a giver's later verdict
+    /// on the same memory and query replaces the earlier one.
+    pub async fn record(
+        &self,
+        project_id: &str,
+        query: &str,
+        given_by: &str,
+        entries: Vec<FeedbackEntry>,
+    ) -> Result<FeedbackOutcome> {
+        if entries.is_empty() {
+            return Err(Error::Validation("No feedback given".into()));
+        }
+        if entries.len() > MAX_FEEDBACK_ENTRIES {
+            return Err(Error::Validation(format!(
+                "At most {} results per request",
+                MAX_FEEDBACK_ENTRIES
+            )));
+        }
+
+        // Check every memory before recording anything
+        for entry in &entries {
+            let memory = db::get_memory(&self.db, &entry.memory_id).await?;
+            if memory.project_id != project_id {
+                return Err(Error::NotFound(format!(
+                    "Memory not found: {}",
+                    entry.memory_id
+                )));
+            }
+        }
+
+        let query = query.trim();
+        let mut wrong: HashSet<String> = HashSet::new();
+        for entry in &entries {
+            db::record_feedback(
+                &self.db,
+                db::RecordFeedback {
+                    id: crate::models::new_id(),
+                    project_id: project_id.to_string(),
+                    memory_id: entry.memory_id.clone(),
+                    query: query.to_string(),
+                    verdict: entry.verdict,
+                    comment: entry
+                        .comment
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|c| !c.is_empty())
+                        .map(String::from),
+                    given_by: given_by.to_string(),
+                },
+            )
+            .await?;
+
+            match entry.verdict {
+                FeedbackVerdict::Used => {
+                    db::record_memory_access(
+                        &self.db,
+                        project_id,
+                        &entry.memory_id,
+                        AccessKind::Used,
+                    )
+                    .await?;
+                }
+                FeedbackVerdict::Wrong => {
+                    wrong.insert(entry.memory_id.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let mut flagged = Vec::new();
+        if !wrong.is_empty() {
+            for memory in db::list_flagged_memories(&self.db, project_id, WRONG_FLAG_THRESHOLD)
+                .await?
+                .into_iter()
+                .filter(|m| wrong.contains(&m.memory_id))
+            {
+                warn!(
+                    project_id,
+                    memory_id = %memory.memory_id,
+                    wrong_count = memory.wrong_count,
+                    "Memory flagged for review"
+                );
+                flagged.push(memory.memory_id);
+            }
+        }
+
+        Ok(FeedbackOutcome {
+            recorded: entries.len(),
+            flagged,
+        })
+    }
+
+    /// List memories flagged for review, with their "wrong" verdicts.
+    pub async fn list_flagged(&self, project_id: &str) -> Result<Vec<FlaggedMemoryReport>> {
+        let flagged = db::list_flagged_memories(&self.db, project_id, WRONG_FLAG_THRESHOLD).await?;
+
+        let mut reports = Vec::with_capacity(flagged.len());
+        for memory in flagged {
+            let feedback = db::list_wrong_feedback(&self.db, &memory.memory_id).await?;
+            reports.push(FlaggedMemoryReport { memory, feedback });
+        }
+
+        Ok(reports)
+    }
+
+    /// Clear a memory's "wrong" verdicts after review, lifting the flag and
+    /// the ranking penalty. Returns how many verdicts were resolved.
+    pub async fn resolve(&self, project_id: &str, memory_id: &str) -> Result<u64> {
+        let memory = db::get_memory(&self.db, memory_id).await?;
+        if memory.project_id != project_id {
+            return Err(Error::NotFound(format!("Memory not found: {}", memory_id)));
+        }
+
+        db::resolve_wrong_feedback(&self.db, memory_id).await
+    }
+}
@@ -1,17 +1,24 @@
 //! Hash-based storage service for the fold/ directory.
 //!
 //! Re-exports from the `fold-storage` crate and provides integration
-//! with fold-core's Memory model.
+//! with fold-core's Memory model, including a SQLite-backed path index
+//! for readable-layout memory files.
 
 use std::path::{Path, PathBuf};
 
+use async_trait::async_trait;
+use tracing::warn;
+
 // Re-export from the fold-storage crate
 pub use fold_storage::{
-    generate_memory_id, slug_to_hash, slug_to_id, slug_to_memory_id, slugify, slugify_unique,
-    EmbeddingConfig, Error as StorageError, FoldStorageService, IndexingConfig, MemoryData,
-    MemoryFrontmatter, ProjectConfig, ProjectInfo, Result as StorageResult, StorageMemory,
+    generate_memory_id, is_memory_file_path, memory_id_from_path, obsidian_tag, slug_to_hash,
+    slug_to_id, slug_to_memory_id, slugify, slugify_unique, strip_related_section, EmbeddingConfig,
+    Error as StorageError, FoldStorageService, IndexingConfig, MemoryData, MemoryFrontmatter,
+    PathIndex, ProjectConfig, ProjectInfo, Result as StorageResult, StorageConfig, StorageLayout,
+    StorageMemory,
 };
 
+use crate::db::{self, DbPool};
 use crate::error::{Error, Result};
 use crate::models::Memory;
 
@@ -56,6 +63,70 @@ impl FoldStorageExt for FoldStorageService {
     }
 }
 
+/// Path index persisted in the `memory_file_paths` table, so readable-layout
+/// files are found after a restart without walking fold/.
+#[derive(Clone)]
+pub struct SqlitePathIndex {
+    db: DbPool,
+}
+
+impl SqlitePathIndex {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+/// Path relative to the project root, with forward slashes.
+fn relative_path(project_root: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(project_root)
+        .ok()
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+}
+
+#[async_trait]
+impl PathIndex for SqlitePathIndex {
+    async fn path_of(&self, project_root: &Path, id: &str) -> Option<PathBuf> {
+        let root = project_root.to_string_lossy();
+        match db::get_memory_file_path(&self.db, &root, id).await {
+            Ok(path) => path.map(|p| project_root.join(p)),
+            Err(e) => {
+                warn!(memory_id = %id, error = %e, "Failed to read memory file path");
+                None
+            }
+        }
+    }
+
+    async fn memory_at(&self, project_root: &Path, path: &Path) -> Option<String> {
+        let root = project_root.to_string_lossy();
+        let rel = relative_path(project_root, path)?;
+        match db::get_memory_id_at_path(&self.db, &root, &rel).await {
+            Ok(id) => id,
+            Err(e) => {
+                warn!(file = %rel, error = %e, "Failed to look up memory file path");
+                None
+            }
+        }
+    }
+
+    async fn record(&self, project_root: &Path, files: &[(String, PathBuf)]) {
+        let root = project_root.to_string_lossy();
+        let files: Vec<(String, String)> = files
+            .iter()
+            .filter_map(|(id, path)| Some((id.clone(), relative_path(project_root, path)?)))
+            .collect();
+        if let Err(e) = db::upsert_memory_file_paths(&self.db, &root, &files).await {
+            warn!(error = %e, "Failed to record memory file paths");
+        }
+    }
+
+    async fn forget(&self, project_root: &Path, id: &str) {
+        let root = project_root.to_string_lossy();
+        if let Err(e) = db::delete_memory_file_path(&self.db, &root, id).await {
+            warn!(memory_id = %id, error = %e, "Failed to forget memory file path");
+        }
+    }
+}
+
 /// Convert StorageMemory to fold-core Memory.
 pub fn storage_memory_to_memory(sm: StorageMemory) -> Memory {
     Memory {
@@ -324,4 +395,64 @@ More content here."#;
         assert_eq!(memory.language(), Some("rust"));
         assert_eq!(memory.tags(), vec!["tag1".to_string()]);
     }
+
+    #[tokio::test]
+    async fn test_sqlite_path_index() {
+        let pool = crate::db::init_pool(":memory:").await.unwrap();
+        crate::db::migrate(&pool).await.unwrap();
+        let root = std::env::temp_dir().join(format!("fold-paths-{}", uuid::Uuid::new_v4()));
+
+        let service = FoldStorageService::with_path_index(std::sync::Arc::new(
+            SqlitePathIndex::new(pool.clone()),
+        ));
+        service
+            .init_fold_directory(&root, "proj-1", "test", "Test")
+            .await
+            .unwrap();
+        service
+            .set_layout(&root, StorageLayout::Readable)
+            .await
+            .unwrap();
+
+        let mut memory = storage_memory_to_memory(StorageMemory {
+            id: "aBcD123456789abc".to_string(),
+            project_id: "proj-1".to_string(),
+            slug: None,
+            memory_type: "decision".to_string(),
+            source: None,
+            content: None,
+            content_hash: None,
+            title: Some("Use Postgres".to_string()),
+            author: None,
+            tags: None,
+            file_path: None,
+            language: None,
+            metadata: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        });
+        write_memory(&service, &root, &memory, "Use Postgres.")
+            .await
+            .unwrap();
+
+        // A fresh service (e.g. after a restart) reads the recorded path
+        let restarted =
+            FoldStorageService::with_path_index(std::sync::Arc::new(SqlitePathIndex::new(pool)));
+        assert_eq!(
+            restarted
+                .memory_id_at(&root, "fold/decision/use-postgres.md")
+                .await
+                .as_deref(),
+            Some("aBcD123456789abc")
+        );
+
+        memory.title = Some("Postgres everywhere".to_string());
+        let path = write_memory(&restarted, &root, &memory, "Use Postgres.")
+            .await
+            .unwrap();
+        assert_eq!(path, root.join("fold/decision/postgres-everywhere.md"));
+        assert!(!root.join("fold/decision/use-postgres.md").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -3,19 +3,34 @@
 //! Handles incoming webhooks from GitHub and GitLab,
 //! processes commits, and creates memory summaries.
 
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 
-use crate::db::DbPool;
+use crate::db::{self, DbPool};
 use crate::error::{Error, Result};
 use crate::models::{
-    CommitInfo, GitCommit, Memory, MemoryCreate, MemoryLink, MemoryType, Project, Repository,
+    CommitInfo, GitCommit, Memory, MemoryCreate, MemoryLink, MemorySource, MemoryType,
+    MemoryUpdate, Project, Repository,
 };
 
-use super::{GitHubService, GitLabService, IndexerService, LlmService, MemoryService};
+use super::fold_storage::{is_memory_file_path, memory_id_from_path, strip_related_section};
+use super::{FoldStorageService, GitHubService, GitLabService, IndexerService, MemoryService};
+
+/// Commit authors that are always ignored (Fold and CI bots).
+const IGNORED_AUTHOR_PATTERNS: &[&str] = &[
+    "fold-meta-bot",  // Fold metadata sync bot
+    "fold",           // Fold bot (general)
+    "[bot]",          // GitHub bot convention
+    "github-actions", // CI/CD
+    "dependabot",     // Dependency updates
+    "noreply@",       // No-reply emails
+];
 
 /// Service for processing git webhooks and syncing repositories.
 #[derive(Clone)]
@@ -24,8 +39,8 @@ pub struct GitSyncService {
     github: Arc<GitHubService>,
     gitlab: Arc<GitLabService>,
     memory: MemoryService,
-    llm: Arc<LlmService>,
     indexer: IndexerService,
+    fold_storage: Arc<FoldStorageService>,
 }
 
 /// Webhook payload from GitHub
@@ -116,6 +131,52 @@ pub struct WebhookResult {
     pub links_created: usize,
 }
 
+/// Net changes to fold/ memory files across the commits of a push.
+///
+/// A file modified and later removed in the same push ends up in `removed`
+/// only, and vice versa.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FoldChanges {
+    pub changed: BTreeSet<String>,
+    pub removed: BTreeSet<String>,
+}
+
+impl FoldChanges {
+    /// Apply one commit's file lists, keeping only live memory files in
+    /// either layout.
+    pub fn record_commit(&mut self, added: &[String], modified: &[String], removed: &[String]) {
+        for path in added.iter().chain(modified) {
+            if is_memory_file_path(path) {
+                self.removed.remove(path);
+                self.changed.insert(path.clone());
+            }
+        }
+        for path in removed {
+            if is_memory_file_path(path) {
+                self.changed.remove(path);
+                self.removed.insert(path.clone());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Result of applying fold/ memory file changes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FoldSyncResult {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub conflicts: usize,
+    pub failed: usize,
+    pub links_created: usize,
+    pub links_deleted: usize,
+}
+
 impl GitSyncService {
     /// Create a new git sync service.
     pub fn new(
@@ -123,16 +184,16 @@ impl GitSyncService {
         github: Arc<GitHubService>,
         gitlab: Arc<GitLabService>,
         memory: MemoryService,
-        llm: Arc<LlmService>,
         indexer: IndexerService,
+        fold_storage: Arc<FoldStorageService>,
     ) -> Self {
         Self {
             db,
             github,
             gitlab,
             memory,
-            llm,
             indexer,
+            fold_storage,
         }
     }
 
@@ -295,23 +356,19 @@ impl GitSyncService {
     ///
     /// Checks both global patterns and project-specific `ignored_commit_authors`.
     fn should_ignore_author(&self, author: &Option<String>, project: &Project) -> bool {
-        let author = match author {
-            Some(a) => a.to_lowercase(),
-            None => return false,
-        };
+        match author {
+            Some(author) => Self::is_ignored_author(author, &project.ignored_commit_authors_vec()),
+            None => false,
+        }
+    }
 
-        // Global bot patterns (always ignored)
-        let global_patterns = [
-            "fold-meta-bot",  // Fold metadata sync bot
-            "fold",           // Fold bot (general)
-            "[bot]",          // GitHub bot convention
-            "github-actions", // CI/CD
-            "dependabot",     // Dependency updates
-            "noreply@",       // No-reply emails
-        ];
+    /// Check an author against the global bot patterns and a project's
+    /// `ignored_commit_authors`.
+    pub fn is_ignored_author(author: &str, project_patterns: &[String]) -> bool {
+        let author = author.to_lowercase();
 
         // Check global patterns
-        if global_patterns
+        if IGNORED_AUTHOR_PATTERNS
             .iter()
             .any(|pattern| author.contains(pattern))
         {
@@ -319,7 +376,6 @@ impl GitSyncService {
         }
 
         // Check project-specific patterns
-        let project_patterns = project.ignored_commit_authors_vec();
         project_patterns
             .iter()
             .any(|pattern| author.contains(&pattern.to_lowercase()))
@@ -362,8 +418,9 @@ impl GitSyncService {
         }
 
         // Generate commit summary using LLM
-        let summary = if self.llm.is_available().await {
-            match self.llm.summarize_commit(commit).await {
+        let llm = self.memory.llm_for(&project.id).await?;
+        let summary = if llm.is_available().await {
+            match llm.summarize_commit(commit).await {
                 Ok(s) => s,
                 Err(e) => {
                     warn!(error = %e, "Failed to generate commit summary");
@@ -659,11 +716,14 @@ impl GitSyncService {
             .await?
             .ok_or_else(|| Error::NotFound(format!("Repository not found: {}", repo_id)))?;
 
-        // Get project
-        let project: Project = sqlx::query_as("SELECT * FROM projects WHERE id = ?")
-            .bind(&repo.project_id)
-            .fetch_one(&self.db)
-            .await?;
+        // Get project (through the db layer so stored secrets are decrypted),
+        // keeping the fields commit processing reads
+        let stored = db::get_project(&self.db, &repo.project_id).await?;
+        let mut project = Project::new(stored.name);
+        project.id = stored.id;
+        project.slug = stored.slug;
+        project.root_path = Some(stored.root_path);
+        project.ignored_commit_authors = stored.ignored_commit_authors;
 
         // Dispatch based on provider
         match repo.provider.as_str() {
@@ -734,4 +794,799 @@ impl GitSyncService {
             links_created: 0,
         })
     }
+
+    // =========================================================================
+    // Fold Sync (human edits to fold/*.md)
+    // =========================================================================
+
+    /// Apply pushed edits to fold/ memory files.
+    ///
+    /// `local_path` is a checkout containing the pushed files. Edited files
+    /// update the memory (title, tags, content and embedding), new files
+    /// create one and removed files delete it. Changes to `related_to` in the
+    /// frontmatter add or remove links. If the memory also changed in Fold
+    /// since its file was last synced, the edit is held back as a conflict.
+    ///
+    /// Edited files are matched to memories by their frontmatter `id`.
+    /// Removed readable-layout files no longer have one, so they are looked
+    /// up in the path index; a removal of a file whose memory was also
+    /// pushed under another name in the same push is a rename.
+    pub async fn sync_fold_changes(
+        &self,
+        project: &db::Project,
+        local_path: &Path,
+        changes: &FoldChanges,
+        commit_sha: Option<&str>,
+    ) -> FoldSyncResult {
+        let mut result = FoldSyncResult::default();
+        let project_root = Path::new(&project.root_path);
+        let mut applied = HashSet::new();
+
+        for path in &changes.changed {
+            let raw = match tokio::fs::read_to_string(local_path.join(path)).await {
+                Ok(raw) => raw,
+                Err(e) => {
+                    warn!(file = %path, error = %e, "Failed to read pushed memory file");
+                    result.failed += 1;
+                    continue;
+                }
+            };
+
+            match self
+                .apply_fold_file(project, path, &raw, commit_sha, false, &mut result)
+                .await
+            {
+                Ok(memory_id) => {
+                    applied.insert(memory_id);
+                }
+                Err(e) => {
+                    warn!(file = %path, error = %e, "Failed to apply memory file edit");
+                    result.failed += 1;
+                }
+            }
+        }
+
+        for path in &changes.removed {
+            let memory_id = match self.fold_storage.memory_id_at(project_root, path).await {
+                Some(id) if !applied.contains(&id) => id,
+                _ => {
+                    result.unchanged += 1;
+                    continue;
+                }
+            };
+            if let Err(e) = self
+                .apply_fold_removal(project, &memory_id, path, commit_sha, false, &mut result)
+                .await
+            {
+                warn!(file = %path, error = %e, "Failed to apply memory file removal");
+                result.failed += 1;
+            }
+        }
+
+        info!(
+            project = %project.slug,
+            created = result.created,
+            updated = result.updated,
+            deleted = result.deleted,
+            conflicts = result.conflicts,
+            failed = result.failed,
+            "Synced fold/ changes from push"
+        );
+
+        result
+    }
+
+    /// Settle a fold sync conflict.
+    ///
+    /// With `apply_pushed` the held-back edit is applied over Fold's copy.
+    /// Otherwise Fold's copy is kept and the pushed file is marked as seen,
+    /// so only later edits to it are synced.
+    pub async fn resolve_fold_conflict(
+        &self,
+        project: &db::Project,
+        conflict: &db::FoldSyncConflict,
+        apply_pushed: bool,
+    ) -> Result<FoldSyncResult> {
+        let mut result = FoldSyncResult::default();
+        let commit_sha = conflict.commit_sha.as_deref();
+
+        match (conflict.file_content.as_deref(), apply_pushed) {
+            (Some(raw), true) => {
+                self.apply_fold_file(
+                    project,
+                    &conflict.file_path,
+                    raw,
+                    commit_sha,
+                    true,
+                    &mut result,
+                )
+                .await?;
+            }
+            (None, true) => {
+                self.apply_fold_removal(
+                    project,
+                    &conflict.memory_id,
+                    &conflict.file_path,
+                    commit_sha,
+                    true,
+                    &mut result,
+                )
+                .await?
+            }
+            (Some(raw), false) => {
+                let related_to = db::get_fold_sync_state(&self.db, &conflict.memory_id)
+                    .await?
+                    .map(|state| state.related_to_vec())
+                    .unwrap_or_default();
+
+                db::upsert_fold_sync_state(
+                    &self.db,
+                    db::UpsertFoldSyncState {
+                        memory_id: conflict.memory_id.clone(),
+                        project_id: project.id.clone(),
+                        content_hash: content_hash(raw),
+                        related_to,
+                        commit_sha: conflict.commit_sha.clone(),
+                    },
+                )
+                .await?;
+            }
+            (None, false) => {}
+        }
+
+        Ok(result)
+    }
+
+    /// Apply one edited or added memory file. `force` skips the conflict check.
+    /// Returns the ID of the memory the file belongs to.
+    async fn apply_fold_file(
+        &self,
+        project: &db::Project,
+        path: &str,
+        raw: &str,
+        commit_sha: Option<&str>,
+        force: bool,
+        result: &mut FoldSyncResult,
+    ) -> Result<String> {
+        if !is_memory_file_path(path) {
+            return Err(Error::Validation(format!("Not a memory file: {}", path)));
+        }
+
+        let (frontmatter, body) = self
+            .fold_storage
+            .parse_frontmatter(raw)
+            .map_err(super::fold_storage::storage_error_to_error)?;
+        let memory_id = frontmatter.id.as_str();
+        // Hashed files are named after their memory; readable ones are not
+        if memory_id_from_path(path).is_some_and(|id| id != memory_id) {
+            return Err(Error::Validation(format!(
+                "Frontmatter id {} does not match file {}",
+                frontmatter.id, path
+            )));
+        }
+
+        let existing = db::get_memory_optional(&self.db, memory_id).await?;
+        if existing
+            .as_ref()
+            .is_some_and(|m| m.project_id != project.id)
+        {
+            return Err(Error::Validation(format!(
+                "Memory {} in {} belongs to another project",
+                memory_id, path
+            )));
+        }
+
+        let hash = content_hash(raw);
+        let state = db::get_fold_sync_state(&self.db, memory_id).await?;
+        if !force && state.as_ref().is_some_and(|s| s.content_hash == hash) {
+            self.index_pushed_file(project, path, memory_id).await;
+            result.unchanged += 1;
+            return Ok(memory_id.to_string());
+        }
+
+        let content = strip_related_section(&body).to_string();
+        let related: Vec<String> = frontmatter
+            .related_to
+            .iter()
+            .filter(|id| id.as_str() != memory_id)
+            .cloned()
+            .collect();
+        let previous_related = state
+            .as_ref()
+            .map(|s| s.related_to_vec())
+            .unwrap_or_default();
+
+        let written = match existing {
+            None => {
+                self.memory
+                    .add(
+                        &project.id,
+                        &project.slug,
+                        MemoryCreate {
+                            id: Some(memory_id.to_string()),
+                            memory_type: MemoryType::from_str(&frontmatter.memory_type)
+                                .unwrap_or_default(),
+                            content: content.clone(),
+                            author: frontmatter.author.clone(),
+                            source: Some(MemorySource::Agent),
+                            title: frontmatter.title.clone(),
+                            tags: frontmatter.tags.clone(),
+                            file_path: frontmatter.file_path.clone(),
+                            language: frontmatter.language.clone(),
+                            ..Default::default()
+                        },
+                        false,
+                    )
+                    .await?;
+                result.created += 1;
+                true
+            }
+            // Fold's own writes come back through pushes too
+            Some(memory)
+                if memory.content_hash.as_deref() == Some(content_hash(&content).as_str())
+                    && memory.title == frontmatter.title
+                    && memory.tags_vec() == frontmatter.tags =>
+            {
+                result.unchanged += 1;
+                false
+            }
+            Some(memory) => {
+                let synced_at = state.as_ref().and_then(|s| parse_timestamp(&s.synced_at));
+                if !force
+                    && changed_since_sync(
+                        parse_timestamp(&memory.updated_at),
+                        synced_at,
+                        Some(frontmatter.updated_at),
+                    )
+                {
+                    self.record_fold_conflict(
+                        project,
+                        memory_id,
+                        path,
+                        "modified",
+                        commit_sha,
+                        Some(raw),
+                    )
+                    .await?;
+                    result.conflicts += 1;
+                    return Ok(memory_id.to_string());
+                }
+
+                self.memory
+                    .update(
+                        &project.id,
+                        &project.slug,
+                        memory_id,
+                        MemoryUpdate {
+                            content: Some(content.clone()),
+                            title: frontmatter.title.clone(),
+                            tags: Some(frontmatter.tags.clone()),
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                result.updated += 1;
+                true
+            }
+        };
+
+        let (created, deleted) = self
+            .sync_related_links(&project.id, memory_id, &previous_related, &related)
+            .await;
+        result.links_created += created;
+        result.links_deleted += deleted;
+
+        // Memory writes drop the Related section, so put the links back
+        if written && !related.is_empty() {
+            let project_root = Path::new(&project.root_path);
+            if let Err(e) = self
+                .fold_storage
+                .update_memory_links(project_root, memory_id, &related)
+                .await
+            {
+                warn!(error = %e, memory_id = %memory_id, "Failed to restore related links in memory file");
+            }
+        }
+        if !written {
+            self.index_pushed_file(project, path, memory_id).await;
+        }
+
+        db::upsert_fold_sync_state(
+            &self.db,
+            db::UpsertFoldSyncState {
+                memory_id: memory_id.to_string(),
+                project_id: project.id.clone(),
+                content_hash: hash,
+                related_to: related,
+                commit_sha: commit_sha.map(String::from),
+            },
+        )
+        .await?;
+
+        Ok(memory_id.to_string())
+    }
+
+    /// Record where a pushed readable-layout file that Fold did not rewrite
+    /// lives, if it is in the project's checkout.
+    async fn index_pushed_file(&self, project: &db::Project, path: &str, memory_id: &str) {
+        if memory_id_from_path(path).is_some() {
+            return;
+        }
+        let project_root = Path::new(&project.root_path);
+        let pushed = project_root.join(path);
+        if tokio::fs::metadata(&pushed).await.is_ok() {
+            self.fold_storage
+                .record_path(project_root, memory_id, &pushed)
+                .await;
+        }
+    }
+
+    /// Apply one removed memory file. `force` skips the conflict check.
+    async fn apply_fold_removal(
+        &self,
+        project: &db::Project,
+        memory_id: &str,
+        path: &str,
+        commit_sha: Option<&str>,
+        force: bool,
+        result: &mut FoldSyncResult,
+    ) -> Result<()> {
+        let existing = db::get_memory_optional(&self.db, memory_id)
+            .await?
+            .filter(|m| m.project_id == project.id);
+        let Some(memory) = existing else {
+            db::delete_fold_sync_state(&self.db, memory_id).await?;
+            result.unchanged += 1;
+            return Ok(());
+        };
+
+        // Archiving moves the file to fold/archive/, which is not a deletion
+        if db::get_archived_memory(&self.db, memory_id)
+            .await?
+            .is_some()
+        {
+            result.unchanged += 1;
+            return Ok(());
+        }
+
+        let synced_at = db::get_fold_sync_state(&self.db, memory_id)
+            .await?
+            .and_then(|s| parse_timestamp(&s.synced_at));
+        if !force && changed_since_sync(parse_timestamp(&memory.updated_at), synced_at, None) {
+            self.record_fold_conflict(project, memory_id, path, "deleted", commit_sha, None)
+                .await?;
+            result.conflicts += 1;
+            return Ok(());
+        }
+
+        self.memory
+            .delete(&project.id, &project.slug, memory_id)
+            .await?;
+        db::delete_fold_sync_state(&self.db, memory_id).await?;
+        result.deleted += 1;
+
+        Ok(())
+    }
+
+    async fn record_fold_conflict(
+        &self,
+        project: &db::Project,
+        memory_id: &str,
+        path: &str,
+        change_type: &str,
+        commit_sha: Option<&str>,
+        file_content: Option<&str>,
+    ) -> Result<()> {
+        warn!(
+            memory_id = %memory_id,
+            file = %path,
+            change_type = %change_type,
+            "Memory changed in Fold since last sync, holding back pushed edit"
+        );
+
+        db::create_fold_sync_conflict(
+            &self.db,
+            db::CreateFoldSyncConflict {
+                id: crate::models::new_id(),
+                project_id: project.id.clone(),
+                memory_id: memory_id.to_string(),
+                file_path: path.to_string(),
+                change_type: change_type.to_string(),
+                commit_sha: commit_sha.map(String::from),
+                file_content: file_content.map(String::from),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Add links for newly related memories and remove links for memories
+    /// dropped from `related_to`. Returns (created, deleted).
+    async fn sync_related_links(
+        &self,
+        project_id: &str,
+        memory_id: &str,
+        previous: &[String],
+        related: &[String],
+    ) -> (usize, usize) {
+        let outgoing = match db::list_outgoing_links(&self.db, memory_id).await {
+            Ok(links) => links,
+            Err(e) => {
+                warn!(error = %e, memory_id = %memory_id, "Failed to load memory links");
+                return (0, 0);
+            }
+        };
+        let mut created = 0;
+        let mut deleted = 0;
+
+        for target in related.iter().filter(|t| !previous.contains(t)) {
+            if outgoing.iter().any(|l| &l.target_id == target) {
+                continue;
+            }
+            match db::get_memory_optional(&self.db, target).await {
+                Ok(Some(m)) if m.project_id == project_id => {}
+                _ => {
+                    debug!(target = %target, "Skipping related link to unknown memory");
+                    continue;
+                }
+            }
+
+            let link = db::create_link(
+                &self.db,
+                db::CreateLink {
+                    id: crate::models::new_id(),
+                    project_id: project_id.to_string(),
+                    source_id: memory_id.to_string(),
+                    target_id: target.clone(),
+                    link_type: db::LinkType::Related,
+                    created_by: db::LinkCreator::User,
+                    confidence: Some(1.0),
+                    context: Some("Added in fold/ file".to_string()),
+                    change_type: None,
+                    additions: None,
+                    deletions: None,
+                },
+            )
+            .await;
+
+            match link {
+                Ok(link) => {
+                    created += 1;
+                    if let Some(events) = self.memory.events() {
+                        events.link_created(
+                            project_id,
+                            &link.id,
+                            memory_id,
+                            target,
+                            &link.link_type,
+                            &link.created_by,
+                        );
+                    }
+                }
+                Err(e) => warn!(error = %e, target = %target, "Failed to create related link"),
+            }
+        }
+
+        for target in previous.iter().filter(|t| !related.contains(t)) {
+            for link in outgoing.iter().filter(|l| &l.target_id == target) {
+                match db::delete_link(&self.db, &link.id).await {
+                    Ok(()) => {
+                        deleted += 1;
+                        if let Some(events) = self.memory.events() {
+                            events.link_deleted(
+                                project_id,
+                                &link.id,
+                                memory_id,
+                                target,
+                                &link.link_type,
+                            );
+                        }
+                    }
+                    Err(e) => warn!(error = %e, link_id = %link.id, "Failed to delete link"),
+                }
+            }
+        }
+
+        (created, deleted)
+    }
+}
+
+/// SHA256 of file or memory content.
+fn content_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Parse a stored timestamp (`datetime('now')` or RFC 3339).
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%:z"))
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").map(|dt| dt.and_utc())
+        })
+        .ok()
+}
+
+/// Whether Fold's copy of a memory changed after the version a pushed edit
+/// was based on: the later of the last sync and the file's `updated_at`.
+///
+/// Allows a second of slack for timestamps stored without sub-second precision.
+fn changed_since_sync(
+    memory_updated_at: Option<DateTime<Utc>>,
+    last_synced_at: Option<DateTime<Utc>>,
+    file_updated_at: Option<DateTime<Utc>>,
+) -> bool {
+    let Some(updated_at) = memory_updated_at else {
+        return false;
+    };
+
+    match last_synced_at.max(file_updated_at) {
+        Some(base) => updated_at > base + Duration::seconds(1),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_changes_net_result() {
+        let paths = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mut changes = FoldChanges::default();
+
+        changes.record_commit(
+            &paths(&["fold/a/b/ab1.md", "src/main.rs"]),
+            &paths(&["fold/c/d/cd1.md", "fold/archive/e/f/ef1.md"]),
+            &[],
+        );
+        changes.record_commit(&[], &[], &paths(&["fold/c/d/cd1.md"]));
+        changes.record_commit(
+            &paths(&["fold/decision/use-postgres.md"]),
+            &paths(&["fold/a/b/ab1.md", "fold/project.toml"]),
+            &[],
+        );
+
+        assert_eq!(
+            changes.changed.iter().collect::<Vec<_>>(),
+            vec!["fold/a/b/ab1.md", "fold/decision/use-postgres.md"]
+        );
+        assert_eq!(
+            changes.removed.iter().collect::<Vec<_>>(),
+            vec!["fold/c/d/cd1.md"]
+        );
+        assert!(FoldChanges::default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_readable_layout_files() {
+        let state = crate::state::AppState::for_tests().await.unwrap();
+        let root = std::env::temp_dir().join(format!("fold-sync-{}", uuid::Uuid::new_v4()));
+        let project = db::create_project(
+            &state.db,
+            db::CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: root.to_string_lossy().to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for (id, title) in [("mem-1", "Use Postgres"), ("mem-2", "Use Redis")] {
+            db::create_memory(
+                &state.db,
+                db::CreateMemory {
+                    id: id.to_string(),
+                    project_id: project.id.clone(),
+                    memory_type: db::MemoryType::Decision,
+                    source: None,
+                    title: Some(title.to_string()),
+                    content: None,
+                    content_hash: Some(content_hash(&format!("{}.", title))),
+                    content_storage: "fold".to_string(),
+                    file_path: None,
+                    language: None,
+                    git_branch: None,
+                    git_commit_sha: None,
+                    author: None,
+                    keywords: None,
+                    tags: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        // A readable-layout file names neither the memory nor its shard
+        let file = "---\nid: mem-1\ntitle: Use Postgres\nmemory_type: decision\n\
+                    created_at: 2026-03-01T12:00:00Z\nupdated_at: 2026-03-01T12:00:00Z\n\
+                    related_to:\n  - mem-2\n---\n\nUse Postgres.\n";
+        std::fs::create_dir_all(root.join("fold/decision")).unwrap();
+        std::fs::write(root.join("fold/decision/use-postgres.md"), file).unwrap();
+
+        let mut changes = FoldChanges::default();
+        changes.record_commit(&["fold/decision/use-postgres.md".to_string()], &[], &[]);
+        let result = state
+            .git_sync
+            .sync_fold_changes(&project, &root, &changes, Some("sha-1"))
+            .await;
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.unchanged, 1);
+        assert_eq!(result.links_created, 1);
+        assert!(db::get_fold_sync_state(&state.db, "mem-1")
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            state
+                .fold_storage
+                .memory_id_at(&root, "fold/decision/use-postgres.md")
+                .await
+                .as_deref(),
+            Some("mem-1")
+        );
+
+        // Renaming the file is not a deletion
+        std::fs::rename(
+            root.join("fold/decision/use-postgres.md"),
+            root.join("fold/decision/postgres.md"),
+        )
+        .unwrap();
+        let mut changes = FoldChanges::default();
+        changes.record_commit(
+            &["fold/decision/postgres.md".to_string()],
+            &[],
+            &["fold/decision/use-postgres.md".to_string()],
+        );
+        let result = state
+            .git_sync
+            .sync_fold_changes(&project, &root, &changes, Some("sha-2"))
+            .await;
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.deleted, 0);
+        assert!(db::get_memory_optional(&state.db, "mem-1")
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            state
+                .fold_storage
+                .memory_id_at(&root, "fold/decision/postgres.md")
+                .await
+                .as_deref(),
+            Some("mem-1")
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sync_rejects_other_project_memory() {
+        let state = crate::state::AppState::for_tests().await.unwrap();
+        let root = std::env::temp_dir().join(format!("fold-sync-{}", uuid::Uuid::new_v4()));
+        let mut projects = Vec::new();
+        for (id, slug) in [("proj-1", "one"), ("proj-2", "two")] {
+            let project = db::create_project(
+                &state.db,
+                db::CreateProject {
+                    id: id.to_string(),
+                    slug: slug.to_string(),
+                    name: slug.to_string(),
+                    description: None,
+                    provider: "local".to_string(),
+                    root_path: root.join(slug).to_string_lossy().to_string(),
+                    remote_owner: None,
+                    remote_repo: None,
+                    remote_branch: None,
+                    access_token: None,
+                },
+            )
+            .await
+            .unwrap();
+            projects.push(project);
+        }
+
+        db::create_memory(
+            &state.db,
+            db::CreateMemory {
+                id: "mem-2".to_string(),
+                project_id: "proj-2".to_string(),
+                memory_type: db::MemoryType::Decision,
+                source: None,
+                title: Some("Use Redis".to_string()),
+                content: Some("Use Redis.".to_string()),
+                content_hash: None,
+                content_storage: "fold".to_string(),
+                file_path: None,
+                language: None,
+                git_branch: None,
+                git_commit_sha: None,
+                author: None,
+                keywords: None,
+                tags: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // A push to proj-1 naming proj-2's memory must not touch it
+        let project_root = root.join("one");
+        let file = "---\nid: mem-2\ntitle: Overwritten\nmemory_type: decision\n\
+                    created_at: 2026-03-01T12:00:00Z\nupdated_at: 2026-03-01T12:00:00Z\n\
+                    ---\n\nOverwritten.\n";
+        std::fs::create_dir_all(project_root.join("fold/decision")).unwrap();
+        std::fs::write(project_root.join("fold/decision/redis.md"), file).unwrap();
+
+        let mut changes = FoldChanges::default();
+        changes.record_commit(&["fold/decision/redis.md".to_string()], &[], &[]);
+        let result = state
+            .git_sync
+            .sync_fold_changes(&projects[0], &project_root, &changes, Some("sha-1"))
+            .await;
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.created, 0);
+
+        let memory = db::get_memory_optional(&state.db, "mem-2")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(memory.project_id, "proj-2");
+        assert_eq!(memory.title.as_deref(), Some("Use Redis"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_changed_since_sync() {
+        let at = |s: &str| parse_timestamp(s);
+        let updated = at("2026-03-01 12:00:00");
+
+        // Never synced and no file timestamp: nothing to compare against
+        assert!(!changed_since_sync(updated, None, None));
+        // Fold wrote the file at the same time it last changed
+        assert!(!changed_since_sync(
+            updated,
+            None,
+            at("2026-03-01T12:00:00Z")
+        ));
+        // Fold changed after the file was written
+        assert!(changed_since_sync(
+            updated,
+            None,
+            at("2026-02-01T09:00:00Z")
+        ));
+        // ...but not after the last sync
+        assert!(!changed_since_sync(
+            updated,
+            at("2026-03-01 12:00:05"),
+            at("2026-02-01T09:00:00Z")
+        ));
+        // Sub-second precision differences are ignored
+        assert!(!changed_since_sync(
+            at("2026-03-01 12:00:00.600+00:00"),
+            at("2026-03-01 12:00:00"),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_ignored_authors() {
+        assert!(GitSyncService::is_ignored_author("dependabot[bot]", &[]));
+        assert!(!GitSyncService::is_ignored_author("Jane Doe", &[]));
+        assert!(GitSyncService::is_ignored_author(
+            "Release Robot",
+            &["release robot".to_string()]
+        ));
+    }
 }
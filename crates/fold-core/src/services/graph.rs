@@ -610,3 +610,89 @@ pub struct GraphStats {
     pub most_connected: Vec<(String, i64)>,
     pub avg_connections: f64,
 }
+
+/// Damping factor for link rank.
+const LINK_RANK_DAMPING: f64 = 0.85;
+
+/// Power iterations for link rank.
+const LINK_RANK_ITERATIONS: usize = 30;
+
+/// PageRank over link edges (source, target), scaled so the highest ranked
+/// memory is 1.0. Memories without links are missing from the map.
+pub fn link_rank(edges: &[(String, String)]) -> HashMap<String, f64> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (source, target) in edges {
+        for id in [source, target] {
+            let next = index.len();
+            index.entry(id.as_str()).or_insert(next);
+        }
+    }
+
+    let n = index.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (source, target) in edges {
+        if source != target {
+            outgoing[index[source.as_str()]].push(index[target.as_str()]);
+        }
+    }
+
+    let base = (1.0 - LINK_RANK_DAMPING) / n as f64;
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..LINK_RANK_ITERATIONS {
+        // Rank of memories with no outgoing links is spread evenly
+        let dangling: f64 = outgoing
+            .iter()
+            .zip(&rank)
+            .filter(|(out, _)| out.is_empty())
+            .map(|(_, r)| r)
+            .sum();
+
+        let mut next = vec![base + LINK_RANK_DAMPING * dangling / n as f64; n];
+        for (node, targets) in outgoing.iter().enumerate() {
+            if targets.is_empty() {
+                continue;
+            }
+            let share = LINK_RANK_DAMPING * rank[node] / targets.len() as f64;
+            for &target in targets {
+                next[target] += share;
+            }
+        }
+        rank = next;
+    }
+
+    let max = rank.iter().cloned().fold(0.0, f64::max);
+    index
+        .into_iter()
+        .map(|(id, i)| (id.to_string(), rank[i] / max))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(source: &str, target: &str) -> (String, String) {
+        (source.to_string(), target.to_string())
+    }
+
+    #[test]
+    fn test_link_rank_favours_in_degree() {
+        let edges = vec![
+            edge("a", "hub"),
+            edge("b", "hub"),
+            edge("c", "hub"),
+            edge("d", "a"),
+        ];
+        let rank = link_rank(&edges);
+
+        assert!((rank["hub"] - 1.0).abs() < 1e-9);
+        assert!(rank["a"] > rank["b"]);
+        assert!(rank["b"] < 0.5);
+        assert!(!rank.contains_key("unlinked"));
+        assert!(link_rank(&[]).is_empty());
+    }
+}
@@ -19,13 +19,13 @@ use sha2::{Digest, Sha256};
 use tokio::fs;
 use tracing::{debug, info, warn};
 
-use crate::db::{self, DbPool};
+use crate::db::{self, DbPool, UsageKind};
 use crate::error::{Error, Result};
-use crate::models::{ChunkCreate, Memory, MemoryCreate, MemorySource, MemoryType, Project};
+use crate::models::{Chunk, ChunkCreate, Memory, MemoryCreate, MemorySource, MemoryType, Project};
 
 use super::{
-    ChunkerService, EmbeddingService, FoldStorageService, GitService, LinkerService, LlmService,
-    MemoryService, QdrantService,
+    ChunkerService, EmbeddingService, FoldStorageService, GitService, LinkerService, MemoryService,
+    QdrantService,
 };
 
 /// Maximum file size to index (100KB)
@@ -141,7 +141,6 @@ const DEFAULT_CONCURRENCY: usize = 4;
 #[derive(Clone)]
 pub struct IndexerService {
     memory_service: MemoryService,
-    llm: Arc<LlmService>,
     fold_storage: Arc<FoldStorageService>,
     git_service: Option<Arc<GitService>>,
     linker: Option<Arc<LinkerService>>,
@@ -173,10 +172,9 @@ pub struct IndexResult {
 
 impl IndexerService {
     /// Create a new indexer service.
-    pub fn new(memory_service: MemoryService, llm: Arc<LlmService>) -> Self {
+    pub fn new(memory_service: MemoryService) -> Self {
         Self {
             memory_service,
-            llm,
             fold_storage: Arc::new(FoldStorageService::new()),
             git_service: None,
             linker: None,
@@ -192,12 +190,10 @@ impl IndexerService {
     /// Create a new indexer service with a specific fold storage service.
     pub fn with_fold_storage(
         memory_service: MemoryService,
-        llm: Arc<LlmService>,
         fold_storage: Arc<FoldStorageService>,
     ) -> Self {
         Self {
             memory_service,
-            llm,
             fold_storage,
             git_service: None,
             linker: None,
@@ -211,14 +207,9 @@ impl IndexerService {
     }
 
     /// Create a new indexer service with git integration for auto-commit.
-    pub fn with_git_service(
-        memory_service: MemoryService,
-        llm: Arc<LlmService>,
-        git_service: Arc<GitService>,
-    ) -> Self {
+    pub fn with_git_service(memory_service: MemoryService, git_service: Arc<GitService>) -> Self {
         Self {
             memory_service,
-            llm,
             fold_storage: Arc::new(FoldStorageService::new()),
             git_service: Some(git_service),
             linker: None,
@@ -587,16 +578,14 @@ impl IndexerService {
         }
 
         // Generate summary using LLM - fail if LLM is unavailable (no dumb fallbacks)
-        if !self.llm.is_available().await {
+        let llm = self.memory_service.llm_for(&project.id).await?;
+        if !llm.is_available().await {
             return Err(Error::Llm(
                 "LLM service is unavailable - cannot index without summarization".to_string(),
             ));
         }
 
-        let code_summary = self
-            .llm
-            .summarize_code(&content, &rel_path, &language)
-            .await?;
+        let code_summary = llm.summarize_code(&content, &rel_path, &language).await?;
 
         let title = code_summary.title;
         let summary_content = code_summary.summary;
@@ -832,16 +821,14 @@ impl IndexerService {
         let memory_id = Self::path_hash(&project.slug, file_path);
 
         // Generate summary using LLM - fail if LLM is unavailable (no dumb fallbacks)
-        if !self.llm.is_available().await {
+        let llm = self.memory_service.llm_for(&project.id).await?;
+        if !llm.is_available().await {
             return Err(Error::Llm(
                 "LLM service is unavailable - cannot index without summarization".to_string(),
             ));
         }
 
-        let code_summary = self
-            .llm
-            .summarize_code(content, file_path, &language)
-            .await?;
+        let code_summary = llm.summarize_code(content, file_path, &language).await?;
 
         let title = code_summary.title;
         let summary_content = code_summary.summary;
@@ -966,9 +953,12 @@ impl IndexerService {
             .db
             .as_ref()
             .ok_or_else(|| Error::Internal("Database not configured for chunking".to_string()))?;
-        let embedding = self.embedding.as_ref().ok_or_else(|| {
-            Error::Internal("Embedding service not configured for chunking".to_string())
-        })?;
+        if self.embedding.is_none() {
+            return Err(Error::Internal(
+                "Embedding service not configured for chunking".to_string(),
+            ));
+        }
+        let embedding = self.memory_service.embeddings_for(project_id).await?;
         let qdrant = self.qdrant.as_ref().ok_or_else(|| {
             Error::Internal("Qdrant service not configured for chunking".to_string())
         })?;
@@ -1023,6 +1013,11 @@ impl IndexerService {
         let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
 
         // Generate embeddings for all chunks
+        if let Err(e) = super::rate_limit::check_quota(db, project_id, UsageKind::Embedding).await {
+            warn!(memory_id = %memory_id, error = %e, "Skipping chunk embeddings");
+            return Ok(chunk_count);
+        }
+        super::rate_limit::record_usage(db, project_id, UsageKind::Embedding, 1).await;
         let embeddings = match embedding.embed(texts).await {
             Ok(embs) => embs,
             Err(e) => {
@@ -1039,45 +1034,7 @@ impl IndexerService {
         let points: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)> = chunks
             .iter()
             .zip(embeddings.into_iter())
-            .map(|(chunk, vector)| {
-                let mut payload = HashMap::new();
-                payload.insert(
-                    "type".to_string(),
-                    serde_json::Value::String("chunk".to_string()),
-                );
-                payload.insert(
-                    "parent_memory_id".to_string(),
-                    serde_json::Value::String(chunk.memory_id.clone()),
-                );
-                payload.insert(
-                    "project_id".to_string(),
-                    serde_json::Value::String(chunk.project_id.clone()),
-                );
-                payload.insert(
-                    "node_type".to_string(),
-                    serde_json::Value::String(chunk.node_type.clone()),
-                );
-                if let Some(ref name) = chunk.node_name {
-                    payload.insert(
-                        "node_name".to_string(),
-                        serde_json::Value::String(name.clone()),
-                    );
-                }
-                payload.insert(
-                    "start_line".to_string(),
-                    serde_json::Value::Number(chunk.start_line.into()),
-                );
-                payload.insert(
-                    "end_line".to_string(),
-                    serde_json::Value::Number(chunk.end_line.into()),
-                );
-                payload.insert(
-                    "language".to_string(),
-                    serde_json::Value::String(chunk.language.clone()),
-                );
-
-                (chunk.id.clone(), vector, payload)
-            })
+            .map(|(chunk, vector)| (chunk.id.clone(), vector, chunk_vector_payload(chunk)))
             .collect();
 
         // Store in Qdrant
@@ -1118,3 +1075,44 @@ impl IndexerService {
         hashes.remove(project_slug);
     }
 }
+
+/// Build the Qdrant payload stored alongside a chunk's vector.
+pub(crate) fn chunk_vector_payload(chunk: &Chunk) -> HashMap<String, serde_json::Value> {
+    let mut payload = HashMap::new();
+    payload.insert(
+        "type".to_string(),
+        serde_json::Value::String("chunk".to_string()),
+    );
+    payload.insert(
+        "parent_memory_id".to_string(),
+        serde_json::Value::String(chunk.memory_id.clone()),
+    );
+    payload.insert(
+        "project_id".to_string(),
+        serde_json::Value::String(chunk.project_id.clone()),
+    );
+    payload.insert(
+        "node_type".to_string(),
+        serde_json::Value::String(chunk.node_type.clone()),
+    );
+    if let Some(ref name) = chunk.node_name {
+        payload.insert(
+            "node_name".to_string(),
+            serde_json::Value::String(name.clone()),
+        );
+    }
+    payload.insert(
+        "start_line".to_string(),
+        serde_json::Value::Number(chunk.start_line.into()),
+    );
+    payload.insert(
+        "end_line".to_string(),
+        serde_json::Value::Number(chunk.end_line.into()),
+    );
+    payload.insert(
+        "language".to_string(),
+        serde_json::Value::String(chunk.language.clone()),
+    );
+
+    payload
+}
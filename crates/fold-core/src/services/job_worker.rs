@@ -17,9 +17,12 @@ use tracing::{debug, error, info, warn};
 
 use crate::db::{self, DbPool, JobType, LogLevel};
 use crate::error::{Error, Result};
+use crate::services::decay::ACCESS_HISTORY_LIMIT;
+use crate::services::{search_analytics, search_eval};
 use crate::services::{
-    EmbeddingService, EventBroadcaster, GitHubService, GitLocalService, GitSyncService,
-    IndexerService, LlmService, MemoryService, MetadataSyncService,
+    ConsolidationService, EmbeddingService, EventBroadcaster, FoldChanges, GitHubService,
+    GitLocalService, GitSyncService, IndexerService, LlmService, MemoryService,
+    MetadataSyncService, RetentionService, SearchAnalyticsService, SearchEvalService,
 };
 
 /// Poll interval for checking new jobs (seconds)
@@ -40,6 +43,21 @@ const REPO_POLL_INTERVAL_SECS: u64 = 300;
 /// How often to check for provider availability and resume paused jobs (seconds)
 const PROVIDER_CHECK_INTERVAL_SECS: u64 = 30;
 
+/// How often to check for projects due a memory consolidation run (seconds)
+const CONSOLIDATION_CHECK_INTERVAL_SECS: u64 = 900;
+
+/// How often to check for projects due a retention run (seconds)
+const RETENTION_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Minimum hours between retention runs for a project
+const RETENTION_INTERVAL_HOURS: i64 = 24;
+
+/// How often to check for projects due a knowledge gap report (seconds)
+const GAP_REPORT_CHECK_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// Minimum days between scheduled knowledge gap reports for a project
+const GAP_REPORT_INTERVAL_DAYS: i64 = 7;
+
 /// Background job worker service.
 #[derive(Clone)]
 pub struct JobWorker {
@@ -56,6 +74,10 @@ struct JobWorkerInner {
     llm: Arc<LlmService>,
     embeddings: Arc<EmbeddingService>,
     events: Arc<EventBroadcaster>,
+    consolidation: ConsolidationService,
+    retention: RetentionService,
+    search_eval: SearchEvalService,
+    search_analytics: SearchAnalyticsService,
     running: RwLock<bool>,
     active_jobs: RwLock<usize>,
     worker_id: String,
@@ -79,6 +101,11 @@ impl JobWorker {
         // Generate unique worker ID
         let worker_id = format!("worker-{}-{}", hostname(), nanoid::nanoid!(8));
 
+        let consolidation = ConsolidationService::new(db.clone(), memory.clone());
+        let retention = RetentionService::new(db.clone(), memory.clone());
+        let search_eval = SearchEvalService::new(db.clone(), memory.clone());
+        let search_analytics = SearchAnalyticsService::new(db.clone(), memory.clone());
+
         Self {
             inner: Arc::new(JobWorkerInner {
                 db,
@@ -90,6 +117,10 @@ impl JobWorker {
                 llm,
                 embeddings,
                 events,
+                consolidation,
+                retention,
+                search_eval,
+                search_analytics,
                 running: RwLock::new(false),
                 active_jobs: RwLock::new(0),
                 worker_id,
@@ -144,6 +175,24 @@ impl JobWorker {
             provider_worker.run_provider_check_loop().await;
         });
 
+        // Spawn memory consolidation scheduling loop
+        let consolidation_worker = self.clone();
+        tokio::spawn(async move {
+            consolidation_worker.run_consolidation_schedule_loop().await;
+        });
+
+        // Spawn retention scheduling loop
+        let retention_worker = self.clone();
+        tokio::spawn(async move {
+            retention_worker.run_retention_schedule_loop().await;
+        });
+
+        // Spawn knowledge gap report scheduling loop
+        let gap_report_worker = self.clone();
+        tokio::spawn(async move {
+            gap_report_worker.run_gap_report_schedule_loop().await;
+        });
+
         info!(worker_id = %self.inner.worker_id, "Job worker started");
 
         JobWorkerHandle {
@@ -282,6 +331,155 @@ impl JobWorker {
         }
     }
 
+    /// Enqueue consolidation jobs for projects whose interval has elapsed.
+    async fn run_consolidation_schedule_loop(&self) {
+        // Wait a bit before starting to let the server fully initialize
+        sleep(Duration::from_secs(30)).await;
+
+        loop {
+            if !*self.inner.running.read().await {
+                break;
+            }
+
+            match db::list_due_consolidations(&self.inner.db).await {
+                Ok(due) => {
+                    for settings in due {
+                        let job_id = crate::models::new_id();
+                        let result = db::create_job(
+                            &self.inner.db,
+                            db::CreateJob::new(job_id, JobType::ConsolidateMemories)
+                                .with_project(settings.project_id.clone())
+                                .with_priority(db::JobPriority::Low),
+                        )
+                        .await;
+
+                        match result {
+                            // Mark now so the next tick does not enqueue a duplicate
+                            Ok(_) => {
+                                let _ = db::mark_consolidation_run(
+                                    &self.inner.db,
+                                    &settings.project_id,
+                                )
+                                .await;
+                            }
+                            Err(e) => warn!(
+                                project_id = %settings.project_id,
+                                error = %e,
+                                "Failed to schedule memory consolidation"
+                            ),
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to list projects due for consolidation");
+                }
+            }
+
+            sleep(Duration::from_secs(CONSOLIDATION_CHECK_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Enqueue retention jobs for projects with archive policies, at most
+    /// once per `RETENTION_INTERVAL_HOURS`.
+    async fn run_retention_schedule_loop(&self) {
+        // Wait a bit before starting to let the server fully initialize
+        sleep(Duration::from_secs(60)).await;
+
+        loop {
+            if !*self.inner.running.read().await {
+                break;
+            }
+
+            match db::list_projects_due_retention(&self.inner.db, RETENTION_INTERVAL_HOURS).await {
+                Ok(project_ids) => {
+                    for project_id in project_ids {
+                        let job_id = crate::models::new_id();
+                        if let Err(e) = db::create_job(
+                            &self.inner.db,
+                            db::CreateJob::new(job_id, JobType::ApplyRetention)
+                                .with_project(project_id.clone())
+                                .with_priority(db::JobPriority::Low),
+                        )
+                        .await
+                        {
+                            warn!(
+                                project_id = %project_id,
+                                error = %e,
+                                "Failed to schedule retention run"
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to list projects due for retention");
+                }
+            }
+
+            sleep(Duration::from_secs(RETENTION_CHECK_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Enqueue weekly knowledge gap reports for searched projects, and purge
+    /// search log entries past `LOG_RETENTION_DAYS`, expired query rewrites
+    /// and access history beyond `ACCESS_HISTORY_LIMIT` per memory.
+    async fn run_gap_report_schedule_loop(&self) {
+        // Wait a bit before starting to let the server fully initialize
+        sleep(Duration::from_secs(60)).await;
+
+        loop {
+            if !*self.inner.running.read().await {
+                break;
+            }
+
+            match db::purge_search_queries(&self.inner.db, search_analytics::LOG_RETENTION_DAYS)
+                .await
+            {
+                Ok(purged) if purged > 0 => debug!(purged, "Purged old search log entries"),
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to purge search log"),
+            }
+
+            match db::purge_query_rewrites(&self.inner.db).await {
+                Ok(purged) if purged > 0 => debug!(purged, "Purged expired query rewrites"),
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to purge query rewrites"),
+            }
+
+            match db::prune_memory_accesses(&self.inner.db, ACCESS_HISTORY_LIMIT).await {
+                Ok(pruned) if pruned > 0 => debug!(pruned, "Pruned old memory accesses"),
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to prune memory accesses"),
+            }
+
+            match db::list_projects_due_gap_report(&self.inner.db, GAP_REPORT_INTERVAL_DAYS).await {
+                Ok(project_ids) => {
+                    for project_id in project_ids {
+                        let job_id = crate::models::new_id();
+                        if let Err(e) = db::create_job(
+                            &self.inner.db,
+                            db::CreateJob::new(job_id, JobType::KnowledgeGapReport)
+                                .with_project(project_id.clone())
+                                .with_priority(db::JobPriority::Low),
+                        )
+                        .await
+                        {
+                            warn!(
+                                project_id = %project_id,
+                                error = %e,
+                                "Failed to schedule knowledge gap report"
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to list projects due a knowledge gap report");
+                }
+            }
+
+            sleep(Duration::from_secs(GAP_REPORT_CHECK_INTERVAL_SECS)).await;
+        }
+    }
+
     /// Run repository polling loop to check for new commits.
     ///
     /// Checks all repositories with `notification_type = 'polling'` every 5 minutes
@@ -575,6 +773,9 @@ impl JobWorker {
                 | JobType::ReindexRepo
                 | JobType::IndexHistory
                 | JobType::GenerateSummary
+                | JobType::ConsolidateMemories
+                | JobType::ApplyRetention
+                | JobType::EvaluateSearch
         )
     }
 
@@ -628,6 +829,10 @@ impl JobWorker {
             Some(JobType::SyncMetadata) => self.process_sync_metadata(job_id).await,
             Some(JobType::ProcessWebhook) => self.process_webhook(job_id).await,
             Some(JobType::GenerateSummary) => self.process_generate_summary(job_id).await,
+            Some(JobType::ConsolidateMemories) => self.process_consolidate_memories(job_id).await,
+            Some(JobType::ApplyRetention) => self.process_apply_retention(job_id).await,
+            Some(JobType::EvaluateSearch) => self.process_evaluate_search(job_id).await,
+            Some(JobType::KnowledgeGapReport) => self.process_knowledge_gap_report(job_id).await,
             Some(JobType::Custom) => self.process_custom(job_id).await,
             None => {
                 warn!(job_id, job_type, "Unknown job type");
@@ -752,7 +957,11 @@ impl JobWorker {
         };
 
         // Generate summary using LLM (max 500 tokens for concise output)
-        match self.inner.llm.complete(&prompt, 500).await {
+        let llm = match &job.project_id {
+            Some(project_id) => self.inner.memory.llm_for(project_id).await?,
+            None => self.inner.llm.clone(),
+        };
+        match llm.complete(&prompt, 500).await {
             Ok(summary) => {
                 self.log_job(
                     job_id,
@@ -786,6 +995,215 @@ impl JobWorker {
         Ok(())
     }
 
+    /// Process memory consolidation job.
+    ///
+    /// Payload: `{"dry_run": bool}` (defaults to false).
+    async fn process_consolidate_memories(&self, job_id: &str) -> Result<()> {
+        let job = db::get_job(&self.inner.db, job_id).await?;
+
+        let project_id = job
+            .project_id
+            .clone()
+            .ok_or_else(|| Error::Internal("Consolidation job has no project".to_string()))?;
+
+        let payload: serde_json::Value = job
+            .payload
+            .as_ref()
+            .and_then(|p| serde_json::from_str(p).ok())
+            .unwrap_or_default();
+        let dry_run = payload
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let report = self
+            .inner
+            .consolidation
+            .run(&project_id, Some(job_id), dry_run)
+            .await?;
+
+        self.log_job(
+            job_id,
+            LogLevel::Info,
+            &format!(
+                "{}{} clusters from {} memories, {} merges applied",
+                if dry_run { "[dry run] " } else { "" },
+                report.clusters,
+                report.candidates,
+                report.applied
+            ),
+        )
+        .await?;
+
+        db::update_job_metadata(&self.inner.db, job_id, &serde_json::to_value(&report)?).await?;
+
+        Ok(())
+    }
+
+    /// Process retention job.
+    ///
+    /// Payload: `{"dry_run": bool}` (defaults to false).
+    async fn process_apply_retention(&self, job_id: &str) -> Result<()> {
+        let job = db::get_job(&self.inner.db, job_id).await?;
+
+        let project_id = job
+            .project_id
+            .clone()
+            .ok_or_else(|| Error::Internal("Retention job has no project".to_string()))?;
+
+        let payload: serde_json::Value = job
+            .payload
+            .as_ref()
+            .and_then(|p| serde_json::from_str(p).ok())
+            .unwrap_or_default();
+        let dry_run = payload
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let report = self.inner.retention.run(&project_id, dry_run).await?;
+
+        self.log_job(
+            job_id,
+            LogLevel::Info,
+            &format!(
+                "{}{} of {} memories selected for archival, {} archived, {} protected",
+                if dry_run { "[dry run] " } else { "" },
+                report.candidates.len(),
+                report.evaluated,
+                report.archived,
+                report.protected
+            ),
+        )
+        .await?;
+
+        db::update_job_metadata(&self.inner.db, job_id, &serde_json::to_value(&report)?).await?;
+
+        Ok(())
+    }
+
+    /// Process search evaluation job.
+    ///
+    /// Payload: `{"set_id": "...", "k": 10}` (`k` defaults to 10).
+    async fn process_evaluate_search(&self, job_id: &str) -> Result<()> {
+        let job = db::get_job(&self.inner.db, job_id).await?;
+
+        let project_id = job
+            .project_id
+            .clone()
+            .ok_or_else(|| Error::Internal("Evaluation job has no project".to_string()))?;
+
+        let payload: serde_json::Value = job
+            .payload
+            .as_ref()
+            .and_then(|p| serde_json::from_str(p).ok())
+            .unwrap_or_default();
+        let set_id = payload
+            .get("set_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::Internal("Evaluation job has no golden set".to_string()))?;
+        let k = payload
+            .get("k")
+            .and_then(|v| v.as_u64())
+            .map(|k| k as usize)
+            .unwrap_or(search_eval::DEFAULT_K)
+            .clamp(1, search_eval::MAX_K);
+
+        let project = db::get_project(&self.inner.db, &project_id).await?;
+        let evaluation = self
+            .inner
+            .search_eval
+            .run(&project, set_id, k, Some(job_id))
+            .await?;
+
+        self.log_job(
+            job_id,
+            LogLevel::Info,
+            &format!(
+                "Evaluated {} queries: recall@{} {:.3}, MRR {:.3}, nDCG {:.3}",
+                evaluation.query_count, k, evaluation.recall_at_k, evaluation.mrr, evaluation.ndcg
+            ),
+        )
+        .await?;
+
+        db::update_job_metadata(
+            &self.inner.db,
+            job_id,
+            &serde_json::json!({
+                "evaluation_id": evaluation.id,
+                "set_id": evaluation.set_id,
+                "k": evaluation.k,
+                "query_count": evaluation.query_count,
+                "recall_at_k": evaluation.recall_at_k,
+                "mrr": evaluation.mrr,
+                "ndcg": evaluation.ndcg,
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Process knowledge gap report job.
+    ///
+    /// Payload: `{"days": 7, "cluster": true}` (both optional).
+    async fn process_knowledge_gap_report(&self, job_id: &str) -> Result<()> {
+        let job = db::get_job(&self.inner.db, job_id).await?;
+
+        let project_id = job
+            .project_id
+            .clone()
+            .ok_or_else(|| Error::Internal("Gap report job has no project".to_string()))?;
+
+        let payload: serde_json::Value = job
+            .payload
+            .as_ref()
+            .and_then(|p| serde_json::from_str(p).ok())
+            .unwrap_or_default();
+        let days = payload
+            .get("days")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(search_analytics::DEFAULT_GAP_WINDOW_DAYS);
+        let cluster = payload
+            .get("cluster")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let report = self
+            .inner
+            .search_analytics
+            .run_gap_report(&project_id, days, cluster, Some(job_id))
+            .await?;
+
+        self.log_job(
+            job_id,
+            LogLevel::Info,
+            &format!(
+                "Knowledge gap report over {} days ({})",
+                report.window_days,
+                if report.clustered != 0 {
+                    "clustered"
+                } else {
+                    "unclustered"
+                }
+            ),
+        )
+        .await?;
+
+        db::update_job_metadata(
+            &self.inner.db,
+            job_id,
+            &serde_json::json!({
+                "report_id": report.id,
+                "window_days": report.window_days,
+                "clustered": report.clustered != 0,
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Process custom job (payload-driven).
     async fn process_custom(&self, job_id: &str) -> Result<()> {
         let job = db::get_job(&self.inner.db, job_id).await?;
@@ -947,6 +1365,38 @@ impl JobWorker {
         )
         .await?;
 
+        // Apply edits people made to fold/ memory files
+        let fold_changes: FoldChanges = payload
+            .get("fold")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        if !fold_changes.is_empty() {
+            let commit_sha = payload.get("commit_sha").and_then(|v| v.as_str());
+            let result = self
+                .inner
+                .git_sync
+                .sync_fold_changes(&project, &local_path, &fold_changes, commit_sha)
+                .await;
+
+            self.log_job(
+                job_id,
+                if result.conflicts > 0 || result.failed > 0 {
+                    LogLevel::Warn
+                } else {
+                    LogLevel::Info
+                },
+                &format!(
+                    "Synced fold/ edits: {} created, {} updated, {} deleted, {} conflicts, {} failed",
+                    result.created,
+                    result.updated,
+                    result.deleted,
+                    result.conflicts,
+                    result.failed
+                ),
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -17,14 +17,13 @@ use crate::db::DbPool;
 use crate::error::{Error, Result};
 use crate::models::{LinkType, Memory, MemoryLink, MemoryType};
 
-use super::{EmbeddingService, LlmService, MemoryService, QdrantService};
+use super::{EmbeddingService, MemoryService, QdrantService};
 
 /// Service for automatically creating links between memories.
 #[derive(Clone)]
 pub struct LinkerService {
     db: DbPool,
     memory: MemoryService,
-    llm: Arc<LlmService>,
     qdrant: Arc<QdrantService>,
     embeddings: Arc<EmbeddingService>,
 }
@@ -53,14 +52,12 @@ impl LinkerService {
     pub fn new(
         db: DbPool,
         memory: MemoryService,
-        llm: Arc<LlmService>,
         qdrant: Arc<QdrantService>,
         embeddings: Arc<EmbeddingService>,
     ) -> Self {
         Self {
             db,
             memory,
-            llm,
             qdrant,
             embeddings,
         }
@@ -95,7 +92,7 @@ impl LinkerService {
         }
 
         // 3. Find LLM-suggested links (if LLM available)
-        if self.llm.is_available().await {
+        if self.memory.llm_for(project_id).await?.is_available().await {
             let llm_suggestions = self
                 .find_llm_links(&memory, project_id, project_slug)
                 .await?;
@@ -433,7 +430,12 @@ impl LinkerService {
         }
 
         // Ask LLM for link suggestions
-        let llm_suggestions = self.llm.suggest_links(memory, &candidate_memories).await?;
+        let llm_suggestions = self
+            .memory
+            .llm_for(project_id)
+            .await?
+            .suggest_links(memory, &candidate_memories)
+            .await?;
 
         let mut suggestions = Vec::new();
 
@@ -550,18 +552,33 @@ impl LinkerService {
             "Created link"
         );
 
+        if let Some(events) = self.memory.events() {
+            events.link_created(
+                project_id, &link.id, source_id, target_id, link_type, created_by,
+            );
+        }
+
         Ok(link)
     }
 
     /// Delete a link.
     pub async fn delete_link(&self, link_id: &str) -> Result<()> {
-        let result = sqlx::query(r#"DELETE FROM memory_links WHERE id = ?"#)
-            .bind(link_id)
-            .execute(&self.db)
-            .await?;
+        let deleted: Option<(String, String, String, String)> = sqlx::query_as(
+            r#"
+            DELETE FROM memory_links WHERE id = ?
+            RETURNING project_id, source_id, target_id, link_type
+            "#,
+        )
+        .bind(link_id)
+        .fetch_optional(&self.db)
+        .await?;
 
-        if result.rows_affected() == 0 {
+        let Some((project_id, source_id, target_id, link_type)) = deleted else {
             return Err(Error::NotFound(format!("Link {}", link_id)));
+        };
+
+        if let Some(events) = self.memory.events() {
+            events.link_deleted(&project_id, link_id, &source_id, &target_id, &link_type);
         }
 
         Ok(())
@@ -6,22 +6,27 @@
 //! Providers are loaded from the database with fallback to environment variables
 //! for initial seeding.
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use fold_llm::chat::{self, StreamDecoder};
+use fold_llm::{ChatRequest, ChatResponse, StreamEvent, Usage};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tokio::time::sleep;
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, info, warn};
 
 use super::ClaudeCodeService;
 use crate::config::LlmConfig;
 use crate::db::{
-    list_enabled_llm_providers, seed_claudecode_provider_async, seed_llm_providers_from_env,
-    update_llm_provider_last_used, DbPool, LlmProviderRow,
+    list_enabled_llm_providers, record_llm_provider_usage, seed_claudecode_provider_async,
+    seed_llm_providers_from_env, DbPool, LlmProviderRow, ProjectProviderRow,
 };
 use crate::error::{Error, Result};
 use crate::models::{CodeSummary, CommitInfo, Memory, SuggestedLink};
@@ -51,6 +56,9 @@ const HEALTH_CHECK_INTERVAL_SECS: u64 = 60;
 /// Number of consecutive errors before marking unavailable
 const ERROR_THRESHOLD: u32 = 3;
 
+/// Stream events buffered ahead of a slow consumer
+const STREAM_BUFFER: usize = 32;
+
 /// Runtime provider configuration (loaded from database)
 #[derive(Debug, Clone)]
 pub struct RuntimeLlmProvider {
@@ -64,18 +72,24 @@ pub struct RuntimeLlmProvider {
 }
 
 impl RuntimeLlmProvider {
-    /// Get the authentication token (prefers OAuth, falls back to API key)
-    pub fn auth_token(&self) -> Option<&str> {
-        self.oauth_access_token
-            .as_deref()
-            .or(self.api_key.as_deref())
-    }
-
     /// Check if provider has valid credentials.
     /// Ollama doesn't require authentication, so it always returns true for Ollama.
     pub fn has_credentials(&self) -> bool {
         self.name == "ollama" || self.api_key.is_some() || self.oauth_access_token.is_some()
     }
+
+    /// The provider as `fold_llm`'s wire formats expect it.
+    fn wire(&self) -> fold_llm::RuntimeLlmProvider {
+        fold_llm::RuntimeLlmProvider {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            base_url: self.base_url.clone(),
+            model: self.model.clone(),
+            api_key: self.api_key.clone(),
+            oauth_access_token: self.oauth_access_token.clone(),
+            priority: self.priority,
+        }
+    }
 }
 
 impl From<LlmProviderRow> for RuntimeLlmProvider {
@@ -102,6 +116,30 @@ impl From<LlmProviderRow> for RuntimeLlmProvider {
     }
 }
 
+impl From<ProjectProviderRow> for RuntimeLlmProvider {
+    fn from(row: ProjectProviderRow) -> Self {
+        let config = row.config_json().unwrap_or(json!({}));
+
+        Self {
+            id: row.id,
+            name: row.name.clone(),
+            base_url: config
+                .get("endpoint")
+                .and_then(|e| e.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| default_endpoint(&row.name)),
+            model: config
+                .get("model")
+                .and_then(|m| m.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| default_model(&row.name)),
+            api_key: row.api_key,
+            oauth_access_token: None,
+            priority: row.priority,
+        }
+    }
+}
+
 /// Get default endpoint for a provider
 fn default_endpoint(name: &str) -> String {
     match name {
@@ -109,6 +147,7 @@ fn default_endpoint(name: &str) -> String {
         "anthropic" | "claudecode" => "https://api.anthropic.com/v1".to_string(),
         "openrouter" => "https://openrouter.ai/api/v1".to_string(),
         "openai" => "https://api.openai.com/v1".to_string(),
+        "ollama" => "http://localhost:11434/v1".to_string(),
         _ => "https://api.openai.com/v1".to_string(),
     }
 }
@@ -120,6 +159,7 @@ fn default_model(name: &str) -> String {
         "anthropic" | "claudecode" => "claude-3-5-haiku-20241022".to_string(),
         "openrouter" => "meta-llama/llama-3-8b-instruct:free".to_string(),
         "openai" => "gpt-4o-mini".to_string(),
+        "ollama" => "llama3.2".to_string(),
         _ => "gpt-4o-mini".to_string(),
     }
 }
@@ -147,59 +187,6 @@ struct LlmServiceInner {
     last_health_check: RwLock<Option<Instant>>,
 }
 
-/// Response from LLM API
-#[derive(Debug, Deserialize)]
-struct LlmResponse {
-    choices: Option<Vec<Choice>>,
-    candidates: Option<Vec<Candidate>>,     // Gemini format
-    content: Option<Vec<AnthropicContent>>, // Anthropic format
-    error: Option<LlmError>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: Option<Message>,
-    text: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Message {
-    content: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct Candidate {
-    content: CandidateContent,
-}
-
-#[derive(Debug, Deserialize)]
-struct CandidateContent {
-    parts: Vec<Part>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Part {
-    text: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicContent {
-    #[serde(rename = "type")]
-    #[allow(dead_code)]
-    content_type: String,
-    text: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct LlmError {
-    message: String,
-    #[serde(rename = "type")]
-    #[allow(dead_code)]
-    error_type: Option<String>,
-    #[allow(dead_code)]
-    code: Option<String>,
-}
-
 impl LlmService {
     /// Create a new LLM service with database-backed providers.
     ///
@@ -327,6 +314,36 @@ impl LlmService {
         }
     }
 
+    /// Create an LLM service over a project's pinned provider chain.
+    ///
+    /// The chain is fixed: it is not reloaded from the global providers.
+    pub fn from_project_providers(rows: Vec<ProjectProviderRow>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let providers: Vec<RuntimeLlmProvider> =
+            rows.into_iter().map(RuntimeLlmProvider::from).collect();
+
+        debug!(
+            providers = ?providers.iter().map(|p| &p.name).collect::<Vec<_>>(),
+            "LLM service initialized from project providers"
+        );
+
+        Self {
+            inner: Arc::new(LlmServiceInner {
+                db: None, // Project chains are not refreshed from the global table
+                providers: RwLock::new(providers),
+                client,
+                last_error: RwLock::new(None),
+                last_error_at: RwLock::new(None),
+                error_count: AtomicU32::new(0),
+                last_health_check: RwLock::new(None),
+            }),
+        }
+    }
+
     /// Reload providers from the database.
     pub async fn refresh_providers(&self) -> Result<()> {
         let Some(ref db) = self.inner.db else {
@@ -422,34 +439,95 @@ impl LlmService {
 
     /// Complete a prompt with automatic provider fallback.
     pub async fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String> {
-        let providers = {
-            let guard = self.inner.providers.read().await;
-            guard.clone()
-        };
+        let request = ChatRequest::prompt(prompt).with_max_tokens(max_tokens);
+        Ok(self.chat(&request).await?.content)
+    }
 
-        if providers.is_empty() {
-            return Err(Error::Llm("No LLM providers configured".to_string()));
-        }
+    /// Run a chat request with automatic provider fallback.
+    ///
+    /// The response reports the provider used and its token usage.
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        self.chat_with(request, Ok).await
+    }
 
+    /// Run a structured chat request and deserialize the response.
+    ///
+    /// The request should carry a response schema (see
+    /// [`ChatRequest::with_json_schema`]). A provider whose output does not
+    /// deserialize counts as failed, so the next provider is tried.
+    pub async fn chat_json<T: DeserializeOwned>(&self, request: &ChatRequest) -> Result<T> {
+        self.chat_with(request, |response| Ok(response.json::<T>()?))
+            .await
+    }
+
+    /// Stream a chat request.
+    ///
+    /// Providers are tried in order until one accepts the request. Its
+    /// response is then forwarded as [`StreamEvent::Delta`] events, ending
+    /// with [`StreamEvent::Done`] carrying the full content and usage. There
+    /// is no fallback once streaming has started.
+    pub async fn chat_stream(
+        &self,
+        request: &ChatRequest,
+    ) -> Result<ReceiverStream<Result<StreamEvent>>> {
+        let providers = self.available_providers().await?;
         let mut last_error = None;
 
         for provider in &providers {
-            if !provider.has_credentials() {
-                debug!(provider = %provider.name, "Skipping provider without credentials");
-                continue;
+            match self.send(provider, request, true).await {
+                Ok(response) => {
+                    self.clear_error().await;
+
+                    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+                    let decoder = StreamDecoder::new(&provider.wire());
+                    tokio::spawn(forward_stream(
+                        response,
+                        decoder,
+                        tx,
+                        self.inner.db.clone(),
+                        provider.id.clone(),
+                    ));
+                    return Ok(ReceiverStream::new(rx));
+                }
+                Err(e) => {
+                    warn!(
+                        provider = %provider.name,
+                        error = %e,
+                        "Provider failed to start stream, trying next"
+                    );
+                    last_error = Some(e);
+                }
             }
+        }
 
-            match self.try_provider(provider, prompt, max_tokens).await {
+        Err(self.all_failed(last_error).await)
+    }
+
+    /// Run a chat request through the provider chain until a response passes
+    /// `accept`.
+    async fn chat_with<T>(
+        &self,
+        request: &ChatRequest,
+        accept: impl Fn(ChatResponse) -> Result<T>,
+    ) -> Result<T> {
+        let providers = self.available_providers().await?;
+        let mut last_error = None;
+
+        for provider in &providers {
+            let result = match self.try_provider(provider, request).await {
                 Ok(response) => {
-                    // Update last used timestamp
-                    if !provider.id.is_empty() {
-                        if let Some(ref db) = self.inner.db {
-                            let _ = update_llm_provider_last_used(db, &provider.id).await;
-                        }
-                    }
+                    // Usage is recorded even when the output is rejected
+                    record_usage(self.inner.db.as_ref(), &provider.id, &response.usage).await;
+                    accept(response)
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(value) => {
                     // Clear error state on success
                     self.clear_error().await;
-                    return Ok(response);
+                    return Ok(value);
                 }
                 Err(e) => {
                     warn!(
@@ -462,27 +540,52 @@ impl LlmService {
             }
         }
 
-        // Record error when all providers fail
+        Err(self.all_failed(last_error).await)
+    }
+
+    /// Providers with credentials, in priority order.
+    async fn available_providers(&self) -> Result<Vec<RuntimeLlmProvider>> {
+        let providers = {
+            let guard = self.inner.providers.read().await;
+            guard.clone()
+        };
+
+        if providers.is_empty() {
+            return Err(Error::Llm("No LLM providers configured".to_string()));
+        }
+
+        Ok(providers
+            .into_iter()
+            .filter(|provider| {
+                if !provider.has_credentials() {
+                    debug!(provider = %provider.name, "Skipping provider without credentials");
+                }
+                provider.has_credentials()
+            })
+            .collect())
+    }
+
+    /// Record that every provider failed, returning the error to report.
+    async fn all_failed(&self, last_error: Option<Error>) -> Error {
         let error_msg = last_error
             .as_ref()
             .map(|e| e.to_string())
             .unwrap_or_else(|| "All providers failed".to_string());
         self.record_error(&error_msg).await;
 
-        Err(last_error.unwrap_or_else(|| Error::Llm("All providers failed".to_string())))
+        last_error.unwrap_or_else(|| Error::Llm("All providers failed".to_string()))
     }
 
     /// Try a specific provider with retries.
     async fn try_provider(
         &self,
         provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> Result<String> {
+        request: &ChatRequest,
+    ) -> Result<ChatResponse> {
         let mut delay = Duration::from_millis(RETRY_DELAY_MS);
 
         for attempt in 0..MAX_RETRIES {
-            match self.call_provider(provider, prompt, max_tokens).await {
+            match self.call_provider(provider, request).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     if Self::is_retryable(&e) && attempt < MAX_RETRIES - 1 {
@@ -520,257 +623,66 @@ impl LlmService {
     async fn call_provider(
         &self,
         provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> Result<String> {
-        let auth_token = provider
-            .auth_token()
-            .ok_or_else(|| Error::Llm(format!("No credentials for provider {}", provider.name)))?;
+        request: &ChatRequest,
+    ) -> Result<ChatResponse> {
+        let response = self.send(provider, request, false).await?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Llm(format!("Failed to read response: {}", e)))?;
+
+        Ok(chat::parse_response(&provider.wire(), &text)?)
+    }
+
+    /// Send a chat request, returning the response once it has a success
+    /// status.
+    async fn send(
+        &self,
+        provider: &RuntimeLlmProvider,
+        request: &ChatRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let wire = provider.wire();
+        let headers = chat::auth_headers(&wire)?;
 
         debug!(
             provider = %provider.name,
             model = %provider.model,
+            stream,
             "Calling LLM provider"
         );
 
-        let (url, body) = match provider.name.as_str() {
-            "gemini" => self.build_gemini_request(provider, prompt, max_tokens),
-            "anthropic" | "claudecode" => {
-                self.build_anthropic_request(provider, prompt, max_tokens)
-            }
-            _ => self.build_openai_request(provider, prompt, max_tokens),
-        };
+        let (url, body) = chat::build_request(&wire, request, stream);
 
-        let mut request = self
+        let mut http = self
             .inner
             .client
             .post(&url)
             .header("Content-Type", "application/json");
+        for (name, value) in headers {
+            http = http.header(name, value);
+        }
 
-        // Add authentication header based on provider
-        request = match provider.name.as_str() {
-            "anthropic" => {
-                // Check if using OAuth token (requires beta header)
-                if provider.oauth_access_token.is_some() {
-                    request
-                        .header("Authorization", format!("Bearer {}", auth_token))
-                        .header("anthropic-version", "2023-06-01")
-                        .header(
-                            "anthropic-beta",
-                            "oauth-2025-04-20, claude-code-20250219, interleaved-thinking-2025-05-14",
-                        )
-                } else {
-                    request
-                        .header("x-api-key", auth_token)
-                        .header("anthropic-version", "2023-06-01")
-                }
-            }
-            "claudecode" => {
-                // Claude Code always uses OAuth tokens from ~/.claude
-                request
-                    .header("Authorization", format!("Bearer {}", auth_token))
-                    .header("anthropic-version", "2023-06-01")
-                    .header(
-                        "anthropic-beta",
-                        "oauth-2025-04-20, claude-code-20250219, interleaved-thinking-2025-05-14",
-                    )
-            }
-            _ => request.header("Authorization", format!("Bearer {}", auth_token)),
-        };
-
-        let response = request
+        let response = http
             .json(&body)
             .send()
             .await
             .map_err(|e| Error::Llm(format!("Request failed: {}", e)))?;
 
         let status = response.status();
-        let text = response
-            .text()
-            .await
-            .map_err(|e| Error::Llm(format!("Failed to read response: {}", e)))?;
-
         if status.as_u16() == 429 {
             return Err(Error::RateLimitExceeded);
         }
 
         if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
             return Err(Error::Llm(format!(
                 "Provider returned {}: {}",
                 status, text
             )));
         }
 
-        self.parse_response(&provider.name, &text)
-    }
-
-    /// Build request for Gemini API
-    fn build_gemini_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let auth_token = provider.auth_token().unwrap_or("");
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            provider.base_url, provider.model, auth_token
-        );
-
-        let body = json!({
-            "contents": [{
-                "parts": [{"text": prompt}]
-            }],
-            "generationConfig": {
-                "maxOutputTokens": max_tokens,
-                "temperature": 0.3
-            }
-        });
-
-        (url, body)
-    }
-
-    /// Build request for OpenAI-compatible APIs (OpenAI, OpenRouter)
-    fn build_openai_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let url = format!("{}/chat/completions", provider.base_url);
-
-        let mut body = json!({
-            "model": provider.model,
-            "messages": [
-                {"role": "user", "content": prompt}
-            ],
-            "max_tokens": max_tokens,
-            "temperature": 0.3
-        });
-
-        // Add OpenRouter-specific headers
-        if provider.name == "openrouter" {
-            body["http_referer"] = json!("https://fold.dev");
-            body["x_title"] = json!("Fold Memory System");
-        }
-
-        (url, body)
-    }
-
-    /// Build request for Anthropic Claude API
-    fn build_anthropic_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let url = format!("{}/messages", provider.base_url);
-
-        let body = json!({
-            "model": provider.model,
-            "messages": [
-                {"role": "user", "content": prompt}
-            ],
-            "max_tokens": max_tokens,
-            "temperature": 0.3
-        });
-
-        (url, body)
-    }
-
-    /// Parse response from different API formats
-    fn parse_response(&self, provider: &str, text: &str) -> Result<String> {
-        let response: LlmResponse = serde_json::from_str(text)
-            .map_err(|e| Error::Llm(format!("Failed to parse response: {}", e)))?;
-
-        if let Some(error) = response.error {
-            return Err(Error::Llm(error.message));
-        }
-
-        // Try Anthropic format first
-        if let Some(content) = response.content {
-            if let Some(content_block) = content.first() {
-                return Ok(content_block.text.clone());
-            }
-        }
-
-        // Try Gemini format
-        if let Some(candidates) = response.candidates {
-            if let Some(candidate) = candidates.first() {
-                if let Some(part) = candidate.content.parts.first() {
-                    return Ok(part.text.clone());
-                }
-            }
-        }
-
-        // Try OpenAI format
-        if let Some(choices) = response.choices {
-            if let Some(choice) = choices.first() {
-                if let Some(message) = &choice.message {
-                    return Ok(message.content.clone());
-                }
-                if let Some(text) = &choice.text {
-                    return Ok(text.clone());
-                }
-            }
-        }
-
-        Err(Error::Llm(format!("No content in {} response", provider)))
-    }
-
-    /// Extract JSON from LLM response text
-    fn extract_json(&self, text: &str) -> Option<Value> {
-        // Try to find JSON in code blocks
-        if let Some(start) = text.find("```json") {
-            let start = start + 7;
-            if let Some(end) = text[start..].find("```") {
-                if let Ok(json) = serde_json::from_str(&text[start..start + end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        // Try to find JSON in generic code blocks
-        if let Some(start) = text.find("```") {
-            let start = start + 3;
-            // Skip language identifier if present
-            let start = text[start..]
-                .find('\n')
-                .map(|i| start + i + 1)
-                .unwrap_or(start);
-            if let Some(end) = text[start..].find("```") {
-                if let Ok(json) = serde_json::from_str(&text[start..start + end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        // Try to find raw JSON object
-        if let Some(start) = text.find('{') {
-            // Find matching closing brace
-            let mut depth = 0;
-            let mut end = start;
-            for (i, c) in text[start..].char_indices() {
-                match c {
-                    '{' => depth += 1,
-                    '}' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            end = start + i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if end > start {
-                if let Ok(json) = serde_json::from_str(&text[start..end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        None
+        Ok(response)
     }
 
     /// Summarize a file and extract detailed metadata.
@@ -791,51 +703,33 @@ Type: {language}
 {content}
 ```
 
-Return JSON:
-- "title": What this file contains or does (max 80 chars)
-- "summary": 1-2 sentences describing the content. State what it says/does.
-- "keywords": Key terms or identifiers from the file (max 10), empty array if none
-- "tags": Relevant categories (max 5), empty array if none obvious - never use vague tags like "unclear" or "insufficient-information"
-- "created_date": Earliest date found (YYYY-MM-DD) or null
-
-ONLY valid JSON."#,
+Provide:
+- title: What this file contains or does (max 80 chars)
+- summary: 1-2 sentences describing the content. State what it says/does.
+- keywords: Key terms or identifiers from the file (max 10), empty if none
+- tags: Relevant categories (max 5), empty if none obvious - never use vague tags like "unclear" or "insufficient-information"
+- created_date: Earliest date found (YYYY-MM-DD), or an empty string if none"#,
             path = path,
             language = if language.is_empty() { "unknown" } else { language },
             content = &content[..floor_char_boundary(&content, content.len().min(4000))]
         );
 
-        let response = self.complete(&prompt, 400).await?;
-
-        let json = self.extract_json(&response).unwrap_or_else(|| json!({}));
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(400)
+            .with_json_schema("file_summary", file_summary_schema());
+        let summary: FileSummary = self.chat_json(&request).await?;
 
         Ok(CodeSummary {
-            title: json["title"].as_str().unwrap_or("").to_string(),
-            summary: json["summary"].as_str().unwrap_or("").to_string(),
-            keywords: json["keywords"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
-            tags: json["tags"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
+            title: summary.title,
+            summary: summary.summary,
+            keywords: summary.keywords,
+            tags: summary.tags,
             language: if language.is_empty() {
                 None
             } else {
                 Some(language.to_string())
             },
-            created_date: json["created_date"]
-                .as_str()
-                .filter(|s| !s.is_empty())
-                .map(String::from),
+            created_date: Some(summary.created_date).filter(|s| !s.trim().is_empty()),
         })
     }
 
@@ -1013,8 +907,7 @@ For each related candidate, suggest a link with:
 - "confidence": 0.0-1.0 how confident the relationship exists
 - "reason": Brief explanation of the relationship
 
-Respond with a JSON array of suggested links. Only include links with confidence > 0.6.
-Example: [{{"target_id": "abc123", "link_type": "references", "confidence": 0.8, "reason": "Both discuss user authentication"}}]"#,
+Only include links with confidence > 0.6."#,
             source_id = memory.id,
             source_type = memory.memory_type,
             source_title = memory.title.as_deref().unwrap_or("Untitled"),
@@ -1028,31 +921,27 @@ Example: [{{"target_id": "abc123", "link_type": "references", "confidence": 0.8,
             candidates = candidates_text
         );
 
-        let response = self.complete(&prompt, 800).await?;
-
-        let json = self.extract_json(&response);
-
-        let links = json
-            .and_then(|v| {
-                v.as_array().map(|arr| {
-                    arr.iter()
-                        .filter_map(|item| {
-                            Some(SuggestedLink {
-                                source_id: memory.id.clone(),
-                                target_id: item["target_id"].as_str()?.to_string(),
-                                link_type: item["link_type"]
-                                    .as_str()
-                                    .unwrap_or("relates_to")
-                                    .to_string(),
-                                confidence: item["confidence"].as_f64().unwrap_or(0.5) as f32,
-                                reason: item["reason"].as_str().unwrap_or("").to_string(),
-                            })
-                        })
-                        .filter(|link| link.confidence > 0.6)
-                        .collect()
-                })
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(800)
+            .with_json_schema("link_suggestions", link_suggestions_schema());
+        let suggestions: LinkSuggestions = self.chat_json(&request).await?;
+
+        let candidate_ids: HashSet<&str> =
+            candidates.iter().take(10).map(|m| m.id.as_str()).collect();
+        let links = suggestions
+            .links
+            .into_iter()
+            // Drop links to memories that were not offered as candidates
+            .filter(|link| candidate_ids.contains(link.target_id.as_str()))
+            .filter(|link| link.confidence > 0.6)
+            .map(|link| SuggestedLink {
+                source_id: memory.id.clone(),
+                target_id: link.target_id,
+                link_type: link.link_type,
+                confidence: link.confidence,
+                reason: link.reason,
             })
-            .unwrap_or_default();
+            .collect();
 
         Ok(links)
     }
@@ -1069,78 +958,183 @@ Example: [{{"target_id": "abc123", "link_type": "references", "confidence": 0.8,
 Content type: {memory_type}
 Content: {content}
 
-Generate a JSON object with:
-- "title": A short descriptive title (max 100 chars)
-- "keywords": Array of 3-7 key terms/concepts
-- "tags": Array of 2-4 broad category tags
-- "context": A one-sentence description of the broader context
-
-Respond with ONLY a valid JSON object, no markdown or explanation."#,
+Generate:
+- title: A short descriptive title (max 100 chars)
+- keywords: 3-7 key terms/concepts
+- tags: 2-4 broad category tags
+- context: A one-sentence description of the broader context"#,
             memory_type = memory_type,
             content = &content[..floor_char_boundary(&content, content.len().min(2000))]
         );
 
-        let response = self.complete(&prompt, 500).await?;
-
-        let json = self.extract_json(&response).unwrap_or_else(|| json!({}));
-
-        Ok(GeneratedMetadata {
-            title: json["title"].as_str().unwrap_or("").to_string(),
-            keywords: json["keywords"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
-            tags: json["tags"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
-            context: json["context"].as_str().unwrap_or("").to_string(),
-        })
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(500)
+            .with_json_schema("generated_metadata", generated_metadata_schema());
+        self.chat_json(&request).await
+    }
+}
+
+/// Record a provider's token usage (also updates its last-used time).
+async fn record_usage(db: Option<&DbPool>, provider_id: &str, usage: &Usage) {
+    if provider_id.is_empty() {
+        return;
+    }
+    if let Some(db) = db {
+        let _ = record_llm_provider_usage(db, provider_id, usage.total() as i64).await;
+    }
+}
+
+/// Forward a streamed response body to a channel as decoded events.
+async fn forward_stream(
+    mut response: reqwest::Response,
+    mut decoder: StreamDecoder,
+    tx: mpsc::Sender<Result<StreamEvent>>,
+    db: Option<DbPool>,
+    provider_id: String,
+) {
+    loop {
+        let bytes = match response.chunk().await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = tx
+                    .send(Err(Error::Llm(format!("Stream interrupted: {}", e))))
+                    .await;
+                return;
+            }
+        };
+
+        let deltas = match decoder.push(&bytes) {
+            Ok(deltas) => deltas,
+            Err(e) => {
+                let _ = tx.send(Err(e.into())).await;
+                return;
+            }
+        };
+        for delta in deltas {
+            if tx.send(Ok(StreamEvent::Delta(delta))).await.is_err() {
+                // Receiver dropped, stop reading
+                return;
+            }
+        }
     }
+
+    let done = match decoder.finish() {
+        Ok(response) => {
+            record_usage(db.as_ref(), &provider_id, &response.usage).await;
+            Ok(StreamEvent::Done(response))
+        }
+        Err(e) => Err(e.into()),
+    };
+    let _ = tx.send(done).await;
+}
+
+/// Structured response to `suggest_links`.
+#[derive(Debug, Deserialize)]
+struct LinkSuggestions {
+    links: Vec<LinkSuggestionItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkSuggestionItem {
+    target_id: String,
+    link_type: String,
+    confidence: f32,
+    #[serde(default)]
+    reason: String,
+}
+
+/// JSON schema for `LinkSuggestions`.
+fn link_suggestions_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "links": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "target_id": {"type": "string"},
+                        "link_type": {
+                            "type": "string",
+                            "enum": [
+                                "references", "implements", "extends",
+                                "relates_to", "depends_on", "deprecates"
+                            ]
+                        },
+                        "confidence": {"type": "number"},
+                        "reason": {"type": "string"}
+                    },
+                    "required": ["target_id", "link_type", "confidence", "reason"]
+                }
+            }
+        },
+        "required": ["links"]
+    })
+}
+
+/// Structured response to `summarize_code`.
+#[derive(Debug, Deserialize)]
+struct FileSummary {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    created_date: String,
+}
+
+/// JSON schema for `FileSummary`.
+fn file_summary_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "title": {"type": "string"},
+            "summary": {"type": "string"},
+            "keywords": {"type": "array", "items": {"type": "string"}},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "created_date": {"type": "string"}
+        },
+        "required": ["title", "summary", "keywords", "tags", "created_date"]
+    })
 }
 
 /// Metadata generated by LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneratedMetadata {
+    #[serde(default)]
     pub title: String,
+    #[serde(default)]
     pub keywords: Vec<String>,
+    #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
     pub context: String,
 }
 
+/// JSON schema for `GeneratedMetadata`.
+fn generated_metadata_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "title": {"type": "string"},
+            "keywords": {"type": "array", "items": {"type": "string"}},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "context": {"type": "string"}
+        },
+        "required": ["title", "keywords", "tags", "context"]
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::LlmConfig;
 
-    #[test]
-    fn test_extract_json() {
-        let service = LlmService::from_config(&LlmConfig { providers: vec![] });
-
-        // Test JSON in code block
-        let text = r#"Here's the result:
-```json
-{"title": "Test", "value": 42}
-```"#;
-        let json = service.extract_json(text);
-        assert!(json.is_some());
-        assert_eq!(json.unwrap()["title"], "Test");
-
-        // Test raw JSON
-        let text = r#"The result is {"title": "Raw", "count": 5} and more text"#;
-        let json = service.extract_json(text);
-        assert!(json.is_some());
-        assert_eq!(json.unwrap()["title"], "Raw");
-    }
-
     #[test]
     fn test_default_endpoints() {
         assert_eq!(
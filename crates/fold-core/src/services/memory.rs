@@ -14,25 +14,50 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 
-use crate::db::{self, DbPool};
+use crate::db::{self, AccessKind, DbPool, DecayModel, Project, RewriteKind, UsageKind};
 use crate::error::{Error, Result};
 use crate::models::{
     ChunkMatch, Memory, MemoryCreate, MemorySearchResult, MemorySource, MemoryType, MemoryUpdate,
 };
 
+use super::context_packer::{self, ContextCandidate, ContextKind, PackedContext};
 use super::decay::{
-    blend_scores, calculate_strength, DEFAULT_HALF_LIFE_DAYS, DEFAULT_STRENGTH_WEIGHT,
+    project_decay_config, DecayConfig, Scorer, ACCESS_BATCH_SIZE, ACCESS_HISTORY_LIMIT,
 };
+use super::event_broadcaster::EventBroadcaster;
 use super::fold_storage::FoldStorageService;
+use super::query_rewrite::{self, QueryRewrite};
 use super::EmbeddingService;
 use fold_qdrant::{QdrantService, SearchFilter};
-use super::LlmService;
+use super::{ChatRequest, LlmService};
+use super::ProviderChainService;
+
+/// Suffix of the cold Qdrant collection holding a project's archived memories.
+/// Slugs cannot contain underscores, so this never collides with a project.
+const ARCHIVE_COLLECTION_SUFFIX: &str = "_archive";
+
+/// Name of the cold collection for a project (passed where a slug is expected).
+fn archive_collection(project_slug: &str) -> String {
+    format!("{}{}", project_slug, ARCHIVE_COLLECTION_SUFFIX)
+}
+
+/// Suffix of the Qdrant collection keeping the vectors of a project's deleted
+/// memories, so point-in-time search can score them without re-embedding.
+const HISTORY_COLLECTION_SUFFIX: &str = "_history";
+
+/// Name of the history collection for a project (passed where a slug is expected).
+fn history_collection(project_slug: &str) -> String {
+    format!("{}{}", project_slug, HISTORY_COLLECTION_SUFFIX)
+}
+
+/// Search hits per memory type considered when packing task context.
+const CONTEXT_CANDIDATES_PER_TYPE: usize = 10;
 
 /// Safe floor char boundary (stable alternative to str::floor_char_boundary)
 fn floor_char_boundary(s: &str, index: usize) -> usize {
@@ -65,9 +90,23 @@ impl Default for ContentAnalysis {
     }
 }
 
+/// JSON schema for `ContentAnalysis`.
+fn content_analysis_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "keywords": {"type": "array", "items": {"type": "string"}},
+            "context": {"type": "string"},
+            "tags": {"type": "array", "items": {"type": "string"}}
+        },
+        "required": ["keywords", "context", "tags"]
+    })
+}
+
 /// LLM decision about memory evolution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct EvolutionDecision {
+    #[serde(default)]
     should_evolve: bool,
     #[serde(default)]
     actions: Vec<String>,
@@ -94,6 +133,102 @@ impl Default for EvolutionDecision {
     }
 }
 
+/// JSON schema for `EvolutionDecision`.
+fn evolution_decision_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "should_evolve": {"type": "boolean"},
+            "actions": {
+                "type": "array",
+                "items": {"type": "string", "enum": ["strengthen", "update_neighbor"]}
+            },
+            "suggested_connections": {"type": "array", "items": {"type": "string"}},
+            "tags_to_update": {"type": "array", "items": {"type": "string"}},
+            "new_context_neighbourhood": {"type": "array", "items": {"type": "string"}},
+            "new_tags_neighbourhood": {
+                "type": "array",
+                "items": {"type": "array", "items": {"type": "string"}}
+            }
+        },
+        "required": ["should_evolve", "actions", "suggested_connections", "tags_to_update"]
+    })
+}
+
+/// How a new memory relates to an existing decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionRelation {
+    Agree,
+    Contradict,
+    Refine,
+    #[serde(other)]
+    Unrelated,
+}
+
+/// LLM classification of a new memory against one existing decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionCheck {
+    pub memory_id: String,
+    pub relation: DecisionRelation,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+/// Maximum number of existing decisions compared against a new memory.
+const CONFLICT_CHECK_LIMIT: usize = 5;
+
+/// Minimum similarity for a decision to be worth comparing.
+const CONFLICT_MIN_SCORE: f32 = 0.5;
+
+/// JSON schema for the decision conflict response parsed by
+/// `parse_decision_checks`.
+fn decision_checks_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "checks": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "memory_id": {"type": "string"},
+                        "relation": {
+                            "type": "string",
+                            "enum": ["agree", "contradict", "refine", "unrelated"]
+                        },
+                        "reason": {"type": "string", "description": "One sentence"},
+                        "confidence": {"type": "number"}
+                    },
+                    "required": ["memory_id", "relation", "reason", "confidence"]
+                }
+            }
+        },
+        "required": ["checks"]
+    })
+}
+
+/// Parse the LLM's `{"checks": [...]}` response, keeping only entries for
+/// candidates that were actually offered.
+fn parse_decision_checks(json: Value, candidates: &HashSet<String>) -> Vec<DecisionCheck> {
+    let checks = match json.get("checks").cloned().unwrap_or(json) {
+        Value::Array(items) => items,
+        _ => return Vec::new(),
+    };
+
+    checks
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<DecisionCheck>(item).ok())
+        .filter(|c| candidates.contains(&c.memory_id))
+        .map(|c| DecisionCheck {
+            confidence: c.confidence.map(|v| v.clamp(0.0, 1.0)),
+            ..c
+        })
+        .collect()
+}
+
 /// Search result with optional neighbour flag.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgenticSearchResult {
@@ -127,6 +262,10 @@ pub struct MemoryService {
     embeddings: Arc<EmbeddingService>,
     llm: Arc<LlmService>,
     fold_storage: Arc<FoldStorageService>,
+    /// Memory and link lifecycle events (unset in tests and tools)
+    events: Option<Arc<EventBroadcaster>>,
+    /// Per-project provider overrides (unset: always the global chain)
+    provider_chains: Option<ProviderChainService>,
 }
 
 impl MemoryService {
@@ -144,6 +283,41 @@ impl MemoryService {
             embeddings,
             llm,
             fold_storage,
+            events: None,
+            provider_chains: None,
+        }
+    }
+
+    /// Emit memory and link lifecycle events through a broadcaster.
+    pub fn with_events(mut self, events: Arc<EventBroadcaster>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// The broadcaster lifecycle events are emitted through, if any.
+    pub fn events(&self) -> Option<&Arc<EventBroadcaster>> {
+        self.events.as_ref()
+    }
+
+    /// Resolve LLM and embedding providers per project.
+    pub fn with_provider_chains(mut self, provider_chains: ProviderChainService) -> Self {
+        self.provider_chains = Some(provider_chains);
+        self
+    }
+
+    /// The LLM service for a project: its pinned chain, or the global one.
+    pub async fn llm_for(&self, project_id: &str) -> Result<Arc<LlmService>> {
+        match &self.provider_chains {
+            Some(chains) => chains.llm_for(project_id).await,
+            None => Ok(self.llm.clone()),
+        }
+    }
+
+    /// The embedding service for a project: its pinned chain, or the global one.
+    pub async fn embeddings_for(&self, project_id: &str) -> Result<Arc<EmbeddingService>> {
+        match &self.provider_chains {
+            Some(chains) => chains.embeddings_for(project_id).await,
+            None => Ok(self.embeddings.clone()),
         }
     }
 
@@ -157,8 +331,13 @@ impl MemoryService {
     /// - Keywords: Key terms and concepts (nouns, verbs, important terminology)
     /// - Context: One sentence summarising the domain, purpose, and key points
     /// - Tags: Broad categories for classification
-    pub async fn analyse_content(&self, content: &str) -> Result<ContentAnalysis> {
-        if !self.llm.is_available().await {
+    pub async fn analyse_content(
+        &self,
+        project_id: &str,
+        content: &str,
+    ) -> Result<ContentAnalysis> {
+        let llm = self.llm_for(project_id).await?;
+        if !llm.is_available().await {
             debug!("LLM not available, returning empty analysis");
             return Ok(ContentAnalysis::default());
         }
@@ -176,51 +355,16 @@ impl MemoryService {
 3. **Tags**: Broad categories for classification (max 6)
 
 Content:
-{}
-
-Return JSON:
-{{
-    "keywords": ["term1", "term2", ...],
-    "context": "Detailed multi-sentence context paragraph...",
-    "tags": ["category1", "category2", ...]
-}}"#,
+{}"#,
             &content[..floor_char_boundary(&content, content.len().min(4000))]
         );
 
-        match self.llm.complete(&prompt, 800).await {
-            Ok(response) => {
-                // Try to extract JSON from response
-                if let Some(json) = self.extract_json(&response) {
-                    let keywords = json["keywords"]
-                        .as_array()
-                        .map(|arr| {
-                            arr.iter()
-                                .filter_map(|v| v.as_str().map(String::from))
-                                .collect()
-                        })
-                        .unwrap_or_default();
-
-                    let context = json["context"].as_str().unwrap_or("").to_string();
-
-                    let tags = json["tags"]
-                        .as_array()
-                        .map(|arr| {
-                            arr.iter()
-                                .filter_map(|v| v.as_str().map(String::from))
-                                .collect()
-                        })
-                        .unwrap_or_default();
-
-                    Ok(ContentAnalysis {
-                        keywords,
-                        context,
-                        tags,
-                    })
-                } else {
-                    warn!("Failed to parse content analysis response as JSON");
-                    Ok(ContentAnalysis::default())
-                }
-            }
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(800)
+            .with_json_schema("content_analysis", content_analysis_schema());
+
+        match llm.chat_json::<ContentAnalysis>(&request).await {
+            Ok(analysis) => Ok(analysis),
             Err(e) => {
                 warn!(error = %e, "Failed to analyse content");
                 Ok(ContentAnalysis::default())
@@ -232,8 +376,9 @@ Return JSON:
     ///
     /// Returns a concise, descriptive title (max 60 chars) suitable for
     /// use in slugs and file names.
-    pub async fn generate_title(&self, content: &str) -> Result<String> {
-        if !self.llm.is_available().await {
+    pub async fn generate_title(&self, project_id: &str, content: &str) -> Result<String> {
+        let llm = self.llm_for(project_id).await?;
+        if !llm.is_available().await {
             debug!("LLM not available for title generation");
             return Err(Error::Internal("LLM not available".to_string()));
         }
@@ -256,7 +401,7 @@ Return only the title, nothing else."#,
             truncated
         );
 
-        match self.llm.complete(&prompt, 80).await {
+        match llm.complete(&prompt, 80).await {
             Ok(response) => {
                 let title = response.trim().trim_matches('"').trim();
                 // Ensure it's not too long
@@ -274,59 +419,6 @@ Return only the title, nothing else."#,
         }
     }
 
-    /// Extract JSON from LLM response text.
-    fn extract_json(&self, text: &str) -> Option<Value> {
-        // Try to find JSON in code blocks
-        if let Some(start) = text.find("```json") {
-            let start = start + 7;
-            if let Some(end) = text[start..].find("```") {
-                if let Ok(json) = serde_json::from_str(&text[start..start + end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        // Try to find JSON in generic code blocks
-        if let Some(start) = text.find("```") {
-            let start = start + 3;
-            let start = text[start..]
-                .find('\n')
-                .map(|i| start + i + 1)
-                .unwrap_or(start);
-            if let Some(end) = text[start..].find("```") {
-                if let Ok(json) = serde_json::from_str(&text[start..start + end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        // Try to find raw JSON object
-        if let Some(start) = text.find('{') {
-            let mut depth = 0;
-            let mut end = start;
-            for (i, c) in text[start..].char_indices() {
-                match c {
-                    '{' => depth += 1,
-                    '}' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            end = start + i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if end > start {
-                if let Ok(json) = serde_json::from_str(&text[start..end]) {
-                    return Some(json);
-                }
-            }
-        }
-
-        None
-    }
-
     // =========================================================================
     // Memory Evolution (LLM-driven)
     // =========================================================================
@@ -339,7 +431,8 @@ Return only the title, nothing else."#,
         neighbours_text: &str,
         neighbour_count: usize,
     ) -> Result<EvolutionDecision> {
-        if !self.llm.is_available().await || neighbour_count == 0 {
+        let llm = self.llm_for(&memory.project_id).await?;
+        if !llm.is_available().await || neighbour_count == 0 {
             return Ok(EvolutionDecision::default());
         }
 
@@ -363,16 +456,8 @@ Decide:
 2. Actions to take:
    - "strengthen": Link to similar memories, update tags
    - "update_neighbor": Update context/tags of related memories
-
-Return JSON:
-{{
-    "should_evolve": true/false,
-    "actions": ["strengthen", "update_neighbor"],
-    "suggested_connections": ["memory_id_1", "memory_id_2"],
-    "tags_to_update": ["tag1", "tag2"],
-    "new_context_neighbourhood": ["new context for neighbour 1", ...],
-    "new_tags_neighbourhood": [["tag1", "tag2"], ...]
-}}"#,
+3. Which neighbour memory IDs to connect, and which tags to add.
+4. For "update_neighbor", the new context and tags for each neighbour, in order."#,
             memory.title.as_deref().unwrap_or(""),
             &content[..floor_char_boundary(&content, content.len().min(1000))],
             memory.context.as_deref().unwrap_or(""),
@@ -382,17 +467,13 @@ Return JSON:
             neighbour_count,
         );
 
-        match self.llm.complete(&prompt, 800).await {
-            Ok(response) => {
-                if let Some(json) = self.extract_json(&response) {
-                    let decision: EvolutionDecision =
-                        serde_json::from_value(json).unwrap_or_default();
-                    Ok(decision)
-                } else {
-                    warn!("Failed to parse evolution decision response as JSON");
-                    Ok(EvolutionDecision::default())
-                }
-            }
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(800)
+            .with_json_schema("evolution_decision", evolution_decision_schema());
+
+        self.record_usage(&memory.project_id, UsageKind::Llm).await;
+        match llm.chat_json::<EvolutionDecision>(&request).await {
+            Ok(decision) => Ok(decision),
             Err(e) => {
                 warn!(error = %e, "Failed to get evolution decision");
                 Ok(EvolutionDecision::default())
@@ -483,7 +564,8 @@ Return JSON:
                     // Create links to suggested connections
                     for target_id in &decision.suggested_connections {
                         // Create bidirectional link
-                        let _ = sqlx::query(
+                        let link_id = crate::models::new_id();
+                        let inserted = sqlx::query(
                             r#"
                             INSERT OR IGNORE INTO memory_links (
                                 id, project_id, source_id, target_id, link_type,
@@ -491,13 +573,26 @@ Return JSON:
                             ) VALUES (?, ?, ?, ?, 'related', 'evolution', 0.8, 'Auto-linked by memory evolution', datetime('now'))
                             "#,
                         )
-                        .bind(crate::models::new_id())
+                        .bind(&link_id)
                         .bind(project_id)
                         .bind(memory_id)
                         .bind(target_id)
                         .execute(&self.db)
                         .await;
 
+                        if let (Ok(result), Some(events)) = (&inserted, &self.events) {
+                            if result.rows_affected() > 0 {
+                                events.link_created(
+                                    project_id,
+                                    &link_id,
+                                    memory_id,
+                                    target_id,
+                                    "related",
+                                    "evolution",
+                                );
+                            }
+                        }
+
                         created_links.push(target_id.clone());
 
                         debug!(
@@ -579,6 +674,200 @@ Return JSON:
         Ok(())
     }
 
+    // =========================================================================
+    // Contradiction Detection (LLM-driven)
+    // =========================================================================
+
+    /// Compare a new decision or spec against the most similar existing
+    /// decisions and record `contradicts` / `refines` links.
+    ///
+    /// Contradictions are also recorded in `memory_conflicts` so a human can
+    /// resolve them. Returns the open conflicts recorded.
+    async fn check_decision_conflicts(
+        &self,
+        memory: &Memory,
+        project_slug: &str,
+        project_root: &Path,
+        embedding: &[f32],
+        content: &str,
+    ) -> Result<Vec<db::MemoryConflict>> {
+        let llm = self.llm_for(&memory.project_id).await?;
+        if !llm.is_available().await {
+            return Ok(Vec::new());
+        }
+
+        let similar = self
+            .qdrant
+            .search(
+                project_slug,
+                embedding.to_vec(),
+                CONFLICT_CHECK_LIMIT + 1,
+                Some(SearchFilter::new().with_type(MemoryType::Decision.as_str())),
+            )
+            .await?;
+
+        let mut candidates = HashSet::new();
+        let mut candidates_text = String::new();
+
+        for result in similar
+            .iter()
+            .filter(|r| r.id != memory.id && r.score >= CONFLICT_MIN_SCORE)
+            .take(CONFLICT_CHECK_LIMIT)
+        {
+            let Ok(Some(existing)) = self
+                .get_without_tracking(&memory.project_id, &result.id)
+                .await
+            else {
+                continue;
+            };
+
+            let existing_content = match existing.content.clone() {
+                Some(c) => c,
+                None => self
+                    .fold_storage
+                    .read_memory(project_root, &existing.id)
+                    .await
+                    .map(|(_, c)| c)
+                    .unwrap_or_default(),
+            };
+
+            candidates_text.push_str(&format!(
+                "memory_id:{}\ttitle:{}\tcontent:{}\n",
+                existing.id,
+                existing.title.as_deref().unwrap_or(""),
+                &existing_content
+                    [..floor_char_boundary(&existing_content, existing_content.len().min(600))],
+            ));
+            candidates.insert(existing.id);
+        }
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let prompt = format!(
+            r#"You are reviewing a new {} against existing architecture decisions.
+
+New memory:
+- Title: {}
+- Content: {}
+
+Existing decisions:
+{}
+
+For each existing decision, classify the new memory as:
+- "agree": consistent with the decision
+- "contradict": incompatible with the decision (both cannot be true)
+- "refine": narrows, extends or updates the decision without invalidating it
+- "unrelated": different topic
+
+Give one check per existing decision, with a confidence from 0.0 to 1.0."#,
+            memory.memory_type,
+            memory.title.as_deref().unwrap_or(""),
+            &content[..floor_char_boundary(content, content.len().min(1500))],
+            candidates_text,
+        );
+
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(800)
+            .with_json_schema("decision_checks", decision_checks_schema());
+
+        self.record_usage(&memory.project_id, UsageKind::Llm).await;
+        let json = match llm.chat_json::<Value>(&request).await {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(error = %e, "Failed to check decision conflicts");
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut conflicts = Vec::new();
+        let mut any_linked = false;
+        for check in parse_decision_checks(json, &candidates) {
+            let link_type = match check.relation {
+                DecisionRelation::Contradict => db::LinkType::Contradicts,
+                DecisionRelation::Refine => db::LinkType::Refines,
+                DecisionRelation::Agree | DecisionRelation::Unrelated => continue,
+            };
+
+            let created = db::create_link(
+                &self.db,
+                db::CreateLink {
+                    id: crate::models::new_id(),
+                    project_id: memory.project_id.clone(),
+                    source_id: memory.id.clone(),
+                    target_id: check.memory_id.clone(),
+                    link_type,
+                    created_by: db::LinkCreator::Ai,
+                    confidence: check.confidence,
+                    context: check.reason.clone(),
+                    change_type: None,
+                    additions: None,
+                    deletions: None,
+                },
+            )
+            .await;
+
+            match created {
+                Ok(link) => {
+                    if let Some(events) = &self.events {
+                        events.link_created(
+                            &link.project_id,
+                            &link.id,
+                            &link.source_id,
+                            &link.target_id,
+                            &link.link_type,
+                            &link.created_by,
+                        );
+                    }
+                    any_linked = true;
+                }
+                Err(Error::AlreadyExists(_)) => {}
+                Err(e) => return Err(e),
+            }
+
+            if check.relation == DecisionRelation::Contradict {
+                let conflict = db::create_memory_conflict(
+                    &self.db,
+                    db::CreateMemoryConflict {
+                        id: crate::models::new_id(),
+                        project_id: memory.project_id.clone(),
+                        memory_id: memory.id.clone(),
+                        conflicting_memory_id: check.memory_id.clone(),
+                        reason: check.reason.clone(),
+                        confidence: check.confidence,
+                    },
+                )
+                .await?;
+
+                info!(
+                    memory_id = %memory.id,
+                    conflicts_with = %check.memory_id,
+                    "New memory contradicts an existing decision"
+                );
+                conflicts.extend(conflict);
+            }
+        }
+
+        // The fold/ Related section lists every outgoing link
+        if any_linked {
+            let related: Vec<String> = db::list_outgoing_links(&self.db, &memory.id)
+                .await?
+                .into_iter()
+                .map(|l| l.target_id)
+                .collect();
+            if let Err(e) = self
+                .fold_storage
+                .update_memory_links(project_root, &memory.id, &related)
+                .await
+            {
+                warn!(error = %e, memory_id = %memory.id, "Failed to update fold file with links");
+            }
+        }
+
+        Ok(conflicts)
+    }
+
     // =========================================================================
     // Build Embedding Text
     // =========================================================================
@@ -608,6 +897,39 @@ Return JSON:
         parts.join("\n")
     }
 
+    /// Build the Qdrant payload stored alongside a memory's vector.
+    fn vector_payload(&self, memory: &Memory) -> HashMap<String, Value> {
+        let mut payload: HashMap<String, Value> = HashMap::new();
+        payload.insert("memory_id".to_string(), json!(memory.id));
+        payload.insert("project_id".to_string(), json!(memory.project_id));
+        payload.insert("type".to_string(), json!(memory.memory_type));
+        if let Some(ref t) = memory.title {
+            payload.insert("title".to_string(), json!(t));
+        }
+        if let Some(ref a) = memory.author {
+            payload.insert("author".to_string(), json!(a));
+        }
+        if let Some(ref fp) = memory.file_path {
+            payload.insert("file_path".to_string(), json!(fp));
+        }
+        payload.insert(
+            "created_at".to_string(),
+            json!(memory.created_at.to_rfc3339()),
+        );
+        payload
+    }
+
+    /// Count one provider call against the project's daily quota.
+    async fn record_usage(&self, project_id: &str, kind: UsageKind) {
+        super::rate_limit::record_usage(&self.db, project_id, kind, 1).await;
+    }
+
+    /// Fail if the project has used up today's quota for a kind of provider
+    /// call. For calls no request check covers.
+    async fn check_quota(&self, project_id: &str, kind: UsageKind) -> Result<()> {
+        super::rate_limit::check_quota(&self.db, project_id, kind).await
+    }
+
     // =========================================================================
     // CRUD Operations
     // =========================================================================
@@ -624,14 +946,33 @@ Return JSON:
         data: MemoryCreate,
         auto_metadata: bool,
     ) -> Result<Memory> {
+        self.add_with_conflicts(project_id, project_slug, data, auto_metadata)
+            .await
+            .map(|(memory, _)| memory)
+    }
+
+    /// Add a memory like [`Self::add`], also returning the open conflicts
+    /// found between a new decision or spec and existing decisions.
+    pub async fn add_with_conflicts(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        data: MemoryCreate,
+        auto_metadata: bool,
+    ) -> Result<(Memory, Vec<db::MemoryConflict>)> {
         // Get project to find root path
         let project = crate::db::get_project(&self.db, project_id).await?;
         let project_root = std::path::PathBuf::from(&project.root_path);
+        let llm = self.llm_for(project_id).await?;
+        let embeddings = self.embeddings_for(project_id).await?;
 
         // Auto-analyse if metadata not provided
         let (keywords, context, tags) =
             if auto_metadata && (data.keywords.is_empty() || data.tags.is_empty()) {
-                let analysis = self.analyse_content(&data.content).await?;
+                let analysis = self.analyse_content(project_id, &data.content).await?;
+                if llm.is_available().await {
+                    self.record_usage(project_id, UsageKind::Llm).await;
+                }
                 (
                     if data.keywords.is_empty() {
                         analysis.keywords
@@ -678,7 +1019,10 @@ Return JSON:
             data.title.clone()
         } else if is_agent_memory && auto_metadata {
             // Generate a title using LLM
-            let generated = self.generate_title(&data.content).await.ok();
+            let generated = self.generate_title(project_id, &data.content).await.ok();
+            if llm.is_available().await {
+                self.record_usage(project_id, UsageKind::Llm).await;
+            }
             generated.or_else(|| {
                 // Fallback: use first line or truncated content
                 let first_line = data.content.lines().next().unwrap_or("Untitled");
@@ -750,6 +1094,20 @@ Return JSON:
             last_accessed: None,
         };
 
+        // Adding under an existing ID replaces that memory, so keep the
+        // version being replaced for point-in-time queries
+        if let Some(existing) = self.get_without_tracking(project_id, &memory.id).await? {
+            if existing.content_hash != memory.content_hash
+                || existing.title != memory.title
+                || existing.keywords != memory.keywords
+                || existing.tags != memory.tags
+                || existing.context != memory.context
+            {
+                let current_content = self.current_content(&project_root, &existing).await;
+                db::record_memory_revision(&self.db, &existing, Some(&current_content)).await?;
+            }
+        }
+
         // Only write to fold/ directory for agent memories
         if is_agent_memory {
             // Ensure fold directory is initialised
@@ -775,31 +1133,16 @@ Return JSON:
 
         // Generate embedding
         let embed_text = self.build_embedding_text(&memory, &data.content);
-        let embedding = self.embeddings.embed_single(&embed_text).await?;
+        let embedding = embeddings.embed_single(&embed_text).await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
 
         // Ensure Qdrant collection exists
         self.qdrant
-            .create_collection(project_slug, self.embeddings.dimension().await)
+            .create_collection(project_slug, embeddings.dimension().await)
             .await?;
 
         // Build Qdrant payload
-        let mut payload: HashMap<String, Value> = HashMap::new();
-        payload.insert("memory_id".to_string(), json!(memory.id));
-        payload.insert("project_id".to_string(), json!(memory.project_id));
-        payload.insert("type".to_string(), json!(memory.memory_type));
-        if let Some(ref t) = memory.title {
-            payload.insert("title".to_string(), json!(t));
-        }
-        if let Some(ref a) = memory.author {
-            payload.insert("author".to_string(), json!(a));
-        }
-        if let Some(ref fp) = memory.file_path {
-            payload.insert("file_path".to_string(), json!(fp));
-        }
-        payload.insert(
-            "created_at".to_string(),
-            json!(memory.created_at.to_rfc3339()),
-        );
+        let payload = self.vector_payload(&memory);
 
         // Store in Qdrant
         self.qdrant
@@ -820,13 +1163,38 @@ Return JSON:
             .await?;
         }
 
+        // New decisions and specs are checked against existing decisions.
+        // Failures here never block the write.
+        let mut conflicts = Vec::new();
+        if is_agent_memory && matches!(data.memory_type, MemoryType::Decision | MemoryType::Spec) {
+            match self
+                .check_decision_conflicts(
+                    &memory,
+                    project_slug,
+                    &project_root,
+                    &embedding,
+                    &data.content,
+                )
+                .await
+            {
+                Ok(found) => conflicts = found,
+                Err(e) => {
+                    warn!(error = %e, memory_id = %memory.id, "Decision conflict check failed")
+                }
+            }
+        }
+
         let source_str = if is_agent_memory { "agent" } else { "indexed" };
         info!(id = %memory.id, memory_type = %memory.memory_type, source = %source_str, "Added memory");
 
+        if let Some(events) = &self.events {
+            events.memory_created(&memory);
+        }
+
         // Return memory with content populated
         let mut result = memory;
         result.content = Some(data.content);
-        Ok(result)
+        Ok((result, conflicts))
     }
 
     /// Get a memory by ID with content resolved based on source.
@@ -863,6 +1231,8 @@ Return JSON:
         .bind(memory_id)
         .execute(&self.db)
         .await;
+        // Direct reads feed the power-law model's retrieval history
+        let _ = db::record_memory_access(&self.db, project_id, memory_id, AccessKind::Read).await;
 
         // Resolve content based on source
         // - File/Git memories: content already in SQLite (summary from LLM)
@@ -880,11 +1250,21 @@ Return JSON:
             if let Ok(project) = crate::db::get_project(&self.db, project_id).await {
                 let project_root = std::path::PathBuf::from(&project.root_path);
 
-                match self
+                let read = match self
                     .fold_storage
                     .read_memory(&project_root, memory_id)
                     .await
                 {
+                    Ok(found) => Ok(found),
+                    // Archived memories live under fold/archive/
+                    Err(e) => self
+                        .fold_storage
+                        .read_archived_memory(&project_root, memory_id)
+                        .await
+                        .map_err(|_| e),
+                };
+
+                match read {
                     Ok((_, content)) => {
                         memory.content = Some(content);
                     }
@@ -905,7 +1285,7 @@ Return JSON:
     }
 
     /// Get a memory without updating access tracking (for internal use).
-    async fn get_without_tracking(
+    pub(crate) async fn get_without_tracking(
         &self,
         project_id: &str,
         memory_id: &str,
@@ -1006,6 +1386,21 @@ Return JSON:
         Ok(memories)
     }
 
+    /// Current content of a memory, read from fold/ for agent memories and
+    /// from SQLite for file and git memories.
+    async fn current_content(&self, project_root: &Path, memory: &Memory) -> String {
+        let source = memory.source.as_deref().unwrap_or("agent");
+        if matches!(source, "file" | "git") {
+            memory.content.clone().unwrap_or_default()
+        } else {
+            self.fold_storage
+                .read_memory(project_root, &memory.id)
+                .await
+                .map(|(_, c)| c)
+                .unwrap_or_default()
+        }
+    }
+
     /// Update a memory.
     ///
     /// Content handling depends on source:
@@ -1024,6 +1419,13 @@ Return JSON:
             .await?
             .ok_or_else(|| Error::NotFound(format!("Memory {}", memory_id)))?;
 
+        if db::get_archived_memory(&self.db, memory_id).await?.is_some() {
+            return Err(Error::Validation(format!(
+                "Memory {} is archived; restore it before editing",
+                memory_id
+            )));
+        }
+
         let project = crate::db::get_project(&self.db, project_id).await?;
         let project_root = std::path::PathBuf::from(&project.root_path);
 
@@ -1031,18 +1433,10 @@ Return JSON:
         let source = existing.source.as_deref().unwrap_or("agent");
         let is_agent_memory = !matches!(source, "file" | "git");
 
-        // Get current content based on source
-        let current_content = if is_agent_memory {
-            // Agent memories: read from fold/
-            self.fold_storage
-                .read_memory(&project_root, memory_id)
-                .await
-                .map(|(_, c)| c)
-                .unwrap_or_default()
-        } else {
-            // File/Git memories: content already in existing.content from SQLite
-            existing.content.clone().unwrap_or_default()
-        };
+        let current_content = self.current_content(&project_root, &existing).await;
+
+        // Keep the version being replaced for point-in-time queries
+        db::record_memory_revision(&self.db, &existing, Some(&current_content)).await?;
 
         // Determine new content
         let new_content = update.content.clone().unwrap_or(current_content);
@@ -1072,6 +1466,11 @@ Return JSON:
         } else {
             Some(new_content.clone())
         };
+        let content_hash = {
+            let mut hasher = Sha256::new();
+            hasher.update(new_content.as_bytes());
+            hex::encode(hasher.finalize())
+        };
 
         // Update metadata (and content for file/git) in SQLite
         sqlx::query(
@@ -1085,6 +1484,7 @@ Return JSON:
                 assignee = ?,
                 metadata = ?,
                 content = ?,
+                content_hash = ?,
                 updated_at = ?
             WHERE id = ? AND project_id = ?
             "#,
@@ -1097,6 +1497,7 @@ Return JSON:
         .bind(&assignee)
         .bind(&metadata)
         .bind(&content_for_db)
+        .bind(&content_hash)
         .bind(now)
         .bind(memory_id)
         .bind(project_id)
@@ -1106,6 +1507,7 @@ Return JSON:
         // Build updated memory struct
         let updated = Memory {
             content: Some(new_content.clone()),
+            content_hash: Some(content_hash),
             title: title.clone(),
             keywords: keywords.clone(),
             tags: tags.clone(),
@@ -1126,25 +1528,14 @@ Return JSON:
 
         // Re-embed with new content
         let embed_text = self.build_embedding_text(&updated, &new_content);
-        let embedding = self.embeddings.embed_single(&embed_text).await?;
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single(&embed_text)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
 
-        let mut payload: HashMap<String, Value> = HashMap::new();
-        payload.insert("memory_id".to_string(), json!(updated.id));
-        payload.insert("project_id".to_string(), json!(updated.project_id));
-        payload.insert("type".to_string(), json!(updated.memory_type));
-        if let Some(ref t) = updated.title {
-            payload.insert("title".to_string(), json!(t));
-        }
-        if let Some(ref a) = updated.author {
-            payload.insert("author".to_string(), json!(a));
-        }
-        if let Some(ref fp) = updated.file_path {
-            payload.insert("file_path".to_string(), json!(fp));
-        }
-        payload.insert(
-            "created_at".to_string(),
-            json!(updated.created_at.to_rfc3339()),
-        );
+        let payload = self.vector_payload(&updated);
 
         self.qdrant
             .upsert(project_slug, &updated.id, embedding, payload)
@@ -1152,6 +1543,10 @@ Return JSON:
 
         debug!(id = %memory_id, "Updated memory");
 
+        if let Some(events) = &self.events {
+            events.memory_updated(&updated);
+        }
+
         Ok(updated)
     }
 
@@ -1164,6 +1559,22 @@ Return JSON:
     ) -> Result<()> {
         let project = crate::db::get_project(&self.db, project_id).await?;
         let project_root = std::path::PathBuf::from(&project.root_path);
+        let archived = db::get_archived_memory(&self.db, memory_id)
+            .await?
+            .is_some();
+
+        // Keep a tombstone and the vector so point-in-time queries still see
+        // the memory
+        if let Some(existing) = self.get_without_tracking(project_id, memory_id).await? {
+            let content = self.current_content(&project_root, &existing).await;
+            db::record_memory_tombstone(&self.db, &existing, Some(&content)).await?;
+            if let Err(e) = self
+                .keep_deleted_vector(project_slug, &existing, archived)
+                .await
+            {
+                warn!(error = %e, memory_id = %memory_id, "Failed to keep embedding of deleted memory");
+            }
+        }
 
         // Delete from SQLite
         let result = sqlx::query(
@@ -1181,8 +1592,13 @@ Return JSON:
             return Err(Error::NotFound(format!("Memory {}", memory_id)));
         }
 
-        // Delete from Qdrant
+        // Delete from Qdrant (and the cold collection if it was archived)
         self.qdrant.delete(project_slug, memory_id).await?;
+        if archived {
+            self.qdrant
+                .delete(&archive_collection(project_slug), memory_id)
+                .await?;
+        }
 
         // Delete from fold/
         if let Err(e) = self
@@ -1207,6 +1623,412 @@ Return JSON:
 
         debug!(id = %memory_id, "Deleted memory");
 
+        if let Some(events) = &self.events {
+            events.memory_deleted(project_id, memory_id);
+        }
+
+        Ok(())
+    }
+
+    /// Copy a memory's stored vector into the project's history collection
+    /// before it is deleted. Nothing is kept when no vector of the current
+    /// provider's dimension is stored.
+    pub(crate) async fn keep_deleted_vector(
+        &self,
+        project_slug: &str,
+        memory: &Memory,
+        archived: bool,
+    ) -> Result<()> {
+        let ids = [memory.id.clone()];
+        let embeddings = self.embeddings_for(&memory.project_id).await?;
+        let dimension = embeddings.dimension().await;
+        let Some(vector) = self
+            .stored_vectors(project_slug, &ids, archived)
+            .await?
+            .remove(&memory.id)
+            .filter(|v| v.len() == dimension)
+        else {
+            return Ok(());
+        };
+
+        let history = history_collection(project_slug);
+        self.qdrant.create_collection(&history, dimension).await?;
+        self.qdrant
+            .upsert(&history, &memory.id, vector, self.vector_payload(memory))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fold memories into a merge's survivor without deleting them.
+    ///
+    /// The survivor is rewritten with the merged text, links on the merged
+    /// memories move to it and the merge is marked applied in one
+    /// transaction. Only then are the survivor's fold/ file and vector
+    /// rewritten, and the merged memories dropped from the vector index so
+    /// they stop appearing in search. Their SQLite rows and fold/ files are
+    /// kept as history.
+    pub async fn merge_into(
+        &self,
+        project_slug: &str,
+        merge: &db::MemoryMerge,
+        merged_ids: &[String],
+    ) -> Result<db::MemoryMerge> {
+        let project_id = merge.project_id.as_str();
+        let existing = self
+            .get_without_tracking(project_id, &merge.survivor_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Memory {}", merge.survivor_id)))?;
+        if db::get_archived_memory(&self.db, &existing.id)
+            .await?
+            .is_some()
+        {
+            return Err(Error::Validation(format!(
+                "Memory {} is archived; restore it before merging into it",
+                existing.id
+            )));
+        }
+
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+        let is_agent_memory = !matches!(existing.source.as_deref(), Some("file" | "git"));
+        let current_content = self.current_content(&project_root, &existing).await;
+
+        let content_hash = {
+            let mut hasher = Sha256::new();
+            hasher.update(merge.content.as_bytes());
+            hex::encode(hasher.finalize())
+        };
+        let survivor = db::MergedSurvivor {
+            previous: &existing,
+            previous_content: &current_content,
+            title: merge.title.clone().or(existing.title.clone()),
+            content: (!is_agent_memory).then(|| merge.content.clone()),
+            content_hash,
+            updated_at: Utc::now(),
+        };
+        let applied = db::apply_memory_merge(&self.db, merge, merged_ids, &survivor).await?;
+
+        let updated = Memory {
+            content: Some(merge.content.clone()),
+            content_hash: Some(survivor.content_hash),
+            title: survivor.title,
+            updated_at: survivor.updated_at,
+            ..existing.clone()
+        };
+        if is_agent_memory {
+            self.fold_storage
+                .write_memory(&project_root, &updated, &merge.content)
+                .await?;
+        }
+
+        let embed_text = self.build_embedding_text(&updated, &merge.content);
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single(&embed_text)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
+        self.qdrant
+            .upsert(
+                project_slug,
+                &updated.id,
+                embedding,
+                self.vector_payload(&updated),
+            )
+            .await?;
+
+        if let Some(events) = &self.events {
+            events.memory_updated(&updated);
+        }
+
+        let to_remove: Vec<String> = merged_ids
+            .iter()
+            .filter(|id| **id != merge.survivor_id)
+            .cloned()
+            .collect();
+        if !to_remove.is_empty() {
+            self.qdrant.delete_batch(project_slug, to_remove).await?;
+        }
+
+        debug!(survivor = %merge.survivor_id, merged = merged_ids.len(), "Merged memories");
+
+        Ok(applied)
+    }
+
+    // =========================================================================
+    // Archive (cold storage)
+    // =========================================================================
+
+    /// Move a memory to cold storage.
+    ///
+    /// The vector moves to the project's archive collection and the fold/ file
+    /// moves to fold/archive/. The SQLite row stays, so the memory can still be
+    /// fetched by ID and restored later.
+    pub async fn archive(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        memory_id: &str,
+        archived: db::CreateArchivedMemory,
+    ) -> Result<db::ArchivedMemory> {
+        if db::get_archived_memory(&self.db, memory_id).await?.is_some() {
+            return Err(Error::Validation(format!(
+                "Memory {} is already archived",
+                memory_id
+            )));
+        }
+
+        let memory = self
+            .get_without_tracking(project_id, memory_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Memory {}", memory_id)))?;
+
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+
+        let embeddings = self.embeddings_for(project_id).await?;
+        let embedding = self
+            .moved_vector(&embeddings, project_slug, &project_root, &memory, false)
+            .await?;
+
+        let cold = archive_collection(project_slug);
+        self.qdrant
+            .create_collection(&cold, embeddings.dimension().await)
+            .await?;
+        self.qdrant
+            .upsert(&cold, memory_id, embedding, self.vector_payload(&memory))
+            .await?;
+        self.qdrant.delete(project_slug, memory_id).await?;
+
+        if let Err(e) = self
+            .fold_storage
+            .archive_memory(&project_root, memory_id)
+            .await
+        {
+            warn!(error = %e, memory_id = %memory_id, "Failed to move memory file to fold/archive/");
+        }
+
+        let record = db::create_archived_memory(&self.db, archived).await?;
+
+        debug!(id = %memory_id, "Archived memory");
+
+        Ok(record)
+    }
+
+    /// Restore an archived memory to the live index and fold/ tree.
+    pub async fn restore(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        memory_id: &str,
+    ) -> Result<Memory> {
+        let record = db::get_archived_memory(&self.db, memory_id)
+            .await?
+            .filter(|r| r.project_id == project_id)
+            .ok_or_else(|| Error::NotFound(format!("Archived memory {}", memory_id)))?;
+
+        let memory = self
+            .get_without_tracking(project_id, &record.memory_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Memory {}", memory_id)))?;
+
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+
+        if let Err(e) = self
+            .fold_storage
+            .restore_memory(&project_root, memory_id)
+            .await
+        {
+            warn!(error = %e, memory_id = %memory_id, "Failed to move memory file out of fold/archive/");
+        }
+
+        let embeddings = self.embeddings_for(project_id).await?;
+        let embedding = self
+            .moved_vector(&embeddings, project_slug, &project_root, &memory, true)
+            .await?;
+
+        self.qdrant
+            .create_collection(project_slug, embeddings.dimension().await)
+            .await?;
+        self.qdrant
+            .upsert(project_slug, memory_id, embedding, self.vector_payload(&memory))
+            .await?;
+        self.qdrant
+            .delete(&archive_collection(project_slug), memory_id)
+            .await?;
+
+        db::delete_archived_memory(&self.db, memory_id).await?;
+
+        debug!(id = %memory_id, "Restored memory");
+
+        Ok(memory)
+    }
+
+    /// The stored vector of a memory moving in or out of cold storage, read
+    /// from the collection it is leaving. Re-embeds when no vector is stored
+    /// there, or when it was made by a provider of another dimension.
+    async fn moved_vector(
+        &self,
+        embeddings: &EmbeddingService,
+        project_slug: &str,
+        project_root: &std::path::Path,
+        memory: &Memory,
+        archived: bool,
+    ) -> Result<Vec<f32>> {
+        let ids = [memory.id.clone()];
+        let dimension = embeddings.dimension().await;
+        if let Some(vector) = self
+            .stored_vectors(project_slug, &ids, archived)
+            .await?
+            .remove(&memory.id)
+            .filter(|v| v.len() == dimension)
+        {
+            return Ok(vector);
+        }
+
+        let content = match memory.content.clone().filter(|c| !c.is_empty()) {
+            Some(c) => c,
+            None => self
+                .fold_storage
+                .read_memory(project_root, &memory.id)
+                .await
+                .map(|(_, content)| content)
+                .unwrap_or_default(),
+        };
+
+        let embed_text = self.build_embedding_text(memory, &content);
+        self.check_quota(&memory.project_id, UsageKind::Embedding)
+            .await?;
+        let embedding = embeddings.embed_single(&embed_text).await?;
+        self.record_usage(&memory.project_id, UsageKind::Embedding).await;
+        Ok(embedding)
+    }
+
+    /// Search a project's archived memories.
+    ///
+    /// Scored like [`Self::search_with_type`] but does not count as an access,
+    /// so searching the archive does not revive memories.
+    pub async fn search_archived(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        query: &str,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+    ) -> Result<Vec<MemorySearchResult>> {
+        if db::count_archived_memories(&self.db, project_id).await? == 0 {
+            return Ok(Vec::new());
+        }
+
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single_for_search(query)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
+        let filter = memory_type.map(|mt| SearchFilter::new().with_type(mt.as_str()));
+        let vector_results = self
+            .qdrant
+            .search(&archive_collection(project_slug), embedding, limit, filter)
+            .await?;
+
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+
+        let ids: Vec<String> = vector_results.iter().map(|vr| vr.id.clone()).collect();
+        let scorer = self.scorer(&project, &ids, None).await?;
+
+        let mut results = Vec::with_capacity(vector_results.len());
+        for vr in vector_results {
+            let mut memory = match self.get_without_tracking(project_id, &vr.id).await? {
+                Some(m) => m,
+                None => continue,
+            };
+
+            if memory.content.is_none() || memory.content.as_ref().is_some_and(|c| c.is_empty()) {
+                if let Ok((_, content)) = self
+                    .fold_storage
+                    .read_archived_memory(&project_root, &vr.id)
+                    .await
+                {
+                    memory.content = Some(content);
+                }
+            }
+
+            results.push(scorer.result(memory, vr.score, Vec::new()));
+        }
+
+        results.sort_by(|a, b| {
+            b.combined_score
+                .partial_cmp(&a.combined_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    // =========================================================================
+    // Project Archives (export/import)
+    // =========================================================================
+
+    /// Read the stored vectors for memories or chunks.
+    /// Looks in the cold collection when `archived` is set.
+    pub async fn stored_vectors(
+        &self,
+        project_slug: &str,
+        ids: &[String],
+        archived: bool,
+    ) -> Result<HashMap<String, Vec<f32>>> {
+        let collection = if archived {
+            archive_collection(project_slug)
+        } else {
+            project_slug.to_string()
+        };
+        Ok(self.qdrant.get_vectors(&collection, ids).await?)
+    }
+
+    /// Insert and index a memory brought in from a project archive.
+    ///
+    /// Agent memory files must already be written to fold/. The supplied
+    /// vector is stored as-is; without one the memory is re-embedded.
+    /// Archived memories go to the cold collection.
+    pub async fn import_memory(
+        &self,
+        project_slug: &str,
+        memory: &Memory,
+        content: &str,
+        vector: Option<Vec<f32>>,
+        archived: bool,
+    ) -> Result<()> {
+        self.insert_memory(memory).await?;
+
+        let embeddings = self.embeddings_for(&memory.project_id).await?;
+        let vector = match vector {
+            Some(vector) => vector,
+            None => {
+                let embed_text = self.build_embedding_text(memory, content);
+                let embedding = embeddings.embed_single(&embed_text).await?;
+                self.record_usage(&memory.project_id, UsageKind::Embedding)
+                    .await;
+                embedding
+            }
+        };
+
+        let collection = if archived {
+            archive_collection(project_slug)
+        } else {
+            project_slug.to_string()
+        };
+        self.qdrant
+            .create_collection(&collection, embeddings.dimension().await)
+            .await?;
+        self.qdrant
+            .upsert(&collection, &memory.id, vector, self.vector_payload(memory))
+            .await?;
+
         Ok(())
     }
 
@@ -1240,9 +2062,34 @@ Return JSON:
         memory_type: Option<MemoryType>,
         limit: usize,
     ) -> Result<Vec<MemorySearchResult>> {
-        // Generate query embedding using search-priority providers
-        let embedding = self.embeddings.embed_single_for_search(query).await?;
+        let embedding = self.embed_query(project_id, query).await?;
+        let results = self
+            .search_embedding(
+                project_id,
+                project_slug,
+                embedding,
+                memory_type,
+                limit,
+                None,
+            )
+            .await?;
+
+        // Update access tracking for returned results
+        self.track_search_access(&results).await;
+
+        Ok(results)
+    }
 
+    /// Decay-weighted memory search for an embedded query.
+    async fn search_embedding(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        embedding: Vec<f32>,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+        decay: Option<&DecayConfig>,
+    ) -> Result<Vec<MemorySearchResult>> {
         // Build filter
         let filter = memory_type.map(|mt| SearchFilter::new().with_type(mt.as_str()));
 
@@ -1256,13 +2103,9 @@ Return JSON:
         let project = crate::db::get_project(&self.db, project_id).await?;
         let project_root = std::path::PathBuf::from(&project.root_path);
 
-        // Get decay config from project settings (or use defaults)
-        let half_life = project
-            .decay_half_life_days
-            .unwrap_or(DEFAULT_HALF_LIFE_DAYS);
-        let strength_weight = project
-            .decay_strength_weight
-            .unwrap_or(DEFAULT_STRENGTH_WEIGHT);
+        // Ranking strength comes from explicit feedback, not impressions
+        let ids: Vec<String> = vector_results.iter().map(|vr| vr.id.clone()).collect();
+        let scorer = self.scorer(&project, &ids, decay).await?;
 
         let mut results = Vec::with_capacity(vector_results.len());
         for vr in vector_results {
@@ -1290,23 +2133,8 @@ Return JSON:
                 }
             }
 
-            // Calculate decay-adjusted strength
-            let strength = calculate_strength(
-                memory.updated_at,
-                memory.last_accessed,
-                memory.retrieval_count,
-                half_life,
-            );
-
-            // Blend semantic relevance with retrieval strength
-            let combined_score = blend_scores(vr.score as f64, strength, strength_weight);
-
-            results.push(MemorySearchResult::with_decay(
-                memory,
-                vr.score,
-                strength as f32,
-                combined_score as f32,
-            ));
+            // Blend semantic relevance with decay-adjusted strength
+            results.push(scorer.result(memory, vr.score, Vec::new()));
         }
 
         // Re-rank by combined score (decay-weighted)
@@ -1319,9 +2147,6 @@ Return JSON:
         // Truncate to requested limit
         results.truncate(limit);
 
-        // Update access tracking for returned results
-        self.track_search_access(&results).await;
-
         Ok(results)
     }
 
@@ -1339,9 +2164,34 @@ Return JSON:
         memory_type: Option<MemoryType>,
         limit: usize,
     ) -> Result<Vec<MemorySearchResult>> {
-        // Generate query embedding using search-priority providers
-        let embedding = self.embeddings.embed_single_for_search(query).await?;
+        let embedding = self.embed_query(project_id, query).await?;
+        let results = self
+            .search_chunks_embedding(
+                project_id,
+                project_slug,
+                embedding,
+                memory_type,
+                limit,
+                None,
+            )
+            .await?;
+
+        // Update access tracking
+        self.track_search_access(&results).await;
+
+        Ok(results)
+    }
 
+    /// Memory and chunk search for an embedded query.
+    async fn search_chunks_embedding(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        embedding: Vec<f32>,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+        decay: Option<&DecayConfig>,
+    ) -> Result<Vec<MemorySearchResult>> {
         // Build filter for memories
         let memory_filter = memory_type.map(|mt| SearchFilter::new().with_type(mt.as_str()));
 
@@ -1362,14 +2212,6 @@ Return JSON:
         let project = crate::db::get_project(&self.db, project_id).await?;
         let project_root = std::path::PathBuf::from(&project.root_path);
 
-        // Get decay config
-        let half_life = project
-            .decay_half_life_days
-            .unwrap_or(DEFAULT_HALF_LIFE_DAYS);
-        let strength_weight = project
-            .decay_strength_weight
-            .unwrap_or(DEFAULT_STRENGTH_WEIGHT);
-
         // Collect matched chunks by parent_memory_id
         let mut chunks_by_memory: HashMap<String, Vec<ChunkMatch>> = HashMap::new();
 
@@ -1420,6 +2262,14 @@ Return JSON:
             });
         }
 
+        // Ranking strength comes from explicit feedback, not impressions
+        let ids: Vec<String> = memory_results
+            .iter()
+            .map(|vr| vr.id.clone())
+            .chain(chunks_by_memory.keys().cloned())
+            .collect();
+        let scorer = self.scorer(&project, &ids, decay).await?;
+
         // Build results - start with direct memory matches
         let mut results_map: HashMap<String, MemorySearchResult> = HashMap::new();
 
@@ -1437,33 +2287,28 @@ Return JSON:
                 }
             }
 
-            let strength = calculate_strength(
-                memory.updated_at,
-                memory.last_accessed,
-                memory.retrieval_count,
-                half_life,
-            );
-
-            let combined_score = blend_scores(vr.score as f64, strength, strength_weight);
-
             // Attach any matched chunks
             let matched_chunks = chunks_by_memory.remove(&vr.id).unwrap_or_default();
 
             results_map.insert(
                 vr.id.clone(),
-                MemorySearchResult::with_chunks(
-                    memory,
-                    vr.score,
-                    strength as f32,
-                    combined_score as f32,
-                    matched_chunks,
-                ),
+                scorer.result(memory, vr.score, matched_chunks),
             );
         }
 
+        // Chunk vectors stay in the live collection when their memory is archived
+        let archived: HashSet<String> = if chunks_by_memory.is_empty() {
+            HashSet::new()
+        } else {
+            db::list_archived_memory_ids(&self.db, project_id)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
         // Add memories found via chunk matches that weren't in direct results
         for (memory_id, matched_chunks) in chunks_by_memory {
-            if results_map.contains_key(&memory_id) {
+            if results_map.contains_key(&memory_id) || archived.contains(&memory_id) {
                 continue;
             }
 
@@ -1483,27 +2328,8 @@ Return JSON:
                 }
             }
 
-            let strength = calculate_strength(
-                memory.updated_at,
-                memory.last_accessed,
-                memory.retrieval_count,
-                half_life,
-            );
-
             // Use best chunk score as the memory's relevance score
-            let best_chunk_score = matched_chunks.first().map(|c| c.score).unwrap_or(0.0);
-            let combined_score = blend_scores(best_chunk_score as f64, strength, strength_weight);
-
-            results_map.insert(
-                memory_id,
-                MemorySearchResult::with_chunks(
-                    memory,
-                    best_chunk_score,
-                    strength as f32,
-                    combined_score as f32,
-                    matched_chunks,
-                ),
-            );
+            results_map.insert(memory_id, scorer.chunk_result(memory, matched_chunks));
         }
 
         // Convert to vec and sort by combined score
@@ -1517,12 +2343,179 @@ Return JSON:
         // Truncate to limit
         results.truncate(limit);
 
-        // Update access tracking
+        Ok(results)
+    }
+
+    /// Search with LLM query rewriting.
+    ///
+    /// The query and each rewrite (paraphrases, a HyDE passage) are searched
+    /// separately and the results fused. If the LLM is unavailable the
+    /// search runs on the original query alone.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_rewritten(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        query: &str,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+        include_chunks: bool,
+        rewrite: QueryRewrite,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let variants = self.query_variants(project_id, query, rewrite).await;
+
+        let searches = variants.iter().map(|variant| async move {
+            let embedding = self.embed_query(project_id, variant).await?;
+            if include_chunks {
+                self.search_chunks_embedding(
+                    project_id,
+                    project_slug,
+                    embedding,
+                    memory_type,
+                    limit,
+                    None,
+                )
+                .await
+            } else {
+                self.search_embedding(
+                    project_id,
+                    project_slug,
+                    embedding,
+                    memory_type,
+                    limit,
+                    None,
+                )
+                .await
+            }
+        });
+        let lists = futures::future::try_join_all(searches).await?;
+
+        let results = query_rewrite::fuse(&variants, lists, limit);
+
+        // Update access tracking once, for the fused results
         self.track_search_access(&results).await;
 
         Ok(results)
     }
 
+    /// The original query followed by its rewrites, using cached rewrites when present.
+    async fn query_variants(
+        &self,
+        project_id: &str,
+        query: &str,
+        rewrite: QueryRewrite,
+    ) -> Vec<String> {
+        let mut variants = vec![query.to_string()];
+        if !rewrite.is_enabled() {
+            return variants;
+        }
+
+        let llm = match self.llm_for(project_id).await {
+            Ok(llm) => llm,
+            Err(e) => {
+                warn!(error = %e, "No LLM for query rewriting, searching original query");
+                return variants;
+            }
+        };
+
+        let mut kinds = Vec::new();
+        if rewrite.expand {
+            kinds.push(RewriteKind::Expand);
+        }
+        if rewrite.hyde {
+            kinds.push(RewriteKind::Hyde);
+        }
+
+        for kind in kinds {
+            match db::get_query_rewrites(&self.db, project_id, query, kind).await {
+                Ok(Some(cached)) if !cached.is_empty() => {
+                    variants.extend(cached);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to read query rewrite cache"),
+            }
+
+            let generated = match kind {
+                RewriteKind::Expand => {
+                    query_rewrite::expand_query(&llm, query, rewrite.expansion_count).await
+                }
+                RewriteKind::Hyde => query_rewrite::hypothetical_document(&llm, query)
+                    .await
+                    .map(|passage| vec![passage]),
+            };
+
+            match generated {
+                Ok(rewrites) => {
+                    self.record_usage(project_id, UsageKind::Llm).await;
+                    let rewrites: Vec<String> =
+                        rewrites.into_iter().filter(|r| !r.is_empty()).collect();
+                    if rewrites.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) =
+                        db::store_query_rewrites(&self.db, project_id, query, kind, &rewrites).await
+                    {
+                        warn!(error = %e, "Failed to cache query rewrites");
+                    }
+                    variants.extend(rewrites);
+                }
+                Err(e) => {
+                    warn!(error = %e, kind = kind.as_str(), "Query rewriting failed");
+                }
+            }
+        }
+
+        variants
+    }
+
+    /// Search for an embedded query with explicit decay settings.
+    ///
+    /// Access is not tracked, so repeated runs (such as search quality
+    /// evaluations) do not change the strengths they measure.
+    pub async fn search_with_decay(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        embedding: Vec<f32>,
+        limit: usize,
+        include_chunks: bool,
+        decay: &DecayConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        if include_chunks {
+            self.search_chunks_embedding(
+                project_id,
+                project_slug,
+                embedding,
+                None,
+                limit,
+                Some(decay),
+            )
+            .await
+        } else {
+            self.search_embedding(
+                project_id,
+                project_slug,
+                embedding,
+                None,
+                limit,
+                Some(decay),
+            )
+            .await
+        }
+    }
+
+    /// Embed a search query using search-priority providers.
+    pub async fn embed_query(&self, project_id: &str, query: &str) -> Result<Vec<f32>> {
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single_for_search(query)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
+        Ok(embedding)
+    }
+
     /// Agentic search with link traversal - follows relationships for holographic retrieval.
     pub async fn search_agentic(
         &self,
@@ -1532,7 +2525,12 @@ Return JSON:
         limit: usize,
     ) -> Result<Vec<AgenticSearchResult>> {
         // Generate query embedding using search-priority providers
-        let embedding = self.embeddings.embed_single_for_search(query).await?;
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single_for_search(query)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
 
         // Search Qdrant
         let qdrant_results = self
@@ -1623,7 +2621,55 @@ Return JSON:
         Ok(links.into_iter().map(|(id,)| id).collect())
     }
 
+    /// Decay config for a project: its algorithm config plus scoring settings.
+    pub async fn scoring_config(&self, project: &Project) -> Result<DecayConfig> {
+        let settings = db::get_scoring_settings(&self.db, &project.id).await?;
+        Ok(project_decay_config(project).with_scoring(&settings))
+    }
+
+    /// Build a scorer for search results among `ids`.
+    ///
+    /// An override replaces only the half-life and strength weight, so
+    /// evaluations still score with the project's decay model.
+    async fn scorer(
+        &self,
+        project: &Project,
+        ids: &[String],
+        decay: Option<&DecayConfig>,
+    ) -> Result<Scorer> {
+        let mut config = self.scoring_config(project).await?;
+        if let Some(decay) = decay {
+            config.half_life_days = decay.half_life_days;
+            config.strength_weight = decay.strength_weight;
+        }
+
+        let feedback = db::get_feedback_counts(&self.db, ids).await?;
+
+        let mut accesses = HashMap::new();
+        if config.model == DecayModel::PowerLaw {
+            for batch in ids.chunks(ACCESS_BATCH_SIZE) {
+                accesses
+                    .extend(db::get_memory_accesses(&self.db, batch, ACCESS_HISTORY_LIMIT).await?);
+            }
+        }
+
+        let link_rank = if config.link_boost_weight > 0.0 {
+            let edges = db::list_project_link_edges(&self.db, &project.id).await?;
+            super::graph::link_rank(&edges)
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Scorer::new(config)
+            .with_feedback(feedback)
+            .with_accesses(accesses)
+            .with_link_rank(link_rank))
+    }
+
     /// Update access tracking for memories returned in search results.
+    ///
+    /// These are impressions only. Ranking strength comes from explicit
+    /// feedback and reads (see [`Scorer`]).
     async fn track_search_access(&self, results: &[MemorySearchResult]) {
         if results.is_empty() {
             return;
@@ -1644,6 +2690,184 @@ Return JSON:
         }
     }
 
+    // =========================================================================
+    // Point-in-Time
+    // =========================================================================
+
+    /// Get a memory as it was at `at`, without access tracking.
+    ///
+    /// Returns `None` if the memory was created later. A memory updated since
+    /// is returned as its revision current at `at`, and one deleted since is
+    /// rebuilt from its tombstone.
+    pub async fn get_as_of(
+        &self,
+        project_id: &str,
+        memory_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<Memory>> {
+        let memory = match self.get_without_tracking(project_id, memory_id).await? {
+            Some(m) => Some(m),
+            None => match db::get_memory_tombstone(&self.db, memory_id).await? {
+                Some(tombstone) if tombstone.valid_to > at => tombstone
+                    .deleted_memory()
+                    .filter(|m| m.project_id == project_id),
+                _ => None,
+            },
+        };
+        let memory = match memory {
+            Some(m) if m.created_at <= at => m,
+            _ => return Ok(None),
+        };
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+        self.memory_as_of(&project_root, memory, at).await.map(Some)
+    }
+
+    /// Search memories as they were at `as_of`.
+    ///
+    /// Memories created later are left out, and memories deleted since are
+    /// found in the history collection and brought back from their
+    /// tombstones. Memories updated since their stored vector was made are
+    /// scored on their revision current at `as_of`, re-embedded in one batch
+    /// to get its relevance, and strength is computed at `as_of`. Memories
+    /// changed before revisions were kept are shown as they are now, and
+    /// memories deleted before their vectors were kept are not found. There
+    /// is no access tracking and no chunk matching.
+    pub async fn search_as_of(
+        &self,
+        project_id: &str,
+        project_slug: &str,
+        query: &str,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+        as_of: DateTime<Utc>,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let embedding = self.embed_query(project_id, query).await?;
+        let filter = memory_type.map(|mt| SearchFilter::new().with_type(mt.as_str()));
+
+        // Fetch extra to make up for memories created since
+        let fetch_limit = (limit * 3).min(150);
+        let mut hits = Vec::with_capacity(fetch_limit * 2);
+        for vr in self
+            .qdrant
+            .search(project_slug, embedding.clone(), fetch_limit, filter.clone())
+            .await?
+        {
+            if let Some(current) = self.get_without_tracking(project_id, &vr.id).await? {
+                hits.push((current, vr.score));
+            }
+        }
+
+        // Deleted memories keep their last vector in the history collection
+        if !db::list_memory_tombstones_after(&self.db, project_id, as_of, 1)
+            .await?
+            .is_empty()
+        {
+            for vr in self
+                .qdrant
+                .search(
+                    &history_collection(project_slug),
+                    embedding.clone(),
+                    fetch_limit,
+                    filter,
+                )
+                .await?
+            {
+                if hits.iter().any(|(m, _)| m.id == vr.id) {
+                    continue;
+                }
+                let deleted = match db::get_memory_tombstone(&self.db, &vr.id).await? {
+                    Some(tombstone) if tombstone.valid_to > as_of => tombstone
+                        .deleted_memory()
+                        .filter(|m| m.project_id == project_id),
+                    _ => None,
+                };
+                if let Some(deleted) = deleted {
+                    hits.push((deleted, vr.score));
+                }
+            }
+        }
+        hits.retain(|(m, _)| m.created_at <= as_of);
+
+        let project = crate::db::get_project(&self.db, project_id).await?;
+        let project_root = std::path::PathBuf::from(&project.root_path);
+        let ids: Vec<String> = hits.iter().map(|(m, _)| m.id.clone()).collect();
+        let scorer = self.scorer(&project, &ids, None).await?.at(as_of);
+
+        // Each memory as of then, with the relevance of its stored vector
+        // when that vector is still of the version shown
+        let mut candidates = Vec::with_capacity(hits.len());
+        for (current, score) in hits {
+            let revised = current.updated_at > as_of;
+            let memory = self.memory_as_of(&project_root, current, as_of).await?;
+            let relevance = if revised && memory.updated_at <= as_of {
+                None
+            } else {
+                Some(score)
+            };
+            candidates.push((memory, relevance));
+        }
+
+        let texts: Vec<String> = candidates
+            .iter()
+            .filter(|(_, relevance)| relevance.is_none())
+            .map(|(m, _)| self.build_embedding_text(m, m.content.as_deref().unwrap_or("")))
+            .collect();
+        let mut vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            self.check_quota(project_id, UsageKind::Embedding).await?;
+            let vectors = self.embeddings_for(project_id).await?.embed(texts).await?;
+            self.record_usage(project_id, UsageKind::Embedding).await;
+            vectors
+        }
+        .into_iter();
+
+        let mut results = Vec::with_capacity(candidates.len());
+        for (memory, relevance) in candidates {
+            let relevance = relevance.unwrap_or_else(|| {
+                let then = vectors.next().unwrap_or_default();
+                super::consolidation::cosine_similarity(&embedding, &then)
+            });
+            results.push(scorer.result(memory, relevance, Vec::new()));
+        }
+
+        results.sort_by(|a, b| {
+            b.combined_score
+                .partial_cmp(&a.combined_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    /// Apply the revision current at `at`, and read content from fold/ when
+    /// it is not in SQLite.
+    async fn memory_as_of(
+        &self,
+        project_root: &std::path::Path,
+        memory: Memory,
+        at: DateTime<Utc>,
+    ) -> Result<Memory> {
+        let mut memory = match db::get_memory_revision_at(&self.db, &memory.id, at).await? {
+            Some(revision) => revision.apply(memory),
+            None => memory,
+        };
+
+        if memory.content.as_ref().is_none_or(|c| c.is_empty()) {
+            if let Ok((_, content)) = self
+                .fold_storage
+                .read_memory(project_root, &memory.id)
+                .await
+            {
+                memory.content = Some(content);
+            }
+        }
+
+        Ok(memory)
+    }
+
     // =========================================================================
     // Context Reconstruction
     // =========================================================================
@@ -1705,7 +2929,12 @@ Return JSON:
         }
 
         // Also add vector-similar memories not explicitly linked
-        let embedding = self.embeddings.embed_single(&content).await?;
+        let embedding = self
+            .embeddings_for(project_id)
+            .await?
+            .embed_single(&content)
+            .await?;
+        self.record_usage(project_id, UsageKind::Embedding).await;
         let similar = self
             .qdrant
             .search(&project.slug, embedding, 5, None)
@@ -1739,18 +2968,25 @@ Return JSON:
     }
 
     // =========================================================================
-    // Legacy Compatibility Methods
+    // Task Context
     // =========================================================================
 
-    /// Get context for a task (legacy compatibility).
+    /// Pack context for a task into a token budget.
+    ///
+    /// Each memory type is searched separately. Memories are represented by
+    /// their matched chunks when they have any, so code comes in as whole
+    /// functions and classes, and the results are packed with
+    /// [`context_packer::pack`]. With `as_of`, memories are searched as they
+    /// were then (see [`Self::search_as_of`]) and come in whole.
     pub async fn get_context_for_task(
         &self,
         project_id: &str,
         project_slug: &str,
         task: &str,
         types: Option<Vec<MemoryType>>,
-        limit: usize,
-    ) -> Result<ContextResult> {
+        max_tokens: usize,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<PackedContext> {
         let types = types.unwrap_or_else(|| {
             vec![
                 MemoryType::Codebase,
@@ -1760,58 +2996,92 @@ Return JSON:
             ]
         });
 
-        let per_type_limit = (limit / types.len()).max(1);
+        let mut candidates = Vec::new();
+        for memory_type in types {
+            let results = match as_of {
+                Some(at) => {
+                    self.search_as_of(
+                        project_id,
+                        project_slug,
+                        task,
+                        Some(memory_type),
+                        CONTEXT_CANDIDATES_PER_TYPE,
+                        at,
+                    )
+                    .await?
+                }
+                None => {
+                    self.search_with_chunks(
+                        project_id,
+                        project_slug,
+                        task,
+                        Some(memory_type),
+                        CONTEXT_CANDIDATES_PER_TYPE,
+                    )
+                    .await?
+                }
+            };
+            let kind = ContextKind::from_memory_type(memory_type);
 
-        let mut context = ContextResult {
-            task: task.to_string(),
-            code: Vec::new(),
-            specs: Vec::new(),
-            decisions: Vec::new(),
-            sessions: Vec::new(),
-            other: Vec::new(),
-        };
+            // Chunk matches can bring in memories of other types
+            for result in results
+                .into_iter()
+                .filter(|r| r.memory.memory_type == memory_type.as_str())
+            {
+                candidates.extend(self.context_candidates(result, kind).await?);
+            }
+        }
 
-        for memory_type in types {
-            let results = self
-                .search_with_type(
-                    project_id,
-                    project_slug,
-                    task,
-                    Some(memory_type),
-                    per_type_limit,
-                )
-                .await?;
+        Ok(context_packer::pack(task, candidates, max_tokens))
+    }
 
-            for result in results {
-                let item = ContextItem {
-                    id: result.memory.id.clone(),
-                    title: result.memory.title.clone(),
-                    content: result
-                        .memory
-                        .content
-                        .as_deref()
-                        .unwrap_or("")
-                        .chars()
-                        .take(500)
-                        .collect(),
-                    score: result.score,
-                    file_path: result.memory.file_path.clone(),
-                    author: result.memory.author.clone(),
-                };
+    /// Context candidates for a search result: its matched chunks, or the
+    /// whole memory when no chunk matched.
+    async fn context_candidates(
+        &self,
+        result: MemorySearchResult,
+        kind: ContextKind,
+    ) -> Result<Vec<ContextCandidate>> {
+        let memory = result.memory;
+        let whole = ContextCandidate {
+            memory_id: memory.id,
+            kind,
+            title: memory.title,
+            file_path: memory.file_path,
+            language: memory.language,
+            start_line: None,
+            end_line: None,
+            node_name: None,
+            score: result.score,
+            content: memory.content.unwrap_or_default(),
+        };
 
-                match memory_type {
-                    MemoryType::Codebase => context.code.push(item),
-                    MemoryType::Spec => context.specs.push(item),
-                    MemoryType::Decision => context.decisions.push(item),
-                    MemoryType::Session => context.sessions.push(item),
-                    _ => context.other.push(item),
-                }
-            }
+        let mut candidates = Vec::new();
+        for chunk_match in &result.matched_chunks {
+            let Some(chunk) = db::get_chunk(&self.db, &chunk_match.id).await? else {
+                continue;
+            };
+            candidates.push(ContextCandidate {
+                start_line: Some(chunk.start_line),
+                end_line: Some(chunk.end_line),
+                node_name: chunk.node_name.or(Some(chunk.node_type)),
+                score: chunk_match.score,
+                content: chunk.content,
+                ..whole.clone()
+            });
+        }
+
+        if candidates.is_empty() {
+            candidates.push(whole);
         }
 
-        Ok(context)
+        Ok(candidates)
     }
 
+    // =========================================================================
+    // Legacy Compatibility Methods
+    // =========================================================================
+
     /// Delete all memories for a project.
     pub async fn delete_all_for_project(
         &self,
@@ -1827,8 +3097,14 @@ Return JSON:
         .execute(&self.db)
         .await?;
 
-        // Delete collection from Qdrant
+        // Delete collections from Qdrant
         self.qdrant.delete_collection(project_slug).await?;
+        self.qdrant
+            .delete_collection(&archive_collection(project_slug))
+            .await?;
+        self.qdrant
+            .delete_collection(&history_collection(project_slug))
+            .await?;
 
         // Delete related links
         sqlx::query(
@@ -1863,7 +3139,7 @@ Return JSON:
     /// Insert or update memory in SQLite.
     /// Uses upsert to handle codebase files that may be re-indexed with the same path-based ID.
     async fn insert_memory(&self, memory: &Memory) -> Result<()> {
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             INSERT INTO memories (
                 id, project_id, slug, type, source, content, content_hash, content_storage,
@@ -1884,6 +3160,7 @@ Return JSON:
                 context = excluded.context,
                 metadata = excluded.metadata,
                 updated_at = datetime('now')
+            WHERE memories.project_id = excluded.project_id
             "#,
         )
         .bind(&memory.id)
@@ -1913,6 +3190,11 @@ Return JSON:
         .execute(&self.db)
         .await?;
 
+        // The ID is taken by another project's memory
+        if result.rows_affected() == 0 {
+            return Err(Error::AlreadyExists(format!("Memory {}", memory.id)));
+        }
+
         Ok(())
     }
 
@@ -1946,24 +3228,36 @@ Return JSON:
     }
 }
 
-/// Context gathered for a task (legacy compatibility).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ContextResult {
-    pub task: String,
-    pub code: Vec<ContextItem>,
-    pub specs: Vec<ContextItem>,
-    pub decisions: Vec<ContextItem>,
-    pub sessions: Vec<ContextItem>,
-    pub other: Vec<ContextItem>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decision_checks() {
+        let candidates: HashSet<String> =
+            ["dec-1", "dec-2"].iter().map(|s| s.to_string()).collect();
+        let json = json!({
+            "checks": [
+                {"memory_id": "dec-1", "relation": "contradict", "reason": "MySQL vs Postgres", "confidence": 1.4},
+                {"memory_id": "dec-2", "relation": "supersede"},
+                {"memory_id": "dec-9", "relation": "refine"},
+                {"relation": "agree"}
+            ]
+        });
 
-/// A single context item (legacy compatibility).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ContextItem {
-    pub id: String,
-    pub title: Option<String>,
-    pub content: String,
-    pub score: f32,
-    pub file_path: Option<String>,
-    pub author: Option<String>,
+        let checks = parse_decision_checks(json, &candidates);
+
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].relation, DecisionRelation::Contradict);
+        assert_eq!(checks[0].confidence, Some(1.0));
+        // Unknown relations degrade to unrelated rather than being dropped
+        assert_eq!(checks[1].relation, DecisionRelation::Unrelated);
+
+        // A bare array is accepted too
+        let bare = json!([{"memory_id": "dec-2", "relation": "refine"}]);
+        assert_eq!(
+            parse_decision_checks(bare, &candidates)[0].relation,
+            DecisionRelation::Refine
+        );
+    }
 }
@@ -14,20 +14,45 @@
 //! - ContentResolver (resolve memory content from external storage)
 //! - FileSource (abstraction for file providers)
 //! - GitHub/GitLab (git provider APIs)
-//! - GitSync (webhook processing)
+//! - GitSync (webhook processing and syncing pushed fold/ edits back to memories)
 //! - Graph (relationship queries)
 //! - Linker (auto-linking)
 //! - Auth (OIDC flows)
 //! - AttachmentStorage (content-addressed file storage)
+//! - Transcripts (agent transcript ingestion as session memories)
+//! - Team (presence and who-is-working-on-what)
+//! - Consolidation (merging near-duplicate agent memories)
+//! - Retention (archiving weak memories to cold storage)
+//! - RateLimit (per-token request buckets and daily provider quotas)
+//! - Audit (append-only record of who changed what)
+//! - OutboundWebhooks (signed delivery of memory and link events with retry)
+//! - ProjectArchive (portable export/import of whole projects)
+//! - VaultImport (Obsidian/Logseq vaults as memories)
+//! - ProviderChains (per-project LLM and embedding provider overrides)
+//! - Ask (question answering with citations to project memories)
+//! - ContextPacker (token-budgeted markdown context bundles)
+//! - QueryRewrite (LLM query expansion and HyDE for search)
+//! - FederatedSearch (search across every project a user can read)
+//! - SearchEval (golden query sets and recall/MRR/nDCG evaluation)
+//! - Feedback (used/irrelevant/wrong verdicts on search results)
+//! - SearchAnalytics (search query log, analytics and knowledge gap reports)
+//! - SearchExplain (why each search result ranked where it did)
+//! - PointInTime (as_of search and context against past memory state)
 
+pub mod ask;
 mod attachment_storage;
+mod audit;
 mod auth;
 mod claudecode;
+mod consolidation;
 mod content_resolver;
+pub mod context_packer;
 pub mod decay;
 mod embeddings_bridge;
 mod event_broadcaster;
 pub mod events;
+pub mod federated_search;
+pub mod feedback;
 pub mod file_source;
 pub mod fold_storage;
 mod git;
@@ -45,16 +70,34 @@ pub mod markdown;
 mod memory;
 mod meta_storage;
 mod metadata_sync;
+mod outbound_webhooks;
 mod permissions;
+pub mod point_in_time;
 mod project;
+mod project_archive;
+mod provider_chains;
+mod query_rewrite;
+mod rate_limit;
+mod retention;
+pub mod search_analytics;
+pub mod search_eval;
+pub mod search_explain;
 mod sse_tracing_layer;
+mod team;
+mod transcripts;
+mod vault_import;
 
+pub use ask::{AskEvent, AskOptions, AskService};
+pub use audit::{AuditActor, AuditService};
 pub use auth::AuthService;
 pub use event_broadcaster::{EventBroadcaster, SharedEventBroadcaster};
 pub use fold_chunker::{ChunkStrategy, ChunkerConfig, ChunkerService, CodeChunk};
 pub use claudecode::{ClaudeCodeInfo, ClaudeCodeService};
+pub use consolidation::{ConsolidationReport, ConsolidationService, MergeProposal};
 pub use content_resolver::ContentResolverService;
 pub use embeddings_bridge::EmbeddingService;
+pub use federated_search::{FederatedSearchOptions, FederatedSearchService};
+pub use feedback::{FeedbackEntry, FeedbackService};
 pub use fold_embeddings::{
     default_dimension, default_endpoint, default_model, EmbeddingCallbacks, EmbeddingConfig,
     EmbeddingProviderConfig, Error as EmbeddingError, NoOpCallbacks, RuntimeEmbeddingProvider,
@@ -62,11 +105,11 @@ pub use fold_embeddings::{
 pub use file_source::{FileSourceProvider, ProviderRegistry};
 pub use fold_storage::{
     storage_error_to_error, storage_memory_to_memory, FoldStorageExt, FoldStorageService,
-    MemoryData, MemoryFrontmatter, StorageMemory,
+    MemoryData, MemoryFrontmatter, SqlitePathIndex, StorageMemory,
 };
 pub use git::GitService;
 pub use git_local::GitLocalService;
-pub use git_sync::GitSyncService;
+pub use git_sync::{FoldChanges, FoldSyncResult, GitSyncService};
 pub use github::GitHubService;
 pub use gitlab::GitLabService;
 pub use graph::GraphService;
@@ -76,13 +119,33 @@ pub use linker::LinkerService;
 pub use llm::LlmService;
 // Re-export fold-llm types for external use
 pub use fold_llm::{
-    default_endpoint as llm_default_endpoint, default_model as llm_default_model,
-    Error as LlmError, GeneratedMetadata, LlmConfig, LlmProviderConfig, RuntimeLlmProvider,
+    default_endpoint as llm_default_endpoint, default_model as llm_default_model, ChatMessage,
+    ChatRequest, ChatResponse, Error as LlmError, GeneratedMetadata, JsonSchema, LlmConfig,
+    LlmProviderConfig, Role as ChatRole, RuntimeLlmProvider, StreamEvent, Usage as LlmUsage,
 };
 pub use memory::MemoryService;
 pub use meta_storage::MetaStorageService;
 pub use metadata_sync::MetadataSyncService;
+pub use outbound_webhooks::{
+    check_target_url, retry_delay, sign_payload, OutboundWebhookService, MAX_DELIVERY_ATTEMPTS,
+    WEBHOOK_EVENT_TYPES,
+};
 pub use permissions::{PermissionService, ProjectAccess};
 pub use project::ProjectService;
+pub use project_archive::{
+    parse_archive, ArchiveManifest, ImportOptions, ImportResult, ProjectArchiveService,
+    ARCHIVE_FORMAT, ARCHIVE_VERSION,
+};
+pub use provider_chains::ProviderChainService;
+pub use query_rewrite::{QueryRewrite, MAX_EXPANSION_COUNT};
+pub use rate_limit::{effective_limit, RateLimitService, RouteClass};
+pub use retention::{ArchiveCandidate, RetentionReport, RetentionService};
+pub use search_analytics::{SearchAnalytics, SearchAnalyticsService};
+pub use search_eval::{EvalConfig, SearchEvalService};
 pub use fold_qdrant::{QdrantService, SearchFilter, VectorSearchResult, CollectionInfo};
 pub use sse_tracing_layer::SseTracingLayer;
+pub use team::{FileConflict, TeamService};
+pub use transcripts::{
+    ParsedTranscript, TranscriptExtraction, TranscriptImportResult, TranscriptService,
+};
+pub use vault_import::{LinkResolver, VaultImportResult, VaultImportService, VaultNote};
@@ -0,0 +1,553 @@
+//! Outbound webhook delivery.
+//!
+//! Projects can register webhooks that receive their memory and link
+//! lifecycle events (`memory:created`, `link:deleted`, ...), e.g. to post
+//! new decisions to a chat channel. Events are queued in
+//! `webhook_deliveries` and POSTed by a background loop that retries
+//! failures with exponential backoff.
+//!
+//! Each request is signed with the webhook's secret:
+//! `X-Fold-Signature-256: sha256=<hex HMAC-SHA256 of the body>`.
+//!
+//! Targets must be public addresses. Loopback, private and link-local
+//! targets are refused at registration and again when sending, and
+//! redirects are not followed, so a webhook cannot reach services on the
+//! server's own network.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use super::event_broadcaster::EventBroadcaster;
+use super::events::FoldEvent;
+use crate::db::{self, CreateWebhookDelivery, DbPool, WebhookDelivery};
+use crate::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Delivery attempts before a delivery is marked failed.
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+
+/// Delay before the first retry; doubles on each further attempt.
+const RETRY_BASE_SECS: i64 = 30;
+
+/// Longest delay between retries.
+const RETRY_MAX_SECS: i64 = 6 * 60 * 60;
+
+/// How often the delivery loop looks for due deliveries.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Deliveries sent per poll.
+const BATCH_SIZE: i64 = 50;
+
+/// Request timeout for a single delivery.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event types outbound webhooks can subscribe to.
+pub const WEBHOOK_EVENT_TYPES: &[&str] = &[
+    "memory:created",
+    "memory:updated",
+    "memory:deleted",
+    "link:created",
+    "link:deleted",
+];
+
+/// Sign a webhook body. Returns the `X-Fold-Signature-256` header value.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before retrying after `attempts` failed attempts, or `None` once
+/// the delivery is out of attempts.
+pub fn retry_delay(attempts: i32) -> Option<chrono::Duration> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        return None;
+    }
+    let exponent = (attempts - 1).clamp(0, 20) as u32;
+    let secs = RETRY_BASE_SECS
+        .saturating_mul(2i64.pow(exponent))
+        .min(RETRY_MAX_SECS);
+    Some(chrono::Duration::seconds(secs))
+}
+
+/// Whether an address is publicly routable. Webhooks may only target these.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = embedded_ipv4(&v6) {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// The IPv4 address an IPv6 address reaches: IPv4-mapped (`::ffff:a.b.c.d`),
+/// IPv4-compatible (`::a.b.c.d`), NAT64 (`64:ff9b::/96`) or 6to4 (`2002::/16`).
+fn embedded_ipv4(v6: &Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(v4) = v6.to_ipv4_mapped() {
+        return Some(v4);
+    }
+
+    let segments = v6.segments();
+    let from_segments = |hi: u16, lo: u16| {
+        let [a, b] = hi.to_be_bytes();
+        let [c, d] = lo.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    match segments {
+        [0, 0, 0, 0, 0, 0, hi, lo] => Some(from_segments(hi, lo)),
+        [0x64, 0xff9b, 0, 0, 0, 0, hi, lo] => Some(from_segments(hi, lo)),
+        [0x2002, hi, lo, ..] => Some(from_segments(hi, lo)),
+        _ => None,
+    }
+}
+
+/// Resolve a host, failing if any of its addresses is not public.
+async fn resolve_public(host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} resolves to internal address {}", host, addr.ip()),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// Check that a webhook URL is http(s) and points at a public address.
+pub async fn check_target_url(url: &str) -> Result<()> {
+    resolve_target(url).await.map(|_| ())
+}
+
+/// Check a webhook URL and resolve its host. Returns the host and its
+/// addresses, or `None` when the URL names an IP address.
+async fn resolve_target(url: &str) -> Result<Option<(String, Vec<SocketAddr>)>> {
+    let invalid =
+        |reason: String| Error::Validation(format!("Invalid webhook URL {}: {}", url, reason));
+
+    let parsed = url::Url::parse(url).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid("must be http or https".to_string()));
+    }
+
+    let ip = match parsed.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(url::Host::Domain(domain)) => {
+            let port = parsed.port_or_known_default().unwrap_or(443);
+            let addrs = resolve_public(domain, port)
+                .await
+                .map_err(|e| invalid(e.to_string()))?;
+            return Ok(Some((domain.to_string(), addrs)));
+        }
+        None => return Err(invalid("missing host".to_string())),
+    };
+
+    if !is_public_ip(ip) {
+        return Err(invalid(format!("{} is an internal address", ip)));
+    }
+    Ok(None)
+}
+
+/// HTTP client settings for deliveries. Redirects are not followed, as they
+/// could lead to an internal address.
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+}
+
+/// Service for queueing and delivering outbound webhooks.
+#[derive(Clone)]
+pub struct OutboundWebhookService {
+    db: DbPool,
+    events: Arc<EventBroadcaster>,
+    client: reqwest::Client,
+}
+
+impl OutboundWebhookService {
+    /// Create a new outbound webhook service.
+    pub fn new(db: DbPool, events: Arc<EventBroadcaster>) -> Self {
+        let client = client_builder().build().unwrap_or_default();
+
+        Self { db, events, client }
+    }
+
+    /// Queue a delivery of an event to each subscribed webhook of its
+    /// project. Returns the number of deliveries queued.
+    pub async fn enqueue(&self, event: &FoldEvent) -> Result<usize> {
+        if !event.is_memory_lifecycle() {
+            return Ok(0);
+        }
+        let Some(project_id) = event.project_id() else {
+            return Ok(0);
+        };
+
+        let event_type = event.event_type();
+        // Events serialize as {"type", "data"}; the payload carries `data`
+        let data = serde_json::to_value(event)?["data"].take();
+        let mut queued = 0;
+
+        for webhook in db::list_active_outbound_webhooks(&self.db, project_id).await? {
+            let Some(target_url) = webhook.target_url.clone() else {
+                continue;
+            };
+            if !webhook.subscribes_to(event_type) {
+                continue;
+            }
+
+            let id = crate::models::new_id();
+            let payload = json!({
+                "id": id,
+                "event": event_type,
+                "project_id": project_id,
+                "webhook_id": webhook.id,
+                "data": data,
+            });
+
+            db::create_webhook_delivery(
+                &self.db,
+                CreateWebhookDelivery {
+                    id,
+                    webhook_id: Some(webhook.id.clone()),
+                    delivery_type: event_type.to_string(),
+                    target_url,
+                    payload,
+                },
+            )
+            .await?;
+            queued += 1;
+        }
+
+        Ok(queued)
+    }
+
+    /// Send all deliveries that are due. Returns the number that succeeded.
+    pub async fn deliver_pending(&self) -> Result<usize> {
+        let pending = db::list_pending_webhook_deliveries(&self.db, BATCH_SIZE).await?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let results = futures::future::join_all(pending.into_iter().map(|d| self.deliver(d))).await;
+
+        Ok(results.into_iter().filter(|ok| *ok).count())
+    }
+
+    /// Attempt one delivery and record the outcome. Returns whether it succeeded.
+    async fn deliver(&self, delivery: WebhookDelivery) -> bool {
+        let outcome = self.send(&delivery).await;
+
+        let (success, error, next_attempt_at) = match outcome {
+            Ok(()) => (true, None, None),
+            Err(e) => {
+                let next = retry_delay(delivery.attempts + 1).map(|delay| {
+                    (chrono::Utc::now() + delay)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                });
+                warn!(
+                    delivery_id = %delivery.id,
+                    target = %delivery.target_url,
+                    attempt = delivery.attempts + 1,
+                    will_retry = next.is_some(),
+                    error = %e,
+                    "Webhook delivery failed"
+                );
+                (false, Some(e), next)
+            }
+        };
+
+        if let Err(e) = db::update_webhook_delivery_attempt(
+            &self.db,
+            &delivery.id,
+            success,
+            error.as_deref(),
+            next_attempt_at.as_deref(),
+        )
+        .await
+        {
+            warn!(error = %e, delivery_id = %delivery.id, "Failed to record webhook delivery");
+        }
+
+        success
+    }
+
+    async fn send(&self, delivery: &WebhookDelivery) -> std::result::Result<(), String> {
+        let webhook_id = delivery
+            .webhook_id
+            .as_deref()
+            .ok_or("Delivery has no webhook")?;
+        let webhook = db::get_webhook_registration(&self.db, webhook_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !webhook.active {
+            return Err("Webhook is disabled".to_string());
+        }
+
+        // Connect to the addresses just checked, so the host cannot be
+        // re-pointed at an internal address between check and request
+        let client = match resolve_target(&delivery.target_url)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some((host, addrs)) => client_builder()
+                .resolve_to_addrs(&host, &addrs)
+                .build()
+                .map_err(|e| e.to_string())?,
+            None => self.client.clone(),
+        };
+
+        let body = delivery.payload.clone().into_bytes();
+        let signature = sign_payload(&webhook.secret, &body);
+
+        let response = client
+            .post(&delivery.target_url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "Fold-Webhooks")
+            .header("X-Fold-Event", &delivery.delivery_type)
+            .header("X-Fold-Delivery", &delivery.id)
+            .header("X-Fold-Signature-256", signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let status = response.status();
+        if status.is_success() {
+            debug!(delivery_id = %delivery.id, status = %status, "Webhook delivered");
+            Ok(())
+        } else {
+            Err(format!("Endpoint returned {}", status))
+        }
+    }
+
+    /// Start the background loops: one queues deliveries for broadcast
+    /// events, the other sends due deliveries.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let listener = self.clone();
+        let mut receiver = self.events.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = listener.enqueue(&event).await {
+                            warn!(error = %e, "Failed to queue webhook deliveries");
+                        }
+                    }
+                    Err(RecvError::Lagged(count)) => {
+                        warn!(count, "Webhook listener lagged, events not delivered");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            info!("Outbound webhook delivery started");
+            loop {
+                if let Err(e) = service.deliver_pending().await {
+                    warn!(error = %e, "Failed to send webhook deliveries");
+                }
+                sleep(POLL_INTERVAL).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateOutboundWebhook, CreateProject};
+
+    async fn setup() -> OutboundWebhookService {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        for (id, events) in [
+            ("wh-memory", vec!["memory:*"]),
+            ("wh-links", vec!["link:created"]),
+        ] {
+            db::create_outbound_webhook(
+                &pool,
+                CreateOutboundWebhook {
+                    id: id.to_string(),
+                    project_id: "proj-1".to_string(),
+                    target_url: "http://127.0.0.1:9/hook".to_string(),
+                    secret: "secret".to_string(),
+                    events: events.into_iter().map(String::from).collect(),
+                    created_by: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        OutboundWebhookService::new(pool, Arc::new(EventBroadcaster::new()))
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // Same as `printf '{"a":1}' | openssl dgst -sha256 -hmac secret`
+        assert_eq!(
+            sign_payload("secret", br#"{"a":1}"#),
+            "sha256=aa9e2e3575f5d7098b6caccd790888c36d5fdb63342a73bada2d6a51747a8494"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(1), Some(chrono::Duration::seconds(30)));
+        assert_eq!(retry_delay(2), Some(chrono::Duration::seconds(60)));
+        assert_eq!(retry_delay(3), Some(chrono::Duration::seconds(120)));
+        assert_eq!(retry_delay(MAX_DELIVERY_ATTEMPTS), None);
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in [
+            "93.184.216.34",
+            "2606:2800:220:1::1",
+            "64:ff9b::93.184.216.34",
+            "2002:5db8:d822::1",
+        ] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "64:ff9b::169.254.169.254",
+            "64:ff9b::7f00:1",
+            "2002:a9fe:a9fe::1",
+            "2002:c0a8:101::",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_target_url() {
+        assert!(check_target_url("https://93.184.216.34/hook").await.is_ok());
+
+        for url in [
+            "ftp://93.184.216.34/hook",
+            "http://127.0.0.1:8080/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost/hook",
+        ] {
+            assert!(check_target_url(url).await.is_err(), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_matches_subscriptions() {
+        let service = setup().await;
+        let events = EventBroadcaster::new();
+        let mut receiver = events.subscribe();
+
+        events.memory_deleted("proj-1", "mem-1");
+        events.link_created("proj-1", "link-1", "mem-1", "mem-2", "related", "user");
+        events.link_deleted("proj-1", "link-1", "mem-1", "mem-2", "related");
+        events.memory_deleted("proj-2", "mem-3");
+
+        let mut queued = Vec::new();
+        for _ in 0..4 {
+            let event = receiver.recv().await.unwrap();
+            queued.push(service.enqueue(&event).await.unwrap());
+        }
+        // memory:deleted -> wh-memory, link:created -> wh-links,
+        // link:deleted -> nobody, other project -> nobody
+        assert_eq!(queued, vec![1, 1, 0, 0]);
+
+        let deliveries = db::list_webhook_deliveries(&service.db, "wh-memory", 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&deliveries[0].payload).unwrap();
+        assert_eq!(payload["event"], "memory:deleted");
+        assert_eq!(payload["data"]["memory_id"], "mem-1");
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_rescheduled() {
+        let service = setup().await;
+        let events = EventBroadcaster::new();
+        let mut receiver = events.subscribe();
+        events.memory_deleted("proj-1", "mem-1");
+        let event = receiver.recv().await.unwrap();
+        service.enqueue(&event).await.unwrap();
+
+        // Loopback targets are refused when sending
+        assert_eq!(service.deliver_pending().await.unwrap(), 0);
+
+        let deliveries = db::list_webhook_deliveries(&service.db, "wh-memory", 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].status, "pending");
+        assert_eq!(deliveries[0].attempts, 1);
+        assert!(deliveries[0].next_attempt_at.is_some());
+        assert!(deliveries[0].error.is_some());
+    }
+}
@@ -0,0 +1,134 @@
+//! Point-in-time queries.
+//!
+//! Search and context take an `as_of` of a timestamp, a date or an indexed
+//! commit SHA, and answer from the knowledge base as it was then: memories
+//! created later are left out and memories updated since are shown as their
+//! revision current at that time.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Serialize;
+
+use crate::db::{self, DbPool};
+use crate::error::{Error, Result};
+
+/// Shortest commit SHA prefix accepted.
+const MIN_SHA_PREFIX: usize = 7;
+
+/// A resolved `as_of`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AsOf {
+    /// The point in time queried
+    pub at: DateTime<Utc>,
+    /// Full SHA when `as_of` named a commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Resolve an `as_of` value for a project.
+///
+/// Accepts an RFC 3339 timestamp, `YYYY-MM-DD HH:MM:SS` (UTC), a date (the
+/// end of that day) or a commit SHA or prefix from the project's indexed
+/// history, which resolves to the commit time.
+pub async fn resolve_as_of(pool: &DbPool, project_id: &str, value: &str) -> Result<AsOf> {
+    let value = value.trim();
+
+    if let Some(at) = parse_timestamp(value) {
+        return Ok(AsOf { at, commit: None });
+    }
+
+    if !is_commit_sha(value) {
+        return Err(Error::Validation(format!(
+            "as_of must be a timestamp, a date or a commit SHA: {}",
+            value
+        )));
+    }
+
+    let mut commits = db::find_git_commits_by_prefix(pool, project_id, value).await?;
+    let commit = match commits.len() {
+        0 => {
+            return Err(Error::Validation(format!(
+                "Commit {} has not been indexed for this project",
+                value
+            )))
+        }
+        1 => commits.remove(0),
+        _ => {
+            return Err(Error::Validation(format!(
+                "Commit prefix {} is ambiguous",
+                value
+            )))
+        }
+    };
+
+    let at = parse_timestamp(&commit.committed_at).ok_or_else(|| {
+        Error::Internal(format!(
+            "Unreadable commit time for {}: {}",
+            commit.sha, commit.committed_at
+        ))
+    })?;
+
+    Ok(AsOf {
+        at,
+        commit: Some(commit.sha),
+    })
+}
+
+/// Parse a timestamp or date. Dates mean the end of the day, so "as of
+/// 2025-03-01" includes everything from that day.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S%.f",
+    ] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(at.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|at| at.and_utc())
+}
+
+/// Whether a stored creation time is no later than `at`. Unreadable times
+/// count as existing.
+pub fn existed_at(created_at: &str, at: DateTime<Utc>) -> bool {
+    parse_timestamp(created_at).is_none_or(|created| created <= at)
+}
+
+fn is_commit_sha(value: &str) -> bool {
+    (MIN_SHA_PREFIX..=40).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        let at = parse_timestamp("2025-03-01T12:30:00+02:00").unwrap();
+        assert_eq!(at.to_rfc3339(), "2025-03-01T10:30:00+00:00");
+
+        let at = parse_timestamp("2025-03-01 10:30:00").unwrap();
+        assert_eq!(at.to_rfc3339(), "2025-03-01T10:30:00+00:00");
+
+        // A date covers the whole day
+        let at = parse_timestamp("2025-03-01").unwrap();
+        assert_eq!(at.to_rfc3339(), "2025-03-01T23:59:59+00:00");
+
+        assert!(parse_timestamp("v2.3").is_none());
+        assert!(parse_timestamp("a1b2c3d").is_none());
+    }
+
+    #[test]
+    fn test_is_commit_sha() {
+        assert!(is_commit_sha("a1b2c3d"));
+        assert!(is_commit_sha("0123456789abcdef0123456789abcdef01234567"));
+        assert!(!is_commit_sha("a1b2c3"));
+        assert!(!is_commit_sha("release-2.3"));
+    }
+}
@@ -11,6 +11,7 @@ use tracing::{debug, info};
 use crate::db::DbPool;
 use crate::error::{Error, Result};
 use crate::models::{Project, ProjectCreate, ProjectStats};
+use crate::secrets;
 
 use super::EmbeddingService;
 use fold_qdrant::QdrantService;
@@ -96,7 +97,7 @@ impl ProjectService {
         .fetch_optional(&self.db)
         .await?;
 
-        Ok(project)
+        project.map(decrypted).transpose()
     }
 
     /// Get a project by slug.
@@ -110,7 +111,7 @@ impl ProjectService {
         .fetch_optional(&self.db)
         .await?;
 
-        Ok(project)
+        project.map(decrypted).transpose()
     }
 
     /// Get a project by ID or slug.
@@ -125,7 +126,7 @@ impl ProjectService {
         .fetch_optional(&self.db)
         .await?;
 
-        Ok(project)
+        project.map(decrypted).transpose()
     }
 
     /// List all projects.
@@ -138,7 +139,7 @@ impl ProjectService {
         .fetch_all(&self.db)
         .await?;
 
-        Ok(projects)
+        projects.into_iter().map(decrypted).collect()
     }
 
     /// List projects for a user (owner or team member).
@@ -157,7 +158,7 @@ impl ProjectService {
         .fetch_all(&self.db)
         .await?;
 
-        Ok(projects)
+        projects.into_iter().map(decrypted).collect()
     }
 
     /// Update a project.
@@ -452,6 +453,12 @@ impl ProjectService {
     }
 }
 
+/// Decrypt the stored metadata repository token, as `db::Project::decrypted` does.
+fn decrypted(mut project: Project) -> Result<Project> {
+    project.metadata_repo_token = secrets::decrypt_opt(project.metadata_repo_token.as_deref())?;
+    Ok(project)
+}
+
 /// Update request for a project
 #[derive(Debug, Clone, Default, serde::Deserialize)]
 pub struct ProjectUpdate {
@@ -0,0 +1,907 @@
+//! Project archive service.
+//!
+//! Exports a whole project as a portable, versioned JSONL archive and
+//! imports one as a new project. The first line is a manifest; every
+//! following line is one record tagged with its `kind`:
+//!
+//! - `memory` - memory row with its content resolved from fold/, plus its
+//!   vector when exported with vectors
+//! - `link` - memory link
+//! - `chunk` - code chunk, plus its vector when exported with vectors
+//! - `archived_memory` - retention archive record (the memory itself is a
+//!   `memory` record)
+//! - `session` - agent working session with its notes
+//!
+//! Imports keep record IDs unless they are already taken on this server,
+//! in which case they are remapped. Stored vectors are only reused when the
+//! archive was embedded with the model and dimension in use here; otherwise
+//! everything is re-embedded. Retention policies are not carried over.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::db::{self, AiSession, AiSessionNote, ArchivedMemory, DbPool, MemoryLink, UsageKind};
+use crate::error::{Error, Result};
+use crate::models::{Chunk, ChunkCreate, Memory};
+
+use super::indexer::chunk_vector_payload;
+use super::{FoldStorageService, MemoryService, QdrantService};
+
+/// Archive format identifier written to the manifest.
+pub const ARCHIVE_FORMAT: &str = "fold-project";
+
+/// Current archive format version. Newer archives are rejected.
+/// Version 2 added `session` records.
+pub const ARCHIVE_VERSION: u32 = 2;
+
+/// Rows read per page while exporting.
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// Points fetched from Qdrant per request while exporting vectors.
+const VECTOR_BATCH_SIZE: usize = 256;
+
+/// Texts embedded per request while importing chunks.
+const EMBED_BATCH_SIZE: usize = 32;
+
+// ============================================================================
+// Archive Format
+// ============================================================================
+
+/// First line of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub project: ArchivedProject,
+    pub embedding: ArchiveEmbedding,
+    /// Whether memory and chunk records carry vectors
+    pub vectors: bool,
+    pub counts: ArchiveCounts,
+}
+
+/// Project settings carried in an archive. Credentials and local paths are
+/// left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedProject {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub provider: String,
+    pub remote_owner: Option<String>,
+    pub remote_repo: Option<String>,
+    pub remote_branch: Option<String>,
+}
+
+/// Embedding model the archived vectors were produced with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveEmbedding {
+    /// `None` for hash-based placeholder vectors
+    pub model: Option<String>,
+    pub dimension: usize,
+}
+
+/// Number of records of each kind.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveCounts {
+    pub memories: usize,
+    pub links: usize,
+    pub chunks: usize,
+    pub archived_memories: usize,
+    #[serde(default)]
+    pub sessions: usize,
+}
+
+/// Memory with resolved content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    #[serde(flatten)]
+    pub memory: Memory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Code chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRecord {
+    #[serde(flatten)]
+    pub chunk: Chunk,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Agent working session with its notes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    #[serde(flatten)]
+    pub session: AiSession,
+    #[serde(default)]
+    pub notes: Vec<AiSessionNote>,
+}
+
+/// One line of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveRecord {
+    Manifest(ArchiveManifest),
+    Memory(MemoryRecord),
+    Link(MemoryLink),
+    Chunk(ChunkRecord),
+    ArchivedMemory(ArchivedMemory),
+    Session(SessionRecord),
+}
+
+/// A parsed archive, records grouped by kind.
+#[derive(Debug, Clone)]
+pub struct ParsedArchive {
+    pub manifest: ArchiveManifest,
+    pub memories: Vec<MemoryRecord>,
+    pub links: Vec<MemoryLink>,
+    pub chunks: Vec<ChunkRecord>,
+    pub archived_memories: Vec<ArchivedMemory>,
+    pub sessions: Vec<SessionRecord>,
+}
+
+/// Parse and validate a JSONL archive.
+pub fn parse_archive(data: &str) -> Result<ParsedArchive> {
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let parse_line = |number: usize, line: &str| {
+        serde_json::from_str::<ArchiveRecord>(line).map_err(|e| {
+            Error::Validation(format!(
+                "Invalid archive record on line {}: {}",
+                number + 1,
+                e
+            ))
+        })
+    };
+
+    let manifest = match lines.next() {
+        Some((number, line)) => match parse_line(number, line)? {
+            ArchiveRecord::Manifest(manifest) => manifest,
+            _ => {
+                return Err(Error::Validation(
+                    "Archive must start with a manifest".to_string(),
+                ))
+            }
+        },
+        None => return Err(Error::Validation("Archive is empty".to_string())),
+    };
+
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(Error::Validation(format!(
+            "Unknown archive format: {}",
+            manifest.format
+        )));
+    }
+    if manifest.version == 0 || manifest.version > ARCHIVE_VERSION {
+        return Err(Error::Validation(format!(
+            "Unsupported archive version {} (this server reads up to {})",
+            manifest.version, ARCHIVE_VERSION
+        )));
+    }
+
+    let mut archive = ParsedArchive {
+        manifest,
+        memories: Vec::new(),
+        links: Vec::new(),
+        chunks: Vec::new(),
+        archived_memories: Vec::new(),
+        sessions: Vec::new(),
+    };
+
+    for (number, line) in lines {
+        match parse_line(number, line)? {
+            ArchiveRecord::Manifest(_) => {
+                return Err(Error::Validation(format!(
+                    "Unexpected second manifest on line {}",
+                    number + 1
+                )))
+            }
+            ArchiveRecord::Memory(record) => archive.memories.push(record),
+            ArchiveRecord::Link(link) => archive.links.push(link),
+            ArchiveRecord::Chunk(record) => archive.chunks.push(record),
+            ArchiveRecord::ArchivedMemory(record) => archive.archived_memories.push(record),
+            ArchiveRecord::Session(record) => archive.sessions.push(record),
+        }
+    }
+
+    Ok(archive)
+}
+
+/// Old-to-new ID mapping built while importing.
+#[derive(Debug, Default)]
+pub struct IdRemap {
+    ids: HashMap<String, String>,
+    remapped: usize,
+}
+
+impl IdRemap {
+    /// Assign the ID an archived record is imported under, keeping the
+    /// original unless it is already taken.
+    pub fn assign(&mut self, old_id: &str, taken: bool) -> String {
+        let new_id = if taken {
+            self.remapped += 1;
+            crate::models::new_id()
+        } else {
+            old_id.to_string()
+        };
+        self.ids.insert(old_id.to_string(), new_id.clone());
+        new_id
+    }
+
+    /// The ID a record was imported under.
+    pub fn get(&self, old_id: &str) -> Option<&str> {
+        self.ids.get(old_id).map(String::as_str)
+    }
+
+    /// Whether a record was given a new ID.
+    pub fn was_remapped(&self, old_id: &str) -> bool {
+        self.get(old_id).is_some_and(|new_id| new_id != old_id)
+    }
+
+    /// Number of records given a new ID.
+    pub fn remapped(&self) -> usize {
+        self.remapped
+    }
+}
+
+/// Whether a memory's content lives in fold/ rather than SQLite.
+fn is_agent_memory(memory: &Memory) -> bool {
+    !matches!(memory.source.as_deref(), Some("file") | Some("git"))
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// Where an imported project is created.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// Slug for the new project (default: the archived slug)
+    pub slug: Option<String>,
+    /// Name for the new project (default: the archived name)
+    pub name: Option<String>,
+    /// Local path where the new project's fold/ lives
+    pub root_path: String,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportResult {
+    pub project_id: String,
+    pub project_slug: String,
+    pub memories: usize,
+    pub links: usize,
+    pub chunks: usize,
+    pub archived_memories: usize,
+    pub sessions: usize,
+    /// Records given a new ID because theirs was taken
+    pub remapped: usize,
+    /// Whether vectors were recomputed rather than taken from the archive
+    pub reembedded: bool,
+    /// Memories that could not be imported
+    pub failed: usize,
+}
+
+/// Service for exporting and importing whole projects.
+#[derive(Clone)]
+pub struct ProjectArchiveService {
+    db: DbPool,
+    memory: MemoryService,
+    qdrant: Arc<QdrantService>,
+    fold_storage: Arc<FoldStorageService>,
+}
+
+impl ProjectArchiveService {
+    /// Create a new project archive service.
+    pub fn new(
+        db: DbPool,
+        memory: MemoryService,
+        qdrant: Arc<QdrantService>,
+        fold_storage: Arc<FoldStorageService>,
+    ) -> Self {
+        Self {
+            db,
+            memory,
+            qdrant,
+            fold_storage,
+        }
+    }
+
+    /// Export a project as a JSONL archive.
+    pub async fn export(&self, project: &db::Project, include_vectors: bool) -> Result<String> {
+        let project_root = Path::new(&project.root_path);
+        let archived_ids: HashSet<String> = db::list_archived_memory_ids(&self.db, &project.id)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut memories = Vec::new();
+        loop {
+            let page = self
+                .memory
+                .list(
+                    &project.id,
+                    None,
+                    None,
+                    EXPORT_PAGE_SIZE,
+                    memories.len() as i64,
+                )
+                .await?;
+            let done = (page.len() as i64) < EXPORT_PAGE_SIZE;
+            memories.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        for memory in &mut memories {
+            if memory.content.as_deref().is_some_and(|c| !c.is_empty()) {
+                continue;
+            }
+            let read = if archived_ids.contains(&memory.id) {
+                self.fold_storage
+                    .read_archived_memory(project_root, &memory.id)
+                    .await
+            } else {
+                self.fold_storage
+                    .read_memory(project_root, &memory.id)
+                    .await
+            };
+            match read {
+                Ok((_, content)) => memory.content = Some(content),
+                Err(e) => {
+                    warn!(memory_id = %memory.id, error = %e, "Exporting memory without content")
+                }
+            }
+        }
+
+        let mut links = Vec::new();
+        loop {
+            let page =
+                db::list_project_links(&self.db, &project.id, EXPORT_PAGE_SIZE, links.len() as i64)
+                    .await?;
+            let done = (page.len() as i64) < EXPORT_PAGE_SIZE;
+            links.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        let chunks = db::get_chunks_for_project(&self.db, &project.id).await?;
+        let archived_memories =
+            db::list_archived_memories(&self.db, &project.id, i64::MAX, 0).await?;
+
+        let mut sessions = Vec::new();
+        for session in db::list_project_ai_sessions(&self.db, &project.id, i64::MAX, 0).await? {
+            let notes = db::list_session_notes(&self.db, &session.id).await?;
+            sessions.push(SessionRecord { session, notes });
+        }
+
+        // Vectors were produced with the project's own embedding provider
+        let embeddings = self.memory.embeddings_for(&project.id).await?;
+        let mut vectors = HashMap::new();
+        if include_vectors {
+            let (cold, live): (Vec<String>, Vec<String>) = memories
+                .iter()
+                .map(|m| m.id.clone())
+                .chain(chunks.iter().map(|c| c.id.clone()))
+                .partition(|id| archived_ids.contains(id));
+
+            for (ids, archived) in [(live, false), (cold, true)] {
+                for batch in ids.chunks(VECTOR_BATCH_SIZE) {
+                    vectors.extend(
+                        self.memory
+                            .stored_vectors(&project.slug, batch, archived)
+                            .await?,
+                    );
+                }
+            }
+        }
+
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            project: ArchivedProject {
+                id: project.id.clone(),
+                slug: project.slug.clone(),
+                name: project.name.clone(),
+                description: project.description.clone(),
+                provider: project.provider.clone(),
+                remote_owner: project.remote_owner.clone(),
+                remote_repo: project.remote_repo.clone(),
+                remote_branch: project.remote_branch.clone(),
+            },
+            embedding: ArchiveEmbedding {
+                model: embeddings.primary_model().await,
+                dimension: embeddings.dimension().await,
+            },
+            vectors: include_vectors,
+            counts: ArchiveCounts {
+                memories: memories.len(),
+                links: links.len(),
+                chunks: chunks.len(),
+                archived_memories: archived_memories.len(),
+                sessions: sessions.len(),
+            },
+        };
+
+        let mut records = vec![ArchiveRecord::Manifest(manifest)];
+        for memory in memories {
+            records.push(ArchiveRecord::Memory(MemoryRecord {
+                vector: vectors.remove(&memory.id),
+                memory,
+            }));
+        }
+        records.extend(links.into_iter().map(ArchiveRecord::Link));
+        for chunk in chunks {
+            records.push(ArchiveRecord::Chunk(ChunkRecord {
+                vector: vectors.remove(&chunk.id),
+                chunk,
+            }));
+        }
+        records.extend(
+            archived_memories
+                .into_iter()
+                .map(ArchiveRecord::ArchivedMemory),
+        );
+        records.extend(sessions.into_iter().map(ArchiveRecord::Session));
+
+        let mut output = String::new();
+        for record in records {
+            output.push_str(&serde_json::to_string(&record)?);
+            output.push('\n');
+        }
+
+        info!(project = %project.slug, vectors = include_vectors, "Exported project archive");
+
+        Ok(output)
+    }
+
+    /// Import a JSONL archive as a new project.
+    pub async fn import(&self, data: &str, options: ImportOptions) -> Result<ImportResult> {
+        let archive = parse_archive(data)?;
+        let manifest = &archive.manifest;
+
+        let project = db::create_project(
+            &self.db,
+            db::CreateProject {
+                id: crate::models::new_id(),
+                slug: options
+                    .slug
+                    .unwrap_or_else(|| manifest.project.slug.clone()),
+                name: options
+                    .name
+                    .unwrap_or_else(|| manifest.project.name.clone()),
+                description: manifest.project.description.clone(),
+                provider: manifest.project.provider.clone(),
+                root_path: options.root_path,
+                remote_owner: manifest.project.remote_owner.clone(),
+                remote_repo: manifest.project.remote_repo.clone(),
+                remote_branch: manifest.project.remote_branch.clone(),
+                access_token: None,
+            },
+        )
+        .await?;
+        let project_root = Path::new(&project.root_path);
+
+        let embeddings = self.memory.embeddings_for(&project.id).await?;
+        let current = ArchiveEmbedding {
+            model: embeddings.primary_model().await,
+            dimension: embeddings.dimension().await,
+        };
+        let reuse_vectors = manifest.vectors && manifest.embedding == current;
+
+        let mut result = ImportResult {
+            project_id: project.id.clone(),
+            project_slug: project.slug.clone(),
+            memories: 0,
+            links: 0,
+            chunks: 0,
+            archived_memories: 0,
+            sessions: 0,
+            remapped: 0,
+            reembedded: !reuse_vectors,
+            failed: 0,
+        };
+
+        // Assign IDs up front so links can be written into memory files
+        let mut ids = IdRemap::default();
+        for record in &archive.memories {
+            let taken = db::get_memory_optional(&self.db, &record.memory.id)
+                .await?
+                .is_some();
+            ids.assign(&record.memory.id, taken);
+        }
+
+        let archived_ids: HashSet<&str> = archive
+            .archived_memories
+            .iter()
+            .map(|a| a.memory_id.as_str())
+            .collect();
+
+        let mut related: HashMap<&str, Vec<String>> = HashMap::new();
+        for link in &archive.links {
+            if let (Some(_), Some(target)) = (ids.get(&link.source_id), ids.get(&link.target_id)) {
+                related
+                    .entry(link.source_id.as_str())
+                    .or_default()
+                    .push(target.to_string());
+            }
+        }
+
+        if archive.memories.iter().any(|r| is_agent_memory(&r.memory)) {
+            self.fold_storage
+                .init_fold_directory(project_root, &project.id, &project.slug, &project.name)
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to init fold directory: {}", e)))?;
+        }
+
+        let mut imported = HashSet::new();
+        for record in archive.memories {
+            let old_id = record.memory.id.clone();
+            let archived = archived_ids.contains(old_id.as_str());
+            let mut memory = record.memory;
+            memory.id = ids.get(&old_id).unwrap_or(&old_id).to_string();
+            memory.project_id = project.id.clone();
+            if ids.was_remapped(&old_id) {
+                // Slugs derive the original ID, so they cannot follow a remap
+                memory.slug = None;
+            }
+            let content = memory.content.take().unwrap_or_default();
+
+            if is_agent_memory(&memory) {
+                memory.content_storage = Some("fold".to_string());
+                let links = related.get(old_id.as_str()).cloned().unwrap_or_default();
+                if let Err(e) = self
+                    .write_memory_file(project_root, &memory, &content, &links, archived)
+                    .await
+                {
+                    warn!(memory_id = %old_id, error = %e, "Failed to write imported memory file");
+                    result.failed += 1;
+                    continue;
+                }
+            } else {
+                memory.content = Some(content.clone());
+            }
+
+            let vector = record.vector.filter(|_| reuse_vectors);
+            match self
+                .memory
+                .import_memory(&project.slug, &memory, &content, vector, archived)
+                .await
+            {
+                Ok(()) => {
+                    imported.insert(old_id);
+                    result.memories += 1;
+                }
+                Err(e) => {
+                    warn!(memory_id = %old_id, error = %e, "Failed to import memory");
+                    result.failed += 1;
+                }
+            }
+        }
+
+        for mut link in archive.links {
+            if !imported.contains(&link.source_id) || !imported.contains(&link.target_id) {
+                continue;
+            }
+            let taken = db::get_link(&self.db, &link.id).await.is_ok();
+            link.id = if taken {
+                crate::models::new_id()
+            } else {
+                link.id
+            };
+            link.source_id = ids.get(&link.source_id).unwrap_or_default().to_string();
+            link.target_id = ids.get(&link.target_id).unwrap_or_default().to_string();
+            link.project_id = project.id.clone();
+            if db::insert_link_record(&self.db, &link).await? {
+                result.links += 1;
+            }
+        }
+
+        result.chunks = self
+            .import_chunks(&project, archive.chunks, &ids, &imported, reuse_vectors)
+            .await?;
+
+        for record in archive.archived_memories {
+            if !imported.contains(&record.memory_id) {
+                continue;
+            }
+            db::create_archived_memory(
+                &self.db,
+                db::CreateArchivedMemory {
+                    memory_id: ids.get(&record.memory_id).unwrap_or_default().to_string(),
+                    project_id: project.id.clone(),
+                    policy_id: None,
+                    strength: record.strength,
+                    reason: record.reason,
+                },
+            )
+            .await?;
+            result.archived_memories += 1;
+        }
+
+        for record in archive.sessions {
+            if self.import_session(&project.id, record).await? {
+                result.sessions += 1;
+            }
+        }
+
+        result.remapped = ids.remapped();
+
+        info!(
+            project = %project.slug,
+            memories = result.memories,
+            links = result.links,
+            chunks = result.chunks,
+            sessions = result.sessions,
+            remapped = result.remapped,
+            reembedded = result.reembedded,
+            failed = result.failed,
+            "Imported project archive"
+        );
+
+        Ok(result)
+    }
+
+    /// Insert an imported agent session and its notes under the new project.
+    /// Returns whether the session was imported.
+    async fn import_session(&self, project_id: &str, record: SessionRecord) -> Result<bool> {
+        let mut session = record.session;
+        if db::get_ai_session(&self.db, &session.id).await.is_ok() {
+            session.id = crate::models::new_id();
+        }
+        session.project_id = project_id.to_string();
+        if !db::insert_ai_session_record(&self.db, &session).await? {
+            return Ok(false);
+        }
+
+        for mut note in record.notes {
+            if db::get_session_note(&self.db, &note.id).await.is_ok() {
+                note.id = crate::models::new_id();
+            }
+            note.session_id = session.id.clone();
+            db::insert_session_note_record(&self.db, &note).await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Write an imported agent memory to fold/ (or fold/archive/).
+    async fn write_memory_file(
+        &self,
+        project_root: &Path,
+        memory: &Memory,
+        content: &str,
+        related: &[String],
+        archived: bool,
+    ) -> Result<()> {
+        self.fold_storage
+            .write_memory_with_links(project_root, memory, content, related)
+            .await?;
+        if archived {
+            self.fold_storage
+                .archive_memory(project_root, &memory.id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Insert imported chunks and store their vectors. Returns the number
+    /// of chunks imported.
+    async fn import_chunks(
+        &self,
+        project: &db::Project,
+        records: Vec<ChunkRecord>,
+        ids: &IdRemap,
+        imported: &HashSet<String>,
+        reuse_vectors: bool,
+    ) -> Result<usize> {
+        let mut points = Vec::new();
+        let mut pending = Vec::new();
+
+        for record in records {
+            if !imported.contains(&record.chunk.memory_id) {
+                continue;
+            }
+            let chunk = record.chunk;
+            let created = db::insert_chunk(
+                &self.db,
+                ChunkCreate {
+                    memory_id: ids.get(&chunk.memory_id).unwrap_or_default().to_string(),
+                    project_id: project.id.clone(),
+                    content: chunk.content,
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    start_byte: chunk.start_byte,
+                    end_byte: chunk.end_byte,
+                    node_type: chunk.node_type,
+                    node_name: chunk.node_name,
+                    language: chunk.language,
+                },
+            )
+            .await?;
+
+            match record.vector.filter(|_| reuse_vectors) {
+                Some(vector) => {
+                    points.push((created.id.clone(), vector, chunk_vector_payload(&created)))
+                }
+                None => pending.push(created),
+            }
+        }
+
+        let count = points.len() + pending.len();
+        let embeddings = self.memory.embeddings_for(&project.id).await?;
+
+        if !pending.is_empty() {
+            let texts = pending.iter().map(|c| c.content.clone()).collect();
+            let batches = pending.len().div_ceil(EMBED_BATCH_SIZE) as i64;
+            let vectors = embeddings.embed_batch(texts, EMBED_BATCH_SIZE).await?;
+            super::rate_limit::record_usage(&self.db, &project.id, UsageKind::Embedding, batches)
+                .await;
+            points.extend(
+                pending
+                    .iter()
+                    .zip(vectors)
+                    .map(|(chunk, vector)| (chunk.id.clone(), vector, chunk_vector_payload(chunk))),
+            );
+        }
+
+        if !points.is_empty() {
+            self.qdrant
+                .create_collection(&project.slug, embeddings.dimension().await)
+                .await?;
+            self.qdrant.upsert_batch(&project.slug, points).await?;
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_line(version: u32) -> String {
+        serde_json::json!({
+            "kind": "manifest",
+            "format": ARCHIVE_FORMAT,
+            "version": version,
+            "exported_at": "2026-01-01T00:00:00Z",
+            "project": {
+                "id": "proj-1",
+                "slug": "test",
+                "name": "Test",
+                "description": null,
+                "provider": "local",
+                "remote_owner": null,
+                "remote_repo": null,
+                "remote_branch": null
+            },
+            "embedding": { "model": "nomic-embed-text", "dimension": 768 },
+            "vectors": true,
+            "counts": { "memories": 1, "links": 0, "chunks": 0, "archived_memories": 0 }
+        })
+        .to_string()
+    }
+
+    fn memory_line() -> String {
+        serde_json::json!({
+            "kind": "memory",
+            "id": "mem-1",
+            "project_id": "proj-1",
+            "type": "decision",
+            "source": "agent",
+            "content": "Use SQLite for metadata",
+            "content_hash": null,
+            "title": "Storage",
+            "author": null,
+            "keywords": null,
+            "tags": null,
+            "context": null,
+            "file_path": null,
+            "language": null,
+            "line_start": null,
+            "line_end": null,
+            "status": null,
+            "assignee": null,
+            "metadata": null,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+            "retrieval_count": 3,
+            "last_accessed": null,
+            "vector": [0.5, -0.25]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_archive_roundtrip() {
+        let data = format!("{}\n{}\n\n", manifest_line(1), memory_line());
+        let archive = parse_archive(&data).unwrap();
+
+        assert_eq!(archive.manifest.project.slug, "test");
+        assert_eq!(archive.memories.len(), 1);
+        let record = &archive.memories[0];
+        assert_eq!(record.memory.memory_type, "decision");
+        assert_eq!(record.memory.retrieval_count, 3);
+        assert_eq!(record.vector.as_deref(), Some(&[0.5, -0.25][..]));
+
+        // Re-serialising keeps the record kind and flattened fields
+        let line = serde_json::to_string(&ArchiveRecord::Memory(record.clone())).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["kind"], "memory");
+        assert_eq!(value["type"], "decision");
+    }
+
+    #[test]
+    fn test_parse_archive_sessions() {
+        let session = serde_json::json!({
+            "kind": "session",
+            "id": "sess-1",
+            "project_id": "proj-1",
+            "task": "Refactor storage",
+            "status": "completed",
+            "summary": "Moved metadata to SQLite",
+            "next_steps": "[\"Add indexes\"]",
+            "agent_type": "claude",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-02T00:00:00Z",
+            "ended_at": "2026-01-02T00:00:00Z",
+            "notes": [{
+                "id": "note-1",
+                "session_id": "sess-1",
+                "note_type": "decision",
+                "content": "Keep Qdrant for vectors",
+                "created_at": "2026-01-01T12:00:00Z"
+            }]
+        });
+        let data = format!("{}\n{}\n", manifest_line(2), session);
+        let archive = parse_archive(&data).unwrap();
+
+        // Older manifests have no session count
+        assert_eq!(archive.manifest.counts.sessions, 0);
+        assert_eq!(archive.sessions.len(), 1);
+        let record = &archive.sessions[0];
+        assert_eq!(record.session.status, "completed");
+        assert_eq!(record.session.next_steps_vec(), vec!["Add indexes"]);
+        assert_eq!(record.notes.len(), 1);
+        assert_eq!(record.notes[0].note_type, "decision");
+    }
+
+    #[test]
+    fn test_parse_archive_rejects_bad_input() {
+        assert!(parse_archive("").is_err());
+        assert!(parse_archive(&memory_line()).is_err());
+        assert!(parse_archive(&manifest_line(ARCHIVE_VERSION + 1)).is_err());
+
+        let data = format!("{}\n{}", manifest_line(1), manifest_line(1));
+        assert!(parse_archive(&data).is_err());
+    }
+
+    #[test]
+    fn test_id_remap() {
+        let mut ids = IdRemap::default();
+        assert_eq!(ids.assign("mem-1", false), "mem-1");
+        let new_id = ids.assign("mem-2", true);
+
+        assert_ne!(new_id, "mem-2");
+        assert_eq!(ids.get("mem-2"), Some(new_id.as_str()));
+        assert!(!ids.was_remapped("mem-1"));
+        assert!(ids.was_remapped("mem-2"));
+        assert!(ids.get("mem-3").is_none());
+        assert_eq!(ids.remapped(), 1);
+    }
+}
@@ -0,0 +1,195 @@
+//! Per-project LLM and embedding provider chains.
+//!
+//! A project may pin its own provider chain (for example a self-hosted Ollama
+//! for a client-confidential codebase). Projects without an override use the
+//! global chain. A pinned chain never falls back to the global providers, so
+//! a confidential project's content is only ever sent where it was pinned.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use crate::db::{self, DbPool, ProjectProviderKind};
+use crate::Result;
+
+use super::{EmbeddingService, LlmService};
+
+/// Resolved chains for one project.
+#[derive(Clone)]
+struct ProjectChains {
+    llm: Arc<LlmService>,
+    embeddings: Arc<EmbeddingService>,
+}
+
+/// Resolves the LLM and embedding services to use for a project.
+#[derive(Clone)]
+pub struct ProviderChainService {
+    db: DbPool,
+    llm: Arc<LlmService>,
+    embeddings: Arc<EmbeddingService>,
+    cache: Arc<RwLock<HashMap<String, ProjectChains>>>,
+}
+
+impl ProviderChainService {
+    /// Create a resolver over the global LLM and embedding services.
+    pub fn new(db: DbPool, llm: Arc<LlmService>, embeddings: Arc<EmbeddingService>) -> Self {
+        Self {
+            db,
+            llm,
+            embeddings,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// The LLM service for a project.
+    pub async fn llm_for(&self, project_id: &str) -> Result<Arc<LlmService>> {
+        Ok(self.resolve(project_id).await?.llm)
+    }
+
+    /// The embedding service for a project.
+    pub async fn embeddings_for(&self, project_id: &str) -> Result<Arc<EmbeddingService>> {
+        Ok(self.resolve(project_id).await?.embeddings)
+    }
+
+    /// Drop a project's cached chains after its overrides change.
+    pub async fn invalidate(&self, project_id: &str) {
+        self.cache.write().await.remove(project_id);
+    }
+
+    /// Drop all cached chains, e.g. after the global providers change.
+    pub async fn invalidate_all(&self) {
+        self.cache.write().await.clear();
+    }
+
+    async fn resolve(&self, project_id: &str) -> Result<ProjectChains> {
+        if let Some(chains) = self.cache.read().await.get(project_id) {
+            return Ok(chains.clone());
+        }
+
+        let llm_rows =
+            db::list_project_providers(&self.db, project_id, ProjectProviderKind::Llm).await?;
+        let embedding_rows =
+            db::list_project_providers(&self.db, project_id, ProjectProviderKind::Embedding)
+                .await?;
+
+        let llm = if llm_rows.is_empty() {
+            self.llm.clone()
+        } else {
+            Arc::new(LlmService::from_project_providers(llm_rows))
+        };
+        let embeddings = if embedding_rows.is_empty() {
+            self.embeddings.clone()
+        } else {
+            let default_dim = self.embeddings.dimension().await;
+            Arc::new(EmbeddingService::from_project_providers(
+                embedding_rows,
+                default_dim,
+            )?)
+        };
+
+        let chains = ProjectChains { llm, embeddings };
+        self.cache
+            .write()
+            .await
+            .insert(project_id.to_string(), chains.clone());
+
+        Ok(chains)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{EmbeddingConfig, LlmConfig};
+    use crate::db::{create_project, init_pool, migrate, CreateProject, ProjectProviderInput};
+    use serde_json::json;
+
+    async fn setup_test_db() -> DbPool {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn service(db: DbPool) -> ProviderChainService {
+        let llm = Arc::new(LlmService::from_config(&LlmConfig { providers: vec![] }));
+        let embeddings = Arc::new(
+            EmbeddingService::from_config(&EmbeddingConfig {
+                providers: vec![],
+                dimension: 384,
+            })
+            .unwrap(),
+        );
+        ProviderChainService::new(db, llm, embeddings)
+    }
+
+    #[tokio::test]
+    async fn test_project_override_replaces_global_chain() {
+        let db = setup_test_db().await;
+        let chains = service(db.clone());
+
+        // No override: the global services are shared
+        assert!(Arc::ptr_eq(
+            &chains.llm_for("proj-1").await.unwrap(),
+            &chains.llm
+        ));
+        assert!(chains
+            .llm_for("proj-1")
+            .await
+            .unwrap()
+            .providers()
+            .await
+            .is_empty());
+
+        db::replace_project_providers(
+            &db,
+            "proj-1",
+            ProjectProviderKind::Llm,
+            vec![ProjectProviderInput {
+                name: "ollama".to_string(),
+                priority: None,
+                api_key: None,
+                config: json!({ "endpoint": "http://localhost:11434/v1", "model": "llama3" }),
+            }],
+        )
+        .await
+        .unwrap();
+
+        // Cached until invalidated
+        assert!(chains
+            .llm_for("proj-1")
+            .await
+            .unwrap()
+            .providers()
+            .await
+            .is_empty());
+        chains.invalidate("proj-1").await;
+
+        let llm = chains.llm_for("proj-1").await.unwrap();
+        assert_eq!(llm.providers().await, vec!["ollama".to_string()]);
+        // The embedding chain is not overridden
+        assert!(Arc::ptr_eq(
+            &chains.embeddings_for("proj-1").await.unwrap(),
+            &chains.embeddings
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! LLM query rewriting for search.
+//!
+//! Short or vaguely worded queries often miss memories that use different
+//! vocabulary. Two rewrites help:
+//! - Expansion: the LLM writes a few paraphrases of the query.
+//! - HyDE: the LLM writes a short passage that would answer the query, and
+//!   that passage is embedded instead of the question.
+//!
+//! Every variant is searched separately and the result lists are fused with
+//! reciprocal rank fusion.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::db::SearchSettings;
+use crate::error::Result;
+use crate::models::{MemorySearchResult, VariantRank};
+
+use super::{ChatRequest, LlmService};
+
+/// Largest number of paraphrases a project may ask for.
+pub const MAX_EXPANSION_COUNT: i64 = 5;
+
+/// Rank offset for reciprocal rank fusion.
+const RRF_K: f32 = 60.0;
+
+/// Which rewrites to apply to a search query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryRewrite {
+    pub expand: bool,
+    pub hyde: bool,
+    pub expansion_count: usize,
+}
+
+impl QueryRewrite {
+    /// The project's configured rewrites.
+    pub fn from_settings(settings: &SearchSettings) -> Self {
+        Self {
+            expand: settings.is_query_expansion(),
+            hyde: settings.is_hyde(),
+            expansion_count: settings.expansion_count.clamp(1, MAX_EXPANSION_COUNT) as usize,
+        }
+    }
+
+    /// Apply per-request switches; `None` keeps the project setting.
+    pub fn with_overrides(mut self, expand: Option<bool>, hyde: Option<bool>) -> Self {
+        if let Some(expand) = expand {
+            self.expand = expand;
+        }
+        if let Some(hyde) = hyde {
+            self.hyde = hyde;
+        }
+        self
+    }
+
+    /// Check if any rewrite is applied.
+    pub fn is_enabled(&self) -> bool {
+        self.expand || self.hyde
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Expansion {
+    #[serde(default)]
+    queries: Vec<String>,
+}
+
+fn expansion_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "queries": {"type": "array", "items": {"type": "string"}}
+        },
+        "required": ["queries"]
+    })
+}
+
+/// Ask the LLM for paraphrases of a search query.
+pub async fn expand_query(llm: &LlmService, query: &str, count: usize) -> Result<Vec<String>> {
+    let prompt = format!(
+        r#"Rewrite this search query for a software project's knowledge base.
+
+Write {} alternative queries that would find the same information. Use different
+vocabulary: synonyms, likely function or module names, and the technical terms a
+developer would use. Keep each query under 15 words.
+
+Query: {}"#,
+        count, query
+    );
+
+    let request = ChatRequest::prompt(prompt)
+        .with_max_tokens(300)
+        .with_json_schema("query_expansion", expansion_schema());
+
+    let expansion: Expansion = llm.chat_json(&request).await?;
+    let original = query.trim().to_lowercase();
+
+    let mut queries: Vec<String> = Vec::with_capacity(count);
+    for q in expansion.queries {
+        let q = q.trim().to_string();
+        if q.is_empty() || q.to_lowercase() == original || queries.contains(&q) {
+            continue;
+        }
+        queries.push(q);
+    }
+    queries.truncate(count);
+
+    Ok(queries)
+}
+
+/// Ask the LLM for a hypothetical passage answering a search query.
+pub async fn hypothetical_document(llm: &LlmService, query: &str) -> Result<String> {
+    let prompt = format!(
+        r#"Write a short passage (3-5 sentences) from a software project's documentation,
+code comments or design notes that answers the question below. Be concrete and use
+the terminology the project would likely use. Do not mention that it is hypothetical.
+
+Question: {}"#,
+        query
+    );
+
+    let request = ChatRequest::prompt(prompt).with_max_tokens(300);
+    Ok(llm.chat(&request).await?.content.trim().to_string())
+}
+
+/// Fuse result lists from several query variants.
+///
+/// Memories are ordered by reciprocal rank fusion, so a memory found by
+/// several variants ranks above one found by a single variant. Each memory
+/// keeps its best scores and the union of its matched chunks, and its score
+/// breakdown records where each variant ranked it.
+///
+/// `lists` holds one result list per entry in `variants`.
+pub fn fuse(
+    variants: &[String],
+    lists: Vec<Vec<MemorySearchResult>>,
+    limit: usize,
+) -> Vec<MemorySearchResult> {
+    let mut fused: HashMap<String, (f32, MemorySearchResult)> = HashMap::new();
+
+    for (variant, list) in lists.into_iter().enumerate() {
+        for (rank, mut result) in list.into_iter().enumerate() {
+            let rrf = 1.0 / (RRF_K + rank as f32 + 1.0);
+            if let Some(breakdown) = result.breakdown.as_mut() {
+                breakdown.rerank.push(VariantRank {
+                    variant,
+                    query: variants.get(variant).cloned().unwrap_or_default(),
+                    rank: rank + 1,
+                    contribution: rrf,
+                });
+            }
+
+            match fused.get_mut(&result.memory.id) {
+                Some((score, existing)) => {
+                    *score += rrf;
+                    merge_result(existing, result);
+                }
+                None => {
+                    fused.insert(result.memory.id.clone(), (rrf, result));
+                }
+            }
+        }
+    }
+
+    let mut results: Vec<(f32, MemorySearchResult)> = fused.into_values().collect();
+    results.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
+                b.1.combined_score
+                    .partial_cmp(&a.1.combined_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+    results.truncate(limit);
+
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn merge_result(existing: &mut MemorySearchResult, other: MemorySearchResult) {
+    existing.score = existing.score.max(other.score);
+    existing.combined_score = existing.combined_score.max(other.combined_score);
+
+    if let (Some(existing), Some(other)) = (existing.breakdown.as_mut(), other.breakdown) {
+        existing.rerank.extend(other.rerank);
+        existing.vector_score = match (existing.vector_score, other.vector_score) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    for chunk in other.matched_chunks {
+        match existing
+            .matched_chunks
+            .iter_mut()
+            .find(|c| c.id == chunk.id)
+        {
+            Some(c) => c.score = c.score.max(chunk.score),
+            None => existing.matched_chunks.push(chunk),
+        }
+    }
+    existing.matched_chunks.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkMatch, Memory, MemoryType, ScoreBreakdown};
+
+    fn result(id: &str, score: f32, chunks: &[(&str, f32)]) -> MemorySearchResult {
+        let memory =
+            Memory::new_with_id(id.to_string(), "proj-1".to_string(), MemoryType::Codebase);
+        let chunks = chunks
+            .iter()
+            .map(|(chunk_id, chunk_score)| ChunkMatch {
+                id: chunk_id.to_string(),
+                node_type: "function".to_string(),
+                node_name: None,
+                start_line: 1,
+                end_line: 10,
+                score: *chunk_score,
+                snippet: None,
+            })
+            .collect();
+        MemorySearchResult::with_chunks(memory, score, 0.5, score, chunks)
+    }
+
+    fn variants(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("query {}", i)).collect()
+    }
+
+    #[test]
+    fn test_fuse_ranks_agreement_first() {
+        let original = vec![result("a", 0.9, &[]), result("b", 0.8, &[])];
+        let expanded = vec![result("b", 0.85, &[]), result("c", 0.7, &[])];
+
+        let fused = fuse(&variants(2), vec![original, expanded], 10);
+        let ids: Vec<&str> = fused.iter().map(|r| r.memory.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["b", "a", "c"]);
+        assert!((fused[0].score - 0.85).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_fuse_merges_chunks_and_truncates() {
+        let first = vec![result("a", 0.9, &[("c1", 0.6)])];
+        let second = vec![
+            result("a", 0.7, &[("c1", 0.8), ("c2", 0.5)]),
+            result("b", 0.6, &[]),
+        ];
+
+        let fused = fuse(&variants(2), vec![first, second], 1);
+
+        assert_eq!(fused.len(), 1);
+        let chunks = &fused[0].matched_chunks;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].id, "c1");
+        assert!((chunks[0].score - 0.8).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_fuse_records_variant_ranks() {
+        let with_breakdown =
+            |id, score| result(id, score, &[]).with_breakdown(ScoreBreakdown::default());
+        let original = vec![with_breakdown("a", 0.9), with_breakdown("b", 0.8)];
+        let hyde = vec![with_breakdown("b", 0.85)];
+
+        let fused = fuse(&variants(2), vec![original, hyde], 10);
+        let b = fused.iter().find(|r| r.memory.id == "b").unwrap();
+        let rerank = &b.breakdown.as_ref().unwrap().rerank;
+
+        assert_eq!(rerank.len(), 2);
+        assert_eq!((rerank[0].variant, rerank[0].rank), (0, 2));
+        assert_eq!((rerank[1].variant, rerank[1].rank), (1, 1));
+        assert_eq!(rerank[1].query, "query 1");
+    }
+
+    #[test]
+    fn test_overrides() {
+        let settings = SearchSettings {
+            expansion_count: 9,
+            query_expansion: 1,
+            ..SearchSettings::defaults("proj-1")
+        };
+        let rewrite = QueryRewrite::from_settings(&settings);
+        assert!(rewrite.expand);
+        assert!(!rewrite.hyde);
+        assert_eq!(rewrite.expansion_count, MAX_EXPANSION_COUNT as usize);
+
+        let rewrite = rewrite.with_overrides(Some(false), Some(true));
+        assert!(!rewrite.expand);
+        assert!(rewrite.hyde);
+        assert!(rewrite.is_enabled());
+    }
+}
@@ -0,0 +1,321 @@
+//! Rate limiting and provider quotas.
+//!
+//! Two separate protections against runaway clients:
+//! - Per-token request buckets for each route class (search, write, LLM-backed),
+//!   so one agent stuck in a loop cannot starve everyone else.
+//! - Daily per-project quotas on embedding and LLM calls, so a loop cannot
+//!   burn paid provider credit. Usage is counted where the calls are made.
+//!
+//! Both surface as 429 responses with a `Retry-After` header.
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use governor::clock::{Clock, DefaultClock};
+use governor::state::keyed::DefaultKeyedStateStore;
+use governor::{Quota, RateLimiter};
+use tracing::warn;
+
+use crate::config::RateLimitConfig;
+use crate::db::{self, DbPool, UsageKind};
+use crate::error::{Error, Result};
+
+type KeyedLimiter = RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>;
+
+/// Drop idle keys once a bucket map grows past this many entries.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Class of route a request belongs to. Each class has its own bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteClass {
+    /// Semantic search and context retrieval (embeds the query)
+    Search,
+    /// Memory create, update and delete (embeds the memory and may call the
+    /// LLM for metadata, evolution and linking)
+    Write,
+    /// Requests that call the LLM synchronously
+    Llm,
+}
+
+impl RouteClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Write => "write",
+            Self::Llm => "llm",
+        }
+    }
+
+    /// Provider quotas a request of this class draws on.
+    pub fn usage_kinds(&self) -> &'static [UsageKind] {
+        match self {
+            Self::Search => &[UsageKind::Embedding],
+            Self::Write | Self::Llm => &[UsageKind::Embedding, UsageKind::Llm],
+        }
+    }
+}
+
+/// Resolve the daily limit for a kind: a project override wins over the
+/// configured default, and 0 means unlimited.
+pub fn effective_limit(
+    config: &RateLimitConfig,
+    quota: Option<&db::ProjectQuota>,
+    kind: UsageKind,
+) -> Option<i64> {
+    let default = match kind {
+        UsageKind::Embedding => config.daily_embedding_calls,
+        UsageKind::Llm => config.daily_llm_calls,
+    };
+    let limit = quota.and_then(|q| q.limit(kind)).unwrap_or(default);
+    (limit > 0).then_some(limit)
+}
+
+/// Seconds until the next UTC midnight, when daily quotas reset.
+fn secs_until_reset() -> u64 {
+    let now = Utc::now();
+    let tomorrow = (now + ChronoDuration::days(1))
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    (tomorrow - now).num_seconds().max(1) as u64
+}
+
+/// Check a project's daily quota against the configured limits.
+///
+/// For provider calls made outside the request path (background jobs), which
+/// no request check has covered.
+pub(crate) async fn check_quota(db: &DbPool, project_id: &str, kind: UsageKind) -> Result<()> {
+    quota_check(db, &crate::config::config().rate_limit, project_id, kind).await
+}
+
+async fn quota_check(
+    db: &DbPool,
+    config: &RateLimitConfig,
+    project_id: &str,
+    kind: UsageKind,
+) -> Result<()> {
+    let quota = db::get_project_quota(db, project_id).await?;
+    let Some(limit) = effective_limit(config, quota.as_ref(), kind) else {
+        return Ok(());
+    };
+
+    let usage = db::get_usage_today(db, project_id).await?;
+    if usage.calls(kind) >= limit {
+        warn!(
+            project_id,
+            kind = kind.as_str(),
+            limit,
+            "Daily quota exceeded"
+        );
+        return Err(Error::QuotaExceeded {
+            quota: format!("{} call", kind.as_str()),
+            retry_after_secs: secs_until_reset(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Count provider calls against a project's daily quota.
+///
+/// Accounting failures are logged and never fail the operation.
+pub(crate) async fn record_usage(db: &DbPool, project_id: &str, kind: UsageKind, count: i64) {
+    if let Err(e) = db::record_usage(db, project_id, kind, count).await {
+        warn!(error = %e, project_id, kind = kind.as_str(), "Failed to record provider usage");
+    }
+}
+
+fn limiter(per_minute: u32) -> Option<KeyedLimiter> {
+    NonZeroU32::new(per_minute).map(|n| RateLimiter::keyed(Quota::per_minute(n)))
+}
+
+/// Service for request rate limits and provider quotas.
+#[derive(Clone)]
+pub struct RateLimitService {
+    db: DbPool,
+    config: RateLimitConfig,
+    search: Option<Arc<KeyedLimiter>>,
+    write: Option<Arc<KeyedLimiter>>,
+    llm: Option<Arc<KeyedLimiter>>,
+}
+
+impl RateLimitService {
+    /// Create a new rate limit service. A bucket size of 0 disables that class.
+    pub fn new(db: DbPool, config: RateLimitConfig) -> Self {
+        let enabled = config.enabled;
+        let bucket = |per_minute| {
+            if enabled {
+                limiter(per_minute).map(Arc::new)
+            } else {
+                None
+            }
+        };
+
+        Self {
+            search: bucket(config.search_per_minute),
+            write: bucket(config.write_per_minute),
+            llm: bucket(config.llm_per_minute),
+            db,
+            config,
+        }
+    }
+
+    /// Take one request from the caller's bucket for a route class.
+    ///
+    /// `key` identifies the caller, e.g. `token:<id>` or `user:<id>`.
+    pub fn check(&self, class: RouteClass, key: &str) -> Result<()> {
+        let limiter = match class {
+            RouteClass::Search => &self.search,
+            RouteClass::Write => &self.write,
+            RouteClass::Llm => &self.llm,
+        };
+        let Some(limiter) = limiter else {
+            return Ok(());
+        };
+
+        if limiter.len() > MAX_TRACKED_KEYS {
+            limiter.retain_recent();
+        }
+
+        limiter.check_key(&key.to_string()).map_err(|not_until| {
+            let wait = not_until.wait_time_from(DefaultClock::default().now());
+            warn!(key, class = class.as_str(), "Rate limit exceeded");
+            Error::RateLimited {
+                retry_after_secs: wait.as_secs().max(1),
+            }
+        })
+    }
+
+    /// Check that a project has not used up today's quota for a kind.
+    pub async fn check_quota(&self, project_id: &str, kind: UsageKind) -> Result<()> {
+        quota_check(&self.db, &self.config, project_id, kind).await
+    }
+
+    /// Check a request against the caller's bucket and every quota its
+    /// class draws on.
+    pub async fn check_request(
+        &self,
+        class: RouteClass,
+        key: Option<&str>,
+        project_id: Option<&str>,
+    ) -> Result<()> {
+        if let Some(key) = key {
+            self.check(class, key)?;
+        }
+
+        if let Some(project_id) = project_id {
+            for kind in class.usage_kinds() {
+                self.check_quota(project_id, *kind).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Configured limits, for reporting.
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: true,
+            search_per_minute: 2,
+            write_per_minute: 0,
+            llm_per_minute: 1,
+            daily_embedding_calls: 1000,
+            daily_llm_calls: 0,
+        }
+    }
+
+    #[test]
+    fn test_effective_limit() {
+        let config = config();
+        assert_eq!(
+            effective_limit(&config, None, UsageKind::Embedding),
+            Some(1000)
+        );
+        assert_eq!(effective_limit(&config, None, UsageKind::Llm), None);
+
+        let quota = db::ProjectQuota {
+            project_id: "proj-1".to_string(),
+            daily_embedding_calls: Some(0),
+            daily_llm_calls: Some(50),
+            updated_at: String::new(),
+        };
+        assert_eq!(
+            effective_limit(&config, Some(&quota), UsageKind::Embedding),
+            None
+        );
+        assert_eq!(
+            effective_limit(&config, Some(&quota), UsageKind::Llm),
+            Some(50)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buckets_are_per_key_and_class() {
+        let pool = db::init_pool(":memory:").await.unwrap();
+        let service = RateLimitService::new(pool, config());
+
+        assert!(service.check(RouteClass::Search, "token:a").is_ok());
+        assert!(service.check(RouteClass::Search, "token:a").is_ok());
+        let err = service.check(RouteClass::Search, "token:a").unwrap_err();
+        assert!(err.retry_after().is_some_and(|secs| secs >= 1));
+
+        // Other callers and classes have their own buckets
+        assert!(service.check(RouteClass::Search, "token:b").is_ok());
+        assert!(service.check(RouteClass::Llm, "token:a").is_ok());
+
+        // A bucket size of 0 disables the class
+        for _ in 0..10 {
+            assert!(service.check(RouteClass::Write, "token:a").is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_background_quota_check() {
+        let pool = db::init_pool(":memory:").await.unwrap();
+        db::migrate(&pool).await.unwrap();
+        db::create_project(
+            &pool,
+            db::CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+        db::set_project_quota(&pool, "proj-1", Some(0), Some(1))
+            .await
+            .unwrap();
+
+        assert!(check_quota(&pool, "proj-1", UsageKind::Llm).await.is_ok());
+        record_usage(&pool, "proj-1", UsageKind::Llm, 1).await;
+        let err = check_quota(&pool, "proj-1", UsageKind::Llm)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::QuotaExceeded { .. }));
+
+        // 0 means unlimited
+        record_usage(&pool, "proj-1", UsageKind::Embedding, 50).await;
+        assert!(check_quota(&pool, "proj-1", UsageKind::Embedding)
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,311 @@
+//! Retention service.
+//!
+//! Evaluates a project's retention policies against each memory's decay
+//! strength and age, and moves memories that fall below a policy into cold
+//! storage. A matching `never_archive` policy always wins, so a project can
+//! say "archive weak agent memories after 180 days, but never decisions".
+//!
+//! Archived memories keep their SQLite row but leave the live vector index
+//! and fold/ tree, so default search no longer returns them.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::db::{self, DbPool, DecayModel, FeedbackCounts, RetentionPolicy};
+use crate::error::Result;
+use crate::models::Memory;
+
+use super::decay::{ACCESS_BATCH_SIZE, ACCESS_HISTORY_LIMIT};
+use super::MemoryService;
+
+/// What the policies say about one memory.
+#[derive(Debug, Clone, Copy)]
+pub enum RetentionDecision<'a> {
+    /// No policy fires
+    Keep,
+    /// A `never_archive` policy matches
+    Protect,
+    /// Archive under this policy
+    Archive(&'a RetentionPolicy),
+}
+
+/// A memory selected for archival.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveCandidate {
+    pub memory_id: String,
+    pub title: Option<String>,
+    pub memory_type: String,
+    pub strength: f64,
+    pub age_days: i64,
+    pub policy_id: String,
+    pub policy_name: String,
+    /// Whether the memory was actually moved (false for dry runs and failures)
+    pub archived: bool,
+}
+
+/// Outcome of a retention run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub evaluated: usize,
+    pub protected: usize,
+    pub archived: usize,
+    pub failed: usize,
+    pub candidates: Vec<ArchiveCandidate>,
+}
+
+/// Check whether a policy applies to a memory of this type and source.
+pub fn policy_matches(policy: &RetentionPolicy, memory_type: &str, source: &str) -> bool {
+    policy.is_enabled()
+        && policy
+            .memory_type
+            .as_deref()
+            .is_none_or(|t| t == memory_type)
+        && policy.source.as_deref().is_none_or(|s| s == source)
+}
+
+/// Check whether an archive policy's thresholds are met.
+pub fn should_archive(policy: &RetentionPolicy, strength: f64, age_days: i64) -> bool {
+    age_days >= policy.min_age_days && policy.max_strength.is_none_or(|max| strength < max)
+}
+
+/// Decide what to do with a memory. Protection beats archival, and among
+/// archive policies the first (oldest) one that fires is reported.
+pub fn evaluate_policies<'a>(
+    policies: &'a [RetentionPolicy],
+    memory_type: &str,
+    source: &str,
+    strength: f64,
+    age_days: i64,
+) -> RetentionDecision<'a> {
+    let matching: Vec<&RetentionPolicy> = policies
+        .iter()
+        .filter(|p| policy_matches(p, memory_type, source))
+        .collect();
+
+    if matching.iter().any(|p| p.is_never_archive()) {
+        return RetentionDecision::Protect;
+    }
+
+    matching
+        .into_iter()
+        .find(|p| should_archive(p, strength, age_days))
+        .map_or(RetentionDecision::Keep, RetentionDecision::Archive)
+}
+
+/// Service for applying retention policies.
+#[derive(Clone)]
+pub struct RetentionService {
+    db: DbPool,
+    memory: MemoryService,
+}
+
+impl RetentionService {
+    /// Create a new retention service.
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self { db, memory }
+    }
+
+    /// Evaluate a project's policies and archive the memories they select.
+    ///
+    /// With `dry_run`, candidates are reported but nothing is moved.
+    pub async fn run(&self, project_id: &str, dry_run: bool) -> Result<RetentionReport> {
+        let project = db::get_project(&self.db, project_id).await?;
+        let policies = db::list_retention_policies(&self.db, project_id).await?;
+
+        let mut report = RetentionReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        if !policies
+            .iter()
+            .any(|p| p.is_enabled() && !p.is_never_archive())
+        {
+            return Ok(report);
+        }
+
+        // Merged memories are already out of the index
+        let memories = sqlx::query_as::<_, Memory>(
+            r#"
+            SELECT * FROM memories
+            WHERE project_id = ?
+              AND id NOT IN (SELECT memory_id FROM archived_memories)
+              AND id NOT IN (SELECT memory_id FROM merged_memories)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        // Same decay model and per-type half-lives as search ranking
+        let config = self.memory.scoring_config(&project).await?;
+        let now = Utc::now();
+        let feedback = db::get_project_feedback_counts(&self.db, project_id).await?;
+        let no_feedback = FeedbackCounts::default();
+        let mut accesses = HashMap::new();
+        if config.model == DecayModel::PowerLaw {
+            let ids: Vec<String> = memories.iter().map(|m| m.id.clone()).collect();
+            for batch in ids.chunks(ACCESS_BATCH_SIZE) {
+                accesses
+                    .extend(db::get_memory_accesses(&self.db, batch, ACCESS_HISTORY_LIMIT).await?);
+            }
+        }
+
+        for memory in memories {
+            report.evaluated += 1;
+
+            let (strength, _) = config.strength(
+                &memory.memory_type,
+                memory.updated_at,
+                feedback.get(&memory.id).unwrap_or(&no_feedback),
+                accesses
+                    .get(&memory.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                now,
+            );
+            let age_days = now.signed_duration_since(memory.created_at).num_days();
+            let source = memory.source.as_deref().unwrap_or("agent");
+
+            let policy =
+                match evaluate_policies(&policies, &memory.memory_type, source, strength, age_days)
+                {
+                    RetentionDecision::Keep => continue,
+                    RetentionDecision::Protect => {
+                        report.protected += 1;
+                        continue;
+                    }
+                    RetentionDecision::Archive(policy) => policy,
+                };
+
+            let mut candidate = ArchiveCandidate {
+                memory_id: memory.id.clone(),
+                title: memory.title.clone(),
+                memory_type: memory.memory_type.clone(),
+                strength,
+                age_days,
+                policy_id: policy.id.clone(),
+                policy_name: policy.name.clone(),
+                archived: false,
+            };
+
+            if !dry_run {
+                let result = self
+                    .memory
+                    .archive(
+                        project_id,
+                        &project.slug,
+                        &memory.id,
+                        db::CreateArchivedMemory {
+                            memory_id: memory.id.clone(),
+                            project_id: project_id.to_string(),
+                            policy_id: Some(policy.id.clone()),
+                            strength: Some(strength),
+                            reason: Some(format!(
+                                "Policy '{}': strength {:.3}, {} days old",
+                                policy.name, strength, age_days
+                            )),
+                        },
+                    )
+                    .await;
+
+                match result {
+                    Ok(_) => {
+                        candidate.archived = true;
+                        report.archived += 1;
+                    }
+                    Err(e) => {
+                        warn!(memory_id = %memory.id, error = %e, "Failed to archive memory");
+                        report.failed += 1;
+                    }
+                }
+            }
+
+            report.candidates.push(candidate);
+        }
+
+        info!(
+            project_id,
+            dry_run,
+            evaluated = report.evaluated,
+            archived = report.archived,
+            candidates = report.candidates.len(),
+            "Retention run finished"
+        );
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(
+        id: &str,
+        memory_type: Option<&str>,
+        source: Option<&str>,
+        max_strength: Option<f64>,
+        min_age_days: i64,
+        never_archive: bool,
+    ) -> RetentionPolicy {
+        RetentionPolicy {
+            id: id.to_string(),
+            project_id: "proj-1".to_string(),
+            name: id.to_string(),
+            memory_type: memory_type.map(String::from),
+            source: source.map(String::from),
+            max_strength,
+            min_age_days,
+            never_archive: never_archive as i32,
+            enabled: 1,
+            created_at: "2026-01-01 00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_policies() {
+        let policies = vec![
+            policy("weak-agent", None, Some("agent"), Some(0.05), 180, false),
+            policy("keep-decisions", Some("decision"), None, None, 0, true),
+        ];
+
+        // Weak and old agent memory is archived
+        assert!(matches!(
+            evaluate_policies(&policies, "general", "agent", 0.02, 200),
+            RetentionDecision::Archive(p) if p.id == "weak-agent"
+        ));
+        // Too young or too strong
+        assert!(matches!(
+            evaluate_policies(&policies, "general", "agent", 0.02, 30),
+            RetentionDecision::Keep
+        ));
+        assert!(matches!(
+            evaluate_policies(&policies, "general", "agent", 0.5, 400),
+            RetentionDecision::Keep
+        ));
+        // Other sources are not covered
+        assert!(matches!(
+            evaluate_policies(&policies, "codebase", "file", 0.01, 400),
+            RetentionDecision::Keep
+        ));
+        // Decisions are never archived
+        assert!(matches!(
+            evaluate_policies(&policies, "decision", "agent", 0.01, 400),
+            RetentionDecision::Protect
+        ));
+
+        // Disabled policies are ignored
+        let mut disabled = policies.clone();
+        disabled[1].enabled = 0;
+        assert!(matches!(
+            evaluate_policies(&disabled, "decision", "agent", 0.01, 400),
+            RetentionDecision::Archive(p) if p.id == "weak-agent"
+        ));
+    }
+}
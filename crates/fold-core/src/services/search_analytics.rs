@@ -0,0 +1,379 @@
+//! Search query logging, analytics and knowledge gap reports.
+//!
+//! Every search is logged with its filters, result count, best raw score and
+//! latency. Analytics summarise the log over a window: frequent queries,
+//! queries that found nothing useful, and the memories returned most often.
+//!
+//! A knowledge gap report groups the poorly served queries into topics with
+//! the LLM and suggests memories to write for each. Without an LLM every
+//! poor query is reported as its own gap.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::db::{
+    self, DbPool, KnowledgeGapReportRow, LogSearchQuery, QueryStat, RetrievedMemory,
+    SearchLogTotals, UsageKind,
+};
+use crate::error::Result;
+
+use super::{ChatRequest, MemoryService};
+
+/// Default analytics window in days.
+pub const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+/// Longest analytics window a caller may ask for.
+pub const MAX_WINDOW_DAYS: i64 = 365;
+
+/// Default window for knowledge gap reports in days.
+pub const DEFAULT_GAP_WINDOW_DAYS: i64 = 7;
+
+/// Best raw similarity below which a search counts as poorly served.
+pub const LOW_SCORE_THRESHOLD: f64 = 0.5;
+
+/// Days search log entries are kept.
+pub const LOG_RETENTION_DAYS: i64 = 90;
+
+/// Most poor queries considered for one gap report.
+const MAX_GAP_QUERIES: i64 = 100;
+
+/// Search analytics over a window.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchAnalytics {
+    pub window_days: i64,
+    pub low_score_threshold: f64,
+    #[serde(flatten)]
+    pub totals: SearchLogTotals,
+    pub top_queries: Vec<QueryStat>,
+    /// Queries that found nothing, or nothing above the threshold
+    pub poor_queries: Vec<QueryStat>,
+    pub top_memories: Vec<RetrievedMemory>,
+}
+
+/// A topic the project's memories do not cover well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeGap {
+    pub topic: String,
+    pub queries: Vec<String>,
+    /// Total searches for the gap's queries in the window
+    pub searches: i64,
+    /// What memory to write to close the gap
+    pub suggestion: String,
+    pub suggested_type: Option<String>,
+}
+
+/// A knowledge gap report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapReport {
+    pub window_days: i64,
+    /// Whether the LLM grouped the queries into topics
+    pub clustered: bool,
+    pub poor_queries: usize,
+    pub gaps: Vec<KnowledgeGap>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Clusters {
+    #[serde(default)]
+    gaps: Vec<Cluster>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cluster {
+    topic: String,
+    #[serde(default)]
+    queries: Vec<String>,
+    #[serde(default)]
+    suggestion: String,
+    suggested_type: Option<String>,
+}
+
+fn clusters_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "gaps": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "topic": {"type": "string"},
+                        "queries": {"type": "array", "items": {"type": "string"}},
+                        "suggestion": {"type": "string"},
+                        "suggested_type": {
+                            "type": "string",
+                            "enum": ["spec", "decision", "task", "general", "session"]
+                        }
+                    },
+                    "required": ["topic", "queries", "suggestion"]
+                }
+            }
+        },
+        "required": ["gaps"]
+    })
+}
+
+/// Service for the search query log and the reports built from it.
+#[derive(Clone)]
+pub struct SearchAnalyticsService {
+    db: DbPool,
+    memory: MemoryService,
+}
+
+impl SearchAnalyticsService {
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self { db, memory }
+    }
+
+    /// Log a search in the background. Logging never fails the search.
+    pub fn log(&self, entry: LogSearchQuery) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            if let Err(e) = db::log_search_query(&db, entry).await {
+                warn!(error = %e, "Failed to log search query");
+            }
+        });
+    }
+
+    /// Summarise a project's searches over the last `days` days.
+    pub async fn analytics(
+        &self,
+        project_id: &str,
+        days: i64,
+        limit: i64,
+        low_score: f64,
+    ) -> Result<SearchAnalytics> {
+        let days = days.clamp(1, MAX_WINDOW_DAYS);
+        let limit = limit.clamp(1, 100);
+
+        Ok(SearchAnalytics {
+            window_days: days,
+            low_score_threshold: low_score,
+            totals: db::get_search_log_totals(&self.db, project_id, days, low_score).await?,
+            top_queries: db::list_top_queries(&self.db, project_id, days, limit).await?,
+            poor_queries: db::list_poor_queries(&self.db, project_id, days, low_score, limit)
+                .await?,
+            top_memories: db::list_top_retrieved_memories(&self.db, project_id, days, limit)
+                .await?,
+        })
+    }
+
+    /// Build a knowledge gap report from the last `days` days of searches.
+    ///
+    /// With `cluster` set and an LLM available, poor queries are grouped
+    /// into topics. Otherwise, or if clustering fails, each query is its
+    /// own gap.
+    pub async fn knowledge_gaps(
+        &self,
+        project_id: &str,
+        days: i64,
+        cluster: bool,
+    ) -> Result<GapReport> {
+        let days = days.clamp(1, MAX_WINDOW_DAYS);
+        let poor = db::list_poor_queries(
+            &self.db,
+            project_id,
+            days,
+            LOW_SCORE_THRESHOLD,
+            MAX_GAP_QUERIES,
+        )
+        .await?;
+
+        let mut clustered = false;
+        let mut gaps = Vec::new();
+        if cluster && !poor.is_empty() {
+            match self.cluster_queries(project_id, &poor).await {
+                Ok(Some(found)) => {
+                    clustered = true;
+                    gaps = found;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(project_id, error = %e, "Gap clustering failed, reporting queries individually");
+                }
+            }
+        }
+
+        if !clustered {
+            gaps = poor.iter().map(single_query_gap).collect();
+        }
+
+        Ok(GapReport {
+            window_days: days,
+            clustered,
+            poor_queries: poor.len(),
+            gaps,
+        })
+    }
+
+    /// Build and store a knowledge gap report.
+    pub async fn run_gap_report(
+        &self,
+        project_id: &str,
+        days: i64,
+        cluster: bool,
+        job_id: Option<&str>,
+    ) -> Result<KnowledgeGapReportRow> {
+        let report = self.knowledge_gaps(project_id, days, cluster).await?;
+
+        db::create_knowledge_gap_report(
+            &self.db,
+            db::CreateKnowledgeGapReport {
+                id: crate::models::new_id(),
+                project_id: project_id.to_string(),
+                job_id: job_id.map(String::from),
+                window_days: report.window_days,
+                clustered: report.clustered,
+                report: serde_json::to_value(&report)?,
+            },
+        )
+        .await
+    }
+
+    /// Group poor queries into topics with the LLM. Returns `None` when no
+    /// LLM is available.
+    async fn cluster_queries(
+        &self,
+        project_id: &str,
+        poor: &[QueryStat],
+    ) -> Result<Option<Vec<KnowledgeGap>>> {
+        let llm = self.memory.llm_for(project_id).await?;
+        if !llm.is_available().await {
+            return Ok(None);
+        }
+
+        let listing = poor
+            .iter()
+            .map(|q| format!("- {} ({} searches)", q.query, q.count))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = format!(
+            r#"These searches against a software project's knowledge base found nothing
+useful. Group them into topics the knowledge base is missing. For each topic give a
+short name, the queries (copied exactly) that belong to it, and one sentence on what
+memory should be written to answer them.
+
+Queries:
+{}"#,
+            listing
+        );
+
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(1500)
+            .with_json_schema("knowledge_gaps", clusters_schema());
+        let clusters: Clusters = llm.chat_json(&request).await?;
+        super::rate_limit::record_usage(&self.db, project_id, UsageKind::Llm, 1).await;
+
+        Ok(Some(assign_clusters(poor, clusters.gaps)))
+    }
+}
+
+/// A gap for a single poorly served query.
+fn single_query_gap(query: &QueryStat) -> KnowledgeGap {
+    KnowledgeGap {
+        topic: query.query.clone(),
+        queries: vec![query.query.clone()],
+        searches: query.count,
+        suggestion: format!("Write a memory that answers \"{}\"", query.query),
+        suggested_type: None,
+    }
+}
+
+/// Turn LLM clusters into gaps. Queries the LLM invented are dropped, each
+/// query belongs to its first cluster only, and queries left out of every
+/// cluster become gaps of their own. Gaps are ordered by total searches.
+fn assign_clusters(poor: &[QueryStat], clusters: Vec<Cluster>) -> Vec<KnowledgeGap> {
+    let by_query: HashMap<String, &QueryStat> = poor
+        .iter()
+        .map(|q| (q.query.trim().to_lowercase(), q))
+        .collect();
+    let mut assigned: HashSet<String> = HashSet::new();
+
+    let mut gaps = Vec::new();
+    for cluster in clusters {
+        let mut queries = Vec::new();
+        let mut searches = 0;
+        for query in cluster.queries {
+            let key = query.trim().to_lowercase();
+            if let Some(stat) = by_query.get(&key) {
+                if assigned.insert(key) {
+                    queries.push(stat.query.clone());
+                    searches += stat.count;
+                }
+            }
+        }
+        if queries.is_empty() {
+            continue;
+        }
+
+        gaps.push(KnowledgeGap {
+            topic: cluster.topic.trim().to_string(),
+            queries,
+            searches,
+            suggestion: cluster.suggestion.trim().to_string(),
+            suggested_type: cluster.suggested_type,
+        });
+    }
+
+    for query in poor {
+        if !assigned.contains(&query.query.trim().to_lowercase()) {
+            gaps.push(single_query_gap(query));
+        }
+    }
+
+    gaps.sort_by_key(|g| std::cmp::Reverse(g.searches));
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(query: &str, count: i64) -> QueryStat {
+        QueryStat {
+            query: query.to_string(),
+            count,
+            zero_results: count,
+            avg_top_score: None,
+            last_seen: "2026-01-01 00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_assign_clusters() {
+        let poor = vec![
+            stat("billing webhooks", 4),
+            stat("stripe retries", 2),
+            stat("oauth scopes", 1),
+        ];
+        let clusters = vec![
+            Cluster {
+                topic: "Billing".to_string(),
+                queries: vec![
+                    "Billing Webhooks".to_string(),
+                    "stripe retries".to_string(),
+                    "invented query".to_string(),
+                ],
+                suggestion: "Document the billing webhook flow".to_string(),
+                suggested_type: Some("spec".to_string()),
+            },
+            Cluster {
+                topic: "Duplicate".to_string(),
+                queries: vec!["stripe retries".to_string()],
+                suggestion: String::new(),
+                suggested_type: None,
+            },
+        ];
+
+        let gaps = assign_clusters(&poor, clusters);
+        assert_eq!(gaps.len(), 2);
+        assert_eq!(gaps[0].topic, "Billing");
+        assert_eq!(gaps[0].queries, vec!["billing webhooks", "stripe retries"]);
+        assert_eq!(gaps[0].searches, 6);
+        // Left out by the LLM, so reported on its own
+        assert_eq!(gaps[1].queries, vec!["oauth scopes"]);
+    }
+}
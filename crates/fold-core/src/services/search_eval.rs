@@ -0,0 +1,362 @@
+//! Search quality evaluation against golden query sets.
+//!
+//! A golden set lists queries with the memories (by ID) or files (by path)
+//! each should find. An evaluation runs every query through the real search
+//! pipeline and scores the top `k` results with binary relevance:
+//! - recall@k: share of expected targets found in the top k
+//! - MRR: mean reciprocal rank of the first relevant result
+//! - nDCG@k: rank-discounted gain against the ideal ordering
+//!
+//! Search runs here do not update access tracking, so evaluating does not
+//! shift the strengths being evaluated.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, DbPool, GoldenQuery, Project, SearchEvaluation};
+use crate::error::{Error, Result};
+use crate::models::MemorySearchResult;
+
+use super::decay::{project_decay_config, DecayConfig};
+use super::MemoryService;
+
+/// Default cut-off rank for metrics.
+pub const DEFAULT_K: usize = 10;
+
+/// Largest cut-off rank a caller may ask for.
+pub const MAX_K: usize = 50;
+
+/// Scoring settings an evaluation runs with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EvalConfig {
+    /// Weight for retrieval strength vs semantic similarity (0.0-1.0)
+    pub strength_weight: f64,
+    /// Half-life in days for memory decay
+    pub decay_half_life_days: f64,
+    /// Also search code chunks
+    #[serde(default)]
+    pub include_chunks: bool,
+}
+
+impl EvalConfig {
+    /// The project's current settings.
+    pub fn for_project(project: &Project) -> Self {
+        let decay = project_decay_config(project);
+        Self {
+            strength_weight: decay.strength_weight,
+            decay_half_life_days: decay.half_life_days,
+            include_chunks: false,
+        }
+    }
+
+    fn decay(&self) -> DecayConfig {
+        DecayConfig::new(self.decay_half_life_days, self.strength_weight)
+    }
+}
+
+/// Metrics for one golden query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryMetrics {
+    pub query_id: String,
+    pub query: String,
+    pub recall: f64,
+    pub reciprocal_rank: f64,
+    pub ndcg: f64,
+    /// Expected memory IDs and file paths missing from the top k
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+}
+
+/// Metrics for a whole golden set under one config.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub set_id: String,
+    pub k: usize,
+    pub config: EvalConfig,
+    pub query_count: usize,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+    pub queries: Vec<QueryMetrics>,
+}
+
+/// Difference between two reports (`b - a`).
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDelta {
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg: f64,
+}
+
+/// Two configs evaluated on the same golden set.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalComparison {
+    pub a: EvalReport,
+    pub b: EvalReport,
+    pub delta: MetricDelta,
+}
+
+/// Service for evaluating search quality.
+#[derive(Clone)]
+pub struct SearchEvalService {
+    db: DbPool,
+    memory: MemoryService,
+}
+
+impl SearchEvalService {
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self { db, memory }
+    }
+
+    /// Evaluate a golden set under several configs.
+    ///
+    /// Each query is embedded once and searched under every config, so the
+    /// reports differ only in scoring.
+    pub async fn evaluate(
+        &self,
+        project: &Project,
+        set_id: &str,
+        configs: &[EvalConfig],
+        k: usize,
+    ) -> Result<Vec<EvalReport>> {
+        let set = db::get_golden_set(&self.db, set_id).await?;
+        if set.project_id != project.id {
+            return Err(Error::NotFound(format!("Golden set not found: {}", set_id)));
+        }
+
+        let queries = db::list_golden_queries(&self.db, set_id).await?;
+        if queries.is_empty() {
+            return Err(Error::Validation(
+                "Golden set has no queries to evaluate".into(),
+            ));
+        }
+
+        let mut per_config: Vec<Vec<QueryMetrics>> = vec![Vec::new(); configs.len()];
+        for golden in &queries {
+            let embedding = self.memory.embed_query(&project.id, &golden.query).await?;
+
+            for (config, metrics) in configs.iter().zip(per_config.iter_mut()) {
+                let results = self
+                    .memory
+                    .search_with_decay(
+                        &project.id,
+                        &project.slug,
+                        embedding.clone(),
+                        k,
+                        config.include_chunks,
+                        &config.decay(),
+                    )
+                    .await?;
+                metrics.push(score_query(golden, &results, k));
+            }
+        }
+
+        Ok(configs
+            .iter()
+            .zip(per_config)
+            .map(|(config, queries)| report(set_id, k, *config, queries))
+            .collect())
+    }
+
+    /// Evaluate two configs side by side.
+    pub async fn compare(
+        &self,
+        project: &Project,
+        set_id: &str,
+        a: EvalConfig,
+        b: EvalConfig,
+        k: usize,
+    ) -> Result<EvalComparison> {
+        let mut reports = self.evaluate(project, set_id, &[a, b], k).await?;
+        let b = reports.pop().expect("two reports");
+        let a = reports.pop().expect("two reports");
+
+        Ok(EvalComparison {
+            delta: MetricDelta {
+                recall_at_k: b.recall_at_k - a.recall_at_k,
+                mrr: b.mrr - a.mrr,
+                ndcg: b.ndcg - a.ndcg,
+            },
+            a,
+            b,
+        })
+    }
+
+    /// Evaluate a golden set with the project's current settings and record
+    /// the result in the evaluation history.
+    pub async fn run(
+        &self,
+        project: &Project,
+        set_id: &str,
+        k: usize,
+        job_id: Option<&str>,
+    ) -> Result<SearchEvaluation> {
+        let config = EvalConfig::for_project(project);
+        let report = self
+            .evaluate(project, set_id, &[config], k)
+            .await?
+            .pop()
+            .expect("one report");
+
+        db::create_search_evaluation(
+            &self.db,
+            db::CreateSearchEvaluation {
+                id: crate::models::new_id(),
+                project_id: project.id.clone(),
+                set_id: set_id.to_string(),
+                job_id: job_id.map(String::from),
+                k: k as i64,
+                config: serde_json::to_value(report.config)?,
+                query_count: report.query_count as i64,
+                recall_at_k: report.recall_at_k,
+                mrr: report.mrr,
+                ndcg: report.ndcg,
+                details: serde_json::to_value(&report.queries)?,
+            },
+        )
+        .await
+    }
+}
+
+/// Average per-query metrics into a report.
+fn report(set_id: &str, k: usize, config: EvalConfig, queries: Vec<QueryMetrics>) -> EvalReport {
+    let n = queries.len().max(1) as f64;
+    EvalReport {
+        set_id: set_id.to_string(),
+        k,
+        config,
+        query_count: queries.len(),
+        recall_at_k: queries.iter().map(|q| q.recall).sum::<f64>() / n,
+        mrr: queries.iter().map(|q| q.reciprocal_rank).sum::<f64>() / n,
+        ndcg: queries.iter().map(|q| q.ndcg).sum::<f64>() / n,
+        queries,
+    }
+}
+
+/// Score ranked results against a golden query's expectations.
+///
+/// A result is relevant when it matches an expected memory ID or file path
+/// not already matched higher up, so several memories for one expected file
+/// count once.
+fn score_query(golden: &GoldenQuery, results: &[MemorySearchResult], k: usize) -> QueryMetrics {
+    let expected_ids = golden.expected_memory_ids_vec();
+    let expected_paths = golden.expected_file_paths_vec();
+    let expected = expected_ids.len() + expected_paths.len();
+
+    let mut found: HashSet<String> = HashSet::new();
+    let mut first_relevant = None;
+    let mut dcg = 0.0;
+
+    for (rank, result) in results.iter().take(k).enumerate() {
+        let memory = &result.memory;
+        let target = if expected_ids.contains(&memory.id) {
+            Some(memory.id.clone())
+        } else {
+            memory
+                .file_path
+                .as_ref()
+                .filter(|path| expected_paths.contains(path))
+                .cloned()
+        };
+
+        if let Some(target) = target {
+            if found.insert(target) {
+                first_relevant.get_or_insert(rank);
+                dcg += 1.0 / (rank as f64 + 2.0).log2();
+            }
+        }
+    }
+
+    let ideal: f64 = (0..expected.min(k))
+        .map(|rank| 1.0 / (rank as f64 + 2.0).log2())
+        .sum();
+
+    let missing = expected_ids
+        .iter()
+        .chain(expected_paths.iter())
+        .filter(|target| !found.contains(*target))
+        .cloned()
+        .collect();
+
+    QueryMetrics {
+        query_id: golden.id.clone(),
+        query: golden.query.clone(),
+        recall: if expected > 0 {
+            found.len() as f64 / expected as f64
+        } else {
+            0.0
+        },
+        reciprocal_rank: first_relevant.map_or(0.0, |rank| 1.0 / (rank as f64 + 1.0)),
+        ndcg: if ideal > 0.0 { dcg / ideal } else { 0.0 },
+        missing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Memory, MemoryType};
+
+    fn golden(ids: &[&str], paths: &[&str]) -> GoldenQuery {
+        GoldenQuery {
+            id: "q-1".to_string(),
+            set_id: "set-1".to_string(),
+            query: "retries".to_string(),
+            expected_memory_ids: serde_json::to_string(ids).unwrap(),
+            expected_file_paths: serde_json::to_string(paths).unwrap(),
+            created_at: String::new(),
+        }
+    }
+
+    fn result(id: &str, path: Option<&str>) -> MemorySearchResult {
+        let mut memory =
+            Memory::new_with_id(id.to_string(), "proj-1".to_string(), MemoryType::Codebase);
+        memory.file_path = path.map(String::from);
+        MemorySearchResult::new(memory, 0.8)
+    }
+
+    #[test]
+    fn test_perfect_ranking() {
+        let golden = golden(&["a"], &["src/retry.rs"]);
+        let results = vec![result("a", None), result("b", Some("src/retry.rs"))];
+
+        let metrics = score_query(&golden, &results, 10);
+        assert_eq!(metrics.recall, 1.0);
+        assert_eq!(metrics.reciprocal_rank, 1.0);
+        assert!((metrics.ndcg - 1.0).abs() < 1e-9);
+        assert!(metrics.missing.is_empty());
+    }
+
+    #[test]
+    fn test_late_and_missing_hits() {
+        let golden = golden(&["a", "z"], &[]);
+        let results = vec![result("x", None), result("y", None), result("a", None)];
+
+        let metrics = score_query(&golden, &results, 10);
+        assert_eq!(metrics.recall, 0.5);
+        assert!((metrics.reciprocal_rank - 1.0 / 3.0).abs() < 1e-9);
+        // DCG 1/log2(4) = 0.5 against ideal 1 + 1/log2(3)
+        let ideal = 1.0 + 1.0 / 3f64.log2();
+        assert!((metrics.ndcg - 0.5 / ideal).abs() < 1e-9);
+        assert_eq!(metrics.missing, vec!["z"]);
+
+        // Outside the cut-off
+        let metrics = score_query(&golden, &results, 2);
+        assert_eq!(metrics.recall, 0.0);
+        assert_eq!(metrics.reciprocal_rank, 0.0);
+    }
+
+    #[test]
+    fn test_file_counts_once() {
+        let golden = golden(&[], &["src/retry.rs"]);
+        let results = vec![
+            result("a", Some("src/retry.rs")),
+            result("b", Some("src/retry.rs")),
+        ];
+
+        let metrics = score_query(&golden, &results, 10);
+        assert_eq!(metrics.recall, 1.0);
+        assert!((metrics.ndcg - 1.0).abs() < 1e-9);
+    }
+}
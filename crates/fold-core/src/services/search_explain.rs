@@ -0,0 +1,285 @@
+//! Search result explanations.
+//!
+//! With `explain=true`, search says why each result ranked where it did:
+//! the raw vector score, the chunk that matched, the decay inputs, the
+//! link-neighbour boost, each query variant's rank and how much each part
+//! added to the combined score. The search as a whole reports how many
+//! candidates each filter removed.
+
+use serde::Serialize;
+
+use crate::db::{self, DbPool};
+use crate::error::Result;
+use crate::models::{MemorySearchResult, ScoreBreakdown, VariantRank};
+
+use super::QueryRewrite;
+
+/// Most links listed per explained result.
+pub const MAX_EXPLAINED_NEIGHBOURS: usize = 5;
+
+/// Why one result ranked where it did.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultExplanation {
+    /// 1-based position in the returned results
+    pub rank: usize,
+    /// Similarity of the memory's own vector (absent when only a chunk matched)
+    pub vector_score: Option<f32>,
+    /// Relevance used for ranking: the vector score, else the best chunk score
+    pub relevance: f32,
+    /// Best matching chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_chunk: Option<ExplainedChunk>,
+    pub decay: DecayInputs,
+    pub links: LinkInfluence,
+    /// How much each part added to the combined score
+    pub contributions: ScoreContributions,
+    /// Rank in each query variant's results (rewritten search only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rerank: Vec<VariantRank>,
+}
+
+/// The chunk that matched, with its line range.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainedChunk {
+    pub id: String,
+    pub node_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    pub start_line: i32,
+    pub end_line: i32,
+    pub score: f32,
+}
+
+/// What retrieval strength was computed from.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecayInputs {
+    pub model: String,
+    /// Absent when the memory's type does not decay
+    pub half_life_days: Option<f32>,
+    pub age_days: f32,
+    /// Times the memory has been returned or read
+    pub retrieval_count: i32,
+    pub decay: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation: Option<f32>,
+    pub access_boost: f32,
+    pub feedback_penalty: f32,
+    pub strength: f32,
+}
+
+/// How links to and from the memory affected its score.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkInfluence {
+    pub link_rank: f32,
+    pub link_boost: f32,
+    /// Linked memories, newest links first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub neighbours: Vec<LinkNeighbour>,
+}
+
+/// A memory linked to an explained result.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkNeighbour {
+    pub memory_id: String,
+    pub link_type: String,
+    /// "incoming" or "outgoing"
+    pub direction: &'static str,
+}
+
+/// Parts of the combined score.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreContributions {
+    /// (1 - strength_weight) * relevance
+    pub relevance: f32,
+    /// strength_weight * strength
+    pub strength: f32,
+    /// Link rank boost
+    pub links: f32,
+    pub combined: f32,
+}
+
+/// Effects of one post-search filter.
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterEffect {
+    pub filter: String,
+    pub removed: usize,
+}
+
+/// How a search's candidates became its results.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchExplanation {
+    /// Results from the vector search before filtering
+    pub candidates: usize,
+    pub filters: Vec<FilterEffect>,
+    /// Results dropped by the limit after filtering
+    pub truncated: usize,
+    /// Sort key of the returned results
+    pub ranked_by: &'static str,
+    pub expand: bool,
+    pub hyde: bool,
+}
+
+impl SearchExplanation {
+    pub fn new(candidates: usize, ranked_by: &'static str, rewrite: &QueryRewrite) -> Self {
+        Self {
+            candidates,
+            filters: Vec::new(),
+            truncated: 0,
+            ranked_by,
+            expand: rewrite.expand,
+            hyde: rewrite.hyde,
+        }
+    }
+
+    /// Record that a filter was applied, even if it removes nothing.
+    pub fn applied(&mut self, filter: &str) {
+        if !self.filters.iter().any(|f| f.filter == filter) {
+            self.filters.push(FilterEffect {
+                filter: filter.to_string(),
+                removed: 0,
+            });
+        }
+    }
+
+    /// Record a candidate removed by a filter.
+    pub fn removed(&mut self, filter: &str) {
+        self.applied(filter);
+        if let Some(effect) = self.filters.iter_mut().find(|f| f.filter == filter) {
+            effect.removed += 1;
+        }
+    }
+}
+
+impl ResultExplanation {
+    /// Explain a scored result. Rank and link neighbours are filled in once
+    /// the final order is known, see [`explain_links`].
+    pub fn from_result(result: &MemorySearchResult) -> Self {
+        let breakdown = result.breakdown.clone().unwrap_or_else(|| ScoreBreakdown {
+            vector_score: Some(result.score),
+            ..Default::default()
+        });
+
+        let weight = breakdown.strength_weight.clamp(0.0, 1.0);
+        let relevance = (1.0 - weight) * result.score.clamp(0.0, 1.0);
+        let strength = weight * result.strength.clamp(0.0, 1.0);
+
+        Self {
+            rank: 0,
+            vector_score: breakdown.vector_score,
+            relevance: result.score,
+            matched_chunk: result.matched_chunks.first().map(|c| ExplainedChunk {
+                id: c.id.clone(),
+                node_type: c.node_type.clone(),
+                node_name: c.node_name.clone(),
+                start_line: c.start_line,
+                end_line: c.end_line,
+                score: c.score,
+            }),
+            decay: DecayInputs {
+                model: breakdown.model,
+                half_life_days: breakdown.half_life_days,
+                age_days: breakdown.age_days,
+                retrieval_count: result.memory.retrieval_count,
+                decay: breakdown.decay,
+                activation: breakdown.activation,
+                access_boost: breakdown.access_boost,
+                feedback_penalty: breakdown.feedback_penalty,
+                strength: result.strength,
+            },
+            links: LinkInfluence {
+                link_rank: breakdown.link_rank,
+                link_boost: breakdown.link_boost,
+                neighbours: Vec::new(),
+            },
+            contributions: ScoreContributions {
+                relevance,
+                strength,
+                links: breakdown.link_boost,
+                combined: result.combined_score,
+            },
+            rerank: breakdown.rerank,
+        }
+    }
+}
+
+/// Set an explanation's rank and load the memory's link neighbours.
+pub async fn explain_links(
+    pool: &DbPool,
+    explanation: &mut ResultExplanation,
+    memory_id: &str,
+    rank: usize,
+) -> Result<()> {
+    explanation.rank = rank;
+    explanation.links.neighbours = db::list_memory_links(pool, memory_id)
+        .await?
+        .into_iter()
+        .take(MAX_EXPLAINED_NEIGHBOURS)
+        .map(|link| {
+            if link.target_id == memory_id {
+                LinkNeighbour {
+                    memory_id: link.source_id,
+                    link_type: link.link_type,
+                    direction: "incoming",
+                }
+            } else {
+                LinkNeighbour {
+                    memory_id: link.target_id,
+                    link_type: link.link_type,
+                    direction: "outgoing",
+                }
+            }
+        })
+        .collect();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkMatch, Memory, MemoryType};
+
+    #[test]
+    fn test_contributions_sum_to_combined() {
+        let memory =
+            Memory::new_with_id("a".to_string(), "proj-1".to_string(), MemoryType::Codebase);
+        let chunk = ChunkMatch {
+            id: "c1".to_string(),
+            node_type: "function".to_string(),
+            node_name: Some("parse".to_string()),
+            start_line: 10,
+            end_line: 42,
+            score: 0.8,
+            snippet: None,
+        };
+        let result = MemorySearchResult::with_chunks(memory, 0.8, 0.5, 0.76, vec![chunk])
+            .with_breakdown(ScoreBreakdown {
+                strength_weight: 0.3,
+                link_boost: 0.05,
+                ..Default::default()
+            });
+
+        let explanation = ResultExplanation::from_result(&result);
+        let c = &explanation.contributions;
+        assert!((c.relevance + c.strength + c.links - c.combined).abs() < 0.001);
+        assert_eq!(explanation.vector_score, None);
+        let chunk = explanation.matched_chunk.unwrap();
+        assert_eq!((chunk.start_line, chunk.end_line), (10, 42));
+    }
+
+    #[test]
+    fn test_filter_effects() {
+        let rewrite = QueryRewrite {
+            expand: false,
+            hyde: false,
+            expansion_count: 0,
+        };
+        let mut explanation = SearchExplanation::new(5, "relevance", &rewrite);
+        explanation.applied("min_score");
+        explanation.removed("source");
+        explanation.removed("source");
+
+        assert_eq!(explanation.filters.len(), 2);
+        assert_eq!(explanation.filters[0].removed, 0);
+        assert_eq!(explanation.filters[1].removed, 2);
+    }
+}
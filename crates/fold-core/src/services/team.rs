@@ -0,0 +1,361 @@
+//! Team presence service.
+//!
+//! Tracks who is working on what in each project so agents can check for
+//! overlapping work ("alice is currently editing src/auth.rs") before
+//! touching the same files. Presence changes are broadcast as SSE events
+//! and a background sweeper ages out members who stop sending updates.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::db::{self, DbPool, TeamStatus, UpdateTeamStatus};
+use crate::error::{Error, Result};
+
+use super::EventBroadcaster;
+
+/// An active team member already working on a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConflict {
+    pub file_path: String,
+    pub username: String,
+    pub current_task: Option<String>,
+    pub last_seen: String,
+}
+
+/// Service for team presence.
+#[derive(Clone)]
+pub struct TeamService {
+    db: DbPool,
+    events: Arc<EventBroadcaster>,
+}
+
+impl TeamService {
+    /// Create a new team service.
+    pub fn new(db: DbPool, events: Arc<EventBroadcaster>) -> Self {
+        Self { db, events }
+    }
+
+    /// Record a member's status, task and files, and broadcast the change.
+    pub async fn update_status(
+        &self,
+        project_id: &str,
+        username: &str,
+        input: UpdateTeamStatus,
+    ) -> Result<TeamStatus> {
+        if username.trim().is_empty() {
+            return Err(Error::Validation("Username cannot be empty".into()));
+        }
+
+        let input = UpdateTeamStatus {
+            current_files: input
+                .current_files
+                .map(|files| files.iter().map(|f| normalise_path(f)).collect()),
+            ..input
+        };
+
+        let status = db::upsert_team_status(&self.db, project_id, username, input).await?;
+
+        self.events.team_status_changed(
+            &status.project_id,
+            &status.username,
+            &status.status,
+            status.current_task.as_deref(),
+            status.current_files_vec(),
+        );
+
+        Ok(status)
+    }
+
+    /// Get a member's presence.
+    pub async fn get(&self, project_id: &str, username: &str) -> Result<TeamStatus> {
+        db::get_team_status(&self.db, project_id, username)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Team member: {}", username)))
+    }
+
+    /// List members of a project, optionally only those currently active.
+    pub async fn list(&self, project_id: &str, active_only: bool) -> Result<Vec<TeamStatus>> {
+        if active_only {
+            db::list_active_team_members(&self.db, project_id).await
+        } else {
+            db::list_team_status(&self.db, project_id).await
+        }
+    }
+
+    /// The name a user's presence is published under.
+    pub async fn presence_name(&self, user_id: &str) -> Result<String> {
+        let user = db::get_user(&self.db, user_id).await?;
+        Ok(user.display_name.or(user.email).unwrap_or(user.id))
+    }
+
+    /// Whose presence a caller may change: their own, or any member's for an
+    /// admin. Defaults to the caller's own when no username is given.
+    pub async fn acting_member(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        username: Option<&str>,
+    ) -> Result<String> {
+        let own = self.presence_name(user_id).await?;
+        match username.map(str::trim).filter(|u| !u.is_empty()) {
+            Some(username) if username != own && !is_admin => Err(Error::Forbidden),
+            Some(username) => Ok(username.to_string()),
+            None => Ok(own),
+        }
+    }
+
+    /// Remove a member from the presence list and broadcast it.
+    pub async fn leave(&self, project_id: &str, username: &str) -> Result<()> {
+        // Ensure the member exists so callers get a 404 rather than a silent no-op
+        self.get(project_id, username).await?;
+        db::delete_team_status(&self.db, project_id, username).await?;
+        self.events.team_member_left(project_id, username);
+        Ok(())
+    }
+
+    /// Find active members (other than `exclude_username`) whose current
+    /// files overlap with `files`.
+    pub async fn find_conflicts(
+        &self,
+        project_id: &str,
+        files: &[String],
+        exclude_username: Option<&str>,
+    ) -> Result<Vec<FileConflict>> {
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let wanted: HashSet<String> = files.iter().map(|f| normalise_path(f)).collect();
+        let members = db::list_active_team_members(&self.db, project_id).await?;
+
+        Ok(members
+            .iter()
+            .filter(|m| Some(m.username.as_str()) != exclude_username)
+            .flat_map(|m| {
+                m.current_files_vec()
+                    .into_iter()
+                    .filter(|f| wanted.contains(&normalise_path(f)))
+                    .map(|file_path| FileConflict {
+                        file_path,
+                        username: m.username.clone(),
+                        current_task: m.current_task.clone(),
+                        last_seen: m.last_seen.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    /// Mark members not seen for `idle_minutes` as idle and broadcast each
+    /// change. Returns the number of members aged out.
+    pub async fn age_out_idle(&self, idle_minutes: i64) -> Result<usize> {
+        let stale = db::list_stale_team_members(&self.db, idle_minutes).await?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        db::mark_stale_members_idle(&self.db, idle_minutes).await?;
+
+        for member in &stale {
+            self.events.team_status_changed(
+                &member.project_id,
+                &member.username,
+                db::TeamMemberStatus::Idle.as_str(),
+                member.current_task.as_deref(),
+                member.current_files_vec(),
+            );
+        }
+
+        debug!(count = stale.len(), "Aged out idle team members");
+        Ok(stale.len())
+    }
+
+    /// Spawn the background task that periodically ages out idle members.
+    pub fn start_presence_sweeper(
+        &self,
+        idle_minutes: i64,
+        interval_secs: u64,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            info!(idle_minutes, "Team presence sweeper started");
+            loop {
+                sleep(Duration::from_secs(interval_secs)).await;
+                if let Err(e) = service.age_out_idle(idle_minutes).await {
+                    warn!(error = %e, "Failed to age out idle team members");
+                }
+            }
+        })
+    }
+}
+
+/// Normalise a file path for comparison (strip leading "./", use "/").
+fn normalise_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_project, init_pool, migrate, CreateProject, TeamMemberStatus};
+    use crate::services::events::FoldEvent;
+
+    async fn setup() -> (TeamService, Arc<EventBroadcaster>) {
+        let pool = init_pool(":memory:").await.unwrap();
+        migrate(&pool).await.unwrap();
+
+        create_project(
+            &pool,
+            CreateProject {
+                id: "proj-1".to_string(),
+                slug: "test".to_string(),
+                name: "Test".to_string(),
+                description: None,
+                provider: "local".to_string(),
+                root_path: "/tmp/test/test".to_string(),
+                remote_owner: None,
+                remote_repo: None,
+                remote_branch: None,
+                access_token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let events = Arc::new(EventBroadcaster::new());
+        (TeamService::new(pool, events.clone()), events)
+    }
+
+    fn active(task: &str, files: &[&str]) -> UpdateTeamStatus {
+        UpdateTeamStatus {
+            status: TeamMemberStatus::Active,
+            current_task: Some(task.to_string()),
+            current_files: Some(files.iter().map(|f| f.to_string()).collect()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_status_emits_event() {
+        let (team, events) = setup().await;
+        let mut rx = events.subscribe();
+
+        team.update_status("proj-1", "alice", active("Auth", &["./src/auth.rs"]))
+            .await
+            .unwrap();
+
+        match rx.recv().await.unwrap() {
+            FoldEvent::TeamStatusChanged(e) => {
+                assert_eq!(e.project_id, "proj-1");
+                assert_eq!(e.username, "alice");
+                assert_eq!(e.status, "active");
+                assert_eq!(e.current_files, vec!["src/auth.rs"]);
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_conflicts() {
+        let (team, _) = setup().await;
+
+        team.update_status("proj-1", "alice", active("Auth", &["src/auth.rs", "src/db.rs"]))
+            .await
+            .unwrap();
+        team.update_status("proj-1", "bob", active("Docs", &["README.md"]))
+            .await
+            .unwrap();
+
+        let conflicts = team
+            .find_conflicts(
+                "proj-1",
+                &["./src/auth.rs".to_string(), "README.md".to_string()],
+                Some("bob"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].username, "alice");
+        assert_eq!(conflicts[0].file_path, "src/auth.rs");
+        assert_eq!(conflicts[0].current_task.as_deref(), Some("Auth"));
+    }
+
+    #[tokio::test]
+    async fn test_leave_and_age_out() {
+        let (team, events) = setup().await;
+
+        team.update_status("proj-1", "alice", active("Auth", &[]))
+            .await
+            .unwrap();
+        assert!(matches!(
+            team.leave("proj-1", "nobody").await,
+            Err(Error::NotFound(_))
+        ));
+
+        sqlx::query("UPDATE team_status SET last_seen = datetime('now', '-1 hour')")
+            .execute(&team.db)
+            .await
+            .unwrap();
+
+        let mut rx = events.subscribe();
+        assert_eq!(team.age_out_idle(15).await.unwrap(), 1);
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            FoldEvent::TeamStatusChanged(e) if e.status == "idle"
+        ));
+        assert!(team.list("proj-1", true).await.unwrap().is_empty());
+
+        team.leave("proj-1", "alice").await.unwrap();
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            FoldEvent::TeamMemberLeft(e) if e.username == "alice"
+        ));
+        assert!(team.list("proj-1", false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_acting_member() {
+        let (team, _) = setup().await;
+        db::create_user(
+            &team.db,
+            db::CreateUser {
+                id: "user-1".to_string(),
+                provider: "local".to_string(),
+                subject: "alice".to_string(),
+                email: Some("alice@example.com".to_string()),
+                display_name: Some("alice".to_string()),
+                avatar_url: None,
+                role: db::UserRole::Member,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            team.acting_member("user-1", false, None).await.unwrap(),
+            "alice"
+        );
+        assert_eq!(
+            team.acting_member("user-1", false, Some("alice"))
+                .await
+                .unwrap(),
+            "alice"
+        );
+        assert!(matches!(
+            team.acting_member("user-1", false, Some("bob")).await,
+            Err(Error::Forbidden)
+        ));
+        assert_eq!(
+            team.acting_member("user-1", true, Some("bob"))
+                .await
+                .unwrap(),
+            "bob"
+        );
+    }
+}
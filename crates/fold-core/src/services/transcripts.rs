@@ -0,0 +1,955 @@
+//! Transcript ingestion service.
+//!
+//! Turns Claude Code JSONL transcripts (`~/.claude/projects/*/*.jsonl`) into
+//! session memories. The transcript is reduced to a compact digest, the LLM
+//! extracts decisions, errors and the outcome, and the resulting session
+//! memory is linked to the codebase memories of every file the agent edited.
+//!
+//! Imports are recorded in `transcript_imports` keyed by transcript ID, so
+//! re-uploading the same transcript (or rescanning it from the watcher) is a
+//! no-op. A session resumed after its import is not re-imported: the session
+//! memory describes the session as it first finished, and the resumed run
+//! gets no memory of its own.
+//!
+//! The watcher remembers the modification time of every transcript it has
+//! read and settled (imported, already imported, or matching no project) and
+//! skips those files unread until they change or the projects do.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::config::TranscriptConfig;
+use crate::db::{self, DbPool, UsageKind};
+use crate::error::{Error, Result};
+use crate::models::{MemoryCreate, MemorySource, MemoryType};
+
+use super::{ChatRequest, LlmService, MemoryService};
+
+/// Tools whose `file_path` input indicates a file was modified.
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Maximum characters of transcript digest sent to the LLM.
+const MAX_DIGEST_CHARS: usize = 12_000;
+
+/// Maximum characters kept per message in the digest.
+const MAX_MESSAGE_CHARS: usize = 600;
+
+/// Author recorded on session memories created from transcripts.
+const TRANSCRIPT_AUTHOR: &str = "claude-code";
+
+/// Find the largest byte index <= `index` that is on a char boundary.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        s.len()
+    } else {
+        let mut i = index;
+        while i > 0 && !s.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    }
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    &s[..floor_char_boundary(s, max)]
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Structured view of a Claude Code transcript.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParsedTranscript {
+    /// Claude Code session ID (`sessionId` on each line).
+    pub session_id: Option<String>,
+    /// Working directory the session ran in.
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Number of user/assistant messages.
+    pub message_count: usize,
+    pub user_prompts: Vec<String>,
+    pub assistant_messages: Vec<String>,
+    /// Files modified by edit tools, in first-touched order (as recorded).
+    pub files_edited: Vec<String>,
+    /// Tool results flagged as errors.
+    pub errors: Vec<String>,
+    /// Summary lines written by Claude Code itself.
+    pub summaries: Vec<String>,
+}
+
+impl ParsedTranscript {
+    /// Parse a JSONL transcript. Malformed lines are skipped.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut seen_files: HashSet<String> = HashSet::new();
+        let mut valid_lines = 0usize;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            valid_lines += 1;
+
+            if parsed.session_id.is_none() {
+                parsed.session_id = entry["sessionId"].as_str().map(String::from);
+            }
+            if parsed.cwd.is_none() {
+                parsed.cwd = entry["cwd"].as_str().map(String::from);
+            }
+            if parsed.git_branch.is_none() {
+                parsed.git_branch = entry["gitBranch"]
+                    .as_str()
+                    .filter(|b| !b.is_empty())
+                    .map(String::from);
+            }
+            if let Some(ts) = entry["timestamp"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+            {
+                if parsed.started_at.is_none() {
+                    parsed.started_at = Some(ts);
+                }
+                parsed.ended_at = Some(ts);
+            }
+
+            match entry["type"].as_str() {
+                Some("summary") => {
+                    if let Some(summary) = entry["summary"].as_str() {
+                        parsed.summaries.push(summary.to_string());
+                    }
+                }
+                Some("user") => {
+                    parsed.message_count += 1;
+                    parsed.parse_user_content(&entry["message"]["content"]);
+                }
+                Some("assistant") => {
+                    parsed.message_count += 1;
+                    parsed.parse_assistant_content(&entry["message"]["content"], &mut seen_files);
+                }
+                _ => {}
+            }
+        }
+
+        if valid_lines == 0 {
+            return Err(Error::Validation(
+                "Transcript contains no valid JSONL entries".into(),
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    fn parse_user_content(&mut self, content: &Value) {
+        match content {
+            Value::String(text) => self.push_user_prompt(text),
+            Value::Array(blocks) => {
+                for block in blocks {
+                    match block["type"].as_str() {
+                        Some("text") => {
+                            if let Some(text) = block["text"].as_str() {
+                                self.push_user_prompt(text);
+                            }
+                        }
+                        Some("tool_result") if block["is_error"].as_bool() == Some(true) => {
+                            let text = tool_result_text(&block["content"]);
+                            if !text.is_empty() {
+                                self.errors
+                                    .push(truncate(&text, MAX_MESSAGE_CHARS).to_string());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_user_prompt(&mut self, text: &str) {
+        let text = text.trim();
+        // Skip command wrappers and system reminders injected into user turns
+        if text.is_empty() || text.starts_with('<') {
+            return;
+        }
+        self.user_prompts.push(text.to_string());
+    }
+
+    fn parse_assistant_content(&mut self, content: &Value, seen_files: &mut HashSet<String>) {
+        let Value::Array(blocks) = content else {
+            if let Some(text) = content.as_str() {
+                self.assistant_messages.push(text.trim().to_string());
+            }
+            return;
+        };
+
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("text") => {
+                    if let Some(text) = block["text"].as_str().map(str::trim) {
+                        if !text.is_empty() {
+                            self.assistant_messages.push(text.to_string());
+                        }
+                    }
+                }
+                Some("tool_use") => {
+                    let name = block["name"].as_str().unwrap_or_default();
+                    if !EDIT_TOOLS.contains(&name) {
+                        continue;
+                    }
+                    let input = &block["input"];
+                    if let Some(path) = input["file_path"]
+                        .as_str()
+                        .or_else(|| input["notebook_path"].as_str())
+                    {
+                        if seen_files.insert(path.to_string()) {
+                            self.files_edited.push(path.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Files edited, made relative to `root`. Files outside `root` are dropped.
+    pub fn files_relative_to(&self, root: &Path) -> Vec<String> {
+        self.files_edited
+            .iter()
+            .filter_map(|f| {
+                let path = Path::new(f);
+                let rel = if path.is_absolute() {
+                    path.strip_prefix(root).ok()?
+                } else {
+                    path
+                };
+                Some(rel.to_string_lossy().replace('\\', "/"))
+            })
+            .collect()
+    }
+
+    /// Build a compact, chronological-ish digest for LLM extraction.
+    pub fn digest(&self, max_chars: usize) -> String {
+        let mut out = String::new();
+
+        if !self.summaries.is_empty() {
+            out.push_str("## Session summaries\n");
+            for s in &self.summaries {
+                out.push_str(&format!("- {}\n", s));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("## User requests\n");
+        for prompt in &self.user_prompts {
+            out.push_str(&format!("- {}\n", truncate(prompt, MAX_MESSAGE_CHARS)));
+        }
+
+        if !self.files_edited.is_empty() {
+            out.push_str("\n## Files edited\n");
+            for f in &self.files_edited {
+                out.push_str(&format!("- {}\n", f));
+            }
+        }
+
+        if !self.errors.is_empty() {
+            out.push_str("\n## Tool errors\n");
+            for e in &self.errors {
+                out.push_str(&format!("- {}\n", e));
+            }
+        }
+
+        out.push_str("\n## Assistant messages\n");
+        for msg in &self.assistant_messages {
+            out.push_str(&format!("- {}\n", truncate(msg, MAX_MESSAGE_CHARS)));
+        }
+
+        truncate(&out, max_chars).to_string()
+    }
+}
+
+/// Flatten a tool_result `content` field (string or block array) to text.
+fn tool_result_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.trim().to_string(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+// ============================================================================
+// Extraction
+// ============================================================================
+
+/// Knowledge extracted from a transcript.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptExtraction {
+    pub title: String,
+    pub summary: String,
+    pub decisions: Vec<String>,
+    pub errors: Vec<String>,
+    pub outcome: String,
+}
+
+impl TranscriptExtraction {
+    /// Heuristic extraction used when no LLM is available.
+    fn fallback(parsed: &ParsedTranscript) -> Self {
+        let first_prompt = parsed.user_prompts.first().map(String::as_str).unwrap_or("");
+        let title = parsed
+            .summaries
+            .last()
+            .map(String::as_str)
+            .unwrap_or(first_prompt);
+        let title = first_line(title, 60);
+
+        Self {
+            title: if title.is_empty() {
+                "Agent session".to_string()
+            } else {
+                title
+            },
+            summary: parsed
+                .summaries
+                .last()
+                .cloned()
+                .unwrap_or_else(|| truncate(first_prompt, MAX_MESSAGE_CHARS).to_string()),
+            decisions: Vec::new(),
+            errors: parsed.errors.iter().take(10).cloned().collect(),
+            outcome: parsed
+                .assistant_messages
+                .last()
+                .map(|m| truncate(m, MAX_MESSAGE_CHARS).to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Render the session memory content as markdown.
+    fn to_markdown(&self, parsed: &ParsedTranscript, files: &[String]) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("## Summary\n\n{}\n", self.summary.trim()));
+
+        if !self.decisions.is_empty() {
+            out.push_str("\n## Decisions\n\n");
+            for d in &self.decisions {
+                out.push_str(&format!("- {}\n", d));
+            }
+        }
+
+        if !files.is_empty() {
+            out.push_str("\n## Files Edited\n\n");
+            for f in files {
+                out.push_str(&format!("- `{}`\n", f));
+            }
+        }
+
+        if !self.errors.is_empty() {
+            out.push_str("\n## Errors\n\n");
+            for e in &self.errors {
+                out.push_str(&format!("- {}\n", first_line(e, 200)));
+            }
+        }
+
+        if !self.outcome.trim().is_empty() {
+            out.push_str(&format!("\n## Outcome\n\n{}\n", self.outcome.trim()));
+        }
+
+        let mut meta = Vec::new();
+        if let Some(ref id) = parsed.session_id {
+            meta.push(format!("session `{}`", id));
+        }
+        if let Some(ref branch) = parsed.git_branch {
+            meta.push(format!("branch `{}`", branch));
+        }
+        if let (Some(start), Some(end)) = (parsed.started_at, parsed.ended_at) {
+            meta.push(format!(
+                "{} – {}",
+                start.format("%Y-%m-%d %H:%M"),
+                end.format("%H:%M UTC")
+            ));
+        }
+        if !meta.is_empty() {
+            out.push_str(&format!("\n---\n_Imported from Claude Code {}_\n", meta.join(", ")));
+        }
+
+        out
+    }
+}
+
+fn first_line(s: &str, max_chars: usize) -> String {
+    let line = s.lines().next().unwrap_or("").trim();
+    line.chars().take(max_chars).collect::<String>().trim_end().to_string()
+}
+
+fn non_empty(items: Vec<String>) -> Vec<String> {
+    items.into_iter().filter(|s| !s.trim().is_empty()).collect()
+}
+
+/// Structured LLM response for transcript extraction.
+#[derive(Debug, Deserialize)]
+struct ExtractionResponse {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    decisions: Vec<String>,
+    #[serde(default)]
+    errors: Vec<String>,
+    #[serde(default)]
+    outcome: String,
+}
+
+/// JSON schema for `ExtractionResponse`.
+fn extraction_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "title": {
+                "type": "string",
+                "description": "Short descriptive title, max 60 characters"
+            },
+            "summary": {
+                "type": "string",
+                "description": "2-4 sentences on what was attempted and achieved"
+            },
+            "decisions": {
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "Each technical decision made and why"
+            },
+            "errors": {
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "Each significant error or blocker and how it was resolved"
+            },
+            "outcome": {
+                "type": "string",
+                "description": "Final state: done, partially done, or abandoned, and what remains"
+            }
+        },
+        "required": ["title", "summary", "decisions", "errors", "outcome"]
+    })
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// Result of ingesting a transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptImportResult {
+    pub transcript_id: String,
+    /// Session memory created (or previously created) for this transcript.
+    pub memory_id: Option<String>,
+    /// True when the transcript had already been imported.
+    pub already_imported: bool,
+    pub message_count: usize,
+    pub files_touched: Vec<String>,
+    pub links_created: usize,
+    pub decisions: Vec<String>,
+}
+
+/// Transcripts the watcher has read and needs nothing more from, with the
+/// modification time they had then.
+#[derive(Debug, Default)]
+struct SettledTranscripts {
+    /// Project roots the files were matched against
+    roots: Vec<String>,
+    files: HashMap<PathBuf, SystemTime>,
+}
+
+/// Service for ingesting agent transcripts as session memories.
+#[derive(Clone)]
+pub struct TranscriptService {
+    db: DbPool,
+    memory: MemoryService,
+    settled: Arc<RwLock<SettledTranscripts>>,
+}
+
+impl TranscriptService {
+    /// Create a new transcript service.
+    pub fn new(db: DbPool, memory: MemoryService) -> Self {
+        Self {
+            db,
+            memory,
+            settled: Arc::new(RwLock::new(SettledTranscripts::default())),
+        }
+    }
+
+    /// Ingest a JSONL transcript into a project.
+    ///
+    /// `transcript_id` defaults to the transcript's `sessionId`.
+    pub async fn ingest(
+        &self,
+        project_id: &str,
+        transcript_id: Option<&str>,
+        content: &str,
+        source_path: Option<&str>,
+    ) -> Result<TranscriptImportResult> {
+        let project = db::get_project(&self.db, project_id).await?;
+        let parsed = ParsedTranscript::parse(content)?;
+
+        let transcript_id = transcript_id
+            .map(String::from)
+            .or_else(|| parsed.session_id.clone())
+            .ok_or_else(|| {
+                Error::Validation("Transcript has no sessionId; supply transcript_id".into())
+            })?;
+
+        if let Some(existing) =
+            db::get_transcript_import(&self.db, &project.id, &transcript_id).await?
+        {
+            debug!(transcript_id = %transcript_id, "Transcript already imported");
+            return Ok(TranscriptImportResult {
+                transcript_id,
+                memory_id: existing.memory_id.clone(),
+                already_imported: true,
+                message_count: existing.message_count as usize,
+                files_touched: existing.files_touched_vec(),
+                links_created: 0,
+                decisions: Vec::new(),
+            });
+        }
+
+        // The import makes provider calls no request check has covered
+        for kind in [UsageKind::Llm, UsageKind::Embedding] {
+            super::rate_limit::check_quota(&self.db, &project.id, kind).await?;
+        }
+
+        let files = parsed.files_relative_to(Path::new(&project.root_path));
+        let llm = self.memory.llm_for(&project.id).await?;
+        if llm.is_available().await {
+            super::rate_limit::record_usage(&self.db, &project.id, UsageKind::Llm, 1).await;
+        }
+        let extraction = self.extract(&llm, &parsed).await;
+
+        let memory = self
+            .memory
+            .add(
+                &project.id,
+                &project.slug,
+                MemoryCreate {
+                    slug: Some(format!("session-{}", transcript_id)),
+                    memory_type: MemoryType::Session,
+                    content: extraction.to_markdown(&parsed, &files),
+                    author: Some(TRANSCRIPT_AUTHOR.to_string()),
+                    source: Some(MemorySource::Agent),
+                    title: Some(extraction.title.clone()),
+                    tags: vec!["session".to_string(), TRANSCRIPT_AUTHOR.to_string()],
+                    context: Some(extraction.summary.clone()).filter(|s| !s.is_empty()),
+                    ..Default::default()
+                },
+                true,
+            )
+            .await?;
+
+        let links_created = self.link_files(&project.id, &memory.id, &files).await;
+
+        match db::create_transcript_import(
+            &self.db,
+            db::CreateTranscriptImport {
+                id: crate::models::new_id(),
+                project_id: project.id.clone(),
+                transcript_id: transcript_id.clone(),
+                memory_id: Some(memory.id.clone()),
+                source_path: source_path.map(String::from),
+                message_count: parsed.message_count as i64,
+                files_touched: files.clone(),
+            },
+        )
+        .await
+        {
+            // A concurrent import won the race; the memory ID is slug-derived so
+            // both wrote the same memory.
+            Ok(_) | Err(Error::AlreadyExists(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        info!(
+            project = %project.slug,
+            transcript_id = %transcript_id,
+            memory_id = %memory.id,
+            files = files.len(),
+            links_created,
+            "Imported transcript"
+        );
+
+        Ok(TranscriptImportResult {
+            transcript_id,
+            memory_id: Some(memory.id),
+            already_imported: false,
+            message_count: parsed.message_count,
+            files_touched: files,
+            links_created,
+            decisions: extraction.decisions,
+        })
+    }
+
+    /// Extract decisions, errors and outcome with the LLM, falling back to
+    /// heuristics when the LLM is unavailable or returns garbage.
+    async fn extract(&self, llm: &LlmService, parsed: &ParsedTranscript) -> TranscriptExtraction {
+        let fallback = TranscriptExtraction::fallback(parsed);
+
+        if !llm.is_available().await {
+            return fallback;
+        }
+
+        let prompt = format!(
+            r#"Below is a condensed transcript of an AI coding agent session.
+Extract what a teammate would need to know later.
+
+{digest}"#,
+            digest = parsed.digest(MAX_DIGEST_CHARS)
+        );
+
+        let request = ChatRequest::prompt(prompt)
+            .with_max_tokens(1200)
+            .with_json_schema("transcript_extraction", extraction_schema());
+
+        let response: ExtractionResponse = match llm.chat_json(&request).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(error = %e, "Transcript extraction failed, using heuristics");
+                return fallback;
+            }
+        };
+
+        let title = Some(first_line(response.title.trim_matches('"'), 60))
+            .filter(|t| !t.is_empty())
+            .unwrap_or(fallback.title);
+
+        TranscriptExtraction {
+            title,
+            summary: Some(response.summary)
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or(fallback.summary),
+            decisions: non_empty(response.decisions),
+            errors: non_empty(response.errors),
+            outcome: Some(response.outcome)
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or(fallback.outcome),
+        }
+    }
+
+    /// Link the session memory to codebase memories for the files it touched.
+    async fn link_files(&self, project_id: &str, memory_id: &str, files: &[String]) -> usize {
+        let mut created = 0;
+
+        for file in files {
+            let target = match db::get_memory_by_file_path(&self.db, project_id, file).await {
+                Ok(Some(m)) => m,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(error = %e, file = %file, "Failed to look up file memory");
+                    continue;
+                }
+            };
+
+            let result = db::create_link(
+                &self.db,
+                db::CreateLink {
+                    id: crate::models::new_id(),
+                    project_id: project_id.to_string(),
+                    source_id: memory_id.to_string(),
+                    target_id: target.id,
+                    link_type: db::LinkType::Modifies,
+                    created_by: db::LinkCreator::System,
+                    confidence: Some(1.0),
+                    context: Some("Edited during agent session".to_string()),
+                    change_type: Some(db::ChangeType::Modified),
+                    additions: None,
+                    deletions: None,
+                },
+            )
+            .await;
+
+            match result {
+                Ok(link) => {
+                    created += 1;
+                    if let Some(events) = self.memory.events() {
+                        events.link_created(
+                            project_id,
+                            &link.id,
+                            &link.source_id,
+                            &link.target_id,
+                            &link.link_type,
+                            &link.created_by,
+                        );
+                    }
+                }
+                Err(Error::AlreadyExists(_)) => {}
+                Err(e) => warn!(error = %e, file = %file, "Failed to link session to file"),
+            }
+        }
+
+        created
+    }
+
+    // =========================================================================
+    // Watcher
+    // =========================================================================
+
+    /// Spawn the background watcher that imports finished transcripts from
+    /// the Claude Code projects directory.
+    pub fn start_watcher(&self, config: TranscriptConfig) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            info!(path = %config.watch_path, "Transcript watcher started");
+            loop {
+                if let Err(e) = service.scan_directory(&config).await {
+                    warn!(error = %e, "Transcript scan failed");
+                }
+                sleep(Duration::from_secs(config.poll_interval_secs)).await;
+            }
+        })
+    }
+
+    /// Scan the watch directory once, importing transcripts that have been
+    /// idle for at least `idle_secs`. Returns the number of new imports.
+    pub async fn scan_directory(&self, config: &TranscriptConfig) -> Result<usize> {
+        let root = PathBuf::from(&config.watch_path);
+        if !root.is_dir() {
+            debug!(path = %root.display(), "Transcript directory does not exist");
+            return Ok(0);
+        }
+
+        let projects = db::list_projects(&self.db).await?;
+        let import_times = db::list_transcript_import_times(&self.db).await?;
+        let idle = Duration::from_secs(config.idle_secs);
+        let mut imported = 0;
+
+        // A new or moved project may now match files that matched none
+        let mut roots: Vec<String> = projects.iter().map(|p| p.root_path.clone()).collect();
+        roots.sort();
+        {
+            let mut settled = self.settled.write().await;
+            if settled.roots != roots {
+                settled.roots = roots;
+                settled.files.clear();
+            }
+        }
+
+        let transcripts = find_transcripts(&root).await?;
+        for path in &transcripts {
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|m| m.modified())
+                .unwrap_or_else(|_| SystemTime::now());
+            if modified.elapsed().unwrap_or_default() < idle {
+                continue; // Session is probably still running
+            }
+            if self.settled.read().await.files.get(path) == Some(&modified) {
+                continue;
+            }
+
+            // Claude Code names transcripts by session ID. Skip files imported
+            // since they last changed without reading them.
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_string());
+            let imported_at = stem.as_ref().and_then(|s| import_times.get(s));
+            if imported_at.is_some_and(|at| SystemTime::from(*at) >= modified) {
+                continue;
+            }
+
+            let content = match tokio::fs::read_to_string(path).await {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!(error = %e, path = %path.display(), "Failed to read transcript");
+                    continue;
+                }
+            };
+            if let Some((project_id, transcript_id)) =
+                self.scan_transcript(&projects, path, &content).await?
+            {
+                let source_path = path.to_string_lossy().to_string();
+                match self
+                    .ingest(
+                        &project_id,
+                        Some(&transcript_id),
+                        &content,
+                        Some(&source_path),
+                    )
+                    .await
+                {
+                    Ok(result) if !result.already_imported => imported += 1,
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(error = %e, path = %source_path, "Failed to import transcript");
+                        continue; // Retried on the next scan
+                    }
+                }
+            }
+
+            self.settled
+                .write()
+                .await
+                .files
+                .insert(path.clone(), modified);
+        }
+
+        // Forget files that are gone
+        let present: HashSet<&PathBuf> = transcripts.iter().collect();
+        self.settled
+            .write()
+            .await
+            .files
+            .retain(|path, _| present.contains(path));
+
+        Ok(imported)
+    }
+
+    /// The project and transcript ID to import a watched transcript as, or
+    /// `None` when there is nothing to import: it does not parse, matches no
+    /// project, or was already imported (a resumed session).
+    async fn scan_transcript(
+        &self,
+        projects: &[db::Project],
+        path: &Path,
+        content: &str,
+    ) -> Result<Option<(String, String)>> {
+        let Ok(parsed) = ParsedTranscript::parse(content) else {
+            return Ok(None);
+        };
+        let Some(cwd) = parsed.cwd.as_deref() else {
+            return Ok(None);
+        };
+
+        // Match on the most specific project root containing the session cwd
+        let Some(project) = projects
+            .iter()
+            .filter(|p| Path::new(cwd).starts_with(&p.root_path))
+            .max_by_key(|p| p.root_path.len())
+        else {
+            return Ok(None);
+        };
+
+        let transcript_id = parsed
+            .session_id
+            .clone()
+            .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()));
+        let Some(transcript_id) = transcript_id else {
+            return Ok(None);
+        };
+
+        if db::get_transcript_import(&self.db, &project.id, &transcript_id)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some((project.id.clone(), transcript_id)))
+    }
+}
+
+/// List `*.jsonl` files one level below `root` (one directory per project).
+async fn find_transcripts(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = tokio::fs::read_dir(root).await?;
+
+    while let Some(dir) = dirs.next_entry().await? {
+        if !dir.file_type().await?.is_dir() {
+            continue;
+        }
+        let mut entries = tokio::fs::read_dir(dir.path()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = r#"{"type":"summary","summary":"Add retry to webhook sender","leafUuid":"x"}
+{"type":"user","sessionId":"abc-123","cwd":"/work/repo","gitBranch":"main","timestamp":"2025-01-10T10:00:00Z","message":{"role":"user","content":"Make webhook delivery retry on 5xx"}}
+{"type":"assistant","sessionId":"abc-123","timestamp":"2025-01-10T10:01:00Z","message":{"role":"assistant","content":[{"type":"text","text":"I'll add exponential backoff."},{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"/work/repo/src/webhooks.rs","old_string":"a","new_string":"b"}},{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"/work/repo/src/lib.rs"}}]}}
+{"type":"user","sessionId":"abc-123","timestamp":"2025-01-10T10:02:00Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"error[E0425]: cannot find value `delay`","is_error":true}]}}
+{"type":"assistant","sessionId":"abc-123","timestamp":"2025-01-10T10:03:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Write","input":{"file_path":"/work/repo/src/webhooks.rs","content":"..."}},{"type":"tool_use","id":"t4","name":"Write","input":{"file_path":"/elsewhere/notes.md","content":"..."}},{"type":"text","text":"Retries now back off up to 5 times."}]}}
+not json
+"#;
+
+    #[test]
+    fn test_parse_transcript() {
+        let parsed = ParsedTranscript::parse(TRANSCRIPT).unwrap();
+
+        assert_eq!(parsed.session_id.as_deref(), Some("abc-123"));
+        assert_eq!(parsed.cwd.as_deref(), Some("/work/repo"));
+        assert_eq!(parsed.git_branch.as_deref(), Some("main"));
+        assert_eq!(parsed.message_count, 4);
+        assert_eq!(parsed.user_prompts, vec!["Make webhook delivery retry on 5xx"]);
+        assert_eq!(parsed.summaries, vec!["Add retry to webhook sender"]);
+        assert_eq!(
+            parsed.files_edited,
+            vec!["/work/repo/src/webhooks.rs", "/elsewhere/notes.md"]
+        );
+        assert_eq!(parsed.errors.len(), 1);
+        assert!(parsed.errors[0].contains("E0425"));
+        assert!(parsed.started_at.unwrap() < parsed.ended_at.unwrap());
+    }
+
+    #[test]
+    fn test_files_relative_to_root() {
+        let parsed = ParsedTranscript::parse(TRANSCRIPT).unwrap();
+        assert_eq!(
+            parsed.files_relative_to(Path::new("/work/repo")),
+            vec!["src/webhooks.rs"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_transcript() {
+        assert!(ParsedTranscript::parse("").is_err());
+        assert!(ParsedTranscript::parse("garbage\n{broken").is_err());
+    }
+
+    #[test]
+    fn test_fallback_extraction() {
+        let parsed = ParsedTranscript::parse(TRANSCRIPT).unwrap();
+        let extraction = TranscriptExtraction::fallback(&parsed);
+
+        assert_eq!(extraction.title, "Add retry to webhook sender");
+        assert_eq!(extraction.outcome, "Retries now back off up to 5 times.");
+        assert_eq!(extraction.errors.len(), 1);
+
+        let markdown = extraction.to_markdown(&parsed, &["src/webhooks.rs".to_string()]);
+        assert!(markdown.contains("## Files Edited"));
+        assert!(markdown.contains("`src/webhooks.rs`"));
+        assert!(markdown.contains("session `abc-123`"));
+    }
+
+    #[test]
+    fn test_digest_is_bounded() {
+        let parsed = ParsedTranscript::parse(TRANSCRIPT).unwrap();
+        assert!(parsed.digest(50).len() <= 50);
+        assert!(parsed.digest(MAX_DIGEST_CHARS).contains("## Tool errors"));
+    }
+}
@@ -0,0 +1,551 @@
+//! Obsidian/Logseq vault import service.
+//!
+//! Ingests an existing markdown vault as agent memories. Each note becomes
+//! one memory: its title, tags, aliases and memory type come from the YAML
+//! frontmatter (falling back to the file name and inline `#tags`), and its
+//! `[[wiki links]]` become `related` memory links.
+//!
+//! Links are resolved the way Obsidian resolves them: by vault path, then by
+//! note name (the shortest path wins when names repeat), then by alias.
+//! Memory IDs are derived from the project and note path, so importing the
+//! same vault again updates the existing memories instead of duplicating them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_yaml::Value as YamlValue;
+use tokio::fs;
+use tracing::{info, warn};
+
+use crate::db::{self, DbPool};
+use crate::error::{Error, Result};
+use crate::models::{MemoryCreate, MemorySource, MemoryType};
+
+use super::fold_storage::slug_to_id;
+use super::{FoldStorageService, MemoryService};
+
+/// Notes larger than this are skipped.
+const MAX_NOTE_BYTES: u64 = 1024 * 1024;
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// A note read from a vault.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultNote {
+    /// Path relative to the vault root, with forward slashes
+    pub path: String,
+    pub title: String,
+    pub memory_type: MemoryType,
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    /// Note body without frontmatter
+    pub content: String,
+    /// Wiki link targets, without headings, block refs or display text
+    pub links: Vec<String>,
+}
+
+impl VaultNote {
+    /// Parse a note. `path` is relative to the vault root.
+    pub fn parse(path: &str, raw: &str) -> Self {
+        let (frontmatter, body) = split_frontmatter(raw);
+        let field = |key: &str| frontmatter.as_ref().and_then(|fm| fm.get(key));
+
+        let stem = Path::new(path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let title = field("title")
+            .and_then(YamlValue::as_str)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .unwrap_or(stem);
+
+        let memory_type = field("memory_type")
+            .or_else(|| field("type"))
+            .and_then(YamlValue::as_str)
+            .and_then(|t| MemoryType::from_str(&t.trim().to_lowercase()))
+            .unwrap_or(MemoryType::General);
+
+        let mut tags = field("tags").map(yaml_string_list).unwrap_or_default();
+        for tag in inline_tags(body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let tags = tags
+            .into_iter()
+            .map(|t| t.trim_start_matches('#').to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let mut links = Vec::new();
+        for link in wiki_links(body) {
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+
+        Self {
+            path: path.to_string(),
+            title,
+            memory_type,
+            author: field("author")
+                .and_then(YamlValue::as_str)
+                .map(String::from),
+            tags,
+            aliases: field("aliases")
+                .or_else(|| field("alias"))
+                .map(yaml_string_list)
+                .unwrap_or_default(),
+            content: body.trim().to_string(),
+            links,
+        }
+    }
+}
+
+/// Split YAML frontmatter from a note. Notes with missing or invalid
+/// frontmatter are treated as all body.
+fn split_frontmatter(raw: &str) -> (Option<serde_yaml::Mapping>, &str) {
+    let raw = raw.trim_start_matches('\u{feff}');
+    let Some(rest) = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))
+    else {
+        return (None, raw);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, raw);
+    };
+
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    match serde_yaml::from_str::<YamlValue>(&rest[..end]) {
+        Ok(YamlValue::Mapping(map)) => (Some(map), body),
+        Ok(_) => (None, body),
+        Err(_) => (None, raw),
+    }
+}
+
+/// Read a frontmatter value that may be a list or a comma/space separated string.
+fn yaml_string_list(value: &YamlValue) -> Vec<String> {
+    match value {
+        YamlValue::Sequence(items) => items
+            .iter()
+            .filter_map(|v| match v {
+                YamlValue::String(s) => Some(s.trim().to_string()),
+                YamlValue::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        YamlValue::String(s) => s
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Lines outside fenced code blocks.
+fn prose_lines(body: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+    body.lines().filter(move |line| {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            return false;
+        }
+        !in_fence
+    })
+}
+
+/// Inline `#tags` outside code blocks. Purely numeric tags (e.g. `#1`) are
+/// not tags in Obsidian.
+fn inline_tags(body: &str) -> Vec<String> {
+    let re = Regex::new(r"(?:^|[\s(])#([\p{L}\p{N}_/-]+)").unwrap();
+    let mut tags = Vec::new();
+    for line in prose_lines(body) {
+        for cap in re.captures_iter(line) {
+            let tag = cap[1].to_string();
+            if !tag.chars().all(|c| c.is_ascii_digit()) && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+/// Targets of `[[wiki links]]` and `![[embeds]]` outside code blocks.
+fn wiki_links(body: &str) -> Vec<String> {
+    let re = Regex::new(r"\[\[([^\]|#^]*)(?:[#^][^\]|]*)?(?:\|[^\]]*)?\]\]").unwrap();
+    prose_lines(body)
+        .flat_map(|line| {
+            re.captures_iter(line)
+                .map(|cap| cap[1].trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+/// Normalise a note path, name or link target for lookup.
+fn link_key(target: &str) -> String {
+    let target = target.trim().replace('\\', "/").to_lowercase();
+    target
+        .strip_suffix(".md")
+        .unwrap_or(&target)
+        .trim_start_matches("./")
+        .to_string()
+}
+
+/// Resolves wiki link targets to memory IDs.
+#[derive(Debug, Default)]
+pub struct LinkResolver {
+    by_path: HashMap<String, String>,
+    by_name: HashMap<String, String>,
+    by_alias: HashMap<String, String>,
+}
+
+impl LinkResolver {
+    /// Build a resolver over notes and the memory ID of each.
+    pub fn new<'a>(notes: impl IntoIterator<Item = (&'a VaultNote, &'a str)>) -> Self {
+        let mut notes: Vec<_> = notes.into_iter().collect();
+        // Obsidian picks the note closest to the vault root for a bare name
+        notes.sort_by_key(|(n, _)| (n.path.matches('/').count(), n.path.clone()));
+
+        let mut resolver = Self::default();
+        for (note, id) in notes {
+            let path = link_key(&note.path);
+            let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+            resolver.by_path.insert(path, id.to_string());
+            resolver
+                .by_name
+                .entry(name)
+                .or_insert_with(|| id.to_string());
+            for alias in &note.aliases {
+                resolver
+                    .by_alias
+                    .entry(link_key(alias))
+                    .or_insert_with(|| id.to_string());
+            }
+        }
+        resolver
+    }
+
+    /// Resolve a link target to a memory ID.
+    pub fn resolve(&self, target: &str) -> Option<&str> {
+        let key = link_key(target);
+        self.by_path
+            .get(&key)
+            .or_else(|| self.by_name.get(&key))
+            .or_else(|| self.by_alias.get(&key))
+            .map(String::as_str)
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// Result of importing a vault.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VaultImportResult {
+    /// Markdown notes found in the vault
+    pub notes: usize,
+    pub imported: usize,
+    /// Notes that were too large, unreadable or failed to import
+    pub skipped: usize,
+    pub links_created: usize,
+    /// Wiki links to notes (or attachments) that are not in the vault
+    pub unresolved_links: usize,
+}
+
+/// Service for importing Obsidian/Logseq vaults as memories.
+#[derive(Clone)]
+pub struct VaultImportService {
+    db: DbPool,
+    memory: MemoryService,
+    fold_storage: Arc<FoldStorageService>,
+}
+
+impl VaultImportService {
+    /// Create a new vault import service.
+    pub fn new(db: DbPool, memory: MemoryService, fold_storage: Arc<FoldStorageService>) -> Self {
+        Self {
+            db,
+            memory,
+            fold_storage,
+        }
+    }
+
+    /// Import every markdown note under `vault_path` into a project.
+    pub async fn import(&self, project_id: &str, vault_path: &Path) -> Result<VaultImportResult> {
+        let project = db::get_project(&self.db, project_id).await?;
+        if !fs::metadata(vault_path).await.is_ok_and(|m| m.is_dir()) {
+            return Err(Error::Validation(format!(
+                "Vault path is not a directory: {}",
+                vault_path.display()
+            )));
+        }
+
+        let files = collect_notes(vault_path).await?;
+        let mut result = VaultImportResult {
+            notes: files.len(),
+            ..Default::default()
+        };
+
+        let mut imported: Vec<(VaultNote, String)> = Vec::new();
+        for (rel_path, abs_path) in files {
+            let too_large = fs::metadata(&abs_path)
+                .await
+                .map_or(true, |m| m.len() > MAX_NOTE_BYTES);
+            let raw = match too_large {
+                true => None,
+                false => fs::read_to_string(&abs_path).await.ok(),
+            };
+            let Some(raw) = raw else {
+                warn!(path = %rel_path, "Skipping unreadable or oversized vault note");
+                result.skipped += 1;
+                continue;
+            };
+
+            let note = VaultNote::parse(&rel_path, &raw);
+            if note.content.is_empty() {
+                result.skipped += 1;
+                continue;
+            }
+
+            let memory_id = slug_to_id(&format!("vault:{}:{}", project.id, note.path));
+            let mut metadata = HashMap::new();
+            metadata.insert("vault_path".to_string(), serde_json::json!(note.path));
+            if !note.aliases.is_empty() {
+                metadata.insert("aliases".to_string(), serde_json::json!(note.aliases));
+            }
+
+            let created = self
+                .memory
+                .add(
+                    &project.id,
+                    &project.slug,
+                    MemoryCreate {
+                        id: Some(memory_id.clone()),
+                        memory_type: note.memory_type,
+                        content: note.content.clone(),
+                        author: note.author.clone(),
+                        source: Some(MemorySource::Agent),
+                        title: Some(note.title.clone()),
+                        tags: note.tags.clone(),
+                        metadata,
+                        ..Default::default()
+                    },
+                    false,
+                )
+                .await;
+
+            match created {
+                Ok(_) => {
+                    result.imported += 1;
+                    imported.push((note, memory_id));
+                }
+                Err(e) => {
+                    warn!(error = %e, path = %rel_path, "Failed to import vault note");
+                    result.skipped += 1;
+                }
+            }
+        }
+
+        let resolver = LinkResolver::new(imported.iter().map(|(n, id)| (n, id.as_str())));
+        let project_root = PathBuf::from(&project.root_path);
+
+        for (note, memory_id) in &imported {
+            let mut targets = HashSet::new();
+            for link in &note.links {
+                match resolver.resolve(link) {
+                    Some(target) if target != memory_id => {
+                        targets.insert(target.to_string());
+                    }
+                    Some(_) => {}
+                    None => result.unresolved_links += 1,
+                }
+            }
+            if targets.is_empty() {
+                continue;
+            }
+
+            for target in &targets {
+                if self
+                    .create_wiki_link(&project.id, memory_id, target)
+                    .await?
+                {
+                    result.links_created += 1;
+                }
+            }
+
+            // Memory writes drop the Related section, so write it from the graph
+            let related: Vec<String> = db::list_outgoing_links(&self.db, memory_id)
+                .await?
+                .into_iter()
+                .map(|l| l.target_id)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            if let Err(e) = self
+                .fold_storage
+                .update_memory_links(&project_root, memory_id, &related)
+                .await
+            {
+                warn!(error = %e, memory_id = %memory_id, "Failed to write related links to memory file");
+            }
+        }
+
+        info!(
+            project_id = %project.id,
+            vault = %vault_path.display(),
+            imported = result.imported,
+            links = result.links_created,
+            "Imported vault"
+        );
+
+        Ok(result)
+    }
+
+    /// Create a `related` link for a wiki link unless one exists.
+    async fn create_wiki_link(&self, project_id: &str, source: &str, target: &str) -> Result<bool> {
+        if db::get_link_by_endpoints(&self.db, source, target, &db::LinkType::Related)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        let link = db::create_link(
+            &self.db,
+            db::CreateLink {
+                id: crate::models::new_id(),
+                project_id: project_id.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                link_type: db::LinkType::Related,
+                created_by: db::LinkCreator::User,
+                confidence: Some(1.0),
+                context: Some("Wiki link in imported vault".to_string()),
+                change_type: None,
+                additions: None,
+                deletions: None,
+            },
+        )
+        .await?;
+
+        if let Some(events) = self.memory.events() {
+            events.link_created(
+                project_id,
+                &link.id,
+                source,
+                target,
+                &link.link_type,
+                &link.created_by,
+            );
+        }
+
+        Ok(true)
+    }
+}
+
+/// Walk a vault for markdown notes, skipping hidden directories such as
+/// `.obsidian`, `.trash` and `.git`. Returns (relative path, absolute path)
+/// pairs sorted by path.
+async fn collect_notes(vault_path: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut notes = Vec::new();
+    let mut dirs = vec![vault_path.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let mut entries = fs::read_dir(&dir).await.map_err(|e| {
+            Error::Internal(format!("Failed to read directory {}: {}", dir.display(), e))
+        })?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+                if let Ok(rel) = path.strip_prefix(vault_path) {
+                    notes.push((rel.to_string_lossy().replace('\\', "/"), path.clone()));
+                }
+            }
+        }
+    }
+
+    notes.sort();
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note() {
+        let raw = r#"---
+title: Use Postgres
+type: decision
+tags: [database, "api design"]
+aliases:
+  - Postgres decision
+---
+
+We chose [[Databases|Postgres]] over [[notes/SQLite#Limits]] for #backend work.
+See ![[diagram.png]] and issue #42.
+
+```
+[[not a link]] #not-a-tag
+```
+"#;
+
+        let note = VaultNote::parse("decisions/postgres.md", raw);
+        assert_eq!(note.title, "Use Postgres");
+        assert_eq!(note.memory_type, MemoryType::Decision);
+        assert_eq!(note.tags, vec!["database", "api design", "backend"]);
+        assert_eq!(note.aliases, vec!["Postgres decision"]);
+        assert_eq!(note.links, vec!["Databases", "notes/SQLite", "diagram.png"]);
+        assert!(note.content.starts_with("We chose"));
+    }
+
+    #[test]
+    fn test_parse_note_without_frontmatter() {
+        let note = VaultNote::parse("Daily/2026-01-05.md", "Met with #team about [[Roadmap]].");
+        assert_eq!(note.title, "2026-01-05");
+        assert_eq!(note.memory_type, MemoryType::General);
+        assert_eq!(note.tags, vec!["team"]);
+        assert_eq!(note.links, vec!["Roadmap"]);
+    }
+
+    #[test]
+    fn test_link_resolver() {
+        let notes = [
+            VaultNote::parse("Roadmap.md", "Top level"),
+            VaultNote::parse("archive/Roadmap.md", "Old"),
+            VaultNote::parse("projects/Fold.md", "---\naliases: [Fold app]\n---\nBody"),
+        ];
+        let ids = ["id-1", "id-2", "id-3"];
+        let resolver = LinkResolver::new(notes.iter().zip(ids));
+
+        assert_eq!(resolver.resolve("Roadmap"), Some("id-1"));
+        assert_eq!(resolver.resolve("archive/Roadmap"), Some("id-2"));
+        assert_eq!(resolver.resolve("projects/fold.md"), Some("id-3"));
+        assert_eq!(resolver.resolve("Fold"), Some("id-3"));
+        assert_eq!(resolver.resolve("fold app"), Some("id-3"));
+        assert_eq!(resolver.resolve("Missing"), None);
+    }
+}
@@ -6,10 +6,13 @@ use std::sync::Arc;
 
 use crate::db::DbPool;
 use crate::services::{
-    AuthService, ContentResolverService, EmbeddingService, EventBroadcaster, FoldStorageService,
-    GitHubService, GitLabService, GitLocalService, GitService, GitSyncService, GraphService,
-    IndexerService, LinkerService, LlmService, MemoryService, MetaStorageService, ProjectService,
-    ProviderRegistry, QdrantService,
+    AskService, AuditService, AuthService, ConsolidationService, ContentResolverService,
+    EmbeddingService, EventBroadcaster, FederatedSearchService, FeedbackService,
+    FoldStorageService, GitHubService, GitLabService, GitLocalService, GitService, GitSyncService,
+    GraphService, IndexerService, LinkerService, LlmService, MemoryService, MetaStorageService,
+    OutboundWebhookService, ProjectArchiveService, ProjectService, ProviderChainService,
+    ProviderRegistry, QdrantService, RateLimitService, SearchAnalyticsService, SearchEvalService,
+    SqlitePathIndex, TeamService, TranscriptService, VaultImportService,
 };
 use crate::{config, Result};
 use std::path::PathBuf;
@@ -55,25 +58,75 @@ pub struct AppState {
     pub fold_storage: Arc<FoldStorageService>,
     /// Event broadcaster for SSE notifications.
     pub events: Arc<EventBroadcaster>,
+    /// Agent transcript ingestion service.
+    pub transcripts: TranscriptService,
+    /// Team presence service.
+    pub team: TeamService,
+    /// Memory consolidation service.
+    pub consolidation: ConsolidationService,
+    /// Request rate limits and provider quotas.
+    pub rate_limits: RateLimitService,
+    /// Audit log of security-relevant and data-changing operations.
+    pub audit: AuditService,
+    /// Outbound webhooks for memory and link events.
+    pub webhooks: OutboundWebhookService,
+    /// Whole-project export and import.
+    pub project_archive: ProjectArchiveService,
+    /// Obsidian/Logseq vault import.
+    pub vault_import: VaultImportService,
+    /// Per-project LLM and embedding provider chains.
+    pub provider_chains: ProviderChainService,
+    /// Question answering over project memories.
+    pub ask: AskService,
+    /// Search across every project a user can read.
+    pub federated_search: FederatedSearchService,
+    /// Search quality evaluation against golden query sets.
+    pub search_eval: SearchEvalService,
+    /// Relevance feedback on search results.
+    pub feedback: FeedbackService,
+    /// Search query log and analytics.
+    pub search_analytics: SearchAnalyticsService,
 }
 
 impl AppState {
     /// Create a new application state, initializing all services.
     pub async fn new() -> Result<Self> {
+        Self::new_with_events(Arc::new(EventBroadcaster::new())).await
+    }
+
+    /// Create a new application state with a pre-existing event broadcaster.
+    /// This allows the broadcaster to be created early for tracing integration.
+    pub async fn new_with_events(events: Arc<EventBroadcaster>) -> Result<Self> {
         let config = config::config();
 
+        // Refuse to start with a master key that is set but unusable
+        crate::secrets::init_keyring()?;
+
         // Initialize database
         let db = crate::db::init_pool(&config.database.path).await?;
 
         // Initialize database schema
         crate::db::initialize_schema(&db).await?;
 
+        // Encrypt plaintext secrets and move them off retired master keys
+        crate::db::encrypt_stored_secrets(&db).await?;
+
         // Initialize core services
-        let qdrant_config = fold_qdrant::QdrantConfig::new(
-            &config.qdrant.url,
-            &config.qdrant.collection_prefix,
-        );
+        let qdrant_config =
+            fold_qdrant::QdrantConfig::new(&config.qdrant.url, &config.qdrant.collection_prefix);
         let qdrant = Arc::new(QdrantService::new(&qdrant_config).await?);
+
+        Self::with_services(db, qdrant, events).await
+    }
+
+    /// Build the services over an initialized database and vector store.
+    async fn with_services(
+        db: DbPool,
+        qdrant: Arc<QdrantService>,
+        events: Arc<EventBroadcaster>,
+    ) -> Result<Self> {
+        let config = config::config();
+
         let embeddings = Arc::new(EmbeddingService::new(db.clone(), &config.embedding).await?);
         let llm = Arc::new(LlmService::new(db.clone(), &config.llm).await?);
         let github = Arc::new(GitHubService::new());
@@ -89,7 +142,13 @@ impl AppState {
             db.clone(),
             meta_storage.clone(),
         ));
-        let fold_storage = Arc::new(FoldStorageService::new());
+        let fold_storage = Arc::new(FoldStorageService::with_path_index(Arc::new(
+            SqlitePathIndex::new(db.clone()),
+        )));
+
+        // Resolve per-project provider overrides, falling back to the global chains
+        let provider_chains =
+            ProviderChainService::new(db.clone(), llm.clone(), embeddings.clone());
 
         // Initialize high-level services with agentic memory
         let memory = MemoryService::new(
@@ -98,7 +157,9 @@ impl AppState {
             embeddings.clone(),
             llm.clone(),
             fold_storage.clone(),
-        );
+        )
+        .with_events(events.clone())
+        .with_provider_chains(provider_chains.clone());
 
         let project = ProjectService::new(db.clone(), qdrant.clone(), embeddings.clone());
 
@@ -112,16 +173,15 @@ impl AppState {
         ));
 
         // Initialize indexer with git service for auto-commit
-        let indexer =
-            IndexerService::with_git_service(memory.clone(), llm.clone(), git_service.clone());
+        let indexer = IndexerService::with_git_service(memory.clone(), git_service.clone());
 
         let git_sync = GitSyncService::new(
             db.clone(),
             github.clone(),
             gitlab.clone(),
             memory.clone(),
-            llm.clone(),
             indexer.clone(),
+            fold_storage.clone(),
         );
 
         let graph = GraphService::new(db.clone());
@@ -129,7 +189,6 @@ impl AppState {
         let linker = Arc::new(LinkerService::new(
             db.clone(),
             memory.clone(),
-            llm.clone(),
             qdrant.clone(),
             embeddings.clone(),
         ));
@@ -144,118 +203,28 @@ impl AppState {
 
         let auth = AuthService::new(db.clone(), config.auth.clone());
 
-        // Initialize event broadcaster for SSE
-        let events = Arc::new(EventBroadcaster::new());
-
-        Ok(Self {
-            db,
-            qdrant,
-            embeddings,
-            llm,
-            github,
-            gitlab,
-            git_local,
-            git_service,
-            providers,
-            memory,
-            project,
-            indexer,
-            git_sync,
-            graph,
-            linker,
-            auth,
-            content_resolver,
-            fold_storage,
-            events,
-        })
-    }
-
-    /// Create a new application state with a pre-existing event broadcaster.
-    /// This allows the broadcaster to be created early for tracing integration.
-    pub async fn new_with_events(events: Arc<EventBroadcaster>) -> Result<Self> {
-        let config = config::config();
-
-        // Initialize database
-        let db = crate::db::init_pool(&config.database.path).await?;
-
-        // Initialize database schema
-        crate::db::initialize_schema(&db).await?;
-
-        // Initialize core services
-        let qdrant_config = fold_qdrant::QdrantConfig::new(
-            &config.qdrant.url,
-            &config.qdrant.collection_prefix,
-        );
-        let qdrant = Arc::new(QdrantService::new(&qdrant_config).await?);
-        let embeddings = Arc::new(EmbeddingService::new(db.clone(), &config.embedding).await?);
-        let llm = Arc::new(LlmService::new(db.clone(), &config.llm).await?);
-        let github = Arc::new(GitHubService::new());
-        let gitlab = Arc::new(GitLabService::new());
-        let git_local = Arc::new(GitLocalService::with_base_dir(&config.storage.repos_path));
-        let providers = Arc::new(ProviderRegistry::with_defaults());
-
-        // Initialize filesystem storage services
-        let meta_storage = Arc::new(MetaStorageService::new(PathBuf::from(
-            &config.storage.fold_path,
-        )));
-        let content_resolver = Arc::new(ContentResolverService::new(
-            db.clone(),
-            meta_storage.clone(),
-        ));
-        let fold_storage = Arc::new(FoldStorageService::new());
+        let transcripts = TranscriptService::new(db.clone(), memory.clone());
 
-        // Initialize high-level services with agentic memory
-        let memory = MemoryService::new(
-            db.clone(),
-            qdrant.clone(),
-            embeddings.clone(),
-            llm.clone(),
-            fold_storage.clone(),
-        );
+        let team = TeamService::new(db.clone(), events.clone());
 
-        let project = ProjectService::new(db.clone(), qdrant.clone(), embeddings.clone());
+        let consolidation = ConsolidationService::new(db.clone(), memory.clone());
 
-        // Initialize git service for auto-commit and sync
-        let git_service = Arc::new(GitService::new(
+        let rate_limits = RateLimitService::new(db.clone(), config.rate_limit.clone());
+        let audit = AuditService::new(db.clone(), &config.audit);
+        let webhooks = OutboundWebhookService::new(db.clone(), events.clone());
+        let project_archive = ProjectArchiveService::new(
             db.clone(),
             memory.clone(),
-            fold_storage.clone(),
             qdrant.clone(),
-            embeddings.clone(),
-        ));
-
-        // Initialize indexer with git service for auto-commit
-        let indexer =
-            IndexerService::with_git_service(memory.clone(), llm.clone(), git_service.clone());
-
-        let git_sync = GitSyncService::new(
-            db.clone(),
-            github.clone(),
-            gitlab.clone(),
-            memory.clone(),
-            llm.clone(),
-            indexer.clone(),
+            fold_storage.clone(),
         );
-
-        let graph = GraphService::new(db.clone());
-
-        let linker = Arc::new(LinkerService::new(
-            db.clone(),
-            memory.clone(),
-            llm.clone(),
-            qdrant.clone(),
-            embeddings.clone(),
-        ));
-
-        // Wire up linker to indexer for holographic auto-linking
-        let mut indexer = indexer;
-        indexer.set_linker(linker.clone());
-        indexer.set_concurrency_limit(config.indexing.concurrency_limit);
-
-        // Wire up chunk services for semantic code chunking
-        indexer.set_chunk_services(embeddings.clone(), qdrant.clone(), db.clone());
-
-        let auth = AuthService::new(db.clone(), config.auth.clone());
+        let vault_import =
+            VaultImportService::new(db.clone(), memory.clone(), fold_storage.clone());
+        let ask = AskService::new(db.clone(), memory.clone());
+        let federated_search = FederatedSearchService::new(db.clone(), memory.clone());
+        let search_eval = SearchEvalService::new(db.clone(), memory.clone());
+        let feedback = FeedbackService::new(db.clone());
+        let search_analytics = SearchAnalyticsService::new(db.clone(), memory.clone());
 
         Ok(Self {
             db,
@@ -277,6 +246,35 @@ impl AppState {
             content_resolver,
             fold_storage,
             events,
+            transcripts,
+            team,
+            consolidation,
+            rate_limits,
+            audit,
+            webhooks,
+            project_archive,
+            vault_import,
+            provider_chains,
+            ask,
+            federated_search,
+            search_eval,
+            feedback,
+            search_analytics,
         })
     }
 }
+
+#[cfg(test)]
+impl AppState {
+    /// State over an in-memory database for route tests. Nothing listens at
+    /// the Qdrant URL, so handlers that need vectors fail.
+    pub(crate) async fn for_tests() -> Result<Self> {
+        let db = crate::db::init_pool(":memory:").await?;
+        crate::db::initialize_schema(&db).await?;
+
+        let qdrant_config = fold_qdrant::QdrantConfig::new("http://127.0.0.1:1", "test_");
+        let qdrant = Arc::new(QdrantService::new_lazy(&qdrant_config)?);
+
+        Self::with_services(db, qdrant, Arc::new(EventBroadcaster::new())).await
+    }
+}
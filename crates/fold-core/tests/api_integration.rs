@@ -172,15 +172,13 @@ async fn build_test_state(pool: DbPool) -> AppState {
         embeddings.clone(),
     ));
 
-    let indexer =
-        IndexerService::with_git_service(memory.clone(), llm.clone(), git_service.clone());
+    let indexer = IndexerService::with_git_service(memory.clone(), git_service.clone());
 
     let git_sync = GitSyncService::new(
         pool.clone(),
         github.clone(),
         gitlab.clone(),
         memory.clone(),
-        llm.clone(),
         indexer.clone(),
     );
 
@@ -189,7 +187,6 @@ async fn build_test_state(pool: DbPool) -> AppState {
     let linker = LinkerService::new(
         pool.clone(),
         memory.clone(),
-        llm.clone(),
         qdrant.clone(),
         embeddings.clone(),
     );
@@ -464,6 +464,13 @@ impl EmbeddingService {
         !guard.is_empty()
     }
 
+    /// Get the model of the first provider, which sets the dimension.
+    /// `None` when hash-based placeholders are in use.
+    pub async fn primary_model(&self) -> Option<String> {
+        let guard = self.inner.providers.read().await;
+        guard.first().map(|p| p.model.clone())
+    }
+
     /// Lazily initialize the service.
     async fn ensure_initialized(&self) -> Result<()> {
         let mut initialized = self.inner.initialized.write().await;
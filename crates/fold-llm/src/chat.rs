@@ -0,0 +1,766 @@
+//! Chat completions with structured output, streaming and usage reporting.
+//!
+//! This module holds the provider wire formats and does no I/O, so any HTTP
+//! client can drive it: build the call with [`build_request`] and
+//! [`auth_headers`], then parse the body with [`parse_response`] or feed the
+//! streamed bytes to a [`StreamDecoder`].
+//!
+//! Structured output maps onto each provider's native mechanism: Gemini
+//! `responseSchema`, OpenAI-compatible `response_format` and Anthropic
+//! tool use. The returned content is the JSON document in every case.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{Error, Result, RuntimeLlmProvider};
+
+/// Default output token limit for a chat request.
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// Default sampling temperature, matching `complete`.
+const DEFAULT_TEMPERATURE: f32 = 0.3;
+
+/// Name of the tool Anthropic is forced to call for structured output.
+const ANTHROPIC_SCHEMA_TOOL: &str = "respond";
+
+/// Who a chat message is from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Assistant => "assistant",
+        }
+    }
+}
+
+/// A single message in a conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// A JSON schema the response must conform to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSchema {
+    /// Identifier for the schema (letters, digits, `_` and `-`).
+    pub name: String,
+    /// The schema itself. The root must be an object.
+    pub schema: Value,
+}
+
+/// A chat completion request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub messages: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    /// Constrain the response to JSON matching this schema.
+    pub response_schema: Option<JsonSchema>,
+}
+
+impl ChatRequest {
+    /// Create a request from a conversation.
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            temperature: DEFAULT_TEMPERATURE,
+            response_schema: None,
+        }
+    }
+
+    /// Create a request from a single user prompt.
+    pub fn prompt(prompt: impl Into<String>) -> Self {
+        Self::new(vec![ChatMessage::user(prompt)])
+    }
+
+    /// Prepend a system message.
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.messages.insert(0, ChatMessage::system(system));
+        self
+    }
+
+    /// Set the output token limit.
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Set the sampling temperature.
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Constrain the response to JSON matching `schema`.
+    pub fn with_json_schema(mut self, name: impl Into<String>, schema: Value) -> Self {
+        self.response_schema = Some(JsonSchema {
+            name: name.into(),
+            schema,
+        });
+        self
+    }
+
+    /// System messages joined into one prompt, for providers that take it
+    /// separately from the conversation.
+    fn system_prompt(&self) -> Option<String> {
+        let system: Vec<&str> = self
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        (!system.is_empty()).then(|| system.join("\n\n"))
+    }
+
+    /// Non-system messages.
+    fn conversation(&self) -> impl Iterator<Item = &ChatMessage> {
+        self.messages.iter().filter(|m| m.role != Role::System)
+    }
+}
+
+/// Token usage reported by the provider for one call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+impl Usage {
+    pub fn total(&self) -> u32 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// A completed chat response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatResponse {
+    /// Response text, or the JSON document for structured requests.
+    pub content: String,
+    /// Provider that produced the response.
+    pub provider: String,
+    pub model: String,
+    pub usage: Usage,
+}
+
+impl ChatResponse {
+    /// Deserialize the content of a structured response.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(self.content.trim()).map_err(|e| {
+            Error::Llm(format!(
+                "{} returned content that does not match the schema: {}",
+                self.provider, e
+            ))
+        })
+    }
+}
+
+/// An event on a streamed chat response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// A piece of response text.
+    Delta(String),
+    /// The stream finished. Carries the full content and usage.
+    Done(ChatResponse),
+}
+
+/// Request dialect spoken by a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Gemini,
+    Anthropic,
+    /// OpenAI and compatible APIs (OpenRouter, Ollama).
+    OpenAi,
+}
+
+impl Dialect {
+    fn of(provider: &str) -> Self {
+        match provider {
+            "gemini" => Self::Gemini,
+            "anthropic" | "claudecode" => Self::Anthropic,
+            _ => Self::OpenAi,
+        }
+    }
+}
+
+// ============================================================================
+// Requests
+// ============================================================================
+
+/// Build the URL and JSON body for a chat call.
+pub fn build_request(
+    provider: &RuntimeLlmProvider,
+    request: &ChatRequest,
+    stream: bool,
+) -> (String, Value) {
+    match Dialect::of(&provider.name) {
+        Dialect::Gemini => build_gemini_request(provider, request, stream),
+        Dialect::Anthropic => build_anthropic_request(provider, request, stream),
+        Dialect::OpenAi => build_openai_request(provider, request, stream),
+    }
+}
+
+/// Authentication headers for a provider.
+///
+/// Fails when the provider has no credentials, except for self-hosted Ollama
+/// which runs without them.
+pub fn auth_headers(provider: &RuntimeLlmProvider) -> Result<Vec<(&'static str, String)>> {
+    let auth_token = match provider.auth_token() {
+        Some(token) => token,
+        None if provider.name == "ollama" => return Ok(Vec::new()),
+        None => {
+            return Err(Error::Llm(format!(
+                "No credentials for provider {}",
+                provider.name
+            )))
+        }
+    };
+
+    let bearer = ("Authorization", format!("Bearer {}", auth_token));
+    let version = ("anthropic-version", "2023-06-01".to_string());
+    // OAuth tokens (Claude Code subscriptions) require the beta header
+    let oauth_beta = (
+        "anthropic-beta",
+        "oauth-2025-04-20, claude-code-20250219, interleaved-thinking-2025-05-14".to_string(),
+    );
+
+    Ok(match provider.name.as_str() {
+        "anthropic" if provider.oauth_access_token.is_none() => {
+            vec![("x-api-key", auth_token.to_string()), version]
+        }
+        "anthropic" | "claudecode" => vec![bearer, version, oauth_beta],
+        _ => vec![bearer],
+    })
+}
+
+fn build_gemini_request(
+    provider: &RuntimeLlmProvider,
+    request: &ChatRequest,
+    stream: bool,
+) -> (String, Value) {
+    let auth_token = provider.auth_token().unwrap_or("");
+    let url = if stream {
+        format!(
+            "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+            provider.base_url, provider.model, auth_token
+        )
+    } else {
+        format!(
+            "{}/models/{}:generateContent?key={}",
+            provider.base_url, provider.model, auth_token
+        )
+    };
+
+    let contents: Vec<Value> = request
+        .conversation()
+        .map(|m| {
+            json!({
+                "role": if m.role == Role::Assistant { "model" } else { "user" },
+                "parts": [{"text": m.content}]
+            })
+        })
+        .collect();
+
+    let mut generation_config = json!({
+        "maxOutputTokens": request.max_tokens,
+        "temperature": request.temperature
+    });
+    if let Some(schema) = &request.response_schema {
+        generation_config["responseMimeType"] = json!("application/json");
+        generation_config["responseSchema"] = gemini_schema(&schema.schema);
+    }
+
+    let mut body = json!({
+        "contents": contents,
+        "generationConfig": generation_config
+    });
+    if let Some(system) = request.system_prompt() {
+        body["systemInstruction"] = json!({"parts": [{"text": system}]});
+    }
+
+    (url, body)
+}
+
+/// Gemini accepts an OpenAPI subset of JSON schema and rejects unknown keys.
+fn gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "additionalProperties" | "$schema"))
+                .map(|(key, value)| (key.clone(), gemini_schema(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+fn build_openai_request(
+    provider: &RuntimeLlmProvider,
+    request: &ChatRequest,
+    stream: bool,
+) -> (String, Value) {
+    let url = format!("{}/chat/completions", provider.base_url);
+
+    let messages: Vec<Value> = request
+        .messages
+        .iter()
+        .map(|m| json!({"role": m.role.as_str(), "content": m.content}))
+        .collect();
+
+    let mut body = json!({
+        "model": provider.model,
+        "messages": messages,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature
+    });
+
+    if let Some(schema) = &request.response_schema {
+        body["response_format"] = json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": schema.name,
+                "schema": schema.schema
+            }
+        });
+    }
+
+    if stream {
+        body["stream"] = json!(true);
+        body["stream_options"] = json!({"include_usage": true});
+    }
+
+    // Add OpenRouter-specific headers
+    if provider.name == "openrouter" {
+        body["http_referer"] = json!("https://fold.dev");
+        body["x_title"] = json!("Fold Memory System");
+    }
+
+    (url, body)
+}
+
+fn build_anthropic_request(
+    provider: &RuntimeLlmProvider,
+    request: &ChatRequest,
+    stream: bool,
+) -> (String, Value) {
+    let url = format!("{}/messages", provider.base_url);
+
+    let messages: Vec<Value> = request
+        .conversation()
+        .map(|m| json!({"role": m.role.as_str(), "content": m.content}))
+        .collect();
+
+    let mut body = json!({
+        "model": provider.model,
+        "messages": messages,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature
+    });
+
+    if let Some(system) = request.system_prompt() {
+        body["system"] = json!(system);
+    }
+
+    // Structured output: force a single tool call whose input is the document
+    if let Some(schema) = &request.response_schema {
+        body["tools"] = json!([{
+            "name": ANTHROPIC_SCHEMA_TOOL,
+            "description": format!("Respond with a {} document.", schema.name),
+            "input_schema": schema.schema
+        }]);
+        body["tool_choice"] = json!({"type": "tool", "name": ANTHROPIC_SCHEMA_TOOL});
+    }
+
+    if stream {
+        body["stream"] = json!(true);
+    }
+
+    (url, body)
+}
+
+// ============================================================================
+// Responses
+// ============================================================================
+
+/// Parse a non-streamed response body.
+pub fn parse_response(provider: &RuntimeLlmProvider, text: &str) -> Result<ChatResponse> {
+    let body: Value = serde_json::from_str(text)
+        .map_err(|e| Error::Llm(format!("Failed to parse response: {}", e)))?;
+    check_error(&body)?;
+
+    let (content, usage) = match Dialect::of(&provider.name) {
+        Dialect::Gemini => (gemini_text(&body), gemini_usage(&body)),
+        Dialect::Anthropic => {
+            let content = body["content"].as_array().map(|blocks| {
+                blocks
+                    .iter()
+                    .filter_map(|block| match block["type"].as_str() {
+                        Some("tool_use") => Some(block["input"].to_string()),
+                        _ => block["text"].as_str().map(String::from),
+                    })
+                    .collect::<String>()
+            });
+            let usage = Usage {
+                input_tokens: token_count(&body["usage"]["input_tokens"]),
+                output_tokens: token_count(&body["usage"]["output_tokens"]),
+            };
+            (content, usage)
+        }
+        Dialect::OpenAi => {
+            let choice = &body["choices"][0];
+            let content = choice["message"]["content"]
+                .as_str()
+                .or_else(|| choice["text"].as_str())
+                .map(String::from);
+            (content, openai_usage(&body))
+        }
+    };
+
+    let content = content
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| Error::Llm(format!("No content in {} response", provider.name)))?;
+
+    Ok(ChatResponse {
+        content,
+        provider: provider.name.clone(),
+        model: provider.model.clone(),
+        usage,
+    })
+}
+
+/// Incrementally decodes a streamed (server-sent events) response.
+pub struct StreamDecoder {
+    dialect: Dialect,
+    provider: String,
+    model: String,
+    /// Bytes of an incomplete line
+    pending: Vec<u8>,
+    content: String,
+    usage: Usage,
+}
+
+impl StreamDecoder {
+    pub fn new(provider: &RuntimeLlmProvider) -> Self {
+        Self {
+            dialect: Dialect::of(&provider.name),
+            provider: provider.name.clone(),
+            model: provider.model.clone(),
+            pending: Vec::new(),
+            content: String::new(),
+            usage: Usage::default(),
+        }
+    }
+
+    /// Feed a chunk of the response body, returning the text deltas it
+    /// completes.
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<String>> {
+        self.pending.extend_from_slice(bytes);
+
+        let mut deltas = Vec::new();
+        while let Some(newline) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(delta) = self.decode_line(line.trim())? {
+                self.content.push_str(&delta);
+                deltas.push(delta);
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// Finish the stream, returning the accumulated response.
+    pub fn finish(mut self) -> Result<ChatResponse> {
+        // A final event without a trailing newline
+        if !self.pending.is_empty() {
+            let rest = std::mem::take(&mut self.pending);
+            if let Some(delta) = self.decode_line(String::from_utf8_lossy(&rest).trim())? {
+                self.content.push_str(&delta);
+            }
+        }
+
+        if self.content.is_empty() {
+            return Err(Error::Llm(format!(
+                "No content in {} stream",
+                self.provider
+            )));
+        }
+
+        Ok(ChatResponse {
+            content: self.content,
+            provider: self.provider,
+            model: self.model,
+            usage: self.usage,
+        })
+    }
+
+    /// Decode one SSE line. Only `data:` lines carry payloads.
+    fn decode_line(&mut self, line: &str) -> Result<Option<String>> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Ok(None);
+        };
+        if data.is_empty() || data == "[DONE]" {
+            return Ok(None);
+        }
+
+        let event: Value = serde_json::from_str(data)
+            .map_err(|e| Error::Llm(format!("Failed to parse stream event: {}", e)))?;
+        check_error(&event)?;
+
+        let delta = match self.dialect {
+            Dialect::Gemini => {
+                // Usage is cumulative on every event
+                if event.get("usageMetadata").is_some() {
+                    self.usage = gemini_usage(&event);
+                }
+                gemini_text(&event)
+            }
+            Dialect::Anthropic => match event["type"].as_str() {
+                Some("message_start") => {
+                    let usage = &event["message"]["usage"];
+                    self.usage.input_tokens = token_count(&usage["input_tokens"]);
+                    self.usage.output_tokens = token_count(&usage["output_tokens"]);
+                    None
+                }
+                Some("content_block_delta") => event["delta"]["text"]
+                    .as_str()
+                    .or_else(|| event["delta"]["partial_json"].as_str())
+                    .map(String::from),
+                Some("message_delta") => {
+                    self.usage.output_tokens = token_count(&event["usage"]["output_tokens"]);
+                    None
+                }
+                _ => None,
+            },
+            Dialect::OpenAi => {
+                // The final event carries usage and no choices
+                if event["usage"].is_object() {
+                    self.usage = openai_usage(&event);
+                }
+                event["choices"][0]["delta"]["content"]
+                    .as_str()
+                    .map(String::from)
+            }
+        };
+
+        Ok(delta.filter(|d| !d.is_empty()))
+    }
+}
+
+fn check_error(body: &Value) -> Result<()> {
+    match body["error"]["message"].as_str() {
+        Some(message) => Err(Error::Llm(message.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn gemini_text(body: &Value) -> Option<String> {
+    body["candidates"][0]["content"]["parts"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|p| p["text"].as_str())
+                .collect::<String>()
+        })
+}
+
+fn gemini_usage(body: &Value) -> Usage {
+    Usage {
+        input_tokens: token_count(&body["usageMetadata"]["promptTokenCount"]),
+        output_tokens: token_count(&body["usageMetadata"]["candidatesTokenCount"]),
+    }
+}
+
+fn openai_usage(body: &Value) -> Usage {
+    Usage {
+        input_tokens: token_count(&body["usage"]["prompt_tokens"]),
+        output_tokens: token_count(&body["usage"]["completion_tokens"]),
+    }
+}
+
+fn token_count(value: &Value) -> u32 {
+    value.as_u64().unwrap_or(0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(name: &str) -> RuntimeLlmProvider {
+        RuntimeLlmProvider {
+            id: String::new(),
+            name: name.to_string(),
+            base_url: "https://llm.test/v1".to_string(),
+            model: "test-model".to_string(),
+            api_key: Some("key".to_string()),
+            oauth_access_token: None,
+            priority: 0,
+        }
+    }
+
+    fn structured_request() -> ChatRequest {
+        ChatRequest::prompt("Tag this")
+            .with_system("You are a tagger")
+            .with_json_schema(
+                "tags",
+                json!({
+                    "type": "object",
+                    "properties": {"tags": {"type": "array", "items": {"type": "string"}}},
+                    "required": ["tags"],
+                    "additionalProperties": false
+                }),
+            )
+    }
+
+    #[test]
+    fn test_structured_output_requests() {
+        let request = structured_request();
+
+        let (url, body) = build_request(&provider("gemini"), &request, false);
+        assert!(url.ends_with(":generateContent?key=key"));
+        assert_eq!(
+            body["generationConfig"]["responseMimeType"],
+            "application/json"
+        );
+        assert!(body["generationConfig"]["responseSchema"]
+            .get("additionalProperties")
+            .is_none());
+        assert_eq!(
+            body["systemInstruction"]["parts"][0]["text"],
+            "You are a tagger"
+        );
+        assert_eq!(body["contents"].as_array().unwrap().len(), 1);
+
+        let (_, body) = build_request(&provider("openai"), &request, false);
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(body["response_format"]["json_schema"]["name"], "tags");
+        assert_eq!(body["messages"][0]["role"], "system");
+
+        let (url, body) = build_request(&provider("anthropic"), &request, true);
+        assert!(url.ends_with("/messages"));
+        assert_eq!(body["system"], "You are a tagger");
+        assert_eq!(body["tool_choice"]["name"], ANTHROPIC_SCHEMA_TOOL);
+        assert_eq!(body["tools"][0]["input_schema"]["required"][0], "tags");
+        assert_eq!(body["stream"], true);
+    }
+
+    #[test]
+    fn test_parse_responses_with_usage() {
+        let gemini = r#"{"candidates":[{"content":{"parts":[{"text":"{\"tags\":[\"a\"]}"}]}}],
+            "usageMetadata":{"promptTokenCount":12,"candidatesTokenCount":5}}"#;
+        let response = parse_response(&provider("gemini"), gemini).unwrap();
+        assert_eq!(
+            response.usage,
+            Usage {
+                input_tokens: 12,
+                output_tokens: 5
+            }
+        );
+
+        #[derive(Deserialize)]
+        struct Tags {
+            tags: Vec<String>,
+        }
+        assert_eq!(response.json::<Tags>().unwrap().tags, vec!["a"]);
+
+        let anthropic = r#"{"content":[{"type":"tool_use","name":"respond","input":{"tags":["b"]}}],
+            "usage":{"input_tokens":20,"output_tokens":8}}"#;
+        let response = parse_response(&provider("anthropic"), anthropic).unwrap();
+        assert_eq!(response.json::<Tags>().unwrap().tags, vec!["b"]);
+        assert_eq!(response.usage.total(), 28);
+
+        let openai = r#"{"choices":[{"message":{"content":"hello"}}],
+            "usage":{"prompt_tokens":3,"completion_tokens":1}}"#;
+        let response = parse_response(&provider("openai"), openai).unwrap();
+        assert_eq!(response.content, "hello");
+        assert!(response.json::<Tags>().is_err());
+
+        let error = r#"{"error":{"message":"bad key"}}"#;
+        assert!(parse_response(&provider("openai"), error).is_err());
+    }
+
+    #[test]
+    fn test_stream_decoder() {
+        // Events split across chunk boundaries
+        let mut decoder = StreamDecoder::new(&provider("openai"));
+        let mut deltas = decoder
+            .push(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\ndata: {\"choi")
+            .unwrap();
+        deltas.extend(
+            decoder
+                .push(b"ces\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n")
+                .unwrap(),
+        );
+        decoder
+            .push(b"data: {\"choices\":[],\"usage\":{\"prompt_tokens\":4,\"completion_tokens\":2}}\n\ndata: [DONE]\n\n")
+            .unwrap();
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+        let response = decoder.finish().unwrap();
+        assert_eq!(response.content, "Hello");
+        assert_eq!(response.usage.total(), 6);
+
+        let mut decoder = StreamDecoder::new(&provider("anthropic"));
+        let events = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":9,\"output_tokens\":1}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"usage\":{\"output_tokens\":3}}\n\n",
+        );
+        assert_eq!(decoder.push(events.as_bytes()).unwrap(), vec!["Hi"]);
+        let response = decoder.finish().unwrap();
+        assert_eq!(
+            response.usage,
+            Usage {
+                input_tokens: 9,
+                output_tokens: 3
+            }
+        );
+
+        let mut decoder = StreamDecoder::new(&provider("gemini"));
+        decoder
+            .push(b"data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Yo\"}]}}],\"usageMetadata\":{\"promptTokenCount\":2,\"candidatesTokenCount\":1}}")
+            .unwrap();
+        // The last event has no trailing newline
+        let response = decoder.finish().unwrap();
+        assert_eq!(response.content, "Yo");
+        assert_eq!(response.usage.total(), 3);
+    }
+}
@@ -4,7 +4,9 @@
 //! when rate limits are hit or providers fail.
 //!
 //! This crate provides a standalone LLM service that can be used independently
-//! of the rest of the Fold system.
+//! of the rest of the Fold system. Besides plain prompt completion it offers a
+//! chat API with system messages, JSON-schema constrained output, token
+//! streaming and per-call usage reporting (see [`chat`]).
 
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
@@ -12,11 +14,16 @@ use std::time::{Duration, Instant};
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use tokio::sync::RwLock;
+use serde_json::Value;
+use tokio::sync::{mpsc, RwLock};
 use tokio::time::sleep;
 use tracing::{debug, info, warn};
 
+pub mod chat;
+
+use chat::StreamDecoder;
+pub use chat::{ChatMessage, ChatRequest, ChatResponse, JsonSchema, Role, StreamEvent, Usage};
+
 /// Maximum retries per provider before fallback
 const MAX_RETRIES: u32 = 2;
 
@@ -29,6 +36,9 @@ const HEALTH_CHECK_INTERVAL_SECS: u64 = 60;
 /// Number of consecutive errors before marking unavailable
 const ERROR_THRESHOLD: u32 = 3;
 
+/// Stream events buffered ahead of a slow consumer
+const STREAM_BUFFER: usize = 32;
+
 /// Error types for the LLM service.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -152,59 +162,6 @@ struct LlmServiceInner {
     on_provider_used: RwLock<Option<Box<dyn Fn(&str) + Send + Sync>>>,
 }
 
-/// Response from LLM API
-#[derive(Debug, Deserialize)]
-struct LlmResponse {
-    choices: Option<Vec<Choice>>,
-    candidates: Option<Vec<Candidate>>,     // Gemini format
-    content: Option<Vec<AnthropicContent>>, // Anthropic format
-    error: Option<LlmError>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: Option<Message>,
-    text: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Message {
-    content: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct Candidate {
-    content: CandidateContent,
-}
-
-#[derive(Debug, Deserialize)]
-struct CandidateContent {
-    parts: Vec<Part>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Part {
-    text: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicContent {
-    #[serde(rename = "type")]
-    #[allow(dead_code)]
-    content_type: String,
-    text: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct LlmError {
-    message: String,
-    #[serde(rename = "type")]
-    #[allow(dead_code)]
-    error_type: Option<String>,
-    #[allow(dead_code)]
-    code: Option<String>,
-}
-
 impl LlmService {
     /// Create LLM service from config.
     pub fn new(config: &LlmConfig) -> Self {
@@ -350,6 +307,15 @@ impl LlmService {
 
     /// Complete a prompt with automatic provider fallback.
     pub async fn complete(&self, prompt: &str, max_tokens: u32) -> Result<String> {
+        let request = ChatRequest::prompt(prompt).with_max_tokens(max_tokens);
+        Ok(self.chat(&request).await?.content)
+    }
+
+    /// Run a chat request with automatic provider fallback.
+    ///
+    /// For requests with a response schema the content is the JSON document;
+    /// use [`ChatResponse::json`] to deserialize it.
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse> {
         let providers = {
             let guard = self.inner.providers.read().await;
             guard.clone()
@@ -367,15 +333,9 @@ impl LlmService {
                 continue;
             }
 
-            match self.try_provider(provider, prompt, max_tokens).await {
+            match self.try_provider(provider, request).await {
                 Ok(response) => {
-                    // Notify callback if set
-                    let callback = self.inner.on_provider_used.read().await;
-                    if let Some(ref cb) = *callback {
-                        cb(&provider.id);
-                    }
-                    // Clear error state on success
-                    self.clear_error().await;
+                    self.provider_succeeded(provider).await;
                     return Ok(response);
                 }
                 Err(e) => {
@@ -389,27 +349,90 @@ impl LlmService {
             }
         }
 
-        // Record error when all providers fail
+        Err(self.all_failed(last_error).await)
+    }
+
+    /// Stream a chat request.
+    ///
+    /// Providers are tried in order until one accepts the request. Its
+    /// response is then forwarded as [`StreamEvent::Delta`] events, ending
+    /// with [`StreamEvent::Done`] carrying the full content and usage. There
+    /// is no fallback once streaming has started.
+    pub async fn chat_stream(
+        &self,
+        request: &ChatRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamEvent>>> {
+        let providers = {
+            let guard = self.inner.providers.read().await;
+            guard.clone()
+        };
+
+        if providers.is_empty() {
+            return Err(Error::NoProviders);
+        }
+
+        let mut last_error = None;
+
+        for provider in &providers {
+            if !provider.has_credentials() {
+                debug!(provider = %provider.name, "Skipping provider without credentials");
+                continue;
+            }
+
+            match self.send(provider, request, true).await {
+                Ok(response) => {
+                    self.provider_succeeded(provider).await;
+
+                    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+                    let decoder = StreamDecoder::new(provider);
+                    tokio::spawn(forward_stream(response, decoder, tx));
+                    return Ok(rx);
+                }
+                Err(e) => {
+                    warn!(
+                        provider = %provider.name,
+                        error = %e,
+                        "Provider failed to start stream, trying next"
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(self.all_failed(last_error).await)
+    }
+
+    /// Notify the usage callback and clear the error state.
+    async fn provider_succeeded(&self, provider: &RuntimeLlmProvider) {
+        let callback = self.inner.on_provider_used.read().await;
+        if let Some(ref cb) = *callback {
+            cb(&provider.id);
+        }
+        drop(callback);
+        self.clear_error().await;
+    }
+
+    /// Record that every provider failed, returning the error to report.
+    async fn all_failed(&self, last_error: Option<Error>) -> Error {
         let error_msg = last_error
             .as_ref()
             .map(|e| e.to_string())
             .unwrap_or_else(|| "All providers failed".to_string());
         self.record_error(&error_msg).await;
 
-        Err(last_error.unwrap_or(Error::Llm("All providers failed".to_string())))
+        last_error.unwrap_or(Error::Llm("All providers failed".to_string()))
     }
 
     /// Try a specific provider with retries.
     async fn try_provider(
         &self,
         provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> Result<String> {
+        request: &ChatRequest,
+    ) -> Result<ChatResponse> {
         let mut delay = Duration::from_millis(RETRY_DELAY_MS);
 
         for attempt in 0..MAX_RETRIES {
-            match self.call_provider(provider, prompt, max_tokens).await {
+            match self.call_provider(provider, request).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     if Self::is_retryable(&e) && attempt < MAX_RETRIES - 1 {
@@ -447,202 +470,65 @@ impl LlmService {
     async fn call_provider(
         &self,
         provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> Result<String> {
-        let auth_token = provider
-            .auth_token()
-            .ok_or_else(|| Error::Llm(format!("No credentials for provider {}", provider.name)))?;
+        request: &ChatRequest,
+    ) -> Result<ChatResponse> {
+        let response = self.send(provider, request, false).await?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Request(format!("Failed to read response: {}", e)))?;
+
+        chat::parse_response(provider, &text)
+    }
+
+    /// Send a chat request, returning the response once it has a success
+    /// status.
+    async fn send(
+        &self,
+        provider: &RuntimeLlmProvider,
+        request: &ChatRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let headers = chat::auth_headers(provider)?;
 
         debug!(
             provider = %provider.name,
             model = %provider.model,
+            stream,
             "Calling LLM provider"
         );
 
-        let (url, body) = match provider.name.as_str() {
-            "gemini" => self.build_gemini_request(provider, prompt, max_tokens),
-            "anthropic" | "claudecode" => {
-                self.build_anthropic_request(provider, prompt, max_tokens)
-            }
-            _ => self.build_openai_request(provider, prompt, max_tokens),
-        };
+        let (url, body) = chat::build_request(provider, request, stream);
 
-        let mut request = self
+        let mut http = self
             .inner
             .client
             .post(&url)
             .header("Content-Type", "application/json");
+        for (name, value) in headers {
+            http = http.header(name, value);
+        }
 
-        // Add authentication header based on provider
-        request = match provider.name.as_str() {
-            "anthropic" => {
-                // Check if using OAuth token (requires beta header)
-                if provider.oauth_access_token.is_some() {
-                    request
-                        .header("Authorization", format!("Bearer {}", auth_token))
-                        .header("anthropic-version", "2023-06-01")
-                        .header(
-                            "anthropic-beta",
-                            "oauth-2025-04-20, claude-code-20250219, interleaved-thinking-2025-05-14",
-                        )
-                } else {
-                    request
-                        .header("x-api-key", auth_token)
-                        .header("anthropic-version", "2023-06-01")
-                }
-            }
-            "claudecode" => {
-                // Claude Code always uses OAuth tokens from ~/.claude
-                request
-                    .header("Authorization", format!("Bearer {}", auth_token))
-                    .header("anthropic-version", "2023-06-01")
-                    .header(
-                        "anthropic-beta",
-                        "oauth-2025-04-20, claude-code-20250219, interleaved-thinking-2025-05-14",
-                    )
-            }
-            _ => request.header("Authorization", format!("Bearer {}", auth_token)),
-        };
-
-        let response = request
+        let response = http
             .json(&body)
             .send()
             .await
             .map_err(|e| Error::Request(format!("Request failed: {}", e)))?;
 
         let status = response.status();
-        let text = response
-            .text()
-            .await
-            .map_err(|e| Error::Request(format!("Failed to read response: {}", e)))?;
-
         if status.as_u16() == 429 {
             return Err(Error::RateLimitExceeded);
         }
 
         if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
             return Err(Error::Llm(format!(
                 "Provider returned {}: {}",
                 status, text
             )));
         }
 
-        self.parse_response(&provider.name, &text)
-    }
-
-    /// Build request for Gemini API
-    fn build_gemini_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let auth_token = provider.auth_token().unwrap_or("");
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            provider.base_url, provider.model, auth_token
-        );
-
-        let body = json!({
-            "contents": [{
-                "parts": [{"text": prompt}]
-            }],
-            "generationConfig": {
-                "maxOutputTokens": max_tokens,
-                "temperature": 0.3
-            }
-        });
-
-        (url, body)
-    }
-
-    /// Build request for OpenAI-compatible APIs (OpenAI, OpenRouter)
-    fn build_openai_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let url = format!("{}/chat/completions", provider.base_url);
-
-        let mut body = json!({
-            "model": provider.model,
-            "messages": [
-                {"role": "user", "content": prompt}
-            ],
-            "max_tokens": max_tokens,
-            "temperature": 0.3
-        });
-
-        // Add OpenRouter-specific headers
-        if provider.name == "openrouter" {
-            body["http_referer"] = json!("https://fold.dev");
-            body["x_title"] = json!("Fold Memory System");
-        }
-
-        (url, body)
-    }
-
-    /// Build request for Anthropic Claude API
-    fn build_anthropic_request(
-        &self,
-        provider: &RuntimeLlmProvider,
-        prompt: &str,
-        max_tokens: u32,
-    ) -> (String, Value) {
-        let url = format!("{}/messages", provider.base_url);
-
-        let body = json!({
-            "model": provider.model,
-            "messages": [
-                {"role": "user", "content": prompt}
-            ],
-            "max_tokens": max_tokens,
-            "temperature": 0.3
-        });
-
-        (url, body)
-    }
-
-    /// Parse response from different API formats
-    fn parse_response(&self, provider: &str, text: &str) -> Result<String> {
-        let response: LlmResponse = serde_json::from_str(text)
-            .map_err(|e| Error::Llm(format!("Failed to parse response: {}", e)))?;
-
-        if let Some(error) = response.error {
-            return Err(Error::Llm(error.message));
-        }
-
-        // Try Anthropic format first
-        if let Some(content) = response.content {
-            if let Some(content_block) = content.first() {
-                return Ok(content_block.text.clone());
-            }
-        }
-
-        // Try Gemini format
-        if let Some(candidates) = response.candidates {
-            if let Some(candidate) = candidates.first() {
-                if let Some(part) = candidate.content.parts.first() {
-                    return Ok(part.text.clone());
-                }
-            }
-        }
-
-        // Try OpenAI format
-        if let Some(choices) = response.choices {
-            if let Some(choice) = choices.first() {
-                if let Some(message) = &choice.message {
-                    return Ok(message.content.clone());
-                }
-                if let Some(text) = &choice.text {
-                    return Ok(text.clone());
-                }
-            }
-        }
-
-        Err(Error::Llm(format!("No content in {} response", provider)))
+        Ok(response)
     }
 
     /// Extract JSON from LLM response text
@@ -701,6 +587,42 @@ impl LlmService {
     }
 }
 
+/// Forward a streamed response body to a channel as decoded events.
+async fn forward_stream(
+    mut response: reqwest::Response,
+    mut decoder: StreamDecoder,
+    tx: mpsc::Sender<Result<StreamEvent>>,
+) {
+    loop {
+        let bytes = match response.chunk().await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = tx
+                    .send(Err(Error::Request(format!("Stream interrupted: {}", e))))
+                    .await;
+                return;
+            }
+        };
+
+        let deltas = match decoder.push(&bytes) {
+            Ok(deltas) => deltas,
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+        };
+        for delta in deltas {
+            if tx.send(Ok(StreamEvent::Delta(delta))).await.is_err() {
+                // Receiver dropped, stop reading
+                return;
+            }
+        }
+    }
+
+    let _ = tx.send(decoder.finish().map(StreamEvent::Done)).await;
+}
+
 /// Metadata generated by LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneratedMetadata {
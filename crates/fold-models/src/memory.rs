@@ -693,6 +693,51 @@ pub struct ChunkMatch {
     pub snippet: Option<String>,
 }
 
+/// How a search result's strength and combined score were computed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Decay model applied: "exponential", "power_law" or "none"
+    pub model: String,
+    /// Half-life for the memory's type (absent when it does not decay)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub half_life_days: Option<f32>,
+    /// Days since the memory was last used, read or updated
+    pub age_days: f32,
+    /// Recency factor before boosts and penalties (0.0-1.0)
+    pub decay: f32,
+    /// ACT-R base-level activation (power-law model only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation: Option<f32>,
+    /// Boost from "used" feedback (exponential model only)
+    pub access_boost: f32,
+    /// Strength lost to "irrelevant" and "wrong" feedback
+    pub feedback_penalty: f32,
+    /// Weight of strength against relevance in the blend
+    pub strength_weight: f32,
+    /// Link PageRank, scaled so the project's best-linked memory is 1.0
+    pub link_rank: f32,
+    /// Amount added to the combined score for link rank
+    pub link_boost: f32,
+    /// Similarity of the memory's own vector (absent when only a chunk matched)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_score: Option<f32>,
+    /// Rank in each query variant's results (rewritten search only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rerank: Vec<VariantRank>,
+}
+
+/// Where a result ranked for one query variant before fusion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantRank {
+    /// Index of the variant; 0 is the original query
+    pub variant: usize,
+    pub query: String,
+    /// 1-based rank among the variant's results
+    pub rank: usize,
+    /// Reciprocal rank fusion score from this variant
+    pub contribution: f32,
+}
+
 /// Search result with score and decay-adjusted ranking.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemorySearchResult {
@@ -709,6 +754,9 @@ pub struct MemorySearchResult {
     /// Matched chunks that contributed to this result (if chunk search was used)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub matched_chunks: Vec<ChunkMatch>,
+    /// How strength and the combined score were computed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<ScoreBreakdown>,
 }
 
 impl MemorySearchResult {
@@ -720,6 +768,7 @@ impl MemorySearchResult {
             strength: 0.0,
             combined_score: score,
             matched_chunks: Vec::new(),
+            breakdown: None,
         }
     }
 
@@ -731,6 +780,7 @@ impl MemorySearchResult {
             strength,
             combined_score,
             matched_chunks: Vec::new(),
+            breakdown: None,
         }
     }
 
@@ -748,8 +798,15 @@ impl MemorySearchResult {
             strength,
             combined_score,
             matched_chunks,
+            breakdown: None,
         }
     }
+
+    /// Attach the score breakdown.
+    pub fn with_breakdown(mut self, breakdown: ScoreBreakdown) -> Self {
+        self.breakdown = Some(breakdown);
+        self
+    }
 }
 
 /// Code summary generated by LLM
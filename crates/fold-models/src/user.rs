@@ -137,7 +137,7 @@ pub struct ApiToken {
     pub user_id: String,
     pub name: String,
     pub token_hash: String,
-    /// JSON array of scopes
+    /// JSON array of scopes (None = full access)
     pub scopes: Option<String>,
     pub last_used: Option<DateTime<Utc>>,
     pub expires_at: Option<DateTime<Utc>>,
@@ -158,9 +158,9 @@ impl ApiToken {
         self.expires_at.map(|exp| exp < Utc::now()).unwrap_or(false)
     }
 
-    /// Check if token has a specific scope
+    /// Check if token has a specific scope. Tokens without scopes have full access.
     pub fn has_scope(&self, scope: &str) -> bool {
-        self.scopes_vec().iter().any(|s| s == scope || s == "*")
+        self.scopes.is_none() || self.scopes_vec().iter().any(|s| s == scope || s == "*")
     }
 }
 
@@ -186,25 +186,56 @@ impl OidcState {
     }
 }
 
-/// Webhook registration for git events
+/// Webhook registration: a git provider hook, or an outbound webhook that
+/// receives a project's memory and link events.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "sqlx", derive(FromRow))]
 #[serde(rename_all = "snake_case")]
 pub struct WebhookRegistration {
     pub id: String,
-    pub repository_id: String,
+    /// Project whose events an outbound webhook receives
+    pub project_id: Option<String>,
+    /// Repository a git provider hook was installed on
+    pub repository_id: Option<String>,
+    /// "outbound", or the git provider
     pub provider: String,
     pub webhook_id: String,
+    /// URL outbound events are POSTed to
+    pub target_url: Option<String>,
+    #[serde(skip_serializing)]
     pub secret: String,
     pub events: String, // JSON array
     pub active: bool,
+    pub created_by: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl WebhookRegistration {
+    /// Provider value for user-registered outbound webhooks.
+    pub const OUTBOUND: &'static str = "outbound";
+
     /// Parse events from JSON string
     pub fn events_vec(&self) -> Vec<String> {
         serde_json::from_str(&self.events).unwrap_or_default()
     }
+
+    /// Whether this is an outbound webhook.
+    pub fn is_outbound(&self) -> bool {
+        self.provider == Self::OUTBOUND
+    }
+
+    /// Whether the webhook wants an event type. Entries may be exact
+    /// (`memory:created`), a prefix wildcard (`memory:*`) or `*`.
+    /// An empty list subscribes to everything.
+    pub fn subscribes_to(&self, event_type: &str) -> bool {
+        let events = self.events_vec();
+        events.is_empty()
+            || events
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => event_type.starts_with(prefix),
+                    None => pattern == event_type,
+                })
+    }
 }
@@ -7,10 +7,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use qdrant_client::qdrant::{
-    condition::ConditionOneOf, r#match::MatchValue, Condition, CreateCollectionBuilder,
-    DeletePointsBuilder, Distance, FieldCondition, Filter, Match, PointId, PointStruct,
-    ScoredPoint, ScrollPointsBuilder, SearchPointsBuilder, UpsertPointsBuilder,
-    Value as QdrantValue, VectorParamsBuilder,
+    condition::ConditionOneOf, r#match::MatchValue, vectors::VectorsOptions, Condition,
+    CreateCollectionBuilder, DeletePointsBuilder, Distance, FieldCondition, Filter,
+    GetPointsBuilder, Match, PointId, PointStruct, ScoredPoint, ScrollPointsBuilder,
+    SearchPointsBuilder, UpsertPointsBuilder, Value as QdrantValue, VectorParamsBuilder,
 };
 use qdrant_client::Qdrant;
 use serde_json::Value;
@@ -73,18 +73,28 @@ pub struct VectorSearchResult {
 impl QdrantService {
     /// Create a new Qdrant service.
     pub async fn new(config: &QdrantConfig) -> Result<Self> {
-        let client = Qdrant::from_url(&config.url)
-            .build()
-            .map_err(|e| Error::VectorStore(format!("Failed to connect to Qdrant: {}", e)))?;
+        let service = Self::new_lazy(config)?;
 
         // Test connection
-        client
+        service
+            .inner
+            .client
             .list_collections()
             .await
             .map_err(|e| Error::VectorStore(format!("Qdrant connection test failed: {}", e)))?;
 
         info!(url = %config.url, prefix = %config.collection_prefix, "Qdrant service connected");
 
+        Ok(service)
+    }
+
+    /// Create a Qdrant service without testing the connection. Qdrant is
+    /// first contacted when a call is made.
+    pub fn new_lazy(config: &QdrantConfig) -> Result<Self> {
+        let client = Qdrant::from_url(&config.url)
+            .build()
+            .map_err(|e| Error::VectorStore(format!("Failed to connect to Qdrant: {}", e)))?;
+
         Ok(Self {
             inner: Arc::new(QdrantServiceInner {
                 client,
@@ -369,6 +379,49 @@ impl QdrantService {
         })
     }
 
+    /// Fetch the stored vectors for points by ID.
+    /// Points that do not exist are left out of the result.
+    pub async fn get_vectors(
+        &self,
+        project_slug: &str,
+        ids: &[String],
+    ) -> Result<HashMap<String, Vec<f32>>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let collection_name = self.collection_name(project_slug);
+        let point_ids: Vec<PointId> = ids.iter().map(|id| PointId::from(id.clone())).collect();
+
+        let response = self
+            .inner
+            .client
+            .get_points(
+                GetPointsBuilder::new(&collection_name, point_ids)
+                    .with_vectors(true)
+                    .with_payload(false),
+            )
+            .await
+            .map_err(|e| Error::VectorStore(format!("Failed to get points: {}", e)))?;
+
+        let vectors = response
+            .result
+            .into_iter()
+            .filter_map(|point| {
+                let id = match point.id?.point_id_options? {
+                    qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid) => uuid,
+                    qdrant_client::qdrant::point_id::PointIdOptions::Num(num) => num.to_string(),
+                };
+                match point.vectors?.vectors_options? {
+                    VectorsOptions::Vector(vector) => Some((id, vector.data)),
+                    VectorsOptions::Vectors(_) => None,
+                }
+            })
+            .collect();
+
+        Ok(vectors)
+    }
+
     /// Scroll through all points in a collection.
     pub async fn scroll(
         &self,
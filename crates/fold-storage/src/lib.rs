@@ -3,6 +3,10 @@
 //! Provides a storage layer that persists memories as markdown files
 //! in a hash-based directory structure: fold/a/b/aBcD123.md
 //!
+//! Projects can opt into a human-readable layout instead, for browsing
+//! fold/ as an Obsidian or Logseq vault: fold/decision/use-postgres.md
+//! (set `layout = "readable"` under `[storage]` in fold/project.toml).
+//!
 //! Memory files use YAML frontmatter for metadata:
 //! ```markdown
 //! ---
@@ -24,7 +28,9 @@
 //! Content goes here...
 //! ```
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -32,9 +38,11 @@ use tokio::fs;
 use tracing::info;
 
 mod error;
+mod path_index;
 pub mod slug;
 
 pub use error::{Error, Result};
+pub use path_index::{InMemoryPathIndex, PathIndex};
 pub use slug::{generate_memory_id, slug_to_hash, slug_to_id, slug_to_memory_id, slugify, slugify_unique};
 
 /// Trait for memory data that can be converted to/from frontmatter.
@@ -227,6 +235,8 @@ pub struct ProjectConfig {
     pub indexing: IndexingConfig,
     #[serde(default)]
     pub embedding: EmbeddingConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
 }
 
 /// Project information section of configuration.
@@ -282,9 +292,131 @@ impl Default for EmbeddingConfig {
     }
 }
 
+/// How memory files are laid out under fold/.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageLayout {
+    /// Hash-sharded by memory ID: fold/a/b/{id}.md
+    #[default]
+    Hashed,
+    /// Human-readable: fold/{memory_type}/{slug}.md
+    Readable,
+}
+
+impl StorageLayout {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hashed => "hashed",
+            Self::Readable => "readable",
+        }
+    }
+}
+
+/// Storage configuration for the project.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub layout: StorageLayout,
+}
+
+/// Normalise a tag to the syntax Obsidian and Logseq accept.
+///
+/// Tags may only contain letters, digits, `-`, `_` and `/`, and cannot be
+/// purely numeric. Returns `None` if nothing usable is left.
+pub fn obsidian_tag(tag: &str) -> Option<String> {
+    let tag: String = tag
+        .trim()
+        .trim_start_matches('#')
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '/') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let tag = tag.trim_matches('-');
+
+    if tag.is_empty() || tag.chars().all(|c| c.is_ascii_digit()) {
+        None
+    } else {
+        Some(tag.to_string())
+    }
+}
+
+/// Remove the generated `## Related` wiki-link section from a memory body.
+pub fn strip_related_section(content: &str) -> &str {
+    match content.find("\n---\n\n## Related") {
+        Some(idx) => content[..idx].trim_end(),
+        None => content,
+    }
+}
+
+/// Extract the memory ID from a repository-relative memory file path.
+///
+/// Matches the live hashed layout `fold/{id[0]}/{id[1]}/{id}.md`; archived
+/// files, readable-layout files and anything else under fold/ return `None`.
+pub fn memory_id_from_path(path: &str) -> Option<&str> {
+    let mut parts = path.strip_prefix("fold/")?.split('/');
+    let (char1, char2, file) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let id = file.strip_suffix(".md")?;
+    if id.get(0..1) != Some(char1) || id.get(1..2) != Some(char2) {
+        return None;
+    }
+
+    Some(id)
+}
+
+/// Whether a repository-relative path is a live memory file in either
+/// layout: `fold/{id[0]}/{id[1]}/{id}.md` or `fold/{memory_type}/{slug}.md`.
+pub fn is_memory_file_path(path: &str) -> bool {
+    if memory_id_from_path(path).is_some() {
+        return true;
+    }
+
+    let Some(rest) = path.strip_prefix("fold/") else {
+        return false;
+    };
+    let mut parts = rest.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(dir), Some(file), None) => {
+            dir.len() > 1 && dir != "archive" && file.len() > 3 && file.ends_with(".md")
+        }
+        _ => false,
+    }
+}
+
+/// Directory for a memory type in the readable layout.
+fn readable_dir(memory_type: &str) -> String {
+    match slugify(memory_type) {
+        dir if dir.is_empty() || dir == "archive" => "general".to_string(),
+        dir => dir,
+    }
+}
+
+/// File name (without extension) for a memory in the readable layout.
+fn readable_name<M: MemoryData>(memory: &M) -> String {
+    let name = memory
+        .slug()
+        .map(slugify)
+        .filter(|s| !s.is_empty())
+        .or_else(|| memory.title().map(slugify).filter(|s| !s.is_empty()));
+    name.unwrap_or_else(|| memory.id().to_string())
+}
+
 /// Service for hash-based storage in the fold/ directory.
 pub struct FoldStorageService {
-    // No state needed - operates on paths provided by caller
+    /// Files of readable-layout memories, whose paths cannot be derived
+    /// from the ID
+    path_index: Arc<dyn PathIndex>,
+    /// Directories walked this process to catch files the index missed
+    /// (e.g. written while the server was down)
+    scanned: Mutex<HashSet<PathBuf>>,
 }
 
 impl Default for FoldStorageService {
@@ -294,9 +426,18 @@ impl Default for FoldStorageService {
 }
 
 impl FoldStorageService {
-    /// Create a new fold storage service.
+    /// Create a new fold storage service with a process-local path index.
     pub fn new() -> Self {
-        Self {}
+        Self::with_path_index(Arc::new(InMemoryPathIndex::default()))
+    }
+
+    /// Create a new fold storage service recording readable-layout file
+    /// paths in the given index.
+    pub fn with_path_index(path_index: Arc<dyn PathIndex>) -> Self {
+        Self {
+            path_index,
+            scanned: Mutex::new(HashSet::new()),
+        }
     }
 
     /// Get the path to a memory file based on its hash.
@@ -319,6 +460,193 @@ impl FoldStorageService {
         project_root.join("fold")
     }
 
+    /// Get the path to an archived memory file.
+    ///
+    /// Archived memories mirror the hash layout under fold/archive/a/b/aBcD123.md
+    pub fn get_archive_path(&self, project_root: &Path, hash: &str) -> PathBuf {
+        let char1 = if !hash.is_empty() { &hash[0..1] } else { "0" };
+        let char2 = if hash.len() >= 2 { &hash[1..2] } else { "0" };
+
+        project_root
+            .join("fold")
+            .join("archive")
+            .join(char1)
+            .join(char2)
+            .join(format!("{}.md", hash))
+    }
+
+    /// Get the readable-layout path for a memory: fold/{memory_type}/{slug}.md
+    ///
+    /// The file name is the memory's slug, or its slugified title, falling
+    /// back to the ID.
+    pub fn get_readable_path<M: MemoryData>(&self, project_root: &Path, memory: &M) -> PathBuf {
+        project_root
+            .join("fold")
+            .join(readable_dir(memory.memory_type()))
+            .join(format!("{}.md", readable_name(memory)))
+    }
+
+    /// Read the project's memory file layout (hashed unless configured).
+    pub async fn layout(&self, project_root: &Path) -> StorageLayout {
+        self.read_project_config(project_root)
+            .await
+            .map(|c| c.storage.layout)
+            .unwrap_or_default()
+    }
+
+    /// Switch a project to another layout, moving existing memory files.
+    ///
+    /// Files are rewritten so their related links use the new layout's
+    /// paths. Returns the number of files moved.
+    pub async fn set_layout(&self, project_root: &Path, layout: StorageLayout) -> Result<usize> {
+        let mut config = self.read_project_config(project_root).await?;
+        if config.storage.layout == layout {
+            return Ok(0);
+        }
+        config.storage.layout = layout;
+        self.write_project_config(project_root, &config).await?;
+
+        let fold_path = self.get_fold_path(project_root);
+        let live = self.scan_memory_files(&fold_path, false).await?;
+        let archived = self
+            .scan_memory_files(&fold_path.join("archive"), false)
+            .await?;
+        let mut moved = 0;
+
+        for (_, path) in &live {
+            self.relayout_file(project_root, path).await?;
+            moved += 1;
+        }
+
+        // Archived files are rewritten as live files, then archived again
+        for (id, path) in &archived {
+            self.relayout_file(project_root, path).await?;
+            self.archive_memory(project_root, id).await?;
+            moved += 1;
+        }
+
+        info!(
+            project_root = %project_root.display(),
+            layout = layout.as_str(),
+            moved,
+            "Changed fold/ layout"
+        );
+
+        Ok(moved)
+    }
+
+    /// Rewrite one memory file at its live path in the current layout.
+    async fn relayout_file(&self, project_root: &Path, path: &Path) -> Result<()> {
+        let raw = fs::read_to_string(path).await?;
+        let (frontmatter, body) = self.parse_frontmatter(&raw)?;
+
+        fs::remove_file(path).await?;
+        self.path_index.forget(project_root, &frontmatter.id).await;
+
+        let memory = frontmatter.to_storage_memory();
+        self.write_memory_with_links(
+            project_root,
+            &memory,
+            strip_related_section(&body),
+            &frontmatter.related_to,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find the file holding a memory, in either layout.
+    async fn locate(&self, project_root: &Path, id: &str, archived: bool) -> Option<PathBuf> {
+        let hashed = if archived {
+            self.get_archive_path(project_root, id)
+        } else {
+            self.get_memory_path(project_root, id)
+        };
+        if fs::metadata(&hashed).await.is_ok() {
+            return Some(hashed);
+        }
+
+        let fold_path = self.get_fold_path(project_root);
+        let archive_path = fold_path.join("archive");
+        let base = if archived { &archive_path } else { &fold_path };
+        let in_base =
+            |path: &Path| path.starts_with(base) && archived == path.starts_with(&archive_path);
+
+        let indexed = self.path_index.path_of(project_root, id).await;
+        if let Some(path) = indexed.filter(|p| in_base(p)) {
+            if fs::metadata(&path).await.is_ok() {
+                return Some(path);
+            }
+        }
+
+        // Every write records its path, so a directory is only walked once
+        // per process, to pick up files the index has not seen
+        if !self.scanned.lock().unwrap().insert(base.clone()) {
+            return None;
+        }
+
+        // Hashed files were checked above, so only type directories are walked
+        let files = self.scan_memory_files(base, true).await.ok()?;
+        self.path_index.record(project_root, &files).await;
+        files
+            .into_iter()
+            .find(|(file_id, _)| file_id == id)
+            .map(|(_, path)| path)
+    }
+
+    /// Memory ID of a repository-relative memory file path, in either
+    /// layout. Readable-layout paths are looked up in the path index.
+    pub async fn memory_id_at(&self, project_root: &Path, path: &str) -> Option<String> {
+        match memory_id_from_path(path) {
+            Some(id) => Some(id.to_string()),
+            None => {
+                self.path_index
+                    .memory_at(project_root, &project_root.join(path))
+                    .await
+            }
+        }
+    }
+
+    /// Record where a readable-layout memory file now lives.
+    pub async fn record_path(&self, project_root: &Path, id: &str, path: &Path) {
+        self.path_index
+            .record(project_root, &[(id.to_string(), path.to_path_buf())])
+            .await;
+    }
+
+    /// Pick the readable-layout path for a memory, adding an ID suffix when
+    /// another memory already has the file.
+    async fn readable_target<M: MemoryData>(&self, project_root: &Path, memory: &M) -> PathBuf {
+        let path = self.get_readable_path(project_root, memory);
+        match self.file_memory_id(&path).await {
+            Some(id) if id != memory.id() => {
+                let suffix: String = memory.id().chars().take(8).collect();
+                path.with_file_name(format!("{}-{}.md", readable_name(memory), suffix))
+            }
+            _ => path,
+        }
+    }
+
+    /// Read the memory ID from a file's frontmatter.
+    async fn file_memory_id(&self, path: &Path) -> Option<String> {
+        let content = fs::read_to_string(path).await.ok()?;
+        self.parse_frontmatter(&content).ok().map(|(fm, _)| fm.id)
+    }
+
+    /// Wiki link target for a related memory in the readable layout: its
+    /// path under fold/ without the extension, which Obsidian resolves.
+    async fn readable_link(&self, project_root: &Path, id: &str) -> String {
+        let fold_path = self.get_fold_path(project_root);
+        self.locate(project_root, id, false)
+            .await
+            .and_then(|path| {
+                path.strip_prefix(&fold_path)
+                    .ok()
+                    .map(|rel| rel.with_extension("").to_string_lossy().replace('\\', "/"))
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+
     /// Write a memory to the fold/ directory.
     ///
     /// Creates the hash-based directory structure and writes the memory
@@ -338,7 +666,9 @@ impl FoldStorageService {
     /// Related memory IDs are included both in the frontmatter and as
     /// navigable [[wiki-style]] links in the body.
     ///
-    /// File path is determined by the memory ID: `fold/{id[0]}/{id[1]}/{id}.md`
+    /// File path is determined by the memory ID: `fold/{id[0]}/{id[1]}/{id}.md`,
+    /// or by its type and slug in the readable layout. A file left at another
+    /// path (after a rename or layout change) is removed.
     pub async fn write_memory_with_links<M: MemoryData>(
         &self,
         project_root: &Path,
@@ -346,7 +676,12 @@ impl FoldStorageService {
         content: &str,
         related_ids: &[String],
     ) -> Result<PathBuf> {
-        let file_path = self.get_memory_path(project_root, memory.id());
+        let layout = self.layout(project_root).await;
+        let previous_path = self.locate(project_root, memory.id(), false).await;
+        let file_path = match layout {
+            StorageLayout::Hashed => self.get_memory_path(project_root, memory.id()),
+            StorageLayout::Readable => self.readable_target(project_root, memory).await,
+        };
 
         // Log this write operation
         info!(
@@ -372,6 +707,13 @@ impl FoldStorageService {
         // Build frontmatter with related_to populated
         let mut frontmatter = MemoryFrontmatter::from_memory(memory);
         frontmatter.related_to = related_ids.to_vec();
+        if layout == StorageLayout::Readable {
+            frontmatter.tags = frontmatter
+                .tags
+                .iter()
+                .filter_map(|t| obsidian_tag(t))
+                .collect();
+        }
         let yaml = serde_yaml::to_string(&frontmatter)
             .map_err(|e| Error::Internal(format!("Failed to serialize frontmatter: {}", e)))?;
 
@@ -380,10 +722,19 @@ impl FoldStorageService {
         if !related_ids.is_empty() {
             full_content.push_str("\n\n---\n\n## Related\n\n");
             for id in related_ids {
-                // Wiki-style link: [[id]] with relative path
-                let char1 = &id[0..1];
-                let char2 = &id[1..2];
-                full_content.push_str(&format!("- [[{}/{}/{}.md|{}]]\n", char1, char2, id, id));
+                match layout {
+                    StorageLayout::Hashed => {
+                        // Wiki-style link: [[id]] with relative path
+                        let char1 = &id[0..1];
+                        let char2 = &id[1..2];
+                        full_content
+                            .push_str(&format!("- [[{}/{}/{}.md|{}]]\n", char1, char2, id, id));
+                    }
+                    StorageLayout::Readable => {
+                        let target = self.readable_link(project_root, id).await;
+                        full_content.push_str(&format!("- [[{}]]\n", target));
+                    }
+                }
             }
         }
 
@@ -407,6 +758,14 @@ impl FoldStorageService {
             ))
         })?;
 
+        if let Some(previous) = previous_path.filter(|p| *p != file_path) {
+            let _ = fs::remove_file(&previous).await;
+        }
+        if layout == StorageLayout::Readable {
+            self.record_path(project_root, memory.id(), &file_path)
+                .await;
+        }
+
         Ok(file_path)
     }
 
@@ -430,14 +789,10 @@ impl FoldStorageService {
         let (memory, content) = self.read_memory(project_root, memory_id).await?;
 
         // Strip any existing Related section from content
-        let clean_content = if let Some(idx) = content.find("\n---\n\n## Related") {
-            content[..idx].to_string()
-        } else {
-            content
-        };
+        let clean_content = strip_related_section(&content);
 
         // Re-write with new links
-        self.write_memory_with_links(project_root, &memory, &clean_content, related_ids)
+        self.write_memory_with_links(project_root, &memory, clean_content, related_ids)
             .await
     }
 
@@ -449,7 +804,10 @@ impl FoldStorageService {
         project_root: &Path,
         hash: &str,
     ) -> Result<(StorageMemory, String)> {
-        let file_path = self.get_memory_path(project_root, hash);
+        let file_path = self
+            .locate(project_root, hash, false)
+            .await
+            .unwrap_or_else(|| self.get_memory_path(project_root, hash));
         let content = fs::read_to_string(&file_path).await.map_err(|e| {
             Error::FileNotFound(format!(
                 "Memory file not found {}: {}",
@@ -466,6 +824,16 @@ impl FoldStorageService {
     /// Splits the file into YAML frontmatter and markdown body,
     /// then deserializes the frontmatter into a StorageMemory.
     pub fn parse_memory_file(&self, content: &str) -> Result<(StorageMemory, String)> {
+        let (frontmatter, body) = self.parse_frontmatter(content)?;
+
+        Ok((frontmatter.to_storage_memory(), body))
+    }
+
+    /// Parse memory file content into raw frontmatter and body.
+    ///
+    /// Unlike `parse_memory_file`, this keeps `related_to`, so callers can
+    /// apply link changes made by editing the file.
+    pub fn parse_frontmatter(&self, content: &str) -> Result<(MemoryFrontmatter, String)> {
         // Split frontmatter and body
         if !content.starts_with("---") {
             return Err(Error::InvalidInput(
@@ -498,17 +866,29 @@ impl FoldStorageService {
         let frontmatter: MemoryFrontmatter = serde_yaml::from_str(frontmatter_str)
             .map_err(|e| Error::InvalidInput(format!("Failed to parse frontmatter: {}", e)))?;
 
-        let memory = frontmatter.to_storage_memory();
-
-        Ok((memory, body.to_string()))
+        Ok((frontmatter, body.to_string()))
     }
 
     /// Scan the fold/ directory for all memory hashes.
     ///
     /// Walks the hash-based directory tree and collects all memory IDs
-    /// (extracted from filenames).
+    /// (extracted from filenames). Readable-layout files are included too.
     pub async fn scan_fold_directory(&self, project_root: &Path) -> Result<Vec<String>> {
         let fold_path = project_root.join("fold");
+        let files = self.scan_memory_files(&fold_path, false).await?;
+        Ok(files.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Collect memory IDs and file paths two levels below `fold_path`.
+    ///
+    /// Hashed files (fold/a/b/{id}.md) take the ID from the file name,
+    /// readable files (fold/{type}/{slug}.md) from their frontmatter.
+    /// `readable_only` skips the single-character hash shard directories.
+    async fn scan_memory_files(
+        &self,
+        fold_path: &Path,
+        readable_only: bool,
+    ) -> Result<Vec<(String, PathBuf)>> {
         let mut hashes = Vec::new();
 
         if !fold_path.exists() {
@@ -536,6 +916,14 @@ impl FoldStorageService {
                 continue;
             }
 
+            // Archived memories are not part of the live set
+            if entry.file_name() == "archive" {
+                continue;
+            }
+            if readable_only && entry.file_name().len() == 1 {
+                continue;
+            }
+
             // Walk second level (second hex char)
             let mut sub_entries = match fs::read_dir(&entry_path).await {
                 Ok(e) => e,
@@ -546,6 +934,12 @@ impl FoldStorageService {
                 let sub_path = sub_entry.path();
 
                 if !sub_entry.file_type().await.map_or(false, |t| t.is_dir()) {
+                    // Readable layout: fold/{type}/{slug}.md
+                    if sub_path.extension().is_some_and(|ext| ext == "md") {
+                        if let Some(id) = self.file_memory_id(&sub_path).await {
+                            hashes.push((id, sub_path));
+                        }
+                    }
                     continue;
                 }
 
@@ -561,7 +955,8 @@ impl FoldStorageService {
                     if let Some(ext) = file_path.extension() {
                         if ext == "md" {
                             if let Some(stem) = file_path.file_stem() {
-                                hashes.push(stem.to_string_lossy().to_string());
+                                let id = stem.to_string_lossy().to_string();
+                                hashes.push((id, file_path));
                             }
                         }
                     }
@@ -572,17 +967,112 @@ impl FoldStorageService {
         Ok(hashes)
     }
 
+    /// Move a memory file into fold/archive/.
+    ///
+    /// Returns false if the memory has no file (e.g. indexed file/git memories).
+    pub async fn archive_memory(&self, project_root: &Path, hash: &str) -> Result<bool> {
+        let Some(from) = self.locate(project_root, hash, false).await else {
+            return Ok(false);
+        };
+        let fold_path = self.get_fold_path(project_root);
+        let to = match from.strip_prefix(&fold_path) {
+            Ok(rel) => fold_path.join("archive").join(rel),
+            Err(_) => self.get_archive_path(project_root, hash),
+        };
+        self.move_memory_file(project_root, hash, &from, &to).await
+    }
+
+    /// Move an archived memory file back into the live fold/ tree.
+    ///
+    /// Returns false if there is no archived file.
+    pub async fn restore_memory(&self, project_root: &Path, hash: &str) -> Result<bool> {
+        let Some(from) = self.locate(project_root, hash, true).await else {
+            return Ok(false);
+        };
+        let fold_path = self.get_fold_path(project_root);
+        let to = match from.strip_prefix(fold_path.join("archive")) {
+            Ok(rel) => fold_path.join(rel),
+            Err(_) => self.get_memory_path(project_root, hash),
+        };
+        self.move_memory_file(project_root, hash, &from, &to).await
+    }
+
+    /// Read an archived memory from fold/archive/.
+    pub async fn read_archived_memory(
+        &self,
+        project_root: &Path,
+        hash: &str,
+    ) -> Result<(StorageMemory, String)> {
+        let file_path = self
+            .locate(project_root, hash, true)
+            .await
+            .unwrap_or_else(|| self.get_archive_path(project_root, hash));
+        let content = fs::read_to_string(&file_path).await.map_err(|e| {
+            Error::FileNotFound(format!(
+                "Archived memory file not found {}: {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+
+        self.parse_memory_file(&content)
+    }
+
+    async fn move_memory_file(
+        &self,
+        project_root: &Path,
+        id: &str,
+        from: &Path,
+        to: &Path,
+    ) -> Result<bool> {
+        if fs::metadata(from).await.is_err() {
+            return Ok(false);
+        }
+
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).await.map_err(|e| {
+                Error::Internal(format!(
+                    "Failed to create directory {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        fs::rename(from, to).await.map_err(|e| {
+            Error::Internal(format!(
+                "Failed to move memory file {} to {}: {}",
+                from.display(),
+                to.display(),
+                e
+            ))
+        })?;
+
+        let hashed = [
+            self.get_memory_path(project_root, id),
+            self.get_archive_path(project_root, id),
+        ];
+        if !hashed.iter().any(|p| p == to) {
+            self.record_path(project_root, id, to).await;
+        }
+
+        Ok(true)
+    }
+
     /// Check if a memory file exists.
     pub async fn exists(&self, project_root: &Path, hash: &str) -> bool {
-        let path = self.get_memory_path(project_root, hash);
-        fs::metadata(&path).await.is_ok()
+        self.locate(project_root, hash, false).await.is_some()
     }
 
-    /// Delete a memory file.
+    /// Delete a memory file, whether live or archived.
     pub async fn delete_memory(&self, project_root: &Path, hash: &str) -> Result<()> {
-        let path = self.get_memory_path(project_root, hash);
+        let paths = [
+            self.locate(project_root, hash, false).await,
+            self.locate(project_root, hash, true).await,
+        ];
+        self.path_index.forget(project_root, hash).await;
 
-        if fs::metadata(&path).await.is_ok() {
+        for path in paths.into_iter().flatten() {
             fs::remove_file(&path).await.map_err(|e| {
                 Error::Internal(format!(
                     "Failed to delete memory file {}: {}",
@@ -676,6 +1166,7 @@ impl FoldStorageService {
             },
             indexing: IndexingConfig::default(),
             embedding: EmbeddingConfig::default(),
+            storage: StorageConfig::default(),
         };
 
         self.write_project_config(project_root, &config).await
@@ -710,6 +1201,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_archive_path() {
+        let service = FoldStorageService::new();
+        let project_root = Path::new("/projects/my-app");
+
+        let path = service.get_archive_path(project_root, "aBcD123456789abc");
+        assert_eq!(
+            path,
+            Path::new("/projects/my-app/fold/archive/a/B/aBcD123456789abc.md")
+        );
+    }
+
     #[test]
     fn test_parse_memory_file() {
         let service = FoldStorageService::new();
@@ -742,6 +1245,161 @@ More content here."#;
         assert!(body.contains("## Section"));
     }
 
+    #[test]
+    fn test_parse_frontmatter_keeps_related() {
+        let service = FoldStorageService::new();
+
+        let content = r#"---
+id: aBcD123456789abc
+title: Test Memory
+memory_type: decision
+created_at: 2026-02-02T10:30:00Z
+updated_at: 2026-02-02T10:30:00Z
+related_to:
+  - f0123456789abcde
+---
+
+Use Postgres.
+
+---
+
+## Related
+
+- [[f/0/f0123456789abcde.md|f0123456789abcde]]
+"#;
+
+        let (frontmatter, body) = service.parse_frontmatter(content).unwrap();
+        assert_eq!(frontmatter.related_to, vec!["f0123456789abcde".to_string()]);
+        assert_eq!(strip_related_section(&body), "Use Postgres.");
+        assert_eq!(strip_related_section("No links."), "No links.");
+    }
+
+    #[test]
+    fn test_memory_id_from_path() {
+        assert_eq!(
+            memory_id_from_path("fold/a/B/aBcD123456789abc.md"),
+            Some("aBcD123456789abc")
+        );
+        assert_eq!(
+            memory_id_from_path("fold/archive/a/B/aBcD123456789abc.md"),
+            None
+        );
+        assert_eq!(memory_id_from_path("fold/a/c/aBcD123456789abc.md"), None);
+        assert_eq!(memory_id_from_path("fold/project.toml"), None);
+        assert_eq!(memory_id_from_path("docs/a/B/aBcD123456789abc.md"), None);
+    }
+
+    #[test]
+    fn test_is_memory_file_path() {
+        assert!(is_memory_file_path("fold/a/B/aBcD123456789abc.md"));
+        assert!(is_memory_file_path("fold/decision/use-postgres.md"));
+        assert!(!is_memory_file_path("fold/archive/use-postgres.md"));
+        assert!(!is_memory_file_path(
+            "fold/archive/decision/use-postgres.md"
+        ));
+        assert!(!is_memory_file_path("fold/project.toml"));
+        assert!(!is_memory_file_path("fold/decision/notes.txt"));
+        assert!(!is_memory_file_path("docs/decision/use-postgres.md"));
+    }
+
+    #[test]
+    fn test_obsidian_tag() {
+        assert_eq!(obsidian_tag("#rust"), Some("rust".to_string()));
+        assert_eq!(obsidian_tag("api design"), Some("api-design".to_string()));
+        assert_eq!(
+            obsidian_tag("area/backend"),
+            Some("area/backend".to_string())
+        );
+        assert_eq!(obsidian_tag("2026"), None);
+        assert_eq!(obsidian_tag("  "), None);
+    }
+
+    fn test_memory(id: &str, title: &str, memory_type: &str) -> StorageMemory {
+        let now = Utc::now();
+        StorageMemory {
+            id: id.to_string(),
+            project_id: "proj-1".to_string(),
+            slug: None,
+            memory_type: memory_type.to_string(),
+            source: None,
+            content: None,
+            content_hash: None,
+            title: Some(title.to_string()),
+            author: None,
+            tags: Some(r#"["api design"]"#.to_string()),
+            file_path: None,
+            language: None,
+            metadata: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readable_layout() {
+        let service = FoldStorageService::new();
+        let root = std::env::temp_dir().join(format!("fold-storage-{}", uuid::Uuid::new_v4()));
+        service
+            .init_fold_directory(&root, "proj-1", "test", "Test")
+            .await
+            .unwrap();
+
+        let hashed = test_memory("f0123456789abcde", "Use Postgres", "decision");
+        service
+            .write_memory(&root, &hashed, "Use Postgres.")
+            .await
+            .unwrap();
+        assert!(service.get_memory_path(&root, &hashed.id).exists());
+
+        let moved = service
+            .set_layout(&root, StorageLayout::Readable)
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+        assert_eq!(service.layout(&root).await, StorageLayout::Readable);
+        assert!(!service.get_memory_path(&root, &hashed.id).exists());
+
+        let readable_path = root.join("fold/decision/use-postgres.md");
+        assert!(readable_path.exists());
+        assert_eq!(
+            service
+                .memory_id_at(&root, "fold/decision/use-postgres.md")
+                .await,
+            Some(hashed.id.clone())
+        );
+
+        // Same title, different memory: the file name gets an ID suffix
+        let other = test_memory("aBcD123456789abc", "Use Postgres", "decision");
+        let path = service
+            .write_memory_with_links(&root, &other, "Again.", std::slice::from_ref(&hashed.id))
+            .await
+            .unwrap();
+        assert_eq!(path, root.join("fold/decision/use-postgres-aBcD1234.md"));
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("- [[decision/use-postgres]]"));
+        assert!(raw.contains("- api-design"));
+
+        let (memory, body) = service.read_memory(&root, &hashed.id).await.unwrap();
+        assert_eq!(memory.title.as_deref(), Some("Use Postgres"));
+        assert_eq!(body, "Use Postgres.");
+
+        assert!(service.archive_memory(&root, &hashed.id).await.unwrap());
+        assert!(root.join("fold/archive/decision/use-postgres.md").exists());
+        assert!(!service.exists(&root, &hashed.id).await);
+        assert!(service.restore_memory(&root, &hashed.id).await.unwrap());
+        assert!(readable_path.exists());
+
+        let mut ids = service.scan_fold_directory(&root).await.unwrap();
+        ids.sort();
+        assert_eq!(ids, vec![other.id.clone(), hashed.id.clone()]);
+
+        service.delete_memory(&root, &other.id).await.unwrap();
+        assert!(!path.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn test_frontmatter_conversion() {
         let now = Utc::now();
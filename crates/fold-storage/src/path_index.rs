@@ -0,0 +1,60 @@
+//! Index of readable-layout memory file locations.
+//!
+//! Readable-layout files are named after the memory's type and slug, so
+//! their paths cannot be derived from the memory ID. The index records
+//! where each file was last written so lookups do not have to walk fold/.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+/// Where memory files live, keyed by memory ID.
+///
+/// Paths are absolute and lie under `project_root`. Implementations treat
+/// the index as a cache: failures are logged, not returned.
+#[async_trait]
+pub trait PathIndex: Send + Sync {
+    /// File last recorded for a memory.
+    async fn path_of(&self, project_root: &Path, id: &str) -> Option<PathBuf>;
+
+    /// Memory last recorded at a file.
+    async fn memory_at(&self, project_root: &Path, path: &Path) -> Option<String>;
+
+    /// Record memory files, replacing earlier entries for the same memories.
+    async fn record(&self, project_root: &Path, files: &[(String, PathBuf)]);
+
+    /// Forget a memory's file.
+    async fn forget(&self, project_root: &Path, id: &str);
+}
+
+/// Process-local path index, used when no persistent index is configured.
+#[derive(Debug, Default)]
+pub struct InMemoryPathIndex {
+    paths: Mutex<HashMap<String, PathBuf>>,
+}
+
+#[async_trait]
+impl PathIndex for InMemoryPathIndex {
+    async fn path_of(&self, _project_root: &Path, id: &str) -> Option<PathBuf> {
+        self.paths.lock().unwrap().get(id).cloned()
+    }
+
+    async fn memory_at(&self, _project_root: &Path, path: &Path) -> Option<String> {
+        self.paths
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, p)| p.as_path() == path)
+            .map(|(id, _)| id.clone())
+    }
+
+    async fn record(&self, _project_root: &Path, files: &[(String, PathBuf)]) {
+        self.paths.lock().unwrap().extend(files.iter().cloned());
+    }
+
+    async fn forget(&self, _project_root: &Path, id: &str) {
+        self.paths.lock().unwrap().remove(id);
+    }
+}
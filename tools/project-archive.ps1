@@ -0,0 +1,49 @@
+# Export a Fold project to a portable archive, or import one as a new project
+# Talks to a running server using FOLD_URL and FOLD_TOKEN
+
+param(
+    [Parameter(Mandatory = $true, Position = 0)]
+    [ValidateSet("export", "import")]
+    [string]$Command,
+    [Parameter(Mandatory = $true, Position = 1)]
+    [string]$Target,
+    [string]$Output,
+    [switch]$Vectors,
+    [string]$RootPath,
+    [string]$Slug,
+    [string]$Name
+)
+
+$foldUrl = if ($env:FOLD_URL) { $env:FOLD_URL } else { "http://localhost:8765" }
+
+if (-not $env:FOLD_TOKEN) {
+    Write-Host "Error: FOLD_TOKEN is not set" -ForegroundColor Red
+    exit 1
+}
+
+$headers = @{ Authorization = "Bearer $($env:FOLD_TOKEN)" }
+
+if ($Command -eq "export") {
+    if (-not $Output) {
+        $Output = "$Target.fold.jsonl"
+    }
+    $vectorsParam = if ($Vectors) { "true" } else { "false" }
+
+    Invoke-WebRequest -Uri "$foldUrl/projects/$Target/export?vectors=$vectorsParam" `
+        -Headers $headers -OutFile $Output
+    Write-Host "Exported $Target to $Output" -ForegroundColor Green
+}
+else {
+    if (-not $RootPath) {
+        Write-Host "Usage: .\project-archive.ps1 import <archive-file> -RootPath <path> [-Slug <slug>] [-Name <name>]" -ForegroundColor Yellow
+        exit 1
+    }
+
+    $query = "root_path=$([uri]::EscapeDataString($RootPath))"
+    if ($Slug) { $query += "&slug=$([uri]::EscapeDataString($Slug))" }
+    if ($Name) { $query += "&name=$([uri]::EscapeDataString($Name))" }
+
+    $result = Invoke-RestMethod -Method Post -Uri "$foldUrl/projects/import?$query" `
+        -Headers $headers -ContentType "application/x-ndjson" -InFile $Target
+    $result | ConvertTo-Json
+}
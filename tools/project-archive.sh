@@ -0,0 +1,76 @@
+#!/bin/bash
+
+# Export a Fold project to a portable archive, or import one as a new project
+# Talks to a running server using FOLD_URL and FOLD_TOKEN
+
+set -e
+
+FOLD_URL="${FOLD_URL:-http://localhost:8765}"
+
+usage() {
+    cat >&2 <<EOF
+Usage:
+  $0 export <project> [output-file] [--vectors]
+  $0 import <archive-file> <root-path> [slug] [name]
+
+Environment:
+  FOLD_URL    Server URL (default: http://localhost:8765)
+  FOLD_TOKEN  API token (required)
+EOF
+    exit 1
+}
+
+urlencode() {
+    python3 -c 'import sys, urllib.parse; print(urllib.parse.quote(sys.argv[1], safe=""))' "$1"
+}
+
+if [ -z "$FOLD_TOKEN" ]; then
+    echo "Error: FOLD_TOKEN is not set" >&2
+    exit 1
+fi
+
+command="$1"
+shift || usage
+
+case "$command" in
+    export)
+        [ -n "$1" ] || usage
+        project="$1"
+        output="${project}.fold.jsonl"
+        vectors="false"
+        shift
+        for arg in "$@"; do
+            case "$arg" in
+                --vectors) vectors="true" ;;
+                *) output="$arg" ;;
+            esac
+        done
+
+        curl -sS --fail-with-body \
+            -H "Authorization: Bearer $FOLD_TOKEN" \
+            -o "$output" \
+            "$FOLD_URL/projects/$project/export?vectors=$vectors"
+        echo "Exported $project to $output" >&2
+        ;;
+    import)
+        [ -n "$1" ] && [ -n "$2" ] || usage
+        archive="$1"
+        root_path="$2"
+        slug="$3"
+        name="$4"
+
+        query="root_path=$(urlencode "$root_path")"
+        [ -n "$slug" ] && query="$query&slug=$(urlencode "$slug")"
+        [ -n "$name" ] && query="$query&name=$(urlencode "$name")"
+
+        curl -sS --fail-with-body \
+            -H "Authorization: Bearer $FOLD_TOKEN" \
+            -H "Content-Type: application/x-ndjson" \
+            --data-binary "@$archive" \
+            "$FOLD_URL/projects/import?$query"
+        echo >&2
+        ;;
+    *)
+        usage
+        ;;
+esac